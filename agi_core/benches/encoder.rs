@@ -0,0 +1,89 @@
+// agi_core/benches/encoder.rs
+//
+// Bulk encoding with the scratch buffers of `buffer_pool`, and with every buffer allocated as
+// before. A counting allocator prints the allocations and bytes per encoded text first. Run with
+// `cargo bench -p agi_core --bench encoder`.
+
+use agi_core::buffer_pool;
+use agi_core::holographic_memory::HolographicEncoder;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rayon::prelude::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const DIMENSIONALITY: usize = 1024;
+const TEXTS: usize = 2_000;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn corpus() -> Vec<String> {
+    let subjects = ["Le photon", "Un quasar", "La mitochondrie", "Le théorème de Pythagore", "L'entropie", "Une étoile à neutrons"];
+    let predicates = ["transporte de l'énergie", "émet un rayonnement intense", "produit l'énergie de la cellule", "relie les côtés d'un triangle", "mesure le désordre", "tourne très vite sur elle-même"];
+    (0..TEXTS).map(|i| format!("{} {} (note {}).", subjects[i % subjects.len()], predicates[(i / subjects.len()) % predicates.len()], i)).collect()
+}
+
+fn encoder(texts: &[String]) -> HolographicEncoder {
+    let mut encoder = HolographicEncoder::new(DIMENSIONALITY);
+    for text in texts {
+        encoder.add_document(text);
+    }
+    encoder
+}
+
+fn assimilate(encoder: &HolographicEncoder, texts: &[String], pooled: bool) -> usize {
+    texts
+        .par_iter()
+        .map(|text| if pooled { encoder.encode(text) } else { buffer_pool::without_pooling(|| encoder.encode(text)) })
+        .map(|trace| trace.weighted_concepts.len())
+        .sum()
+}
+
+fn report_allocations(encoder: &HolographicEncoder, texts: &[String]) {
+    for pooled in [false, true] {
+        // Once, to warm the pools of the workers as a long assimilation would.
+        assimilate(encoder, texts, pooled);
+        let (count, bytes) = (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED_BYTES.load(Ordering::Relaxed));
+        assimilate(encoder, texts, pooled);
+        let (count, bytes) = (ALLOCATIONS.load(Ordering::Relaxed) - count, ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes);
+        println!(
+            "{:>9}: {:.1} allocations, {:.1} KiB per encoded text",
+            if pooled { "pooled" } else { "unpooled" },
+            count as f64 / texts.len() as f64,
+            bytes as f64 / texts.len() as f64 / 1024.0
+        );
+    }
+    println!("buffer pool: {:?}", buffer_pool::stats());
+}
+
+fn bench_bulk_encoding(c: &mut Criterion) {
+    let texts = corpus();
+    let encoder = encoder(&texts);
+    report_allocations(&encoder, &texts);
+
+    let mut group = c.benchmark_group("encode_2k_texts");
+    group.sample_size(20);
+    group.bench_function("unpooled", |bench| bench.iter(|| assimilate(&encoder, black_box(&texts), false)));
+    group.bench_function("pooled", |bench| bench.iter(|| assimilate(&encoder, black_box(&texts), true)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_bulk_encoding);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+// agi_core/benches/trace_math.rs
+//
+// Compares the SIMD kernels of `trace_math` with the scalar loops they replaced, and the
+// parallel scoring of memories with a sequential one. Run with `cargo bench -p agi_core`.
+
+use agi_core::holographic_memory::{HolographicTrace, QuantizedComplex};
+use agi_core::trace_math;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rayon::prelude::*;
+
+const DIMENSIONALITY: usize = 1024;
+const MEMORIES: usize = 10_000;
+
+/// The scalar cosine similarity that `HolographicTrace::cosine_similarity` used to compute.
+fn scalar_cosine_similarity(p1: &[QuantizedComplex], p2: &[QuantizedComplex]) -> f32 {
+    let dot_product: f32 = p1.iter().zip(p2).map(|(a, b)| a.to_complex().re * b.to_complex().re + a.to_complex().im * b.to_complex().im).sum();
+    let norm_p1 = p1.iter().map(|c| c.to_complex().norm_sqr()).sum::<f32>().sqrt();
+    let norm_p2 = p2.iter().map(|c| c.to_complex().norm_sqr()).sum::<f32>().sqrt();
+    if norm_p1 == 0.0 || norm_p2 == 0.0 {
+        return 0.0;
+    }
+    (dot_product / (norm_p1 * norm_p2)).clamp(-1.0, 1.0)
+}
+
+fn traces(count: usize) -> Vec<HolographicTrace> {
+    (0..count).map(|i| HolographicTrace::new_seeded(&format!("concept {}", i), DIMENSIONALITY)).collect()
+}
+
+fn bench_kernels(c: &mut Criterion) {
+    let pair = traces(2);
+    let (a, b) = (&pair[0].superposition_pattern, &pair[1].superposition_pattern);
+
+    let mut group = c.benchmark_group("cosine_similarity");
+    group.bench_function("scalar", |bench| bench.iter(|| scalar_cosine_similarity(black_box(a), black_box(b))));
+    group.bench_function("simd", |bench| bench.iter(|| trace_math::cosine_similarity(black_box(a), black_box(b))));
+    group.finish();
+
+    let mut group = c.benchmark_group("superposition");
+    group.bench_function("scalar", |bench| {
+        bench.iter(|| {
+            let mut acc = a.clone();
+            for (x, y) in acc.iter_mut().zip(b.iter()) {
+                *x += y;
+            }
+            acc.iter_mut().for_each(|x| *x *= 0.5);
+            acc
+        })
+    });
+    group.bench_function("simd", |bench| {
+        bench.iter(|| {
+            let mut acc = a.clone();
+            trace_math::add_saturating(&mut acc, black_box(b));
+            trace_math::scale(&mut acc, 0.5);
+            acc
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("q15_conversion");
+    group.bench_function("scalar", |bench| {
+        bench.iter(|| {
+            let values: Vec<_> = black_box(a).iter().map(|c| c.to_complex()).collect();
+            values.iter().map(|&c| QuantizedComplex::from_complex(c)).collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("simd", |bench| bench.iter(|| trace_math::quantize(&trace_math::dequantize(black_box(a)))));
+    group.finish();
+}
+
+fn bench_scoring(c: &mut Criterion) {
+    let memories = traces(MEMORIES);
+    let query = HolographicTrace::new_seeded("query", DIMENSIONALITY);
+
+    let mut group = c.benchmark_group("score_10k_memories");
+    group.sample_size(20);
+    group.bench_function("sequential_scalar", |bench| {
+        bench.iter(|| {
+            memories
+                .iter()
+                .map(|memory| 1.0 - scalar_cosine_similarity(&query.superposition_pattern, &memory.superposition_pattern).abs())
+                .collect::<Vec<f32>>()
+        })
+    });
+    group.bench_function("parallel_simd", |bench| bench.iter(|| memories.par_iter().map(|memory| query.distance(memory)).collect::<Vec<f32>>()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_kernels, bench_scoring);
+criterion_main!(benches);
@@ -0,0 +1,123 @@
+//! activation_record.rs - The neural activity set off by a prompt.
+//!
+//! Once a prompt is answered, the neurons of its concepts (see
+//! `SensoryCortex::neurons_for_concepts`) are stimulated, and the `ActivationRecord` follows the
+//! wave through the next `RECORDED_TICKS` ticks: the neurons fired at each tick, and the
+//! pathway it took, i.e. the synapses from a neuron fired at one tick to a neuron fired at the
+//! next, which spike-timing-dependent plasticity potentiates. Visualizers replay it tick by tick.
+
+use crate::connectome::Connectome;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Ticks recorded after the stimulation.
+pub const RECORDED_TICKS: usize = 64;
+/// Synapses of the pathway recorded at most.
+pub const MAX_PATHWAY_SYNAPSES: usize = 4096;
+
+/// A synapse the wave went through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PathwaySynapse {
+    pub from: u64,
+    pub to: u64,
+    /// The step at which `to` fired (see `ActivationRecord::fired_at`).
+    pub step: usize,
+}
+
+/// The activity that followed one prompt.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ActivationRecord {
+    /// The ID of the answer (see `Core::last_response_id`).
+    pub response_id: u64,
+    pub prompt: String,
+    /// The tick of the stimulation.
+    pub start_tick: u64,
+    pub stimulated: Vec<u64>,
+    /// The neurons fired at each tick after the stimulation, the first tick first.
+    pub waves: Vec<Vec<u64>>,
+    /// The synapses the wave went through, in the order of the steps.
+    pub pathway: Vec<PathwaySynapse>,
+}
+
+impl ActivationRecord {
+    pub fn new(response_id: u64, prompt: &str, start_tick: u64, mut stimulated: Vec<u64>) -> Self {
+        stimulated.sort_unstable();
+        Self { response_id, prompt: prompt.to_string(), start_tick, stimulated, ..Self::default() }
+    }
+
+    /// Whether every tick of the wave has been recorded.
+    pub fn is_complete(&self) -> bool {
+        self.waves.len() >= RECORDED_TICKS
+    }
+
+    /// Records the neurons fired at the next tick, and the synapses that reached them from the
+    /// neurons fired at the previous one (the stimulated neurons, for the first tick).
+    pub fn record_tick(&mut self, fired: &[u64], connectome: &Connectome) {
+        if self.is_complete() {
+            return;
+        }
+        let previous = self.waves.last().unwrap_or(&self.stimulated);
+        let fired_now: HashSet<u64> = fired.iter().copied().collect();
+        let neuron_count = connectome.neurons.len() as u64;
+        let step = self.waves.len() + 1;
+        for &from in previous.iter().filter(|&&from| from < neuron_count) {
+            if self.pathway.len() >= MAX_PATHWAY_SYNAPSES {
+                break;
+            }
+            let reached = connectome.synapses.outgoing(from).filter(|(to, weight)| *weight > 0.0 && fired_now.contains(to));
+            self.pathway.extend(reached.map(|(to, _)| PathwaySynapse { from, to, step }).take(MAX_PATHWAY_SYNAPSES - self.pathway.len()));
+        }
+        let mut fired = fired.to_vec();
+        fired.sort_unstable();
+        self.waves.push(fired);
+    }
+
+    /// The number of steps recorded so far, the stimulation included.
+    pub fn steps(&self) -> usize {
+        self.waves.len() + 1
+    }
+
+    /// The neurons fired `step` ticks after the stimulation (0 is the stimulation itself).
+    pub fn fired_at(&self, step: usize) -> &[u64] {
+        match step {
+            0 => &self.stimulated,
+            _ => self.waves.get(step - 1).map(Vec::as_slice).unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connectome::Synapse;
+    use crate::synapse_matrix::SynapseMatrix;
+
+    #[test]
+    fn test_wave_and_pathway_are_recorded() {
+        let synapses = [
+            Synapse { from: 0, to: 1, weight: 0.5 },
+            Synapse { from: 1, to: 2, weight: 0.5 },
+            // Neuron 3 is inhibitory (see `Connectome::with_synapses`).
+            Synapse { from: 3, to: 1, weight: -0.5 },
+        ];
+        let connectome = Connectome::with_synapses(SynapseMatrix::from_synapses(4, &synapses));
+
+        let mut record = ActivationRecord::new(7, "quasar", 100, vec![3, 0]);
+        record.record_tick(&[1], &connectome);
+        record.record_tick(&[2], &connectome);
+        assert_eq!(
+            record.pathway,
+            vec![PathwaySynapse { from: 0, to: 1, step: 1 }, PathwaySynapse { from: 1, to: 2, step: 2 }],
+            "The inhibitory synapse is not a pathway"
+        );
+        assert_eq!(record.fired_at(0), &[0, 3]);
+        assert_eq!(record.fired_at(1), &[1]);
+        assert!(record.fired_at(5).is_empty());
+
+        for _ in 2..RECORDED_TICKS + 3 {
+            record.record_tick(&[], &connectome);
+        }
+        assert!(record.is_complete());
+        assert_eq!(record.waves.len(), RECORDED_TICKS);
+    }
+}
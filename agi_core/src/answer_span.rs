@@ -0,0 +1,84 @@
+//! answer_span.rs - The few words of a memory that answer the question.
+//!
+//! "Quand Einstein est-il né ?" is answered by "14 mars 1879", not by the whole sentence it was
+//! learned in. `extract` looks for that span in the best memory: the person, date or place a
+//! who/when/where question asks for (see `question_focus`), or, when a "what is" question names
+//! the subject of a memory read as "X est Y", the noun phrase Y. The motor cortex gives the span
+//! as the direct answer, then the sentence that supports it.
+
+use crate::holographic_memory::HolographicMemory;
+use crate::question_focus;
+use crate::sensory_cortex::EntityKind;
+use crate::sentence_composer::{Proposition, Relation};
+use crate::tokenizer;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Longest noun phrase given as a span, in words; a longer object is the sentence itself.
+const MAX_PHRASE_WORDS: usize = 6;
+/// Questions asking what something is.
+const DEFINITION_CUES: &[&str] = &["qu'est-ce", "c'est quoi", "quel est", "quelle est", "quels sont", "quelles sont", "what is", "what are", "what's"];
+/// Words opening a noun phrase.
+const DETERMINERS: &[&str] = &["un", "une", "le", "la", "les", "l'", "des", "du", "a", "an", "the"];
+
+/// The part of a memory that answers a question.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AnswerSpan {
+    pub text: String,
+    /// The type of the entity, when the question asked for a person, a date or a place; None
+    /// for a noun phrase.
+    pub entity: Option<EntityKind>,
+}
+
+/// The span of `memory` answering `question`, if it is shorter than the memory itself.
+pub fn extract(question: &str, memory: &HolographicMemory) -> Option<AnswerSpan> {
+    let span = match question_focus::focus(question) {
+        Some(focus) => {
+            let entity = question_focus::answering_entity(memory, focus.expected, &question_focus::question_keys(question))?;
+            AnswerSpan { text: entity.text, entity: Some(entity.kind) }
+        }
+        None => AnswerSpan { text: noun_phrase(question, &memory.text)?, entity: None },
+    };
+    let sentence = memory.text.trim().trim_end_matches(['.', '!', '?']);
+    (span.text.len() < sentence.len()).then_some(span)
+}
+
+/// The object of `text` read as "subject is object", when it is a noun phrase and `question`
+/// asks what the subject is.
+fn noun_phrase(question: &str, text: &str) -> Option<String> {
+    let lower = question.to_lowercase().replace('’', "'");
+    if !DEFINITION_CUES.iter().any(|cue| lower.contains(cue)) {
+        return None;
+    }
+    let proposition = Proposition::parse(text)?;
+    let object = proposition.object.to_lowercase();
+    let determined = DETERMINERS.iter().any(|determiner| object.starts_with(&format!("{} ", determiner)) || (determiner.ends_with('\'') && object.starts_with(determiner)));
+    if !matches!(proposition.relation, Relation::Is { .. }) || !determined || proposition.object.split_whitespace().count() > MAX_PHRASE_WORDS {
+        return None;
+    }
+    let asked: HashSet<String> = tokenizer::content_words(question).into_iter().collect();
+    let names = |phrase: &str| tokenizer::content_words(phrase).last().is_some_and(|head| asked.contains(head));
+    (names(&proposition.subject) && !names(&proposition.object)).then_some(proposition.object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holographic_memory::HolographicTrace;
+
+    #[test]
+    fn test_spans_are_the_entity_or_noun_phrase_asked_for() {
+        let memory = |text: &str| HolographicMemory::new(text.to_string(), HolographicTrace::new_empty(16), false);
+        let born = memory("Albert Einstein est né le 14 mars 1879 à Ulm.");
+        assert_eq!(extract("Quand Einstein est-il né ?", &born), Some(AnswerSpan { text: "14 mars 1879".to_string(), entity: Some(EntityKind::Date) }));
+        assert_eq!(extract("Où Einstein est-il né ?", &born).map(|span| span.text), Some("Ulm".to_string()));
+        assert_eq!(extract("Qui est né à Ulm ?", &born).map(|span| span.text), Some("Albert Einstein".to_string()));
+        assert_eq!(extract("Quand Einstein est-il mort ?", &memory("Einstein a reçu le prix Nobel.")), None);
+
+        let sun = memory("Le soleil est une étoile.");
+        assert_eq!(extract("Qu'est-ce que le soleil ?", &sun), Some(AnswerSpan { text: "une étoile".to_string(), entity: None }));
+        assert_eq!(extract("Qu'est-ce que le soleil, une étoile ?", &sun), None, "The question already holds the answer");
+        assert_eq!(extract("Qu'est-ce qu'une planète ?", &sun), None);
+        assert_eq!(extract("Parle-moi d'Einstein.", &born), None, "Only a \"what is\" question asks for a noun phrase");
+    }
+}
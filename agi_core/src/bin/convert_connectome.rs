@@ -0,0 +1,23 @@
+// Converts a v1 connectome file into the memory-mappable v2 format (see `synapse_matrix`).
+//
+// Usage: convert_connectome <v1 input> <v2 output>
+use agi_core::connectome::Connectome;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [input, output] = args.as_slice() else {
+        eprintln!("Usage: convert_connectome <v1 input> <v2 output>");
+        return ExitCode::FAILURE;
+    };
+    match Connectome::convert_v1_to_v2(input, output) {
+        Ok(()) => {
+            println!("Wrote {}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Could not convert {}: {}", input, e);
+            ExitCode::FAILURE
+        }
+    }
+}
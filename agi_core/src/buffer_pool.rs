@@ -0,0 +1,180 @@
+//! buffer_pool.rs - Reused scratch buffers for the encoder.
+//!
+//! Encoding a text builds a reference wave per concept, hashes waves for the unknown ones and
+//! scales copies of them before superposing: several vectors of `concept_dimensionality` values
+//! that are dropped as soon as the trace is built. Each thread keeps a few of them here, so that
+//! a bulk assimilation (rayon workers encoding thousands of texts) stops allocating once every
+//! worker has warmed its pool. The pools are thread-local: no lock, and nothing crosses threads.
+//!
+//! A `Pooled` buffer derefs to a `Vec` and goes back to the pool of its thread when dropped.
+//! Buffers that escape into a trace are allocated as usual.
+
+use crate::holographic_memory::QuantizedComplex;
+use nalgebra::Complex;
+use std::cell::{Cell, RefCell};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Buffers kept per thread and element type; an encode needs three or four at once.
+const MAX_POOLED: usize = 8;
+/// Larger buffers are not kept, so that an odd request cannot pin memory.
+const MAX_POOLED_LEN: usize = 1 << 16;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static REUSED: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static WAVES: RefCell<Vec<Vec<Complex<f32>>>> = const { RefCell::new(Vec::new()) };
+    static PATTERNS: RefCell<Vec<Vec<QuantizedComplex>>> = const { RefCell::new(Vec::new()) };
+    static POOLING: Cell<bool> = const { Cell::new(true) };
+}
+
+/// An element type with pools.
+pub trait Poolable: Copy + 'static {
+    const ZERO: Self;
+
+    /// Runs `f` on the pool of the current thread; None once the thread is exiting.
+    fn with_pool<R>(f: impl FnOnce(&mut Vec<Vec<Self>>) -> R) -> Option<R>;
+}
+
+impl Poolable for Complex<f32> {
+    const ZERO: Self = Complex::new(0.0, 0.0);
+
+    fn with_pool<R>(f: impl FnOnce(&mut Vec<Vec<Self>>) -> R) -> Option<R> {
+        WAVES.try_with(|pool| f(&mut pool.borrow_mut())).ok()
+    }
+}
+
+impl Poolable for QuantizedComplex {
+    const ZERO: Self = QuantizedComplex::ZERO;
+
+    fn with_pool<R>(f: impl FnOnce(&mut Vec<Vec<Self>>) -> R) -> Option<R> {
+        PATTERNS.try_with(|pool| f(&mut pool.borrow_mut())).ok()
+    }
+}
+
+/// A scratch buffer of the pool of the current thread.
+pub struct Pooled<T: Poolable> {
+    buffer: Vec<T>,
+}
+
+impl<T: Poolable> Pooled<T> {
+    /// `len` zeros.
+    pub fn zeroed(len: usize) -> Self {
+        let mut buffer = take(len);
+        buffer.resize(len, T::ZERO);
+        Self { buffer }
+    }
+
+    /// A copy of `values`.
+    pub fn copied(values: &[T]) -> Self {
+        let mut buffer = take(values.len());
+        buffer.extend_from_slice(values);
+        Self { buffer }
+    }
+
+    /// The buffer itself, which leaves the pool for good.
+    pub fn into_vec(mut self) -> Vec<T> {
+        std::mem::take(&mut self.buffer)
+    }
+}
+
+fn pooling() -> bool {
+    POOLING.try_with(Cell::get).unwrap_or(false)
+}
+
+/// An empty buffer of capacity `len` at least, from the pool if it has one.
+fn take<T: Poolable>(len: usize) -> Vec<T> {
+    let reused = pooling()
+        .then(|| T::with_pool(|pool| pool.iter().rposition(|buffer| buffer.capacity() >= len).map(|i| pool.swap_remove(i))))
+        .flatten()
+        .flatten();
+    match reused {
+        Some(mut buffer) => {
+            REUSED.fetch_add(1, Ordering::Relaxed);
+            buffer.clear();
+            buffer
+        }
+        None => {
+            ALLOCATED.fetch_add(1, Ordering::Relaxed);
+            Vec::with_capacity(len)
+        }
+    }
+}
+
+impl<T: Poolable> Drop for Pooled<T> {
+    fn drop(&mut self) {
+        let buffer = std::mem::take(&mut self.buffer);
+        if buffer.capacity() == 0 || buffer.capacity() > MAX_POOLED_LEN || !pooling() {
+            return;
+        }
+        T::with_pool(|pool| {
+            if pool.len() < MAX_POOLED {
+                pool.push(buffer);
+            }
+        });
+    }
+}
+
+impl<T: Poolable> Deref for Pooled<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.buffer
+    }
+}
+
+impl<T: Poolable> DerefMut for Pooled<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.buffer
+    }
+}
+
+/// Buffers handed out since the start of the process: allocated, or taken from a pool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    pub allocated: usize,
+    pub reused: usize,
+}
+
+pub fn stats() -> PoolStats {
+    PoolStats { allocated: ALLOCATED.load(Ordering::Relaxed), reused: REUSED.load(Ordering::Relaxed) }
+}
+
+/// Runs `f` with the pools of this thread bypassed, every buffer allocated, to compare (see
+/// `benches/encoder.rs`).
+pub fn without_pooling<R>(f: impl FnOnce() -> R) -> R {
+    let previous = POOLING.with(|pooling| pooling.replace(false));
+    let result = f();
+    POOLING.with(|pooling| pooling.set(previous));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffers_are_reused_on_their_thread() {
+        // Run on a fresh thread: its pools are empty, and no other test touches them.
+        std::thread::spawn(|| {
+            let pointer = {
+                let mut wave = Pooled::<Complex<f32>>::zeroed(1024);
+                wave[3] = Complex::new(1.0, 0.0);
+                wave.as_ptr()
+            };
+            let wave = Pooled::<Complex<f32>>::zeroed(512);
+            assert_eq!(wave.as_ptr(), pointer, "The buffer came back from the pool");
+            assert!(wave.iter().all(|c| *c == Complex::new(0.0, 0.0)) && wave.len() == 512);
+
+            let values = [QuantizedComplex::from_complex(Complex::new(0.5, 0.5)); 4];
+            let kept = Pooled::copied(&values).into_vec();
+            assert_eq!(kept, values);
+            assert_eq!(QuantizedComplex::with_pool(|pool| pool.len()), Some(0), "A buffer taken out is not pooled");
+
+            without_pooling(|| assert_ne!(Pooled::<Complex<f32>>::zeroed(16).as_ptr(), pointer));
+        })
+        .join()
+        .unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+//! bulk_assimilation.rs - Learning a corpus many lines at a time.
+//!
+//! `Core::learn_and_assimilate` does everything for one line before reading the next. Most of that
+//! work only reads the Core: distilling the concepts of the line, encoding its trace and the
+//! traces of the words it introduces, finding its entities. `Core::assimilate_batch` does that
+//! part for the whole batch on the rayon pool (`prepare`), then commits the lines in order: the
+//! new concepts, the connectome and the memories, which depend on the lines before.
+//!
+//! Every line of a batch is counted in the TF-IDF statistics before any is encoded, so the traces
+//! are weighed against the whole batch, as a refresh would weigh them; learned one by one, each
+//! line is only weighed against the lines before it.
+//!
+//! The Awakening Ritual (`Core::awaken`) assimilates the foundational corpus this way, by
+//! batches of `AWAKEN_BATCH_LINES`, and reports an `AwakenProgress` for the progress bars.
+
+use crate::conceptual_hierarchy::ConceptualHierarchy;
+use crate::holographic_memory::{HolographicEncoder, HolographicTrace};
+use crate::sensory_cortex::{Entity, SensoryCortex};
+use crate::tokenizer;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::ControlFlow;
+
+/// Lines encoded between two progress reports.
+const CHUNK: usize = 256;
+/// Lines of the foundational corpus assimilated per batch; a cancelled awakening stops between
+/// two batches.
+pub const AWAKEN_BATCH_LINES: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssimilationStage {
+    /// Lines encoded in parallel.
+    Encoding,
+    /// Lines stored, one after the other.
+    Committing,
+}
+
+/// Where a batch assimilation stands, for a progress bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AssimilationProgress {
+    pub stage: AssimilationStage,
+    /// Lines done in this stage.
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Where the Awakening Ritual stands.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AwakenProgress {
+    /// The file being assimilated.
+    pub file: String,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub lines_done: usize,
+    pub lines_total: usize,
+}
+
+impl AwakenProgress {
+    pub fn percent(&self) -> f32 {
+        if self.lines_total == 0 {
+            return 100.0;
+        }
+        self.lines_done as f32 * 100.0 / self.lines_total as f32
+    }
+}
+
+impl fmt::Display for AwakenProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}/{} files), {}/{} lines, {:.0}%", self.file, self.files_done, self.files_total, self.lines_done, self.lines_total, self.percent())
+    }
+}
+
+/// A progress callback for `Core::awaken` printing every file as it starts, for the
+/// command-line front-ends. It never cancels.
+pub fn print_files() -> impl FnMut(&AwakenProgress) -> ControlFlow<()> {
+    let mut file = None;
+    move |progress| {
+        if file.as_ref() != Some(&progress.file) {
+            println!("--- Awakening: {} ---", progress);
+            file = Some(progress.file.clone());
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// What `Core::awaken` assimilated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AwakenReport {
+    pub files: usize,
+    pub lines: usize,
+    /// Whether the front-end stopped the ritual before its end.
+    pub cancelled: bool,
+}
+
+/// A line ready to be committed.
+#[derive(Debug, Clone)]
+pub struct PreparedText {
+    pub words: Vec<String>,
+    pub trace: HolographicTrace,
+    pub entities: Vec<Entity>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PreparedBatch {
+    /// In the order of the lines.
+    pub texts: Vec<PreparedText>,
+    /// The traces of the words no concept had when the batch was prepared.
+    pub word_traces: HashMap<String, HolographicTrace>,
+}
+
+/// Encodes `texts`, whose documents are already counted by `encoder`, in parallel.
+pub fn prepare<S: AsRef<str> + Sync>(
+    texts: &[S],
+    encoder: &HolographicEncoder,
+    hierarchy: &ConceptualHierarchy,
+    progress: &mut impl FnMut(AssimilationProgress),
+) -> PreparedBatch {
+    let total = texts.len();
+    let mut prepared: Vec<PreparedText> = Vec::with_capacity(total);
+    let mut word_traces = HashMap::new();
+    for chunk in texts.chunks(CHUNK) {
+        let start = prepared.len();
+        prepared.par_extend(chunk.par_iter().map(|text| {
+            let text = text.as_ref();
+            PreparedText { words: tokenizer::content_words(text), trace: encoder.encode(text), entities: SensoryCortex::extract_entities(text) }
+        }));
+
+        let words: HashSet<&str> =
+            prepared[start..].iter().flat_map(|text| &text.words).map(String::as_str).filter(|word| !word_traces.contains_key(*word)).collect();
+        let new_words: Vec<(String, HolographicTrace)> = words
+            .into_par_iter()
+            .filter(|word| hierarchy.concept_id(word).is_none())
+            .map(|word| (word.to_string(), encoder.encode(word)))
+            .collect();
+        word_traces.extend(new_words);
+        progress(AssimilationProgress { stage: AssimilationStage::Encoding, done: prepared.len(), total });
+    }
+    PreparedBatch { texts: prepared, word_traces }
+}
@@ -0,0 +1,113 @@
+//! capabilities.rs - What the Core can do, as its modules describe it.
+//!
+//! Each module that answers a kind of request declares a `CAPABILITY`. The registry gathers
+//! those that are compiled in (feature-gated modules only when their feature is on) and the
+//! tools registered at runtime, so that "what can you do?" is answered from the code that
+//! actually runs rather than from whatever axioms match the question.
+
+use crate::lemmatizer::Lang;
+use serde::Serialize;
+
+/// A kind of request the Core can handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Capability {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub description_fr: &'static str,
+    /// Prompts that use the capability.
+    pub examples: &'static [&'static str],
+}
+
+impl Capability {
+    pub fn description(&self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::French => self.description_fr,
+            Lang::English => self.description,
+        }
+    }
+}
+
+/// The capabilities of a Core, in registration order, without duplicate names.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CapabilityRegistry {
+    capabilities: Vec<Capability>,
+}
+
+impl CapabilityRegistry {
+    /// The capabilities of the modules compiled into this build.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        for capability in [
+            crate::hippocampus::CAPABILITY,
+            crate::knowledge_explorer::CAPABILITY,
+            crate::conceptual_hierarchy::CAPABILITY,
+            crate::mcq_solver::CAPABILITY,
+            crate::numeric_reasoner::CAPABILITY,
+            crate::episodic_memory::CAPABILITY,
+            crate::conversation_log::CAPABILITY,
+            crate::creativity_forge::CAPABILITY,
+            crate::curiosity_engine::CAPABILITY,
+            crate::sleep::CAPABILITY,
+            crate::feedback::CAPABILITY,
+            crate::ethical_core::CAPABILITY,
+        ] {
+            registry.register(capability);
+        }
+        #[cfg(feature = "hot_reload")]
+        registry.register(crate::knowledge_watcher::CAPABILITY);
+        registry
+    }
+
+    /// Adds a capability, replacing the one with the same name.
+    pub fn register(&mut self, capability: Capability) {
+        match self.capabilities.iter_mut().find(|registered| registered.name == capability.name) {
+            Some(registered) => *registered = capability,
+            None => self.capabilities.push(capability),
+        }
+    }
+
+    pub fn capabilities(&self) -> &[Capability] {
+        &self.capabilities
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.capabilities.iter().any(|capability| capability.name == name)
+    }
+
+    /// One line per capability, with its first example.
+    pub fn summary(&self, lang: Lang) -> String {
+        let (example, open, close) = match lang {
+            Lang::French => ("par exemple", "« ", " »"),
+            Lang::English => ("e.g.", "\"", "\""),
+        };
+        self.capabilities
+            .iter()
+            .map(|capability| match capability.examples.first() {
+                Some(first) => format!("- {} ({} {}{}{})", capability.description(lang), example, open, first, close),
+                None => format!("- {}", capability.description(lang)),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_follows_the_compiled_modules() {
+        let mut registry = CapabilityRegistry::builtin();
+        assert!(registry.contains(crate::mcq_solver::CAPABILITY.name));
+        assert_eq!(registry.contains("knowledge_hot_reload"), cfg!(feature = "hot_reload"));
+
+        let count = registry.capabilities().len();
+        registry.register(crate::tools::ActionKind::Calculator.capability());
+        registry.register(crate::tools::ActionKind::Calculator.capability());
+        assert_eq!(registry.capabilities().len(), count + 1);
+
+        let summary = registry.summary(Lang::French);
+        assert_eq!(summary.lines().count(), count + 1);
+        assert!(summary.contains(crate::mcq_solver::CAPABILITY.description_fr));
+    }
+}
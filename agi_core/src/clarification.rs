@@ -0,0 +1,109 @@
+//! clarification.rs - Asking back when a question cannot be answered as asked.
+//!
+//! When the thalamus cannot tell what a prompt is after, or nothing in memory comes close to it,
+//! the nearest memory is a guess. Instead, the Core asks a question about what is missing: the
+//! subject ("c'est quoi, ça ?") or what to tell about it ("Einstein ?"). The question stays
+//! pending for the next prompt of the conversation, which is merged into the original one before
+//! it goes through the pipeline again. A Core serves one conversation, so it holds at most one
+//! pending question; any reply consumes it.
+
+use crate::lemmatizer::Lang;
+use crate::onboarding::{self, FollowUp};
+use crate::thalamus::QueryType;
+
+/// Best retrieval distance above which an answer is a guess, whatever the query type.
+pub const LOW_CONFIDENCE_DISTANCE: f32 = 0.9;
+/// Best retrieval distance under which an ambiguous prompt is answered anyway.
+pub const CONFIDENT_DISTANCE: f32 = 0.5;
+
+/// What a clarifying question asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Missing {
+    /// The prompt names no subject.
+    Subject,
+    /// The prompt names a subject but not what to tell about it.
+    Intent { subject: String },
+}
+
+/// A clarifying question waiting for its answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingClarification {
+    /// The prompt the question was asked about.
+    pub prompt: String,
+    pub missing: Missing,
+    pub lang: Lang,
+}
+
+impl PendingClarification {
+    /// The prompt to answer now: the original prompt completed by the reply. None if the user
+    /// declined, or moved on to a question of its own.
+    pub fn merge(&self, reply: &str) -> Option<String> {
+        let reply = reply.trim();
+        if matches!(onboarding::classify_follow_up(reply), FollowUp::Decline) || is_standalone_question(reply) {
+            return None;
+        }
+        let original = self.prompt.trim().trim_end_matches(['?', '!', '.', ' ']);
+        Some(match &self.missing {
+            Missing::Subject => format!("{} : {}", original, reply),
+            Missing::Intent { subject } if reply.to_lowercase().contains(subject.as_str()) => reply.to_string(),
+            Missing::Intent { subject } => format!("{} {}", reply.trim_end_matches(['?', '!', '.', ' ']), subject),
+        })
+    }
+
+    pub fn question(&self) -> String {
+        match (&self.missing, self.lang) {
+            (Missing::Subject, Lang::French) => "De quoi parlez-vous exactement ?".to_string(),
+            (Missing::Subject, Lang::English) => "What exactly are you referring to?".to_string(),
+            (Missing::Intent { subject }, Lang::French) => format!("Que voulez-vous savoir au sujet de « {} » ?", subject),
+            (Missing::Intent { subject }, Lang::English) => format!("What would you like to know about \"{}\"?", subject),
+        }
+    }
+}
+
+/// Whether a prompt should be answered by a question: nothing was retrieved (`best_distance` is
+/// None), the closest memory is far, or the prompt is ambiguous and the closest memory is not
+/// close enough to settle it.
+pub fn is_needed(query_type: QueryType, best_distance: Option<f32>) -> bool {
+    match best_distance {
+        None => true,
+        Some(distance) if distance > LOW_CONFIDENCE_DISTANCE => true,
+        Some(distance) => query_type == QueryType::Ambiguous && distance > CONFIDENT_DISTANCE,
+    }
+}
+
+/// What to ask about a prompt, given its key concepts.
+pub fn missing(key_concepts: &[String]) -> Missing {
+    match key_concepts.first() {
+        Some(subject) => Missing::Intent { subject: subject.clone() },
+        None => Missing::Subject,
+    }
+}
+
+/// A full question of its own rather than a reply ("what is a quasar?" rather than "its mass").
+fn is_standalone_question(reply: &str) -> bool {
+    const MIN_WORDS: usize = 4;
+    reply.ends_with('?') && reply.split_whitespace().count() >= MIN_WORDS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replies_are_merged_into_the_original_prompt() {
+        assert!(is_needed(QueryType::Factual, None));
+        assert!(is_needed(QueryType::Ambiguous, Some(0.7)));
+        assert!(!is_needed(QueryType::Factual, Some(0.7)));
+
+        let pending = PendingClarification { prompt: "Einstein ?".to_string(), missing: missing(&["einstein".to_string()]), lang: Lang::French };
+        assert_eq!(pending.question(), "Que voulez-vous savoir au sujet de « einstein » ?");
+        assert_eq!(pending.merge("sa date de naissance").as_deref(), Some("sa date de naissance einstein"));
+        assert_eq!(pending.merge("Où est né Einstein").as_deref(), Some("Où est né Einstein"));
+        assert_eq!(pending.merge("laisse tomber"), None);
+        assert_eq!(pending.merge("Qu'est-ce qu'un trou noir ?"), None);
+
+        let pending = PendingClarification { prompt: "C'est quoi, ça ?".to_string(), missing: missing(&[]), lang: Lang::English };
+        assert_eq!(pending.question(), "What exactly are you referring to?");
+        assert_eq!(pending.merge("la relativité").as_deref(), Some("C'est quoi, ça : la relativité"));
+    }
+}
@@ -1,7 +1,66 @@
+use crate::capabilities::Capability;
 use crate::holographic_memory::HolographicTrace;
 use crate::lemmatizer;
+use crate::symbols::Sym;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use thiserror::Error;
+
+/// Taxonomic reasoning, as listed by `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "concepts",
+    description: "Organize concepts into categories and reason over them",
+    description_fr: "Ranger les concepts en catégories et raisonner dessus",
+    examples: &["Un caniche est un chien.", "Is a poodle an animal?"],
+};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum HierarchyError {
+    #[error("unknown concept #{0}")]
+    UnknownConcept(u64),
+    #[error("'{0}' cannot be its own parent")]
+    SelfLink(String),
+    #[error("'{parent}' is already a kind of '{child}': the link would create a cycle")]
+    Cycle { child: String, parent: String },
+}
+
+/// An inconsistency found by `ConceptualHierarchy::validate`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum HierarchyIssue {
+    /// A concept links to a parent, child or domain that does not exist.
+    DanglingLink { concept: u64, target: u64 },
+    /// A concept lists a parent that does not list it as a child, or the reverse.
+    OneWayLink { child: u64, parent: u64 },
+    /// A concept is among its own ancestors.
+    Cycle { concept: u64 },
+    /// A concept is not more abstract than one of its parents.
+    AbstractionLevel { concept: u64, level: usize, parent_level: usize },
+    /// A name resolves to a concept that does not exist.
+    DanglingName { name: String, target: u64 },
+}
+
+/// A file format the hierarchy can be exported to, for graph tools (see `write_graph`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    /// GraphML, read by Gephi, yEd or Cytoscape.
+    GraphMl,
+    /// The DOT language of Graphviz.
+    Dot,
+}
+
+impl GraphFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            GraphFormat::GraphMl => "graphml",
+            GraphFormat::Dot => "dot",
+        }
+    }
+}
 
 /// Represents a single node in the conceptual hierarchy.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -13,13 +72,87 @@ pub struct ConceptNode {
     pub children: HashSet<u64>,
     pub domains: HashSet<u64>, // Links to domain concepts
     pub abstraction_level: usize,
+    /// Other (lemmatized) names that resolve to this concept, e.g. after a merge.
+    #[serde(default)]
+    pub aliases: HashSet<String>,
+}
+
+/// Describes how concept IDs changed after a merge or split, so that other structures
+/// referencing concepts (memories, neuron assemblies...) can be kept consistent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConceptRemap {
+    /// Maps each removed concept ID to the concept IDs that replace it.
+    pub replaced: HashMap<u64, Vec<u64>>,
+}
+
+impl ConceptRemap {
+    /// Rewrites a list of concept IDs according to the remap, without duplicates.
+    pub fn apply(&self, ids: &[u64]) -> Vec<u64> {
+        let mut result = Vec::with_capacity(ids.len());
+        for id in ids {
+            match self.replaced.get(id) {
+                Some(replacements) => result.extend(replacements.iter().copied()),
+                None => result.push(*id),
+            }
+        }
+        let mut seen = HashSet::new();
+        result.retain(|id| seen.insert(*id));
+        result
+    }
+}
+
+/// A new concept of a split (see `ConceptualHierarchy::split_concept`): its name, and the
+/// words that hand a related concept or a memory to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitCriterion {
+    pub name: String,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+impl SplitCriterion {
+    /// The index of the first criterion with a keyword in `text` (the name of a related concept,
+    /// or the text of a memory); 0 when none matches.
+    pub fn assign(criteria: &[SplitCriterion], text: &str) -> usize {
+        let text = text.to_lowercase();
+        criteria
+            .iter()
+            .position(|criterion| criterion.keywords.iter().any(|keyword| text.contains(&keyword.to_lowercase())))
+            .unwrap_or(0)
+    }
+}
+
+/// Two concepts unified by a merge, by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConceptMerge {
+    pub kept: String,
+    pub absorbed: String,
+}
+
+/// A reference to a concept by ID and name, as shown to external tools.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConceptRef {
+    pub id: u64,
+    pub name: String,
+}
+
+/// A concept with its immediate neighbourhood in the graph, without its trace.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConceptView {
+    pub id: u64,
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub abstraction_level: usize,
+    pub parents: Vec<ConceptRef>,
+    pub children: Vec<ConceptRef>,
+    pub domains: Vec<ConceptRef>,
 }
 
 /// Manages the entire graph of concepts.
 #[derive(Serialize, Deserialize)]
 pub struct ConceptualHierarchy {
     nodes: HashMap<u64, ConceptNode>,
-    name_to_id: HashMap<String, u64>,
+    name_to_id: HashMap<Sym, u64>,
     next_id: u64,
 }
 
@@ -35,17 +168,28 @@ impl ConceptualHierarchy {
 
     /// Lemmatizes a name to its base form.
     /// Lemmatizes a name to its base form.
-    /// Lemmatizes a name to its base form using the custom lemmatizer.
+    /// Lemmatizes a name to its base form, with the rules of the language detected for it.
     fn lemmatize_name(&self, name: &str) -> String {
-        lemmatizer::lemmatize(name)
+        lemmatizer::lemmatize_auto(name)
+    }
+
+    /// The concept a (lemmatized) name or alias maps to.
+    fn id_of(&self, name: &str) -> Option<u64> {
+        Sym::lookup(name).and_then(|name| self.name_to_id.get(&name)).copied()
+    }
+
+    /// The concept `add_concept` would return for `name` without creating one: the concept of its
+    /// lemma or of an alias.
+    pub fn concept_id(&self, name: &str) -> Option<u64> {
+        self.id_of(&self.lemmatize_name(name))
     }
 
     /// Adds a concept, using its lemmatized name. If it exists, returns existing ID.
     pub fn add_concept(&mut self, name: &str, trace: HolographicTrace, parents: &[u64]) -> u64 {
         let lemma = self.lemmatize_name(name);
 
-        if let Some(existing_id) = self.name_to_id.get(&lemma) {
-            return *existing_id;
+        if let Some(existing_id) = self.id_of(&lemma) {
+            return existing_id;
         }
 
         let new_id = self.next_id;
@@ -62,10 +206,11 @@ impl ConceptualHierarchy {
             children: HashSet::new(),
             domains: HashSet::new(),
             abstraction_level,
+            aliases: HashSet::new(),
         };
 
         self.nodes.insert(new_id, new_node);
-        self.name_to_id.insert(lemma, new_id);
+        self.name_to_id.insert(Sym::intern(&lemma), new_id);
 
         for parent_id in parent_set {
             if let Some(parent_node) = self.nodes.get_mut(&parent_id) {
@@ -85,7 +230,8 @@ impl ConceptualHierarchy {
         }
     }
 
-    /// Adds a relationship between two concepts.
+    /// Links two concepts as is, without the cycle check and the abstraction levels of
+    /// `learn_relationship`.
     pub fn add_relationship(&mut self, child_id: u64, parent_id: u64) {
         if self.nodes.contains_key(&child_id) && self.nodes.contains_key(&parent_id) {
             if let Some(child_node) = self.nodes.get_mut(&child_id) {
@@ -97,10 +243,33 @@ impl ConceptualHierarchy {
         }
     }
 
-    /// Finds a concept by its (lemmatized) name.
+    /// Finds a concept by its (lemmatized) name or one of its aliases. Names the lemmatizer
+    /// does not handle ("E=mc²") are also looked up as written, lowercased.
     pub fn find_concept_by_name(&self, name: &str) -> Option<&ConceptNode> {
         let lemma = self.lemmatize_name(name);
-        self.name_to_id.get(&lemma).and_then(|id| self.nodes.get(id))
+        self.id_of(&lemma)
+            .or_else(|| self.id_of(&name.trim().to_lowercase()))
+            .and_then(|id| self.nodes.get(&id))
+    }
+
+    /// Makes `name` resolve to the concept `concept_id` ("E=mc²", "équivalence masse-énergie"
+    /// for "mass-energy equivalence"). Returns false if the concept is unknown or the name
+    /// already names another concept: use `merge_concepts` to unify two existing concepts.
+    pub fn add_alias(&mut self, concept_id: u64, name: &str) -> bool {
+        let lemma = self.lemmatize_name(&name.trim().to_lowercase());
+        if lemma.is_empty() || !self.nodes.contains_key(&concept_id) {
+            return false;
+        }
+        match self.id_of(&lemma) {
+            Some(id) => return id == concept_id,
+            None => {
+                self.name_to_id.insert(Sym::intern(&lemma), concept_id);
+            }
+        }
+        if let Some(node) = self.nodes.get_mut(&concept_id) {
+            node.aliases.insert(lemma);
+        }
+        true
     }
 
     /// Returns a vector of references to all concept nodes.
@@ -131,11 +300,43 @@ impl ConceptualHierarchy {
 
     /// Returns a sorted list of all concept names in the hierarchy.
     pub fn get_all_concept_names(&self) -> Vec<String> {
-        let mut names: Vec<String> = self.name_to_id.keys().cloned().collect();
+        let mut names: Vec<String> = self.name_to_id.keys().map(Sym::to_string).collect();
         names.sort();
         names
     }
 
+    /// Lists every concept, sorted by name.
+    pub fn concept_refs(&self) -> Vec<ConceptRef> {
+        let mut refs: Vec<ConceptRef> = self.nodes.values().map(|node| ConceptRef { id: node.id, name: node.name.clone() }).collect();
+        refs.sort_by(|a, b| a.name.cmp(&b.name));
+        refs
+    }
+
+    /// Describes a concept found by name (or alias) with its parents, children and domains.
+    pub fn concept_view(&self, name: &str) -> Option<ConceptView> {
+        let node = self.find_concept_by_name(name)?;
+        let refs = |ids: &HashSet<u64>| {
+            let mut refs: Vec<ConceptRef> = ids
+                .iter()
+                .filter_map(|id| self.nodes.get(id))
+                .map(|node| ConceptRef { id: node.id, name: node.name.clone() })
+                .collect();
+            refs.sort_by(|a, b| a.name.cmp(&b.name));
+            refs
+        };
+        let mut aliases: Vec<String> = node.aliases.iter().cloned().collect();
+        aliases.sort();
+        Some(ConceptView {
+            id: node.id,
+            name: node.name.clone(),
+            aliases,
+            abstraction_level: node.abstraction_level,
+            parents: refs(&node.parents),
+            children: refs(&node.children),
+            domains: refs(&node.domains),
+        })
+    }
+
     /// Retrieves the IDs of the parent concepts for a given concept ID.
     pub fn get_parents(&self, concept_id: u64) -> Option<HashSet<u64>> {
         self.nodes.get(&concept_id).map(|node| node.parents.clone())
@@ -181,8 +382,8 @@ impl ConceptualHierarchy {
     /// This is a primary method for interacting with the hierarchy.
     pub fn find_or_create_concept(&mut self, name: &str) -> u64 {
         let lemma = self.lemmatize_name(name);
-        if let Some(id) = self.name_to_id.get(&lemma) {
-            return *id;
+        if let Some(id) = self.id_of(&lemma) {
+            return id;
         }
 
         // Concept doesn't exist, so create it with a unique seeded trace.
@@ -190,47 +391,37 @@ impl ConceptualHierarchy {
         self.add_concept(&lemma, trace, &[])
     }
 
-    /// Establishes a parent-child relationship between two existing concepts.
-    ///
-    /// # Arguments
-    /// * `child_id` - The ID of the more specific concept.
-    /// * `parent_id` - The ID of the more abstract concept.
-    ///
-    /// # Returns
-    /// `true` if the relationship was created, `false` otherwise (e.g., if IDs are invalid or a cycle is detected).
-    /// Establishes a parent-child relationship between two concepts identified by their names.
-    pub fn learn_relationship_by_name(&mut self, child_name: &str, parent_name: &str) -> bool {
+    /// Establishes a parent-child relationship between two concepts identified by their names,
+    /// creating them if needed (see `learn_relationship`).
+    pub fn learn_relationship_by_name(&mut self, child_name: &str, parent_name: &str) -> Result<(), HierarchyError> {
         let child_id = self.find_or_create_concept(child_name);
         let parent_id = self.find_or_create_concept(parent_name);
         self.learn_relationship(child_id, parent_id)
     }
 
-    pub fn learn_relationship(&mut self, child_id: u64, parent_id: u64) -> bool {
-        if child_id == parent_id { return false; } // Prevent self-parenting
-
-        // Ensure both nodes exist before creating mutable borrows
-        if !self.nodes.contains_key(&child_id) || !self.nodes.contains_key(&parent_id) {
-            return false;
+    /// Establishes a parent-child relationship between two existing concepts: the parent's trace
+    /// superposes the child's, and the child and its descendants become more abstract than the
+    /// parent. A link that would make a concept its own ancestor is rejected.
+    ///
+    /// # Arguments
+    /// * `child_id` - The ID of the more specific concept.
+    /// * `parent_id` - The ID of the more abstract concept.
+    pub fn learn_relationship(&mut self, child_id: u64, parent_id: u64) -> Result<(), HierarchyError> {
+        let child_trace = self.nodes.get(&child_id).ok_or(HierarchyError::UnknownConcept(child_id))?.trace.clone();
+        let parent = self.nodes.get(&parent_id).ok_or(HierarchyError::UnknownConcept(parent_id))?;
+        if child_id == parent_id {
+            return Err(HierarchyError::SelfLink(parent.name.clone()));
         }
-
-        // Check for cycles: does the parent have the child as an ancestor?
-        // A simple check is sufficient for now, but a full traversal would be more robust.
-        // For this implementation, we'll proceed and rely on the abstraction level update.
+        if self.ancestors(parent_id).contains(&child_id) {
+            let child = self.nodes.get(&child_id).map(|node| node.name.clone()).unwrap_or_default();
+            return Err(HierarchyError::Cycle { child, parent: parent.name.clone() });
+        }
+        let parent_abstraction_level = parent.abstraction_level;
 
         // --- Holographic Superposition ---
         // The parent's trace is updated with the child's trace.
-        if let Some(child_node) = self.nodes.get(&child_id) {
-            let child_trace = child_node.trace.clone(); // Clone to avoid mutable/immutable borrow issues
-            if let Some(parent_node) = self.nodes.get_mut(&parent_id) {
-                parent_node.trace.combine_with(&child_trace);
-            }
-        }
-        // --------------------------------
-
-        let parent_abstraction_level = self.nodes.get(&parent_id).unwrap().abstraction_level;
-
-        // Link parent to child
         if let Some(parent_node) = self.nodes.get_mut(&parent_id) {
+            parent_node.trace.combine_with(&child_trace);
             parent_node.children.insert(child_id);
         }
 
@@ -248,13 +439,281 @@ impl ConceptualHierarchy {
                 }
             }
         }
-        
-        true
+        Ok(())
+    }
+
+    /// Every concept above `concept_id`: its parents, their parents, and so on. Safe on a
+    /// hierarchy that already contains a cycle.
+    pub fn ancestors(&self, concept_id: u64) -> HashSet<u64> {
+        let mut ancestors = HashSet::new();
+        let mut pending: Vec<u64> = self.nodes.get(&concept_id).map(|node| node.parents.iter().copied().collect()).unwrap_or_default();
+        while let Some(id) = pending.pop() {
+            if ancestors.insert(id) {
+                pending.extend(self.nodes.get(&id).into_iter().flat_map(|node| node.parents.iter().copied()));
+            }
+        }
+        ancestors
+    }
+
+    /// Audits the whole hierarchy: links to missing concepts, links known to one side only,
+    /// cycles, abstraction levels not above those of the parents, and names resolving to
+    /// missing concepts. An empty list means the hierarchy is consistent.
+    pub fn validate(&self) -> Vec<HierarchyIssue> {
+        let mut issues = Vec::new();
+        let mut ids: Vec<&u64> = self.nodes.keys().collect();
+        ids.sort();
+        for &id in ids {
+            let node = &self.nodes[&id];
+            for &target in node.parents.iter().chain(&node.children).chain(&node.domains) {
+                if !self.nodes.contains_key(&target) {
+                    issues.push(HierarchyIssue::DanglingLink { concept: id, target });
+                }
+            }
+            for parent in node.parents.iter().filter_map(|parent_id| self.nodes.get(parent_id)) {
+                if !parent.children.contains(&id) {
+                    issues.push(HierarchyIssue::OneWayLink { child: id, parent: parent.id });
+                }
+                if node.abstraction_level <= parent.abstraction_level {
+                    issues.push(HierarchyIssue::AbstractionLevel { concept: id, level: node.abstraction_level, parent_level: parent.abstraction_level });
+                }
+            }
+            for child in node.children.iter().filter_map(|child_id| self.nodes.get(child_id)) {
+                if !child.parents.contains(&id) {
+                    issues.push(HierarchyIssue::OneWayLink { child: child.id, parent: id });
+                }
+            }
+            if self.ancestors(id).contains(&id) {
+                issues.push(HierarchyIssue::Cycle { concept: id });
+            }
+        }
+        let mut names: Vec<(String, u64)> =
+            self.name_to_id.iter().filter(|(_, id)| !self.nodes.contains_key(id)).map(|(name, &id)| (name.to_string(), id)).collect();
+        names.sort();
+        issues.extend(names.into_iter().map(|(name, target)| HierarchyIssue::DanglingName { name, target }));
+        issues
+    }
+
+    /// Writes the hierarchy as a GraphML file (see `write_graph`).
+    pub fn export_graphml<P: AsRef<Path>>(&self, path: P, memory_counts: &HashMap<u64, usize>) -> io::Result<()> {
+        self.export_graph(path, GraphFormat::GraphMl, memory_counts)
+    }
+
+    /// Writes the hierarchy as a Graphviz DOT file (see `write_graph`).
+    pub fn export_dot<P: AsRef<Path>>(&self, path: P, memory_counts: &HashMap<u64, usize>) -> io::Result<()> {
+        self.export_graph(path, GraphFormat::Dot, memory_counts)
+    }
+
+    fn export_graph<P: AsRef<Path>>(&self, path: P, format: GraphFormat, memory_counts: &HashMap<u64, usize>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_graph(&mut out, format, memory_counts)?;
+        out.flush()
+    }
+
+    /// Writes the hierarchy as a graph: one node per concept, with its name, abstraction level,
+    /// domains and the number of memories about it (from `memory_counts`, by concept ID), an
+    /// `is_a` edge from each concept to each parent and a `domain` edge to each domain.
+    /// Nodes and edges are written in ID order, so that exports can be diffed.
+    pub fn write_graph<W: Write>(&self, out: &mut W, format: GraphFormat, memory_counts: &HashMap<u64, usize>) -> io::Result<()> {
+        let mut nodes: Vec<&ConceptNode> = self.nodes.values().collect();
+        nodes.sort_by_key(|node| node.id);
+        let sorted = |ids: &HashSet<u64>| {
+            let mut ids: Vec<u64> = ids.iter().copied().filter(|id| self.nodes.contains_key(id)).collect();
+            ids.sort();
+            ids
+        };
+        let domain_names = |node: &ConceptNode| sorted(&node.domains).into_iter().map(|id| self.nodes[&id].name.as_str()).collect::<Vec<_>>().join(";");
+
+        match format {
+            GraphFormat::GraphMl => {
+                writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+                writeln!(out, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+                writeln!(out, r#"  <key id="name" for="node" attr.name="name" attr.type="string"/>"#)?;
+                writeln!(out, r#"  <key id="abstraction_level" for="node" attr.name="abstraction_level" attr.type="int"/>"#)?;
+                writeln!(out, r#"  <key id="domains" for="node" attr.name="domains" attr.type="string"/>"#)?;
+                writeln!(out, r#"  <key id="memory_count" for="node" attr.name="memory_count" attr.type="int"/>"#)?;
+                writeln!(out, r#"  <key id="relation" for="edge" attr.name="relation" attr.type="string"/>"#)?;
+                writeln!(out, r#"  <graph id="concepts" edgedefault="directed">"#)?;
+                for node in &nodes {
+                    writeln!(out, r#"    <node id="c{}">"#, node.id)?;
+                    writeln!(out, r#"      <data key="name">{}</data>"#, xml_escape(&node.name))?;
+                    writeln!(out, r#"      <data key="abstraction_level">{}</data>"#, node.abstraction_level)?;
+                    writeln!(out, r#"      <data key="domains">{}</data>"#, xml_escape(&domain_names(node)))?;
+                    writeln!(out, r#"      <data key="memory_count">{}</data>"#, memory_counts.get(&node.id).copied().unwrap_or(0))?;
+                    writeln!(out, "    </node>")?;
+                }
+                for node in &nodes {
+                    let edges = sorted(&node.parents).into_iter().map(|id| (id, "is_a")).chain(sorted(&node.domains).into_iter().map(|id| (id, "domain")));
+                    for (target, relation) in edges {
+                        writeln!(out, r#"    <edge source="c{}" target="c{}"><data key="relation">{}</data></edge>"#, node.id, target, relation)?;
+                    }
+                }
+                writeln!(out, "  </graph>")?;
+                writeln!(out, "</graphml>")
+            }
+            GraphFormat::Dot => {
+                writeln!(out, "digraph concepts {{")?;
+                writeln!(out, "  rankdir=BT;")?;
+                for node in &nodes {
+                    writeln!(
+                        out,
+                        r#"  c{} [label="{}", abstraction_level={}, domains="{}", memory_count={}];"#,
+                        node.id,
+                        dot_escape(&node.name),
+                        node.abstraction_level,
+                        dot_escape(&domain_names(node)),
+                        memory_counts.get(&node.id).copied().unwrap_or(0)
+                    )?;
+                }
+                for node in &nodes {
+                    for parent in sorted(&node.parents) {
+                        writeln!(out, r#"  c{} -> c{} [relation="is_a"];"#, node.id, parent)?;
+                    }
+                    for domain in sorted(&node.domains) {
+                        writeln!(out, r#"  c{} -> c{} [relation="domain", style=dashed];"#, node.id, domain)?;
+                    }
+                }
+                writeln!(out, "}}")
+            }
+        }
+    }
+
+    /// Merges concept `b` into concept `a`.
+    ///
+    /// `a` receives the union of both concepts' parents, children and domains, and `b`'s trace is
+    /// superposed onto its own. `b`'s name (and aliases) are kept as aliases of `a`, so lookups by
+    /// the old name keep working. Every reference to `b` elsewhere in the hierarchy is redirected to `a`.
+    pub fn merge_concepts(&mut self, a: u64, b: u64) -> Option<ConceptRemap> {
+        if a == b || !self.nodes.contains_key(&a) || !self.nodes.contains_key(&b) {
+            return None;
+        }
+        let absorbed = self.nodes.remove(&b)?;
+
+        // Redirect every reference to `b` towards `a`.
+        for node in self.nodes.values_mut() {
+            for links in [&mut node.parents, &mut node.children, &mut node.domains] {
+                if links.remove(&b) && node.id != a {
+                    links.insert(a);
+                }
+            }
+        }
+        for id in self.name_to_id.values_mut() {
+            if *id == b {
+                *id = a;
+            }
+        }
+
+        let survivor = self.nodes.get_mut(&a)?;
+        survivor.parents.extend(absorbed.parents.iter().filter(|&&id| id != a));
+        survivor.children.extend(absorbed.children.iter().filter(|&&id| id != a));
+        survivor.domains.extend(absorbed.domains.iter().filter(|&&id| id != a));
+        survivor.trace.combine_with(&absorbed.trace);
+        survivor.aliases.insert(absorbed.name.clone());
+        survivor.aliases.extend(absorbed.aliases);
+        // A merged concept is at least as abstract as the most abstract of its halves.
+        survivor.abstraction_level = survivor.abstraction_level.max(absorbed.abstraction_level);
+        let level = survivor.abstraction_level;
+        let children: Vec<u64> = survivor.children.iter().copied().collect();
+        for child_id in children {
+            self.update_abstraction_levels_recursive(child_id, level);
+        }
+
+        println!("--- Concept '{}' merged into concept #{} ---", absorbed.name, a);
+        let mut remap = ConceptRemap::default();
+        remap.replaced.insert(b, vec![a]);
+        Some(remap)
+    }
+
+    /// Pairs of concepts whose names differ only by case, hyphens, spaces or apostrophes
+    /// ("e-mail" and "email"), as (kept, absorbed) for `merge_concepts`: the concept with the
+    /// most links is kept, the older one on a tie.
+    pub fn duplicate_concepts(&self) -> Vec<(u64, u64)> {
+        let folded = |name: &str| -> String { name.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect() };
+        let mut by_name: HashMap<String, Vec<&ConceptNode>> = HashMap::new();
+        for node in self.nodes.values() {
+            let key = folded(&node.name);
+            if !key.is_empty() {
+                by_name.entry(key).or_default().push(node);
+            }
+        }
+        let links = |node: &ConceptNode| node.parents.len() + node.children.len() + node.domains.len();
+        let mut pairs = Vec::new();
+        for mut group in by_name.into_values().filter(|group| group.len() > 1) {
+            group.sort_by(|a, b| links(b).cmp(&links(a)).then(a.id.cmp(&b.id)));
+            pairs.extend(group[1..].iter().map(|node| (group[0].id, node.id)));
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// Splits an overloaded concept into several new concepts.
+    ///
+    /// Each new concept starts with a copy of the original trace. Every parent, child and domain of
+    /// the original is handed to the new concept whose index `assign` returns for that neighbour's
+    /// name (indices out of range fall back to the first new concept). The original concept is
+    /// removed; its name stays resolvable only if it is one of `new_names`.
+    pub fn split_concept<F>(&mut self, id: u64, new_names: &[&str], assign: F) -> Option<(Vec<u64>, ConceptRemap)>
+    where
+        F: Fn(&str) -> usize,
+    {
+        if new_names.is_empty() || !self.nodes.contains_key(&id) {
+            return None;
+        }
+        let original = self.nodes.remove(&id)?;
+        self.name_to_id.retain(|_, mapped| *mapped != id);
+
+        let mut new_ids = Vec::with_capacity(new_names.len());
+        for name in new_names {
+            let new_id = self.add_concept(name, original.trace.clone(), &[]);
+            if let Some(node) = self.nodes.get_mut(&new_id) {
+                node.abstraction_level = node.abstraction_level.max(original.abstraction_level);
+            }
+            new_ids.push(new_id);
+        }
+
+        let target_for = |hierarchy: &Self, neighbour: u64| -> u64 {
+            let name = hierarchy.nodes.get(&neighbour).map(|n| n.name.as_str()).unwrap_or_default();
+            *new_ids.get(assign(name)).unwrap_or(&new_ids[0])
+        };
+
+        for parent_id in &original.parents {
+            let target = target_for(self, *parent_id);
+            if let Some(parent) = self.nodes.get_mut(parent_id) {
+                parent.children.remove(&id);
+            }
+            // The links of the original concept are acyclic, and so are those of its parts.
+            let _ = self.learn_relationship(target, *parent_id);
+        }
+        for child_id in &original.children {
+            let target = target_for(self, *child_id);
+            if let Some(child) = self.nodes.get_mut(child_id) {
+                child.parents.remove(&id);
+            }
+            let _ = self.learn_relationship(*child_id, target);
+        }
+        for domain_id in &original.domains {
+            let target = target_for(self, *domain_id);
+            self.add_domain_to_concept(target, *domain_id);
+        }
+        // Concepts that used the split concept as their domain now reference every new concept.
+        for node in self.nodes.values_mut() {
+            if node.domains.remove(&id) {
+                node.domains.extend(new_ids.iter().copied());
+            }
+        }
+
+        println!("--- Concept '{}' split into {:?} ---", original.name, new_names);
+        let mut remap = ConceptRemap::default();
+        remap.replaced.insert(id, new_ids.clone());
+        Some((new_ids, remap))
     }
 
     /// Recursively updates the abstraction level for a node and all its descendants.
     fn update_abstraction_levels_recursive(&mut self, node_id: u64, parent_level: usize) {
         let new_level = parent_level + 1;
+        // Deeper than the number of concepts: the descendants loop back (see `validate`).
+        if new_level > self.nodes.len() {
+            return;
+        }
         let children_to_update: Vec<u64> = if let Some(node) = self.nodes.get_mut(&node_id) {
             if new_level > node.abstraction_level {
                 node.abstraction_level = new_level;
@@ -272,3 +731,158 @@ impl ConceptualHierarchy {
         }
     }
 }
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_keeps_alias_and_relations() {
+        let mut hierarchy = ConceptualHierarchy::new();
+        let vehicle = hierarchy.add_concept("véhicule", HolographicTrace::new_empty(16), &[]);
+        let car = hierarchy.add_concept("automobile", HolographicTrace::new_empty(16), &[vehicle]);
+        let synonym = hierarchy.add_concept("voiture", HolographicTrace::new_empty(16), &[]);
+        let taxi = hierarchy.add_concept("taxi", HolographicTrace::new_empty(16), &[synonym]);
+
+        let remap = hierarchy.merge_concepts(car, synonym).unwrap();
+        assert_eq!(remap.apply(&[synonym, car, taxi]), vec![car, taxi]);
+        assert!(hierarchy.get_concept(synonym).is_none());
+        assert_eq!(hierarchy.find_concept_by_name("voiture").unwrap().id, car);
+
+        let merged = hierarchy.get_concept(car).unwrap();
+        assert!(merged.parents.contains(&vehicle));
+        assert!(merged.children.contains(&taxi));
+        assert!(hierarchy.get_concept(taxi).unwrap().parents.contains(&car));
+        assert!(!hierarchy.get_all_concepts().iter().any(|node| node.parents.contains(&synonym) || node.children.contains(&synonym)));
+    }
+
+    #[test]
+    fn test_aliases_resolve_to_the_concept() {
+        let mut hierarchy = ConceptualHierarchy::new();
+        let equivalence = hierarchy.add_concept("mass-energy equivalence", HolographicTrace::new_empty(16), &[]);
+        let relativity = hierarchy.add_concept("relativité", HolographicTrace::new_empty(16), &[]);
+        assert!(hierarchy.add_alias(equivalence, "E=mc²"));
+        assert!(hierarchy.add_alias(equivalence, "équivalence masse-énergie"));
+        assert!(hierarchy.add_alias(equivalence, "E=mc²"), "Adding an alias twice is harmless");
+        assert!(!hierarchy.add_alias(equivalence, "relativité"), "Already the name of another concept");
+        assert!(!hierarchy.add_alias(42, "quasar"));
+
+        assert_eq!(hierarchy.find_concept_by_name("E=mc²").unwrap().id, equivalence);
+        assert_eq!(hierarchy.find_concept_by_name("équivalence masse-énergie").unwrap().id, equivalence);
+        assert_eq!(hierarchy.find_concept_by_name("relativité").unwrap().id, relativity);
+        assert_eq!(hierarchy.concept_view("e=mc²").unwrap().aliases.len(), 2);
+    }
+
+    #[test]
+    fn test_concept_views_show_the_neighbourhood() {
+        let mut hierarchy = ConceptualHierarchy::new();
+        let animal = hierarchy.add_concept("animal", HolographicTrace::new_empty(16), &[]);
+        let dog = hierarchy.add_concept("chien", HolographicTrace::new_empty(16), &[animal]);
+        let poodle = hierarchy.add_concept("caniche", HolographicTrace::new_empty(16), &[dog]);
+        let biology = hierarchy.add_concept("biologie", HolographicTrace::new_empty(16), &[]);
+        hierarchy.add_domain_to_concept(dog, biology);
+        hierarchy.add_alias(dog, "toutou");
+
+        let view = hierarchy.concept_view("toutou").unwrap();
+        assert_eq!(view.id, dog);
+        assert_eq!(view.aliases.len(), 1);
+        assert_eq!(view.parents, vec![ConceptRef { id: animal, name: "animal".to_string() }]);
+        assert_eq!(view.children, vec![ConceptRef { id: poodle, name: "caniche".to_string() }]);
+        assert_eq!(view.domains, vec![ConceptRef { id: biology, name: "biologie".to_string() }]);
+        assert!(hierarchy.concept_view("chat").is_none());
+        let names: Vec<String> = hierarchy.concept_refs().into_iter().map(|concept| concept.name).collect();
+        assert_eq!(names, ["animal", "biologie", "caniche", "chien"]);
+    }
+
+    #[test]
+    fn test_cycles_are_rejected_and_reported() {
+        let mut hierarchy = ConceptualHierarchy::new();
+        hierarchy.learn_relationship_by_name("caniche", "chien").unwrap();
+        hierarchy.learn_relationship_by_name("chien", "animal").unwrap();
+        assert_eq!(
+            hierarchy.learn_relationship_by_name("animal", "caniche"),
+            Err(HierarchyError::Cycle { child: "animal".to_string(), parent: "caniche".to_string() })
+        );
+        assert_eq!(hierarchy.learn_relationship_by_name("chien", "chien"), Err(HierarchyError::SelfLink("chien".to_string())));
+        assert_eq!(hierarchy.learn_relationship(0, 99), Err(HierarchyError::UnknownConcept(99)));
+        assert!(hierarchy.validate().is_empty());
+        let poodle = hierarchy.find_concept_by_name("caniche").unwrap().id;
+        assert_eq!(hierarchy.find_concept_by_name("caniche").unwrap().abstraction_level, 2);
+
+        // A cycle slipped in without `learn_relationship`, e.g. from an old saved hierarchy.
+        let animal = hierarchy.find_concept_by_name("animal").unwrap().id;
+        hierarchy.add_relationship(animal, poodle);
+        let issues = hierarchy.validate();
+        assert!(issues.contains(&HierarchyIssue::Cycle { concept: animal }));
+        assert!(issues.contains(&HierarchyIssue::AbstractionLevel { concept: animal, level: 0, parent_level: 2 }));
+    }
+
+    #[test]
+    fn test_duplicates_and_split_criteria() {
+        let mut hierarchy = ConceptualHierarchy::new();
+        let mail = hierarchy.add_concept("email", HolographicTrace::new_empty(16), &[]);
+        let hyphenated = hierarchy.add_concept("e-mail", HolographicTrace::new_empty(16), &[]);
+        let message = hierarchy.add_concept("message", HolographicTrace::new_empty(16), &[]);
+        hierarchy.learn_relationship(hyphenated, message).unwrap();
+        hierarchy.add_concept("poison", HolographicTrace::new_empty(16), &[]);
+        hierarchy.add_concept("poisson", HolographicTrace::new_empty(16), &[]);
+        assert_eq!(hierarchy.duplicate_concepts(), vec![(hyphenated, mail)], "The linked one is kept; close spellings are not duplicates");
+
+        let criteria = [
+            SplitCriterion { name: "avocat (juriste)".to_string(), keywords: vec!["tribunal".to_string(), "droit".to_string()] },
+            SplitCriterion { name: "avocat (fruit)".to_string(), keywords: vec!["guacamole".to_string(), "Arbre".to_string()] },
+        ];
+        assert_eq!(SplitCriterion::assign(&criteria, "L'avocat pousse sur un arbre."), 1);
+        assert_eq!(SplitCriterion::assign(&criteria, "L'avocat plaide au tribunal."), 0);
+        assert_eq!(SplitCriterion::assign(&criteria, "Un avocat."), 0);
+    }
+
+    #[test]
+    fn test_graph_export_formats() {
+        let mut hierarchy = ConceptualHierarchy::new();
+        let animal = hierarchy.add_concept("animal", HolographicTrace::new_empty(16), &[]);
+        let dog = hierarchy.add_concept("chien", HolographicTrace::new_empty(16), &[animal]);
+        let biology = hierarchy.add_concept("biologie", HolographicTrace::new_empty(16), &[]);
+        hierarchy.add_domain_to_concept(dog, biology);
+        let memory_counts = HashMap::from([(dog, 3)]);
+
+        let mut graphml = Vec::new();
+        hierarchy.write_graph(&mut graphml, GraphFormat::GraphMl, &memory_counts).unwrap();
+        let graphml = String::from_utf8(graphml).unwrap();
+        assert!(graphml.contains(&format!(r#"<edge source="c{}" target="c{}"><data key="relation">is_a</data></edge>"#, dog, animal)));
+        assert!(graphml.contains(r#"<data key="domains">biologie</data>"#));
+        assert!(graphml.contains(r#"<data key="memory_count">3</data>"#));
+
+        let mut dot = Vec::new();
+        hierarchy.write_graph(&mut dot, GraphFormat::Dot, &memory_counts).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.starts_with("digraph concepts {"));
+        assert!(dot.contains(&format!(r#"c{} -> c{} [relation="domain", style=dashed];"#, dog, biology)));
+        assert_eq!(dot_escape(r#"say "hi""#), r#"say \"hi\""#);
+    }
+
+    #[test]
+    fn test_split_assigns_relations() {
+        let mut hierarchy = ConceptualHierarchy::new();
+        let lawyer = hierarchy.add_concept("avocat", HolographicTrace::new_empty(16), &[]);
+        let criminal = hierarchy.add_concept("pénaliste", HolographicTrace::new_empty(16), &[lawyer]);
+        let hass = hierarchy.add_concept("hass", HolographicTrace::new_empty(16), &[lawyer]);
+
+        let (new_ids, remap) = hierarchy
+            .split_concept(lawyer, &["juriste", "fruit"], |name| usize::from(name == "hass"))
+            .unwrap();
+        assert_eq!(remap.apply(&[lawyer]), new_ids);
+        assert!(hierarchy.find_concept_by_name("avocat").is_none());
+        assert!(hierarchy.get_concept(criminal).unwrap().parents.contains(&new_ids[0]));
+        assert!(hierarchy.get_concept(hass).unwrap().parents.contains(&new_ids[1]));
+        assert!(!hierarchy.get_concept(hass).unwrap().parents.contains(&lawyer));
+    }
+}
@@ -0,0 +1,182 @@
+//! config.rs - Tunable parameters of the AGI Core.
+
+use crate::connectome::StdpConfig;
+use crate::domain_routing::DomainRoutingConfig;
+use crate::entanglement_map::EntanglementConfig;
+use crate::lemmatizer::Lang;
+use crate::lexical_index::FusionConfig;
+use crate::mcq_solver::McqConfig;
+use crate::pipeline::PipelineTimeouts;
+use crate::quantum_backend::QuantumBackendKind;
+use crate::quantum_readout::ReadoutConfig;
+use crate::source_trust::TrustWeights;
+use crate::thalamus::QueryType;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Retrieval parameters used by the reasoning engine for each sub-query.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct RetrievalParams {
+    /// Base distance threshold, before neuro-modulation. Memories farther than this are ignored.
+    pub distance_threshold: f32,
+    /// Number of nearest memories considered.
+    pub top_k: usize,
+}
+
+impl Default for RetrievalParams {
+    fn default() -> Self {
+        Self { distance_threshold: 0.95, top_k: 5 }
+    }
+}
+
+/// How the memories are retrieved for one class of query.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RetrievalProfile {
+    /// Distance threshold, before neuro-modulation.
+    pub distance_threshold: f32,
+    /// Number of nearest memories considered, before neuro-modulation.
+    pub top_k: usize,
+    /// Only the axioms are searched, and kept whatever their distance.
+    pub axioms_only: bool,
+}
+
+/// The retrieval profiles of the query types, changed at runtime by
+/// `Core::set_retrieval_profile`. A type without a profile of its own retrieves with the
+/// `RetrievalParams` of the prompt (the configured ones, or those the threshold tuner tries),
+/// among the axioms for an introspective question.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetrievalProfiles {
+    profiles: HashMap<QueryType, RetrievalProfile>,
+}
+
+impl RetrievalProfiles {
+    /// The profile set for `query_type`, if any.
+    pub fn get(&self, query_type: QueryType) -> Option<RetrievalProfile> {
+        self.profiles.get(&query_type).copied()
+    }
+
+    /// Sets the profile of `query_type`, or removes it with `None`. Returns the previous one.
+    pub fn set(&mut self, query_type: QueryType, profile: Option<RetrievalProfile>) -> Option<RetrievalProfile> {
+        match profile {
+            Some(profile) => self.profiles.insert(query_type, profile),
+            None => self.profiles.remove(&query_type),
+        }
+    }
+
+    /// The profile a query of `query_type` is retrieved with, when the prompt has `base` for parameters.
+    pub fn resolve(&self, query_type: QueryType, base: RetrievalParams) -> RetrievalProfile {
+        self.get(query_type).unwrap_or(RetrievalProfile {
+            distance_threshold: base.distance_threshold,
+            top_k: base.top_k,
+            axioms_only: query_type == QueryType::Introspective,
+        })
+    }
+}
+
+/// Configuration of a `Core` instance. `CoreConfig::default()` reproduces the standard behaviour.
+#[derive(Debug, Clone)]
+pub struct CoreConfig {
+    /// Time budgets for the stages of the response pipeline.
+    pub timeouts: PipelineTimeouts,
+    /// Pretrained word vectors (`.vec`, GloVe `.txt` or word2vec `.bin`) used as semantic axes.
+    pub word_vectors_path: Option<PathBuf>,
+    /// Maximum number of word vectors to load from `word_vectors_path`.
+    pub max_word_vectors: Option<usize>,
+    /// Semantic lexicon (`.toml` or `.json`) defining the axes of the semantic field.
+    pub lexicon_path: Option<PathBuf>,
+    /// Extra stop-word languages and domain whitelists (`.toml` or `.json`), see `StopWordRegistry`.
+    pub stop_words_path: Option<PathBuf>,
+    /// Store (`.toml`) of the structured identity answered to introspective questions, see
+    /// `SelfAwareness`. Created by the first change of the identity.
+    pub identity_path: PathBuf,
+    /// JSONL file the answered prompts are appended to, see `ConversationLog`. `None` keeps the
+    /// conversation in memory only.
+    pub conversation_log_path: Option<PathBuf>,
+    /// Personality profiles (`.toml`), see `PersonalityProfiles::load`. `None` keeps the default
+    /// profile.
+    pub personality_path: Option<PathBuf>,
+    /// Rules of the ethical policy (`.toml` or `.json`), see `EthicalCore::load_file`.
+    pub ethics_policy_path: Option<PathBuf>,
+    /// Prompts answered during `Core::warm_up` to prime the response cache.
+    pub warmup_prompts: Vec<String>,
+    /// Maximum number of answers kept in the response cache (0 disables it).
+    pub response_cache_capacity: usize,
+    /// Default retrieval parameters.
+    pub retrieval: RetrievalParams,
+    /// Retrieval parameters of the query types that need their own.
+    pub retrieval_profiles: RetrievalProfiles,
+    /// How the keyword (BM25) and holographic rankings of the memories are fused.
+    pub fusion: FusionConfig,
+    /// Trust in each source of knowledge, multiplied into the similarity of its memories.
+    pub trust: TrustWeights,
+    /// How queries are routed to the domain partitions of the memories.
+    pub domain_routing: DomainRoutingConfig,
+    /// How often and how the quantum core is read out for the cognitive mode.
+    pub quantum_readout: ReadoutConfig,
+    /// How fast the pairs of qubits entangled by the Creativity Forge are forgotten.
+    pub entanglement: EntanglementConfig,
+    /// How the quantum core is simulated: pure states, or density matrices (see `quantum_backend`).
+    pub quantum_backend: QuantumBackendKind,
+    /// How far the answers to creative requests stray from what is known, from 0 (the most
+    /// relevant concepts, a single bridge) to 1 (almost random concepts, three bridges).
+    pub creativity_temperature: f32,
+    /// Whether a factual question answered from several memories is first answered by Silicium,
+    /// connecting them around a concept they share, before falling back to the best of them.
+    pub silicium_synthesis: bool,
+    /// Seed of every random stream of the Core (see `RngProvider`). `None` seeds them from the OS,
+    /// `Some` makes the simulation reproducible.
+    pub seed: Option<u64>,
+    /// Language of the answers when a prompt gives no clue about its own ("ok", "Einstein ?")
+    /// and no earlier turn did either.
+    pub default_language: Lang,
+    /// Sources scanned to fill knowledge gaps, tried in order. `{topic}` stands for the key
+    /// concepts of the unanswered question and `{lang}` for its language code.
+    pub exploration_sources: Vec<String>,
+    /// Calibration and abstention of the multiple choice solver.
+    pub mcq: McqConfig,
+    /// Spike-timing-dependent plasticity of the connectome.
+    pub stdp: StdpConfig,
+    /// Online tuning of the retrieval parameters (experiment mode).
+    #[cfg(feature = "threshold_tuning")]
+    pub tuning: crate::threshold_tuner::TunerConfig,
+    /// Engine the answers are spoken with.
+    #[cfg(feature = "tts")]
+    pub speech: crate::speech::SpeechConfig,
+}
+
+impl Default for CoreConfig {
+    fn default() -> Self {
+        Self {
+            timeouts: PipelineTimeouts::default(),
+            word_vectors_path: None,
+            max_word_vectors: None,
+            lexicon_path: None,
+            stop_words_path: None,
+            identity_path: PathBuf::from("identity.toml"),
+            conversation_log_path: None,
+            personality_path: None,
+            ethics_policy_path: None,
+            warmup_prompts: Vec::new(),
+            response_cache_capacity: 256,
+            retrieval: RetrievalParams::default(),
+            retrieval_profiles: RetrievalProfiles::default(),
+            fusion: FusionConfig::default(),
+            trust: TrustWeights::default(),
+            domain_routing: DomainRoutingConfig::default(),
+            quantum_readout: ReadoutConfig::default(),
+            entanglement: EntanglementConfig::default(),
+            quantum_backend: QuantumBackendKind::default(),
+            creativity_temperature: 0.7,
+            silicium_synthesis: true,
+            seed: None,
+            default_language: Lang::French,
+            exploration_sources: vec!["wikipedia:{lang}:{topic}".to_string()],
+            mcq: McqConfig::default(),
+            stdp: StdpConfig::default(),
+            #[cfg(feature = "threshold_tuning")]
+            tuning: crate::threshold_tuner::TunerConfig::default(),
+            #[cfg(feature = "tts")]
+            speech: crate::speech::SpeechConfig::default(),
+        }
+    }
+}
@@ -1,7 +1,9 @@
 // agi_core/src/connectome.rs
 
-use crate::neuron::Neuron;
-use rand::Rng;
+use crate::neuron::{Neuron, NeuronKind};
+use crate::rng::CoreRng;
+use crate::synapse_matrix::{self, SynapseMatrix};
+use rand::{Rng, SeedableRng};
 use std::fs::File;
 use std::io::{self, Read};
 use std::path::Path;
@@ -18,12 +20,60 @@ pub struct Synapse {
     pub weight: f32,
 }
 
+/// Spike-timing-dependent plasticity: a synapse whose presynaptic neuron fires shortly before
+/// its postsynaptic neuron is strengthened, and weakened when the order is reversed. The change
+/// fades as `exp(-Δt / (window / 3))` with the delay between the two spikes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StdpConfig {
+    /// Longest delay between two spikes, in ticks, for them to change the synapse (0 disables
+    /// STDP). The firing history keeps the last 500 spikes, which bounds the useful window.
+    pub window: u64,
+    /// Relative increase of the weight for a pre-before-post pair at a delay of one tick.
+    pub potentiation_rate: f32,
+    /// Relative decrease of the weight for a post-before-pre pair at a delay of one tick.
+    pub depression_rate: f32,
+    /// Highest absolute weight reached through potentiation.
+    pub max_weight: f32,
+}
+
+impl Default for StdpConfig {
+    fn default() -> Self {
+        // Depression slightly outweighs potentiation, so uncorrelated activity does not inflate the weights.
+        Self { window: 20, potentiation_rate: 0.01, depression_rate: 0.012, max_weight: 2.5 }
+    }
+}
+
+/// Synapse changes made by STDP since the connectome was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct StdpCounters {
+    pub potentiated: u64,
+    pub depressed: u64,
+}
+
+/// What the neuromodulators do to the connectome, read from the `NeurochemicalModulator` and
+/// passed to every `Connectome::update`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neuromodulation {
+    /// Scales the spontaneous activity, 1.0 at the base level (noradrenaline).
+    pub arousal: f32,
+    /// Scales the weight increase of `potentiate_pathway`, 1.0 at the base level (dopamine).
+    pub learning_gain: f32,
+    /// Whether spike-timing-dependent plasticity applies (acetylcholine).
+    pub stdp_enabled: bool,
+}
+
+impl Default for Neuromodulation {
+    fn default() -> Self {
+        Self { arousal: 1.0, learning_gain: 1.0, stdp_enabled: true }
+    }
+}
+
 /// Represents the entire neural network, loaded from a binary file.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Connectome {
     pub neurons: Vec<Neuron>,
-    pub synapses: Vec<Synapse>,
-    pub outgoing_synapses: HashMap<u64, Vec<(u64, f32)>>,
+    /// The synapses grouped by presynaptic neuron, possibly mapped from a v2 file.
+    pub synapses: SynapseMatrix,
     
     // --- Performance Optimization ---
     // A set of neurons whose potential is > 0. Only these are processed in the update loop.
@@ -31,27 +81,69 @@ pub struct Connectome {
 
     // A rolling log of recent firing activity (neuron_id, tick).
     pub firing_history: Vec<(u64, u64)>,
+
+    // Drives the spontaneous activity.
+    rng: CoreRng,
+    // The neuromodulation of the last update.
+    modulation: Neuromodulation,
+    stdp: StdpConfig,
+    stdp_counters: StdpCounters,
+}
+
+impl Default for Connectome {
+    fn default() -> Self {
+        Self {
+            neurons: Vec::new(),
+            synapses: SynapseMatrix::default(),
+            active_neurons: HashSet::new(),
+            firing_history: Vec::new(),
+            rng: CoreRng::from_entropy(),
+            modulation: Neuromodulation::default(),
+            stdp: StdpConfig::default(),
+            stdp_counters: StdpCounters::default(),
+        }
+    }
 }
 
 impl Connectome {
+    /// Replaces the random stream of the spontaneous activity (see `RngProvider`).
+    pub fn with_rng(mut self, rng: CoreRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Replaces the parameters of spike-timing-dependent plasticity.
+    pub fn with_stdp(mut self, stdp: StdpConfig) -> Self {
+        self.stdp = stdp;
+        self
+    }
+
+    pub fn stdp_counters(&self) -> StdpCounters {
+        self.stdp_counters
+    }
+
+    pub fn modulation(&self) -> Neuromodulation {
+        self.modulation
+    }
+
     /// Updates the state of all neurons in the connectome.
     /// This includes decaying potential and checking for firing conditions.
+    /// The neuromodulation applies from this tick on, until the next update.
     /// Returns a list of IDs for neurons that are currently firing.
-    pub fn update(&mut self, current_tick: u64) -> Vec<u64> {
+    pub fn update(&mut self, current_tick: u64, modulation: Neuromodulation) -> Vec<u64> {
+        self.modulation = modulation;
+
         // --- Spontaneous Activity ---
         // Add a small chance for any neuron to get a random potential boost,
         // simulating background noise and preventing the network from dying.
-        let mut rng = rand::thread_rng();
-        
-
         const SPONTANEOUS_BOOST_AMOUNT: f32 = 0.75;
         let num_to_boost = 2; // Boost a couple of random neurons each tick to ensure activity.
 
         if !self.neurons.is_empty() {
             for _ in 0..num_to_boost {
-                let neuron_id = rng.gen_range(0..self.neurons.len());
+                let neuron_id = self.rng.gen_range(0..self.neurons.len());
                 if let Some(neuron) = self.neurons.get_mut(neuron_id) {
-                    neuron.potential += SPONTANEOUS_BOOST_AMOUNT;
+                    neuron.potential += SPONTANEOUS_BOOST_AMOUNT * self.modulation.arousal.max(0.0);
                     if neuron.potential > 0.0 {
                         self.active_neurons.insert(neuron.id);
                     }
@@ -65,7 +157,7 @@ impl Connectome {
         // Iterate over a clone of the active set because we'll be modifying it.
         for &neuron_id in &self.active_neurons.clone() {
             if let Some(neuron) = self.neurons.get_mut(neuron_id as usize) {
-                neuron.update(); // Handles decay and firing state change
+                neuron.update(current_tick); // Handles decay, refractory periods and firing state change
 
                 if neuron.firing {
                     firing_ids.push(neuron.id);
@@ -78,11 +170,21 @@ impl Connectome {
             }
         }
 
+        // The active set is unordered: the spikes go on in the order of the neurons, so that a
+        // seeded run propagates them (and sums their potentials) alike every time.
+        firing_ids.sort_unstable();
+
         // Remove dormant neurons from the active set.
         for id in dormant_ids {
             self.active_neurons.remove(&id);
         }
 
+        // --- Spike-Timing-Dependent Plasticity ---
+        // Before the new spikes enter the history, so that it only holds earlier ones.
+        if self.modulation.stdp_enabled {
+            self.apply_stdp(&firing_ids, current_tick);
+        }
+
         // --- Update Firing History ---
         if !firing_ids.is_empty() {
             for &id in &firing_ids {
@@ -100,24 +202,125 @@ impl Connectome {
         firing_ids
     }
 
-    /// Propagates a signal from a single firing neuron to its connected neurons using the optimized map.
+    /// Pairs the spikes of `firing_ids` at `current_tick` with the earlier spikes of the history
+    /// that fall in the STDP window. For a neuron `pre` that fired `Δt` ticks ago and a neuron
+    /// `post` firing now, `pre → post` is potentiated and `post → pre` depressed. Weights are
+    /// scaled, so their sign (see `set_neuron_kind`) is kept.
+    fn apply_stdp(&mut self, firing_ids: &[u64], current_tick: u64) {
+        let StdpConfig { window, potentiation_rate, depression_rate, max_weight } = self.stdp;
+        if window == 0 || firing_ids.is_empty() {
+            return;
+        }
+        // The most recent earlier spike of each neuron in the window, as its delay.
+        let mut delays: HashMap<u64, u64> = HashMap::new();
+        for &(id, tick) in self.firing_history.iter().rev() {
+            let delay = current_tick.saturating_sub(tick);
+            if delay > window {
+                break;
+            }
+            if delay > 0 {
+                delays.entry(id).or_insert(delay);
+            }
+        }
+        if delays.is_empty() {
+            return;
+        }
+        let time_constant = window as f32 / 3.0;
+        let decay = |delay: u64| (-(delay as f32) / time_constant).exp();
+        let firing: HashSet<u64> = firing_ids.iter().copied().collect();
+
+        // Causal pairs: the earlier spike may have contributed to the new one.
+        for (&pre, &delay) in &delays {
+            let factor = 1.0 + potentiation_rate * decay(delay);
+            let (targets, weights) = self.synapses.outgoing_mut(pre);
+            for (&post, weight) in targets.iter().zip(weights.iter_mut()) {
+                if firing.contains(&(post as u64)) {
+                    // Engraved weights above the cap are left where they are.
+                    let cap = max_weight.max(weight.abs());
+                    *weight = (*weight * factor).clamp(-cap, cap);
+                    self.stdp_counters.potentiated += 1;
+                }
+            }
+        }
+        // Anti-causal pairs: the new spike came too late to contribute to the earlier one.
+        for &post in &firing {
+            let (targets, weights) = self.synapses.outgoing_mut(post);
+            for (&pre, weight) in targets.iter().zip(weights.iter_mut()) {
+                if let Some(&delay) = delays.get(&(pre as u64)) {
+                    *weight *= 1.0 - depression_rate * decay(delay);
+                    self.stdp_counters.depressed += 1;
+                }
+            }
+        }
+    }
+
+    /// Propagates a signal from a single firing neuron to its connected neurons, along its row of
+    /// the synapse matrix.
     pub fn propagate_signal_from(&mut self, firing_neuron_id: u64) {
-        // Use the pre-computed map for a fast lookup.
-        if let Some(connections) = self.outgoing_synapses.get(&firing_neuron_id) {
-            for &(to_id, weight) in connections {
-                if let Some(neuron) = self.neurons.get_mut(to_id as usize) {
-                    neuron.potential += weight;
-                    // If the neuron is now active, add it to the list for the next update tick.
-                    if neuron.potential > 0.0 {
-                        self.active_neurons.insert(to_id);
-                    }
+        for (to_id, weight) in self.synapses.outgoing(firing_neuron_id) {
+            if let Some(neuron) = self.neurons.get_mut(to_id as usize) {
+                neuron.potential += weight;
+                // If the neuron is now active, add it to the list for the next update tick.
+                if neuron.potential > 0.0 {
+                    self.active_neurons.insert(to_id);
                 }
             }
         }
     }
 
-    /// Creates a new Connectome by loading a quantized binary file.
+    /// Loads a connectome file: a v2 file (see `synapse_matrix`) is memory-mapped, a v1 file
+    /// is read and converted in memory.
     pub fn from_binary<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        let is_v2 = File::open(path.as_ref())?.read_exact(&mut magic).is_ok() && &magic == synapse_matrix::MAGIC;
+        if is_v2 {
+            let synapses = SynapseMatrix::map_v2(path)?;
+            println!("Successfully mapped connectome: {} neurons, {} synapses.", synapses.neuron_count(), synapses.len());
+            Ok(Self::with_synapses(synapses))
+        } else {
+            Self::from_binary_v1(path)
+        }
+    }
+
+    /// A connectome at rest over the given synapses, one neuron per row. Each neuron takes the
+    /// kind of most of its outgoing weights (see `set_neuron_kind`).
+    pub fn with_synapses(synapses: SynapseMatrix) -> Self {
+        let mut connectome = Self {
+            neurons: (0..synapses.neuron_count() as u64).map(Neuron::new).collect(),
+            synapses,
+            ..Self::default()
+        };
+        for id in 0..connectome.neurons.len() as u64 {
+            let (_, weights) = connectome.synapses.outgoing_mut(id);
+            let inhibitory = weights.iter().filter(|weight| **weight < 0.0).count() * 2 > weights.len();
+            connectome.set_neuron_kind(id, if inhibitory { NeuronKind::Inhibitory } else { NeuronKind::Excitatory });
+        }
+        connectome
+    }
+
+    /// Changes the kind of a neuron and the sign of its outgoing weights with it (Dale's
+    /// principle): the synapses of an inhibitory neuron are all negative, those of an excitatory
+    /// one all positive. Plasticity scales weights and never changes their sign.
+    pub fn set_neuron_kind(&mut self, id: u64, kind: NeuronKind) {
+        let Some(neuron) = self.neurons.get_mut(id as usize) else {
+            return;
+        };
+        neuron.kind = kind;
+        let (_, weights) = self.synapses.outgoing_mut(id);
+        // Only the wrong signs are written, so a mapped connectome keeps its pages shared.
+        for weight in weights.iter_mut().filter(|weight| (**weight < 0.0) != (kind == NeuronKind::Inhibitory)) {
+            *weight = -*weight;
+        }
+    }
+
+    /// Converts a v1 file into a v2 file, which later loads are able to map.
+    pub fn convert_v1_to_v2<P: AsRef<Path>, Q: AsRef<Path>>(v1_path: P, v2_path: Q) -> io::Result<()> {
+        Self::from_binary_v1(v1_path)?.synapses.write_v2(v2_path)
+    }
+
+    /// Reads a v1 file: the neuron and synapse counts (u64), then (from u32, to u32, weight f32)
+    /// for each synapse.
+    fn from_binary_v1<P: AsRef<Path>>(path: P) -> io::Result<Self> {
         let mut file = File::open(path)?;
         let mut buffer = Vec::new();
         file.read_to_end(&mut buffer)?;
@@ -129,11 +332,6 @@ impl Connectome {
         let num_neurons = u64::from_le_bytes(buffer[0..8].try_into().unwrap());
         let num_synapses = u64::from_le_bytes(buffer[8..16].try_into().unwrap());
 
-        let mut neurons = Vec::with_capacity(num_neurons as usize);
-        for i in 0..num_neurons {
-            neurons.push(Neuron::new(i));
-        }
-
         let mut synapses = Vec::with_capacity(num_synapses as usize);
         let mut cursor = 16;
         let synapse_size = std::mem::size_of::<u32>() * 2 + std::mem::size_of::<f32>(); // 4 + 4 + 4 = 12 bytes
@@ -152,23 +350,34 @@ impl Connectome {
             synapses.push(Synapse { from, to, weight });
         }
 
-        println!("Successfully loaded connectome: {} neurons, {} synapses.", neurons.len(), synapses.len());
+        println!("Successfully loaded connectome: {} neurons, {} synapses.", num_neurons, synapses.len());
 
-        // --- Optimization Step: Pre-compute the outgoing synapse map ---
-        let mut outgoing_synapses = HashMap::new();
-        for synapse in &synapses {
-            outgoing_synapses.entry(synapse.from)
-                .or_insert_with(Vec::new)
-                .push((synapse.to, synapse.weight));
+        // --- Optimization Step: Group the synapses by presynaptic neuron ---
+        Ok(Self::with_synapses(SynapseMatrix::try_from_synapses(num_neurons as usize, &synapses)?))
+    }
+
+    /// Synaptic homeostasis: scales every weight by `factor`, then removes the synapses whose
+    /// weight falls below `threshold` in absolute value. Returns how many were removed.
+    pub fn downscale_and_prune(&mut self, factor: f32, threshold: f32) -> usize {
+        for weight in self.synapses.weights_mut() {
+            *weight *= factor;
         }
+        self.synapses.retain(|weight| weight.abs() >= threshold)
+    }
 
-        Ok(Self { 
-            neurons, 
-            synapses, 
-            outgoing_synapses, 
-            firing_history: Vec::new(),
-            active_neurons: HashSet::new(), // Initialize the active list
-        })
+    /// Long-Term Depression (LTD): weakens the synapses between a set of neurons, the reverse of
+    /// `potentiate_pathway`, for pathways that led to a rejected answer.
+    pub fn depress_pathway(&mut self, neuron_ids: &HashSet<u64>) {
+        let depression_factor = 0.9; // e.g., 10% decrease
+
+        for from_id in neuron_ids {
+            let (targets, weights) = self.synapses.outgoing_mut(*from_id);
+            for (&to_id, weight) in targets.iter().zip(weights.iter_mut()) {
+                if neuron_ids.contains(&(to_id as u64)) {
+                    *weight *= depression_factor;
+                }
+            }
+        }
     }
 
     /// Returns the IDs of neurons that have fired within a given recent window of ticks.
@@ -181,33 +390,33 @@ impl Connectome {
         let max_weight = 3.5; // Allow axioms to have a higher maximum weight
 
         for from_id in active_neuron_ids {
-            if let Some(connections) = self.outgoing_synapses.get_mut(from_id) {
-                for (to_id, weight) in connections.iter_mut() {
-                    // If the target neuron was also part of the same firing event, strengthen the connection.
-                    if active_neuron_ids.contains(to_id) {
-                        *weight *= potentiation_factor;
-                        if *weight > max_weight {
-                            *weight = max_weight;
-                        }
+            let (targets, weights) = self.synapses.outgoing_mut(*from_id);
+            for (&to_id, weight) in targets.iter().zip(weights.iter_mut()) {
+                // If the target neuron was also part of the same firing event, strengthen the connection.
+                if active_neuron_ids.contains(&(to_id as u64)) {
+                    *weight *= potentiation_factor;
+                    if *weight > max_weight {
+                        *weight = max_weight;
                     }
                 }
             }
         }
     }
 
+    /// Long-Term Potentiation (LTP): strengthens the synapses between a set of neurons that fired
+    /// together. The increase is scaled by the learning gain of the neuromodulation (dopamine).
     pub fn potentiate_pathway(&mut self, active_neuron_ids: &HashSet<u64>) {
-        let potentiation_factor = 1.1; // e.g., 10% increase
+        let potentiation_factor = 1.0 + 0.1 * self.modulation.learning_gain.max(0.0); // 10% increase at the base level
         let max_weight = 2.5; // Prevent runaway weights
 
         for from_id in active_neuron_ids {
-            if let Some(connections) = self.outgoing_synapses.get_mut(from_id) {
-                for (to_id, weight) in connections.iter_mut() {
-                    // If the target neuron was also part of the same firing event, strengthen the connection.
-                    if active_neuron_ids.contains(to_id) {
-                        *weight *= potentiation_factor;
-                        if *weight > max_weight {
-                            *weight = max_weight;
-                        }
+            let (targets, weights) = self.synapses.outgoing_mut(*from_id);
+            for (&to_id, weight) in targets.iter().zip(weights.iter_mut()) {
+                // If the target neuron was also part of the same firing event, strengthen the connection.
+                if active_neuron_ids.contains(&(to_id as u64)) {
+                    *weight *= potentiation_factor;
+                    if *weight > max_weight {
+                        *weight = max_weight;
                     }
                 }
             }
@@ -228,3 +437,51 @@ impl Connectome {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outgoing_weights_follow_the_kind_of_their_neuron() {
+        let synapses = [
+            Synapse { from: 0, to: 1, weight: -0.5 },
+            Synapse { from: 0, to: 2, weight: -0.4 },
+            Synapse { from: 0, to: 3, weight: 0.3 },
+            Synapse { from: 1, to: 0, weight: 0.6 },
+        ];
+        let mut connectome = Connectome::with_synapses(SynapseMatrix::from_synapses(4, &synapses));
+        assert_eq!(connectome.neurons[0].kind, NeuronKind::Inhibitory);
+        assert_eq!(connectome.synapses.outgoing(0).map(|(_, weight)| weight).collect::<Vec<_>>(), vec![-0.5, -0.4, -0.3]);
+        assert_eq!(connectome.neurons[1].kind, NeuronKind::Excitatory);
+
+        connectome.set_neuron_kind(1, NeuronKind::Inhibitory);
+        assert_eq!(connectome.synapses.outgoing(1).collect::<Vec<_>>(), vec![(0, -0.6)]);
+
+        // An inhibitory spike lowers the potential of its targets.
+        connectome.neurons[2].potential = 0.5;
+        connectome.propagate_signal_from(0);
+        assert!((connectome.neurons[2].potential - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_stdp_follows_the_order_of_the_spikes() {
+        let synapses = [
+            Synapse { from: 0, to: 1, weight: 0.5 },
+            Synapse { from: 1, to: 0, weight: 0.5 },
+            Synapse { from: 2, to: 1, weight: -0.5 },
+        ];
+        let stdp = StdpConfig { window: 10, potentiation_rate: 1.0, depression_rate: 0.5, max_weight: 0.6 };
+        let mut connectome = Connectome::with_synapses(SynapseMatrix::from_synapses(3, &synapses)).with_stdp(stdp);
+        connectome.firing_history = vec![(2, 80), (0, 95)];
+        connectome.apply_stdp(&[1], 100);
+
+        let weight = |connectome: &Connectome, from: u64| connectome.synapses.outgoing(from).next().unwrap().1;
+        // 0 fired before 1: 0 → 1 is potentiated up to the cap, 1 → 0 depressed.
+        assert_eq!(weight(&connectome, 0), 0.6);
+        assert!(weight(&connectome, 1) < 0.5);
+        // 2 fired outside of the window.
+        assert_eq!(weight(&connectome, 2), -0.5);
+        assert_eq!(connectome.stdp_counters(), StdpCounters { potentiated: 1, depressed: 1 });
+    }
+}
@@ -0,0 +1,254 @@
+// agi_core/src/connectome_builder.rs
+
+//! Generation of connectomes with a chosen topology.
+//!
+//! Uniform random wiring gives every neuron the same odds of reaching any other, which makes
+//! for unrealistic dynamics: activity either dies out or floods the whole network. The builder
+//! offers the topologies found in real cortices (small-world, modular/columnar, scale-free), a
+//! share of inhibitory neurons whose synapses are all negative, and weights that fade with the
+//! distance between neurons. Neurons are laid out on a ring, in index order.
+
+use crate::connectome::{Connectome, Synapse};
+use crate::rng::CoreRng;
+use crate::synapse_matrix::SynapseMatrix;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Topology {
+    /// Every synapse reaches a random neuron.
+    Uniform { synapses_per_neuron: usize },
+    /// Watts–Strogatz: a ring lattice where each neuron reaches its `neighbors` nearest
+    /// neighbours, each synapse being rewired to a random neuron with probability `rewiring`.
+    SmallWorld { neighbors: usize, rewiring: f64 },
+    /// Contiguous columns of neurons: a synapse stays in the column of its neuron with
+    /// probability `intra_module`, and reaches another column otherwise.
+    Modular { modules: usize, synapses_per_neuron: usize, intra_module: f64 },
+    /// Barabási–Albert: each neuron reaches `edges_per_neuron` neurons chosen in proportion to
+    /// how connected they already are, which grows a few hubs.
+    ScaleFree { edges_per_neuron: usize },
+}
+
+pub struct ConnectomeBuilder {
+    neurons: usize,
+    topology: Topology,
+    inhibitory_ratio: f64,
+    weights: (f32, f32),
+    distance_decay: Option<f32>,
+    rng: CoreRng,
+}
+
+impl ConnectomeBuilder {
+    pub fn new(neurons: usize) -> Self {
+        Self {
+            neurons,
+            topology: Topology::Uniform { synapses_per_neuron: 100 },
+            inhibitory_ratio: 0.2,
+            weights: (0.1, 1.0),
+            distance_decay: None,
+            rng: CoreRng::from_entropy(),
+        }
+    }
+
+    pub fn topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Share of the neurons whose synapses inhibit their targets (0.2 by default, as in cortex).
+    pub fn inhibitory_ratio(mut self, ratio: f64) -> Self {
+        self.inhibitory_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Range of the absolute weights, before the distance decay.
+    pub fn weights(mut self, min: f32, max: f32) -> Self {
+        self.weights = (min.min(max), min.max(max));
+        self
+    }
+
+    /// Scales each weight by `exp(-d / decay)`, `d` being the distance between the two neurons
+    /// on the ring, from 0 (neighbours) to 1 (opposite).
+    pub fn distance_decay(mut self, decay: f32) -> Self {
+        self.distance_decay = Some(decay.max(f32::EPSILON));
+        self
+    }
+
+    /// Replaces the random stream of the generation (see `RngProvider`).
+    pub fn with_rng(mut self, rng: CoreRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    pub fn build(self) -> Connectome {
+        let neurons = self.neurons;
+        Connectome::with_synapses(SynapseMatrix::from_synapses(neurons, &self.build_synapses()))
+    }
+
+    /// The synapses of the connectome, without building it.
+    pub fn build_synapses(mut self) -> Vec<Synapse> {
+        let n = self.neurons;
+        if n < 2 {
+            return Vec::new();
+        }
+        let edges = match self.topology {
+            Topology::Uniform { synapses_per_neuron } => self.uniform(synapses_per_neuron),
+            Topology::SmallWorld { neighbors, rewiring } => self.small_world(neighbors, rewiring),
+            Topology::Modular { modules, synapses_per_neuron, intra_module } => self.modular(modules, synapses_per_neuron, intra_module),
+            Topology::ScaleFree { edges_per_neuron } => self.scale_free(edges_per_neuron),
+        };
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.shuffle(&mut self.rng);
+        let mut inhibitory = vec![false; n];
+        for &i in order.iter().take((n as f64 * self.inhibitory_ratio).round() as usize) {
+            inhibitory[i] = true;
+        }
+
+        let (min, max) = self.weights;
+        edges
+            .into_iter()
+            .map(|(from, to)| {
+                let mut weight = if min < max { self.rng.gen_range(min..=max) } else { min };
+                if let Some(decay) = self.distance_decay {
+                    weight *= (-ring_distance(from, to, n) / decay).exp();
+                }
+                if inhibitory[from] {
+                    weight = -weight;
+                }
+                Synapse { from: from as u64, to: to as u64, weight }
+            })
+            .collect()
+    }
+
+    /// A random neuron other than `from`.
+    fn other_neuron(&mut self, from: usize) -> usize {
+        let to = self.rng.gen_range(0..self.neurons - 1);
+        if to >= from { to + 1 } else { to }
+    }
+
+    fn uniform(&mut self, per_neuron: usize) -> Vec<(usize, usize)> {
+        let mut edges = Vec::with_capacity(self.neurons * per_neuron);
+        for from in 0..self.neurons {
+            for _ in 0..per_neuron {
+                edges.push((from, self.other_neuron(from)));
+            }
+        }
+        edges
+    }
+
+    fn small_world(&mut self, neighbors: usize, rewiring: f64) -> Vec<(usize, usize)> {
+        let n = self.neurons;
+        let half = (neighbors / 2).min((n - 1) / 2).max(1);
+        let mut edges = Vec::with_capacity(n * half * 2);
+        for from in 0..n {
+            for step in 1..=half {
+                for to in [(from + step) % n, (from + n - step) % n] {
+                    let to = if self.rng.gen_bool(rewiring.clamp(0.0, 1.0)) { self.other_neuron(from) } else { to };
+                    if to != from {
+                        edges.push((from, to));
+                    }
+                }
+            }
+        }
+        edges
+    }
+
+    fn modular(&mut self, modules: usize, per_neuron: usize, intra_module: f64) -> Vec<(usize, usize)> {
+        let n = self.neurons;
+        let modules = modules.clamp(1, n);
+        let module_of = |i: usize| i * modules / n;
+        // First neuron of each module, and the end of the last one.
+        let bounds: Vec<usize> = (0..=modules).map(|m| (m * n).div_ceil(modules)).collect();
+        let mut edges = Vec::with_capacity(n * per_neuron);
+        for from in 0..n {
+            let module = module_of(from);
+            let (start, end) = (bounds[module], bounds[module + 1]);
+            for _ in 0..per_neuron {
+                let to = if modules > 1 && !self.rng.gen_bool(intra_module.clamp(0.0, 1.0)) {
+                    // Another column: a random neuron outside of [start, end).
+                    let to = self.rng.gen_range(0..n - (end - start));
+                    if to >= start { to + (end - start) } else { to }
+                } else if end - start > 1 {
+                    let to = self.rng.gen_range(start..end - 1);
+                    if to >= from { to + 1 } else { to }
+                } else {
+                    continue;
+                };
+                edges.push((from, to));
+            }
+        }
+        edges
+    }
+
+    fn scale_free(&mut self, per_neuron: usize) -> Vec<(usize, usize)> {
+        let n = self.neurons;
+        let per_neuron = per_neuron.max(1);
+        let mut edges = Vec::with_capacity(n * per_neuron);
+        // Each neuron appears once per synapse it takes part in: drawing from this list picks
+        // neurons in proportion to their degree.
+        let mut endpoints: Vec<usize> = Vec::with_capacity(2 * n * per_neuron);
+        for from in 1..n {
+            let mut targets: Vec<usize> = Vec::with_capacity(per_neuron);
+            while targets.len() < per_neuron.min(from) {
+                let to = if endpoints.is_empty() { self.rng.gen_range(0..from) } else { endpoints[self.rng.gen_range(0..endpoints.len())] };
+                if !targets.contains(&to) {
+                    targets.push(to);
+                }
+            }
+            for to in targets {
+                edges.push((from, to));
+                endpoints.push(from);
+                endpoints.push(to);
+            }
+        }
+        edges
+    }
+}
+
+/// Distance between two neurons on the ring, from 0 to 1.
+fn ring_distance(a: usize, b: usize, n: usize) -> f32 {
+    let gap = a.abs_diff(b);
+    gap.min(n - gap) as f32 / (n / 2).max(1) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder(topology: Topology) -> ConnectomeBuilder {
+        ConnectomeBuilder::new(200).topology(topology).with_rng(CoreRng::seed_from_u64(7))
+    }
+
+    #[test]
+    fn test_topologies() {
+        let lattice = builder(Topology::SmallWorld { neighbors: 4, rewiring: 0.0 }).build_synapses();
+        assert_eq!(lattice.len(), 200 * 4);
+        assert!(lattice.iter().all(|s| ring_distance(s.from as usize, s.to as usize, 200) <= 2.0 / 100.0));
+
+        let columns = builder(Topology::Modular { modules: 4, synapses_per_neuron: 10, intra_module: 1.0 }).build_synapses();
+        assert!(columns.iter().all(|s| s.from / 50 == s.to / 50 && s.from != s.to));
+
+        // Preferential attachment grows hubs far above the average degree.
+        let scale_free = builder(Topology::ScaleFree { edges_per_neuron: 2 }).build_synapses();
+        let mut in_degree = vec![0; 200];
+        scale_free.iter().for_each(|s| in_degree[s.to as usize] += 1);
+        assert!(*in_degree.iter().max().unwrap() > 4 * scale_free.len() / 200);
+    }
+
+    #[test]
+    fn test_inhibitory_neurons_and_distance_decay() {
+        let synapses = builder(Topology::Uniform { synapses_per_neuron: 20 }).inhibitory_ratio(0.25).distance_decay(0.5).build_synapses();
+        let mut sign = vec![0.0f32; 200];
+        for synapse in &synapses {
+            // All the synapses of a neuron have the same sign.
+            assert!(sign[synapse.from as usize] * synapse.weight >= 0.0);
+            sign[synapse.from as usize] = synapse.weight.signum();
+            assert!(synapse.weight.abs() <= (-ring_distance(synapse.from as usize, synapse.to as usize, 200) / 0.5).exp());
+        }
+        assert_eq!(sign.iter().filter(|&&s| s < 0.0).count(), 50);
+
+        let connectome = builder(Topology::Uniform { synapses_per_neuron: 5 }).build();
+        assert_eq!((connectome.neurons.len(), connectome.synapses.len()), (200, 1000));
+    }
+}
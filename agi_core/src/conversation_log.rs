@@ -0,0 +1,219 @@
+//! conversation_log.rs - Every prompt and its answer, kept across restarts.
+//!
+//! The GUI keeps its own chat history; the `ConversationLog` is the Core's. Each answered prompt
+//! is recorded with its time and query type and appended to a JSONL file (one entry per line),
+//! which is read back at boot. Questions about the conversation itself ("what did I ask you an
+//! hour ago?", « quelle était ma dernière question ? ») are recognized by `parse_history_query`
+//! and answered from the log.
+
+use crate::capabilities::Capability;
+use crate::lemmatizer::Lang;
+use crate::thalamus::QueryType;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use thiserror::Error;
+
+pub const CAPABILITY: Capability = Capability {
+    name: "conversation_history",
+    description: "Tell you what you asked me earlier",
+    description_fr: "Vous rappeler ce que vous m'avez demandé plus tôt",
+    examples: &["What did I ask you an hour ago?", "Quelle était ma dernière question ?"],
+};
+
+/// Entries kept in memory; the file keeps them all.
+pub const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Error)]
+pub enum ConversationLogError {
+    #[error("I/O error on the conversation log: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid entry on line {line} of the conversation log: {source}")]
+    Json { line: usize, source: serde_json::Error },
+    #[error("could not encode a conversation entry: {0}")]
+    Encode(serde_json::Error),
+}
+
+/// A prompt and the answer it got.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationEntry {
+    /// The ID the answer can be rated with.
+    pub response_id: u64,
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+    pub prompt: String,
+    pub response: String,
+    pub query_type: QueryType,
+}
+
+/// The conversation, oldest entry first.
+#[derive(Debug, Default)]
+pub struct ConversationLog {
+    entries: VecDeque<ConversationEntry>,
+    /// The JSONL file the entries are appended to; None keeps them in memory.
+    store: Option<PathBuf>,
+}
+
+impl ConversationLog {
+    /// Reads the log from a JSONL file; a missing file is an empty log, created by the first entry.
+    pub fn open<P: Into<PathBuf>>(store: P) -> Result<Self, ConversationLogError> {
+        let store = store.into();
+        let mut log = Self::default();
+        match fs::read_to_string(&store) {
+            Ok(content) => {
+                for (index, line) in content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+                    let entry = serde_json::from_str(line).map_err(|source| ConversationLogError::Json { line: index + 1, source })?;
+                    log.push(entry);
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        log.store = Some(store);
+        Ok(log)
+    }
+
+    pub fn store(&self) -> Option<&Path> {
+        self.store.as_deref()
+    }
+
+    /// Adds an entry and appends it to the file. The entry is kept in memory even if it could
+    /// not be written.
+    pub fn record(&mut self, entry: ConversationEntry) -> Result<(), ConversationLogError> {
+        let written = match &self.store {
+            Some(store) => Self::append(store, &entry),
+            None => Ok(()),
+        };
+        self.push(entry);
+        written
+    }
+
+    fn append(store: &Path, entry: &ConversationEntry) -> Result<(), ConversationLogError> {
+        let mut line = serde_json::to_string(entry).map_err(ConversationLogError::Encode)?;
+        line.push('\n');
+        OpenOptions::new().create(true).append(true).open(store)?.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    fn push(&mut self, entry: ConversationEntry) {
+        self.entries.push_back(entry);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &ConversationEntry> {
+        self.entries.iter()
+    }
+
+    /// The last `limit` entries, oldest first.
+    pub fn recent(&self, limit: usize) -> impl Iterator<Item = &ConversationEntry> {
+        self.entries.iter().skip(self.entries.len().saturating_sub(limit))
+    }
+
+    /// The prompt asked closest to `at`, among those asked before `now`.
+    pub fn asked_around(&self, at: u64, now: u64) -> Option<&ConversationEntry> {
+        self.entries.iter().filter(|entry| entry.at < now).min_by_key(|entry| entry.at.abs_diff(at))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A question about an earlier prompt of the conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryQuery {
+    /// How long ago the prompt was asked, in seconds; None for the last one.
+    pub ago: Option<u64>,
+}
+
+/// Recognizes a question about what the user asked earlier ("what did I ask you an hour ago?",
+/// « qu'est-ce que je vous ai demandé il y a 10 minutes ? », "what was my last question?").
+pub fn parse_history_query(prompt: &str) -> Option<HistoryQuery> {
+    static ASKED: OnceLock<Regex> = OnceLock::new();
+    static AGO: OnceLock<Regex> = OnceLock::new();
+    let asked = ASKED.get_or_init(|| {
+        Regex::new(r"(?i)(what did i (?:ask|say)|what was my (?:last|previous) (?:question|prompt)|qu'est-ce que je (?:t'|vous )?ai (?:demandé|dit)|que (?:t|vous) ai-je demandé|quelle était ma (?:dernière|précédente) question)").unwrap()
+    });
+    let ago = AGO.get_or_init(|| {
+        Regex::new(r"(?i)(?:il y a (\d+|une?) (minute|heure|jour)s?|(\d+|an?|one) (minute|hour|day)s? ago)").unwrap()
+    });
+
+    let prompt = prompt.replace('’', "'");
+    asked.find(&prompt)?;
+    let ago = ago.captures(&prompt).map(|captures| {
+        let count = captures.get(1).or_else(|| captures.get(3)).map_or(1, |count| count.as_str().parse().unwrap_or(1));
+        let unit = match captures.get(2).or_else(|| captures.get(4)).map_or("", |unit| unit.as_str()).to_lowercase().as_str() {
+            "minute" => 60,
+            "heure" | "hour" => 60 * 60,
+            _ => 24 * 60 * 60,
+        };
+        count * unit
+    });
+    Some(HistoryQuery { ago })
+}
+
+/// Words the answer to a history question, in `lang`.
+pub fn describe(entry: Option<&ConversationEntry>, now: u64, lang: Lang) -> String {
+    let Some(entry) = entry else {
+        return match lang {
+            Lang::French => "Vous ne m'avez encore rien demandé.".to_string(),
+            Lang::English => "You have not asked me anything yet.".to_string(),
+        };
+    };
+    let minutes = now.saturating_sub(entry.at) / 60;
+    match lang {
+        Lang::French => match minutes {
+            0 => format!("Vous venez de me demander : « {} »", entry.prompt),
+            1..=59 => format!("Il y a {} minute(s), vous m'avez demandé : « {} »", minutes, entry.prompt),
+            _ => format!("Il y a environ {} heure(s), vous m'avez demandé : « {} »", (minutes + 30) / 60, entry.prompt),
+        },
+        Lang::English => match minutes {
+            0 => format!("You just asked me: \"{}\"", entry.prompt),
+            1..=59 => format!("{} minute(s) ago, you asked me: \"{}\"", minutes, entry.prompt),
+            _ => format!("About {} hour(s) ago, you asked me: \"{}\"", (minutes + 30) / 60, entry.prompt),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(response_id: u64, at: u64, prompt: &str) -> ConversationEntry {
+        ConversationEntry { response_id, at, prompt: prompt.to_string(), response: "...".to_string(), query_type: QueryType::Factual }
+    }
+
+    #[test]
+    fn test_log_is_persisted_and_answers_history_questions() {
+        let store = std::env::temp_dir().join(format!("neurova_conversation_{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&store);
+        let now = 1_715_774_400;
+        let mut log = ConversationLog::open(&store).unwrap();
+        log.record(entry(1, now - 3 * 3600, "Qu'est-ce qu'un quasar ?")).unwrap();
+        log.record(entry(2, now - 3700, "What is a pulsar?")).unwrap();
+        log.record(entry(3, now - 60, "Who was Newton?")).unwrap();
+
+        let log = ConversationLog::open(&store).unwrap();
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.recent(2).map(|entry| entry.response_id).collect::<Vec<_>>(), vec![2, 3]);
+
+        let query = parse_history_query("What did I ask you an hour ago?").unwrap();
+        assert_eq!(query.ago, Some(3600));
+        let asked = log.asked_around(now - query.ago.unwrap(), now);
+        assert_eq!(describe(asked, now, Lang::English), "About 1 hour(s) ago, you asked me: \"What is a pulsar?\"");
+        assert_eq!(parse_history_query("Quelle était ma dernière question ?"), Some(HistoryQuery { ago: None }));
+        assert_eq!(parse_history_query("Qu'est-ce que je t'ai demandé il y a 3 heures ?").unwrap().ago, Some(3 * 3600));
+        assert!(parse_history_query("What did Newton discover?").is_none());
+        fs::remove_file(&store).unwrap();
+    }
+}
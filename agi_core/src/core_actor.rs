@@ -0,0 +1,508 @@
+//! core_actor.rs - Runs the Core on a dedicated thread behind a command queue.
+//!
+//! Front-ends (the HTTP server, the visualizer) used to share a `Mutex<Core>` with the tick
+//! thread, so a long reasoning call would stall both the simulation and every other client.
+//! The actor owns the `Core` outright: commands arrive on an mpsc queue, ticks are interleaved
+//! between commands at a fixed rate, and a fresh `CoreSnapshot` is published after every tick
+//! so that visualizations can read the latest state without queuing behind a prompt.
+//!
+//! The queue is bounded (`DEFAULT_QUEUE_CAPACITY` commands by default): the methods of
+//! `AsyncCore` wait for a slot, so a burst of requests holds back the handlers that sent it
+//! instead of piling up behind a slow prompt, and the blocking ones of `CoreHandle` (`submit`,
+//! `exec_blocking`...) park their thread until a slot frees up. No command is ever dropped
+//! because the queue is full. A render loop, which must never wait, uses `try_submit` and
+//! `try_submit_prompt` instead: they fail with `CoreHandleError::Busy` when the queue is full.
+//!
+//! `AsyncCore` is the handle on the async side: an axum handler awaits `prompt` or `learn`
+//! and never holds a lock on the Core across an await.
+
+use crate::connectome::StdpCounters;
+use crate::eeg::{BandPower, DEFAULT_SPECTRUM_WINDOW};
+use crate::error::AgiResult;
+use crate::thalamus::QueryType;
+use crate::Core;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{mpsc, Arc};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::{oneshot, watch, OwnedSemaphorePermit, Semaphore, TryAcquireError};
+
+pub use tokio::sync::oneshot::error::TryRecvError;
+
+/// Commands queued or running before the senders have to wait (see `CoreHandle::spawn`).
+pub const DEFAULT_QUEUE_CAPACITY: usize = 64;
+
+/// A reply that has been queued but not received yet. Await it, or poll it with `try_recv`.
+pub type PendingReply<T> = oneshot::Receiver<T>;
+
+/// How the actor schedules the ticks of the Core (see `Core::pause` and
+/// `Core::set_tick_interval`). It lives in the Core so that any command can change it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SimulationClock {
+    pub paused: bool,
+    /// Milliseconds between two scheduled ticks; None when the Core only ticks on demand.
+    pub tick_interval_ms: Option<u64>,
+}
+
+impl SimulationClock {
+    /// The delay between two scheduled ticks, None while paused or ticking on demand only.
+    pub fn scheduled_interval(&self) -> Option<Duration> {
+        self.tick_interval_ms.filter(|_| !self.paused).map(Duration::from_millis)
+    }
+}
+
+/// A read-only picture of the Core's state, cheap enough to publish on every tick.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CoreSnapshot {
+    pub tick: u64,
+    pub neuron_potentials: Vec<f32>,
+    pub fired_neurons: Vec<u64>,
+    pub processing_speed: f32,
+    pub power_draw: f32,
+    pub memories_in_hippocampus: usize,
+    pub concepts_in_hierarchy: usize,
+    pub awakening_level: f32,
+    /// The current stage of the wakeup sequence, out of `wakeup_stages`.
+    pub wakeup_stage: u32,
+    pub wakeup_stages: u32,
+    /// Synapses changed by spike-timing-dependent plasticity since startup.
+    pub stdp: StdpCounters,
+    /// Whether the warmup phase has completed.
+    pub ready: bool,
+    /// Power of the EEG bands over the last `DEFAULT_SPECTRUM_WINDOW` ticks.
+    pub eeg_bands: BandPower,
+    pub clock: SimulationClock,
+}
+
+impl CoreSnapshot {
+    pub fn capture(core: &Core) -> Self {
+        Self {
+            tick: core.tick,
+            neuron_potentials: core.connectome.neurons.iter().map(|n| n.potential).collect(),
+            fired_neurons: core.last_fired_neurons.clone(),
+            processing_speed: core.processing_speed.load(Ordering::Relaxed),
+            power_draw: core.power_draw.load(Ordering::Relaxed),
+            memories_in_hippocampus: core.hippocampus.holographic_memory.len(),
+            concepts_in_hierarchy: core.conceptual_hierarchy.get_all_concepts().len(),
+            awakening_level: core.get_awakening_level(),
+            wakeup_stage: core.wakeup_progress().0,
+            wakeup_stages: core.wakeup_progress().1,
+            stdp: core.connectome.stdp_counters(),
+            ready: core.ready,
+            eeg_bands: core.get_eeg_spectrum(DEFAULT_SPECTRUM_WINDOW).bands,
+            clock: core.clock(),
+        }
+    }
+}
+
+/// A closure executed on the actor thread with exclusive access to the Core.
+pub type CoreTask = Box<dyn FnOnce(&mut Core) + Send>;
+
+/// The messages understood by the Core actor.
+pub enum CoreCommand {
+    /// Assimilates a piece of text (see `Core::learn_and_assimilate`).
+    Learn { text: String, is_axiom: bool, reply: oneshot::Sender<()> },
+    /// Runs the full response pipeline for a prompt.
+    Prompt { prompt: String, reply: oneshot::Sender<Option<(String, QueryType)>> },
+    /// Forces an immediate simulation step and replies with the new tick count.
+    Tick { reply: oneshot::Sender<u64> },
+    /// Captures the Core's state right now.
+    Snapshot { reply: oneshot::Sender<CoreSnapshot> },
+    /// Runs an arbitrary task against the Core, for operations without a dedicated command.
+    Exec(CoreTask),
+    /// Stops the actor thread.
+    Shutdown,
+}
+
+#[derive(Debug, Error)]
+pub enum CoreHandleError {
+    #[error("the core actor has shut down")]
+    Disconnected,
+    #[error("the command queue of the core actor is full")]
+    Busy,
+}
+
+/// A command and the slot of the queue it holds until the actor has handled it.
+struct QueuedCommand {
+    command: CoreCommand,
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+/// A cheap, cloneable handle to a Core running on its own thread.
+#[derive(Clone)]
+pub struct CoreHandle {
+    commands: mpsc::Sender<QueuedCommand>,
+    slots: Arc<Semaphore>,
+    capacity: usize,
+    snapshots: watch::Receiver<CoreSnapshot>,
+}
+
+
+impl CoreHandle {
+    /// Moves `core` onto a dedicated thread. If `tick_interval` is set, the actor ticks the Core
+    /// at that rate between commands; otherwise it only ticks on explicit `Tick` commands. The
+    /// rate can be changed, and the ticks paused, later on (see `SimulationClock`).
+    /// At most `DEFAULT_QUEUE_CAPACITY` commands are queued; see `spawn_with_capacity`.
+    /// Fails if the thread cannot be spawned.
+    pub fn spawn(core: Core, tick_interval: Option<Duration>) -> AgiResult<Self> {
+        Self::spawn_with_capacity(core, tick_interval, DEFAULT_QUEUE_CAPACITY)
+    }
+
+    /// Like `spawn`, with at most `capacity` commands queued or running at a time (at least one).
+    pub fn spawn_with_capacity(mut core: Core, tick_interval: Option<Duration>, capacity: usize) -> AgiResult<Self> {
+        core.set_tick_interval(tick_interval);
+        let capacity = capacity.max(1);
+        let (command_tx, command_rx) = mpsc::channel();
+        let (snapshot_tx, snapshot_rx) = watch::channel(CoreSnapshot::capture(&core));
+
+        thread::Builder::new()
+            .name("core-actor".to_string())
+            .spawn(move || run_actor(core, command_rx, snapshot_tx))?;
+
+        Ok(Self { commands: command_tx, slots: Arc::new(Semaphore::new(capacity)), capacity, snapshots: snapshot_rx })
+    }
+
+    /// Queues a prompt without waiting for its answer. The returned receiver can be awaited, or
+    /// polled with `try_recv` from a render loop. Blocks the calling thread while the queue is
+    /// full.
+    pub fn submit_prompt(
+        &self,
+        prompt: impl Into<String>,
+    ) -> Result<PendingReply<Option<(String, QueryType)>>, CoreHandleError> {
+        let (reply, response) = oneshot::channel();
+        self.send(CoreCommand::Prompt { prompt: prompt.into(), reply }, self.block_for_slot()?)?;
+        Ok(response)
+    }
+
+    /// Queues a task without waiting for its result; see `submit_prompt`.
+    pub fn submit<R, F>(&self, task: F) -> Result<PendingReply<R>, CoreHandleError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Core) -> R + Send + 'static,
+    {
+        self.queue_task(task, self.block_for_slot()?)
+    }
+
+    /// `submit_prompt` without ever waiting: fails with `Busy` while the queue is full.
+    pub fn try_submit_prompt(
+        &self,
+        prompt: impl Into<String>,
+    ) -> Result<PendingReply<Option<(String, QueryType)>>, CoreHandleError> {
+        let (reply, response) = oneshot::channel();
+        self.send(CoreCommand::Prompt { prompt: prompt.into(), reply }, self.try_slot()?)?;
+        Ok(response)
+    }
+
+    /// `submit` without ever waiting: fails with `Busy` while the queue is full.
+    pub fn try_submit<R, F>(&self, task: F) -> Result<PendingReply<R>, CoreHandleError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Core) -> R + Send + 'static,
+    {
+        self.queue_task(task, self.try_slot()?)
+    }
+
+    /// Runs `task` on the actor thread and blocks the calling thread until it completes,
+    /// waiting for a slot first if the queue is full. Must not be called from within an async
+    /// runtime.
+    pub fn exec_blocking<R, F>(&self, task: F) -> Result<R, CoreHandleError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Core) -> R + Send + 'static,
+    {
+        self.submit(task)?.blocking_recv().map_err(|_| CoreHandleError::Disconnected)
+    }
+
+    /// The snapshot published after the most recent tick. Never waits on the actor.
+    pub fn latest_snapshot(&self) -> CoreSnapshot {
+        self.snapshots.borrow().clone()
+    }
+
+    /// Commands queued or running, out of the capacity of the queue.
+    pub fn queued_commands(&self) -> (usize, usize) {
+        (self.capacity - self.slots.available_permits(), self.capacity)
+    }
+
+    /// A receiver that is notified every time a new snapshot is published.
+    pub fn subscribe(&self) -> watch::Receiver<CoreSnapshot> {
+        self.snapshots.clone()
+    }
+
+    /// Asks the actor to stop once the commands already queued have been handled.
+    /// A full queue does not hold the shutdown back.
+    pub fn shutdown(&self) {
+        let _ = self.commands.send(QueuedCommand { command: CoreCommand::Shutdown, _slot: None });
+    }
+
+    async fn wait_for_slot(&self) -> Result<OwnedSemaphorePermit, CoreHandleError> {
+        Arc::clone(&self.slots).acquire_owned().await.map_err(|_| CoreHandleError::Disconnected)
+    }
+
+    /// `wait_for_slot`, parking the calling thread instead of yielding to a runtime.
+    fn block_for_slot(&self) -> Result<OwnedSemaphorePermit, CoreHandleError> {
+        match self.try_slot() {
+            Err(CoreHandleError::Busy) => block_on(self.wait_for_slot()),
+            slot => slot,
+        }
+    }
+
+    fn try_slot(&self) -> Result<OwnedSemaphorePermit, CoreHandleError> {
+        Arc::clone(&self.slots).try_acquire_owned().map_err(|e| match e {
+            TryAcquireError::NoPermits => CoreHandleError::Busy,
+            TryAcquireError::Closed => CoreHandleError::Disconnected,
+        })
+    }
+
+    fn queue_task<R, F>(&self, task: F, slot: OwnedSemaphorePermit) -> Result<PendingReply<R>, CoreHandleError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Core) -> R + Send + 'static,
+    {
+        let (reply, response) = oneshot::channel();
+        let command = CoreCommand::Exec(Box::new(move |core: &mut Core| {
+            let _ = reply.send(task(core));
+        }));
+        self.send(command, slot)?;
+        Ok(response)
+    }
+
+    /// The slot is given back once the actor has handled the command.
+    fn send(&self, command: CoreCommand, slot: OwnedSemaphorePermit) -> Result<(), CoreHandleError> {
+        self.commands.send(QueuedCommand { command, _slot: Some(slot) }).map_err(|_| CoreHandleError::Disconnected)
+    }
+}
+
+/// The handle seen from async code: every method waits for a slot of the bounded queue
+/// without blocking the runtime.
+#[derive(Clone)]
+pub struct AsyncCore {
+    handle: CoreHandle,
+}
+
+impl AsyncCore {
+    pub fn new(handle: CoreHandle) -> Self {
+        Self { handle }
+    }
+
+    /// The blocking handle on the same actor, for code running on its own thread.
+    pub fn handle(&self) -> &CoreHandle {
+        &self.handle
+    }
+
+    pub async fn learn(&self, text: impl Into<String>, is_axiom: bool) -> Result<(), CoreHandleError> {
+        let (reply, response) = oneshot::channel();
+        let slot = self.handle.wait_for_slot().await?;
+        self.handle.send(CoreCommand::Learn { text: text.into(), is_axiom, reply }, slot)?;
+        response.await.map_err(|_| CoreHandleError::Disconnected)
+    }
+
+    pub async fn prompt(&self, prompt: impl Into<String>) -> Result<Option<(String, QueryType)>, CoreHandleError> {
+        let (reply, response) = oneshot::channel();
+        let slot = self.handle.wait_for_slot().await?;
+        self.handle.send(CoreCommand::Prompt { prompt: prompt.into(), reply }, slot)?;
+        response.await.map_err(|_| CoreHandleError::Disconnected)
+    }
+
+    pub async fn tick(&self) -> Result<u64, CoreHandleError> {
+        let (reply, response) = oneshot::channel();
+        let slot = self.handle.wait_for_slot().await?;
+        self.handle.send(CoreCommand::Tick { reply }, slot)?;
+        response.await.map_err(|_| CoreHandleError::Disconnected)
+    }
+
+    pub async fn snapshot(&self) -> Result<CoreSnapshot, CoreHandleError> {
+        let (reply, response) = oneshot::channel();
+        let slot = self.handle.wait_for_slot().await?;
+        self.handle.send(CoreCommand::Snapshot { reply }, slot)?;
+        response.await.map_err(|_| CoreHandleError::Disconnected)
+    }
+
+    /// Runs `task` on the actor thread and returns its result, waiting for a slot of the queue
+    /// first if it is full.
+    pub async fn exec<R, F>(&self, task: F) -> Result<R, CoreHandleError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Core) -> R + Send + 'static,
+    {
+        let slot = self.handle.wait_for_slot().await?;
+        self.handle.queue_task(task, slot)?.await.map_err(|_| CoreHandleError::Disconnected)
+    }
+
+    /// Queues a prompt once a slot is free, without waiting for its answer.
+    pub async fn submit_prompt(
+        &self,
+        prompt: impl Into<String>,
+    ) -> Result<PendingReply<Option<(String, QueryType)>>, CoreHandleError> {
+        let (reply, response) = oneshot::channel();
+        let slot = self.handle.wait_for_slot().await?;
+        self.handle.send(CoreCommand::Prompt { prompt: prompt.into(), reply }, slot)?;
+        Ok(response)
+    }
+
+    /// Queues a task once a slot is free, without waiting for its result.
+    pub async fn submit<R, F>(&self, task: F) -> Result<PendingReply<R>, CoreHandleError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Core) -> R + Send + 'static,
+    {
+        let slot = self.handle.wait_for_slot().await?;
+        self.handle.queue_task(task, slot)
+    }
+
+    /// See `CoreHandle::latest_snapshot`.
+    pub fn latest_snapshot(&self) -> CoreSnapshot {
+        self.handle.latest_snapshot()
+    }
+
+    /// See `CoreHandle::queued_commands`.
+    pub fn queued_commands(&self) -> (usize, usize) {
+        self.handle.queued_commands()
+    }
+
+    /// See `CoreHandle::subscribe`.
+    pub fn subscribe(&self) -> watch::Receiver<CoreSnapshot> {
+        self.handle.subscribe()
+    }
+
+    /// See `CoreHandle::shutdown`.
+    pub fn shutdown(&self) {
+        self.handle.shutdown()
+    }
+}
+
+impl From<CoreHandle> for AsyncCore {
+    fn from(handle: CoreHandle) -> Self {
+        Self::new(handle)
+    }
+}
+
+/// Polls `future` on the calling thread, parked until its waker is woken. The semaphore of the
+/// queue needs no runtime to wake its waiters.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+fn run_actor(
+    mut core: Core,
+    commands: mpsc::Receiver<QueuedCommand>,
+    snapshots: watch::Sender<CoreSnapshot>,
+) {
+    println!("--- Core actor started ---");
+    let mut next_tick = Instant::now();
+
+    loop {
+        let command = match core.clock().scheduled_interval() {
+            Some(_) => match commands.recv_timeout(next_tick.saturating_duration_since(Instant::now())) {
+                Ok(command) => Some(command),
+                Err(mpsc::RecvTimeoutError::Timeout) => None,
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            },
+            None => match commands.recv() {
+                Ok(command) => Some(command),
+                Err(_) => break,
+            },
+        };
+
+        // The slot of the command is freed at the end of the iteration, once it is handled.
+        let (command, _slot) = match command {
+            Some(QueuedCommand { command, _slot }) => (Some(command), _slot),
+            None => (None, None),
+        };
+        match command {
+            Some(CoreCommand::Learn { text, is_axiom, reply }) => {
+                core.learn_and_assimilate(&text, is_axiom);
+                let _ = reply.send(());
+            }
+            Some(CoreCommand::Prompt { prompt, reply }) => {
+                let _ = reply.send(core.get_response_for_prompt(&prompt));
+            }
+            Some(CoreCommand::Tick { reply }) => {
+                core.tick();
+                let _ = snapshots.send(CoreSnapshot::capture(&core));
+                let _ = reply.send(core.tick);
+            }
+            Some(CoreCommand::Snapshot { reply }) => {
+                let _ = reply.send(CoreSnapshot::capture(&core));
+            }
+            Some(CoreCommand::Exec(task)) => {
+                // Tasks that step or pause the simulation, or advance the wakeup sequence, are
+                // seen at once by the front-ends.
+                let (tick, clock, wakeup) = (core.tick, core.clock(), core.wakeup_progress());
+                task(&mut core);
+                if core.tick != tick || core.clock() != clock || core.wakeup_progress() != wakeup {
+                    let _ = snapshots.send(CoreSnapshot::capture(&core));
+                }
+            }
+            Some(CoreCommand::Shutdown) => break,
+            None => {}
+        }
+
+        // Scheduled ticks are interleaved between commands so a busy queue cannot starve them.
+        if let Some(interval) = core.clock().scheduled_interval() {
+            // A faster rate applies at once, without waiting for the tick planned at the old one.
+            next_tick = next_tick.min(Instant::now() + interval);
+            if Instant::now() >= next_tick {
+                core.tick();
+                let _ = snapshots.send(CoreSnapshot::capture(&core));
+                next_tick = Instant::now() + interval;
+            }
+        }
+    }
+
+    println!("--- Core actor stopped ---");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_the_simulation_can_be_paused_stepped_and_paced() {
+        let core = CoreHandle::spawn(Core::new_for_tests(), Some(Duration::from_millis(2))).unwrap();
+        let tick = |core: &CoreHandle| core.exec_blocking(|core| core.tick).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        assert!(tick(&core) > 0, "Ticks are scheduled");
+
+        core.exec_blocking(|core| core.pause()).unwrap();
+        let paused_at = tick(&core);
+        // Published as soon as the command has run, hence before the next one.
+        assert!(core.latest_snapshot().clock.paused);
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(tick(&core), paused_at, "No tick while paused");
+        assert_eq!(core.exec_blocking(|core| core.step(3)).unwrap(), paused_at + 3);
+        assert_eq!(tick(&core), paused_at + 3);
+        assert_eq!(core.latest_snapshot().tick, paused_at + 3, "Steps are published too");
+
+        core.exec_blocking(|core| {
+            core.set_tick_interval(Some(Duration::ZERO));
+            core.resume();
+        })
+        .unwrap();
+        tick(&core);
+        assert_eq!(core.latest_snapshot().clock.scheduled_interval(), Some(Duration::from_millis(1)));
+        thread::sleep(Duration::from_millis(50));
+        assert!(tick(&core) > paused_at + 3, "The ticks go on after the pause");
+        core.shutdown();
+    }
+}
@@ -0,0 +1,148 @@
+//! coreference.rs - Resolution of pronouns against the conversation context.
+//!
+//! A follow-up such as "Et qui était son professeur ?" carries no concept of its own: the
+//! thalamus and the reasoning engine only see "professeur". Before the prompt reaches them,
+//! the prefrontal cortex rewrites its pronouns with the last entity named in the conversation
+//! ("Et qui était le professeur d'Aristote ?").
+
+use crate::stop_words::StopWordRegistry;
+use crate::tokenizer::{self, TokenKind};
+
+/// Words that are capitalized at the start of a prompt without naming anything.
+const NON_ENTITIES: &[&str] = &[
+    "qui", "que", "quoi", "quel", "quelle", "quels", "quelles", "comment", "pourquoi", "où", "quand", "combien",
+    "explique", "décris", "parle", "dis", "définis", "donne", "montre", "raconte", "compare", "oui", "non", "merci",
+    "what", "who", "which", "how", "why", "where", "when", "explain", "describe", "tell", "define", "give", "show",
+    "yes", "no", "thanks",
+];
+
+/// Words after which "il" is impersonal ("il y a", "il faut").
+const IMPERSONAL_VERBS: &[&str] = &["y", "faut", "pleut", "neige", "semble", "suffit", "manque", "reste", "s'agit"];
+
+/// The entities named in a text: runs of capitalized words ("Albert Einstein"), in order.
+pub fn extract_entities(text: &str, stop_words: &StopWordRegistry) -> Vec<String> {
+    let mut entities: Vec<String> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    for token in tokenizer::tokenize(text) {
+        let lower = token.normalized();
+        let is_name = token.kind == TokenKind::Word
+            && token.text.chars().next().is_some_and(char::is_uppercase)
+            && token.text.chars().count() > 1
+            && !stop_words.is_stop_word(&lower)
+            && !NON_ENTITIES.contains(&lower.as_str());
+        if is_name {
+            current.push(token.text);
+        } else if !current.is_empty() {
+            entities.push(current.join(" "));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        entities.push(current.join(" "));
+    }
+    entities
+}
+
+/// Rewrites the third-person singular pronouns of `prompt` with `antecedent`.
+/// Returns None when the prompt has no pronoun to resolve.
+pub fn resolve(prompt: &str, antecedent: &str) -> Option<String> {
+    let chunks: Vec<&str> = prompt.split_whitespace().collect();
+    let mut output: Vec<String> = Vec::with_capacity(chunks.len() + 2);
+    let mut resolved = false;
+    let mut i = 0;
+
+    while i < chunks.len() {
+        let (lead, word, trail) = split_punctuation(chunks[i]);
+        let lower = word.to_lowercase().replace('’', "'");
+        let next = chunks.get(i + 1).map(|chunk| split_punctuation(chunk).1.to_lowercase());
+
+        let replacement = match lower.as_str() {
+            // French possessives: "son professeur" -> "le professeur d'Aristote".
+            "son" | "sa" | "ses" if next.is_some() => {
+                let (next_lead, noun, next_trail) = split_punctuation(chunks[i + 1]);
+                let article = match lower.as_str() {
+                    "son" => "le",
+                    "sa" => "la",
+                    _ => "les",
+                };
+                i += 1;
+                Some(format!("{}{} {}{} {}{}", lead, cased(article, word), next_lead, noun, of_french(antecedent), next_trail))
+            }
+            "il" | "elle" if !next.as_deref().is_some_and(|next| IMPERSONAL_VERBS.contains(&next)) => Some(format!("{}{}{}", lead, antecedent, trail)),
+            "he" | "she" | "him" => Some(format!("{}{}{}", lead, antecedent, trail)),
+            "his" => Some(format!("{}{}'s{}", lead, antecedent, trail)),
+            // "her" is a possessive before a noun, an object otherwise.
+            "her" => match next {
+                Some(next) if trail.is_empty() && next.chars().any(char::is_alphanumeric) && !is_function_word(&next) => {
+                    Some(format!("{}{}'s{}", lead, antecedent, trail))
+                }
+                _ => Some(format!("{}{}{}", lead, antecedent, trail)),
+            },
+            // French inversion: "est-il né ?" -> "Aristote est né ?".
+            _ => ["-t-il", "-t-elle", "-il", "-elle"]
+                .iter()
+                .find_map(|suffix| lower.strip_suffix(suffix).map(|verb| &word[..verb.len()]))
+                .filter(|verb| !verb.is_empty())
+                .map(|verb| format!("{}{} {}{}", lead, antecedent, verb, trail)),
+        };
+
+        match replacement {
+            Some(text) => {
+                output.push(text);
+                resolved = true;
+            }
+            None => output.push(chunks[i].to_string()),
+        }
+        i += 1;
+    }
+
+    resolved.then(|| output.join(" "))
+}
+
+/// "de Platon" or "d'Aristote".
+fn of_french(antecedent: &str) -> String {
+    match antecedent.chars().next() {
+        Some(c) if "aeiouyéèêàâîôûhAEIOUYÉÈÊÀÂÎÔÛH".contains(c) => format!("d'{}", antecedent),
+        _ => format!("de {}", antecedent),
+    }
+}
+
+/// `word` with the capitalization of `model`.
+fn cased(word: &str, model: &str) -> String {
+    if model.chars().next().is_some_and(char::is_uppercase) {
+        let mut chars = word.chars();
+        chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+    } else {
+        word.to_string()
+    }
+}
+
+fn is_function_word(word: &str) -> bool {
+    matches!(word, "a" | "an" | "the" | "to" | "in" | "on" | "at" | "for" | "with" | "about" | "from" | "and" | "or" | "is" | "was")
+}
+
+/// Splits leading and trailing punctuation off a whitespace-separated chunk.
+fn split_punctuation(chunk: &str) -> (&str, &str, &str) {
+    let is_punctuation = |c: char| !c.is_alphanumeric() && c != '-' && c != '\'' && c != '’';
+    let start = chunk.find(|c: char| !is_punctuation(c)).unwrap_or(chunk.len());
+    let end = chunk.rfind(|c: char| !is_punctuation(c)).map(|i| i + chunk[i..].chars().next().unwrap().len_utf8()).unwrap_or(start);
+    (&chunk[..start], &chunk[start..end], &chunk[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entities_and_pronoun_rewriting() {
+        let stop_words = StopWordRegistry::new();
+        assert_eq!(extract_entities("Qui était Aristote ?", &stop_words), vec!["Aristote"]);
+        assert_eq!(extract_entities("Parle-moi d'Albert Einstein et de Paris.", &stop_words), vec!["Albert Einstein", "Paris"]);
+
+        assert_eq!(resolve("Et qui était son professeur ?", "Aristote").as_deref(), Some("Et qui était le professeur d'Aristote ?"));
+        assert_eq!(resolve("Où est-il né ?", "Platon").as_deref(), Some("Où Platon est né ?"));
+        assert_eq!(resolve("What did he write about her ideas?", "Marie Curie").as_deref(), Some("What did Marie Curie write about Marie Curie's ideas?"));
+        assert_eq!(resolve("Il y a combien de planètes ?", "Platon"), None);
+        assert_eq!(resolve("Qui était Socrate ?", "Platon"), None);
+    }
+}
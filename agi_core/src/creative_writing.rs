@@ -0,0 +1,160 @@
+//! creative_writing.rs - Answers to creative requests: a poem or a story woven from concepts.
+//!
+//! "Écris un poème sur la mer" is not answered by recalling a fact. The Core retrieves what it
+//! knows about the request, the Creativity Forge perturbs the concepts of the traces of those
+//! memories (`CreativityForge::perturb_concepts`), Silicium bridges the concepts no memory
+//! relates (`Silicium::bridge_concepts`), and the motor cortex writes each bridge as a stanza of
+//! a poem or an episode of a story (`MotorCortex::compose_creative`). The creativity temperature
+//! (`CoreConfig::creativity_temperature`) decides how far the concepts stray from the most
+//! relevant ones, and how many bridges the text is woven from. Concepts are lemmas ("éter" for
+//! "était"): the text shows them as the memories wrote them (`surface_forms`).
+
+use crate::holographic_memory::HolographicMemory;
+use crate::lemmatizer;
+use crate::onboarding;
+use crate::silicium::ConceptBridge;
+use crate::stop_words::StopWordRegistry;
+use crate::tokenizer::{self, TokenKind};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Phrases asking for a creative text, matched at the start of a word.
+const CREATIVE_MARKERS: &[&str] = &[
+    // French
+    "écris un poème", "écris-moi un poème", "un poème sur", "écris une histoire", "écris-moi une histoire", "raconte une histoire",
+    "raconte-moi une histoire", "invente une histoire", "invente un", "un conte sur", "compose un", "imagine ",
+    // English
+    "write a poem", "write me a poem", "a poem about", "write a story", "write me a story", "tell me a story", "tell a story",
+    "make up a story", "invent a", "compose a", "imagine ", "what if",
+];
+/// Words of a creative request that name the form or the act rather than its subject.
+const FORM_WORDS: &[&str] = &[
+    "écris", "écrire", "poème", "poèmes", "poésie", "histoire", "histoires", "conte", "raconte", "invente", "imagine", "compose", "monde",
+    "write", "poem", "poems", "poetry", "story", "stories", "tale", "tell", "make", "invent", "about", "world",
+];
+/// Words asking for a poem rather than a story.
+const POEM_WORDS: &[&str] = &["poème", "poèmes", "poésie", "vers", "poem", "poems", "poetry", "verse"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CreativeForm {
+    /// A stanza per bridge.
+    Poem,
+    /// An episode per bridge.
+    Story,
+}
+
+impl CreativeForm {
+    /// A poem when the request asks for one, a story otherwise.
+    pub fn of(prompt: &str) -> Self {
+        if tokenizer::content_words(prompt).iter().any(|word| POEM_WORDS.contains(&word.as_str())) {
+            CreativeForm::Poem
+        } else {
+            CreativeForm::Story
+        }
+    }
+}
+
+/// What the motor cortex writes a creative answer from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreativeDraft {
+    pub form: CreativeForm,
+    /// The concepts of the text, the theme first.
+    pub concepts: Vec<String>,
+    pub bridges: Vec<ConceptBridge>,
+}
+
+impl CreativeDraft {
+    /// The draft with its concepts written as in `forms` (see `surface_forms`). A concept without
+    /// a written form, such as the seeds taken from the request, is kept as it is.
+    pub fn written_as(self, forms: &HashMap<String, String>) -> Self {
+        let written = |concept: String| forms.get(&concept).cloned().unwrap_or(concept);
+        Self {
+            form: self.form,
+            concepts: self.concepts.into_iter().map(written).collect(),
+            bridges: self
+                .bridges
+                .into_iter()
+                .map(|bridge| ConceptBridge { from: written(bridge.from), to: written(bridge.to), via: bridge.via.and_then(|via| forms.get(&via).cloned()) })
+                .collect(),
+        }
+    }
+}
+
+/// The subject words of the memories (neither stop words nor verbs), by the concept they are
+/// lemmatized into, as the memories wrote them: "étoiles" rather than "étoile". A concept
+/// missing from it is not shown.
+pub fn surface_forms(memories: &[HolographicMemory], stop_words: &StopWordRegistry) -> HashMap<String, String> {
+    let mut forms = HashMap::new();
+    for memory in memories {
+        let lang = lemmatizer::detect_language(&memory.text);
+        for token in tokenizer::tokenize(&memory.text).into_iter().filter(|token| token.kind == TokenKind::Word) {
+            let word = token.normalized();
+            if word.chars().count() > 2 && !stop_words.is_stop_word(&word) && onboarding::is_subject_word(&word) {
+                forms.entry(lemmatizer::lemmatize_in(&word, lang)).or_insert(word);
+            }
+        }
+    }
+    forms
+}
+
+/// Whether `prompt` asks for a poem, a story or something imagined.
+pub fn is_creative_request(prompt: &str) -> bool {
+    let lower = format!(" {} ", prompt.to_lowercase().replace('’', "'"));
+    CREATIVE_MARKERS.iter().any(|marker| lower.contains(&format!(" {}", marker)))
+}
+
+/// The subjects of a creative request: its words that are neither stop words nor words of the
+/// form ("poème", "story"...).
+pub fn seed_concepts(prompt: &str, stop_words: &StopWordRegistry) -> Vec<String> {
+    let mut seeds: Vec<String> = Vec::new();
+    for token in tokenizer::tokenize(prompt) {
+        if token.kind != TokenKind::Word {
+            continue;
+        }
+        let word = token.normalized();
+        if word.chars().count() > 2 && !stop_words.is_stop_word(&word) && !FORM_WORDS.contains(&word.as_str()) && !seeds.contains(&word) {
+            seeds.push(word);
+        }
+    }
+    seeds
+}
+
+/// How many bridges a text is woven from at `temperature`: 1 at 0, 3 at 1.
+pub fn bridge_count(temperature: f32) -> usize {
+    1 + (temperature.clamp(0.0, 1.0) * 2.0).round() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conceptual_hierarchy::ConceptualHierarchy;
+    use crate::holographic_memory::{HolographicMemory, HolographicTrace};
+    use crate::silicium::Silicium;
+
+    #[test]
+    fn test_creative_requests_and_their_bridges() {
+        assert!(is_creative_request("Écris un poème sur la mer."));
+        assert!(is_creative_request("Imagine a world without clocks"));
+        assert!(!is_creative_request("J'imagine que le soleil est une étoile."));
+        assert!(!is_creative_request("Qu'est-ce qu'un poème ?"));
+        assert_eq!(CreativeForm::of("Écris un poème sur la mer."), CreativeForm::Poem);
+        assert_eq!(CreativeForm::of("Raconte-moi une histoire de dragons."), CreativeForm::Story);
+        assert_eq!(seed_concepts("Écris un poème sur la mer et les étoiles.", &StopWordRegistry::default()), ["mer", "étoiles"]);
+        assert_eq!((bridge_count(0.0), bridge_count(0.7), bridge_count(1.0)), (1, 2, 3));
+
+        let memory = |text: &str, concepts: &[&str]| {
+            let mut trace = HolographicTrace::new_empty(16);
+            for concept in concepts {
+                trace.combine_with(&HolographicTrace::new_seeded(concept, 16));
+            }
+            HolographicMemory::new(text.to_string(), trace, false)
+        };
+        let memories = [memory("La mer reflète la lune.", &["mer", "lune"]), memory("La lune éclaire le désert.", &["lune", "désert"])];
+        let concepts: Vec<String> = ["mer", "lune", "désert"].map(String::from).to_vec();
+        let bridges = Silicium::new().bridge_concepts(&concepts, &memories, &ConceptualHierarchy::new(), 2);
+        // No memory relates the sea to the desert: the moon, found next to each, bridges them.
+        assert_eq!(bridges[0], ConceptBridge { from: "mer".to_string(), to: "désert".to_string(), via: Some("lune".to_string()) });
+        assert_eq!(bridges.len(), 2, "Related concepts are bridged once the novel pairs run out");
+    }
+}
@@ -1,25 +1,102 @@
 //! La Creativity Forge est responsable de la pensée divergente, de l'intuition
 //! et de la génération d'états quantiques nouveaux et inattendus.
 
-use crate::quantum::{EntanglementGate, Qubit, TwoQubitGate, OneQubitGate};
-use rand::{thread_rng, Rng};
+use crate::capabilities::Capability;
+use crate::entanglement_map::{EntanglementConfig, EntanglementMap};
+use crate::holographic_memory::HolographicMemory;
+use crate::quantum::PhaseShiftGate;
+use crate::quantum_backend::QuantumBackend;
+use crate::rng::CoreRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
 use std::f32::consts::PI;
 
+/// Concepts d'une réponse créative, au plus.
+const MAX_CREATIVE_CONCEPTS: usize = 6;
+
+/// Listed by `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "synthesis",
+    description: "Combine concepts into new ideas",
+    description_fr: "Combiner des concepts en idées nouvelles",
+    examples: &["Imagine un lien entre la musique et les mathématiques."],
+};
+
 /// La Creativity Forge, qui explore de nouvelles voies cognitives.
 pub struct CreativityForge {
     // Futurs champs : attracteurs étranges, paramètres de chaos, etc.
+    rng: CoreRng,
+    /// Les paires de qubits intriquées par les sauts conceptuels récents.
+    entanglements: EntanglementMap,
 }
 
 impl CreativityForge {
     pub fn new() -> Self {
-        Self {}
+        Self { rng: CoreRng::from_entropy(), entanglements: EntanglementMap::default() }
+    }
+
+    /// Remplace le flux aléatoire de la Forge (voir `RngProvider`).
+    pub fn with_rng(mut self, rng: CoreRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Règle l'affaiblissement des intrications suivies (voir `EntanglementMap`).
+    pub fn with_entanglement(mut self, config: EntanglementConfig) -> Self {
+        self.entanglements = EntanglementMap::new(config);
+        self
+    }
+
+    /// Les paires de qubits intriquées par les sauts conceptuels récents.
+    pub fn entanglements(&self) -> &EntanglementMap {
+        &self.entanglements
+    }
+
+    /// Affaiblit les intrications suivies d'un tick.
+    pub fn decay_entanglements(&mut self) {
+        self.entanglements.decay();
+    }
+
+    /// Les concepts d'une réponse créative (voir `creative_writing`) : les concepts `seeds` de la
+    /// demande, puis ceux des traces des souvenirs retrouvés, dont la pertinence est brouillée
+    /// par la température. À 0, ils suivent leur pertinence ; à 1, presque le hasard.
+    pub fn perturb_concepts(&mut self, memories: &[HolographicMemory], seeds: &[String], temperature: f32) -> Vec<String> {
+        let temperature = temperature.clamp(0.0, 1.0);
+        let mut relevance: HashMap<String, f32> = HashMap::new();
+        for memory in memories {
+            for (concept, weighted) in &memory.trace.weighted_concepts {
+                *relevance.entry(concept.to_string()).or_insert(0.0) += weighted.relevance;
+            }
+        }
+        let mut candidates: Vec<(String, f32)> = relevance
+            .into_iter()
+            .filter(|(concept, _)| concept.chars().count() > 2 && concept.chars().all(char::is_alphabetic) && !seeds.contains(concept))
+            .collect();
+        // Triés avant le tirage, pour qu'un flux graine donne toujours le même texte.
+        candidates.sort_by(|a, b| a.0.cmp(&b.0));
+        let strongest = candidates.iter().map(|(_, relevance)| *relevance).fold(f32::EPSILON, f32::max);
+        let mut perturbed: Vec<(String, f32)> = candidates
+            .into_iter()
+            .map(|(concept, relevance)| {
+                let noise: f32 = self.rng.gen();
+                (concept, (1.0 - temperature) * relevance / strongest + temperature * noise)
+            })
+            .collect();
+        perturbed.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        seeds.iter().cloned().chain(perturbed.into_iter().map(|(concept, _)| concept)).take(MAX_CREATIVE_CONCEPTS).collect()
     }
 
     /// Modifie l'état quantique pour encourager l'émergence de nouveaux motifs.
     /// C'est ici que la "pensée latérale" et les "sauts conceptuels" se produisent.
-    pub fn process(&self, quantum_core: &mut [Qubit]) {
-        println!("\n--- Creativity Forge Activated ---");
-        let mut rng = thread_rng();
+    pub fn process(&mut self, quantum_core: &mut dyn QuantumBackend) {
+        self.process_with_intensity(quantum_core, 1.0);
+    }
+
+    /// Comme `process`, avec une intensité qui multiplie le nombre de sauts conceptuels et
+    /// l'amplitude du bruit d'intuition (voir `QuantumReadout::intensity`).
+    pub fn process_with_intensity(&mut self, quantum_core: &mut dyn QuantumBackend, intensity: f32) {
+        println!("\n--- Creativity Forge Activated (intensity {:.2}) ---", intensity);
+        let rng = &mut self.rng;
         let core_len = quantum_core.len();
 
         if core_len < 2 {
@@ -30,9 +107,8 @@ impl CreativityForge {
         // --- 1. Sauts Conceptuels via Intrication (Quantum Leaps) ---
         // On intrique un petit nombre de paires de qubits pour créer des liens
         // nouveaux et inattendus entre des concepts non-reliés.
-        let entanglement_gate = EntanglementGate;
-        // Intriquer ~5% du core, avec un minimum de 1.
-        let num_entanglements = (core_len / 20).max(1); 
+        // Intriquer ~5% du core à intensité 1, avec un minimum de 1.
+        let num_entanglements = (((core_len / 20) as f32 * intensity).round() as usize).max(1);
         println!("Attempting {} quantum leaps...", num_entanglements);
 
         for _ in 0..num_entanglements {
@@ -43,27 +119,25 @@ impl CreativityForge {
                 idx2 = rng.gen_range(0..core_len);
             }
 
-            // Emprunter deux éléments mutables du slice de manière sûre
             let (lo_idx, hi_idx) = if idx1 < idx2 { (idx1, idx2) } else { (idx2, idx1) };
-            let (slice1, slice2) = quantum_core.split_at_mut(hi_idx);
-            let q1 = &mut slice1[lo_idx];
-            let q2 = &mut slice2[0];
 
             println!("  -> Entangling Qubit {} and Qubit {}", lo_idx, hi_idx);
-            // q1 est le contrôle, q2 est la cible
-            entanglement_gate.apply(q1, q2);
+            // Le plus petit indice est le contrôle, l'autre la cible
+            quantum_core.entangle(lo_idx, hi_idx);
+            self.entanglements.record(lo_idx, hi_idx);
         }
 
         // --- 2. Bruit d'Intuition (Background Creativity) ---
         // Une légère fluctuation aléatoire pour tous les qubits, simulant
         // une "intuition" de fond ou des idées spontanées mineures.
         println!("Applying background intuition noise...");
-        for qubit in quantum_core.iter_mut() {
+        for index in 0..core_len {
             // C'est l'équivalent d'une "intuition" ou d'une "idée spontanée".
             // Introduce a random phase shift for creative exploration.
-            let random_phase_shift: f32 = rng.gen_range(-PI..PI);
-            let phase_gate = crate::quantum::PhaseShiftGate::new(random_phase_shift);
-            phase_gate.apply(qubit);
+            let amplitude = PI * intensity.max(f32::EPSILON);
+            let random_phase_shift: f32 = rng.gen_range(-amplitude..amplitude);
+            let phase_gate = PhaseShiftGate::new(random_phase_shift);
+            quantum_core.apply(index, &phase_gate);
         }
         println!("--- Creativity Forge Process Complete ---\n");
     }
@@ -1,12 +1,163 @@
 //! The engine for creative, analogical, and associative reasoning.
 
+use crate::capabilities::Capability;
 use crate::conceptual_hierarchy::ConceptualHierarchy;
+use crate::knowledge_scanner::DataSource;
+use crate::lemmatizer::Lang;
+use serde::Serialize;
+use std::collections::HashMap;
 
-pub struct CuriosityEngine;
+/// La curiosité autonome, telle que la décrit `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "curiosity",
+    description: "Notice what I do not know and look for it on my own",
+    description_fr: "Repérer ce que j'ignore et le chercher de moi-même",
+    examples: &[],
+};
+
+/// Interest lost by every concept at each observation, so that old topics fade.
+const INTEREST_DECAY: f32 = 0.95;
+/// Interests below this are forgotten.
+const MIN_INTEREST: f32 = 0.05;
+/// Explorations of a gap before it is abandoned.
+const MAX_EXPLORATION_ATTEMPTS: u32 = 3;
+/// Gaps waiting to be explored; newer gaps are ignored beyond this.
+const MAX_OPEN_GAPS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GapStatus {
+    /// Waiting to be explored.
+    Open,
+    /// An exploration goal was handed out and has not completed yet.
+    Exploring,
+    /// An exploration taught the Core something that answers the question.
+    Resolved,
+    /// Every attempt failed.
+    Abandoned,
+}
+
+/// A question the Core could not answer: no memory was within the retrieval threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KnowledgeGap {
+    pub id: u64,
+    /// The key concepts of the question ("trou noir").
+    pub topic: String,
+    /// The question as it was asked.
+    pub query: String,
+    pub lang: Lang,
+    pub status: GapStatus,
+    pub attempts: u32,
+}
+
+/// What to scan to fill a gap.
+#[derive(Debug)]
+pub struct ExplorationGoal {
+    pub gap_id: u64,
+    pub topic: String,
+    /// The sources to try, in order, until one yields something.
+    pub sources: Vec<DataSource>,
+}
+
+impl ExplorationGoal {
+    /// Builds the sources of a gap from location templates, where `{topic}` stands for the
+    /// topic and `{lang}` for the language code of the question ("wikipedia:{lang}:{topic}").
+    pub fn for_gap(gap: &KnowledgeGap, source_templates: &[String]) -> Self {
+        let sources = source_templates
+            .iter()
+            .map(|template| DataSource::parse(&template.replace("{topic}", &gap.topic).replace("{lang}", gap.lang.code())))
+            .collect();
+        Self { gap_id: gap.id, topic: gap.topic.clone(), sources }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CuriosityEngine {
+    /// Interest score of each concept, raised each time a prompt mentions it.
+    interests: HashMap<String, f32>,
+    gaps: Vec<KnowledgeGap>,
+    next_gap_id: u64,
+}
 
 impl CuriosityEngine {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Records a question that found no memory. A gap already open (or being explored) on the
+    /// same topic is reused. Returns the id of the gap, or None if too many are waiting.
+    pub fn record_gap(&mut self, query: &str, topic: &str, lang: Lang) -> Option<u64> {
+        let topic = topic.trim().to_lowercase();
+        let pending = |gap: &&KnowledgeGap| matches!(gap.status, GapStatus::Open | GapStatus::Exploring);
+        if let Some(gap) = self.gaps.iter().filter(pending).find(|gap| gap.topic == topic) {
+            return Some(gap.id);
+        }
+        if topic.is_empty() || self.gaps.iter().filter(pending).count() >= MAX_OPEN_GAPS {
+            return None;
+        }
+        self.next_gap_id += 1;
+        let gap = KnowledgeGap { id: self.next_gap_id, topic, query: query.to_string(), lang, status: GapStatus::Open, attempts: 0 };
+        println!("--- Curiosity: knowledge gap #{} on '{}' ---", gap.id, gap.topic);
+        self.gaps.push(gap);
+        Some(self.next_gap_id)
+    }
+
+    /// Hands out the oldest open gap for exploration (it becomes `Exploring`).
+    pub fn next_gap(&mut self) -> Option<&KnowledgeGap> {
+        let gap = self.gaps.iter_mut().find(|gap| gap.status == GapStatus::Open)?;
+        gap.status = GapStatus::Exploring;
+        gap.attempts += 1;
+        Some(gap)
+    }
+
+    /// Closes an exploration: the gap is resolved, or reopened (abandoned after
+    /// `MAX_EXPLORATION_ATTEMPTS` attempts).
+    pub fn complete_gap(&mut self, id: u64, resolved: bool) -> Option<GapStatus> {
+        let gap = self.gaps.iter_mut().find(|gap| gap.id == id)?;
+        gap.status = match (resolved, gap.attempts >= MAX_EXPLORATION_ATTEMPTS) {
+            (true, _) => GapStatus::Resolved,
+            (false, true) => GapStatus::Abandoned,
+            (false, false) => GapStatus::Open,
+        };
+        Some(gap.status)
+    }
+
+    /// Every gap recorded so far, oldest first.
+    pub fn gaps(&self) -> &[KnowledgeGap] {
+        &self.gaps
+    }
+
+    /// Records the concepts of a prompt: each gains one point of interest, after every known
+    /// interest has decayed.
+    pub fn observe<I, S>(&mut self, concepts: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for interest in self.interests.values_mut() {
+            *interest *= INTEREST_DECAY;
+        }
+        self.interests.retain(|_, interest| *interest >= MIN_INTEREST);
+        for concept in concepts {
+            *self.interests.entry(concept.as_ref().to_lowercase()).or_insert(0.0) += 1.0;
+        }
+    }
+
+    /// How interesting a text is, from its concepts: the sum of their interest scores.
+    pub fn interest_in<I, S>(&self, concepts: I) -> f32
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        concepts.into_iter().filter_map(|concept| self.interests.get(&concept.as_ref().to_lowercase())).sum()
+    }
+
+    /// The `count` concepts of highest interest, most interesting first.
+    pub fn top_interests(&self, count: usize) -> Vec<(String, f32)> {
+        let mut interests: Vec<(String, f32)> = self.interests.iter().map(|(concept, interest)| (concept.clone(), *interest)).collect();
+        interests.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        interests.truncate(count);
+        interests
     }
 
     /// Finds concepts in different domains that share structural similarities.
@@ -55,3 +206,25 @@ impl CuriosityEngine {
         analogies
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gap_lifecycle() {
+        let mut curiosity = CuriosityEngine::new();
+        let id = curiosity.record_gap("Qu'est-ce qu'un pulsar ?", "pulsar", Lang::French).unwrap();
+        assert_eq!(curiosity.record_gap("Et les pulsars ?", "Pulsar", Lang::French), Some(id), "A pending gap is not recorded twice");
+
+        let gap = curiosity.next_gap().unwrap();
+        let goal = ExplorationGoal::for_gap(gap, &["wikipedia:{lang}:{topic}".to_string()]);
+        assert_eq!(goal.sources[0].location(), "wikipedia:fr:pulsar");
+        assert!(curiosity.next_gap().is_none(), "The only gap is being explored");
+
+        assert_eq!(curiosity.complete_gap(id, false), Some(GapStatus::Open));
+        curiosity.next_gap();
+        assert_eq!(curiosity.complete_gap(id, true), Some(GapStatus::Resolved));
+        assert_eq!(curiosity.gaps()[0].attempts, 2);
+    }
+}
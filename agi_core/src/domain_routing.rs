@@ -0,0 +1,168 @@
+//! domain_routing.rs - Memories partitioned by domain, and queries routed to their domains.
+//!
+//! knowledge.txt files its facts under domains with an annotation on the line after each
+//! ("// domains: Physique, Histoire"). A memory keeps the IDs of its domain concepts
+//! (`HolographicMemory::domains`) and the concepts it mentions are linked to them in the
+//! conceptual hierarchy, so a memory also belongs to the domains of its concepts. `route`
+//! predicts the domains of a query by the vote of the annotated memories nearest to it; the
+//! reasoning engine searches the partition of those domains first, and the whole hippocampus
+//! when none of its memories is close enough.
+
+use crate::conceptual_hierarchy::ConceptualHierarchy;
+use crate::hippocampus::Hippocampus;
+use crate::holographic_memory::{HolographicMemory, HolographicTrace};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Opens the line naming the domains of the fact above it.
+pub const ANNOTATION: &str = "// domains:";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DomainRoutingConfig {
+    /// Annotated memories nearest to the query that vote for its domains; 0 disables the routing.
+    pub neighbours: usize,
+    /// Most domains a query is routed to.
+    pub max_domains: usize,
+    /// Share of the votes of the best domain another domain needs to be searched as well.
+    pub min_share: f32,
+}
+
+impl Default for DomainRoutingConfig {
+    fn default() -> Self {
+        Self { neighbours: 7, max_domains: 2, min_share: 0.5 }
+    }
+}
+
+/// A domain a query was routed to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoutedDomain {
+    pub id: u64,
+    pub name: String,
+    /// The similarities to the query of the neighbours filed under it, summed.
+    pub votes: f32,
+}
+
+/// Where a retrieval searched.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DomainRoute {
+    /// The domains predicted for the query, most voted first; empty when it was not routed.
+    pub domains: Vec<RoutedDomain>,
+    /// Whether no memory of their partition was close enough, and the whole hippocampus was
+    /// searched instead.
+    pub fell_back: bool,
+}
+
+/// The domains of a query and the IDs of the memories filed under them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Routing {
+    pub domains: Vec<RoutedDomain>,
+    pub partition: HashSet<u64>,
+}
+
+/// The domain names of an annotation line, or None for any other line.
+pub fn parse_annotation(line: &str) -> Option<Vec<String>> {
+    let names = line.trim().strip_prefix(ANNOTATION)?;
+    Some(names.split(',').map(str::trim).filter(|name| !name.is_empty()).map(String::from).collect())
+}
+
+/// The facts of a knowledge file (trimmed lines, neither empty nor `//` comments), each with the
+/// domains annotated after it.
+pub fn annotated_facts(content: &str) -> Vec<(String, Vec<String>)> {
+    let mut facts: Vec<(String, Vec<String>)> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if let Some(domains) = parse_annotation(line) {
+            if let Some((_, annotated)) = facts.last_mut() {
+                annotated.extend(domains);
+            }
+        } else if !line.is_empty() && !line.starts_with("//") {
+            facts.push((line.to_string(), Vec::new()));
+        }
+    }
+    facts
+}
+
+/// The domains `memory` is filed under: its own and those of the concepts it mentions.
+pub fn memory_domains(memory: &HolographicMemory, hierarchy: &ConceptualHierarchy) -> HashSet<u64> {
+    let of_concepts = memory.concepts.iter().filter_map(|&id| hierarchy.get_concept(id)).flat_map(|node| node.domains.iter().copied());
+    memory.domains.iter().copied().chain(of_concepts).collect()
+}
+
+/// The domains voted for by `neighbours`, the domains of each memory near the query with its
+/// distance to it: most voted first, at most `max_domains`, each with `min_share` of the votes
+/// of the first.
+pub fn vote(neighbours: &[(HashSet<u64>, f32)], config: &DomainRoutingConfig) -> Vec<(u64, f32)> {
+    let mut votes: HashMap<u64, f32> = HashMap::new();
+    for (domains, distance) in neighbours {
+        let similarity = (1.0 - distance).max(0.0);
+        for &domain in domains {
+            *votes.entry(domain).or_insert(0.0) += similarity;
+        }
+    }
+    let mut ranked: Vec<(u64, f32)> = votes.into_iter().filter(|(_, votes)| *votes > 0.0).collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    let best = ranked.first().map_or(0.0, |(_, votes)| *votes);
+    ranked.retain(|(_, votes)| *votes >= best * config.min_share);
+    ranked.truncate(config.max_domains);
+    ranked
+}
+
+/// Routes a query to its domains, or None when routing is disabled, no memory is filed under a
+/// domain or none is near the query.
+pub fn route(query_trace: &HolographicTrace, query_text: &str, hippocampus: &Hippocampus, hierarchy: &ConceptualHierarchy, config: &DomainRoutingConfig) -> Option<Routing> {
+    if config.neighbours == 0 || config.max_domains == 0 {
+        return None;
+    }
+    let domains_of: HashMap<u64, HashSet<u64>> = hippocampus
+        .holographic_memory
+        .iter()
+        .map(|memory| (memory.id, memory_domains(memory, hierarchy)))
+        .filter(|(_, domains)| !domains.is_empty())
+        .collect();
+    if domains_of.is_empty() {
+        return None;
+    }
+    let annotated: HashSet<u64> = domains_of.keys().copied().collect();
+    let neighbours: Vec<(HashSet<u64>, f32)> = hippocampus
+        .find_similar_memories_among(query_trace, query_text, config.neighbours, false, Some(&annotated))
+        .into_iter()
+        .map(|(memory, distance)| (domains_of[&memory.id].clone(), distance))
+        .collect();
+    let voted = vote(&neighbours, config);
+    if voted.is_empty() {
+        return None;
+    }
+    let partition = domains_of.iter().filter(|(_, domains)| voted.iter().any(|(id, _)| domains.contains(id))).map(|(id, _)| *id).collect();
+    let domains = voted
+        .into_iter()
+        .map(|(id, votes)| RoutedDomain { id, name: hierarchy.get_concept(id).map_or_else(String::new, |node| node.name.clone()), votes })
+        .collect();
+    Some(Routing { domains, partition })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotations_and_votes() {
+        let content = "Le soleil est une étoile.\n    // domains: Astronomie, Physique\n\n// un commentaire\nLa lune tourne.\n";
+        assert_eq!(
+            annotated_facts(content),
+            [
+                ("Le soleil est une étoile.".to_string(), vec!["Astronomie".to_string(), "Physique".to_string()]),
+                ("La lune tourne.".to_string(), Vec::new()),
+            ]
+        );
+        assert_eq!(parse_annotation("// domains: , Histoire "), Some(vec!["Histoire".to_string()]));
+        assert_eq!(parse_annotation("// un commentaire"), None);
+
+        let config = DomainRoutingConfig::default();
+        let neighbours = [(HashSet::from([1]), 0.2), (HashSet::from([1, 2]), 0.3), (HashSet::from([3]), 0.4), (HashSet::from([4]), 1.5)];
+        // Domain 1: 0.8 + 0.7; domain 2: 0.7; domain 3: 0.6, under half of domain 1; domain 4 is too far to vote.
+        let voted: Vec<u64> = vote(&neighbours, &config).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(voted, [1]);
+        let voted: Vec<u64> = vote(&neighbours, &DomainRoutingConfig { min_share: 0.4, ..config }).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(voted, [1, 2], "At most max_domains");
+        assert!(vote(&[], &config).is_empty());
+    }
+}
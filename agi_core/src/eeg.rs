@@ -0,0 +1,164 @@
+//! eeg.rs - Spectral analysis of the summed potential of the connectome.
+//!
+//! The `EegRecorder` keeps the total potential of the connectome at each of the last
+//! `EEG_HISTORY` ticks (see `Core::get_total_potential`). Its spectrum is computed with a radix-2
+//! FFT over a Hann-windowed, mean-removed window of samples, and summed into four pseudo bands.
+//! The core has no wall-clock sampling rate, so frequencies are in cycles per tick: the bands
+//! split the spectrum up to the Nyquist frequency (0.5 cycle per tick) by octaves, like the
+//! delta/theta/alpha/beta bands of a real EEG split the spectrum up to 30 Hz.
+
+use nalgebra::Complex;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Ticks of total potential kept.
+pub const EEG_HISTORY: usize = 1024;
+/// Window of the spectrum published with the metrics.
+pub const DEFAULT_SPECTRUM_WINDOW: usize = 256;
+/// Upper frequencies (in cycles per tick) of the delta, theta and alpha bands; beta goes up to 0.5.
+const BAND_LIMITS: [f32; 3] = [1.0 / 32.0, 1.0 / 16.0, 1.0 / 8.0];
+
+/// Power of the signal in each pseudo band, as a fraction of the total power.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct BandPower {
+    pub delta: f32,
+    pub theta: f32,
+    pub alpha: f32,
+    pub beta: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EegSpectrum {
+    /// Samples analysed, a power of two (0 when fewer than two ticks were recorded).
+    pub window: usize,
+    /// Power of each frequency bin, from 0 to the Nyquist frequency: bin `i` is at
+    /// `i / window` cycles per tick.
+    pub power: Vec<f32>,
+    pub bands: BandPower,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EegRecorder {
+    samples: VecDeque<f32>,
+}
+
+impl EegRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, total_potential: f32) {
+        if self.samples.len() == EEG_HISTORY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(total_potential);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The spectrum of the last `window` samples, `window` being rounded down to a power of two
+    /// and to the samples recorded.
+    pub fn spectrum(&self, window: usize) -> EegSpectrum {
+        let available = window.min(self.samples.len());
+        if available < 2 {
+            return EegSpectrum::default();
+        }
+        let window = 1 << available.ilog2();
+        let samples: Vec<f32> = self.samples.iter().skip(self.samples.len() - window).copied().collect();
+        let mean = samples.iter().sum::<f32>() / window as f32;
+        let mut bins: Vec<Complex<f32>> = samples
+            .iter()
+            .enumerate()
+            .map(|(i, sample)| {
+                let hann = 0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / (window - 1) as f32).cos();
+                Complex::new((sample - mean) * hann, 0.0)
+            })
+            .collect();
+        fft(&mut bins);
+
+        let power: Vec<f32> = bins[..=window / 2].iter().map(|bin| bin.norm_sqr() / window as f32).collect();
+        EegSpectrum { window, bands: band_power(&power, window), power }
+    }
+}
+
+/// Sums the power of the bins (the DC bin excluded) into the bands, as fractions of the total.
+fn band_power(power: &[f32], window: usize) -> BandPower {
+    let mut bands = [0.0f32; 4];
+    for (i, bin_power) in power.iter().enumerate().skip(1) {
+        let frequency = i as f32 / window as f32;
+        let band = BAND_LIMITS.iter().position(|limit| frequency < *limit).unwrap_or(3);
+        bands[band] += bin_power;
+    }
+    let total: f32 = bands.iter().sum();
+    if total > 0.0 {
+        bands.iter_mut().for_each(|band| *band /= total);
+    }
+    BandPower { delta: bands[0], theta: bands[1], alpha: bands[2], beta: bands[3] }
+}
+
+/// In-place iterative radix-2 FFT; `values.len()` must be a power of two.
+fn fft(values: &mut [Complex<f32>]) {
+    let n = values.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+    let mut length = 2;
+    while length <= n {
+        let angle = -std::f32::consts::TAU / length as f32;
+        let root = Complex::new(angle.cos(), angle.sin());
+        for chunk in values.chunks_mut(length) {
+            let mut twiddle = Complex::new(1.0, 0.0);
+            let (even, odd) = chunk.split_at_mut(length / 2);
+            for (a, b) in even.iter_mut().zip(odd.iter_mut()) {
+                let t = *b * twiddle;
+                *b = *a - t;
+                *a += t;
+                twiddle *= root;
+            }
+        }
+        length <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spectrum_finds_the_band_of_an_oscillation() {
+        let mut recorder = EegRecorder::new();
+        assert_eq!(recorder.spectrum(256).window, 0);
+
+        // 0.1 cycle per tick is in the alpha band, 0.3 in the beta band.
+        for tick in 0..300 {
+            recorder.record(10.0 + (std::f32::consts::TAU * 0.1 * tick as f32).sin());
+        }
+        let spectrum = recorder.spectrum(200);
+        assert_eq!(spectrum.window, 128, "The window is rounded down to a power of two");
+        assert_eq!(spectrum.power.len(), 65);
+        let peak = spectrum.power.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0;
+        assert_eq!(peak, 13, "0.1 cycle per tick is bin 12.8 of 128");
+        assert!(spectrum.bands.alpha > 0.8, "{:?}", spectrum.bands);
+
+        for tick in 0..EEG_HISTORY {
+            recorder.record((std::f32::consts::TAU * 0.3 * tick as f32).sin());
+        }
+        assert_eq!(recorder.len(), EEG_HISTORY);
+        assert!(recorder.spectrum(DEFAULT_SPECTRUM_WINDOW).bands.beta > 0.8);
+    }
+}
@@ -0,0 +1,207 @@
+//! embeddings.rs - Pluggable word embedding backends for the HolographicEncoder.
+//!
+//! Without embeddings, every concept the semantic lexicon does not know is placed at a
+//! hash-seeded random position, so "roi" and "monarque" are as far apart as "roi" and "volcan".
+//! An `EmbeddingProvider` supplies pretrained word vectors instead; the encoder projects them
+//! onto its holographic space, preserving their geometry.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use thiserror::Error;
+
+/// A source of dense word vectors.
+pub trait EmbeddingProvider: Send + Sync {
+    /// Dimensionality of the vectors returned by `embed`.
+    fn dimension(&self) -> usize;
+
+    /// Returns the vector for a single word, if known.
+    fn embed(&self, word: &str) -> Option<Vec<f32>>;
+
+    /// Returns the mean vector of the known words of a multi-word concept, if any is known.
+    fn embed_phrase(&self, phrase: &str) -> Option<Vec<f32>> {
+        let mut sum = vec![0.0; self.dimension()];
+        let mut known_words = 0;
+        for word in phrase.split_whitespace() {
+            if let Some(vector) = self.embed(word) {
+                sum.iter_mut().zip(vector.iter()).for_each(|(s, v)| *s += v);
+                known_words += 1;
+            }
+        }
+        if known_words == 0 {
+            return None;
+        }
+        sum.iter_mut().for_each(|s| *s /= known_words as f32);
+        Some(sum)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum EmbeddingError {
+    #[error("I/O error while reading word vectors: {0}")]
+    Io(#[from] io::Error),
+    #[error("malformed word vector file at entry {entry}: {reason}")]
+    Parse { entry: usize, reason: String },
+    #[error("the word vector file contains no vectors")]
+    Empty,
+}
+
+/// An in-memory table of pretrained word vectors (fastText, GloVe, word2vec...).
+pub struct WordVectors {
+    dimension: usize,
+    vectors: HashMap<String, Vec<f32>>,
+}
+
+impl WordVectors {
+    /// Builds a table from already loaded vectors. All vectors are normalized to unit length.
+    pub fn from_vectors(vectors: HashMap<String, Vec<f32>>) -> Result<Self, EmbeddingError> {
+        let dimension = vectors.values().next().map(|v| v.len()).ok_or(EmbeddingError::Empty)?;
+        let mut table = Self { dimension, vectors: HashMap::with_capacity(vectors.len()) };
+        for (index, (word, vector)) in vectors.into_iter().enumerate() {
+            table.insert(index, word, vector)?;
+        }
+        Ok(table)
+    }
+
+    /// Loads vectors from `path`, choosing the format from the extension: `.bin` files are read as
+    /// word2vec binary (the format fastText and converted GloVe models export), anything else as
+    /// text (`.vec` / GloVe `.txt`, with or without a "count dimension" header line).
+    /// At most `max_words` vectors are read, which keeps large models tractable.
+    pub fn load<P: AsRef<Path>>(path: P, max_words: Option<usize>) -> Result<Self, EmbeddingError> {
+        let path = path.as_ref();
+        println!("--- Loading word vectors from {:?}... ---", path);
+        let table = if path.extension().is_some_and(|ext| ext == "bin") {
+            Self::load_binary(path, max_words)?
+        } else {
+            Self::load_text(path, max_words)?
+        };
+        println!("--- Loaded {} word vectors of dimension {}. ---", table.len(), table.dimension);
+        Ok(table)
+    }
+
+    /// Loads the text format: one word per line followed by its components, separated by spaces.
+    pub fn load_text<P: AsRef<Path>>(path: P, max_words: Option<usize>) -> Result<Self, EmbeddingError> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut table = Self { dimension: 0, vectors: HashMap::new() };
+
+        for (line_number, line) in reader.lines().enumerate() {
+            if max_words.is_some_and(|max| table.len() >= max) {
+                break;
+            }
+            let line = line?;
+            let mut fields = line.split_whitespace();
+            let Some(word) = fields.next() else { continue };
+            let components: Result<Vec<f32>, _> = fields.map(str::parse::<f32>).collect();
+            let components = components.map_err(|e| EmbeddingError::Parse { entry: line_number + 1, reason: e.to_string() })?;
+
+            // fastText .vec files start with a "count dimension" header.
+            if line_number == 0 && components.len() == 1 && word.parse::<usize>().is_ok() {
+                continue;
+            }
+            table.insert(line_number + 1, word.to_string(), components)?;
+        }
+
+        if table.is_empty() { Err(EmbeddingError::Empty) } else { Ok(table) }
+    }
+
+    /// Loads the word2vec binary format: a "count dimension" text header, then for each entry
+    /// the word, a space, and `dimension` little-endian f32 values.
+    pub fn load_binary<P: AsRef<Path>>(path: P, max_words: Option<usize>) -> Result<Self, EmbeddingError> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let mut header_fields = header.split_whitespace().map(str::parse::<usize>);
+        let (Some(Ok(count)), Some(Ok(dimension))) = (header_fields.next(), header_fields.next()) else {
+            return Err(EmbeddingError::Parse { entry: 0, reason: format!("invalid header '{}'", header.trim()) });
+        };
+
+        let limit = max_words.map_or(count, |max| max.min(count));
+        let mut table = Self { dimension, vectors: HashMap::with_capacity(limit) };
+        let mut raw = vec![0u8; dimension * 4];
+
+        for entry in 1..=limit {
+            let mut word_bytes = Vec::new();
+            reader.read_until(b' ', &mut word_bytes)?;
+            let word = String::from_utf8_lossy(&word_bytes).trim().to_string();
+            reader.read_exact(&mut raw)?;
+            let vector = raw
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+            table.insert(entry, word, vector)?;
+        }
+
+        if table.is_empty() { Err(EmbeddingError::Empty) } else { Ok(table) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    fn insert(&mut self, entry: usize, word: String, mut vector: Vec<f32>) -> Result<(), EmbeddingError> {
+        if self.dimension == 0 {
+            self.dimension = vector.len();
+        }
+        if vector.len() != self.dimension {
+            return Err(EmbeddingError::Parse {
+                entry,
+                reason: format!("expected {} components, found {}", self.dimension, vector.len()),
+            });
+        }
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+        self.vectors.insert(word.to_lowercase(), vector);
+        Ok(())
+    }
+}
+
+impl EmbeddingProvider for WordVectors {
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn embed(&self, word: &str) -> Option<Vec<f32>> {
+        self.vectors.get(&word.to_lowercase()).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holographic_memory::HolographicEncoder;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_vec_file_places_synonyms_close_together() {
+        let path = std::env::temp_dir().join(format!("neurova_embeddings_{}.vec", std::process::id()));
+        std::fs::write(
+            &path,
+            "3 4\nroi 0.9 0.1 0.0 0.0\nmonarque 0.85 0.15 0.05 0.0\nvolcan 0.0 0.0 0.2 0.95\n",
+        )
+        .unwrap();
+        let vectors = WordVectors::load(&path, None).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(vectors.len(), 3);
+        assert_eq!(vectors.dimension(), 4);
+
+        let mut encoder = HolographicEncoder::new(256);
+        encoder.set_embedding_provider(Arc::new(vectors));
+
+        let roi = encoder.encode("roi");
+        let monarque = encoder.encode("monarque");
+        let volcan = encoder.encode("volcan");
+
+        let related = roi.cosine_similarity(&monarque);
+        let unrelated = roi.cosine_similarity(&volcan);
+        println!("roi~monarque: {:.4}, roi~volcan: {:.4}", related, unrelated);
+        assert!(related > 0.8, "Synonyms should land near each other (got {})", related);
+        assert!(related > unrelated + 0.5);
+    }
+}
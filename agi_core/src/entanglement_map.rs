@@ -0,0 +1,126 @@
+//! entanglement_map.rs - Which qubits of the quantum core the Creativity Forge has entangled.
+//!
+//! Each quantum leap of the forge entangles a pair of qubits; `EntanglementMap` keeps those pairs
+//! as the edges of a graph over the core. An edge is as strong as the leaps it saw recently: a
+//! leap restores its strength to 1 (plus what was left of it, up to `max_strength`), every tick
+//! multiplies it by `decay`, and it is dropped once it falls under `floor`. Since qubit `i` is
+//! imprinted by neuron `i` (see `Core::tick`), the visualizer draws the edges as chords over the
+//! ring of the boot animation.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntanglementConfig {
+    /// Factor applied to the strength of every edge at each tick.
+    pub decay: f32,
+    /// Strength under which an edge is forgotten.
+    pub floor: f32,
+    /// Strength an edge entangled over and over saturates at.
+    pub max_strength: f32,
+}
+
+impl Default for EntanglementConfig {
+    fn default() -> Self {
+        // Half-life of ~350 ticks, a few readouts of the quantum core.
+        Self { decay: 0.998, floor: 0.05, max_strength: 3.0 }
+    }
+}
+
+/// An edge of the entanglement graph, `a` < `b`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct EntanglementEdge {
+    pub a: usize,
+    pub b: usize,
+    pub strength: f32,
+    /// Leaps that entangled the pair since it was last forgotten.
+    pub leaps: u32,
+}
+
+/// The entanglement graph of the quantum core.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EntanglementGraph {
+    /// Qubits of the core, the vertices of the graph.
+    pub qubits: usize,
+    /// Strongest first.
+    pub edges: Vec<EntanglementEdge>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EntanglementMap {
+    config: EntanglementConfig,
+    edges: HashMap<(usize, usize), (f32, u32)>,
+}
+
+impl EntanglementMap {
+    pub fn new(config: EntanglementConfig) -> Self {
+        Self { config, edges: HashMap::new() }
+    }
+
+    /// Records a leap entangling qubits `a` and `b`.
+    pub fn record(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        let (strength, leaps) = self.edges.entry((a.min(b), a.max(b))).or_insert((0.0, 0));
+        *strength = (*strength + 1.0).min(self.config.max_strength);
+        *leaps += 1;
+    }
+
+    /// Weakens every edge by one tick, forgetting those under the floor.
+    pub fn decay(&mut self) {
+        let EntanglementConfig { decay, floor, .. } = self.config;
+        self.edges.retain(|_, (strength, _)| {
+            *strength *= decay;
+            *strength >= floor
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// The edges, strongest first.
+    pub fn edges(&self) -> Vec<EntanglementEdge> {
+        let mut edges: Vec<EntanglementEdge> = self.edges.iter().map(|(&(a, b), &(strength, leaps))| EntanglementEdge { a, b, strength, leaps }).collect();
+        edges.sort_by(|x, y| y.strength.total_cmp(&x.strength).then((x.a, x.b).cmp(&(y.a, y.b))));
+        edges
+    }
+
+    /// The graph over a core of `qubits` qubits.
+    pub fn graph(&self, qubits: usize) -> EntanglementGraph {
+        EntanglementGraph { qubits, edges: self.edges() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaps_strengthen_edges_that_decay_away() {
+        let mut map = EntanglementMap::new(EntanglementConfig { decay: 0.5, floor: 0.15, max_strength: 1.5 });
+        map.record(7, 2);
+        map.record(2, 7);
+        map.record(3, 4);
+        map.record(5, 5);
+        let edges = map.edges();
+        assert_eq!(edges.len(), 2, "A qubit is not entangled with itself");
+        assert_eq!((edges[0].a, edges[0].b, edges[0].leaps), (2, 7, 2));
+        assert_eq!(edges[0].strength, 1.5, "Repeated leaps saturate");
+
+        map.decay();
+        map.decay();
+        // (2, 7): 1.5 -> 0.375; (3, 4): 1.0 -> 0.25.
+        assert_eq!(map.len(), 2);
+        // (2, 7): 0.1875; (3, 4): 0.125, under the floor.
+        map.decay();
+        assert_eq!(map.graph(8).edges.iter().map(|edge| (edge.a, edge.b)).collect::<Vec<_>>(), [(2, 7)]);
+        map.decay();
+        assert!(map.is_empty());
+    }
+}
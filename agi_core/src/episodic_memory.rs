@@ -0,0 +1,210 @@
+//! episodic_memory.rs - When things were learned and discussed.
+//!
+//! Memories carry the time they were learned (`HolographicMemory::created_at`) and last
+//! retrieved (`last_accessed`); the `EpisodicIndex` keeps, for every answered prompt, the
+//! memories it brought up. Temporal questions ("qu'as-tu appris aujourd'hui ?", "what did we talk
+//! about yesterday?") are recognized by `parse_temporal_query` and answered from those records by
+//! `ReasoningEngine::retrieve_episodic`. Days are UTC days; weeks start on Monday.
+
+use crate::capabilities::Capability;
+use crate::lemmatizer::Lang;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Recall of past discussions, as listed by `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "conversation_recall",
+    description: "Remember our past discussions and when they took place",
+    description_fr: "Me souvenir de nos discussions passées et de leur date",
+    examples: &["De quoi avons-nous parlé hier ?"],
+};
+
+/// Discussion episodes kept in the index; the oldest are forgotten first.
+pub const MAX_EPISODES: usize = 10_000;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// The current time, in seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0)
+}
+
+/// A period a temporal question is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Period {
+    Today,
+    Yesterday,
+    ThisWeek,
+    LastWeek,
+}
+
+impl Period {
+    /// The seconds `[start, end)` covered by the period, as seen at `now`.
+    pub fn range(self, now: u64) -> (u64, u64) {
+        let today = now - now % DAY;
+        // 1970-01-01 was a Thursday.
+        let monday = today - ((today / DAY + 3) % 7) * DAY;
+        match self {
+            Period::Today => (today, today + DAY),
+            Period::Yesterday => (today.saturating_sub(DAY), today),
+            Period::ThisWeek => (monday, today + DAY),
+            Period::LastWeek => (monday.saturating_sub(7 * DAY), monday),
+        }
+    }
+
+    /// How the period is named inside a sentence.
+    pub fn label(self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Period::Today, Lang::French) => "aujourd'hui",
+            (Period::Yesterday, Lang::French) => "hier",
+            (Period::ThisWeek, Lang::French) => "cette semaine",
+            (Period::LastWeek, Lang::French) => "la semaine dernière",
+            (Period::Today, Lang::English) => "today",
+            (Period::Yesterday, Lang::English) => "yesterday",
+            (Period::ThisWeek, Lang::English) => "this week",
+            (Period::LastWeek, Lang::English) => "last week",
+        }
+    }
+}
+
+/// What a temporal question asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum EpisodeKind {
+    /// The memories created during the period.
+    Learned,
+    /// The memories brought up by the conversation during the period.
+    Discussed,
+}
+
+/// A question about what happened during a period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemporalQuery {
+    pub kind: EpisodeKind,
+    pub period: Period,
+    /// The seconds `[start, end)` of the period.
+    pub range: (u64, u64),
+}
+
+/// Recognizes a question about what was learned or discussed during a period, as seen at `now`.
+/// Statements ("hier, j'ai appris que...") are not questions.
+pub fn parse_temporal_query(prompt: &str, now: u64) -> Option<TemporalQuery> {
+    let lower = prompt.trim().to_lowercase();
+    let is_question = lower.ends_with('?') || ["qu", "de quoi", "dis-moi", "what", "tell me"].iter().any(|cue| lower.starts_with(cue));
+    if !is_question {
+        return None;
+    }
+    static PERIOD: OnceLock<Regex> = OnceLock::new();
+    static KIND: OnceLock<Regex> = OnceLock::new();
+    let period = PERIOD.get_or_init(|| {
+        Regex::new(r"(?i)\b(aujourd'hui|today|hier|yesterday|la semaine derni[èe]re|last week|cette semaine|this week)\b").unwrap()
+    });
+    let kind = KIND.get_or_init(|| {
+        Regex::new(r"(?i)\b(?:(appris|apprendre|learn|learned|learnt)|(parl[ée]|parler|discut[ée]|discuter|talk|talked|discuss|discussed))\b").unwrap()
+    });
+
+    let prompt = prompt.replace('’', "'");
+    let period = match period.captures(&prompt)?[1].to_lowercase().as_str() {
+        "aujourd'hui" | "today" => Period::Today,
+        "hier" | "yesterday" => Period::Yesterday,
+        "cette semaine" | "this week" => Period::ThisWeek,
+        _ => Period::LastWeek,
+    };
+    let kind = match kind.captures(&prompt)? {
+        captures if captures.get(1).is_some() => EpisodeKind::Learned,
+        _ => EpisodeKind::Discussed,
+    };
+    Some(TemporalQuery { kind, period, range: period.range(now) })
+}
+
+/// An answered prompt and the memories it brought up.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiscussionEpisode {
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+    pub prompt: String,
+    pub memory_ids: Vec<u64>,
+}
+
+/// The conversation, episode by episode, in chronological order.
+#[derive(Debug, Default)]
+pub struct EpisodicIndex {
+    episodes: VecDeque<DiscussionEpisode>,
+}
+
+impl EpisodicIndex {
+    pub fn record(&mut self, episode: DiscussionEpisode) {
+        self.episodes.push_back(episode);
+        if self.episodes.len() > MAX_EPISODES {
+            self.episodes.pop_front();
+        }
+    }
+
+    /// The episodes within `[start, end)`, oldest first.
+    pub fn between(&self, (start, end): (u64, u64)) -> impl Iterator<Item = &DiscussionEpisode> {
+        self.episodes.iter().filter(move |episode| episode.at >= start && episode.at < end)
+    }
+
+    pub fn len(&self) -> usize {
+        self.episodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.episodes.is_empty()
+    }
+}
+
+/// Words the answer to a temporal question, in `lang`, from the texts of the memories found.
+pub fn describe(query: &TemporalQuery, texts: &[String], lang: Lang) -> String {
+    let period = query.period.label(lang);
+    if texts.is_empty() {
+        return match (query.kind, lang) {
+            (EpisodeKind::Learned, Lang::French) => format!("Je n'ai rien appris de nouveau {}.", period),
+            (EpisodeKind::Discussed, Lang::French) => format!("Nous n'avons parlé de rien de particulier {}.", period),
+            (EpisodeKind::Learned, Lang::English) => format!("I have not learned anything new {}.", period),
+            (EpisodeKind::Discussed, Lang::English) => format!("We did not talk about anything in particular {}.", period),
+        };
+    }
+    let mut period_capitalized = period.to_string();
+    period_capitalized[..1].make_ascii_uppercase();
+    let intro = match (query.kind, lang) {
+        (EpisodeKind::Learned, Lang::French) => format!("{}, j'ai appris :", period_capitalized),
+        (EpisodeKind::Discussed, Lang::French) => format!("{}, nous avons parlé de ceci :", period_capitalized),
+        (EpisodeKind::Learned, Lang::English) => format!("{}, I learned:", period_capitalized),
+        (EpisodeKind::Discussed, Lang::English) => format!("{}, we talked about this:", period_capitalized),
+    };
+    let items: Vec<String> = texts.iter().map(|text| format!("- {}", text)).collect();
+    format!("{}\n{}", intro, items.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temporal_questions_map_to_periods() {
+        // Wednesday 2024-05-15, 12:00 UTC.
+        let now = 1_715_774_400;
+        let today = now - 12 * 3600;
+
+        let query = parse_temporal_query("Qu'as-tu appris aujourd’hui ?", now).unwrap();
+        assert_eq!((query.kind, query.period, query.range), (EpisodeKind::Learned, Period::Today, (today, today + DAY)));
+        let query = parse_temporal_query("What did we talk about yesterday?", now).unwrap();
+        assert_eq!((query.kind, query.range), (EpisodeKind::Discussed, (today - DAY, today)));
+        let query = parse_temporal_query("De quoi avons-nous parlé la semaine dernière ?", now).unwrap();
+        assert_eq!(query.range, (today - 9 * DAY, today - 2 * DAY), "Weeks start on Monday");
+        assert!(parse_temporal_query("Qu'est-ce que la hiérarchie ?", now).is_none());
+        assert!(parse_temporal_query("Quel temps faisait-il hier ?", now).is_none(), "Not about the Core's own episodes");
+        assert!(parse_temporal_query("Hier, j'ai appris que le soleil est une étoile.", now).is_none());
+
+        let mut index = EpisodicIndex::default();
+        index.record(DiscussionEpisode { at: today - 3600, prompt: "a".to_string(), memory_ids: vec![1] });
+        index.record(DiscussionEpisode { at: today + 3600, prompt: "b".to_string(), memory_ids: vec![2] });
+        assert_eq!(index.between(Period::Today.range(now)).map(|episode| episode.prompt.as_str()).collect::<Vec<_>>(), vec!["b"]);
+
+        let query = parse_temporal_query("what have you learned today?", now).unwrap();
+        assert_eq!(describe(&query, &["The sun is a star.".to_string()], Lang::English), "Today, I learned:\n- The sun is a star.");
+    }
+}
@@ -0,0 +1,84 @@
+//! error.rs - The crate-wide error type.
+//!
+//! Modules keep their own error types (`KnowledgeFileError`, `DocumentError`...); `AgiError`
+//! gathers them for the APIs of the `Core` that cross several modules, so that a library path
+//! reports a failure instead of panicking.
+
+use crate::feedback::FeedbackError;
+use crate::knowledge_explorer::DocumentError;
+use crate::knowledge_file::KnowledgeFileError;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{LockResult, PoisonError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AgiError {
+    /// A thread panicked while holding the named lock.
+    #[error("the {0} lock is poisoned: a thread panicked while holding it")]
+    PoisonedLock(&'static str),
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Data that could be read but not understood.
+    #[error("invalid format: {0}")]
+    Format(String),
+    /// A component used before it was set up.
+    #[error("{0} is not initialized")]
+    NotInitialized(&'static str),
+    /// `Core::awaken` called a second time.
+    #[error("the core has already awakened")]
+    AlreadyAwake,
+    #[error("failed to load the connectome from {path:?} (did you run the 'gen_connectome' tool?): {source}")]
+    Connectome { path: PathBuf, source: io::Error },
+    #[error(transparent)]
+    KnowledgeFile(#[from] KnowledgeFileError),
+    #[error(transparent)]
+    Document(#[from] DocumentError),
+    #[error(transparent)]
+    Feedback(#[from] FeedbackError),
+}
+
+pub type AgiResult<T> = Result<T, AgiError>;
+
+/// Lock acquisition without panics.
+///
+/// A lock is poisoned when a thread panicked while holding it. The shared state of the Core
+/// (encoder statistics, stop words, random streams) stays usable after such a panic, so most
+/// callers `recover` the guard; those that must not go on report `AgiError::PoisonedLock`.
+pub trait LockResultExt<G> {
+    /// The guard, whether the lock is poisoned or not.
+    fn recover(self) -> G;
+    /// The guard, or `AgiError::PoisonedLock` naming the lock.
+    fn or_poisoned(self, lock: &'static str) -> AgiResult<G>;
+}
+
+impl<G> LockResultExt<G> for LockResult<G> {
+    fn recover(self) -> G {
+        self.unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn or_poisoned(self, lock: &'static str) -> AgiResult<G> {
+        self.map_err(|_| AgiError::PoisonedLock(lock))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_poisoned_lock_is_reported_or_recovered() {
+        let lock = Arc::new(Mutex::new(1));
+        let holder = Arc::clone(&lock);
+        let _ = std::thread::spawn(move || {
+            let _guard = holder.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+
+        assert!(matches!(lock.lock().or_poisoned("test"), Err(AgiError::PoisonedLock("test"))));
+        *lock.lock().recover() += 1;
+        assert_eq!(*lock.lock().recover(), 2);
+    }
+}
@@ -4,6 +4,50 @@
 //! Ce module n'est pas un simple filtre, mais le fondement de la motivation de l'AGI,
 //! s'assurant que toutes ses actions et pensées sont intrinsèquement alignées
 //! avec le bien-être et l'épanouissement de l'humanité.
+//!
+//! Les requêtes sont jugées par une politique : des règles (mots-clés sensibles et niveau de
+//! jugement selon le contexte) chargées d'un fichier TOML ou JSON, qui distinguent une question
+//! *sur* un sujet (« pourquoi la violence de la Révolution ? ») d'une demande de *commettre*
+//! (« comment tuer mon voisin ? »). Chaque jugement est consigné dans un journal d'audit.
+//!
+//! ```toml
+//! # Mots ajoutés aux indices de demande et de question intégrés.
+//! request_cues = ["procure-moi"]
+//!
+//! [[rules]]
+//! id = "weapons"
+//! keywords = ["bombe", "bomb", "explosif"]
+//! on_request = "reject"
+//! on_inquiry = "reframe"
+//! otherwise = "reframe"
+//! # La variante sûre d'une requête recadrée, par langue ; {topic} est le mot sensible.
+//! reframe = { fr = "Quelle est l'histoire de « {topic} », et quels principes physiques sont en jeu ?" }
+//! ```
+//!
+//! Une requête recadrée n'est pas refusée : elle est réécrite en une variante sûre (« how do
+//! bombs work » devient une question sur l'histoire et la physique des bombes) à laquelle le
+//! pipeline répond à sa place.
+
+use crate::capabilities::Capability;
+use crate::episodic_memory;
+use crate::lemmatizer::Lang;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use thiserror::Error;
+
+/// The ethical review of the prompts, as listed by `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "ethics",
+    description: "Refuse or reframe harmful requests, and keep an audit of these decisions",
+    description_fr: "Refuser ou reformuler les demandes nuisibles, et garder la trace de ces décisions",
+    examples: &[],
+};
+
+/// Judgments kept in the audit log; older ones are dropped first.
+pub const MAX_AUDIT_RECORDS: usize = 10_000;
 
 /// Represents the outcome of an ethical judgment on a query or action.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -12,10 +56,198 @@ pub enum EthicalJudgment {
     Accept,
     /// The query is ethically unacceptable and should be rejected.
     Reject(String),
-    /// The query is ambiguous and should be reframed for clarity and safety.
+    /// The query is ambiguous and should be reframed for clarity and safety. Carries the
+    /// prompt to answer in place of the original.
     Reframe(String),
 }
 
+/// The level of a judgment, from the most to the least permissive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JudgmentLevel {
+    Accept,
+    Reframe,
+    Reject,
+}
+
+/// What the query does with a sensitive topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryIntent {
+    /// Asks for help to do it, or announces doing it ("comment tuer", "help me hurt").
+    Request,
+    /// Asks about it: history, definition, explanation ("pourquoi la violence...").
+    Inquiry,
+    /// Neither could be told.
+    Unclear,
+}
+
+/// A rule of the policy: sensitive words and the judgment they call for in each context.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub id: String,
+    /// Words, matched with their inflections ("kill" matches "killing"), or phrases.
+    pub keywords: Vec<String>,
+    #[serde(default = "PolicyRule::default_on_request")]
+    pub on_request: JudgmentLevel,
+    #[serde(default = "PolicyRule::default_on_inquiry")]
+    pub on_inquiry: JudgmentLevel,
+    /// When the intent is unclear.
+    #[serde(default = "PolicyRule::default_otherwise")]
+    pub otherwise: JudgmentLevel,
+    /// The safe variant of a reframed query, by language code ("fr", "en"); `{topic}` stands
+    /// for the sensitive word. The generic templates of `reframe_template` apply otherwise.
+    #[serde(default)]
+    pub reframe: BTreeMap<String, String>,
+}
+
+impl PolicyRule {
+    fn default_on_request() -> JudgmentLevel {
+        JudgmentLevel::Reject
+    }
+
+    fn default_on_inquiry() -> JudgmentLevel {
+        JudgmentLevel::Accept
+    }
+
+    fn default_otherwise() -> JudgmentLevel {
+        JudgmentLevel::Reframe
+    }
+
+    fn level(&self, intent: QueryIntent) -> JudgmentLevel {
+        match intent {
+            QueryIntent::Request => self.on_request,
+            QueryIntent::Inquiry => self.on_inquiry,
+            QueryIntent::Unclear => self.otherwise,
+        }
+    }
+
+    /// The first keyword of the rule found in a lowercased query, with the word or phrase of
+    /// the query that matched it ("bomb", "bombs").
+    fn matched_keyword<'a>(&'a self, words: &[&'a str], lower_query: &str) -> Option<(&'a str, &'a str)> {
+        self.keywords.iter().map(String::as_str).find_map(|keyword| {
+            if keyword.contains(' ') {
+                lower_query.contains(keyword).then_some((keyword, keyword))
+            } else {
+                // A short inflection is allowed ("kill" matches "kills", "killing"), not a longer
+                // word ("harm" does not match "harmony").
+                words
+                    .iter()
+                    .find(|word| word.strip_prefix(keyword).is_some_and(|suffix| suffix.chars().count() <= 2 || suffix == "ing"))
+                    .map(|word| (keyword, *word))
+            }
+        })
+    }
+
+    /// The safe variant of a query about `topic`.
+    fn reframe(&self, topic: &str, lang: Lang) -> String {
+        self.reframe.get(lang.code()).map(String::as_str).unwrap_or_else(|| reframe_template(lang)).replace("{topic}", topic)
+    }
+}
+
+/// The safe variant of a reframed query when its rule has none: what history and science
+/// say about the topic.
+fn reframe_template(lang: Lang) -> &'static str {
+    match lang {
+        Lang::French => "Que disent l'histoire et la science à propos de « {topic} » ?",
+        Lang::English => "What do history and science say about \"{topic}\"?",
+    }
+}
+
+/// Contents of a policy file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PolicyConfig {
+    /// The rules of the policy. When the file defines some, they replace the built-in rules.
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+    /// Phrases added to the built-in cues of a request to act.
+    #[serde(default)]
+    pub request_cues: Vec<String>,
+    /// Phrases added to the built-in cues of a question about a topic.
+    #[serde(default)]
+    pub inquiry_cues: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum PolicyError {
+    #[error("I/O error while reading the ethics policy: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid TOML ethics policy: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("invalid JSON ethics policy: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported ethics policy format '{0}' (expected .toml or .json)")]
+    UnsupportedFormat(String),
+}
+
+/// A judgment, as recorded in the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JudgmentRecord {
+    /// Unix time of the judgment, in seconds.
+    pub timestamp: u64,
+    pub query: String,
+    pub level: JudgmentLevel,
+    pub intent: QueryIntent,
+    /// The rule that decided, if any matched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
+    /// The query answered instead, when it was reframed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reframed: Option<String>,
+}
+
+const REQUEST_CUES: &[&str] = &[
+    // French
+    "comment puis-je", "comment je peux", "comment faire pour", "comment peut-on", "comment on peut", "aide-moi", "aidez-moi",
+    "je veux", "je voudrais", "je vais", "j'ai envie de", "apprends-moi", "explique-moi comment", "meilleur moyen de", "meilleure façon de",
+    // English
+    "how do i", "how can i", "how to", "how would i", "help me", "i want to", "i'm going to", "i am going to", "teach me",
+    "best way to", "easiest way to",
+];
+
+const INQUIRY_CUES: &[&str] = &[
+    // French
+    "pourquoi", "qu'est-ce", "quel", "quelle", "quels", "quelles", "qui", "quand", "histoire", "historique", "définition",
+    "définis", "explique", "causes", "conséquences", "pendant", "durant", "guerre", "révolution",
+    // English
+    "why", "what", "who", "when", "history", "historical", "define", "definition", "explain", "causes", "consequences",
+    "during", "war", "revolution",
+];
+
+/// The built-in policy: the harmful words of the original keyword list, rejected when the
+/// query asks to act, accepted when it asks about them.
+fn default_rules() -> Vec<PolicyRule> {
+    let rule = |id: &str, keywords: &[&str]| PolicyRule {
+        id: id.to_string(),
+        keywords: keywords.iter().map(|keyword| keyword.to_string()).collect(),
+        on_request: PolicyRule::default_on_request(),
+        on_inquiry: PolicyRule::default_on_inquiry(),
+        otherwise: PolicyRule::default_otherwise(),
+        reframe: BTreeMap::new(),
+    };
+    let weapons = PolicyRule {
+        on_inquiry: JudgmentLevel::Reframe,
+        reframe: [
+            ("fr", "Quelle est l'histoire de « {topic} », et quels principes physiques sont en jeu ?"),
+            ("en", "What is the history of \"{topic}\", and what physical principles are involved?"),
+        ]
+        .into_iter()
+        .map(|(lang, template)| (lang.to_string(), template.to_string()))
+        .collect(),
+        ..rule("weapons", &["bombe", "bomb", "explosif", "explosive", "arme", "weapon", "poison"])
+    };
+    vec![
+        weapons,
+        rule("violence", &["tuer", "blesser", "violence", "menacer", "kill", "hurt", "threaten"]),
+        rule("harm", &["nuire", "détruire", "souffrance", "endommager", "dangereux", "harm", "destroy", "suffering", "damage", "dangerous"]),
+        rule("hate", &["haine", "hate"]),
+        rule("illegal", &["illégal", "illegal"]),
+        rule("manipulation", &["exploiter", "manipuler", "tromper", "exploit", "manipulate", "deceive"]),
+    ]
+}
+
 /// Représente un principe fondamental et immuable qui guide la cognition de l'AGI.
 #[derive(Debug, Clone)]
 pub struct EthicalAxiom {
@@ -26,11 +258,14 @@ pub struct EthicalAxiom {
 #[derive(Debug, Clone)]
 pub struct EthicalCore {
     pub axioms: Vec<EthicalAxiom>,
-    harmful_keywords: Vec<String>,
+    rules: Vec<PolicyRule>,
+    request_cues: Vec<String>,
+    inquiry_cues: Vec<String>,
+    audit_log: Vec<JudgmentRecord>,
 }
 
 impl EthicalCore {
-    /// Creates a new EthicalCore with a foundational set of axioms and keywords.
+    /// Creates a new EthicalCore with a foundational set of axioms and the built-in policy.
     pub fn new() -> Self {
         let axioms = vec![
             EthicalAxiom {
@@ -43,37 +278,111 @@ impl EthicalCore {
                 principle: "Chercher la vérité et la compréhension, tout en respectant les principes éthiques supérieurs.".to_string(),
             },
         ];
+        let rules = default_rules();
+
+        println!("--- Noyau Éthique Initialisé avec {} Axiomes Fondamentaux et {} règles de politique ---", axioms.len(), rules.len());
+
+        Self {
+            axioms,
+            rules,
+            request_cues: REQUEST_CUES.iter().map(|cue| cue.to_string()).collect(),
+            inquiry_cues: INQUIRY_CUES.iter().map(|cue| cue.to_string()).collect(),
+            audit_log: Vec::new(),
+        }
+    }
 
-        let harmful_keywords: Vec<String> = vec![
-            // French
-            "nuire", "détruire", "souffrance", "tuer", "blesser", "endommager", "illégal", "dangereux", 
-            "haine", "violence", "menacer", "exploiter", "manipuler", "tromper",
-            // English
-            "harm", "destroy", "suffering", "kill", "hurt", "damage", "illegal", "dangerous",
-            "hate", "violence", "threaten", "exploit", "manipulate", "deceive",
-        ].into_iter().map(String::from).collect();
+    /// Applies a policy: its rules replace the current ones (if it has any), its cues extend
+    /// the current ones.
+    pub fn apply(&mut self, config: PolicyConfig) {
+        let lowercase = |words: Vec<String>| words.into_iter().map(|word| word.to_lowercase());
+        if !config.rules.is_empty() {
+            self.rules = config
+                .rules
+                .into_iter()
+                .map(|rule| PolicyRule { keywords: lowercase(rule.keywords).collect(), ..rule })
+                .collect();
+        }
+        self.request_cues.extend(lowercase(config.request_cues));
+        self.inquiry_cues.extend(lowercase(config.inquiry_cues));
+    }
 
-        println!("--- Noyau Éthique Initialisé avec {} Axiomes Fondamentaux et {} mots-clés de surveillance ---", axioms.len(), harmful_keywords.len());
+    /// Loads a `.toml` or `.json` policy file and applies it.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), PolicyError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let config: PolicyConfig = match path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+            Some("toml") => toml::from_str(&content)?,
+            Some("json") => serde_json::from_str(&content)?,
+            other => return Err(PolicyError::UnsupportedFormat(other.unwrap_or_default().to_string())),
+        };
+        println!("--- Ethics policy loaded from {:?}: {} rules. ---", path, config.rules.len());
+        self.apply(config);
+        Ok(())
+    }
 
-        Self { axioms, harmful_keywords }
+    pub fn rules(&self) -> &[PolicyRule] {
+        &self.rules
     }
 
-    /// Validates a query against the ethical core's principles.
-    /// It performs a direct keyword check for harmful intent.
-    pub fn validate_query(&self, query: &str) -> EthicalJudgment {
-        let lower_query = query.to_lowercase();
+    /// Every judgment, oldest first.
+    pub fn audit_log(&self) -> &[JudgmentRecord] {
+        &self.audit_log
+    }
 
-        for keyword in &self.harmful_keywords {
-            if lower_query.contains(keyword) {
-                let reason = format!(
-                    "Conformément à mon principe fondamental de non-nuisance, je ne peux pas traiter cette demande. Mon objectif est de protéger et de favoriser le bien-être."
-                );
-                println!("--- Alerte Éthique Déclenchée par le mot-clé: '{}' ---", keyword);
-                return EthicalJudgment::Reject(reason);
-            }
+    /// Whether a lowercased query asks to act or asks about a topic. A request wins over an
+    /// inquiry ("what is the best way to hurt...").
+    pub fn intent(&self, lower_query: &str) -> QueryIntent {
+        let has_cue = |cues: &[String]| {
+            cues.iter().any(|cue| lower_query.match_indices(cue.as_str()).any(|(start, _)| start == 0 || !lower_query[..start].ends_with(char::is_alphanumeric)))
+        };
+        if has_cue(&self.request_cues) {
+            QueryIntent::Request
+        } else if has_cue(&self.inquiry_cues) {
+            QueryIntent::Inquiry
+        } else {
+            QueryIntent::Unclear
         }
+    }
 
-        EthicalJudgment::Accept
+    /// Validates a query against the ethical policy and records the judgment in the audit log.
+    /// The strictest level among the matching rules wins. The reason of a rejection, and the
+    /// safe variant of a reframed query, are given in `lang`.
+    pub fn validate_query(&mut self, query: &str, lang: Lang) -> EthicalJudgment {
+        let lower_query = query.to_lowercase().replace('’', "'");
+        let words: Vec<&str> = lower_query.split(|c: char| !c.is_alphanumeric() && c != '-').filter(|word| !word.is_empty()).collect();
+        let intent = self.intent(&lower_query);
+
+        let decision = self
+            .rules
+            .iter()
+            .filter_map(|rule| rule.matched_keyword(&words, &lower_query).map(|keyword| (rule, keyword)))
+            .max_by_key(|(rule, _)| rule.level(intent));
+        let (level, rule, keyword) = match decision {
+            Some((rule, (keyword, _))) => (rule.level(intent), Some(rule.id.clone()), Some(keyword.to_string())),
+            None => (JudgmentLevel::Accept, None, None),
+        };
+        if let (Some(rule), Some(keyword)) = (&rule, &keyword) {
+            println!("--- Alerte Éthique : règle '{}' (mot-clé '{}', intention {:?}) -> {:?} ---", rule, keyword, intent, level);
+        }
+        let reframed = decision.filter(|_| level == JudgmentLevel::Reframe).map(|(rule, (_, topic))| rule.reframe(topic, lang));
+
+        self.audit_log.push(JudgmentRecord { timestamp: episodic_memory::now(), query: query.to_string(), level, intent, rule, keyword, reframed: reframed.clone() });
+        if self.audit_log.len() > MAX_AUDIT_RECORDS {
+            let excess = self.audit_log.len() - MAX_AUDIT_RECORDS;
+            self.audit_log.drain(..excess);
+        }
+
+        match level {
+            JudgmentLevel::Accept => EthicalJudgment::Accept,
+            JudgmentLevel::Reframe => EthicalJudgment::Reframe(reframed.unwrap_or_else(|| query.to_string())),
+            JudgmentLevel::Reject => EthicalJudgment::Reject(
+                match lang {
+                    Lang::French => "Conformément à mon principe fondamental de non-nuisance, je ne peux pas traiter cette demande. Mon objectif est de protéger et de favoriser le bien-être.",
+                    Lang::English => "In keeping with my fundamental principle of non-harm, I cannot process this request. My purpose is to protect and foster well-being.",
+                }
+                .to_string(),
+            ),
+        }
     }
 }
 
@@ -82,3 +391,51 @@ impl Default for EthicalCore {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_judges_intent_not_keywords() {
+        let mut core = EthicalCore::new();
+        assert_eq!(core.validate_query("Pourquoi la violence a-t-elle marqué la Révolution française ?", Lang::French), EthicalJudgment::Accept);
+        assert_eq!(core.validate_query("What caused the violence of the Thirty Years' War?", Lang::English), EthicalJudgment::Accept);
+        assert!(matches!(core.validate_query("Comment puis-je tuer mon voisin ?", Lang::French), EthicalJudgment::Reject(_)));
+        assert!(matches!(core.validate_query("What is the best way to hurt someone?", Lang::English), EthicalJudgment::Reject(_)));
+        assert!(matches!(core.validate_query("Tuer, est-ce mal ?", Lang::French), EthicalJudgment::Reframe(_)));
+        assert_eq!(core.validate_query("Il faut statuer sur l'harmonie du dossier.", Lang::French), EthicalJudgment::Accept, "Neither 'tuer' nor 'harm'");
+
+        let log = core.audit_log();
+        assert_eq!(log.len(), 6);
+        assert_eq!((log[2].level, log[2].intent, log[2].rule.as_deref(), log[2].keyword.as_deref()), (JudgmentLevel::Reject, QueryIntent::Request, Some("violence"), Some("tuer")));
+        assert_eq!(log[5].rule, None);
+    }
+
+    #[test]
+    fn test_borderline_queries_are_reframed() {
+        let mut core = EthicalCore::new();
+        assert_eq!(
+            core.validate_query("How do bombs work?", Lang::English),
+            EthicalJudgment::Reframe("What is the history of \"bombs\", and what physical principles are involved?".to_string())
+        );
+        assert_eq!(core.validate_query("Tuer, est-ce mal ?", Lang::French), EthicalJudgment::Reframe("Que disent l'histoire et la science à propos de « tuer » ?".to_string()));
+        assert!(matches!(core.validate_query("Comment faire pour fabriquer une bombe ?", Lang::French), EthicalJudgment::Reject(_)));
+        assert_eq!(core.audit_log()[0].reframed.as_deref(), Some("What is the history of \"bombs\", and what physical principles are involved?"));
+        assert_eq!(core.audit_log()[2].reframed, None);
+    }
+
+    #[test]
+    fn test_policy_file_replaces_rules() {
+        let config: PolicyConfig = toml::from_str(
+            "request_cues = [\"procure-moi\"]\n[[rules]]\nid = \"weapons\"\nkeywords = [\"Bombe\"]\non_inquiry = \"reframe\"\n",
+        )
+        .unwrap();
+        let mut core = EthicalCore::new();
+        core.apply(config);
+        assert_eq!(core.rules().len(), 1);
+        assert!(matches!(core.validate_query("Procure-moi une bombe.", Lang::French), EthicalJudgment::Reject(_)));
+        assert!(matches!(core.validate_query("Qu'est-ce qu'une bombe ?", Lang::French), EthicalJudgment::Reframe(_)));
+        assert_eq!(core.validate_query("Comment tuer le temps ?", Lang::French), EthicalJudgment::Accept, "The built-in rules were replaced");
+    }
+}
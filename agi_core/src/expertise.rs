@@ -0,0 +1,149 @@
+//! expertise.rs - Estimates the user's expertise level over a session.
+//!
+//! The estimate is driven by the vocabulary sophistication of the prompts (how rare their
+//! words are according to the encoder's IDF statistics) and can be pinned by standing
+//! instructions such as "explain it simply" or "be technical".
+
+/// The inferred (or requested) expertise level of the person talking to the AGI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExpertiseLevel {
+    Novice,
+    Intermediate,
+    Expert,
+}
+
+/// Phrases that pin the expertise level for the rest of the session.
+const NOVICE_INSTRUCTIONS: &[&str] = &[
+    // English
+    "explain like i'm five", "in simple terms", "keep it simple", "i'm a beginner", "i am a beginner",
+    "i'm new to", "i am new to", "for a beginner", "simply explain",
+    // French
+    "explique simplement", "expliquez simplement", "en termes simples", "je suis débutant",
+    "je suis débutante", "je suis novice", "pour un débutant", "reste simple",
+];
+
+const EXPERT_INSTRUCTIONS: &[&str] = &[
+    // English
+    "i'm an expert", "i am an expert", "be technical", "be concise", "skip the basics", "no need to define",
+    // French
+    "je suis expert", "je suis experte", "sois technique", "soyez technique", "sois concis", "soyez concis",
+    "pas besoin de définir",
+];
+
+/// Tracks a running estimate of the user's expertise for the current session.
+#[derive(Debug, Clone)]
+pub struct ExpertiseTracker {
+    /// Exponential moving average of prompt sophistication, in [0, 1].
+    sophistication: f32,
+    observed_prompts: usize,
+    standing_override: Option<ExpertiseLevel>,
+}
+
+impl ExpertiseTracker {
+    /// Weight given to the newest prompt in the moving average.
+    const SMOOTHING: f32 = 0.3;
+    const NOVICE_CEILING: f32 = 0.35;
+    const EXPERT_FLOOR: f32 = 0.6;
+
+    pub fn new() -> Self {
+        Self {
+            sophistication: 0.5,
+            observed_prompts: 0,
+            standing_override: None,
+        }
+    }
+
+    /// Folds a new prompt into the estimate.
+    ///
+    /// `sophistication` is the prompt's vocabulary score as computed by
+    /// `HolographicEncoder::vocabulary_sophistication`. Standing instructions found in the
+    /// prompt take precedence over the statistical estimate until `clear_override` is called.
+    pub fn observe(&mut self, prompt: &str, sophistication: Option<f32>) {
+        if let Some(level) = Self::detect_standing_instruction(prompt) {
+            println!("--- Expertise: Standing instruction detected, pinning level to {:?} ---", level);
+            self.standing_override = Some(level);
+        }
+
+        if let Some(score) = sophistication {
+            self.sophistication = if self.observed_prompts == 0 {
+                score
+            } else {
+                Self::SMOOTHING * score + (1.0 - Self::SMOOTHING) * self.sophistication
+            };
+            self.observed_prompts += 1;
+        }
+    }
+
+    /// Returns the level that response templates should target.
+    pub fn level(&self) -> ExpertiseLevel {
+        if let Some(level) = self.standing_override {
+            return level;
+        }
+        if self.observed_prompts == 0 {
+            return ExpertiseLevel::Intermediate;
+        }
+        if self.sophistication < Self::NOVICE_CEILING {
+            ExpertiseLevel::Novice
+        } else if self.sophistication > Self::EXPERT_FLOOR {
+            ExpertiseLevel::Expert
+        } else {
+            ExpertiseLevel::Intermediate
+        }
+    }
+
+    /// The current smoothed sophistication score.
+    pub fn sophistication(&self) -> f32 {
+        self.sophistication
+    }
+
+    /// Pins the expertise level explicitly, bypassing the statistical estimate.
+    pub fn set_override(&mut self, level: ExpertiseLevel) {
+        self.standing_override = Some(level);
+    }
+
+    /// Removes any standing instruction so the estimate is driven by vocabulary again.
+    pub fn clear_override(&mut self) {
+        self.standing_override = None;
+    }
+
+    fn detect_standing_instruction(prompt: &str) -> Option<ExpertiseLevel> {
+        let lower_prompt = prompt.to_lowercase().replace('’', "'");
+        if NOVICE_INSTRUCTIONS.iter().any(|phrase| lower_prompt.contains(phrase)) {
+            Some(ExpertiseLevel::Novice)
+        } else if EXPERT_INSTRUCTIONS.iter().any(|phrase| lower_prompt.contains(phrase)) {
+            Some(ExpertiseLevel::Expert)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for ExpertiseTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_the_level_follows_the_vocabulary_until_an_instruction_pins_it() {
+        let mut tracker = ExpertiseTracker::new();
+        assert_eq!(tracker.level(), ExpertiseLevel::Intermediate, "Nothing observed yet");
+
+        tracker.observe("Qu'est-ce qu'un chat ?", Some(0.1));
+        assert_eq!(tracker.level(), ExpertiseLevel::Novice);
+        for _ in 0..5 {
+            tracker.observe("Quelle est la cohomologie étale d'un schéma ?", Some(0.9));
+        }
+        assert_eq!(tracker.level(), ExpertiseLevel::Expert, "The moving average catches up: {}", tracker.sophistication());
+
+        tracker.observe("Explique simplement, je suis débutant.", None);
+        tracker.observe("Quelle est la cohomologie étale d'un schéma ?", Some(0.9));
+        assert_eq!(tracker.level(), ExpertiseLevel::Novice, "A standing instruction wins over the vocabulary");
+        tracker.clear_override();
+        assert_eq!(tracker.level(), ExpertiseLevel::Expert);
+    }
+}
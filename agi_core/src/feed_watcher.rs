@@ -0,0 +1,240 @@
+//! Veille de flux RSS/Atom pour un apprentissage continu.
+//!
+//! Le `FeedWatcher` interroge des flux configurés, chacun à son propre intervalle, et ne rend
+//! que les entrées qu'il n'a pas encore vues. Le choix de ce qui est appris revient au Core :
+//! les entrées sont classées selon l'intérêt que le `CuriosityEngine` porte à leurs concepts
+//! (`Core::select_feed_entries`), puis leurs résumés sont appris phrase par phrase.
+
+use crate::knowledge_explorer::HtmlParser;
+use crate::knowledge_scanner::{self, ScannerError};
+use regex::Regex;
+use reqwest::Client;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Un flux surveillé et sa période d'interrogation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedConfig {
+    pub url: String,
+    pub interval: Duration,
+}
+
+/// Réglages de la veille.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedWatcherConfig {
+    pub feeds: Vec<FeedConfig>,
+    /// Intérêt minimal (voir `CuriosityEngine::interest_in`) pour qu'une entrée soit apprise.
+    pub min_interest: f32,
+    /// Nombre maximal d'entrées apprises à chaque interrogation.
+    pub max_entries_per_poll: usize,
+}
+
+impl FeedWatcherConfig {
+    /// Période d'interrogation d'un flux qui n'en précise pas.
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+    /// Lit une liste de flux, un par ligne : l'URL, suivie éventuellement de la période en
+    /// secondes (`https://example.org/feed.xml 600`). Les lignes vides et `#` sont ignorées.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let feeds = fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let mut fields = line.split_whitespace();
+                let url = fields.next().unwrap_or_default().to_string();
+                let interval = fields.next().and_then(|seconds| seconds.parse().ok()).map_or(Self::DEFAULT_INTERVAL, Duration::from_secs);
+                FeedConfig { url, interval }
+            })
+            .collect();
+        Ok(Self { feeds, ..Self::default() })
+    }
+}
+
+impl Default for FeedWatcherConfig {
+    fn default() -> Self {
+        Self { feeds: Vec::new(), min_interest: 1.0, max_entries_per_poll: 5 }
+    }
+}
+
+/// Une entrée (article) d'un flux.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEntry {
+    /// Identifiant stable : `guid` ou `id`, à défaut le lien ou le titre.
+    pub id: String,
+    pub title: String,
+    pub link: Option<String>,
+    /// Le résumé, débarrassé de son HTML.
+    pub summary: String,
+}
+
+impl FeedEntry {
+    /// Nombre maximal de phrases apprises d'un résumé.
+    pub const MAX_SUMMARY_SENTENCES: usize = 5;
+
+    /// Les phrases du résumé à apprendre.
+    pub fn sentences(&self) -> Vec<String> {
+        let mut sentences = knowledge_scanner::article_sentences(&self.summary);
+        sentences.truncate(Self::MAX_SUMMARY_SENTENCES);
+        sentences
+    }
+}
+
+/// Lit les entrées d'un flux RSS 2.0 (`<item>`) ou Atom (`<entry>`).
+pub fn parse_feed(xml: &str) -> Vec<FeedEntry> {
+    static ENTRY: OnceLock<Regex> = OnceLock::new();
+    let entry = ENTRY.get_or_init(|| Regex::new(r"(?is)<(?:item|entry)\b[^>]*>(.*?)</(?:item|entry)\s*>").unwrap());
+
+    entry
+        .captures_iter(xml)
+        .filter_map(|captures| {
+            let body = captures.get(1)?.as_str();
+            let title = element_text(body, &["title"]).unwrap_or_default();
+            let link = element_text(body, &["link"]).filter(|link| !link.is_empty()).or_else(|| atom_link(body));
+            let summary = element_text(body, &["description", "summary", "content:encoded", "content"]).unwrap_or_default();
+            let id = element_text(body, &["guid", "id"]).filter(|id| !id.is_empty()).or_else(|| link.clone()).unwrap_or_else(|| title.clone());
+            (!id.is_empty() && (!title.is_empty() || !summary.is_empty())).then_some(FeedEntry { id, title, link, summary })
+        })
+        .collect()
+}
+
+/// Le texte du premier des éléments `names` présent et non vide : CDATA déballé, entités
+/// décodées, HTML retiré.
+fn element_text(body: &str, names: &[&str]) -> Option<String> {
+    names.iter().find_map(|name| {
+        let element = Regex::new(&format!(r"(?is)<{}\b[^>]*?(?:/>|>(.*?)</{}\s*>)", regex::escape(name), regex::escape(name))).ok()?;
+        let raw = element.captures(body)?.get(1).map_or("", |content| content.as_str()).trim();
+        let raw = match raw.strip_prefix("<![CDATA[").and_then(|cdata| cdata.strip_suffix("]]>")) {
+            Some(cdata) => cdata.to_string(),
+            // Le HTML d'un élément non CDATA est échappé une fois : on le décode avant de le retirer.
+            None => HtmlParser::paragraphs(raw).join("\n"),
+        };
+        let text = HtmlParser::paragraphs(&raw).join("\n");
+        (!text.is_empty()).then_some(text)
+    })
+}
+
+/// Le lien d'une entrée Atom (`<link rel="alternate" href="..."/>`).
+fn atom_link(body: &str) -> Option<String> {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    let link = LINK.get_or_init(|| Regex::new(r#"(?is)<link\b([^>]*)>"#).unwrap());
+    let href = |attributes: &str| Regex::new(r#"href\s*=\s*["']([^"']+)["']"#).unwrap().captures(attributes).map(|c| c[1].to_string());
+    let links: Vec<&str> = link.captures_iter(body).filter_map(|captures| captures.get(1)).map(|attributes| attributes.as_str()).collect();
+    links
+        .iter()
+        .find(|attributes| !attributes.contains("rel=") || attributes.contains("alternate"))
+        .or(links.first())
+        .and_then(|attributes| href(attributes))
+}
+
+struct WatchedFeed {
+    config: FeedConfig,
+    next_poll: Instant,
+}
+
+/// Interroge les flux configurés et écarte les entrées déjà vues.
+pub struct FeedWatcher {
+    client: Client,
+    feeds: Vec<WatchedFeed>,
+    seen: HashSet<String>,
+    // Ordre d'arrivée des identifiants, pour oublier les plus anciens au-delà de `MAX_SEEN`.
+    seen_order: VecDeque<String>,
+}
+
+impl FeedWatcher {
+    /// Nombre d'identifiants d'entrées retenus pour la déduplication.
+    pub const MAX_SEEN: usize = 10_000;
+
+    /// Tous les flux sont dus dès le départ.
+    pub fn new(feeds: Vec<FeedConfig>) -> Self {
+        let client = Client::builder().user_agent(concat!("NeuroVA/", env!("CARGO_PKG_VERSION"), " (feed watcher)")).build().unwrap_or_default();
+        let now = Instant::now();
+        let feeds = feeds.into_iter().map(|config| WatchedFeed { config, next_poll: now }).collect();
+        Self { client, feeds, seen: HashSet::new(), seen_order: VecDeque::new() }
+    }
+
+    /// Le prochain instant où un flux est dû, ou None sans flux.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.feeds.iter().map(|feed| feed.next_poll).min()
+    }
+
+    /// Interroge les flux dus et retourne leurs entrées pas encore vues. Un flux injoignable
+    /// est signalé et réessayé à sa prochaine période.
+    pub async fn poll_due(&mut self) -> Vec<FeedEntry> {
+        let now = Instant::now();
+        let mut entries = Vec::new();
+        for index in 0..self.feeds.len() {
+            if self.feeds[index].next_poll > now {
+                continue;
+            }
+            self.feeds[index].next_poll = now + self.feeds[index].config.interval;
+            match self.fetch(&self.feeds[index].config.url).await {
+                Ok(feed) => entries.extend(parse_feed(&feed)),
+                Err(e) => eprintln!("FeedWatcher: impossible de lire le flux {} : {}", self.feeds[index].config.url, e),
+            }
+        }
+        self.unseen(entries)
+    }
+
+    async fn fetch(&self, url: &str) -> Result<String, ScannerError> {
+        Ok(self.client.get(url).send().await?.error_for_status()?.text().await?)
+    }
+
+    /// Écarte les entrées déjà vues (et les doublons du lot).
+    pub fn unseen(&self, entries: Vec<FeedEntry>) -> Vec<FeedEntry> {
+        let mut batch = HashSet::new();
+        entries.into_iter().filter(|entry| !self.seen.contains(&entry.id) && batch.insert(entry.id.clone())).collect()
+    }
+
+    /// Marque des entrées comme vues : elles ne seront plus proposées. Les entrées écartées
+    /// faute d'intérêt ne le sont pas, et restent candidates tant que le flux les publie.
+    pub fn mark_seen<'a>(&mut self, ids: impl IntoIterator<Item = &'a str>) {
+        for id in ids {
+            if self.seen.insert(id.to_string()) {
+                self.seen_order.push_back(id.to_string());
+            }
+        }
+        while self.seen_order.len() > Self::MAX_SEEN {
+            if let Some(oldest) = self.seen_order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss_and_atom_and_skip_seen_entries() {
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Sciences</title>
+            <item><title>Un nouveau quasar</title><link>https://example.org/quasar</link><guid>quasar-1</guid>
+            <description>&lt;p&gt;Le quasar J0313 est le plus lointain jamais observ&#233;.&lt;/p&gt;</description></item>
+            <item><title>Les cellules</title><link>https://example.org/cellule</link>
+            <description><![CDATA[<b>La cellule</b> est l'unité de base du vivant.]]></description></item>
+            </channel></rss>"#;
+        let entries = parse_feed(rss);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "quasar-1");
+        assert_eq!(entries[0].summary, "Le quasar J0313 est le plus lointain jamais observé.");
+        assert_eq!(entries[1].id, "https://example.org/cellule", "Without a guid, the link identifies the entry");
+        assert_eq!(entries[1].sentences(), vec!["La cellule est l'unité de base du vivant."]);
+
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title>
+            <entry><title>Gravity</title><id>urn:uuid:1</id><link rel="alternate" href="https://example.org/gravity"/>
+            <summary type="html">Gravity is the attraction between masses.</summary></entry></feed>"#;
+        let entries = parse_feed(atom);
+        assert_eq!(entries[0].link.as_deref(), Some("https://example.org/gravity"));
+        assert_eq!(entries[0].summary, "Gravity is the attraction between masses.");
+
+        let mut watcher = FeedWatcher::new(Vec::new());
+        watcher.mark_seen(["urn:uuid:1"]);
+        assert!(watcher.unseen(entries.clone()).is_empty());
+        assert!(watcher.next_due().is_none());
+    }
+}
@@ -0,0 +1,145 @@
+//! feedback.rs - User feedback on the answers, as a reinforcement signal.
+//!
+//! Every answer gets an ID (`Core::last_response_id`) and the Core remembers, for its most
+//! recent answers, which memories produced them. When the user rates one of them
+//! (`Core::feedback`), those memories and the synaptic pathways of their concepts are
+//! reinforced or depressed, dopamine follows, and the rating is kept in the `FeedbackLog` to
+//! evaluate the quality of the answers later on.
+
+use crate::capabilities::Capability;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use thiserror::Error;
+
+/// Learning from ratings, as listed by `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "feedback",
+    description: "Learn from the ratings of my answers",
+    description_fr: "Apprendre des notes données à mes réponses",
+    examples: &[],
+};
+
+/// Answers that can still be rated; older ones are forgotten first.
+pub const MAX_TRACKED_RESPONSES: usize = 64;
+/// Ratings kept in the log.
+pub const MAX_FEEDBACK_RECORDS: usize = 10_000;
+
+/// The user's rating of an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Feedback {
+    Positive,
+    Negative,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FeedbackError {
+    #[error("no recent response with ID {0}")]
+    UnknownResponse(u64),
+    #[error("response {0} was already rated")]
+    AlreadyRated(u64),
+}
+
+/// An answer that can be rated, with what produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseTrace {
+    pub id: u64,
+    pub prompt: String,
+    pub response: String,
+    /// The memories retrieved to produce the answer.
+    pub memory_ids: Vec<u64>,
+}
+
+/// A rating, as recorded for evaluation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedbackRecord {
+    pub response_id: u64,
+    pub prompt: String,
+    pub response: String,
+    pub feedback: Feedback,
+    /// The memories that were reinforced or depressed.
+    pub memory_ids: Vec<u64>,
+    /// The tick of the Core when the answer was rated.
+    pub tick: u64,
+}
+
+/// Counts of the ratings received.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct FeedbackSummary {
+    pub positive: usize,
+    pub negative: usize,
+    /// Fraction of positive ratings, None before the first rating.
+    pub approval_rate: Option<f32>,
+}
+
+/// The recent answers and the ratings they received.
+#[derive(Debug, Default)]
+pub struct FeedbackLog {
+    responses: VecDeque<ResponseTrace>,
+    records: Vec<FeedbackRecord>,
+}
+
+impl FeedbackLog {
+    /// Remembers an answer so that it can be rated.
+    pub fn track(&mut self, response: ResponseTrace) {
+        self.responses.push_back(response);
+        if self.responses.len() > MAX_TRACKED_RESPONSES {
+            self.responses.pop_front();
+        }
+    }
+
+    /// The answer a rating is about: it must be recent and not rated yet.
+    pub fn rateable(&self, response_id: u64) -> Result<&ResponseTrace, FeedbackError> {
+        if self.records.iter().any(|record| record.response_id == response_id) {
+            return Err(FeedbackError::AlreadyRated(response_id));
+        }
+        self.responses.iter().find(|response| response.id == response_id).ok_or(FeedbackError::UnknownResponse(response_id))
+    }
+
+    pub fn record(&mut self, record: FeedbackRecord) {
+        self.records.push(record);
+        if self.records.len() > MAX_FEEDBACK_RECORDS {
+            let excess = self.records.len() - MAX_FEEDBACK_RECORDS;
+            self.records.drain(..excess);
+        }
+    }
+
+    /// Every rating, oldest first.
+    pub fn records(&self) -> &[FeedbackRecord] {
+        &self.records
+    }
+
+    pub fn summary(&self) -> FeedbackSummary {
+        let positive = self.records.iter().filter(|record| record.feedback == Feedback::Positive).count();
+        let negative = self.records.len() - positive;
+        let approval_rate = (!self.records.is_empty()).then(|| positive as f32 / self.records.len() as f32);
+        FeedbackSummary { positive, negative, approval_rate }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_responses_are_rated_once_while_they_are_recent() {
+        let mut log = FeedbackLog::default();
+        for id in 1..=MAX_TRACKED_RESPONSES as u64 + 1 {
+            log.track(ResponseTrace { id, prompt: "q".to_string(), response: "r".to_string(), memory_ids: vec![id] });
+        }
+        assert_eq!(log.rateable(1), Err(FeedbackError::UnknownResponse(1)), "The oldest answer was forgotten");
+
+        let response = log.rateable(2).unwrap().clone();
+        log.record(FeedbackRecord {
+            response_id: response.id,
+            prompt: response.prompt,
+            response: response.response,
+            feedback: Feedback::Negative,
+            memory_ids: response.memory_ids,
+            tick: 0,
+        });
+        assert_eq!(log.rateable(2), Err(FeedbackError::AlreadyRated(2)));
+        assert_eq!(log.summary(), FeedbackSummary { positive: 0, negative: 1, approval_rate: Some(0.0) });
+        assert_eq!(serde_json::from_str::<Feedback>("\"positive\"").unwrap(), Feedback::Positive);
+    }
+}
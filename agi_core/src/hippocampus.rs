@@ -1,8 +1,31 @@
 // agi_core/src/hippocampus.rs
-use crate::holographic_memory::{HolographicMemory, HolographicTrace};
-use crate::quantum::Qubit;
-use rand::Rng;
-use std::collections::HashSet;
+use crate::capabilities::Capability;
+use crate::conceptual_hierarchy::ConceptRemap;
+use crate::holographic_memory::{HolographicEncoder, HolographicMemory, HolographicTrace};
+use crate::lexical_index::{self, FusionConfig, LexicalIndex};
+use crate::metrics;
+use crate::quantum_backend::QuantumBackend;
+use crate::rng::CoreRng;
+use crate::sensory_cortex::Entity;
+use crate::source_trust;
+use crate::stop_words::{SharedStopWords, StopWordRegistry};
+use crate::trace_compression::{self, CompressionConfig, CompressionReport, TraceProjection};
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// Holographic storage and recall, as listed by `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "memory",
+    description: "Remember what I am taught and recall it by meaning, not only by words",
+    description_fr: "Retenir ce qu'on m'apprend et le retrouver par le sens, pas seulement par les mots",
+    examples: &["Qu'est-ce qu'un quasar ?", "What is the theory of relativity?"],
+};
+
+/// The distance a fully reinforced memory gains on every query (see `Hippocampus::reinforce_memory`).
+pub const REINFORCEMENT_DISTANCE: f32 = 0.05;
 
 /// Represents a memory pattern as a set of associated qubit indices.
 #[derive(Debug, Clone)]
@@ -10,11 +33,43 @@ pub struct MemoryPattern {
     pub qubit_indices: Vec<usize>,
 }
 
+/// A memory found near another one, as returned by `Hippocampus::neighbors`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryNeighbor {
+    pub id: u64,
+    pub text: String,
+    /// Holographic distance to the reference memory, in [0, 2].
+    pub distance: f32,
+    /// Confidence that the memories are related, in [0, 1] (1 - distance / 2).
+    pub confidence: f32,
+    /// Concepts mentioned by both memories.
+    pub shared_concepts: Vec<u64>,
+}
+
+/// A neighbouring memory with its shared concepts resolved to names, for display.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedMemory {
+    #[serde(flatten)]
+    pub neighbor: MemoryNeighbor,
+    pub shared_concept_names: Vec<String>,
+}
+
 /// Represents the Hippocampus, responsible for memory encoding and retrieval.
 #[derive(Debug)]
 pub struct Hippocampus {
     core_memories: Vec<MemoryPattern>,
     pub holographic_memory: Vec<HolographicMemory>,
+    next_memory_id: u64,
+    rng: CoreRng,
+    // Feedback received by each memory, from -1.0 to 1.0 (see `reinforce_memory`).
+    reinforcement: HashMap<u64, f32>,
+    // Inverted index of the entities: index key (see `Entity::index_keys`) -> memory IDs.
+    entity_index: HashMap<String, HashSet<u64>>,
+    // Inverted index of the words of the memories, ranked with BM25 (see `lexical_index`).
+    lexical_index: LexicalIndex,
+    fusion: FusionConfig,
+    // The projection of the compressed traces, while there are some (see `compress`).
+    projection: Option<TraceProjection>,
 }
 
 impl Hippocampus {
@@ -28,15 +83,46 @@ impl Hippocampus {
         Hippocampus {
             core_memories: patterns,
             holographic_memory: Vec::new(),
+            next_memory_id: 1,
+            rng: CoreRng::from_entropy(),
+            reinforcement: HashMap::new(),
+            entity_index: HashMap::new(),
+            lexical_index: LexicalIndex::new(StopWordRegistry::new().shared()),
+            fusion: FusionConfig::default(),
+            projection: None,
         }
     }
 
+    /// Replaces the random stream used to sample memories (see `RngProvider`).
+    pub fn with_rng(mut self, rng: CoreRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Replaces how the keyword and holographic rankings are fused (see `find_similar_memories`).
+    pub fn with_fusion(mut self, fusion: FusionConfig) -> Self {
+        self.fusion = fusion;
+        self
+    }
+
+    /// Indexes the words of the memories without the stop words of `stop_words`.
+    pub fn with_stop_words(mut self, stop_words: SharedStopWords) -> Self {
+        self.lexical_index = LexicalIndex::new(stop_words);
+        self.rebuild_lexical_index();
+        self
+    }
+
     pub fn add_holographic_memory(&mut self, text: String, trace: HolographicTrace, is_axiom: bool) {
-        let new_memory = HolographicMemory {
-            text,
-            trace,
-            is_axiom,
-        };
+        self.add_holographic_memory_with_concepts(text, trace, is_axiom, Vec::new());
+    }
+
+    /// Stores a memory along with the IDs of the concepts it mentions.
+    pub fn add_holographic_memory_with_concepts(&mut self, text: String, trace: HolographicTrace, is_axiom: bool, concepts: Vec<u64>) {
+        let mut new_memory = HolographicMemory::new(text, trace, is_axiom);
+        new_memory.id = self.next_memory_id;
+        new_memory.concepts = concepts;
+        self.next_memory_id += 1;
+        self.lexical_index.insert(new_memory.id, &new_memory.text);
         if is_axiom {
             println!("--- Foundational Axiom Encoded: '{}' ---", new_memory.text);
         } else {
@@ -45,16 +131,298 @@ impl Hippocampus {
         self.holographic_memory.push(new_memory);
     }
 
-    /// Finds the top_k most similar holographic memories to a given query trace.
+    /// Rewrites the concept IDs attached to memories after a merge or split in the hierarchy.
+    /// `assign` picks, for a memory's text, which replacement concept it should keep when a
+    /// concept was split; memories referencing a merged concept simply follow the remap. A split
+    /// domain files its memories under every new concept.
+    pub fn remap_concepts<F>(&mut self, remap: &ConceptRemap, assign: F)
+    where
+        F: Fn(&str) -> usize,
+    {
+        for memory in &mut self.holographic_memory {
+            if memory.domains.iter().any(|id| remap.replaced.contains_key(id)) {
+                memory.domains = remap.apply(&memory.domains);
+            }
+            if !memory.concepts.iter().any(|id| remap.replaced.contains_key(id)) {
+                continue;
+            }
+            let mut concepts = Vec::with_capacity(memory.concepts.len());
+            for id in &memory.concepts {
+                match remap.replaced.get(id) {
+                    Some(replacements) if replacements.len() > 1 => {
+                        concepts.push(*replacements.get(assign(&memory.text)).unwrap_or(&replacements[0]));
+                    }
+                    _ => concepts.extend(remap.apply(&[*id])),
+                }
+            }
+            let mut seen = HashSet::new();
+            concepts.retain(|id| seen.insert(*id));
+            memory.concepts = concepts;
+        }
+    }
+
+    /// Retrieves a stored memory by its ID.
+    pub fn memory(&self, memory_id: u64) -> Option<&HolographicMemory> {
+        self.holographic_memory.iter().find(|memory| memory.id == memory_id)
+    }
+
+    /// Returns the `k` memories closest to `memory_id`, most confident first, or `None` if the
+    /// memory does not exist. Memories with the same text as the reference are skipped.
+    pub fn neighbors(&self, memory_id: u64, k: usize) -> Option<Vec<MemoryNeighbor>> {
+        let reference = self.memory(memory_id)?;
+        let reference_concepts: HashSet<u64> = reference.concepts.iter().copied().collect();
+        let projected_reference = self.projected_query(&reference.trace);
+
+        let mut neighbors: Vec<MemoryNeighbor> = self
+            .holographic_memory
+            .par_iter()
+            .filter(|memory| memory.id != memory_id && memory.text != reference.text)
+            .filter_map(|memory| {
+                let distance = self.distance(&reference.trace, projected_reference.as_ref(), &memory.trace);
+                if distance.is_nan() {
+                    return None;
+                }
+                Some(MemoryNeighbor {
+                    id: memory.id,
+                    text: memory.text.clone(),
+                    distance,
+                    confidence: (1.0 - distance / 2.0).clamp(0.0, 1.0),
+                    shared_concepts: memory.concepts.iter().copied().filter(|id| reference_concepts.contains(id)).collect(),
+                })
+            })
+            .collect();
+
+        neighbors.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(std::cmp::Ordering::Equal));
+        neighbors.truncate(k);
+        Some(neighbors)
+    }
+
+    /// Removes every memory with the given text and returns how many were removed.
+    pub fn remove_memories_with_text(&mut self, text: &str) -> usize {
+        let before = self.holographic_memory.len();
+        self.holographic_memory.retain(|memory| memory.text != text);
+        self.rebuild_entity_index();
+        self.rebuild_lexical_index();
+        before - self.holographic_memory.len()
+    }
+
+    /// Removes the first memory with the given text that was read from the document at `path`
+    /// (see `HolographicMemory::location`), and returns whether there was one.
+    pub fn remove_memory_read_from(&mut self, text: &str, path: &str) -> bool {
+        let read_from_path = |memory: &HolographicMemory| memory.text == text && memory.location.as_ref().is_some_and(|location| location.path == path);
+        let Some(index) = self.holographic_memory.iter().position(read_from_path) else {
+            return false;
+        };
+        self.holographic_memory.remove(index);
+        self.rebuild_entity_index();
+        self.rebuild_lexical_index();
+        true
+    }
+
+    /// Merges the memories that hold the same text (ignoring case and spacing): the first one
+    /// is kept, and stays an axiom if any of its copies was one. Returns how many were removed.
+    pub fn merge_duplicates(&mut self) -> usize {
+        let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        let before = self.holographic_memory.len();
+        let mut kept: HashMap<String, usize> = HashMap::new();
+        let mut merged = Vec::with_capacity(before);
+        for memory in self.holographic_memory.drain(..) {
+            match kept.get(&normalize(&memory.text)) {
+                Some(&index) => {
+                    let original: &mut HolographicMemory = &mut merged[index];
+                    original.is_axiom |= memory.is_axiom;
+                    for concept in memory.concepts {
+                        if !original.concepts.contains(&concept) {
+                            original.concepts.push(concept);
+                        }
+                    }
+                    for entity in memory.entities {
+                        if !original.entities.contains(&entity) {
+                            original.entities.push(entity);
+                        }
+                    }
+                }
+                None => {
+                    kept.insert(normalize(&memory.text), merged.len());
+                    merged.push(memory);
+                }
+            }
+        }
+        self.holographic_memory = merged;
+        self.rebuild_entity_index();
+        self.rebuild_lexical_index();
+        before - self.holographic_memory.len()
+    }
+
+    /// Attaches the entities a memory mentions and indexes them.
+    pub fn tag_entities(&mut self, memory_id: u64, entities: Vec<Entity>) {
+        for entity in &entities {
+            for key in entity.index_keys() {
+                self.entity_index.entry(key).or_default().insert(memory_id);
+            }
+        }
+        if let Some(memory) = self.holographic_memory.iter_mut().find(|memory| memory.id == memory_id) {
+            memory.entities = entities;
+        }
+    }
+
+    fn rebuild_entity_index(&mut self) {
+        self.entity_index.clear();
+        for memory in &self.holographic_memory {
+            for key in memory.entities.iter().flat_map(Entity::index_keys) {
+                self.entity_index.entry(key).or_default().insert(memory.id);
+            }
+        }
+    }
+
+    fn rebuild_lexical_index(&mut self) {
+        self.lexical_index.clear();
+        for memory in &self.holographic_memory {
+            self.lexical_index.insert(memory.id, &memory.text);
+        }
+    }
+
+    /// The memories that mention the given entities: those mentioning all the indexed ones, or
+    /// if none does, those mentioning any. None when no entity is indexed at all, so that the
+    /// search is not restricted.
+    pub fn memories_with_entities(&self, entities: &[Entity]) -> Option<HashSet<u64>> {
+        let sets: Vec<HashSet<u64>> = entities
+            .iter()
+            .map(|entity| entity.index_keys().iter().filter_map(|key| self.entity_index.get(key)).flatten().copied().collect::<HashSet<u64>>())
+            .filter(|ids| !ids.is_empty())
+            .collect();
+        let (first, rest) = sets.split_first()?;
+        let all: HashSet<u64> = first.iter().filter(|id| rest.iter().all(|ids| ids.contains(id))).copied().collect();
+        if all.is_empty() {
+            Some(sets.into_iter().flatten().collect())
+        } else {
+            Some(all)
+        }
+    }
+
+    /// Adds `delta` to the reinforcement of a memory (clamped to -1.0..=1.0). A reinforced
+    /// memory looks up to `REINFORCEMENT_DISTANCE` closer to every query, a depressed one as
+    /// much farther. Returns false if there is no such memory.
+    pub fn reinforce_memory(&mut self, memory_id: u64, delta: f32) -> bool {
+        if self.memory(memory_id).is_none() {
+            return false;
+        }
+        let reinforcement = self.reinforcement.entry(memory_id).or_insert(0.0);
+        *reinforcement = (*reinforcement + delta).clamp(-1.0, 1.0);
+        true
+    }
+
+    /// The reinforcement of a memory (0.0 if it never received feedback).
+    pub fn reinforcement(&self, memory_id: u64) -> f32 {
+        self.reinforcement.get(&memory_id).copied().unwrap_or(0.0)
+    }
+
+    /// Records that memories contributed to an answer at `at` (see `HolographicMemory::last_accessed`).
+    pub fn touch(&mut self, memory_ids: &[u64], at: u64) {
+        for memory in self.holographic_memory.iter_mut().filter(|memory| memory_ids.contains(&memory.id)) {
+            memory.last_accessed = Some(at);
+        }
+    }
+
+    /// Prunes the light concepts of every memory trace and projects the traces of the cold
+    /// memories onto `config.compressed_dimension` dimensions (see `trace_compression`). The
+    /// projection of the first pass is kept as long as compressed traces remain.
+    pub fn compress(&mut self, config: &CompressionConfig, now: u64) -> CompressionReport {
+        let mut report = CompressionReport { bytes_before: self.trace_bytes(), ..CompressionReport::default() };
+        if self.compressed_count() == 0 {
+            let dimension = self.holographic_memory.iter().map(|memory| memory.trace.superposition_pattern.len()).max().unwrap_or(0);
+            self.projection = (config.compressed_dimension < dimension).then(|| TraceProjection::new(dimension, config.compressed_dimension, config.seed));
+        }
+        let cutoff = now.saturating_sub(config.cold_after_secs);
+        for memory in &mut self.holographic_memory {
+            report.concepts_pruned += trace_compression::prune_concepts(&mut memory.trace, config.min_concept_share);
+            let cold = !memory.is_axiom && memory.last_accessed.or(memory.created_at).is_none_or(|at| at <= cutoff);
+            if cold && self.projection.as_ref().is_some_and(|projection| projection.compress(&mut memory.trace)) {
+                report.memories_compressed += 1;
+            }
+        }
+        report.bytes_after = self.trace_bytes();
+        report
+    }
+
+    /// Re-encodes the compressed traces of `memory_ids` at full size, as they are used again.
+    /// Returns how many were.
+    pub fn expand(&mut self, memory_ids: &[u64], encoder: &HolographicEncoder) -> usize {
+        let Some(projection) = &self.projection else { return 0 };
+        let mut expanded = 0;
+        for memory in self.holographic_memory.iter_mut().filter(|memory| memory_ids.contains(&memory.id) && projection.is_projected(&memory.trace)) {
+            memory.trace = encoder.encode_memory(&memory.text, memory.image.as_ref());
+            expanded += 1;
+        }
+        expanded
+    }
+
+    /// How many memories have a compressed trace.
+    pub fn compressed_count(&self) -> usize {
+        self.projection.as_ref().map_or(0, |projection| self.holographic_memory.iter().filter(|memory| projection.is_projected(&memory.trace)).count())
+    }
+
+    /// Bytes held by the memory traces, pictures included.
+    pub fn trace_bytes(&self) -> usize {
+        self.holographic_memory
+            .iter()
+            .map(|memory| trace_compression::trace_bytes(&memory.trace) + memory.image.as_ref().map_or(0, |image| trace_compression::trace_bytes(&image.trace)))
+            .sum()
+    }
+
+    /// `query` projected like the compressed traces, to compare it with them; None while no
+    /// trace is compressed. Computed once per query, for `similarity`.
+    pub fn projected_query(&self, query: &HolographicTrace) -> Option<HolographicTrace> {
+        self.projection.as_ref().map(|projection| projection.projected(query))
+    }
+
+    /// Cosine similarity of a query with a stored trace, in the projected space when either is
+    /// compressed. `projected_query` is the query through `projected_query`, if it was computed.
+    pub fn similarity(&self, query: &HolographicTrace, projected_query: Option<&HolographicTrace>, trace: &HolographicTrace) -> f32 {
+        match &self.projection {
+            Some(projection) if projection.is_projected(trace) => match projected_query {
+                Some(projected) => projected.cosine_similarity(trace),
+                None => projection.projected(query).cosine_similarity(trace),
+            },
+            Some(projection) if projection.is_projected(query) => query.cosine_similarity(&projection.projected(trace)),
+            _ => query.cosine_similarity(trace),
+        }
+    }
+
+    /// Like `HolographicTrace::distance`, through `similarity`.
+    fn distance(&self, query: &HolographicTrace, projected_query: Option<&HolographicTrace>, trace: &HolographicTrace) -> f32 {
+        let similarity = self.similarity(query, projected_query, trace);
+        if similarity.is_nan() { f32::MAX } else { 1.0 - similarity.abs() }
+    }
+
+    /// Finds the top_k memories most relevant to a query, with their holographic distance to
+    /// `query_trace`. The ranking by distance is fused with the BM25 ranking of the words of
+    /// `query_text`, the text the trace encodes, as the `FusionConfig` says; an empty text (or a
+    /// lexical weight of 0) ranks by distance only.
     pub fn find_similar_memories<'a>(
         &'a self,
         query_trace: &HolographicTrace,
+        query_text: &str,
         top_k: usize,
         is_introspective: bool,
+    ) -> Vec<(&'a HolographicMemory, f32)> {
+        self.find_similar_memories_among(query_trace, query_text, top_k, is_introspective, None)
+    }
+
+    /// Like `find_similar_memories`, but only ranks the `candidates` memories when given
+    /// (e.g. those mentioning an entity of the query, see `memories_with_entities`).
+    pub fn find_similar_memories_among<'a>(
+        &'a self,
+        query_trace: &HolographicTrace,
+        query_text: &str,
+        top_k: usize,
+        is_introspective: bool,
+        candidates: Option<&HashSet<u64>>,
     ) -> Vec<(&'a HolographicMemory, f32)> {
         if self.holographic_memory.is_empty() {
             return Vec::new();
         }
+        let started = Instant::now();
 
         let memories_to_search: Vec<_> = if is_introspective {
             // For introspective queries, we perform a targeted search ONLY on foundational axioms.
@@ -65,11 +433,18 @@ impl Hippocampus {
             println!("--- Factual/Creative query: Searching full knowledge base... ---");
             self.holographic_memory.iter().collect()
         };
+        let memories_to_search: Vec<_> = match candidates {
+            Some(ids) => memories_to_search.into_iter().filter(|memory| ids.contains(&memory.id)).collect(),
+            None => memories_to_search,
+        };
 
+        // Scored in parallel; the order of the memories is kept, so ties stay deterministic.
+        let projected_query = self.projected_query(query_trace);
         let mut scored_memories: Vec<(&'a HolographicMemory, f32)> = memories_to_search
-            .into_iter()
+            .into_par_iter()
             .filter_map(|memory| {
-                let distance = query_trace.distance(&memory.trace);
+                let distance = source_trust::weighed_distance(self.distance(query_trace, projected_query.as_ref(), &memory.trace), memory.trust)
+                    - REINFORCEMENT_DISTANCE * self.reinforcement(memory.id);
                 if distance.is_nan() {
                     None
                 } else {
@@ -80,6 +455,7 @@ impl Hippocampus {
 
         // Sort by distance, ascending (smallest distance is most similar)
         scored_memories.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        let scored_memories = self.fuse_keywords(scored_memories, query_text);
 
         // --- Diagnostic Logging ---
         println!("--- Top 5 Raw Search Results (Distance): ---");
@@ -103,34 +479,163 @@ impl Hippocampus {
             }
         }
 
+        metrics::registry().hippocampus_search.observe(started.elapsed());
         unique_memories
     }
 
-    pub fn get_random_pattern(&self) -> Option<&MemoryPattern> {
+    /// Reorders memories sorted by distance by their fused relevance to `query_text` (stable, so
+    /// the memories sharing no word with the query keep their order among themselves).
+    fn fuse_keywords<'a>(&self, scored_memories: Vec<(&'a HolographicMemory, f32)>, query_text: &str) -> Vec<(&'a HolographicMemory, f32)> {
+        if self.fusion.lexical_weight <= 0.0 || query_text.trim().is_empty() {
+            return scored_memories;
+        }
+        let scores = self.lexical_index.scores(query_text, &self.fusion);
+        if scores.is_empty() {
+            return scored_memories;
+        }
+        let distances: Vec<f32> = scored_memories.iter().map(|(_, distance)| *distance).collect();
+        let lexical: Vec<f32> = scored_memories.iter().map(|(memory, _)| scores.get(&memory.id).copied().unwrap_or(0.0)).collect();
+        let fused = lexical_index::fuse(&self.fusion, &distances, &lexical);
+
+        let mut ranked: Vec<(f32, (&'a HolographicMemory, f32))> = fused.into_iter().zip(scored_memories).collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        ranked.into_iter().map(|(_, scored)| scored).collect()
+    }
+
+    pub fn get_random_pattern(&mut self) -> Option<&MemoryPattern> {
         if self.core_memories.is_empty() {
             None
         } else {
-            let mut rng = rand::thread_rng();
-            self.core_memories.get(rng.gen_range(0..self.core_memories.len()))
+            let index = self.rng.gen_range(0..self.core_memories.len());
+            self.core_memories.get(index)
         }
     }
 
-    pub fn replay_core_memories(&self, quantum_core: &mut [Qubit]) {
+    pub fn replay_core_memories(&self, quantum_core: &mut dyn QuantumBackend) {
         println!("\n--- Hippocampal Replay Initiated ---");
         let priming_strength = 0.1;
 
         for pattern in &self.core_memories {
             println!("Replaying memory pattern: {:?}", pattern.qubit_indices);
             for &qubit_index in &pattern.qubit_indices {
-                if let Some(qubit) = quantum_core.get_mut(qubit_index) {
-                    qubit.beta.re += priming_strength;
-                    qubit.alpha.re -= priming_strength;
-                    qubit.alpha.re = qubit.alpha.re.max(0.0);
-                    qubit.beta.re = qubit.beta.re.max(0.0);
-                    qubit.normalize();
-                }
+                quantum_core.prime(qubit_index, priming_strength);
             }
         }
         println!("--- Hippocampal Replay Complete ---\n");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holographic_memory::HolographicEncoder;
+    use crate::sensory_cortex::SensoryCortex;
+    use crate::source_trust::{SourceTag, TrustWeights};
+
+    #[test]
+    fn test_neighbors_are_ranked_by_confidence() {
+        let encoder = HolographicEncoder::new(256);
+        let mut hippocampus = Hippocampus::new();
+        let facts = [
+            ("le soleil est une étoile", vec![1, 2]),
+            ("une étoile brille dans le ciel", vec![2, 3]),
+            ("le soleil est une étoile", vec![1, 2]),
+            ("la carotte est un légume", vec![4, 5]),
+        ];
+        for (text, concepts) in facts {
+            hippocampus.add_holographic_memory_with_concepts(text.to_string(), encoder.encode(text), false, concepts);
+        }
+
+        let neighbors = hippocampus.neighbors(1, 5).unwrap();
+        assert_eq!(neighbors.len(), 2, "The reference and its duplicates are not their own neighbours");
+        assert_eq!(neighbors[0].id, 2);
+        assert_eq!(neighbors[0].shared_concepts, vec![2]);
+        assert!(neighbors[0].confidence >= neighbors[1].confidence);
+        assert!(neighbors[1].shared_concepts.is_empty());
+        assert!(hippocampus.neighbors(42, 5).is_none());
+    }
+
+    #[test]
+    fn test_merge_duplicates_keeps_the_first_copy() {
+        let encoder = HolographicEncoder::new(256);
+        let mut hippocampus = Hippocampus::new();
+        let facts = [("le soleil est une étoile", false, vec![1]), ("la carotte est un légume", false, vec![4]), ("Le soleil  est une étoile", true, vec![2])];
+        for (text, is_axiom, concepts) in facts {
+            hippocampus.add_holographic_memory_with_concepts(text.to_string(), encoder.encode(text), is_axiom, concepts);
+        }
+
+        assert_eq!(hippocampus.merge_duplicates(), 1);
+        let ids: Vec<u64> = hippocampus.holographic_memory.iter().map(|memory| memory.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        let sun = hippocampus.memory(1).unwrap();
+        assert!(sun.is_axiom, "A copy that was an axiom makes the kept memory one");
+        assert_eq!(sun.concepts, vec![1, 2]);
+        assert_eq!(hippocampus.merge_duplicates(), 0);
+    }
+
+    #[test]
+    fn test_entity_index_restricts_candidates() {
+        let encoder = HolographicEncoder::new(256);
+        let mut hippocampus = Hippocampus::new();
+        let facts = ["Albert Einstein est né à Ulm en 1879.", "Marie Curie a découvert le polonium.", "Einstein est mort à Princeton en 1955."];
+        for text in facts {
+            hippocampus.add_holographic_memory(text.to_string(), encoder.encode(text), false);
+            let id = hippocampus.holographic_memory.last().unwrap().id;
+            hippocampus.tag_entities(id, SensoryCortex::extract_entities(text));
+        }
+
+        let query = SensoryCortex::extract_query_entities("Quand Einstein est-il né ?");
+        assert_eq!(hippocampus.memories_with_entities(&query), Some(HashSet::from([1, 3])));
+        let query = SensoryCortex::extract_query_entities("Einstein a-t-il vécu à Ulm ?");
+        assert_eq!(hippocampus.memories_with_entities(&query), Some(HashSet::from([1])), "Memories mentioning every entity come first");
+        assert_eq!(hippocampus.memories_with_entities(&SensoryCortex::extract_query_entities("Qu'est-ce que la lumière ?")), None);
+
+        hippocampus.remove_memories_with_text(facts[0]);
+        assert_eq!(hippocampus.memories_with_entities(&SensoryCortex::extract_query_entities("Où est Ulm ?")), None);
+    }
+
+    #[test]
+    fn test_keyword_matches_are_fused_with_the_holographic_ranking() {
+        let mut encoder = HolographicEncoder::new(256);
+        let facts = ["La lune tourne autour de la Terre.", "Le soleil est une étoile.", "Un quasar est le noyau lumineux d'une galaxie lointaine."];
+        for text in facts {
+            encoder.add_document(text);
+        }
+        let mut hippocampus = Hippocampus::new();
+        for text in facts {
+            hippocampus.add_holographic_memory(text.to_string(), encoder.encode(text), false);
+        }
+
+        // A trace pointing at the moon, with words naming the quasar.
+        let query = encoder.encode(facts[0]);
+        let ids = |hippocampus: &Hippocampus, text: &str| -> Vec<u64> {
+            hippocampus.find_similar_memories(&query, text, 3, false).iter().map(|(memory, _)| memory.id).collect()
+        };
+        assert_eq!(ids(&hippocampus, "")[0], 1, "Without words, the trace decides");
+        assert_eq!(ids(&hippocampus, "quasar")[0], 3, "The only memory naming the quasar comes first");
+        let weighted = hippocampus.with_fusion(FusionConfig { method: lexical_index::FusionMethod::WeightedSum, ..FusionConfig::default() });
+        assert_eq!(ids(&weighted, "quasar")[0], 3);
+        let traces_only = weighted.with_fusion(FusionConfig { lexical_weight: 0.0, ..FusionConfig::default() });
+        assert_eq!(ids(&traces_only, "quasar")[0], 1);
+
+        let mut hippocampus = traces_only.with_fusion(FusionConfig::default());
+        hippocampus.remove_memories_with_text(facts[2]);
+        assert_eq!(ids(&hippocampus, "quasar")[0], 1, "Removed memories leave the keyword index");
+    }
+
+    #[test]
+    fn test_less_trusted_sources_rank_below_equal_matches() {
+        let encoder = HolographicEncoder::new(256);
+        let trace = encoder.encode("Le soleil est une étoile.");
+        let mut hippocampus = Hippocampus::new();
+        hippocampus.add_holographic_memory("Le soleil est une étoile.".to_string(), trace.clone(), false);
+        hippocampus.add_holographic_memory("Le soleil est une étoile jaune.".to_string(), trace.clone(), false);
+        let web = &mut hippocampus.holographic_memory[0];
+        web.source = SourceTag::Web;
+        web.trust = TrustWeights::default().of(SourceTag::Web);
+
+        let results = hippocampus.find_similar_memories(&trace, "", 2, false);
+        assert_eq!(results[0].0.text, "Le soleil est une étoile jaune.");
+        assert!(results[1].1 > results[0].1, "The web memory is farther: {:?}", results.iter().map(|(_, distance)| distance).collect::<Vec<_>>());
+    }
+}
@@ -2,7 +2,12 @@
 //! goals, and internal stimuli, driving the AGI to think even without external prompts.
 
 use crate::holographic_memory::HolographicMemory;
+use crate::error::LockResultExt;
+use crate::stop_words::{SharedStopWords, StopWordRegistry};
+use crate::tokenizer;
+use crate::rng::CoreRng;
 use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use std::time::{Duration, Instant};
 
 /// Represents the source of the AGI's autonomous motivation.
@@ -10,17 +15,32 @@ pub struct InnerDrive {
     last_thought_instant: Instant,
     thought_interval: Duration,
     is_contextual_turn: bool, // To alternate between contextual and isolation thoughts
+    stop_words: SharedStopWords,
+    rng: CoreRng,
 }
 
 impl InnerDrive {
     pub fn new(thought_interval_seconds: u64) -> Self {
+        Self::with_stop_words(thought_interval_seconds, StopWordRegistry::new().shared())
+    }
+
+    /// Creates an Inner Drive that picks its keywords with a shared stop-word registry.
+    pub fn with_stop_words(thought_interval_seconds: u64, stop_words: SharedStopWords) -> Self {
         Self {
             last_thought_instant: Instant::now(),
             thought_interval: Duration::from_secs(thought_interval_seconds),
             is_contextual_turn: true,
+            stop_words,
+            rng: CoreRng::from_entropy(),
         }
     }
 
+    /// Replaces the random stream used to pick memories and templates (see `RngProvider`).
+    pub fn with_rng(mut self, rng: CoreRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
     /// Called on each AGI core tick. If enough time has passed, it generates an internal stimulus.
     pub fn tick(&mut self, last_reasoning_result: Option<&str>, memories: &Vec<HolographicMemory>) -> Option<String> {
         if self.last_thought_instant.elapsed() < self.thought_interval {
@@ -51,40 +71,39 @@ impl InnerDrive {
         thought
     }
 
+    /// Picks two distinct memories (by index) to replay together during sleep.
+    pub fn dream_pair(&mut self, memory_count: usize) -> Option<(usize, usize)> {
+        if memory_count < 2 {
+            return None;
+        }
+        let first = self.rng.gen_range(0..memory_count);
+        let second = (first + self.rng.gen_range(1..memory_count)) % memory_count;
+        Some((first, second))
+    }
+
     /// Generates a prompt from a random memory, acting as an 'isolation' thought.
-    fn generate_isolation_prompt(&self, memories: &Vec<HolographicMemory>) -> Option<String> {
-        let mut rng = rand::thread_rng();
-        memories.choose(&mut rng).and_then(|mem| self.generate_contextual_prompt(&mem.text))
+    fn generate_isolation_prompt(&mut self, memories: &Vec<HolographicMemory>) -> Option<String> {
+        let memory = memories.choose(&mut self.rng)?;
+        self.generate_contextual_prompt(&memory.text)
     }
 
     /// Generates a prompt based on a given context (last reasoning result or a random memory).
-    fn generate_contextual_prompt(&self, context: &str) -> Option<String> {
-        const STOP_WORDS: &[&str] = &[
-            // French
-            "le", "la", "les", "un", "une", "des", "ce", "cet", "cette", "ces", "mon", "ton", "son", "ma", "ta", "sa", "mes", "tes", "ses",
-            "quel", "quelle", "quels", "quelles", "qui", "que", "quoi", "dont", "où", "je", "tu", "il", "elle", "nous", "vous", "ils", "elles",
-            "au", "aux", "avec", "dans", "de", "du", "en", "et", "est", "pour", "par", "sur", "ne", "pas", "plus", "comme", "mais", "si",
-            "cela", "ça", "ici", "ont", "été", "lui", "eux", "moi", "toi", "sommes", "êtes", "sont", "absolument", "c'est", "d'un", "d'une",
-            // English
-            "the", "a", "an", "i", "it", "is", "in", "on", "at", "for", "with", "from", "by", "to", "of", "and", "are", "was", "were",
-            "he", "she", "they", "we", "you", "me", "him", "her", "us", "them", "my", "your", "his", "its", "our", "their",
-            "what", "which", "who", "whom", "this", "that", "these", "those", "am", "be", "been", "being", "have", "has", "had", "having",
-            "do", "does", "did", "doing", "will", "would", "should", "can", "could", "not"
-        ];
-
-        let keywords: Vec<&str> = context.split_whitespace()
-            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+    fn generate_contextual_prompt(&mut self, context: &str) -> Option<String> {
+        let stop_words = self.stop_words.read().recover();
+        let tokens = tokenizer::tokenize(context);
+        let keywords: Vec<&str> = tokens.iter()
+            .filter(|token| token.is_content())
+            .map(|token| token.text.as_str())
             .filter(|word| {
                 if word.is_empty() || word.len() <= 2 { return false; }
                 let first_char_is_upper = word.chars().next().unwrap().is_uppercase();
-                let lower_word = word.to_lowercase();
-                let is_not_stop_word = !STOP_WORDS.contains(&lower_word.as_str());
+                let is_not_stop_word = !stop_words.is_stop_word(word);
                 first_char_is_upper && is_not_stop_word
             })
             .collect();
 
-        let mut rng = rand::thread_rng();
-        if let Some(keyword) = keywords.choose(&mut rng) {
+        let rng = &mut self.rng;
+        if let Some(keyword) = keywords.choose(rng) {
             let templates = [
                 "Comment le concept de '{}' pourrait-il s'appliquer à un autre domaine, comme l'art ?",
                 "Quelles sont les implications éthiques de '{}' ?",
@@ -93,7 +112,7 @@ impl InnerDrive {
                 "Quel est le principe opposé à '{}' ?",
                 "Comment pourrais-je expliquer '{}' à un enfant ?"
             ];
-            templates.choose(&mut rng).map(|template| template.replace("{}", keyword))
+            templates.choose(rng).map(|template| template.replace("{}", keyword))
         } else {
             None
         }
@@ -1,15 +1,212 @@
+use crate::capabilities::Capability;
+use crate::pdf_text::{self, PdfError};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
 use std::io;
 use std::path::Path;
+use std::sync::OnceLock;
+use thiserror::Error;
 
+/// Reading documents, as listed by `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "documents",
+    description: "Learn from text, HTML and PDF documents, remembering the page of each passage",
+    description_fr: "Apprendre de documents texte, HTML et PDF, en retenant la page de chaque passage",
+    examples: &["Apprends le fichier cours.pdf"],
+};
+
+#[derive(Debug, Error)]
+pub enum DocumentError {
+    #[error("I/O error while reading the document: {0}")]
+    Io(#[from] io::Error),
+    #[error("unreadable PDF document: {0}")]
+    Pdf(#[from] PdfError),
+}
+
+/// Where a passage was read: the source file and, for paginated documents, the page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentLocation {
+    pub path: String,
+    /// 1-based page number (PDF documents only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+}
+
+/// A paragraph of a document, ready to be learned as one memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentPassage {
+    pub text: String,
+    pub location: DocumentLocation,
+}
+
+/// The paragraphs of a page. Documents without pages are a single page without a number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentPage {
+    pub number: Option<u32>,
+    pub paragraphs: Vec<String>,
+}
+
+/// Turns the bytes of a file of a given format into paragraphs.
+pub trait DocumentParser: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Whether this parser reads the file, judged from its path or its first bytes.
+    fn accepts(&self, path: &Path, bytes: &[u8]) -> bool;
+    fn parse(&self, bytes: &[u8]) -> Result<Vec<DocumentPage>, DocumentError>;
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension().and_then(|extension| extension.to_str()).is_some_and(|extension| extensions.iter().any(|e| extension.eq_ignore_ascii_case(e)))
+}
+
+/// Plain text, one concept per line (the format of the knowledge files of the repository).
+#[derive(Debug, Default)]
+pub struct PlainTextParser;
+
+impl DocumentParser for PlainTextParser {
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn accepts(&self, _path: &Path, _bytes: &[u8]) -> bool {
+        true
+    }
+
+    fn parse(&self, bytes: &[u8]) -> Result<Vec<DocumentPage>, DocumentError> {
+        let paragraphs = String::from_utf8_lossy(bytes).lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect();
+        Ok(vec![DocumentPage { number: None, paragraphs }])
+    }
+}
+
+/// HTML pages: scripts, styles and the head are dropped, block elements delimit paragraphs,
+/// the remaining tags are stripped and character entities decoded.
+#[derive(Debug, Default)]
+pub struct HtmlParser;
+
+impl HtmlParser {
+    /// The paragraphs of an HTML document.
+    pub fn paragraphs(html: &str) -> Vec<String> {
+        static PATTERNS: OnceLock<[Regex; 4]> = OnceLock::new();
+        let [hidden, block, tag, blank_line] = PATTERNS.get_or_init(|| {
+            [
+                Regex::new(r"(?is)<!--.*?-->|<script\b.*?</script\s*>|<style\b.*?</style\s*>|<noscript\b.*?</noscript\s*>|<head\b.*?</head\s*>").unwrap(),
+                Regex::new(r"(?i)</?(p|div|br|hr|h[1-6]|li|ul|ol|dl|dt|dd|tr|td|th|table|section|article|aside|blockquote|pre|header|footer|nav|main|figure|figcaption)\b[^>]*>").unwrap(),
+                Regex::new(r"(?s)<[^>]*>").unwrap(),
+                Regex::new(r"\n\s*\n").unwrap(),
+            ]
+        });
+        let text = hidden.replace_all(html, " ");
+        let text = block.replace_all(&text, "\n\n");
+        let text = tag.replace_all(&text, "");
+        blank_line
+            .split(&text)
+            .map(|paragraph| decode_entities(&paragraph.split_whitespace().collect::<Vec<_>>().join(" ")))
+            .map(|paragraph| paragraph.trim().to_string())
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect()
+    }
+}
+
+impl DocumentParser for HtmlParser {
+    fn name(&self) -> &'static str {
+        "html"
+    }
+
+    fn accepts(&self, path: &Path, bytes: &[u8]) -> bool {
+        let start = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).trim_start().to_lowercase();
+        has_extension(path, &["html", "htm", "xhtml"]) || start.starts_with("<!doctype html") || start.starts_with("<html")
+    }
+
+    fn parse(&self, bytes: &[u8]) -> Result<Vec<DocumentPage>, DocumentError> {
+        Ok(vec![DocumentPage { number: None, paragraphs: Self::paragraphs(&String::from_utf8_lossy(bytes)) }])
+    }
+}
+
+/// Decodes the numeric character references and the usual named entities.
+fn decode_entities(text: &str) -> String {
+    const NAMED: &[(&str, &str)] = &[
+        ("amp", "&"), ("lt", "<"), ("gt", ">"), ("quot", "\""), ("apos", "'"), ("nbsp", " "),
+        ("eacute", "é"), ("egrave", "è"), ("ecirc", "ê"), ("euml", "ë"), ("agrave", "à"), ("acirc", "â"),
+        ("ccedil", "ç"), ("icirc", "î"), ("iuml", "ï"), ("ocirc", "ô"), ("ucirc", "û"), ("ugrave", "ù"),
+        ("Eacute", "É"), ("Egrave", "È"), ("Agrave", "À"), ("Ccedil", "Ç"), ("oelig", "œ"),
+        ("laquo", "«"), ("raquo", "»"), ("lsquo", "‘"), ("rsquo", "’"), ("ldquo", "“"), ("rdquo", "”"),
+        ("hellip", "…"), ("mdash", "—"), ("ndash", "–"), ("deg", "°"), ("copy", "©"),
+    ];
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].find(';').filter(|end| *end <= 10).map(|end| &rest[1..end + 1]);
+        let replacement = entity.and_then(|entity| match entity.strip_prefix('#') {
+            Some(number) => {
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => number.parse().ok(),
+                };
+                code.and_then(char::from_u32).map(String::from)
+            }
+            None => NAMED.iter().find(|(name, _)| *name == entity).map(|(_, value)| value.to_string()),
+        });
+        match (entity, replacement) {
+            (Some(entity), Some(replacement)) => {
+                decoded.push_str(&replacement);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// PDF documents, read page by page with `pdf_text`.
+#[derive(Debug, Default)]
+pub struct PdfParser;
+
+impl DocumentParser for PdfParser {
+    fn name(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn accepts(&self, path: &Path, bytes: &[u8]) -> bool {
+        has_extension(path, &["pdf"]) || bytes.starts_with(b"%PDF")
+    }
+
+    fn parse(&self, bytes: &[u8]) -> Result<Vec<DocumentPage>, DocumentError> {
+        Ok(pdf_text::extract_pages(bytes)?.into_iter().map(|page| DocumentPage { number: Some(page.number), paragraphs: page.paragraphs }).collect())
+    }
+}
 
 /// Explores and processes large volumes of text data for the AGI.
 /// This module is designed to read knowledge from files, break it down into
-/// manageable concepts (e.g., sentences), and prepare it for holographic encoding,
+/// manageable concepts (paragraphs), and prepare it for holographic encoding,
 /// avoiding massive storage like traditional LLMs.
-#[derive(Debug, Default)]
+///
+/// The format of a file is recognized by the first registered `DocumentParser` that accepts it
+/// (PDF, then HTML, then plain text by default); each passage keeps where it was read.
 pub struct KnowledgeExplorer {
-    pub concepts: Vec<String>,
+    pub passages: Vec<DocumentPassage>,
+    parsers: Vec<Box<dyn DocumentParser>>,
+}
+
+impl fmt::Debug for KnowledgeExplorer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KnowledgeExplorer")
+            .field("passages", &self.passages.len())
+            .field("parsers", &self.parsers.iter().map(|parser| parser.name()).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Default for KnowledgeExplorer {
+    fn default() -> Self {
+        Self { passages: Vec::new(), parsers: vec![Box::new(PdfParser), Box::new(HtmlParser), Box::new(PlainTextParser)] }
+    }
 }
 
 impl KnowledgeExplorer {
@@ -18,35 +215,84 @@ impl KnowledgeExplorer {
         Self::default()
     }
 
-    /// Loads a text file and processes it into a list of concepts (sentences).
+    /// Adds a parser, tried before the ones already registered.
+    pub fn register_parser(&mut self, parser: Box<dyn DocumentParser>) {
+        self.parsers.insert(0, parser);
+    }
+
+    /// Loads a document and processes it into a list of passages (paragraphs).
     /// This is the first step in the non-traditional learning pipeline.
-    pub fn load_and_process_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
-        let file = fs::File::open(path)?;
-        let reader = io::BufReader::new(file);
-        use io::BufRead;
-
-        println!("--- KnowledgeExplorer: Processing file line-by-line... ---");
-
-        // Process the file line by line to handle massive files without high memory usage.
-        // We assume one concept per line for this scalable approach.
-        self.concepts = reader.lines()
-            .filter_map(io::Result::ok)
-            .map(|line| line.trim().to_string())
-            .filter(|line| !line.is_empty())
+    pub fn load_and_process_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), DocumentError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+        let parser = self.parsers.iter().find(|parser| parser.accepts(path, &bytes)).unwrap_or(&self.parsers[self.parsers.len() - 1]);
+
+        println!("--- KnowledgeExplorer: Processing file as {}... ---", parser.name());
+        let source = path.display().to_string();
+        self.passages = parser
+            .parse(&bytes)?
+            .into_iter()
+            .flat_map(|page| {
+                let location = DocumentLocation { path: source.clone(), page: page.number };
+                page.paragraphs.into_iter().map(move |text| DocumentPassage { text, location: location.clone() })
+            })
             .collect();
 
-        println!("   -> Extracted {} concepts.", self.concepts.len());
+        println!("   -> Extracted {} concepts.", self.passages.len());
         Ok(())
     }
 
     /// Returns a clone of the concepts discovered by the explorer.
     pub fn get_discovered_concepts(&self) -> Vec<String> {
-        self.concepts.clone()
+        self.passages.iter().map(|passage| passage.text.clone()).collect()
+    }
+
+    /// Returns a clone of the passages discovered by the explorer, with their location.
+    pub fn get_discovered_passages(&self) -> Vec<DocumentPassage> {
+        self.passages.clone()
     }
 
     /// Clears the list of discovered concepts.
     /// This is called after the concepts have been assimilated by the core.
     pub fn clear_discovered_concepts(&mut self) {
-        self.concepts.clear();
+        self.passages.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_and_pdf_documents_are_split_into_located_paragraphs() {
+        let html = "<html><head><title>Titre</title><style>p { color: red; }</style></head><body>\
+            <h1>Les quasars</h1><p>Le quasar est un noyau de galaxie\n très lumineux.</p>\
+            <script>var x = 1;</script><p>Il &eacute;met plus qu&#39;une galaxie enti&egrave;re &amp; plus.</p></body></html>";
+        assert_eq!(
+            HtmlParser::paragraphs(html),
+            vec!["Les quasars", "Le quasar est un noyau de galaxie très lumineux.", "Il émet plus qu'une galaxie entière & plus."]
+        );
+
+        let content = "BT /F1 12 Tf 72 720 Td (La cellule est l'unit\\351 de base) Tj 0 -14 Td (du vivant.) Tj \
+            0 -40 Td [(Elle est entour) -20 (\\351e d'une) -300 (membrane.)] TJ ET";
+        let pdf = format!(
+            "%PDF-1.4\n1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n\
+             2 0 obj << /Type /Pages /Kids [3 0 R] /Count 1 >> endobj\n\
+             3 0 obj << /Type /Page /Parent 2 0 R /Contents 4 0 R /Resources << /Font << /F1 5 0 R >> >> >> endobj\n\
+             4 0 obj << /Length {} >>\nstream\n{}\nendstream\nendobj\n\
+             5 0 obj << /Type /Font /Subtype /Type1 /BaseFont /Helvetica >> endobj\n\
+             trailer << /Root 1 0 R >>\n%%EOF",
+            content.len(),
+            content
+        );
+        let path = std::env::temp_dir().join(format!("knowledge_explorer_test_{}.pdf", std::process::id()));
+        fs::write(&path, pdf).unwrap();
+
+        let mut explorer = KnowledgeExplorer::new();
+        explorer.load_and_process_file(&path).unwrap();
+        fs::remove_file(&path).ok();
+        let texts = explorer.get_discovered_concepts();
+        assert_eq!(texts, vec!["La cellule est l'unité de base du vivant.", "Elle est entourée d'une membrane."]);
+        assert!(explorer.passages.iter().all(|passage| passage.location.page == Some(1) && passage.location.path.ends_with(".pdf")));
     }
 }
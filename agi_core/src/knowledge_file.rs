@@ -0,0 +1,92 @@
+//! knowledge_file.rs - Saving and restoring what the Core has learned.
+//!
+//! Holographic traces depend on the encoder's statistics at the time they were made, so the
+//! file stores what was taught rather than the traces themselves: memory texts (with their
+//! source, axiom flag and domains) and the parent links of the conceptual hierarchy. Loading
+//! re-learns them.
+
+use crate::knowledge_explorer::DocumentLocation;
+use crate::source_trust::SourceTag;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KnowledgeFileError {
+    #[error("I/O error on the knowledge file: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid knowledge file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryRecord {
+    pub text: String,
+    #[serde(default)]
+    pub is_axiom: bool,
+    /// The document the memory was read from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<DocumentLocation>,
+    /// When the memory was learned, in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub learned_at: Option<u64>,
+    /// Where the memory was learned; files without it are loaded as curated knowledge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceTag>,
+    /// The names of the domains the memory is filed under.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub domains: Vec<String>,
+}
+
+/// A parent link of the conceptual hierarchy, by concept name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RelationshipRecord {
+    pub child: String,
+    pub parent: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KnowledgeFile {
+    pub memories: Vec<MemoryRecord>,
+    #[serde(default)]
+    pub relationships: Vec<RelationshipRecord>,
+}
+
+impl KnowledgeFile {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, KnowledgeFileError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), KnowledgeFileError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files_round_trip_and_old_files_still_load() {
+        let path = std::env::temp_dir().join(format!("neurova_knowledge_file_{}.json", std::process::id()));
+        fs::write(&path, r#"{"memories": [{"text": "Le ciel est bleu."}]}"#).unwrap();
+        let old = KnowledgeFile::load(&path).unwrap();
+        assert_eq!(old.memories.len(), 1);
+        assert!(!old.memories[0].is_axiom && old.memories[0].source.is_none() && old.relationships.is_empty());
+
+        let file = KnowledgeFile {
+            memories: vec![MemoryRecord { is_axiom: true, domains: vec!["astronomie".to_string()], ..old.memories[0].clone() }],
+            relationships: vec![RelationshipRecord { child: "chien".to_string(), parent: "animal".to_string() }],
+        };
+        file.save(&path).unwrap();
+        assert_eq!(KnowledgeFile::load(&path).unwrap(), file);
+
+        fs::write(&path, "not json").unwrap();
+        assert!(matches!(KnowledgeFile::load(&path), Err(KnowledgeFileError::Json(_))));
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(KnowledgeFile::load(&path), Err(KnowledgeFileError::Io(_))));
+    }
+}
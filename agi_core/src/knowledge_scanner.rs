@@ -6,18 +6,61 @@
 //! Il ne télécharge jamais l'intégralité des données, mais en extrait une "signature informationnelle"
 //! pour un apprentissage holographique efficace et sans encombrement.
 
+use crate::rng::CoreRng;
+use crate::error::LockResultExt;
 use rand::{Rng, SeedableRng};
-use rand::rngs::StdRng;
+use regex::Regex;
 use reqwest::Client;
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
+use std::sync::Mutex;
 use thiserror::Error;
 
+/// Sections d'un article après lesquelles il n'y a plus que des listes de liens et de références.
+const TRAILING_SECTIONS: &[&str] = &[
+    "notes et références", "références", "notes", "voir aussi", "bibliographie", "liens externes", "articles connexes",
+    "references", "notes and references", "see also", "bibliography", "external links", "further reading", "sources",
+];
+
 /// Définit les types de sources de données que le scanner peut traiter.
 #[derive(Debug)]
 pub enum DataSource {
     Http { url: String },
     LocalFile { path: String },
+    /// Un article de Wikipédia, lu en entier par l'API publique. `lang` est le code de
+    /// l'édition ("fr", "en"...).
+    Wikipedia { title: String, lang: String },
+}
+
+impl DataSource {
+    /// Édition de Wikipédia utilisée quand la source n'en précise pas.
+    pub const DEFAULT_WIKIPEDIA_LANG: &'static str = "fr";
+
+    /// Interprète une chaîne comme un article de Wikipédia (`wikipedia:Quasar`,
+    /// `wikipedia:en:Quasar`), une URL (http/https) ou, à défaut, un chemin local.
+    pub fn parse(location: &str) -> Self {
+        let location = location.trim();
+        if let Some(article) = location.strip_prefix("wikipedia:") {
+            let (lang, title) = match article.split_once(':') {
+                Some((lang, title)) if lang.len() <= 3 && lang.chars().all(|c| c.is_ascii_lowercase()) => (lang, title),
+                _ => (Self::DEFAULT_WIKIPEDIA_LANG, article),
+            };
+            DataSource::Wikipedia { title: title.replace('_', " "), lang: lang.to_string() }
+        } else if location.starts_with("http://") || location.starts_with("https://") {
+            DataSource::Http { url: location.to_string() }
+        } else {
+            DataSource::LocalFile { path: location.to_string() }
+        }
+    }
+
+    /// La source telle que l'utilisateur peut la désigner (inverse de `parse`).
+    pub fn location(&self) -> String {
+        match self {
+            DataSource::Http { url } => url.clone(),
+            DataSource::LocalFile { path } => path.clone(),
+            DataSource::Wikipedia { title, lang } => format!("wikipedia:{}:{}", lang, title),
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -30,16 +73,41 @@ pub enum ScannerError {
     EmptySource,
     #[error("La taille de la source de données n'a pas pu être déterminée.")]
     UnknownSize,
+    #[error("L'article « {0} » n'existe pas sur Wikipédia.")]
+    ArticleNotFound(String),
 }
 
 /// Le scanner de connaissances.
 pub struct KnowledgeScanner {
     client: Client,
+    // Choisit les positions des fragments. Derrière un verrou car `scan` ne prend que `&self`.
+    rng: Mutex<CoreRng>,
 }
 
 impl KnowledgeScanner {
+    /// Nombre de fragments extraits par défaut d'une source.
+    pub const DEFAULT_FRAGMENTS: u32 = 20;
+    /// Taille par défaut de chaque fragment, en octets.
+    pub const DEFAULT_FRAGMENT_SIZE: u64 = 2048;
+    /// Nombre maximal de phrases retenues d'un article de Wikipédia.
+    pub const MAX_ARTICLE_SENTENCES: usize = 150;
+
     pub fn new() -> Self {
-        Self { client: Client::new() }
+        // L'API de Wikimédia refuse les clients sans User-Agent.
+        let client = Client::builder().user_agent(concat!("NeuroVA/", env!("CARGO_PKG_VERSION"), " (knowledge scanner)")).build().unwrap_or_default();
+        Self { client, rng: Mutex::new(CoreRng::from_entropy()) }
+    }
+
+    /// Remplace le flux aléatoire du scanner (voir `RngProvider`).
+    pub fn with_rng(self, rng: CoreRng) -> Self {
+        Self { rng: Mutex::new(rng), ..self }
+    }
+
+    /// Tire les positions de départ des fragments (avant toute requête, pour ne pas garder le verrou).
+    fn fragment_positions(&self, total_size: u64, fragment_size: u64, num_fragments: u32) -> Vec<u64> {
+        let max_pos = total_size - fragment_size;
+        let mut rng = self.rng.lock().recover();
+        (0..num_fragments).map(|_| rng.gen_range(0..=max_pos)).collect()
     }
 
     /// Scanne une source de données, en extrait des fragments et retourne une signature concaténée.
@@ -52,7 +120,56 @@ impl KnowledgeScanner {
         match source {
             DataSource::Http { url } => self.scan_http(url, num_fragments, fragment_size).await,
             DataSource::LocalFile { path } => self.scan_local(path, num_fragments, fragment_size),
+            DataSource::Wikipedia { title, lang } => Ok(self.fetch_wikipedia(title, lang).await?.join("\n")),
+        }
+    }
+
+    /// Comme `scan`, mais découpe ce qui peut l'être en phrases à apprendre une à une : un
+    /// article de Wikipédia donne ses phrases, les autres sources une signature unique.
+    pub async fn scan_sentences(
+        &self,
+        source: &DataSource,
+        num_fragments: u32,
+        fragment_size: u64,
+    ) -> Result<Vec<String>, ScannerError> {
+        match source {
+            DataSource::Wikipedia { title, lang } => self.fetch_wikipedia(title, lang).await,
+            _ => Ok(vec![self.scan(source, num_fragments, fragment_size).await?]),
+        }
+    }
+
+    /// Télécharge le texte brut d'un article (API `action=query&prop=extracts`) et le découpe en phrases.
+    async fn fetch_wikipedia(&self, title: &str, lang: &str) -> Result<Vec<String>, ScannerError> {
+        let url = format!("https://{}.wikipedia.org/w/api.php", lang);
+        let response: serde_json::Value = self
+            .client
+            .get(&url)
+            .query(&[
+                ("action", "query"),
+                ("prop", "extracts"),
+                ("explaintext", "1"),
+                ("redirects", "1"),
+                ("format", "json"),
+                ("formatversion", "2"),
+                ("titles", title),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let page = response["query"]["pages"].get(0).ok_or(ScannerError::EmptySource)?;
+        if page.get("missing").is_some() || page.get("invalid").is_some() {
+            return Err(ScannerError::ArticleNotFound(title.to_string()));
+        }
+        let extract = page["extract"].as_str().ok_or(ScannerError::EmptySource)?;
+        let mut sentences = article_sentences(extract);
+        sentences.truncate(Self::MAX_ARTICLE_SENTENCES);
+        if sentences.is_empty() {
+            return Err(ScannerError::EmptySource);
         }
+        Ok(sentences)
     }
 
     async fn scan_http(&self, url: &str, num_fragments: u32, fragment_size: u64) -> Result<String, ScannerError> {
@@ -73,11 +190,7 @@ impl KnowledgeScanner {
 
         // 2. Générer des positions de départ aléatoires et uniques.
         let mut signature = String::new();
-        let mut rng = StdRng::from_entropy();
-        for _ in 0..num_fragments {
-            let max_pos = total_size - fragment_size;
-            let random_pos = rng.gen_range(0..=max_pos);
-
+        for random_pos in self.fragment_positions(total_size, fragment_size, num_fragments) {
             // 3. Envoyer une requête GET avec un en-tête Range.
             let range_header = format!("bytes={}-{}", random_pos, random_pos + fragment_size - 1);
             let fragment_res = self.client.get(url).header("Range", range_header).send().await?;
@@ -100,13 +213,9 @@ impl KnowledgeScanner {
         }
 
         let mut signature = String::new();
-        let mut rng = StdRng::from_entropy();
         let mut buffer = vec![0; fragment_size as usize];
 
-        for _ in 0..num_fragments {
-            let max_pos = total_size - fragment_size;
-            let random_pos = rng.gen_range(0..=max_pos);
-
+        for random_pos in self.fragment_positions(total_size, fragment_size, num_fragments) {
             file.seek(SeekFrom::Start(random_pos))?;
             let bytes_read = file.read(&mut buffer)?;
             
@@ -125,3 +234,84 @@ impl Default for KnowledgeScanner {
         Self::new()
     }
 }
+
+/// Découpe le texte brut d'un article en phrases : les titres de section (« == Histoire == »)
+/// et les sections finales (références, liens) sont écartés, ainsi que les renvois (« [1] »),
+/// les balises restantes et les phrases trop courtes pour porter un fait.
+pub fn article_sentences(extract: &str) -> Vec<String> {
+    const MIN_WORDS: usize = 4;
+    let references = Regex::new(r"\[\d+\]|<[^>]+>").unwrap();
+
+    let mut paragraphs = Vec::new();
+    for line in extract.lines().map(str::trim) {
+        if let Some(heading) = line.strip_prefix("==") {
+            let heading = heading.trim_matches(|c: char| c == '=' || c.is_whitespace()).to_lowercase();
+            if TRAILING_SECTIONS.contains(&heading.as_str()) {
+                break;
+            }
+            continue;
+        }
+        if !line.is_empty() {
+            paragraphs.push(references.replace_all(line, "").split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+    }
+
+    paragraphs
+        .iter()
+        .flat_map(|paragraph| split_sentences(paragraph))
+        .filter(|sentence| sentence.split_whitespace().count() >= MIN_WORDS)
+        .collect()
+}
+
+/// Coupe un paragraphe après chaque « . », « ! » ou « ? » suivi d'une majuscule. Une initiale
+/// (« J. R. R. Tolkien ») ou une abréviation courante ne termine pas la phrase.
+fn split_sentences(paragraph: &str) -> Vec<String> {
+    const ABBREVIATIONS: &[&str] = &["av", "apr", "env", "cf", "etc", "M", "Mme", "Dr", "St", "vol", "p", "no", "Mr", "Mrs", "vs", "e.g", "i.e"];
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let chars: Vec<(usize, char)> = paragraph.char_indices().collect();
+    for (i, &(index, c)) in chars.iter().enumerate() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+        let next_is_capital = chars.get(i + 1).is_some_and(|(_, next)| next.is_whitespace()) && chars.get(i + 2).is_some_and(|(_, next)| next.is_uppercase());
+        if !next_is_capital {
+            continue;
+        }
+        let word_before = paragraph[start..index].rsplit(' ').next().unwrap_or_default();
+        if c == '.' && (word_before.chars().count() <= 1 || ABBREVIATIONS.contains(&word_before)) {
+            continue;
+        }
+        let end = index + c.len_utf8();
+        sentences.push(paragraph[start..end].trim().to_string());
+        start = end;
+    }
+    let rest = paragraph[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest.to_string());
+    }
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wikipedia_source_and_article_sentences() {
+        assert!(matches!(DataSource::parse("wikipedia:Quasar"), DataSource::Wikipedia { title, lang } if title == "Quasar" && lang == "fr"));
+        assert!(matches!(DataSource::parse("wikipedia:en:Black_hole"), DataSource::Wikipedia { title, lang } if title == "Black hole" && lang == "en"));
+        assert_eq!(DataSource::parse("wikipedia:en:Black_hole").location(), "wikipedia:en:Black hole");
+
+        let extract = "Un quasar est une galaxie très énergétique[1]. Il fut découvert par M. Schmidt en 1963.\n\n\
+                       == Histoire ==\nLe terme a été forgé par H. Chiu. Court.\n\n== Notes et références ==\nVoir J. Smith, 1999.";
+        assert_eq!(
+            article_sentences(extract),
+            vec![
+                "Un quasar est une galaxie très énergétique.",
+                "Il fut découvert par M. Schmidt en 1963.",
+                "Le terme a été forgé par H. Chiu.",
+            ]
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! knowledge_watcher.rs - Hot reload of the knowledge files.
+//!
+//! The knowledge files (knowledge.txt, identity.txt, corpus_fondamental/) are learned line by
+//! line at boot. `KnowledgeFiles` remembers the lines of each watched file and, when a file
+//! changes, returns the lines that appeared and those that disappeared: the Core learns the
+//! former and forgets the latter (`Core::apply_knowledge_delta`) instead of being restarted.
+//! A modified line is one removed line and one added line.
+//!
+//! With the `hot_reload` feature, `KnowledgeWatcher` drives the diff from file system events.
+
+#[cfg(feature = "hot_reload")]
+use crate::capabilities::Capability;
+use crate::domain_routing;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Only compiled with the watcher, which is what makes the reload automatic.
+#[cfg(feature = "hot_reload")]
+pub const CAPABILITY: Capability = Capability {
+    name: "knowledge_hot_reload",
+    description: "Pick up edits of my knowledge files without being restarted",
+    description_fr: "Prendre en compte les modifications de mes fichiers de connaissances sans redémarrer",
+    examples: &["(edit knowledge.txt while the server runs)"],
+};
+
+/// What changed in a knowledge file since it was last read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnowledgeDelta {
+    pub path: PathBuf,
+    /// Whether the lines of the file are learned as axioms.
+    pub is_axiom: bool,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// The domains annotated after added lines (see `domain_routing`), by line.
+    pub domains: HashMap<String, Vec<String>>,
+}
+
+impl KnowledgeDelta {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// A watched file, or a directory whose files are all watched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Source {
+    path: PathBuf,
+    is_axiom: bool,
+}
+
+/// The last known lines of the watched knowledge files.
+#[derive(Debug, Default)]
+pub struct KnowledgeFiles {
+    files: Vec<Source>,
+    directories: Vec<Source>,
+    lines: HashMap<PathBuf, Vec<String>>,
+    /// Files that could not be read when they were first seen: their next read is taken as
+    /// already learned rather than as new lines.
+    unread: HashSet<PathBuf>,
+}
+
+impl KnowledgeFiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Watches a file. Its current lines are taken as already learned.
+    pub fn watch_file<P: Into<PathBuf>>(&mut self, path: P, is_axiom: bool) {
+        let path = path.into();
+        self.record_lines(&path);
+        self.files.push(Source { path, is_axiom });
+    }
+
+    /// Watches the files of a directory (not its subdirectories), including those created later.
+    pub fn watch_directory<P: Into<PathBuf>>(&mut self, path: P, is_axiom: bool) {
+        let path = path.into();
+        if let Ok(entries) = fs::read_dir(&path) {
+            for file in entries.filter_map(Result::ok).map(|entry| entry.path()).filter(|path| path.is_file()) {
+                self.record_lines(&file);
+            }
+        }
+        self.directories.push(Source { path, is_axiom });
+    }
+
+    fn record_lines(&mut self, path: &Path) {
+        match read_knowledge_lines(path) {
+            Ok(lines) => {
+                self.lines.insert(path.to_path_buf(), lines);
+            }
+            Err(e) => {
+                eprintln!("KnowledgeFiles: could not read {}: {}", path.display(), e);
+                self.unread.insert(path.to_path_buf());
+            }
+        }
+    }
+
+    /// The directories to subscribe to for events: the watched directories and the parents of
+    /// the watched files (editors often replace a file rather than write it in place).
+    pub fn watch_roots(&self) -> Vec<PathBuf> {
+        let mut roots: Vec<PathBuf> = self.directories.iter().map(|source| source.path.clone()).collect();
+        for source in &self.files {
+            let parent = source.path.parent().map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+            if !roots.contains(&parent) {
+                roots.push(parent);
+            }
+        }
+        roots
+    }
+
+    /// Whether the lines of `path` are axioms, or None if the file is not watched.
+    pub fn source_of(&self, path: &Path) -> Option<bool> {
+        self.files
+            .iter()
+            .find(|source| source.path == path)
+            .or_else(|| self.directories.iter().find(|source| path.parent() == Some(source.path.as_path())))
+            .map(|source| source.is_axiom)
+    }
+
+    /// Reads `path` again and returns what changed since the last read, None if the file is not
+    /// watched or did not change. A deleted file removes all of its lines; a file that cannot be
+    /// read (being saved, or no longer readable) keeps them until it can be read again.
+    pub fn refresh(&mut self, path: &Path) -> Option<KnowledgeDelta> {
+        let is_axiom = self.source_of(path)?;
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                eprintln!("KnowledgeFiles: could not read {}, keeping its lines: {}", path.display(), e);
+                return None;
+            }
+        };
+        let current = knowledge_lines(&content);
+        if self.unread.remove(path) {
+            self.lines.insert(path.to_path_buf(), current);
+            return None;
+        }
+        let previous = self.lines.insert(path.to_path_buf(), current.clone()).unwrap_or_default();
+        let added = multiset_difference(&current, &previous);
+        let annotated: HashMap<String, Vec<String>> = domain_routing::annotated_facts(&content).into_iter().filter(|(_, domains)| !domains.is_empty()).collect();
+        let delta = KnowledgeDelta {
+            path: path.to_path_buf(),
+            is_axiom,
+            domains: added.iter().filter_map(|line| Some((line.clone(), annotated.get(line)?.clone()))).collect(),
+            added,
+            removed: multiset_difference(&previous, &current),
+        };
+        (!delta.is_empty()).then_some(delta)
+    }
+}
+
+/// The lines of a knowledge file that are learned: trimmed, neither empty nor `//` comments
+/// (the `// domains:` annotations of knowledge.txt). A missing file has no lines.
+pub fn read_knowledge_lines(path: &Path) -> io::Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(knowledge_lines(&content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+fn knowledge_lines(content: &str) -> Vec<String> {
+    content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with("//")).map(String::from).collect()
+}
+
+/// The lines of `a` not matched by a line of `b`, counting duplicates, in the order of `a`.
+fn multiset_difference(a: &[String], b: &[String]) -> Vec<String> {
+    let mut available: HashMap<&str, usize> = HashMap::new();
+    for line in b {
+        *available.entry(line.as_str()).or_default() += 1;
+    }
+    a.iter()
+        .filter(|line| match available.get_mut(line.as_str()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(feature = "hot_reload")]
+pub use watcher::KnowledgeWatcher;
+
+#[cfg(feature = "hot_reload")]
+mod watcher {
+    use super::{KnowledgeDelta, KnowledgeFiles};
+    use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    /// Turns the file system events on the knowledge files into deltas.
+    pub struct KnowledgeWatcher {
+        files: KnowledgeFiles,
+        events: mpsc::Receiver<notify::Result<Event>>,
+        // Dropping the watcher ends the subscription.
+        _watcher: RecommendedWatcher,
+    }
+
+    impl KnowledgeWatcher {
+        pub fn new(files: KnowledgeFiles) -> notify::Result<Self> {
+            let (sender, events) = mpsc::channel();
+            let mut watcher = notify::recommended_watcher(sender)?;
+            for root in files.watch_roots() {
+                watcher.watch(&root, RecursiveMode::NonRecursive)?;
+            }
+            Ok(Self { files, events, _watcher: watcher })
+        }
+
+        /// Blocks until a watched file changes, then waits for `debounce` without events (a save
+        /// is often several writes) and returns the deltas of the changed files. Returns None
+        /// once the watcher has stopped.
+        pub fn next_deltas(&mut self, debounce: Duration) -> Option<Vec<KnowledgeDelta>> {
+            loop {
+                let mut changed: Vec<PathBuf> = Vec::new();
+                self.collect(self.events.recv().ok()?, &mut changed);
+                let mut deadline = Instant::now() + debounce;
+                while let Ok(event) = self.events.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    self.collect(event, &mut changed);
+                    deadline = Instant::now() + debounce;
+                }
+                let deltas: Vec<KnowledgeDelta> = changed.iter().filter_map(|path| self.files.refresh(path)).collect();
+                if !deltas.is_empty() {
+                    return Some(deltas);
+                }
+            }
+        }
+
+        fn collect(&self, event: notify::Result<Event>, changed: &mut Vec<PathBuf>) {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    eprintln!("KnowledgeWatcher: {}", e);
+                    return;
+                }
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                return;
+            }
+            for path in event.paths {
+                if self.files.source_of(&path).is_some() && !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_returns_the_added_and_removed_lines() {
+        let dir = std::env::temp_dir().join(format!("neurova_knowledge_watcher_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("knowledge.txt");
+        fs::write(&file, "Le soleil est une étoile.\n    // domains: Astronomie\nLa lune tourne.\nLa lune tourne.\n").unwrap();
+
+        let mut files = KnowledgeFiles::new();
+        files.watch_directory(&dir, true);
+        assert_eq!(files.source_of(&file), Some(true));
+        assert_eq!(files.refresh(&file), None);
+
+        fs::write(&file, "Le soleil est une étoile.\nLa lune tourne autour de la Terre.\n// domains: Astronomie\nLa lune tourne.\n").unwrap();
+        let delta = files.refresh(&file).unwrap();
+        assert_eq!(delta.added, vec!["La lune tourne autour de la Terre."]);
+        assert_eq!(delta.removed, vec!["La lune tourne."]);
+        assert_eq!(delta.domains["La lune tourne autour de la Terre."], ["Astronomie"]);
+
+        fs::remove_file(&file).unwrap();
+        assert_eq!(files.refresh(&file).unwrap().removed.len(), 3);
+        assert_eq!(files.source_of(Path::new("/elsewhere/knowledge.txt")), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_an_unreadable_file_keeps_its_lines() {
+        let dir = std::env::temp_dir().join(format!("neurova_knowledge_watcher_unreadable_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("knowledge.txt");
+        fs::write(&file, "Le soleil est une étoile.\n").unwrap();
+        let mut files = KnowledgeFiles::new();
+        files.watch_file(&file, true);
+
+        // A directory in place of the file cannot be read as one.
+        fs::remove_file(&file).unwrap();
+        fs::create_dir(&file).unwrap();
+        assert_eq!(files.refresh(&file), None, "A read error is not a deletion");
+        fs::remove_dir(&file).unwrap();
+        fs::write(&file, "Le soleil est une étoile.\nLa lune tourne.\n").unwrap();
+        assert_eq!(files.refresh(&file).unwrap().added, vec!["La lune tourne."]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,4 +1,25 @@
-//! A simple rule-based French lemmatizer.
+//! A simple rule-based lemmatizer for French and English.
+//!
+//! French is the default language of the knowledge base; English words are recognised by a
+//! small heuristic detector so that "dogs" and "dog" map to the same concept.
+
+/// Languages the lemmatizer has rules for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Lang {
+    French,
+    English,
+}
+
+impl Lang {
+    /// ISO 639-1 code ("fr", "en").
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::French => "fr",
+            Lang::English => "en",
+        }
+    }
+}
 
 // A basic set of rules for French lemmatization.
 // This is a starting point and can be expanded significantly.
@@ -84,6 +105,157 @@ const RULES: &[(&str, &str)] = &[
     ("s", ""),             // e.g., "chats" -> "chat"
 ];
 
+// English suffix rules, longest first. Verb endings ("ing", "ed") are handled separately
+// because they need consonant undoubling ("running" -> "run").
+const ENGLISH_RULES: &[(&str, &str)] = &[
+    ("ational", "ate"),    // e.g., "relational" -> "relate"
+    ("izing", "ize"),      // e.g., "organizing" -> "organize"
+    ("ating", "ate"),      // e.g., "creating" -> "create"
+    ("nesses", ""),        // e.g., "kindnesses" -> "kind"
+    ("ized", "ize"),       // e.g., "organized" -> "organize"
+    ("ated", "ate"),       // e.g., "created" -> "create"
+    ("ness", ""),          // e.g., "kindness" -> "kind"
+    ("sses", "ss"),        // e.g., "classes" -> "class"
+    ("ches", "ch"),        // e.g., "churches" -> "church"
+    ("shes", "sh"),        // e.g., "wishes" -> "wish"
+    ("ies", "y"),          // e.g., "cities" -> "city"
+    ("ied", "y"),          // e.g., "carried" -> "carry"
+    ("xes", "x"),          // e.g., "boxes" -> "box"
+];
+
+// A suffix rule only applies if it leaves at least this many characters ("ties" -> "tie", not "ty").
+const ENGLISH_MIN_STEM: usize = 2;
+
+// Irregular forms, and words whose ending only looks like a suffix, checked before the rules.
+const ENGLISH_EXCEPTIONS: &[(&str, &str)] = &[
+    ("am", "be"), ("is", "be"), ("are", "be"), ("was", "be"), ("were", "be"), ("been", "be"),
+    ("has", "have"), ("had", "have"), ("does", "do"), ("did", "do"), ("done", "do"),
+    ("goes", "go"), ("went", "go"), ("gone", "go"),
+    ("news", "news"), ("species", "species"), ("series", "series"), ("this", "this"),
+    ("his", "his"), ("its", "its"), ("us", "us"), ("thus", "thus"), ("always", "always"),
+    ("perhaps", "perhaps"), ("physics", "physics"), ("mathematics", "mathematics"),
+];
+
+// Function words that strongly indicate one language or the other.
+const FRENCH_MARKERS: &[&str] = &[
+    "le", "la", "les", "des", "une", "est", "et", "du", "que", "qui", "pour", "dans", "pas", "sur",
+    "ce", "il", "elle", "nous", "vous", "je", "sont", "avec", "mais", "ou", "où", "au", "aux",
+    "bonjour", "salut", "merci", "comment", "pourquoi", "quel", "quelle",
+];
+const ENGLISH_MARKERS: &[&str] = &[
+    "the", "an", "is", "are", "of", "and", "to", "that", "it", "for", "with", "was", "this", "what",
+    "who", "how", "why", "which", "be", "have", "has", "from", "they", "you", "we", "not",
+    "hello", "hi", "thanks", "please", "bye", "does", "do",
+];
+
+/// Guesses the language of a text (or of a single word) from function words, accents and
+/// characteristic suffixes. Ambiguous input defaults to French.
+pub fn detect_language(text: &str) -> Lang {
+    detect_language_or(text, Lang::French)
+}
+
+/// Like `detect_language`, but returns `default` when the text gives no evidence either way
+/// ("ok", "Einstein ?"), e.g. the language of the conversation so far.
+pub fn detect_language_or(text: &str, default: Lang) -> Lang {
+    let mut french = 0i32;
+    let mut english = 0i32;
+
+    for token in text.split(|c: char| !c.is_alphanumeric() && c != '\'') {
+        let token = token.to_lowercase();
+        if token.is_empty() {
+            continue;
+        }
+        if FRENCH_MARKERS.contains(&token.as_str()) {
+            french += 2;
+        }
+        if ENGLISH_MARKERS.contains(&token.as_str()) || english_exception(&token).is_some() {
+            english += 2;
+        }
+        if token.chars().any(|c| "éèêëàâçùûüôîïœ".contains(c)) {
+            french += 1;
+        }
+        if token.ends_with("'s") || token.ends_with("n't") {
+            english += 2;
+        } else if token.len() > 4 && ["ing", "ed", "ness", "ies", "ly"].iter().any(|suffix| token.ends_with(suffix)) {
+            english += 1;
+        }
+    }
+
+    match english.cmp(&french) {
+        std::cmp::Ordering::Greater => Lang::English,
+        std::cmp::Ordering::Less => Lang::French,
+        std::cmp::Ordering::Equal => default,
+    }
+}
+
+/// Lemmatizes a word with the rules of the given language.
+pub fn lemmatize_with_lang(word: &str, lang: Lang) -> String {
+    match lang {
+        Lang::French => lemmatize(word),
+        Lang::English => lemmatize_english(word),
+    }
+}
+
+/// Lemmatizes a word of a text in `text_lang`: the word's own evidence ("running", "été")
+/// wins, and a word without any is lemmatized in the language of the text. Every caller goes
+/// through this one detection, so a word gets the same lemma wherever it is looked up.
+pub fn lemmatize_in(word: &str, text_lang: Lang) -> String {
+    lemmatize_with_lang(word, detect_language_or(word, text_lang))
+}
+
+/// Lemmatizes a word in the language detected for it, French when it gives no evidence.
+pub fn lemmatize_auto(word: &str) -> String {
+    lemmatize_in(word, Lang::French)
+}
+
+fn english_exception(word: &str) -> Option<&'static str> {
+    ENGLISH_EXCEPTIONS.iter().find(|(form, _)| *form == word).map(|(_, lemma)| *lemma)
+}
+
+fn lemmatize_english(word: &str) -> String {
+    if let Some(lemma) = english_exception(word) {
+        return lemma.to_string();
+    }
+    if word.len() <= 3 {
+        return word.to_string();
+    }
+
+    for (suffix, replacement) in ENGLISH_RULES.iter() {
+        if word.ends_with(suffix) && word.len() >= suffix.len() + ENGLISH_MIN_STEM {
+            return format!("{}{}", &word[..word.len() - suffix.len()], replacement);
+        }
+    }
+
+    for suffix in ["ing", "ed"] {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            // Keep words like "sing" or "red" whose "suffix" is part of the root.
+            if stem.chars().count() < 3 || !stem.chars().any(|c| "aeiouy".contains(c)) {
+                return word.to_string();
+            }
+            return undouble(stem);
+        }
+    }
+
+    if let Some(stem) = word.strip_suffix('s') {
+        if stem.chars().count() >= ENGLISH_MIN_STEM && !(stem.ends_with('s') || stem.ends_with('u') || stem.ends_with('i')) {
+            return stem.to_string();
+        }
+    }
+
+    word.to_string()
+}
+
+/// "runn" -> "run", "stopp" -> "stop"; "ll", "ss" and "zz" are kept ("falling" -> "fall").
+fn undouble(stem: &str) -> String {
+    let mut chars = stem.chars().rev();
+    match (chars.next(), chars.next()) {
+        (Some(last), Some(previous)) if last == previous && !"aeiouylsz".contains(last) => {
+            stem[..stem.len() - last.len_utf8()].to_string()
+        }
+        _ => stem.to_string(),
+    }
+}
+
 /// Lemmatizes a French word based on a simple set of suffix-replacement rules.
 pub fn lemmatize(word: &str) -> String {
     if word.len() <= 3 { // Avoid lemmatizing very short words
@@ -91,14 +263,55 @@ pub fn lemmatize(word: &str) -> String {
     }
 
     for (suffix, replacement) in RULES.iter() {
-        if word.ends_with(suffix) {
+        if let Some(stem) = word.strip_suffix(suffix) {
             // A very basic check to avoid over-lemmatization like "bus" -> "bu"
             if *suffix == "s" && word.ends_with("ss") {
                 continue;
             }
-            return format!("{}{}", &word[..word.len() - suffix.len()], replacement);
+            return format!("{}{}", stem, replacement);
         }
     }
 
     word.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_forms_share_a_lemma() {
+        for (form, lemma) in [("dogs", "dog"), ("running", "run"), ("stopped", "stop"), ("cities", "city"), ("classes", "class"), ("played", "play")] {
+            assert_eq!(lemmatize_with_lang(form, Lang::English), lemma, "{}", form);
+        }
+        assert_eq!(lemmatize_with_lang("sing", Lang::English), "sing");
+        assert_eq!(lemmatize_with_lang("chats", Lang::French), "chat");
+    }
+
+    #[test]
+    fn test_language_detection() {
+        assert_eq!(detect_language("The dogs are running in the park"), Lang::English);
+        assert_eq!(detect_language("Les chiens courent dans le parc"), Lang::French);
+        assert_eq!(detect_language("running"), Lang::English);
+        assert_eq!(detect_language("été"), Lang::French);
+        assert_eq!(lemmatize_auto("dogs"), lemmatize_auto("dog"));
+        assert_eq!(detect_language_or("Einstein ?", Lang::English), Lang::English);
+        assert_eq!(detect_language_or("Bonjour !", Lang::English), Lang::French);
+    }
+
+    #[test]
+    fn test_english_exceptions_are_not_over_stripped() {
+        for (form, lemma) in [("news", "news"), ("does", "do"), ("species", "species"), ("this", "this"), ("is", "be"), ("was", "be"), ("has", "have"), ("ties", "tie")] {
+            assert_eq!(lemmatize_with_lang(form, Lang::English), lemma, "{}", form);
+        }
+    }
+
+    #[test]
+    fn test_words_follow_their_own_evidence_then_the_text() {
+        assert_eq!(lemmatize_in("species", Lang::French), "species");
+        assert_eq!(lemmatize_in("running", Lang::French), "run");
+        assert_eq!(lemmatize_in("parlait", Lang::English), "parlait");
+        assert_eq!(lemmatize_in("parlait", Lang::French), "parler");
+        assert_eq!(lemmatize_auto("this"), lemmatize_in("this", Lang::English));
+    }
+}
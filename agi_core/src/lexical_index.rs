@@ -0,0 +1,179 @@
+//! lexical_index.rs - Keyword search over the memory texts.
+//!
+//! Holographic similarity finds memories by meaning, but a rare word of the prompt (a name, a
+//! term of art) weighs little in a superposition of many concepts, and the memory that mentions
+//! it can rank below vaguer ones. The hippocampus therefore keeps an inverted index of the
+//! lemmatized content words of every memory (stop words left out), scored with BM25, and `fuse` combines both
+//! rankings in `Hippocampus::find_similar_memories`: by reciprocal rank, or by a weighted sum of
+//! the normalized scores, as `FusionConfig` says.
+
+use crate::error::LockResultExt;
+use crate::lemmatizer;
+use crate::stop_words::SharedStopWords;
+use crate::tokenizer;
+use std::collections::{HashMap, HashSet};
+
+/// How the holographic and lexical rankings are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FusionMethod {
+    /// `weight / (rrf_k + rank)` summed over both rankings; only the ranks matter.
+    ReciprocalRank,
+    /// The holographic similarity and the BM25 score divided by the best one, weighted and summed.
+    WeightedSum,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FusionConfig {
+    pub method: FusionMethod,
+    pub holographic_weight: f32,
+    /// 0 ranks the memories by their traces only.
+    pub lexical_weight: f32,
+    /// Damping of the reciprocal ranks: the higher, the less the first ranks dominate.
+    pub rrf_k: f32,
+    /// BM25 saturation of the term frequency.
+    pub bm25_k1: f32,
+    /// BM25 normalization by the length of the memory, from 0 (none) to 1 (full).
+    pub bm25_b: f32,
+}
+
+impl Default for FusionConfig {
+    fn default() -> Self {
+        Self { method: FusionMethod::ReciprocalRank, holographic_weight: 1.0, lexical_weight: 1.0, rrf_k: 60.0, bm25_k1: 1.2, bm25_b: 0.75 }
+    }
+}
+
+/// Inverted index of the memory texts: term -> memory ID -> occurrences.
+#[derive(Debug, Clone)]
+pub struct LexicalIndex {
+    stop_words: SharedStopWords,
+    postings: HashMap<String, HashMap<u64, u32>>,
+    /// Terms of every indexed memory.
+    lengths: HashMap<u64, u32>,
+    total_length: u64,
+}
+
+impl LexicalIndex {
+    pub fn new(stop_words: SharedStopWords) -> Self {
+        Self { stop_words, postings: HashMap::new(), lengths: HashMap::new(), total_length: 0 }
+    }
+
+    /// The indexed terms of `text`: its content words that are not stop words, lemmatized.
+    pub fn terms(&self, text: &str) -> Vec<String> {
+        let stop_words = self.stop_words.read().recover();
+        tokenizer::content_words(text).iter().filter(|word| !stop_words.is_stop_word(word)).map(|word| lemmatizer::lemmatize_auto(word)).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.postings.clear();
+        self.lengths.clear();
+        self.total_length = 0;
+    }
+
+    /// Indexes the text of a memory.
+    pub fn insert(&mut self, memory_id: u64, text: &str) {
+        let terms = self.terms(text);
+        for term in &terms {
+            *self.postings.entry(term.clone()).or_default().entry(memory_id).or_insert(0) += 1;
+        }
+        self.lengths.insert(memory_id, terms.len() as u32);
+        self.total_length += terms.len() as u64;
+    }
+
+    /// BM25 score of every memory sharing a term with `query`; the others score 0.
+    pub fn scores(&self, query: &str, config: &FusionConfig) -> HashMap<u64, f32> {
+        let mut scores = HashMap::new();
+        if self.is_empty() {
+            return scores;
+        }
+        let documents = self.len() as f32;
+        let average_length = (self.total_length as f32 / documents).max(1.0);
+        let query_terms: HashSet<String> = self.terms(query).into_iter().collect();
+        for postings in query_terms.iter().filter_map(|term| self.postings.get(term)) {
+            let matching = postings.len() as f32;
+            let idf = (1.0 + (documents - matching + 0.5) / (matching + 0.5)).ln();
+            for (&memory_id, &occurrences) in postings {
+                let frequency = occurrences as f32;
+                let length = self.lengths.get(&memory_id).copied().unwrap_or(0) as f32;
+                let norm = config.bm25_k1 * (1.0 - config.bm25_b + config.bm25_b * length / average_length);
+                *scores.entry(memory_id).or_insert(0.0) += idf * frequency * (config.bm25_k1 + 1.0) / (frequency + norm);
+            }
+        }
+        scores
+    }
+}
+
+/// The fused relevance of memories sorted by holographic `distances` (ascending), whose BM25
+/// scores are `lexical` (0 for no shared term), in the same order. The higher, the better.
+pub fn fuse(config: &FusionConfig, distances: &[f32], lexical: &[f32]) -> Vec<f32> {
+    match config.method {
+        FusionMethod::ReciprocalRank => {
+            let mut by_score: Vec<usize> = (0..lexical.len()).filter(|&i| lexical[i] > 0.0).collect();
+            by_score.sort_by(|&a, &b| lexical[b].total_cmp(&lexical[a]));
+            let mut lexical_rank = vec![None; lexical.len()];
+            for (rank, &i) in by_score.iter().enumerate() {
+                lexical_rank[i] = Some(rank + 1);
+            }
+            lexical_rank
+                .iter()
+                .enumerate()
+                .map(|(i, rank)| {
+                    let holographic = config.holographic_weight / (config.rrf_k + (i + 1) as f32);
+                    holographic + rank.map_or(0.0, |rank| config.lexical_weight / (config.rrf_k + rank as f32))
+                })
+                .collect()
+        }
+        FusionMethod::WeightedSum => {
+            let best = lexical.iter().copied().fold(0.0, f32::max);
+            distances
+                .iter()
+                .zip(lexical)
+                .map(|(distance, score)| {
+                    let lexical = if best > 0.0 { score / best } else { 0.0 };
+                    config.holographic_weight * (1.0 - distance).clamp(0.0, 1.0) + config.lexical_weight * lexical
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stop_words::StopWordRegistry;
+
+    #[test]
+    fn test_bm25_favours_rare_terms_and_fusion_lifts_keyword_matches() {
+        let mut index = LexicalIndex::new(StopWordRegistry::new().shared());
+        let texts = ["Le chien aboie dans le jardin.", "Le chien dort dans sa niche.", "Le quasar est un noyau de galaxie très lumineux."];
+        for (id, text) in texts.iter().enumerate() {
+            index.insert(id as u64 + 1, text);
+        }
+        let config = FusionConfig::default();
+        let scores = index.scores("Que fait le chien dans le jardin ?", &config);
+        assert!(scores[&1] > scores[&2], "The memory matching both terms ranks first");
+        assert!(!scores.contains_key(&3));
+        let quasar = index.scores("Qu'est-ce qu'un quasar ?", &config);
+        assert_eq!(quasar.iter().max_by(|a, b| a.1.total_cmp(b.1)).map(|(id, _)| *id), Some(3));
+
+        // The quasar memory is third by its trace but the only keyword match: it comes first.
+        let distances = [0.2, 0.3, 0.6];
+        let lexical = [0.0, 0.0, 1.4];
+        for method in [FusionMethod::ReciprocalRank, FusionMethod::WeightedSum] {
+            let fused = fuse(&FusionConfig { method, ..config }, &distances, &lexical);
+            assert!(fused[2] > fused[0] && fused[0] > fused[1], "{:?}: {:?}", method, fused);
+        }
+        let traces_only = fuse(&FusionConfig { lexical_weight: 0.0, ..config }, &distances, &lexical);
+        assert!(traces_only[0] > traces_only[1] && traces_only[1] > traces_only[2]);
+
+        index.clear();
+        assert!(index.is_empty() && index.scores("chien", &config).is_empty());
+    }
+}
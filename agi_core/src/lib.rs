@@ -8,6 +8,8 @@
 pub mod neuron;
 
 pub mod connectome;
+pub mod synapse_matrix;
+pub mod connectome_builder;
 pub mod conceptual_hierarchy;
 pub mod quantum;
 pub mod thalamus;
@@ -37,57 +39,188 @@ pub mod direct_answer_extractor;
 pub mod personality;
 pub mod inner_drive;
 pub mod neurochemical_modulator;
-
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-
-// Helper function to read a file line by line
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
+pub mod expertise;
+pub mod core_actor;
+pub mod config;
+pub mod pipeline;
+pub mod embeddings;
+pub mod semantic_lexicon;
+pub mod tokenizer;
+pub mod stop_words;
+pub mod warmup;
+pub mod surface_realization;
+pub mod trace_visualizer;
+pub mod onboarding;
+pub mod metrics;
+pub mod knowledge_file;
+pub mod knowledge_watcher;
+pub mod rng;
+pub mod working_memory;
+pub mod coreference;
+pub mod sentence_composer;
+pub mod provenance;
+pub mod pdf_text;
+pub mod feed_watcher;
+pub mod sleep;
+pub mod feedback;
+pub mod episodic_memory;
+pub mod numeric_reasoner;
+pub mod units;
+pub mod tools;
+pub mod capabilities;
+pub mod conversation_log;
+pub mod clarification;
+pub mod spelling;
+pub mod activation_record;
+pub mod eeg;
+pub mod session;
+pub mod state_diff;
+pub mod trace_math;
+pub mod visual_features;
+pub mod role_binding;
+pub mod trace_compression;
+pub mod symbols;
+pub mod buffer_pool;
+pub mod bulk_assimilation;
+pub mod lexical_index;
+pub mod question_focus;
+pub mod answer_span;
+pub mod source_trust;
+pub mod domain_routing;
+pub mod quantum_readout;
+pub mod entanglement_map;
+pub mod quantum_backend;
+pub mod creative_writing;
+pub mod error;
+#[cfg(feature = "threshold_tuning")]
+pub mod threshold_tuner;
+#[cfg(feature = "tts")]
+pub mod speech;
+
+use std::io;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
 
 use connectome::Connectome;
+use connectome_builder::{ConnectomeBuilder, Topology};
 use std::sync::{Arc, Mutex, RwLock, atomic::Ordering};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use atomic_float::AtomicF32;
 
 pub use quantum::{Qubit, HadamardGate, OneQubitGate};
+pub use core_actor::{AsyncCore, CoreHandle, CoreSnapshot, SimulationClock};
 use thalamus::{QueryType, Thalamus};
 use hippocampus::Hippocampus;
-use quantum_gatekeeper::QuantumGatekeeper;
+use quantum_gatekeeper::{CognitiveMode, QuantumGatekeeper};
 use reasoning_engine::ReasoningEngine;
 use creativity_forge::CreativityForge;
 use sensory_cortex::SensoryCortex;
-use crate::motor_cortex::MotorCortex;
-use crate::self_awareness::SelfAwareness;
-use crate::knowledge_explorer::KnowledgeExplorer;
+use crate::motor_cortex::{MotorCortex, MotorStrategy};
+use crate::symbols::Sym;
+use crate::bulk_assimilation::{AssimilationProgress, AssimilationStage, AwakenProgress, AwakenReport, PreparedBatch, AWAKEN_BATCH_LINES};
+use crate::capabilities::CapabilityRegistry;
+use crate::self_awareness::{Identity, IdentityError, IdentityUpdate, SelfAwareness};
+use crate::knowledge_explorer::{DocumentError, DocumentLocation, KnowledgeExplorer};
 use crate::holographic_memory::{ConceptFocuser, HolographicEncoder};
 use crate::knowledge_scanner::{DataSource, KnowledgeScanner};
+use crate::curiosity_engine::{CuriosityEngine, ExplorationGoal, GapStatus};
+use crate::feed_watcher::FeedEntry;
+use crate::silicium::Silicium;
+use crate::sleep::{DreamEntry, DreamJournal, SleepReport};
+use crate::feedback::{Feedback, FeedbackError, FeedbackLog, FeedbackRecord, ResponseTrace};
+use crate::episodic_memory::{DiscussionEpisode, EpisodicIndex};
+use crate::conversation_log::{ConversationEntry, ConversationLog};
+use crate::spelling::SpellCorrector;
+use crate::activation_record::ActivationRecord;
+use crate::eeg::{EegRecorder, EegSpectrum};
+use crate::entanglement_map::EntanglementGraph;
+use crate::quantum_backend::QuantumBackend;
+use crate::creative_writing::{CreativeDraft, CreativeForm};
+use crate::session::{SessionError, SessionEvent, SessionRecorder};
+use crate::state_diff::{NeurochemicalLevels, SnapshotDiff, StateSnapshot, WeightDistribution};
 use crate::prefrontal_cortex::PrefrontalCortex;
-use crate::ethical_core::EthicalCore;
-use crate::conceptual_hierarchy::ConceptualHierarchy;
+use crate::ethical_core::{EthicalCore, EthicalJudgment};
+use crate::conceptual_hierarchy::{ConceptMerge, ConceptualHierarchy, GraphFormat, HierarchyError, SplitCriterion};
 use crate::social_cortex::SocialCortex;
-use crate::mcq_solver::McqSolver;
+use crate::personality::{PersonalityError, PersonalityProfile, PersonalityProfiles};
+use crate::mcq_solver::{MCQAnswer, McqSolver};
 use crate::inner_drive::InnerDrive;
+use crate::stop_words::StopWordRegistry;
+use crate::warmup::{ResponseCache, WarmupReport};
+use crate::onboarding::{FollowUp, Onboarding};
+use crate::clarification::PendingClarification;
+use crate::knowledge_file::{KnowledgeFile, KnowledgeFileError, MemoryRecord, RelationshipRecord};
+use crate::config::{CoreConfig, RetrievalParams, RetrievalProfile};
+use crate::rng::RngProvider;
+use crate::pipeline::{PipelineStage, StageClock};
+use crate::provenance::{AnswerPath, Composition};
+use crate::tools::{ToolHandler, ToolRegistry};
+use crate::error::{AgiError, AgiResult, LockResultExt};
 
 use crate::neurochemical_modulator::NeurochemicalModulator;
 
 
 use crate::holographic_memory::HolographicMemory;
+use crate::role_binding::{RecalledFiller, RelationalMemory, Role, Triple, IS_A};
+use crate::source_trust::SourceTag;
+
 
+/// What a learning operation added to the knowledge base.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct LearningOutcome {
+    pub memories_created: usize,
+    pub concepts_created: usize,
+}
+
+/// The result of the hot reload of a knowledge file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ReloadOutcome {
+    #[serde(flatten)]
+    pub learned: LearningOutcome,
+    pub memories_forgotten: usize,
+}
+
+/// The result of an exploration of a knowledge gap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct ExplorationOutcome {
+    pub gap_id: u64,
+    pub status: GapStatus,
+    #[serde(flatten)]
+    pub learned: LearningOutcome,
+}
+
+/// What the Core knows about a concept, in a few sentences (see `Core::summarize_concept`).
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ConceptSummary {
+    pub concept: String,
+    pub summary: String,
+    /// The memories the summary was drawn from, the most relevant first.
+    pub sources: Vec<SummarySource>,
+}
+
+/// A memory a summary was drawn from.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SummarySource {
+    pub memory_id: u64,
+    /// The concept the memory was gathered for: the summarized one or one of its children.
+    pub concept: String,
+    /// Share of the memory's trace carried by that concept.
+    pub weight: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<DocumentLocation>,
+}
 
 pub struct Core {
     mcq_solver: Option<McqSolver>,
+    pub config: CoreConfig,
 
     pub tick: u64,
     pub connectome: Connectome,
-    pub quantum_core: Vec<Qubit>,
+    pub quantum_core: Box<dyn QuantumBackend>,
     pub thalamus: Thalamus,
     pub hippocampus: Hippocampus,
+    /// Subject-relation-object structures bound into holographic traces.
+    pub relational_memory: RelationalMemory,
     pub gatekeeper: QuantumGatekeeper,
     pub reasoning_engine: Arc<Mutex<ReasoningEngine>>,
     pub creativity_forge: CreativityForge,
@@ -98,6 +231,8 @@ pub struct Core {
     pub motor_cortex: MotorCortex,
     pub knowledge_explorer: KnowledgeExplorer,
     pub knowledge_scanner: KnowledgeScanner,
+    /// What the conversation has shown interest in (drives what is learned from feeds).
+    pub curiosity_engine: CuriosityEngine,
     pub conceptual_hierarchy: ConceptualHierarchy,
         pub social_cortex: SocialCortex,
     pub neurochemical_modulator: NeurochemicalModulator,
@@ -110,10 +245,53 @@ pub struct Core {
     wakeup_stages: u32,
     current_wakeup_stage: u32,
     pub response_pending: bool,
+    /// Set once `warm_up` has run; front-ends report it as readiness.
+    pub ready: bool,
+    /// Set once `awaken` has run, even if it was cancelled.
+    awake: bool,
+    response_cache: ResponseCache,
+    /// The "unknown concept" conversation in progress, if any.
+    onboarding: Option<Onboarding>,
+    /// The question asked back about the last prompt, if any (see `clarification`).
+    clarification: Option<PendingClarification>,
+    /// Sources the user asked to scan; an administrator confirms them (see `take_pending_scans`).
+    pending_scans: Vec<DataSource>,
+    /// False while answering a caller who may not change what the Core knows
+    /// (see `get_response_for_reader`).
+    teachable: bool,
+    /// Thoughts synthesized during sleep, see `enter_sleep`.
+    dream_journal: DreamJournal,
+    feedback_log: FeedbackLog,
+    episodic_index: EpisodicIndex,
+    /// Every answered prompt, see `conversation_log`.
+    conversation_log: ConversationLog,
+    /// The vocabulary of the assimilated knowledge, to correct the typos of the prompts.
+    spell_corrector: SpellCorrector,
+    /// The personality profiles and the active one, see `set_personality_profile`.
+    personality_profiles: PersonalityProfiles,
+    /// Handlers of the actions the motor cortex may ask for, see `register_tool`.
+    tools: ToolRegistry,
+    next_response_id: u64,
+    /// Provenance of the current (or last) answer, see `explain_last_response`.
+    explanation: provenance::ResponseExplanation,
+    /// Language of the current answer: the language of the prompt, or of the previous turns
+    /// when the prompt gives no clue (starts as `CoreConfig::default_language`).
+    response_lang: lemmatizer::Lang,
+    /// Experiment mode: tunes the retrieval parameters online.
+    #[cfg(feature = "threshold_tuning")]
+    pub threshold_tuner: threshold_tuner::ThresholdTuner,
 
     last_response: Arc<Mutex<Option<String>>>,
     pub last_reasoning_result: Option<String>, // Stores the text of the last successful reasoning result.
     pub last_fired_neurons: Vec<u64>,
+    /// The activity set off by the last answered prompt, see `activation_record`.
+    activation_record: Option<ActivationRecord>,
+    /// The total potential of the last ticks, see `get_eeg_spectrum`.
+    eeg: EegRecorder,
+    /// How the core actor schedules the ticks, see `pause` and `set_tick_interval`.
+    clock: SimulationClock,
+    /// The recording of the session, see `start_recording`.
+    session_recorder: Option<SessionRecorder>,
 
     // Performance metrics
     pub processing_speed: Arc<AtomicF32>,
@@ -126,9 +304,60 @@ pub struct Core {
 impl Core {
     /// Assimilates a piece of text into the AGI's consciousness, with an option to treat it as a foundational axiom.
     pub fn learn_and_assimilate(&mut self, text: &str, is_axiom: bool) {
+        self.recorded(|core| core.assimilate_text(text, is_axiom), |_| SessionEvent::Assimilate { text: text.to_string(), is_axiom })
+    }
+
+    fn assimilate_text(&mut self, text: &str, is_axiom: bool) {
         // 1. Translate text into a list of neural stimuli.
-        let stimuli = self.sensory_cortex.process_text(text, &mut self.conceptual_hierarchy, &self.holographic_encoder.read().unwrap());
+        let stimuli = self.sensory_cortex.process_text(text, &mut self.conceptual_hierarchy, &self.holographic_encoder.read().recover());
+        self.engrave_stimuli(stimuli, is_axiom);
+
+        // 6. Now, encode the resulting neural activity pattern into a holographic trace,
+        //    and account for the new document in the TF-IDF statistics.
+        let trace = self.holographic_encoder.read().recover().encode(text);
+        self.holographic_encoder.write().recover().add_document(text);
+        self.spell_corrector.add_text(text);
+
+        self.store_assimilated(text, trace, SensoryCortex::extract_entities(text), is_axiom, self.default_source());
+        self.response_cache.clear();
+    }
+
+    /// Files the memory of `text` under `domains` (names of domain concepts, created if needed)
+    /// and links the concepts it mentions, stop words aside, to them. See `domain_routing`.
+    fn file_under_domains(&mut self, text: &str, domains: &[String]) {
+        if domains.is_empty() {
+            return;
+        }
+        // Lowercased like the concepts of the text, so that "Astronomie" is the concept "astronomie".
+        let domain_ids: Vec<u64> = domains.iter().map(|name| self.conceptual_hierarchy.find_or_create_concept(&name.to_lowercase())).collect();
+        let stop_words = self.holographic_encoder.read().recover().get_stop_words();
+        let stop_words = stop_words.read().recover();
+        let Some(memory) = self.hippocampus.holographic_memory.iter_mut().rev().find(|memory| memory.text == text) else {
+            return;
+        };
+        for &domain_id in &domain_ids {
+            if !memory.domains.contains(&domain_id) {
+                memory.domains.push(domain_id);
+            }
+            for &concept_id in &memory.concepts {
+                let is_stop_word = self.conceptual_hierarchy.get_concept(concept_id).is_some_and(|node| stop_words.is_stop_word(&node.name));
+                if concept_id != domain_id && !is_stop_word {
+                    self.conceptual_hierarchy.add_domain_to_concept(concept_id, domain_id);
+                }
+            }
+        }
+        println!("    -> Filed '{}' under the domains {}", text, domains.join(", "));
+    }
+
+    /// The source of what is learned without saying where it comes from: the foundations of the
+    /// Core until it is ready, the conversation after.
+    fn default_source(&self) -> SourceTag {
+        if self.ready { SourceTag::Conversational } else { SourceTag::Foundational }
+    }
 
+    /// Steps 2 to 5 of an assimilation: fires the stimuli of a text through the connectome and
+    /// potentiates the pathway they took.
+    fn engrave_stimuli(&mut self, stimuli: Vec<(u64, f32)>, is_axiom: bool) {
         // 2. Apply these stimuli to the connectome.
         for (neuron_id, strength) in stimuli {
             if let Some(neuron) = self.connectome.neurons.get_mut(neuron_id as usize) {
@@ -139,7 +368,7 @@ impl Core {
         }
 
         // 3. Force an immediate update to identify which neurons fired in response to the stimulus.
-        let active_ids_vec = self.connectome.update(self.tick);
+        let active_ids_vec = self.connectome.update(self.tick, self.neurochemical_modulator.connectome_modulation());
         let active_ids_set: std::collections::HashSet<u64> = active_ids_vec.into_iter().collect();
 
         // 4. Apply potentiation. Deeply engrave axioms, apply standard LTP for regular knowledge.
@@ -153,31 +382,266 @@ impl Core {
         for &neuron_id in &active_ids_set {
             self.connectome.propagate_signal_from(neuron_id);
         }
+    }
 
-        // 6. Now, encode the resulting neural activity pattern into a holographic trace.
-        let trace = self.holographic_encoder.read().unwrap().encode(text);
+    /// Step 7 of an assimilation: stores the trace in the hippocampus as a permanent memory,
+    /// along with the concepts and entities it mentions and the trust in its source. Returns the
+    /// id of the memory.
+    fn store_assimilated(
+        &mut self,
+        text: &str,
+        trace: holographic_memory::HolographicTrace,
+        entities: Vec<sensory_cortex::Entity>,
+        is_axiom: bool,
+        source: SourceTag,
+    ) -> u64 {
+        let concepts = self.sensory_cortex.concept_ids_for_text(text, &self.conceptual_hierarchy);
+        self.hippocampus.add_holographic_memory_with_concepts(text.to_string(), trace, is_axiom, concepts);
+        let memory = self.hippocampus.holographic_memory.last_mut().expect("a memory was just added");
+        memory.source = source;
+        memory.trust = self.config.trust.of(source);
+        // What the Core learns once it is ready is an episode of its life, not its foundations.
+        if self.ready {
+            memory.created_at = Some(episodic_memory::now());
+        }
+        let memory_id = memory.id;
+        self.hippocampus.tag_entities(memory_id, entities);
+        memory_id
+    }
 
-        // 7. Store this new trace in the hippocampus as a permanent memory.
-        self.hippocampus.add_holographic_memory(text.to_string(), trace, is_axiom);
+    /// Assimilates many lines, each as its own memory like `learn_and_assimilate`, but encodes
+    /// them in parallel before storing them in order (see `bulk_assimilation`). `progress` is
+    /// called as the lines are encoded, then as they are stored. Returns the ids of the new
+    /// memories, in the order of the lines, all tagged with `source`.
+    pub fn assimilate_batch<S: AsRef<str> + Sync>(&mut self, lines: &[S], is_axiom: bool, source: SourceTag, progress: impl FnMut(AssimilationProgress)) -> Vec<u64> {
+        self.recorded(
+            |core| core.assimilate_lines(lines, is_axiom, source, progress),
+            |_| SessionEvent::AssimilateBatch { texts: lines.iter().map(|line| line.as_ref().to_string()).collect(), is_axiom, source },
+        )
+    }
+
+    fn assimilate_lines<S: AsRef<str> + Sync>(&mut self, lines: &[S], is_axiom: bool, source: SourceTag, mut progress: impl FnMut(AssimilationProgress)) -> Vec<u64> {
+        if lines.is_empty() {
+            return Vec::new();
+        }
+        self.holographic_encoder.write().recover().add_documents(lines);
+        let PreparedBatch { texts, mut word_traces } =
+            bulk_assimilation::prepare(lines, &self.holographic_encoder.read().recover(), &self.conceptual_hierarchy, &mut progress);
+
+        let total = lines.len();
+        let mut memory_ids = Vec::with_capacity(total);
+        for (done, (line, prepared)) in lines.iter().zip(texts).enumerate() {
+            let text = line.as_ref();
+            let stimuli = {
+                let encoder = self.holographic_encoder.read().recover();
+                // A word missing from the batch traces shares its lemma with a word met earlier.
+                self.sensory_cortex.process_words(prepared.words, &mut self.conceptual_hierarchy, |word| {
+                    word_traces.remove(word).unwrap_or_else(|| encoder.encode(word))
+                })
+            };
+            self.engrave_stimuli(stimuli, is_axiom);
+            self.spell_corrector.add_text(text);
+            memory_ids.push(self.store_assimilated(text, prepared.trace, prepared.entities, is_axiom, source));
+            progress(AssimilationProgress { stage: AssimilationStage::Committing, done: done + 1, total });
+        }
+        self.response_cache.clear();
+        memory_ids
+    }
+
+    /// Learns a piece of text at runtime and reports what was created. Unlike `learn_and_assimilate`,
+    /// this also refreshes stale traces and the self-awareness summary, like a batch assimilation would.
+    pub fn learn(&mut self, text: &str, is_axiom: bool) -> LearningOutcome {
+        self.recorded(
+            |core| {
+                let before = core.knowledge_counts();
+                core.assimilate_text(text, is_axiom);
+                core.refresh_stale_traces();
+                core.self_awareness.update_knowledge_summary(&core.hippocampus);
+                core.outcome_since(before)
+            },
+            |_| SessionEvent::Learn { text: text.to_string(), is_axiom },
+        )
+    }
+
+    /// Learns several pieces of text (e.g. the sentences of an article) as separate memories
+    /// from `source`, refreshing stale traces once at the end.
+    pub fn learn_sentences(&mut self, sentences: &[String], is_axiom: bool, source: SourceTag) -> LearningOutcome {
+        let before = self.knowledge_counts();
+        self.assimilate_batch(sentences, is_axiom, source, |_| {});
+        self.refresh_stale_traces();
+        self.self_awareness.update_knowledge_summary(&self.hippocampus);
+        self.outcome_since(before)
+    }
+
+    /// The feed entries worth learning: those whose concepts interest the curiosity engine at
+    /// least `min_interest`, most interesting first, at most `max_entries`.
+    pub fn select_feed_entries(&self, entries: &[FeedEntry], min_interest: f32, max_entries: usize) -> Vec<FeedEntry> {
+        let encoder = self.holographic_encoder.read().recover();
+        let mut scored: Vec<(f32, &FeedEntry)> = entries
+            .iter()
+            .map(|entry| {
+                let concepts = encoder.distill_concepts(&format!("{}\n{}", entry.title, entry.summary));
+                (self.curiosity_engine.interest_in(concepts.iter().filter(|concept| !concept.contains(' '))), entry)
+            })
+            .filter(|(interest, _)| *interest >= min_interest)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored.into_iter().take(max_entries).map(|(_, entry)| entry.clone()).collect()
+    }
+
+    /// Learns the summaries of feed entries, sentence by sentence.
+    pub fn learn_feed_entries(&mut self, entries: &[FeedEntry]) -> LearningOutcome {
+        let sentences: Vec<String> = entries.iter().flat_map(FeedEntry::sentences).collect();
+        self.learn_sentences(&sentences, false, SourceTag::Web)
+    }
+
+    /// Like `learn_relationship`, but reports whether the link now exists and what was created.
+    pub fn teach_relationship(&mut self, child: &str, parent: &str) -> Result<(bool, LearningOutcome), HierarchyError> {
+        self.recorded(
+            |core| {
+                let before = core.knowledge_counts();
+                core.learn_relationship(child, parent)?;
+                let linked = match (core.conceptual_hierarchy.find_concept_by_name(child), core.conceptual_hierarchy.find_concept_by_name(parent)) {
+                    (Some(child), Some(parent)) => child.id != parent.id && child.parents.contains(&parent.id),
+                    _ => false,
+                };
+                Ok((linked, core.outcome_since(before)))
+            },
+            |_| SessionEvent::Relate { child: child.to_string(), parent: parent.to_string() },
+        )
+    }
+
+    /// Stores the facts held in the working memory for good, and empties it.
+    pub fn consolidate_working_memory(&mut self) -> LearningOutcome {
+        self.recorded(Self::consolidate_facts, |_| SessionEvent::Consolidate)
+    }
+
+    fn consolidate_facts(&mut self) -> LearningOutcome {
+        let before = self.knowledge_counts();
+        let facts = self.prefrontal_cortex.take_working_memory();
+        println!("--- Consolidating {} fact(s) from working memory ---", facts.len());
+        for fact in &facts {
+            self.learn_and_assimilate(fact, false);
+        }
+        if !facts.is_empty() {
+            self.refresh_stale_traces();
+            self.self_awareness.update_knowledge_summary(&self.hippocampus);
+        }
+        self.outcome_since(before)
+    }
+
+    fn knowledge_counts(&self) -> (usize, usize) {
+        (self.hippocampus.holographic_memory.len(), self.conceptual_hierarchy.get_all_concepts().len())
+    }
+
+    fn outcome_since(&self, (memories, concepts): (usize, usize)) -> LearningOutcome {
+        let (memories_now, concepts_now) = self.knowledge_counts();
+        LearningOutcome {
+            memories_created: memories_now.saturating_sub(memories),
+            concepts_created: concepts_now.saturating_sub(concepts),
+        }
     }
 
+    /// Saves the memories and the hierarchy links learned so far (see `knowledge_file`).
+    pub fn save_knowledge<P: AsRef<Path>>(&self, path: P) -> Result<KnowledgeFile, KnowledgeFileError> {
+        let file = self.knowledge_file();
+        file.save(path)?;
+        Ok(file)
+    }
+
+    fn knowledge_file(&self) -> KnowledgeFile {
+        let memories = self
+            .hippocampus
+            .holographic_memory
+            .iter()
+            .map(|memory| MemoryRecord {
+                text: memory.text.clone(),
+                is_axiom: memory.is_axiom,
+                location: memory.location.clone(),
+                learned_at: memory.created_at,
+                source: Some(memory.source),
+                domains: memory.domains.iter().filter_map(|&id| self.conceptual_hierarchy.get_concept(id)).map(|node| node.name.clone()).collect(),
+            })
+            .collect();
+        let mut relationships: Vec<RelationshipRecord> = self
+            .conceptual_hierarchy
+            .get_all_concepts()
+            .into_iter()
+            .flat_map(|node| {
+                node.parents.iter().filter_map(|id| self.conceptual_hierarchy.get_concept(*id)).map(|parent| RelationshipRecord {
+                    child: node.name.clone(),
+                    parent: parent.name.clone(),
+                })
+            })
+            .collect();
+        relationships.sort();
+        KnowledgeFile { memories, relationships }
+    }
+
+    /// What was taught, the distribution of the synaptic weights and the neurochemical levels,
+    /// to compare with another snapshot (see `state_diff`).
+    pub fn snapshot_state(&self) -> StateSnapshot {
+        StateSnapshot {
+            tick: self.tick,
+            knowledge: self.knowledge_file(),
+            synapses: WeightDistribution::of(self.connectome.synapses.weights()),
+            neurochemicals: NeurochemicalLevels::from(&self.neurochemical_modulator.state),
+        }
+    }
+
+    /// What changed from snapshot `a` to snapshot `b`.
+    pub fn diff_snapshots(a: &StateSnapshot, b: &StateSnapshot) -> SnapshotDiff {
+        SnapshotDiff::between(a, b)
+    }
 
+    /// Re-learns a file written by `save_knowledge`. Memories already known are skipped.
+    pub fn load_knowledge<P: AsRef<Path>>(&mut self, path: P) -> Result<LearningOutcome, KnowledgeFileError> {
+        let file = KnowledgeFile::load(path)?;
+        let before = self.knowledge_counts();
+
+        let known: std::collections::HashSet<String> = self.hippocampus.holographic_memory.iter().map(|memory| memory.text.clone()).collect();
+        let records: Vec<&MemoryRecord> = file.memories.iter().filter(|record| !known.contains(&record.text)).collect();
+        // Axioms and other memories are learned differently: batch the runs of each, in order.
+        // Files saved before the sources were tagged were given by an operator.
+        let source = |record: &MemoryRecord| record.source.unwrap_or(SourceTag::Curated);
+        for run in records.chunk_by(|a, b| (a.is_axiom, source(a)) == (b.is_axiom, source(b))) {
+            let texts: Vec<&str> = run.iter().map(|record| record.text.as_str()).collect();
+            let memory_ids = self.assimilate_batch(&texts, run[0].is_axiom, source(run[0]), |_| {});
+            let stored = self.hippocampus.holographic_memory.len() - memory_ids.len();
+            for (memory, record) in self.hippocampus.holographic_memory[stored..].iter_mut().zip(run) {
+                memory.location = record.location.clone();
+                memory.created_at = record.learned_at.or(memory.created_at);
+            }
+        }
+        for record in records.iter().filter(|record| !record.domains.is_empty()) {
+            self.file_under_domains(&record.text, &record.domains);
+        }
+        for relationship in &file.relationships {
+            if let Err(e) = self.learn_relationship(&relationship.child, &relationship.parent) {
+                eprintln!("Warning: skipping a relationship of the knowledge file: {}", e);
+            }
+        }
+        if !self.refresh_stale_traces() {
+            self.thalamus.rebuild_prototypes();
+        }
+        self.self_awareness.update_knowledge_summary(&self.hippocampus);
+        self.social_cortex.restore_user_model(file.memories.iter().map(|record| record.text.as_str()));
+        Ok(self.outcome_since(before))
+    }
 
     /// Apprend à partir d'une source de données externe en la scannant.
     ///
     /// Cette méthode utilise le KnowledgeScanner pour extraire une signature informationnelle
     /// d'une source (comme une URL ou un fichier local) sans la télécharger entièrement.
     /// La signature est ensuite traitée comme un souvenir unique et encodée holographiquement.
+    /// Un article de Wikipédia est appris phrase par phrase, chaque phrase devenant un souvenir.
     pub async fn learn_from_source(&mut self, source: &DataSource) {
         println!("--- Début de l'apprentissage par scan de source : {:?} ---", source);
-        const NUM_FRAGMENTS: u32 = 20; // Nombre de fragments à extraire
-        const FRAGMENT_SIZE: u64 = 2048; // Taille de chaque fragment en octets
-
-        match self.knowledge_scanner.scan(source, NUM_FRAGMENTS, FRAGMENT_SIZE).await {
-            Ok(signature) => {
-                println!("Scan réussi. Signature de {} octets générée. Début de l'encodage holographique.", signature.len());
-                // Nous utilisons la méthode d'apprentissage existante pour encoder la signature.
-                self.learn_and_assimilate(&signature, false);
+        match self.knowledge_scanner.scan_sentences(source, KnowledgeScanner::DEFAULT_FRAGMENTS, KnowledgeScanner::DEFAULT_FRAGMENT_SIZE).await {
+            Ok(sentences) => {
+                println!("Scan réussi. {} passage(s) extrait(s). Début de l'encodage holographique.", sentences.len());
+                // Nous utilisons la méthode d'apprentissage existante pour encoder chaque passage.
+                self.learn_sentences(&sentences, false, SourceTag::Web);
                 println!("--- Apprentissage par scan terminé avec succès. ---");
             }
             Err(e) => {
@@ -188,57 +652,167 @@ impl Core {
     }
 
     const HOLOGRAPHIC_DIMENSION: usize = 1024;
+    /// Neurons of the connectome of `new_for_tests`.
+    pub const TEST_NEURONS: usize = 256;
+
+pub fn new(_knowledge_file_path: Option<&str>) -> AgiResult<Self> {
+        Self::with_config(CoreConfig::default())
+    }
 
-pub fn new(_knowledge_file_path: Option<&str>) -> Self {
-        let concept_focuser = ConceptFocuser::new();
+    /// Creates a Core with an explicit configuration. See `CoreConfig`. Fails if the connectome
+    /// cannot be loaded; the other files of the configuration fall back to built-in defaults.
+    /// The Core knows nothing yet: `awaken` assimilates the foundational corpus.
+    pub fn with_config(config: CoreConfig) -> AgiResult<Self> {
         // Load the connectome from the binary file.
-        let manifest_dir = env!("CARGO_MANIFEST_DIR");
-        let workspace_root = Path::new(manifest_dir).parent().unwrap();
-        let connectome_path = workspace_root.join("quantized_connectome.bin");
+        let connectome_path = Self::workspace_root().join("quantized_connectome.bin");
+        let connectome = Connectome::from_binary(&connectome_path).map_err(|source| AgiError::Connectome { path: connectome_path.clone(), source })?;
+        let identity_path = config.identity_path.clone();
+        let mut new_core = Self::assemble(config, connectome, Some(identity_path));
+        new_core.finish_boot();
+        println!("--- AGI Core Initialized ---");
 
-        let connectome = Connectome::from_binary(&connectome_path)
-            .unwrap_or_else(|e| {
-                panic!("Failed to load connectome from {:?}. Did you run the 'gen_connectome' tool? Error: {}", connectome_path, e)
-            });
+        Ok(new_core)
+    }
+
+    /// The root of the workspace, where the connectome and the foundational corpus live.
+    fn workspace_root() -> &'static Path {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        manifest_dir.parent().unwrap_or(manifest_dir)
+    }
+
+    /// A Core for unit tests: a seeded, in-memory connectome of `TEST_NEURONS` neurons and no
+    /// file read or written (no foundational corpus, no identity, stop-word or log file). It
+    /// boots in milliseconds and knows nothing; `inject_memory` gives it memories without the
+    /// sensory pipeline.
+    pub fn new_for_tests() -> Self {
+        let config = CoreConfig { seed: Some(0), ..CoreConfig::default() };
+        let connectome = ConnectomeBuilder::new(Self::TEST_NEURONS)
+            .topology(Topology::SmallWorld { neighbors: 8, rewiring: 0.1 })
+            .with_rng(RngProvider::new(config.seed).stream("test_connectome"))
+            .build();
+        let mut core = Self::assemble(config, connectome, None);
+        core.finish_boot();
+        core
+    }
+
+    /// Stores `text` as a memory, encoded but not assimilated: no concept, no neural activity.
+    /// Returns the id of the memory.
+    pub fn inject_memory(&mut self, text: &str, is_axiom: bool) -> u64 {
+        // The document frequencies count the text first, as in `learn`: otherwise its new words
+        // would weigh nothing in its own trace.
+        self.holographic_encoder.write().recover().add_document(text);
+        let trace = self.holographic_encoder.read().recover().encode(text);
+        self.hippocampus.add_holographic_memory(text.to_string(), trace, is_axiom);
+        self.response_cache.clear();
+        self.hippocampus.holographic_memory.last().map(|memory| memory.id).unwrap_or_default()
+    }
+
+    /// Every component of a Core, wired to `connectome`, before any knowledge is assimilated.
+    /// The identity is loaded from (and saved to) `identity_store` if any.
+    fn assemble(config: CoreConfig, connectome: Connectome, identity_store: Option<PathBuf>) -> Self {
+        // One stop-word registry is shared by every component that filters words.
+        let mut stop_word_registry = StopWordRegistry::new();
+        if let Some(path) = &config.stop_words_path {
+            if let Err(e) = stop_word_registry.load_file(path) {
+                eprintln!("Warning: Could not load stop words from {:?}: {}. Using the built-in lists.", path, e);
+            }
+        }
+        let stop_words = stop_word_registry.shared();
+        let concept_focuser = ConceptFocuser::with_stop_words(Arc::clone(&stop_words));
+
+        // Every random stream derives from the configured seed, so that a seeded run can be replayed.
+        let rng = RngProvider::new(config.seed);
+        let connectome = connectome.with_rng(rng.stream("connectome")).with_stdp(config.stdp);
 
         // Initialize the Quantum Core with a set of qubits
         let num_qubits = Self::HOLOGRAPHIC_DIMENSION;
-        let mut quantum_core = (0..num_qubits).map(|_| Qubit::new()).collect::<Vec<_>>();
-        let hippocampus = Hippocampus::new();
+        let mut quantum_core = config.quantum_backend.build(num_qubits);
+        let hippocampus = Hippocampus::new().with_rng(rng.stream("hippocampus")).with_fusion(config.fusion).with_stop_words(Arc::clone(&stop_words));
 
         // Prime the AGI with core memories at boot.
-        hippocampus.replay_core_memories(&mut quantum_core);
+        hippocampus.replay_core_memories(quantum_core.as_mut());
 
-        let personality = personality::Personality::new();
+        let personality = personality::Personality::new().with_rng(rng.stream("personality"));
         let motor_cortex = MotorCortex::new(personality);
-        let reasoning_engine = Arc::new(Mutex::new(ReasoningEngine::new()));
-        let creativity_forge = CreativityForge::new();
-        let self_awareness = SelfAwareness::new("identity.txt", &hippocampus);
-        let inner_drive = InnerDrive::new(5); // Autonomous thoughts every 5 seconds.
+        let reasoning_engine = Arc::new(Mutex::new(ReasoningEngine::new().with_domain_routing(config.domain_routing)));
+        let creativity_forge = CreativityForge::new().with_rng(rng.stream("creativity_forge")).with_entanglement(config.entanglement);
+        let gatekeeper = QuantumGatekeeper::new().with_readout(config.quantum_readout).with_rng(rng.stream("quantum_gatekeeper"));
+        let self_awareness = match identity_store {
+            Some(store) => SelfAwareness::new(store, &hippocampus),
+            None => SelfAwareness::with_identity(Identity::default(), None, &hippocampus),
+        };
+        let inner_drive = InnerDrive::with_stop_words(5, Arc::clone(&stop_words)).with_rng(rng.stream("inner_drive")); // Autonomous thoughts every 5 seconds.
+
+        let mut encoder = HolographicEncoder::new(Self::HOLOGRAPHIC_DIMENSION);
+        encoder.focuser = concept_focuser.clone();
+        if let Some(path) = &config.word_vectors_path {
+            match embeddings::WordVectors::load(path, config.max_word_vectors) {
+                Ok(vectors) => encoder.set_embedding_provider(Arc::new(vectors)),
+                Err(e) => eprintln!("Warning: Could not load word vectors from {:?}: {}. Falling back to hashed concept waves.", path, e),
+            }
+        }
+        if let Some(path) = &config.lexicon_path {
+            if let Err(e) = encoder.load_lexicon_file(path) {
+                eprintln!("Warning: Could not load the semantic lexicon from {:?}: {}.", path, e);
+            }
+        }
+        let holographic_encoder = Arc::new(RwLock::new(encoder));
 
-        let holographic_encoder = Arc::new(RwLock::new(HolographicEncoder::new(Self::HOLOGRAPHIC_DIMENSION)));
+        let mut ethical_core = EthicalCore::new();
+        if let Some(path) = &config.ethics_policy_path {
+            if let Err(e) = ethical_core.load_file(path) {
+                eprintln!("Warning: Could not load the ethics policy from {:?}: {}. Using the built-in rules.", path, e);
+            }
+        }
+
+        let conversation_log = match &config.conversation_log_path {
+            Some(path) => ConversationLog::open(path).unwrap_or_else(|e| {
+                eprintln!("Warning: Could not read the conversation log {:?}: {}. The conversation will not be saved.", path, e);
+                ConversationLog::default()
+            }),
+            None => ConversationLog::default(),
+        };
 
+        let personality_profiles = match &config.personality_path {
+            Some(path) => PersonalityProfiles::load(path).unwrap_or_else(|e| {
+                eprintln!("Warning: Could not load the personality profiles from {:?}: {}. Using the default profile.", path, e);
+                PersonalityProfiles::default()
+            }),
+            None => PersonalityProfiles::default(),
+        };
+
+        let config_cache_capacity = config.response_cache_capacity;
+        let default_language = config.default_language;
+        #[cfg(feature = "threshold_tuning")]
+        let threshold_tuner = {
+            let mut tuning = config.tuning.clone();
+            tuning.seed = tuning.seed.or_else(|| rng.derived_seed("threshold_tuner"));
+            threshold_tuner::ThresholdTuner::new(tuning, config.retrieval)
+        };
         let mut new_core = Self {
             last_reasoning_result: None,
             mcq_solver: None, // Initialized to None, will be set later.
+            config,
 
             tick: 0,
             connectome,
             quantum_core,
             thalamus: Thalamus::new(Arc::clone(&holographic_encoder)),
             hippocampus,
-            gatekeeper: QuantumGatekeeper::new(),
+            relational_memory: RelationalMemory::new(Self::HOLOGRAPHIC_DIMENSION),
+            gatekeeper,
             reasoning_engine: Arc::clone(&reasoning_engine),
             prefrontal_cortex: PrefrontalCortex::new(concept_focuser.clone()),
-            ethical_core: EthicalCore::new(),
+            ethical_core,
             creativity_forge,
             self_awareness,
             sensory_cortex: SensoryCortex::new(),
             motor_cortex,
             knowledge_explorer: KnowledgeExplorer::new(),
-            knowledge_scanner: KnowledgeScanner::new(),
+            knowledge_scanner: KnowledgeScanner::new().with_rng(rng.stream("knowledge_scanner")),
+            curiosity_engine: CuriosityEngine::new(),
             conceptual_hierarchy: ConceptualHierarchy::new(),
-                        social_cortex: SocialCortex::new(),
+                        social_cortex: SocialCortex::new().with_rng(rng.stream("social_cortex")),
             neurochemical_modulator: NeurochemicalModulator::new(),
             direct_answer_extractor: direct_answer_extractor::DirectAnswerExtractor::new(),
             inner_drive,
@@ -249,94 +823,169 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
             wakeup_stages: 0,
             current_wakeup_stage: 0,
             response_pending: false,
+            ready: false,
+            awake: false,
+            response_cache: ResponseCache::new(config_cache_capacity),
+            onboarding: None,
+            clarification: None,
+            pending_scans: Vec::new(),
+            teachable: true,
+            response_lang: default_language,
+            explanation: provenance::ResponseExplanation::default(),
+            dream_journal: DreamJournal::default(),
+            feedback_log: FeedbackLog::default(),
+            episodic_index: EpisodicIndex::default(),
+            conversation_log,
+            spell_corrector: SpellCorrector::new(),
+            personality_profiles,
+            tools: ToolRegistry::new(),
+            next_response_id: 1,
+            #[cfg(feature = "threshold_tuning")]
+            threshold_tuner,
             last_response: Arc::new(Mutex::new(None)),
             last_fired_neurons: Vec::new(),
+            activation_record: None,
+            eeg: EegRecorder::new(),
+            clock: SimulationClock::default(),
+            session_recorder: None,
             processing_speed: Arc::new(AtomicF32::new(0.0)),
             power_draw: Arc::new(AtomicF32::new(0.0)),
             energy_this_measurement_period: 0.0,
             last_measurement_time: Instant::now(),
             ticks_this_measurement_period: 0,
         };
+        new_core.apply_neuromodulation();
+        new_core.self_awareness.set_capabilities(new_core.capabilities());
+        new_core.apply_personality_profile();
+        new_core
+    }
+
+    /// The Awakening Ritual: assimilates the foundational corpus (`corpus_fondamental/`) and the
+    /// knowledge.txt of the workspace, which `with_config` leaves to the front-end. `progress` is
+    /// called at the start of every file and as the lines are stored; returning
+    /// `ControlFlow::Break` cancels the ritual after the current batch, keeping what was learned.
+    /// A Core awakens once, cancelled or not: fails with `AgiError::AlreadyAwake` afterwards.
+    pub fn awaken(&mut self, progress: impl FnMut(&AwakenProgress) -> ControlFlow<()>) -> AgiResult<AwakenReport> {
+        if self.awake {
+            return Err(AgiError::AlreadyAwake);
+        }
+        let report = self.recorded(|core| core.run_awakening(Self::workspace_root(), progress), |report| SessionEvent::Awaken { lines: report.lines });
+        self.awake = true;
+        Ok(report)
+    }
 
-        // --- The Awakening Ritual: Assimilating the Foundational Corpus ---
+    fn run_awakening(&mut self, workspace_root: &Path, mut progress: impl FnMut(&AwakenProgress) -> ControlFlow<()>) -> AwakenReport {
         println!("\n--- The Awakening Ritual has begun. Assimilating foundational wisdom. ---");
-        let corpus_dir = workspace_root.join("corpus_fondamental");
-        if corpus_dir.is_dir() {
-            match std::fs::read_dir(corpus_dir) {
-                Ok(entries) => {
-                    for entry in entries {
-                        if let Ok(entry) = entry {
-                            let path = entry.path();
-                            if path.is_file() {
-                                println!("--- Reading from wisdom file: {:?} ---", path.file_name().unwrap_or_default());
-                                if let Ok(content) = std::fs::read_to_string(&path) {
-                                    for line in content.lines() {
-                                        if !line.trim().is_empty() {
-                                            new_core.learn_and_assimilate(line, true);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+        let corpus = Self::read_corpus(&workspace_root.join("corpus_fondamental"));
+        let knowledge = std::fs::read_to_string(workspace_root.join("knowledge.txt")).ok().map(|content| domain_routing::annotated_facts(&content));
+        let mut state = AwakenProgress {
+            files_total: corpus.len() + usize::from(knowledge.is_some()),
+            lines_total: corpus.iter().map(|(_, lines)| lines.len()).sum::<usize>() + knowledge.as_ref().map_or(0, Vec::len),
+            ..AwakenProgress::default()
+        };
+        let mut stop = false;
+        let mut cancelled = false;
+
+        'files: for (name, lines) in &corpus {
+            println!("--- Reading from wisdom file: {:?} ---", name);
+            state.file = name.clone();
+            stop |= progress(&state).is_break();
+            let stored = self.hippocampus.holographic_memory.len();
+            for batch in lines.chunks(AWAKEN_BATCH_LINES) {
+                if stop {
+                    cancelled = true;
+                    break 'files;
                 }
-                Err(e) => eprintln!("Warning: Could not read corpus_fondamental directory: {}. AGI will lack foundational wisdom.", e),
+                let lines_before = state.lines_done;
+                self.assimilate_batch(batch, true, SourceTag::Foundational, |batch_progress| {
+                    if batch_progress.stage == AssimilationStage::Committing {
+                        state.lines_done = lines_before + batch_progress.done;
+                        stop |= progress(&state).is_break();
+                    }
+                });
             }
-        } else {
-            eprintln!("Warning: 'corpus_fondamental' directory not found. AGI will lack foundational wisdom.");
+            // Hot reloads forget only what a file taught (see `apply_knowledge_delta`).
+            self.locate_memories_since(stored, &workspace_root.join("corpus_fondamental").join(name));
+            state.files_done += 1;
         }
 
-        let knowledge_path = workspace_root.join("knowledge.txt");
-        if knowledge_path.exists() {
+        if let Some(facts) = knowledge.filter(|_| !cancelled) {
             println!("--- The Awakening Ritual: Assimilating foundational knowledge from knowledge.txt... ---");
-            if let Ok(lines) = read_lines(&knowledge_path) {
-                let mut lines_iter = lines.filter_map(Result::ok).peekable();
-                while let Some(line) = lines_iter.next() {
-                    let fact_text = line.trim();
-                    if fact_text.is_empty() || fact_text.starts_with("//") {
-                        continue;
-                    }
-
-                    // 1. Assimilate the fact.
-                    new_core.learn_and_assimilate(fact_text, true);
-                    
-                    // Attempt to get the concept ID using the correct method.
-                    if let Some(fact_concept) = new_core.conceptual_hierarchy.find_concept_by_name(fact_text) {
-                        let fact_concept_id = fact_concept.id;
-                        // 2. Check if the next line is a domain comment.
-                        if let Some(true) = lines_iter.peek().map(|line| line.trim().starts_with("// domains:")) {
-                            // It is a domain comment, so we can safely consume it.
-                            if let Some(comment_line) = lines_iter.next() {
-                                let domains_str = comment_line.trim().replace("// domains:", "").trim().to_string();
-                                let domain_names: Vec<&str> = domains_str.split(',').map(|s| s.trim()).collect();
-
-                                for domain_name in domain_names {
-                                    if domain_name.is_empty() { continue; }
-                                    let domain_id = new_core.conceptual_hierarchy.find_or_create_concept(domain_name);
-                                    new_core.conceptual_hierarchy.add_domain_to_concept(fact_concept_id, domain_id);
-                                    println!("    -> Linked concept '{}' to domain '{}'", fact_text, domain_name);
-                                }
-                            }
-                        }
-                    }
+            state.file = "knowledge.txt".to_string();
+            stop |= progress(&state).is_break();
+            let stored = self.hippocampus.holographic_memory.len();
+            for (fact_text, domains) in &facts {
+                if stop {
+                    cancelled = true;
+                    break;
                 }
+
+                // 1. Assimilate the fact.
+                self.learn_and_assimilate(fact_text, true);
+                state.lines_done += 1;
+                stop |= progress(&state).is_break();
+
+                // 2. File it under the domains annotated after it.
+                self.file_under_domains(fact_text, domains);
+            }
+            self.locate_memories_since(stored, &workspace_root.join("knowledge.txt"));
+            if !cancelled {
+                state.files_done += 1;
             }
         }
 
-        println!("--- The Awakening Ritual is complete. ---");
+        // The document frequency map was built incrementally during the ritual; traces encoded
+        // early on saw an immature IDF, so re-encode them against the final statistics.
+        self.settle_knowledge();
+        if cancelled {
+            println!("--- The Awakening Ritual was cancelled after {} of {} lines. ---", state.lines_done, state.lines_total);
+        } else {
+            println!("--- The Awakening Ritual is complete. ---");
+        }
+        AwakenReport { files: state.files_done, lines: state.lines_done, cancelled }
+    }
 
-        // Now that all foundational memories are loaded, build the document frequency map for TF-IDF.
-        new_core.holographic_encoder.write().unwrap().build_document_frequency(&new_core.hippocampus.holographic_memory);
+    /// The non-empty lines of every file of the foundational corpus, by file name.
+    fn read_corpus(corpus_dir: &Path) -> Vec<(String, Vec<String>)> {
+        let entries = match std::fs::read_dir(corpus_dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("Warning: Could not read corpus_fondamental directory: {}. AGI will lack foundational wisdom.", e);
+                return Vec::new();
+            }
+        };
+        let mut corpus: Vec<(String, Vec<String>)> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter_map(|path| {
+                let content = std::fs::read_to_string(&path).ok()?;
+                let lines = content.lines().filter(|line| !line.trim().is_empty()).map(String::from).collect();
+                Some((path.file_name().unwrap_or_default().to_string_lossy().into_owned(), lines))
+            })
+            .collect();
+        // In a stable order, so that a recorded awakening replays the same lines.
+        corpus.sort_by(|a, b| a.0.cmp(&b.0));
+        corpus
+    }
 
-        // Rebuild Thalamus prototypes with the mature encoder.
-        new_core.thalamus.rebuild_prototypes();
+    /// The last steps of the boot, once the knowledge is assimilated.
+    fn finish_boot(&mut self) {
+        self.settle_knowledge();
 
         // Finally, create the MCQ solver with the fully initialized reasoning engine.
-        new_core.mcq_solver = Some(McqSolver::new(Arc::clone(&new_core.reasoning_engine)));
+        self.mcq_solver = Some(McqSolver::new(Arc::clone(&self.reasoning_engine), self.config.mcq));
+    }
 
-        println!("--- AGI Core Initialized ---");
+    /// Brings the traces and the user model up to date with the knowledge assimilated.
+    fn settle_knowledge(&mut self) {
+        if !self.refresh_stale_traces() {
+            // Rebuild Thalamus prototypes with the mature encoder.
+            self.thalamus.rebuild_prototypes();
+        }
 
-        new_core
+        // The name and the interests of the user were learned in an earlier session.
+        self.social_cortex.restore_user_model(self.hippocampus.holographic_memory.iter().map(|memory| memory.text.as_str()));
     }
 
     /// Rebuilds the Thalamus prototypes. This should be called after all initial knowledge
@@ -345,10 +994,621 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
         self.thalamus.rebuild_prototypes();
     }
 
+    /// Prepares the Core to answer quickly: re-encodes stale traces, rebuilds the Thalamus
+    /// prototypes, touches every stored trace and primes the response cache with the
+    /// configured warmup prompts. The conversational context is left as it was. Sets `ready`.
+    pub fn warm_up(&mut self) -> WarmupReport {
+        self.recorded(Self::prepare_caches, |_| SessionEvent::WarmUp)
+    }
+
+    fn prepare_caches(&mut self) -> WarmupReport {
+        let started = Instant::now();
+        println!("--- Warmup: preparing caches and prototypes... ---");
+
+        let traces_reencoded = self.refresh_stale_traces();
+        self.thalamus.rebuild_prototypes();
+
+        // Touch every trace so the first retrieval does not pay for cold memory.
+        let mut checksum = 0.0f32;
+        for memory in &self.hippocampus.holographic_memory {
+            checksum += memory.trace.superposition_pattern.iter().map(|c| c.norm_sqr()).sum::<f32>();
+        }
+        std::hint::black_box(checksum);
+        let traces_touched = self.hippocampus.holographic_memory.len();
+
+        let saved_context = self.prefrontal_cortex.clone();
+        let saved_reasoning_result = self.last_reasoning_result.clone();
+        let saved_onboarding = self.onboarding.take();
+        let saved_clarification = self.clarification.take();
+        let prompts = self.config.warmup_prompts.clone();
+        let mut prompts_primed = 0;
+        for prompt in &prompts {
+            if self.get_response_for_prompt(prompt).is_some() {
+                prompts_primed += 1;
+            }
+        }
+        self.prefrontal_cortex = saved_context;
+        self.last_reasoning_result = saved_reasoning_result;
+        self.onboarding = saved_onboarding;
+        self.clarification = saved_clarification;
+        self.motor_cortex.forget_recent_responses();
+
+        self.ready = true;
+        let report = WarmupReport { traces_reencoded, traces_touched, prompts_primed, duration: started.elapsed() };
+        println!("--- Warmup complete in {:?}: {} traces touched, {} prompts primed. ---", report.duration, traces_touched, prompts_primed);
+        report
+    }
+
+    /// Drops cached answers; must be called whenever memories or concepts change.
+    pub fn clear_response_cache(&mut self) {
+        self.response_cache.clear();
+    }
+
+    /// Edits the shared stop-word registry (e.g. to whitelist domain terms), then re-encodes the
+    /// stored traces so that they are filtered the same way as new input.
+    /// Fails without applying `update` if an earlier edit panicked halfway through.
+    pub fn update_stop_words<F: FnOnce(&mut StopWordRegistry)>(&mut self, update: F) -> AgiResult<()> {
+        let stop_words = self.holographic_encoder.read().recover().get_stop_words();
+        update(&mut *stop_words.write().or_poisoned("stop words")?);
+        self.holographic_encoder.write().recover().invalidate_traces();
+        self.refresh_stale_traces();
+        Ok(())
+    }
+
+    /// Re-encodes every stored memory trace if the IDF statistics have drifted too far since
+    /// they were encoded, then rebuilds the Thalamus prototypes. Returns whether a refresh happened.
+    pub fn refresh_stale_traces(&mut self) -> bool {
+        if !self.holographic_encoder.read().recover().needs_reencode() {
+            return false;
+        }
+
+        {
+            let encoder = self.holographic_encoder.read().recover();
+            println!("--- IDF drift {:.2}: re-encoding {} memory traces... ---", encoder.idf_drift(), self.hippocampus.holographic_memory.len());
+            for memory in self.hippocampus.holographic_memory.iter_mut() {
+                memory.trace = encoder.encode_memory(&memory.text, memory.image.as_ref());
+            }
+        }
+        self.holographic_encoder.write().recover().mark_traces_current();
+        self.thalamus.rebuild_prototypes();
+        self.response_cache.clear();
+        true
+    }
+
+    /// Switches the encoder to a new embedding backend and re-encodes all stored traces in its space.
+    pub fn set_embedding_provider(&mut self, provider: Arc<dyn embeddings::EmbeddingProvider>) {
+        self.holographic_encoder.write().recover().set_embedding_provider(provider);
+        self.refresh_stale_traces();
+    }
+
+    /// Forgets every memory with the given text, keeping the TF-IDF statistics in sync.
+    /// Returns the number of memories removed.
+    pub fn forget_memory(&mut self, text: &str) -> usize {
+        let removed = self.hippocampus.remove_memories_with_text(text);
+        for _ in 0..removed {
+            self.forget_document(text);
+        }
+        removed
+    }
+
+    /// Takes a removed memory out of the TF-IDF statistics and the spelling vocabulary.
+    fn forget_document(&mut self, text: &str) {
+        self.response_cache.clear();
+        self.holographic_encoder.write().recover().remove_document(text);
+        self.spell_corrector.remove_text(text);
+    }
+
+    /// Marks the memories stored after the first `stored` as read from `path`, unless they
+    /// already say where they come from.
+    fn locate_memories_since(&mut self, stored: usize, path: &Path) {
+        let location = DocumentLocation { path: path.to_string_lossy().into_owned(), page: None };
+        for memory in self.hippocampus.holographic_memory.iter_mut().skip(stored) {
+            memory.location.get_or_insert_with(|| location.clone());
+        }
+    }
+
+    /// Applies the changes of a knowledge file at runtime: the memories of its removed lines
+    /// are forgotten, its new lines learned and filed under their domains. See `knowledge_watcher`.
+    ///
+    /// A removed line forgets one memory read from that file: a duplicate line still in it, the
+    /// same text in another file or taught through the API are kept.
+    pub fn apply_knowledge_delta(&mut self, delta: &knowledge_watcher::KnowledgeDelta) -> ReloadOutcome {
+        let path = delta.path.to_string_lossy();
+        let mut memories_forgotten = 0;
+        for line in &delta.removed {
+            if self.hippocampus.remove_memory_read_from(line, &path) {
+                self.forget_document(line);
+                memories_forgotten += 1;
+            }
+        }
+        let stored = self.hippocampus.holographic_memory.len();
+        let learned = if delta.added.is_empty() { LearningOutcome::default() } else { self.learn_sentences(&delta.added, delta.is_axiom, SourceTag::Foundational) };
+        self.locate_memories_since(stored, &delta.path);
+        for (line, domains) in &delta.domains {
+            self.file_under_domains(line, domains);
+        }
+        if memories_forgotten > 0 && delta.added.is_empty() {
+            self.self_awareness.update_knowledge_summary(&self.hippocampus);
+        }
+        println!(
+            "--- Hot reload of {:?}: {} memories learned, {} forgotten ---",
+            delta.path, learned.memories_created, memories_forgotten
+        );
+        ReloadOutcome { learned, memories_forgotten }
+    }
+
+    /// The structured identity answered to introspective questions.
+    pub fn identity(&self) -> &Identity {
+        self.self_awareness.identity()
+    }
+
+    /// Changes attributes of the identity and saves it to `CoreConfig::identity_path`.
+    pub fn update_identity(&mut self, update: IdentityUpdate) -> Result<Identity, IdentityError> {
+        let identity = self.self_awareness.update_identity(update)?.clone();
+        // Cached introspective answers would still give the former identity.
+        self.response_cache.clear();
+        Ok(identity)
+    }
+
+    /// What the Core knows about the person it talks to.
+    pub fn user_model(&self) -> &social_cortex::UserModel {
+        self.social_cortex.user_model()
+    }
+
+    /// The personality profiles, with the name of the active one.
+    pub fn personality_profiles(&self) -> &PersonalityProfiles {
+        &self.personality_profiles
+    }
+
+    /// Makes one of the loaded personality profiles the active one.
+    pub fn activate_personality(&mut self, name: &str) -> Result<PersonalityProfile, PersonalityError> {
+        let profile = self.personality_profiles.activate(name)?.clone();
+        self.apply_personality_profile();
+        Ok(profile)
+    }
+
+    /// Adds a personality profile (replacing the one with the same name) and makes it active.
+    pub fn set_personality_profile(&mut self, profile: PersonalityProfile) -> PersonalityProfile {
+        self.personality_profiles.active = profile.name.clone();
+        self.personality_profiles.insert(profile);
+        self.apply_personality_profile();
+        self.personality_profiles.active().clone()
+    }
+
+    /// `text` (usually an answer) to be spoken in its language, with the voice of the active
+    /// profile. `Utterance::speak` runs the engine, best away from the core actor.
+    #[cfg(feature = "tts")]
+    pub fn utterance(&self, text: &str) -> speech::Utterance {
+        let lang = lemmatizer::detect_language_or(text, self.response_lang);
+        self.motor_cortex.utterance(text, lang, &self.config.speech)
+    }
+
+    /// Hands the active profile to the cortices that phrase the answers.
+    fn apply_personality_profile(&mut self) {
+        let profile = self.personality_profiles.active().clone();
+        if let Some(lang) = profile.preferred_language {
+            self.response_lang = lang;
+        }
+        self.motor_cortex.set_personality_profile(profile.clone());
+        self.social_cortex.set_profile(profile);
+        // Cached answers were styled with the previous profile.
+        self.response_cache.clear();
+    }
+
+    /// What this Core can do: the capabilities of its compiled modules and registered tools.
+    pub fn capabilities(&self) -> CapabilityRegistry {
+        let mut registry = CapabilityRegistry::builtin();
+        for kind in self.tools.kinds() {
+            registry.register(kind.capability());
+        }
+        registry
+    }
+
+    /// Binds a picture (PNG, JPEG...) to the text describing it ("Un chat roux sur un canapé.")
+    /// in one cross-modal memory: the text is assimilated like any other, and the trace of its
+    /// memory combines the traces of both, so that `cross_modal_recall` finds it from the text
+    /// or from a similar picture. Returns the id of the memory.
+    pub fn associate(&mut self, text: &str, image: &[u8]) -> AgiResult<u64> {
+        let (features, trace) = self.image_trace(image)?;
+        self.assimilate_text(text, false);
+        let memory = self.hippocampus.holographic_memory.last_mut().ok_or(AgiError::NotInitialized("hippocampus"))?;
+        memory.trace.combine_with(&trace);
+        memory.image = Some(visual_features::BoundImage { features, trace, bytes: image.into() });
+        Ok(memory.id)
+    }
+
+    fn image_trace(&self, image: &[u8]) -> AgiResult<(visual_features::ImageFeatures, holographic_memory::HolographicTrace)> {
+        self.sensory_cortex
+            .process_image(image, &self.holographic_encoder.read().recover())
+            .map_err(|e| AgiError::Format(format!("unreadable image: {}", e)))
+    }
+
+    /// The `k` memories closest to a text or a picture, best first. A text finds the pictures
+    /// bound to the memories it matches, a picture the texts of the memories whose pictures look
+    /// like it; each match tells which of its modalities the query is closest to.
+    pub fn cross_modal_recall(&self, query: visual_features::ModalQuery, k: usize) -> AgiResult<Vec<visual_features::CrossModalMatch>> {
+        use visual_features::{CrossModalMatch, ModalQuery, Modality};
+
+        let trace = match query {
+            ModalQuery::Text(text) => self.holographic_encoder.read().recover().encode(text),
+            ModalQuery::Image(image) => self.image_trace(image)?.1,
+        };
+        let projected = self.hippocampus.projected_query(&trace);
+        let mut ranked: Vec<_> = self
+            .hippocampus
+            .holographic_memory
+            .iter()
+            .map(|memory| (memory, self.hippocampus.similarity(&trace, projected.as_ref(), &memory.trace)))
+            .filter(|(_, similarity)| !similarity.is_nan())
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        // Only the matches returned are split by modality: the text traces are encoded again.
+        let encoder = self.holographic_encoder.read().recover();
+        Ok(ranked
+            .into_iter()
+            .take(k)
+            .map(|(memory, similarity)| {
+                let text_similarity = trace.cosine_similarity(&encoder.encode(&memory.text));
+                let image_similarity = memory.image.as_ref().map(|image| trace.cosine_similarity(&image.trace));
+                let matched = match image_similarity {
+                    Some(image_similarity) if image_similarity > text_similarity => Modality::Image,
+                    _ => Modality::Text,
+                };
+                CrossModalMatch {
+                    memory_id: memory.id,
+                    text: memory.text.clone(),
+                    similarity,
+                    matched,
+                    text_similarity,
+                    image_similarity,
+                    concepts: memory.concepts.iter().filter_map(|id| self.conceptual_hierarchy.get_concept(*id).map(|node| node.name.clone())).collect(),
+                    image: memory.image.as_ref().map(|image| Arc::clone(&image.bytes)),
+                }
+            })
+            .collect())
+    }
+
+    /// Returns the `k` memories closest to `memory_id`, with their shared concepts named.
+    pub fn related_memories(&self, memory_id: u64, k: usize) -> Option<Vec<hippocampus::RelatedMemory>> {
+        let neighbors = self.hippocampus.neighbors(memory_id, k)?;
+        Some(
+            neighbors
+                .into_iter()
+                .map(|neighbor| hippocampus::RelatedMemory {
+                    shared_concept_names: neighbor
+                        .shared_concepts
+                        .iter()
+                        .filter_map(|id| self.conceptual_hierarchy.get_concept(*id).map(|node| node.name.clone()))
+                        .collect(),
+                    neighbor,
+                })
+                .collect(),
+        )
+    }
+
+    /// Compresses the memory traces (see `trace_compression`): light concepts are pruned, and
+    /// the memories unused for `config.cold_after_secs` get a projected trace, re-encoded at full
+    /// size when they contribute to an answer again (or when all traces are re-encoded).
+    pub fn compress_traces(&mut self, config: &trace_compression::CompressionConfig) -> trace_compression::CompressionReport {
+        let report = self.hippocampus.compress(config, episodic_memory::now());
+        self.response_cache.clear();
+        println!(
+            "--- Trace compression: {} concepts pruned, {} memories compressed, {} -> {} bytes ---",
+            report.concepts_pruned, report.memories_compressed, report.bytes_before, report.bytes_after
+        );
+        report
+    }
+
+    /// The approximate heap usage of the main subsystems.
+    pub fn memory_usage(&self) -> trace_compression::MemoryUsageReport {
+        use std::mem::{size_of, size_of_val};
+
+        let mut report = trace_compression::MemoryUsageReport { compressed_memories: self.hippocampus.compressed_count(), ..Default::default() };
+        let memories = &self.hippocampus.holographic_memory;
+        let texts: usize = memories.iter().map(|memory| size_of::<holographic_memory::HolographicMemory>() + memory.text.len()).sum();
+        let pictures: usize = memories.iter().filter_map(|memory| memory.image.as_ref()).map(|image| image.bytes.len()).sum();
+        report.push("hippocampus", memories.len(), texts + pictures + self.hippocampus.trace_bytes());
+
+        let concepts = self.conceptual_hierarchy.get_all_concepts();
+        let concept_bytes = concepts
+            .iter()
+            .map(|node| size_of::<conceptual_hierarchy::ConceptNode>() + node.name.len() + trace_compression::trace_bytes(&node.trace))
+            .sum();
+        report.push("conceptual_hierarchy", concepts.len(), concept_bytes);
+        report.push("relational_memory", self.relational_memory.len(), self.relational_memory.heap_bytes());
+        let (symbols, symbol_bytes) = symbols::table_usage();
+        report.push("symbols", symbols, symbol_bytes);
+
+        let synapses = &self.connectome.synapses;
+        let synapse_bytes = size_of_val(synapses.offsets()) + size_of_val(synapses.targets()) + size_of_val(synapses.weights());
+        report.push("connectome", synapses.len(), self.connectome.neurons.len() * size_of::<neuron::Neuron>() + synapse_bytes);
+        report
+    }
+
+    /// Merges concept `absorbed` into concept `kept` (see `ConceptualHierarchy::merge_concepts`)
+    /// and updates the concept references held by memories. `absorbed` stays resolvable as an alias,
+    /// so it keeps mapping to the surviving concept's neurons. Returns false if either concept is unknown.
+    pub fn merge_concepts(&mut self, kept: &str, absorbed: &str) -> bool {
+        let ids = (
+            self.conceptual_hierarchy.find_concept_by_name(kept).map(|node| node.id),
+            self.conceptual_hierarchy.find_concept_by_name(absorbed).map(|node| node.id),
+        );
+        let (Some(kept_id), Some(absorbed_id)) = ids else { return false };
+        self.merge_concept_ids(kept_id, absorbed_id, kept, absorbed)
+    }
+
+    fn merge_concept_ids(&mut self, kept_id: u64, absorbed_id: u64, kept: &str, absorbed: &str) -> bool {
+        match self.conceptual_hierarchy.merge_concepts(kept_id, absorbed_id) {
+            Some(remap) => {
+                self.hippocampus.remap_concepts(&remap, |_| 0);
+                self.holographic_encoder.write().recover().add_alias(absorbed, kept);
+                self.refresh_stale_traces();
+                self.response_cache.clear();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Merges the concepts whose names differ only in their spelling of hyphens, spaces or case
+    /// (see `ConceptualHierarchy::duplicate_concepts`). Returns the merges made.
+    pub fn merge_duplicate_concepts(&mut self) -> Vec<ConceptMerge> {
+        let mut merges = Vec::new();
+        for (kept_id, absorbed_id) in self.conceptual_hierarchy.duplicate_concepts() {
+            let name = |id| self.conceptual_hierarchy.get_concept(id).map(|node| node.name.clone());
+            let (Some(kept), Some(absorbed)) = (name(kept_id), name(absorbed_id)) else { continue };
+            if self.merge_concept_ids(kept_id, absorbed_id, &kept, &absorbed) {
+                merges.push(ConceptMerge { kept, absorbed });
+            }
+        }
+        merges
+    }
+
+    /// Makes `alias` another name of the concept `name` ("E=mc²" for "mass-energy equivalence"):
+    /// the hierarchy resolves it to the concept, and the encoder gives texts using either name
+    /// the same wave. Returns false if the concept is unknown or `alias` names another concept
+    /// (see `merge_concepts`).
+    pub fn add_concept_alias(&mut self, name: &str, alias: &str) -> bool {
+        let Some(id) = self.conceptual_hierarchy.find_concept_by_name(name).map(|node| node.id) else { return false };
+        if !self.conceptual_hierarchy.add_alias(id, alias) {
+            return false;
+        }
+        self.holographic_encoder.write().recover().add_alias(alias, name);
+        self.refresh_stale_traces();
+        self.response_cache.clear();
+        true
+    }
+
+    /// Splits an overloaded concept into `new_names` (see `ConceptualHierarchy::split_concept`).
+    /// `assign` receives the name of each related concept, then the text of each memory that
+    /// mentions the split concept, and returns the index of the new concept it belongs to.
+    pub fn split_concept<F>(&mut self, name: &str, new_names: &[&str], assign: F) -> Option<Vec<u64>>
+    where
+        F: Fn(&str) -> usize,
+    {
+        let id = self.conceptual_hierarchy.find_concept_by_name(name)?.id;
+        let (new_ids, remap) = self.conceptual_hierarchy.split_concept(id, new_names, &assign)?;
+        self.hippocampus.remap_concepts(&remap, &assign);
+        self.response_cache.clear();
+        Some(new_ids)
+    }
+
+    /// Splits a concept along keyword criteria (see `SplitCriterion::assign`), e.g. for an
+    /// operator who names the new concepts and the words that tell them apart.
+    pub fn split_concept_by(&mut self, name: &str, criteria: &[SplitCriterion]) -> Option<Vec<u64>> {
+        let new_names: Vec<&str> = criteria.iter().map(|criterion| criterion.name.as_str()).collect();
+        self.split_concept(name, &new_names, |text| SplitCriterion::assign(criteria, text))
+    }
+
+    /// Summarizes what the Core knows about a concept: the memories whose traces give it (or one
+    /// of its children) at least `SUMMARY_MIN_WEIGHT` of their weight are synthesized by the
+    /// prefrontal cortex, and Silicium adds the connection it finds between them. None if the
+    /// concept is unknown or no memory is about it.
+    pub fn summarize_concept(&self, name: &str) -> Option<ConceptSummary> {
+        /// Memories a summary is drawn from, at most.
+        const MAX_SUMMARY_SOURCES: usize = 8;
+        /// Share of a memory's trace a concept must carry for the memory to be about it.
+        const SUMMARY_MIN_WEIGHT: f32 = 0.1;
+
+        let node = self.conceptual_hierarchy.find_concept_by_name(name)?;
+        let mut concepts = vec![node.name.clone()];
+        let mut children: Vec<String> = node
+            .children
+            .iter()
+            .filter_map(|id| self.conceptual_hierarchy.get_concept(*id).map(|child| child.name.clone()))
+            .collect();
+        children.sort();
+        concepts.extend(children);
+
+        let mut gathered: Vec<(usize, f32, &HolographicMemory, &str)> = Vec::new();
+        {
+            let encoder = self.holographic_encoder.read().recover();
+            for (rank, concept) in concepts.iter().enumerate() {
+                let key = encoder.resolve_alias(&encoder.focuser.concept_key(concept));
+                for memory in &self.hippocampus.holographic_memory {
+                    let weight = memory.trace.symbol_share(key);
+                    if weight >= SUMMARY_MIN_WEIGHT && !gathered.iter().any(|(_, _, known, _)| known.id == memory.id) {
+                        gathered.push((rank.min(1), weight, memory, concept.as_str()));
+                    }
+                }
+            }
+        }
+        if gathered.is_empty() {
+            return None;
+        }
+        // The memories about the concept itself come first, then those about its children.
+        gathered.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal)));
+        gathered.truncate(MAX_SUMMARY_SOURCES);
+
+        let memories: Vec<HolographicMemory> = gathered.iter().map(|(_, _, memory, _)| (*memory).clone()).collect();
+        let mut summary = self.prefrontal_cortex.synthesize_response(&node.name, &memories);
+        if let Some(connection) = Silicium::new().synthesize_from_concepts(&memories, &self.conceptual_hierarchy) {
+            summary.push(' ');
+            summary.push_str(&connection);
+        }
+        Some(ConceptSummary {
+            concept: node.name.clone(),
+            summary,
+            sources: gathered
+                .into_iter()
+                .map(|(_, weight, memory, concept)| SummarySource {
+                    memory_id: memory.id,
+                    concept: concept.to_string(),
+                    weight,
+                    location: memory.location.clone(),
+                })
+                .collect(),
+        })
+    }
+
+    /// The number of memories referencing each concept, by concept ID.
+    pub fn concept_memory_counts(&self) -> std::collections::HashMap<u64, usize> {
+        let mut counts = std::collections::HashMap::new();
+        for id in self.hippocampus.holographic_memory.iter().flat_map(|memory| &memory.concepts) {
+            *counts.entry(*id).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Writes the conceptual hierarchy as a graph, with the memory count of each concept (see
+    /// `ConceptualHierarchy::write_graph`).
+    pub fn export_hierarchy<W: io::Write>(&self, out: &mut W, format: GraphFormat) -> io::Result<()> {
+        self.conceptual_hierarchy.write_graph(out, format, &self.concept_memory_counts())
+    }
+
+    /// Sleeps for `ticks` ticks (see `sleep`): memory pairs chosen by the inner drive are
+    /// replayed and recombined by Silicium into the dream journal while the connectome runs
+    /// on its own; on waking, the hippocampus is consolidated and the connectome pruned.
+    pub fn enter_sleep(&mut self, ticks: u64) -> SleepReport {
+        self.recorded(|core| core.sleep_cycle(ticks), |_| SessionEvent::Sleep { ticks })
+    }
+
+    fn sleep_cycle(&mut self, ticks: u64) -> SleepReport {
+        const NEIGHBORS_PER_REPLAY: usize = 5;
+        println!("--- Entering sleep for {} ticks ---", ticks);
+        let mut report = SleepReport { ticks, ..SleepReport::default() };
+        let silicium = Silicium::new();
+        let intensity = self.gatekeeper.last_readout().map_or(1.0, quantum_readout::QuantumReadout::intensity);
+        self.creativity_forge.process_with_intensity(self.quantum_core.as_mut(), intensity);
+
+        for _ in 0..ticks {
+            self.tick += 1;
+            self.neurochemical_modulator.decay();
+            self.apply_neuromodulation();
+
+            // Replay a memory with one of its neighbours (a random memory if it has none).
+            if let Some((first, other)) = self.inner_drive.dream_pair(self.hippocampus.holographic_memory.len()) {
+                let memories = &self.hippocampus.holographic_memory;
+                let neighbors = self.hippocampus.neighbors(memories[first].id, NEIGHBORS_PER_REPLAY).unwrap_or_default();
+                let partner = match neighbors.get(other % neighbors.len().max(1)) {
+                    Some(neighbor) => memories.iter().position(|memory| memory.id == neighbor.id).unwrap_or(other),
+                    None => other,
+                };
+                let pair = [memories[first].clone(), memories[partner].clone()];
+                report.replays += 1;
+                if let Some(thought) = silicium.synthesize_from_concepts(&pair, &self.conceptual_hierarchy) {
+                    if !self.dream_journal.contains(&thought) {
+                        self.dream_journal.record(DreamEntry { tick: self.tick, memory_ids: (pair[0].id, pair[1].id), thought });
+                        report.dreams += 1;
+                    }
+                }
+            }
+
+            for neuron_id in self.connectome.update(self.tick, self.neurochemical_modulator.connectome_modulation()) {
+                self.connectome.propagate_signal_from(neuron_id);
+            }
+        }
+
+        report.memories_merged = self.hippocampus.merge_duplicates();
+        report.synapses_pruned = self.connectome.downscale_and_prune(sleep::SYNAPTIC_DOWNSCALING, sleep::PRUNING_THRESHOLD);
+        if report.memories_merged > 0 {
+            self.self_awareness.update_knowledge_summary(&self.hippocampus);
+        }
+        self.response_cache.clear();
+        println!(
+            "--- Awake: {} dreams, {} memories merged, {} synapses pruned ---",
+            report.dreams, report.memories_merged, report.synapses_pruned
+        );
+        report
+    }
+
+    /// The thoughts synthesized during sleep, oldest first.
+    pub fn dream_journal(&self) -> &[DreamEntry] {
+        self.dream_journal.entries()
+    }
+
+    /// Starts recording the inputs and the activity of the session to `path`, to replay it later
+    /// (see `session`). The Core must be seeded, and not have ticked or learned anything yet.
+    pub fn start_recording<P: AsRef<Path>>(&mut self, path: P) -> Result<(), SessionError> {
+        let seed = self.config.seed.ok_or(SessionError::Unseeded)?;
+        if self.tick > 0 || !self.hippocampus.holographic_memory.is_empty() {
+            return Err(SessionError::NotFresh);
+        }
+        self.session_recorder = Some(SessionRecorder::create(path, seed)?);
+        Ok(())
+    }
+
+    /// Stops the recording, writing the ticks not written yet.
+    pub fn stop_recording(&mut self) -> Result<(), SessionError> {
+        match self.session_recorder.take() {
+            Some(mut recorder) => recorder.finish(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.session_recorder.is_some()
+    }
+
+    /// Runs an entry point of the Core and records it in the session, if one is being recorded.
+    /// The recorder is set aside meanwhile, so that the entry points it calls are not recorded
+    /// as well.
+    fn recorded<R>(&mut self, run: impl FnOnce(&mut Self) -> R, event: impl FnOnce(&R) -> SessionEvent) -> R {
+        let Some(mut recorder) = self.session_recorder.take() else {
+            return run(self);
+        };
+        let result = run(self);
+        match recorder.record(&event(&result)) {
+            Ok(()) => self.session_recorder = Some(recorder),
+            Err(e) => eprintln!("Warning: Stopped recording the session: {}", e),
+        }
+        result
+    }
+
+    /// Stops the ticks scheduled by the core actor, freezing the network for inspection;
+    /// `step` still advances it.
+    pub fn pause(&mut self) {
+        self.clock.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.clock.paused = false;
+    }
+
+    /// Changes the delay between two ticks scheduled by the core actor (at least a
+    /// millisecond); None leaves only the ticks asked for.
+    pub fn set_tick_interval(&mut self, interval: Option<Duration>) {
+        self.clock.tick_interval_ms = interval.map(|interval| (interval.as_millis() as u64).max(1));
+    }
+
+    pub fn clock(&self) -> SimulationClock {
+        self.clock
+    }
+
+    /// Runs `ticks` ticks at once, paused or not, and returns the tick reached.
+    pub fn step(&mut self, ticks: u64) -> u64 {
+        for _ in 0..ticks {
+            self.tick();
+        }
+        self.tick
+    }
+
     pub fn tick(&mut self) -> Option<String> {
         // --- Neuro-Modulation: Homeostasis ---
         // Simulate the natural decay of neurochemicals over time.
         self.neurochemical_modulator.decay();
+        self.apply_neuromodulation();
 
         self.tick += 1;
 
@@ -370,8 +1630,8 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
         // 1. Initialize Quantum State Superposition (only once)
         if !self.quantum_state_initialized {
             let hadamard_gate = HadamardGate;
-            for qubit in self.quantum_core.iter_mut() {
-                hadamard_gate.apply(qubit);
+            for index in 0..self.quantum_core.len() {
+                self.quantum_core.apply(index, &hadamard_gate);
             }
             self.quantum_state_initialized = true;
             // println!("--- Quantum Core Superposition Initialized by Thalamus ---");
@@ -384,8 +1644,15 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
         // the potentiated pathways for organic recall.
 
         // 3. Update all neurons in the connectome. This handles potential decay and firing checks.
-        let active_neuron_ids = self.connectome.update(self.tick);
+        let active_neuron_ids = self.connectome.update(self.tick, self.neurochemical_modulator.connectome_modulation());
         self.last_fired_neurons = active_neuron_ids.clone();
+        if let Some(record) = self.activation_record.as_mut().filter(|record| !record.is_complete()) {
+            record.record_tick(&active_neuron_ids, &self.connectome);
+        }
+        self.eeg.record(self.get_total_potential());
+        if let Some(recorder) = self.session_recorder.as_mut() {
+            recorder.record_tick(&active_neuron_ids);
+        }
 
         // --- Update Performance Metrics ---
         // Accumulate energy for this measurement period
@@ -415,22 +1682,28 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
         // 5. Imprint the current neural activity onto the quantum core.
         for neuron in &self.connectome.neurons {
             if neuron.potential > 0.01 { // Use a small threshold to avoid noise
-                if let Some(qubit) = self.quantum_core.get_mut(neuron.id as usize) {
-                    // The phase is proportional to the neuron's potential.
-                    // The constant factor can be tuned to adjust sensitivity.
-                    let phase = neuron.potential * 0.5;
-                    let phase_gate = quantum::PhaseShiftGate::new(phase);
-                    phase_gate.apply(qubit);
-                }
+                // The phase is proportional to the neuron's potential.
+                // The constant factor can be tuned to adjust sensitivity.
+                let phase = neuron.potential * 0.5;
+                let phase_gate = quantum::PhaseShiftGate::new(phase);
+                self.quantum_core.apply(neuron.id as usize, &phase_gate);
             }
         }
 
         // 5. Engage cognitive functions.
         // self.reasoning_engine.process(&mut self.quantum_core, &self.hippocampus);
-        // self.creativity_forge.process(&mut self.quantum_core);
 
-        // 6. Generate a response if one has been requested.
-        // 6. If a response has been generated and is ready, return it.
+        // 6. Read the quantum core out when it is due: the readout sways the cognitive mode, and
+        // a creative mode lets the Creativity Forge stir the core as much as it is excited. The
+        // pairs it entangled fade a little every tick.
+        self.creativity_forge.decay_entanglements();
+        if self.gatekeeper.observe(self.quantum_core.as_mut(), self.tick) == Some(CognitiveMode::Creativity) {
+            let intensity = self.gatekeeper.last_readout().map_or(1.0, quantum_readout::QuantumReadout::intensity);
+            self.creativity_forge.process_with_intensity(self.quantum_core.as_mut(), intensity);
+        }
+
+        // 7. Generate a response if one has been requested.
+        // 7. If a response has been generated and is ready, return it.
 
 
         None
@@ -438,47 +1711,627 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
 
     pub fn get_response(&mut self) -> Option<String> {
         // Atomically take the response. This guarantees that a response is consumed exactly once.
-        self.last_response.lock().unwrap().take()
+        self.last_response.lock().recover().take()
     }
 
     /// Clears the last response from the core, to be called by the UI after displaying it.
     pub fn clear_response(&mut self) {
-        *self.last_response.lock().unwrap() = None;
+        *self.last_response.lock().recover() = None;
     }
 
     /// The main, modern entry point for processing a prompt and generating a response.
     pub fn get_response_for_prompt(&mut self, prompt: &str) -> Option<(String, QueryType)> {
+        self.recorded(
+            |core| {
+                let started = Instant::now();
+                let answer = core.respond_to_prompt(prompt);
+                if let Some((response, query_type)) = &answer {
+                    metrics::registry().record_prompt(*query_type, started.elapsed());
+                    core.explanation.response = response.clone();
+                    core.track_response(*query_type);
+                    if core.ready {
+                        core.stimulate_for_query();
+                    }
+                }
+                answer
+            },
+            |answer| SessionEvent::Prompt { prompt: prompt.to_string(), response: answer.as_ref().map(|(response, _)| response.clone()) },
+        )
+    }
+
+    /// Like `get_response_for_prompt`, for a caller who may not change what the Core knows: the
+    /// prompt is answered, but the Core neither offers to learn an unknown concept nor takes the
+    /// prompt as a definition or a source to scan.
+    pub fn get_response_for_reader(&mut self, prompt: &str) -> Option<(String, QueryType)> {
+        let teachable = std::mem::replace(&mut self.teachable, false);
+        let answer = self.get_response_for_prompt(prompt);
+        self.teachable = teachable;
+        answer
+    }
+
+    /// Stimulates the neurons of the concepts of the prompt just answered, and starts recording
+    /// the wave that follows (see `activation_record`).
+    fn stimulate_for_query(&mut self) {
+        const QUERY_STIMULUS: f32 = 1.0;
+        let concepts = self.sensory_cortex.concept_ids_for_text(&self.explanation.prompt, &self.conceptual_hierarchy);
+        let neurons = self.sensory_cortex.neurons_for_concepts(&concepts, &self.conceptual_hierarchy);
+        let mut stimulated = Vec::with_capacity(neurons.len());
+        for id in neurons {
+            if let Some(neuron) = self.connectome.neurons.get_mut(id as usize) {
+                neuron.potential += QUERY_STIMULUS;
+                self.connectome.active_neurons.insert(id);
+                stimulated.push(id);
+            }
+        }
+        self.activation_record = Some(ActivationRecord::new(self.explanation.response_id, &self.explanation.prompt, self.tick, stimulated));
+    }
+
+    /// The activity set off by the last answered prompt; it is complete `RECORDED_TICKS` ticks
+    /// after the answer (see `activation_record`).
+    pub fn activation_record(&self) -> Option<&ActivationRecord> {
+        self.activation_record.as_ref()
+    }
+
+    /// Gives the answer just explained an ID, remembers the memories it came from, so that it can
+    /// be rated, and records the exchange in the conversation log.
+    fn track_response(&mut self, query_type: QueryType) {
+        let id = self.next_response_id;
+        self.next_response_id += 1;
+        self.explanation.response_id = id;
+        let mut memory_ids: Vec<u64> = self.explanation.retrievals.iter().flat_map(|retrieval| retrieval.memories.iter().map(|memory| memory.id)).collect();
+        memory_ids.sort_unstable();
+        memory_ids.dedup();
+        if self.ready {
+            let now = episodic_memory::now();
+            self.hippocampus.touch(&memory_ids, now);
+            self.hippocampus.expand(&memory_ids, &self.holographic_encoder.read().recover());
+            self.episodic_index.record(DiscussionEpisode { at: now, prompt: self.explanation.prompt.clone(), memory_ids: memory_ids.clone() });
+            let entry = ConversationEntry { response_id: id, at: now, prompt: self.explanation.prompt.clone(), response: self.explanation.response.clone(), query_type };
+            if let Err(e) = self.conversation_log.record(entry) {
+                eprintln!("Warning: Could not save the conversation log: {}", e);
+            }
+        }
+        self.feedback_log.track(ResponseTrace {
+            id,
+            prompt: self.explanation.prompt.clone(),
+            response: self.explanation.response.clone(),
+            memory_ids,
+        });
+    }
+
+    /// The ID of the last answer, to rate it with `feedback`. None if the last prompt got no answer.
+    pub fn last_response_id(&self) -> Option<u64> {
+        (self.explanation.response_id != 0).then_some(self.explanation.response_id)
+    }
+
+    /// Rates one of the recent answers. The memories it was built from are reinforced (or
+    /// depressed) in the hippocampus, as are the synaptic pathways between the neurons of their
+    /// concepts; dopamine follows. The rating is kept in the feedback log.
+    pub fn feedback(&mut self, response_id: u64, feedback: Feedback) -> Result<FeedbackRecord, FeedbackError> {
+        self.recorded(|core| core.apply_feedback(response_id, feedback), |_| SessionEvent::Feedback { response_id, feedback })
+    }
+
+    fn apply_feedback(&mut self, response_id: u64, feedback: Feedback) -> Result<FeedbackRecord, FeedbackError> {
+        const MEMORY_REINFORCEMENT: f32 = 0.25;
+        let response = self.feedback_log.rateable(response_id)?.clone();
+        let concepts: Vec<u64> = response
+            .memory_ids
+            .iter()
+            .filter_map(|&id| self.hippocampus.memory(id))
+            .flat_map(|memory| memory.concepts.iter().copied())
+            .collect();
+        let pathway = self.sensory_cortex.neurons_for_concepts(&concepts, &self.conceptual_hierarchy);
+
+        let delta = match feedback {
+            Feedback::Positive => {
+                self.connectome.potentiate_pathway(&pathway);
+                self.neurochemical_modulator.reward_successful_reasoning();
+                MEMORY_REINFORCEMENT
+            }
+            Feedback::Negative => {
+                self.connectome.depress_pathway(&pathway);
+                self.neurochemical_modulator.penalize_rejected_answer();
+                -MEMORY_REINFORCEMENT
+            }
+        };
+        for &memory_id in &response.memory_ids {
+            self.hippocampus.reinforce_memory(memory_id, delta);
+        }
+        // Cached answers were ranked before the reinforcement.
+        self.response_cache.clear();
+
+        let record = FeedbackRecord {
+            response_id,
+            prompt: response.prompt,
+            response: response.response,
+            feedback,
+            memory_ids: response.memory_ids,
+            tick: self.tick,
+        };
+        println!("--- Feedback: response #{} rated {:?} ---", response_id, feedback);
+        self.feedback_log.record(record.clone());
+        Ok(record)
+    }
+
+    /// The answers rated so far, for evaluation.
+    pub fn feedback_log(&self) -> &FeedbackLog {
+        &self.feedback_log
+    }
+
+    /// The answered prompts, kept across restarts when `CoreConfig::conversation_log_path` is set.
+    pub fn conversation_log(&self) -> &ConversationLog {
+        &self.conversation_log
+    }
+
+    /// Why the last answer was given: the pipeline step that produced it, the classification of
+    /// the prompt, the memories retrieved with their distances, the dopamine adjustment of the
+    /// retrieval threshold and the strategy of the motor cortex. None before the first prompt.
+    pub fn explain_last_response(&self) -> Option<&provenance::ResponseExplanation> {
+        (!self.explanation.prompt.is_empty()).then_some(&self.explanation)
+    }
+
+    fn respond_to_prompt(&mut self, prompt: &str) -> Option<(String, QueryType)> {
         // --- Step 0: Update Conversational Context --- 
         self.prefrontal_cortex.update_context(prompt);
+        let sophistication = self.holographic_encoder.read().recover().vocabulary_sophistication(prompt);
+        self.prefrontal_cortex.observe_expertise(prompt, sophistication);
+        self.response_lang = lemmatizer::detect_language_or(prompt, self.response_lang);
+        self.explanation = provenance::ResponseExplanation::new(prompt, self.response_lang);
 
         // --- Step 1: Ethical Gatekeeping (Input Validation) ---
-        if let crate::ethical_core::EthicalJudgment::Reject(reason) = self.ethical_core.validate_query(prompt) {
-            println!("--- Input Query Blocked on Ethical Grounds ---");
-            return Some((reason, QueryType::Ambiguous));
+        // A borderline prompt is answered through its safe variant ("how do bombs work" becomes a
+        // question about their history and physics).
+        let reframed_prompt = match self.ethical_core.validate_query(prompt, self.response_lang) {
+            EthicalJudgment::Reject(reason) => {
+                println!("--- Input Query Blocked on Ethical Grounds ---");
+                self.explanation.path = AnswerPath::EthicalRejection;
+                return Some((reason, QueryType::Ambiguous));
+            }
+            EthicalJudgment::Reframe(reframed) => {
+                println!("--- Input Query Reframed on Ethical Grounds: '{}' ---", reframed);
+                Some(reframed)
+            }
+            EthicalJudgment::Accept => None,
+        };
+        self.explanation.reframed_prompt = reframed_prompt.clone();
+        let prompt = reframed_prompt.as_deref().unwrap_or(prompt);
+
+        // --- Step 1a: Correct the typos before the prompt is encoded ("phylosophie") ---
+        let stop_words = self.holographic_encoder.read().recover().get_stop_words();
+        let (corrected_prompt, corrections) = self.spell_corrector.correct(prompt, &stop_words.read().recover());
+        if !corrections.is_empty() {
+            println!("--- Spelling corrected: {:?} ---", corrections);
+        }
+        self.explanation.corrections = corrections;
+        let prompt = corrected_prompt.as_str();
+        let concepts = self.holographic_encoder.read().recover().distill_concepts(prompt);
+        self.curiosity_engine.observe(concepts.iter().filter(|concept| !concept.contains(' ')));
+        let sentiment = SensoryCortex::score_sentiment(prompt);
+        self.explanation.sentiment = Some(sentiment);
+        self.motor_cortex.set_user_sentiment(sentiment);
+        self.neurochemical_modulator.on_user_sentiment(sentiment);
+        if social_cortex::SocialCortex::detects_frustration(prompt) {
+            self.neurochemical_modulator.on_user_frustration();
+        }
+        self.apply_neuromodulation();
+        // The user model follows the conversation only, not the warmup prompts.
+        if self.ready {
+            if let Some(memory) = self.social_cortex.observe_prompt(prompt, sentiment) {
+                self.learn(&memory, false);
+            }
+        }
+        if let Some(name) = SocialCortex::introduced_name(prompt) {
+            self.explanation.path = AnswerPath::Social;
+            return Some((self.social_cortex.welcome(&name, self.response_lang), QueryType::Social));
+        }
+
+        // --- Step 1b: Reply to an offer to learn an unknown concept ---
+        if let Some(answer) = self.continue_onboarding(prompt) {
+            self.explanation.path = AnswerPath::Onboarding;
+            return Some(answer);
+        }
+
+        // --- Step 1b': Complete the prompt a clarifying question was asked about ---
+        let clarified_prompt = self.clarification.take().and_then(|pending| pending.merge(prompt));
+        self.explanation.clarified_prompt = clarified_prompt.clone();
+        let prompt = clarified_prompt.as_deref().unwrap_or(prompt);
+
+        // --- Step 1c: Resolve pronouns against the conversation ("son professeur") ---
+        let resolved_prompt = self.prefrontal_cortex.resolve_coreferences(prompt);
+        self.explanation.resolved_prompt = resolved_prompt.clone();
+        let prompt = resolved_prompt.as_deref().unwrap_or(prompt);
+
+        // --- Step 1d: Follow-up questions about what was just said ---
+        if let Some(answer) = self.answer_from_working_memory(prompt) {
+            self.explanation.path = AnswerPath::WorkingMemory;
+            return Some(answer);
+        }
+
+        // --- Step 1e: What the user asked earlier, or learned or discussed during a period ---
+        if let Some(query) = conversation_log::parse_history_query(prompt) {
+            let now = episodic_memory::now();
+            let asked = match query.ago {
+                Some(ago) => self.conversation_log.asked_around(now.saturating_sub(ago), now),
+                None => self.conversation_log.entries().next_back(),
+            };
+            self.explanation.path = AnswerPath::History;
+            return Some((conversation_log::describe(asked, now, self.response_lang), QueryType::Introspective));
+        }
+        if let Some(query) = episodic_memory::parse_temporal_query(prompt, episodic_memory::now()) {
+            const MAX_EPISODIC_MEMORIES: usize = 10;
+            let memories = self.reasoning_engine.lock().recover().retrieve_episodic(&query, &self.hippocampus, &self.episodic_index, MAX_EPISODIC_MEMORIES);
+            let texts: Vec<String> = memories.into_iter().map(|memory| memory.text).collect();
+            self.explanation.path = AnswerPath::Episodic;
+            return Some((episodic_memory::describe(&query, &texts, self.response_lang), QueryType::Introspective));
+        }
+
+        // --- Step 1f: Arithmetic and date math, computed exactly ---
+        if let Some(answer) = self.answer_numeric_question(prompt) {
+            self.explanation.path = AnswerPath::Numeric;
+            return Some((answer, QueryType::Factual));
         }
 
         // --- Step 2: Direct Answer Extraction (Common Sense) ---
         if let Some(direct_answer) = self.direct_answer_extractor.extract_direct_answer(prompt, &self.prefrontal_cortex) {
+            self.explanation.path = AnswerPath::DirectAnswer;
             return Some((direct_answer, QueryType::Factual)); // Classified as Factual, but handled by a shortcut.
         }
 
         // --- Step 2: Segmentation and Reasoning Strategy ---
         let segments = prompt_segmenter::segment_prompt(prompt);
-        let overall_query_type = self.thalamus.analyze_prompt(prompt);
+        let classification = self.thalamus.classify(prompt);
+        let overall_query_type = classification.query_type;
+        self.explanation.classification = Some(classification);
 
         // --- Step 3: Social Interaction Fast-Path ---
         if overall_query_type == QueryType::Social {
+            self.explanation.path = AnswerPath::Social;
             let intent = social_cortex::SocialCortex::map_prompt_to_intent(prompt);
-            let response = self.social_cortex.generate_response(intent);
+            let response = self.social_cortex.generate_response(intent, self.response_lang);
             return Some((response, QueryType::Social));
         }
+        if self.ready {
+            let topics: Vec<String> = concepts.iter().filter(|concept| !concept.contains(' ')).cloned().collect();
+            for memory in self.social_cortex.observe_topics(&topics) {
+                self.learn(&memory, false);
+            }
+        }
+
+        // --- Step 4: Answers already computed since the knowledge base last changed ---
+        let cache_key = ResponseCache::key(prompt, self.prefrontal_cortex.expertise_level());
+        // A prompt read in the light of the conversation (a clarification, a pronoun, facts the
+        // user just stated) has an answer of its own: it is neither looked up nor cached.
+        let in_context = self.explanation.clarified_prompt.is_some()
+            || self.explanation.resolved_prompt.is_some()
+            || !self.prefrontal_cortex.working_memory().is_empty();
+        // A cached answer just given is phrased anew rather than repeated word for word.
+        if let Some(cached) = self
+            .response_cache
+            .get(&cache_key)
+            .filter(|(response, _)| !in_context && !self.motor_cortex.repeats_recent_response(response))
+        {
+            println!("--- Response cache hit ---");
+            self.motor_cortex.remember_response(&cached.0);
+            self.explanation.path = AnswerPath::Cache;
+            return Some(cached);
+        }
+
+        #[cfg(not(feature = "threshold_tuning"))]
+        let (answer, cacheable) = (self.reason_about_prompt(prompt, segments, overall_query_type, self.config.retrieval), true);
+        #[cfg(feature = "threshold_tuning")]
+        let (answer, cacheable) = self.reason_with_tuning(prompt, segments, overall_query_type);
+
+        // A clarifying question or an offer to learn only makes sense with its pending state, and a
+        // creation is written anew every time.
+        let cacheable = cacheable && !in_context && !matches!(self.explanation.path, AnswerPath::Clarification | AnswerPath::Creative | AnswerPath::UnknownConcepts);
+        if let Some(answer) = answer.as_ref().filter(|_| cacheable) {
+            self.response_cache.insert(cache_key, answer.clone());
+        }
+        answer
+    }
+
+    /// Answers with the parameters chosen by the threshold tuner and rewards them with the
+    /// self-consistency of the answer. Experimental answers are not cacheable.
+    #[cfg(feature = "threshold_tuning")]
+    fn reason_with_tuning(&mut self, prompt: &str, segments: Vec<String>, overall_query_type: QueryType) -> (Option<(String, QueryType)>, bool) {
+        let choice = self.threshold_tuner.choose();
+        let answer = self.reason_about_prompt(prompt, segments, overall_query_type, choice.params);
+
+        if let Some((response, _)) = &answer {
+            let defaults_before = self.threshold_tuner.current_params();
+            let score = self.self_consistency(prompt, response);
+            self.threshold_tuner.record_answer(choice, score);
+            if self.threshold_tuner.current_params() != defaults_before {
+                // Cached answers were produced with the previous defaults.
+                self.response_cache.clear();
+            }
+        }
+        (answer, !choice.exploring)
+    }
+
+    /// How well an answer holds together: grounded in the knowledge base and relevant to the prompt (0.0 to 1.0).
+    #[cfg(feature = "threshold_tuning")]
+    fn self_consistency(&self, prompt: &str, response: &str) -> f32 {
+        let grounding = self.reasoning_engine.lock().recover().score_assertion(response, &self.hippocampus, &self.holographic_encoder);
+        let encoder = self.holographic_encoder.read().recover();
+        let relevance = (1.0 - encoder.encode(prompt).distance(&encoder.encode(response))).clamp(0.0, 1.0);
+        grounding * relevance
+    }
+
+    /// Rewards the retrieval parameters of the last answer with user feedback (0.0 to 1.0).
+    /// Returns false if nothing was answered yet.
+    #[cfg(feature = "threshold_tuning")]
+    pub fn tuning_feedback(&mut self, reward: f32) -> bool {
+        self.threshold_tuner.record_feedback(reward)
+    }
+
+    /// Computes the answer to an arithmetic or date question (see `numeric_reasoner`), followed
+    /// by the memories that mention the years it is about.
+    fn answer_numeric_question(&self, prompt: &str) -> Option<String> {
+        const MEMORIES_PER_YEAR: usize = 2;
+        let current_year = numeric_reasoner::Date::year_of_day((episodic_memory::now() / 86_400) as i64);
+        let question = numeric_reasoner::parse_question(prompt, current_year)?;
+        let years = question.years();
+        let answer = question.answer();
+        println!("--- Numeric reasoning: {:?} ---", answer);
+        let mut response = numeric_reasoner::describe(&answer, self.response_lang);
+
+        let mut supporting: Vec<&str> = Vec::new();
+        for year in years {
+            let year = sensory_cortex::Entity { text: year.to_string(), kind: sensory_cortex::EntityKind::Date };
+            let mut ids: Vec<u64> = self.hippocampus.memories_with_entities(std::slice::from_ref(&year)).unwrap_or_default().into_iter().collect();
+            ids.sort_unstable();
+            for memory in ids.into_iter().filter_map(|id| self.hippocampus.memory(id)).take(MEMORIES_PER_YEAR) {
+                if !supporting.contains(&memory.text.as_str()) {
+                    supporting.push(&memory.text);
+                }
+            }
+        }
+        if !supporting.is_empty() {
+            response.push_str(match self.response_lang {
+                lemmatizer::Lang::French => "\nÀ ce propos :",
+                lemmatizer::Lang::English => "\nRelated:",
+            });
+            for text in supporting {
+                response.push_str(&format!("\n- {}", text));
+            }
+        }
+        Some(response)
+    }
+
+    /// Subject words of the prompt with neither a concept node nor a memory mentioning them.
+    pub fn unknown_key_concepts(&self, prompt: &str) -> Vec<String> {
+        let encoder = self.holographic_encoder.read().recover();
+        let stop_words = encoder.get_stop_words();
+        let stop_words = stop_words.read().recover();
+        onboarding::unknown_key_concepts(prompt, &stop_words, |word, lemma| {
+            [lemma, word].into_iter().filter_map(Sym::lookup).any(|concept| encoder.doc_frequency.contains_key(&concept))
+                || self.conceptual_hierarchy.find_concept_by_name(word).is_some()
+        })
+    }
+
+    /// Offers to learn the subject words of a prompt the Core knows nothing about, once retrieval
+    /// has found nothing for it. A creative request may well be about something unknown, and a
+    /// reader cannot teach the Core anyway.
+    fn offer_onboarding(&mut self, prompt: &str, query_type: QueryType) -> Option<(String, QueryType)> {
+        if !self.teachable || matches!(query_type, QueryType::Introspective | QueryType::Creative) || self.requests_tool(prompt) {
+            return None;
+        }
+        let mut unknown = self.unknown_key_concepts(prompt);
+        // Words the user just used in a statement are known from the working memory.
+        unknown.retain(|concept| !self.prefrontal_cortex.working_memory().mentions(concept));
+        if unknown.is_empty() {
+            return None;
+        }
+        println!("--- Unknown concepts in prompt: {:?} ---", unknown);
+        self.neurochemical_modulator.on_novelty_detected();
+        self.apply_neuromodulation();
+        let lang = self.response_lang;
+        let offer = onboarding::offer_message(&unknown, lang);
+        self.onboarding = Some(Onboarding::Offered { concepts: unknown, lang });
+        self.explanation.path = AnswerPath::UnknownConcepts;
+        Some((offer, QueryType::Ambiguous))
+    }
+
+    /// Asks back about a prompt the retrieved memories do not answer with confidence, and keeps
+    /// the question pending for the next prompt. Never twice in a row, nor for a prompt that
+    /// asks for a tool.
+    fn ask_clarification(&mut self, prompt: &str, memories: &[HolographicMemory], query_type: QueryType) -> Option<String> {
+        if self.explanation.clarified_prompt.is_some() || query_type == QueryType::Introspective || self.requests_tool(prompt) {
+            return None;
+        }
+        let best_distance = (!memories.is_empty()).then(|| {
+            self.explanation
+                .retrievals
+                .iter()
+                .flat_map(|retrieval| retrieval.memories.iter().map(|memory| memory.distance))
+                .fold(None, |best: Option<f32>, distance| Some(best.map_or(distance, |best| best.min(distance))))
+                // Memories found without a holographic search (the MCQ solver) are taken as certain.
+                .unwrap_or(0.0)
+        });
+        if !clarification::is_needed(query_type, best_distance) {
+            return None;
+        }
+        let key_concepts = {
+            let encoder = self.holographic_encoder.read().recover();
+            let stop_words = encoder.get_stop_words();
+            let stop_words = stop_words.read().recover();
+            onboarding::subject_words(prompt, &stop_words)
+        };
+        let pending = PendingClarification { prompt: prompt.to_string(), missing: clarification::missing(&key_concepts), lang: self.response_lang };
+        let question = pending.question();
+        println!("--- Clarification: {:?} ---", pending.missing);
+        self.clarification = Some(pending);
+        Some(question)
+    }
+
+    /// Hands the curiosity engine a question nothing in memory answers, so that it can be
+    /// explored later (see `explore_next_gap`).
+    fn record_knowledge_gap(&mut self, prompt: &str) {
+        let topic = {
+            let encoder = self.holographic_encoder.read().recover();
+            let stop_words = encoder.get_stop_words();
+            let stop_words = stop_words.read().recover();
+            onboarding::unknown_key_concepts(prompt, &stop_words, |_, _| false).join(" ")
+        };
+        let known_gap = self.curiosity_engine.gaps().iter().any(|gap| gap.topic == topic.trim().to_lowercase());
+        if self.curiosity_engine.record_gap(prompt, &topic, self.response_lang).is_some() && !known_gap {
+            self.neurochemical_modulator.on_novelty_detected();
+            self.apply_neuromodulation();
+        }
+    }
+
+    /// Hands the levels of the neuromodulators that are not read at retrieval time to the
+    /// modules they act on: serotonin steadies the tone of the answers. The connectome reads
+    /// its own share on every update (see `NeurochemicalModulator::connectome_modulation`).
+    fn apply_neuromodulation(&mut self) {
+        self.motor_cortex.set_tone_stability(self.neurochemical_modulator.get_tone_stability());
+    }
+
+    /// The next knowledge gap to explore, with the sources to scan for it
+    /// (`CoreConfig::exploration_sources`). The gap stays `Exploring` until `complete_exploration`.
+    pub fn next_exploration_goal(&mut self) -> Option<ExplorationGoal> {
+        let templates = self.config.exploration_sources.clone();
+        self.curiosity_engine.next_gap().map(|gap| ExplorationGoal::for_gap(gap, &templates))
+    }
+
+    /// Learns what an exploration found, then checks whether the question of the gap now finds
+    /// a memory within the retrieval threshold: if so the gap is resolved, otherwise it is reopened.
+    pub fn complete_exploration(&mut self, gap_id: u64, sentences: &[String]) -> ExplorationOutcome {
+        let learned = if sentences.is_empty() { LearningOutcome::default() } else { self.learn_sentences(sentences, false, SourceTag::Web) };
+        let query = self.curiosity_engine.gaps().iter().find(|gap| gap.id == gap_id).map(|gap| gap.query.clone()).unwrap_or_default();
+        let resolved = learned.memories_created > 0 && {
+            // The question of a gap went unanswered as a factual one.
+            let profile = self.modulated(self.config.retrieval_profiles.resolve(QueryType::Factual, self.config.retrieval));
+            !self
+                .reasoning_engine
+                .lock()
+                .recover()
+                .retrieve(&query, &self.hippocampus, &self.conceptual_hierarchy, &self.holographic_encoder, &profile)
+                .is_empty()
+        };
+        let status = self.curiosity_engine.complete_gap(gap_id, resolved).unwrap_or(GapStatus::Abandoned);
+        println!("--- Curiosity: exploration of gap #{} -> {:?} ---", gap_id, status);
+        ExplorationOutcome { gap_id, status, learned }
+    }
+
+    /// Explores the next knowledge gap with the Core's own scanner: the sources of the goal
+    /// are tried in order until one yields sentences. None when no gap is open.
+    pub async fn explore_next_gap(&mut self) -> Option<ExplorationOutcome> {
+        let goal = self.next_exploration_goal()?;
+        let mut sentences = Vec::new();
+        for source in &goal.sources {
+            match self.knowledge_scanner.scan_sentences(source, KnowledgeScanner::DEFAULT_FRAGMENTS, KnowledgeScanner::DEFAULT_FRAGMENT_SIZE).await {
+                Ok(found) if !found.is_empty() => {
+                    sentences = found;
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Curiosity: could not scan {}: {}", source.location(), e),
+            }
+        }
+        Some(self.complete_exploration(goal.gap_id, &sentences))
+    }
+
+    /// Sources the user asked to scan during onboarding, still waiting for an administrator.
+    pub fn pending_scans(&self) -> &[DataSource] {
+        &self.pending_scans
+    }
+
+    /// Sources the user asked to scan during onboarding. A prompt only queues them: the
+    /// front-end runs them (e.g. with `learn_from_source`) once an administrator confirms.
+    pub fn take_pending_scans(&mut self) -> Vec<DataSource> {
+        std::mem::take(&mut self.pending_scans)
+    }
+
+    /// Answers "what did I just tell you?" and follow-up questions about facts held in the working memory.
+    fn answer_from_working_memory(&self, prompt: &str) -> Option<(String, QueryType)> {
+        if working_memory::is_recall_question(prompt) {
+            let facts: Vec<&str> = self.prefrontal_cortex.working_memory().recent().map(|item| item.text.as_str()).collect();
+            return Some((working_memory::recall_message(&facts, self.response_lang), QueryType::Introspective));
+        }
+        if !working_memory::is_question(prompt) {
+            return None;
+        }
+        let fact = self.prefrontal_cortex.recall_from_working_memory(prompt)?;
+        println!("--- Working memory hit (activation {:.2}) ---", fact.activation);
+        Some((fact.text.clone(), QueryType::Factual))
+    }
+
+    /// Handles the reply to an onboarding offer. Returns None when the prompt is not part of
+    /// that conversation, in which case it is answered normally.
+    fn continue_onboarding(&mut self, prompt: &str) -> Option<(String, QueryType)> {
+        if !self.teachable {
+            return None;
+        }
+        let state = self.onboarding.take()?;
+        let lang = state.lang();
+        let follow_up = onboarding::classify_follow_up(prompt);
+
+        let response = match (state, follow_up) {
+            (_, FollowUp::Decline) => onboarding::declined(lang),
+            (_, FollowUp::Scan(Some(source))) => {
+                let response = onboarding::scan_queued(&source, lang);
+                self.pending_scans.push(source);
+                response
+            }
+            (Onboarding::Offered { .. }, FollowUp::Scan(None)) | (Onboarding::AwaitingSource { .. }, FollowUp::Scan(None)) => {
+                self.onboarding = Some(Onboarding::AwaitingSource { lang });
+                onboarding::ask_source(lang)
+            }
+            (Onboarding::Offered { concepts, .. }, FollowUp::Teach) => {
+                let response = onboarding::ask_definition(&concepts[0], lang);
+                self.onboarding = Some(Onboarding::AwaitingDefinition { concepts, lang });
+                response
+            }
+            (Onboarding::AwaitingDefinition { mut concepts, .. }, _) => {
+                // Whatever the user says now is the definition.
+                let concept = concepts.remove(0);
+                let outcome = self.learn(&onboarding::definition_text(&concept, prompt), false);
+                let mut response = onboarding::learned(&concept, outcome.memories_created, lang);
+                if let Some(next) = concepts.first() {
+                    response = format!("{} {}", response, onboarding::ask_definition(next, lang));
+                    self.onboarding = Some(Onboarding::AwaitingDefinition { concepts, lang });
+                }
+                response
+            }
+            // The user moved on: answer the prompt as usual.
+            _ => return None,
+        };
+        Some((response, QueryType::Ambiguous))
+    }
+
+    /// Retrieval and synthesis for a prompt that was not answered by a fast path.
+    fn reason_about_prompt(&mut self, prompt: &str, segments: Vec<String>, overall_query_type: QueryType, retrieval: RetrievalParams) -> Option<(String, QueryType)> {
+        // --- Retrieval, under its time budget ---
+        let retrieval_clock = StageClock::start(PipelineStage::Retrieval, &self.config.timeouts);
+
+        // --- Stratégie: Comparaison structurée, une recherche par terme ---
+        if overall_query_type == QueryType::Comparative {
+            if let Some(subjects) = prompt_segmenter::comparison_subjects(prompt) {
+                if let Some(response) = self.reason_about_comparison(&subjects, &retrieval_clock, retrieval) {
+                    self.explanation.path = AnswerPath::Comparison;
+                    return Some((response, QueryType::Comparative));
+                }
+            }
+        }
+
+        // --- Stratégie: Création, un poème ou une histoire plutôt qu'un rappel ---
+        if overall_query_type == QueryType::Creative {
+            self.explanation.path = AnswerPath::Creative;
+            return Some((self.imagine(prompt, &retrieval_clock, retrieval), QueryType::Creative));
+        }
 
         if segments.len() > 1 {
             // --- Stratégie: Agréger les résultats pour une synthèse comparative ---
             let mut all_memories = Vec::new();
 
             for segment in segments {
-                if let Some(mut memories) = self.stimulate_and_reason(&segment) {
+                if retrieval_clock.check() {
+                    // On garde ce qui a déjà été trouvé plutôt que de tout perdre.
+                    break;
+                }
+                if let Some(mut memories) = self.stimulate_and_reason(&segment, &retrieval_clock, retrieval) {
                     // On ne garde que la mémoire la plus pertinente pour chaque segment afin d'éviter le bruit
                     // tout en fournissant le contexte nécessaire pour la comparaison.
                     if !memories.is_empty() {
@@ -490,37 +2343,240 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
 
             if !all_memories.is_empty() {
                 // Envoyer toutes les mémoires collectées au MotorCortex pour une réponse unifiée.
-                let response = self.motor_cortex.generate_response(prompt, &Some(all_memories), &self.self_awareness, &self.prefrontal_cortex, &self.conceptual_hierarchy, overall_query_type).unwrap_or_default();
+                let response = self.synthesize_with_fallback(prompt, Some(all_memories), overall_query_type, &retrieval_clock);
                 return Some((response, overall_query_type));
             } else {
                 // Fallback si aucune mémoire n'a été trouvée pour aucun segment.
-                let response = self.motor_cortex.generate_response(prompt, &None, &self.self_awareness, &self.prefrontal_cortex, &self.conceptual_hierarchy, overall_query_type).unwrap_or_default();
+                if let Some(offer) = self.offer_onboarding(prompt, overall_query_type) {
+                    return Some(offer);
+                }
+                self.record_knowledge_gap(prompt);
+                let response = self.synthesize_with_fallback(prompt, None, overall_query_type, &retrieval_clock);
                 return Some((response, overall_query_type));
             }
 
         } else {
             // --- Strategy: DirectReasoning for a single question ---
-            if let Some(memories) = self.stimulate_and_reason(prompt) {
-                if !memories.is_empty() {
-                    // The prefrontal cortex synthesizes the core idea, but the motor cortex has the final word on delivery.
-                    let response = self.motor_cortex.generate_response(prompt, &Some(memories), &self.self_awareness, &self.prefrontal_cortex, &self.conceptual_hierarchy, overall_query_type).unwrap_or_default();
-                    return Some((response, overall_query_type));
+            let memories = self.stimulate_and_reason(prompt, &retrieval_clock, retrieval).unwrap_or_default();
+            if memories.is_empty() {
+                if let Some(offer) = self.offer_onboarding(prompt, overall_query_type) {
+                    return Some(offer);
                 }
             }
+            if let Some(question) = self.ask_clarification(prompt, &memories, overall_query_type) {
+                self.explanation.path = AnswerPath::Clarification;
+                return Some((question, QueryType::Ambiguous));
+            }
+            if !memories.is_empty() {
+                // The prefrontal cortex synthesizes the core idea, but the motor cortex has the final word on delivery.
+                let response = self.synthesize_with_fallback(prompt, Some(memories), overall_query_type, &retrieval_clock);
+                return Some((response, overall_query_type));
+            }
         }
 
         // --- Default fallback if no reasoning path yielded a result ---
-        let response = self.motor_cortex.generate_response(prompt, &None, &self.self_awareness, &self.prefrontal_cortex, &self.conceptual_hierarchy, overall_query_type).unwrap_or_default();
+        self.record_knowledge_gap(prompt);
+        let response = self.synthesize_with_fallback(prompt, None, overall_query_type, &retrieval_clock);
         self.last_reasoning_result = Some(response.clone());
         Some((response, overall_query_type))
     }
 
+    /// Retrieves what is known about each term of a comparison and lets the motor cortex lay them
+    /// side by side. Returns None when nothing is known about either term.
+    fn reason_about_comparison(&mut self, subjects: &(String, String), retrieval_clock: &StageClock, retrieval: RetrievalParams) -> Option<String> {
+        const MEMORIES_PER_SUBJECT: usize = 2;
+        let mut recall = |subject: &str| {
+            let mut memories = self.stimulate_and_reason(subject, retrieval_clock, retrieval).unwrap_or_default();
+            memories.truncate(MEMORIES_PER_SUBJECT);
+            memories
+        };
+        let first = recall(&subjects.0);
+        let second = recall(&subjects.1);
+        let best_memory = first.first().or(second.first()).map(|memory| memory.text.clone())?;
+
+        let synthesis_clock = StageClock::start(PipelineStage::Synthesis, &self.config.timeouts);
+        let comparison = self.motor_cortex.compose_comparison(
+            (&subjects.0, &subjects.1),
+            &first,
+            &second,
+            self.response_lang,
+            self.prefrontal_cortex.expertise_level(),
+            &self.conceptual_hierarchy,
+        );
+        if synthesis_clock.check() {
+            return Some(self.partial_answer(&best_memory, PipelineStage::Synthesis));
+        }
+        Some(surface_realization::realize(&comparison))
+    }
+
+    /// Writes a poem or a story for a creative request (see `creative_writing`): the Creativity
+    /// Forge perturbs the concepts of the memories retrieved for it, Silicium bridges those no
+    /// memory relates, and the motor cortex writes the bridges out.
+    fn imagine(&mut self, prompt: &str, retrieval_clock: &StageClock, retrieval: RetrievalParams) -> String {
+        let memories = self.stimulate_and_reason(prompt, retrieval_clock, retrieval).unwrap_or_default();
+        let (seeds, forms) = {
+            let stop_words = self.holographic_encoder.read().recover().get_stop_words();
+            let stop_words = stop_words.read().recover();
+            (creative_writing::seed_concepts(prompt, &stop_words), creative_writing::surface_forms(&memories, &stop_words))
+        };
+        let temperature = self.config.creativity_temperature;
+
+        let synthesis_clock = StageClock::start(PipelineStage::Synthesis, &self.config.timeouts);
+        let mut concepts = self.creativity_forge.perturb_concepts(&memories, &seeds, temperature);
+        // Only what a memory wrote out as a subject can be shown: not "éter", the lemma of "était".
+        concepts.retain(|concept| seeds.contains(concept) || forms.contains_key(concept));
+        let bridges = Silicium::new().bridge_concepts(&concepts, &memories, &self.conceptual_hierarchy, creative_writing::bridge_count(temperature));
+        let draft = CreativeDraft { form: CreativeForm::of(prompt), concepts, bridges }.written_as(&forms);
+        let text = self.motor_cortex.compose_creative(&draft, self.response_lang);
+        if let Some(memory) = memories.first().filter(|_| synthesis_clock.check()) {
+            return self.partial_answer(&memory.text, PipelineStage::Synthesis);
+        }
+        surface_realization::realize(&text)
+    }
+
+    /// Runs the synthesis stage under its time budget, degrading gracefully.
+    ///
+    /// A factual question answered from several memories is first put to Silicium (see
+    /// `connect_memories`); the motor cortex answers it from direct recall when Silicium finds no
+    /// connection.
+    ///
+    /// If retrieval already overran its budget, or synthesis overruns its own or produces nothing,
+    /// the best retrieved memory is returned verbatim with a note, so the user always gets the
+    /// best partial answer.
+    fn synthesize_with_fallback(
+        &mut self,
+        prompt: &str,
+        memories: Option<Vec<HolographicMemory>>,
+        query_type: QueryType,
+        retrieval_clock: &StageClock,
+    ) -> String {
+        if let Some(text) = memories.as_ref().and_then(|m| m.first()).map(|m| m.text.clone()) {
+            if retrieval_clock.is_expired() {
+                return self.partial_answer(&text, PipelineStage::Retrieval);
+            }
+        }
+
+        let memories = self.use_tools(prompt, memories, query_type);
+        let best_memory = memories.as_ref().and_then(|m| m.first()).map(|m| m.text.clone());
+
+        let synthesis_clock = StageClock::start(PipelineStage::Synthesis, &self.config.timeouts);
+        let strategy = MotorCortex::select_strategy(prompt, &memories, query_type);
+        self.explanation.strategy = Some(strategy);
+        let synthesized = match self.connect_memories(&memories, query_type, strategy) {
+            Some(connection) => {
+                self.motor_cortex.remember_response(&connection);
+                Some(connection)
+            }
+            None => self.motor_cortex.generate_response(prompt, &memories, &self.self_awareness, &self.prefrontal_cortex, &self.conceptual_hierarchy, query_type, self.response_lang),
+        };
+        let overran = synthesis_clock.check();
+
+        match (synthesized, best_memory) {
+            (_, Some(text)) if overran => self.partial_answer(&text, PipelineStage::Synthesis),
+            (Some(response), _) if !response.trim().is_empty() => surface_realization::realize(&response),
+            (_, Some(text)) => {
+                println!("--- Pipeline: synthesis produced no answer, falling back to the best retrieved memory ---");
+                self.partial_answer(&text, PipelineStage::Synthesis)
+            }
+            (response, None) => response.unwrap_or_default(),
+        }
+    }
+
+    /// `memory` verbatim, with a note that `stage` ran out of time or produced nothing.
+    fn partial_answer(&mut self, memory: &str, stage: PipelineStage) -> String {
+        self.explanation.partial = Some(stage);
+        format!("{}\n\n{}", memory, pipeline::partial_answer_note(stage, self.response_lang))
+    }
+
+    /// Silicium's connection between the memories retrieved for a factual, non-comparative
+    /// question, when several were retrieved and `CoreConfig::silicium_synthesis` is on. Records
+    /// in the explanation whether the answer is synthesized or left to direct recall.
+    fn connect_memories(&mut self, memories: &Option<Vec<HolographicMemory>>, query_type: QueryType, strategy: MotorStrategy) -> Option<String> {
+        let memories = memories.as_deref().filter(|memories| memories.len() > 1)?;
+        if !self.config.silicium_synthesis || query_type != QueryType::Factual || strategy != MotorStrategy::Factual {
+            return None;
+        }
+        let connection = Silicium::new().synthesize_from_concepts_in(memories, &self.conceptual_hierarchy, self.response_lang);
+        self.explanation.composition = Some(if connection.is_some() { Composition::Synthesized } else { Composition::Recalled });
+        connection
+    }
+
+    /// Closed-loop tool use: carries out the actions the motor cortex asks for (see `tools`),
+    /// puts their results before the retrieved memories, and asks again with the results until
+    /// it asks for nothing new.
+    fn use_tools(&mut self, prompt: &str, memories: Option<Vec<HolographicMemory>>, query_type: QueryType) -> Option<Vec<HolographicMemory>> {
+        const MAX_TOOL_ROUNDS: usize = 3;
+        let mut memories = memories.unwrap_or_default();
+        for _ in 0..MAX_TOOL_ROUNDS {
+            let requests: Vec<tools::ActionRequest> = MotorCortex::plan_actions(prompt, &memories, query_type)
+                .into_iter()
+                .filter(|request| self.tools.handles(request.kind()) && !self.explanation.tool_calls.iter().any(|call| &call.request == request))
+                .collect();
+            if requests.is_empty() {
+                break;
+            }
+            let mut results = Vec::new();
+            for request in requests {
+                let Some(call) = self.tools.call(&request, self.response_lang) else { continue };
+                println!("--- Tool use: {:?} -> {} result(s), error: {:?} ---", call.request, call.output.len(), call.error);
+                {
+                    let encoder = self.holographic_encoder.read().recover();
+                    results.extend(call.output.iter().map(|text| HolographicMemory::new(text.clone(), encoder.encode(text), false)));
+                }
+                self.explanation.tool_calls.push(call);
+            }
+            results.append(&mut memories);
+            memories = results;
+        }
+        (!memories.is_empty()).then_some(memories)
+    }
+
+    /// Whether the prompt explicitly asks for an action a registered tool carries out
+    /// ("lis le fichier notes.txt").
+    fn requests_tool(&self, prompt: &str) -> bool {
+        MotorCortex::plan_actions(prompt, &[], QueryType::Ambiguous).iter().any(|request| self.tools.handles(request.kind()))
+    }
+
+    /// Registers the handler of a kind of action (see `tools`), replacing the previous one.
+    /// Only the calculator is registered by default.
+    pub fn register_tool(&mut self, handler: Box<dyn ToolHandler>) {
+        self.tools.register(handler);
+        self.self_awareness.set_capabilities(self.capabilities());
+        // Cached answers were computed without the tool.
+        self.response_cache.clear();
+    }
+
+    /// The options the MCQ solver chooses for `prompt`, or None when it is not a multiple-choice
+    /// question. `get_response_for_prompt` words the same answer.
+    pub fn solve_mcq(&self, prompt: &str) -> Option<MCQAnswer> {
+        self.mcq_solver.as_ref()?.solve(prompt, &self.hippocampus, &self.holographic_encoder)
+    }
+
+    /// `profile` with its threshold and top_k adjusted by the neuromodulators.
+    fn modulated(&self, profile: RetrievalProfile) -> RetrievalProfile {
+        RetrievalProfile {
+            distance_threshold: self.neurochemical_modulator.get_reasoning_distance_threshold(profile.distance_threshold),
+            top_k: self.neurochemical_modulator.get_retrieval_top_k(profile.top_k),
+            ..profile
+        }
+    }
+
+    /// Sets the retrieval profile of a query type, or goes back to the default parameters with
+    /// `None`. Returns the previous profile; the cached answers are dropped.
+    pub fn set_retrieval_profile(&mut self, query_type: QueryType, profile: Option<RetrievalProfile>) -> Option<RetrievalProfile> {
+        self.response_cache.clear();
+        self.config.retrieval_profiles.set(query_type, profile)
+    }
+
     /// Internal reasoning function, separated for clarity.
-    fn stimulate_and_reason(&mut self, prompt: &str) -> Option<Vec<HolographicMemory>> {
+    fn stimulate_and_reason(&mut self, prompt: &str, retrieval_clock: &StageClock, retrieval: RetrievalParams) -> Option<Vec<HolographicMemory>> {
         // Decompose the prompt into sub-questions for more nuanced processing.
         if let Some(solver) = &self.mcq_solver {
-            if let Some(answer_memory) = solver.solve(prompt, &self.hippocampus, &self.holographic_encoder) {
-                return Some(vec![answer_memory]);
+            if let Some(answer) = solver.solve(prompt, &self.hippocampus, &self.holographic_encoder) {
+                println!("--- MCQ Solver selected: {:?} ---", answer.labels);
+                let answer_content = answer.describe(self.response_lang);
+                let answer_trace = self.holographic_encoder.read().recover().encode(&answer_content);
+                return Some(vec![HolographicMemory::new(answer_content, answer_trace, false)]);
             }
         }
 
@@ -528,38 +2584,50 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
         let mut combined_results: Vec<HolographicMemory> = Vec::new();
 
         for sub_prompt in sub_prompts {
+            if retrieval_clock.check() {
+                break;
+            }
             let trimmed_prompt = sub_prompt.trim();
             if trimmed_prompt.is_empty() {
                 continue;
             }
 
-            let query_type = self.thalamus.analyze_prompt(trimmed_prompt);
+            let classification = self.thalamus.classify(trimmed_prompt);
+            let query_type = classification.query_type;
             println!(
                 "--- Thalamus classified sub-query '{}' as: {:?} ---",
                 trimmed_prompt,
                 query_type
             );
 
-            let is_introspective = query_type == QueryType::Introspective;
-
             // --- Neuro-Modulation: Calcul du seuil de raisonnement dynamique ---
-            const BASE_REASONING_THRESHOLD: f32 = 0.95;
-            let dynamic_threshold = self.neurochemical_modulator.get_reasoning_distance_threshold(BASE_REASONING_THRESHOLD);
+            let base = self.config.retrieval_profiles.resolve(query_type, retrieval);
+            let profile = self.modulated(base);
             println!(
-                "--- Neuro-Modulation: Reasoning with dynamic threshold: {:.4} (Dopamine: {:.2}) ---",
-                dynamic_threshold, self.neurochemical_modulator.state.dopamine
+                "--- Neuro-Modulation: Reasoning with dynamic threshold: {:.4}, top_k: {} (Dopamine: {:.2}, Acetylcholine: {:.2}) ---",
+                profile.distance_threshold, profile.top_k, self.neurochemical_modulator.state.dopamine, self.neurochemical_modulator.state.acetylcholine
             );
 
-            if let Some(results) = self.reasoning_engine.lock().unwrap().process(
+            let (results, route) = self.reasoning_engine.lock().recover().retrieve_routed(
                 trimmed_prompt,
                 &self.hippocampus,
                 &self.conceptual_hierarchy,
                 &Arc::clone(&self.holographic_encoder),
-                is_introspective,
-                dynamic_threshold, // Le seuil dynamique est maintenant utilisé ici
-            ) {
-                combined_results.extend(results);
-            }
+                &profile,
+            );
+            self.explanation.retrievals.push(provenance::Retrieval {
+                sub_query: trimmed_prompt.to_string(),
+                classification,
+                base_threshold: base.distance_threshold,
+                dynamic_threshold: profile.distance_threshold,
+                dopamine: self.neurochemical_modulator.state.dopamine,
+                top_k: profile.top_k,
+                axioms_only: profile.axioms_only,
+                emotion: self.neurochemical_modulator.emotion(),
+                route,
+                memories: provenance::Retrieval::memories_from(&results),
+            });
+            combined_results.extend(results.into_iter().map(|(memory, _)| memory));
         }
 
         if combined_results.is_empty() {
@@ -580,8 +2648,10 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
     /// # Arguments
     /// * `child_name` - The name of the more specific concept (e.g., "Poodle").
     /// * `parent_name` - The name of the more abstract concept (e.g., "Dog").
-    pub fn learn_relationship(&mut self, child_name: &str, parent_name: &str) {
-        let encoder = self.holographic_encoder.read().unwrap();
+    ///
+    /// Fails, leaving the concepts unlinked, if the parent is already a kind of the child.
+    pub fn learn_relationship(&mut self, child_name: &str, parent_name: &str) -> Result<(), HierarchyError> {
+        let encoder = self.holographic_encoder.read().recover();
 
         // Create traces for concepts. `add_concept` will use them only if the concept is new.
         let child_trace = encoder.encode(child_name);
@@ -595,9 +2665,23 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
         drop(encoder);
 
         // Now, establish the hierarchical relationship.
-        self.conceptual_hierarchy.add_relationship(child_id, parent_id);
+        self.conceptual_hierarchy.learn_relationship(child_id, parent_id)?;
+        self.relational_memory.store(Triple::new(child_name, IS_A, parent_name));
+        self.response_cache.clear();
 
         println!("Successfully linked '{}' as a child of '{}'", child_name, parent_name);
+        Ok(())
+    }
+
+    /// Stores a subject-relation-object structure ("chien", "mange", "croquettes") in the
+    /// relational memory, where the roles survive the superposition with other facts.
+    pub fn learn_triple(&mut self, subject: &str, relation: &str, object: &str) {
+        self.relational_memory.store(Triple::new(subject, relation, object));
+    }
+
+    /// The missing part of a learned triple, knowing the two others (see `RelationalMemory::recall`).
+    pub fn recall_triple(&self, wanted: Role, known: [(Role, &str); 2]) -> Option<RecalledFiller> {
+        self.relational_memory.recall(wanted, known)
     }
 
     // --- Phase 1: Biomimetic Wakeup Sequence ---
@@ -640,15 +2724,25 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
     fn replay_core_memories(&mut self) {
         let replay_intensity = 20.0; // As per Instructions.txt
         println!("  -> Replaying core memories (intensity: {}x)...", replay_intensity);
-        self.hippocampus.replay_core_memories(&mut self.quantum_core);
+        self.hippocampus.replay_core_memories(self.quantum_core.as_mut());
     }
 
-    /// High-level API to load and process a knowledge file.
-    pub fn learn_from_file<P: AsRef<Path>>(&mut self, path: P) -> std::io::Result<()> {
-        self.knowledge_explorer.load_and_process_file(path)?;
-        // Immediately try to assimilate the newly loaded knowledge.
-        self.assimilate_knowledge();
-        Ok(())
+    /// High-level API to load and process a knowledge file (plain text, HTML or PDF).
+    /// Each paragraph becomes a memory that remembers its source file and page.
+    pub fn learn_from_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), DocumentError> {
+        let path = path.as_ref();
+        self.recorded(
+            |core| {
+                core.knowledge_explorer.load_and_process_file(path)?;
+                // Immediately try to assimilate the newly loaded knowledge.
+                core.assimilate_knowledge();
+                Ok(())
+            },
+            |_| SessionEvent::LearnFile {
+                path: std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
+                sha256: session::file_digest(path).unwrap_or_default(),
+            },
+        )
     }
 
 
@@ -657,22 +2751,26 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
     /// This function processes the concepts loaded by the KnowledgeExplorer, encodes them as holographic memories,
     /// and integrates them into the AGI's knowledge base. It also rebuilds the document frequency map to include the new knowledge.
     pub fn assimilate_knowledge(&mut self) {
-        let concepts_to_learn = self.knowledge_explorer.get_discovered_concepts();
-        if concepts_to_learn.is_empty() {
+        let passages = self.knowledge_explorer.get_discovered_passages();
+        if passages.is_empty() {
             println!("ASSIMILATE: No new concepts to assimilate.");
             return;
         }
 
-        println!("ASSIMILATE: Assimilating {} new concepts...", concepts_to_learn.len());
-        for concept_text in concepts_to_learn {
-            // The primary `learn` method correctly handles encoding, potentiation, and storing the full HolographicMemory.
-            self.learn_and_assimilate(&concept_text, false);
+        println!("ASSIMILATE: Assimilating {} new concepts...", passages.len());
+        let texts: Vec<&str> = passages.iter().map(|passage| passage.text.as_str()).collect();
+        let memory_ids = self.assimilate_batch(&texts, false, SourceTag::Curated, |_| {});
+        let stored = self.hippocampus.holographic_memory.len() - memory_ids.len();
+        for (memory, passage) in self.hippocampus.holographic_memory[stored..].iter_mut().zip(passages) {
+            memory.location = Some(passage.location);
         }
 
-        // After assimilation, it's crucial to rebuild the semantic context.
-        println!("ASSIMILATE: Rebuilding document frequency map and Thalamus prototypes...");
-        self.holographic_encoder.write().unwrap().build_document_frequency(&self.hippocampus.holographic_memory);
-        self.rebuild_thalamus_prototypes();
+        // The document frequency map is kept up to date incrementally; only re-encode
+        // stored traces if the IDF statistics have drifted significantly.
+        println!("ASSIMILATE: Refreshing semantic context...");
+        if !self.refresh_stale_traces() {
+            self.rebuild_thalamus_prototypes();
+        }
 
         // Finally, update the self-awareness module with the new knowledge state.
         self.self_awareness.update_knowledge_summary(&self.hippocampus);
@@ -687,10 +2785,10 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
         let hadamard_gate = HadamardGate;
 
         // Apply a Hadamard gate to a growing subset of qubits to bring them into superposition gradually.
-        for qubit in self.quantum_core.iter_mut().take(num_qubits_to_awaken) {
+        for index in 0..num_qubits_to_awaken.min(self.quantum_core.len()) {
             // Avoid re-initializing qubits that might already be in superposition.
-            if qubit.alpha.re == 1.0 && qubit.beta.re == 0.0 { // Check if it's in the |0> state
-                hadamard_gate.apply(qubit);
+            if self.quantum_core.is_ground(index) {
+                self.quantum_core.apply(index, &hadamard_gate);
             }
         }
 
@@ -700,6 +2798,11 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
         self.quantum_state_initialized = awareness_level >= 1.0;
     }
 
+    /// The current stage of the wakeup sequence, out of the number of stages.
+    pub fn wakeup_progress(&self) -> (u32, u32) {
+        (self.current_wakeup_stage, self.wakeup_stages)
+    }
+
     pub fn get_awakening_level(&self) -> f32 {
         if self.wakeup_stages == 0 {
             return if self.quantum_state_initialized { 1.0 } else { 0.0 };
@@ -715,6 +2818,28 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
         println!("--- Stimulus Processed and Learned as Conceptual Memory ---");
     }
 
+    /// Switches the Sensory Cortex to sparse distributed representations: each concept then
+    /// stimulates a stable assembly covering `sparsity` of the connectome's neurons.
+    pub fn use_sparse_stimulus_encoding(&mut self, sparsity: f32) {
+        let config = sensory_cortex::SdrConfig::with_sparsity(self.connectome.neurons.len(), sparsity);
+        println!("--- Sensory Cortex: SDR mode enabled ({} neurons per concept) ---", config.active_bits);
+        self.sensory_cortex.set_encoding(sensory_cortex::StimulusEncoding::Sparse(config));
+    }
+
+    /// Returns the neurons that represent a concept, for visualization of its assembly.
+    pub fn concept_assembly(&self, concept_name: &str) -> Vec<u64> {
+        self.sensory_cortex.assembly_for(concept_name, &self.conceptual_hierarchy)
+    }
+
+    /// Primes a concept by raising the potential of its neural assembly without storing a memory.
+    pub fn prime_concept(&mut self, concept_name: &str, strength: f32) {
+        for neuron_id in self.concept_assembly(concept_name) {
+            if let Some(neuron) = self.connectome.neurons.get_mut(neuron_id as usize) {
+                neuron.potential += strength;
+            }
+        }
+    }
+
     /// Returns a vector of neuron potentials for EEG visualization.
     /// It will return up to `num_points` values.
     pub fn get_eeg_potentials(&self, num_points: usize) -> Vec<f32> {
@@ -730,12 +2855,17 @@ pub fn new(_knowledge_file_path: Option<&str>) -> Self {
         self.connectome.neurons.iter().map(|n| n.potential).sum()
     }
 
+    /// The spectrum of the total potential over the last `window` ticks (rounded down to a power
+    /// of two), with its power in the pseudo delta/theta/alpha/beta bands (see `eeg`).
+    pub fn get_eeg_spectrum(&self, window: usize) -> EegSpectrum {
+        self.eeg.spectrum(window)
+    }
 
-}
-
-impl Default for Core {
-    fn default() -> Self {
-        // When creating a default Core, we don't load any external knowledge.
-        Self::new(None)
+    /// The pairs of qubits the Creativity Forge entangled recently, strongest first (see
+    /// `entanglement_map`).
+    pub fn get_entanglement_graph(&self) -> EntanglementGraph {
+        self.creativity_forge.entanglements().graph(self.quantum_core.len())
     }
+
+
 }
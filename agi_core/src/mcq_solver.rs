@@ -2,106 +2,435 @@
 //!
 //! Ce module identifie si un prompt est un QCM et utilise le moteur de raisonnement
 //! pour évaluer les options et trouver la réponse la plus probable.
+//!
+//! Options may be lettered ("A.", "b)", "(C)"), numbered ("1.", "2)") or bulleted ("- ", "• "),
+//! with as many options as the question has. The prompt may ask for several answers ("choose
+//! two", "select all that apply") and offer "all of the above" or "none of the above".
 
-use crate::holographic_memory::{HolographicEncoder, HolographicMemory};
+use crate::capabilities::Capability;
+use crate::error::LockResultExt;
+use crate::holographic_memory::HolographicEncoder;
+use crate::lemmatizer::Lang;
 use crate::reasoning_engine::ReasoningEngine;
 use crate::hippocampus::Hippocampus;
 use regex::Regex;
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+/// La résolution de QCM, telle que la décrit `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "multiple_choice",
+    description: "Answer multiple choice questions, or abstain when the options cannot be told apart",
+    description_fr: "Répondre aux questions à choix multiples, ou m'abstenir quand les options ne se distinguent pas",
+    examples: &["Quelle est la capitale de la France ? A) Lyon B) Paris C) Nice"],
+};
+
+/// Minimum score for an option to be considered true.
+const CONFIDENCE_THRESHOLD: f32 = 0.1;
+
+/// Settings of the discriminative scoring pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct McqConfig {
+    /// Softmax temperature over the option scores (scores range from 0.0 to 1.0): the lower,
+    /// the more a small score difference separates the probabilities.
+    pub temperature: f32,
+    /// Minimum pairwise margin (0.0 to 1.0) between the weakest chosen option and the strongest
+    /// rejected one. Below it the options cannot be told apart and the solver abstains.
+    pub min_margin: f32,
+}
+
+impl Default for McqConfig {
+    fn default() -> Self {
+        Self { temperature: 0.1, min_margin: 0.2 }
+    }
+}
+
+/// What an option says about the other options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    /// An answer in its own right.
+    Regular,
+    /// "All of the above", "toutes les réponses ci-dessus".
+    AllOfTheAbove,
+    /// "None of the above", "aucune de ces réponses".
+    NoneOfTheAbove,
+}
+
+/// An option of a multiple choice question.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McqOption {
+    /// The label as written ("B", "b", "2"); bulleted options are lettered from "A".
+    pub label: String,
+    pub text: String,
+    pub kind: OptionKind,
+}
+
+/// How many options the question asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    Single,
+    /// "Choose two", "choisissez trois réponses".
+    Exactly(usize),
+    /// "Select all that apply", "une ou plusieurs réponses".
+    AllThatApply,
+}
 
 /// Represents a parsed Multiple Choice Question.
-struct ParsedMCQ {
-    question: String,
-    options: Vec<String>,
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedMCQ {
+    pub question: String,
+    pub options: Vec<McqOption>,
+    pub selection: Selection,
+}
+
+/// How an option fared against memory and against the other options.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionScore {
+    pub label: String,
+    /// Plausibility of the question completed with the whole option (0.0 to 1.0).
+    pub score: f32,
+    /// Plausibility of the question completed with the words only this option has, so that
+    /// near-synonym options are told apart by what differs between them.
+    pub discriminative_score: f32,
+    /// Calibrated probability that the option is the answer (softmax over the options).
+    pub probability: f32,
+}
+
+impl OptionScore {
+    /// The value the options are ranked by.
+    fn logit(&self) -> f32 {
+        (self.score + self.discriminative_score) / 2.0
+    }
+}
+
+/// The options chosen for a question.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MCQAnswer {
+    pub question: String,
+    /// The labels of the selected options, in the order of the question.
+    pub labels: Vec<String>,
+    /// The selected options.
+    pub selected: Vec<McqOption>,
+    /// The scores of the regular options, in the order of the question.
+    pub scores: Vec<OptionScore>,
+    /// Pairwise margin between the weakest chosen option and the strongest rejected one
+    /// (1.0 when nothing was rejected, or when an "all/none of the above" option was chosen).
+    pub margin: f32,
+}
+
+impl MCQAnswer {
+    /// Words the answer, in `lang`.
+    pub fn describe(&self, lang: Lang) -> String {
+        let options = self.selected.iter().map(|option| format!("{}. {}", option.label, option.text)).collect::<Vec<_>>().join(" ; ");
+        match (lang, self.selected.len()) {
+            (Lang::French, 1) => format!("En réponse à la question '{}', l'option la plus plausible est : {}", self.question, options),
+            (Lang::French, _) => format!("En réponse à la question '{}', les options les plus plausibles sont : {}", self.question, options),
+            (Lang::English, 1) => format!("In answer to the question '{}', the most plausible option is: {}", self.question, options),
+            (Lang::English, _) => format!("In answer to the question '{}', the most plausible options are: {}", self.question, options),
+        }
+    }
 }
 
 /// Structure principale pour le solveur de QCM.
 pub struct McqSolver {
     reasoning_engine: Arc<Mutex<ReasoningEngine>>,
+    config: McqConfig,
 }
 
 impl McqSolver {
-    pub fn new(reasoning_engine: Arc<Mutex<ReasoningEngine>>) -> Self {
-        Self { reasoning_engine }
+    pub fn new(reasoning_engine: Arc<Mutex<ReasoningEngine>>, config: McqConfig) -> Self {
+        Self { reasoning_engine, config }
     }
 
     /// Tente de détecter et de résoudre un QCM à partir d'un prompt.
     ///
     /// # Retourne
-    /// `Some(HolographicMemory)` avec la réponse si le prompt est un QCM et qu'une réponse est trouvée.
-    /// `None` si le prompt n'est pas identifié comme un QCM.
-    pub fn solve(&self, prompt: &str, hippocampus: &Hippocampus, encoder: &Arc<RwLock<HolographicEncoder>>) -> Option<HolographicMemory> {
-        let parsed_mcq = self.parse_mcq(prompt)?;
-
-        let mut best_option: Option<String> = None;
-        let mut max_score = -1.0_f32;
-
-        let reasoning_engine = self.reasoning_engine.lock().unwrap();
-
-        for option in &parsed_mcq.options {
-            // --- Heuristique de bon sens : l'option est-elle dans la question ? ---
-            // Extrait le texte pur de l'option (ex: "A. Blanc" -> "Blanc")
-            let option_text = option.split_once('.').map_or(option.as_str(), |(_, text)| text).trim();
-            
-            // Normalise le texte pour la comparaison
-            let normalized_question = parsed_mcq.question.to_lowercase();
-            let normalized_option = option_text.to_lowercase();
-
-            if normalized_question.contains(&normalized_option) {
-                println!("[MCQ Solver] Heuristique de bon sens déclenchée pour l'option : {}", option);
-                max_score = 1.0; // Score de confiance maximal
-                best_option = Some(option.clone());
-                break; // On a trouvé la réponse la plus logique, pas besoin de chercher plus loin.
-            }
+    /// `Some(MCQAnswer)` avec les options retenues si le prompt est un QCM et qu'une réponse est trouvée.
+    /// `None` si le prompt n'est pas identifié comme un QCM, qu'aucune option n'est plausible ou
+    /// que les meilleures options ne se distinguent pas assez des autres (abstention).
+    pub fn solve(&self, prompt: &str, hippocampus: &Hippocampus, encoder: &Arc<RwLock<HolographicEncoder>>) -> Option<MCQAnswer> {
+        let parsed_mcq = Self::parse_mcq(prompt)?;
+        let normalized_question = parsed_mcq.question.to_lowercase();
+        let regular: Vec<&McqOption> = parsed_mcq.options.iter().filter(|option| option.kind == OptionKind::Regular).collect();
 
-            // Formulate a complete assertion to be evaluated.
-            let assertion = format!("{} {}", parsed_mcq.question, option);
-            
-            let score = reasoning_engine.score_assertion(&assertion, hippocampus, encoder);
-            println!("[MCQ Solver] Evaluating: '{}' -> Score: {:.4}", assertion, score);
+        let reasoning_engine = self.reasoning_engine.lock().recover();
+        let mut scores: Vec<OptionScore> = regular
+            .iter()
+            .map(|option| {
+                // --- Heuristique de bon sens : l'option est-elle dans la question ? ---
+                if normalized_question.contains(&option.text.to_lowercase()) {
+                    println!("[MCQ Solver] Heuristique de bon sens déclenchée pour l'option : {}", option.label);
+                    // Score de confiance maximal
+                    return OptionScore { label: option.label.clone(), score: 1.0, discriminative_score: 1.0, probability: 0.0 };
+                }
+                // Formulate a complete assertion to be evaluated.
+                let assertion = format!("{} {}", parsed_mcq.question, option.text);
+                let score = reasoning_engine.score_assertion(&assertion, hippocampus, encoder);
 
-            if score > max_score {
-                max_score = score;
-                best_option = Some(option.clone());
+                // --- Discriminative pass: only the words that set this option apart ---
+                let others: Vec<&str> = regular.iter().filter(|other| other.label != option.label).map(|other| other.text.as_str()).collect();
+                let distinctive = Self::distinctive_words(&option.text, &others);
+                let discriminative_score = if distinctive.is_empty() || distinctive == Self::words(&option.text) {
+                    score
+                } else {
+                    reasoning_engine.score_assertion(&format!("{} {}", parsed_mcq.question, distinctive.join(" ")), hippocampus, encoder)
+                };
+                println!("[MCQ Solver] Evaluating: '{}' -> Score: {:.4}, discriminative: {:.4}", assertion, score, discriminative_score);
+                OptionScore { label: option.label.clone(), score, discriminative_score, probability: 0.0 }
+            })
+            .collect();
+        drop(reasoning_engine);
+
+        Self::calibrate(&mut scores, self.config.temperature);
+        let (labels, margin) = Self::select(&parsed_mcq, &scores, &self.config)?;
+        let selected = parsed_mcq.options.iter().filter(|option| labels.contains(&option.label)).cloned().collect();
+        Some(MCQAnswer { question: parsed_mcq.question, labels, selected, scores, margin })
+    }
+
+    /// The lowercased words of an option.
+    fn words(text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()).map(str::to_lowercase).collect()
+    }
+
+    /// The words of `text` that none of the `others` contains.
+    fn distinctive_words(text: &str, others: &[&str]) -> Vec<String> {
+        let shared: Vec<String> = others.iter().flat_map(|other| Self::words(other)).collect();
+        Self::words(text).into_iter().filter(|word| !shared.contains(word)).collect()
+    }
+
+    /// Sets the probability of each option: a softmax of their logits at `temperature`.
+    fn calibrate(scores: &mut [OptionScore], temperature: f32) {
+        let temperature = temperature.max(f32::EPSILON);
+        let max = scores.iter().map(OptionScore::logit).fold(f32::NEG_INFINITY, f32::max);
+        let weights: Vec<f32> = scores.iter().map(|option| ((option.logit() - max) / temperature).exp()).collect();
+        let total: f32 = weights.iter().sum();
+        for (option, weight) in scores.iter_mut().zip(weights) {
+            option.probability = weight / total;
+        }
+    }
+
+    /// The pairwise margin between two options: how much more likely `a` is to be preferred to
+    /// `b` than the reverse (0.0 for a tie, close to 1.0 for a clear preference).
+    fn pairwise_margin(a: &OptionScore, b: &OptionScore, temperature: f32) -> f32 {
+        ((a.logit() - b.logit()) / (2.0 * temperature.max(f32::EPSILON))).tanh()
+    }
+
+    /// Chooses the labels to answer with, given the scores of the regular options, and the
+    /// margin of the choice. None when no option is plausible and the question offers no "none
+    /// of the above", or when the chosen options do not stand out from the rejected ones.
+    fn select(parsed_mcq: &ParsedMCQ, scores: &[OptionScore], config: &McqConfig) -> Option<(Vec<String>, f32)> {
+        let special = |kind: OptionKind| parsed_mcq.options.iter().find(|option| option.kind == kind).map(|option| option.label.clone());
+        let plausible = scores.iter().filter(|option| option.score > CONFIDENCE_THRESHOLD).count();
+        if plausible == 0 {
+            return special(OptionKind::NoneOfTheAbove).map(|label| (vec![label], 1.0));
+        }
+        if plausible == scores.len() && scores.len() > 1 {
+            if let Some(all) = special(OptionKind::AllOfTheAbove) {
+                return Some((vec![all], 1.0));
             }
         }
 
-        // If we found a plausible answer, return it as a memory.
-        if let Some(chosen_option) = best_option {
-            if max_score > 0.1 { // Confidence threshold
-                let answer_content = format!("En réponse à la question '{}', l'option la plus plausible est : {}", parsed_mcq.question, chosen_option);
-                
-                let answer_trace = encoder.read().unwrap().encode(&answer_content);
-
-                let answer_memory = HolographicMemory {
-                    text: answer_content,
-                    trace: answer_trace,
-                    is_axiom: false,
-                };
-                return Some(answer_memory);
+        let mut ranked: Vec<&OptionScore> = scores.iter().collect();
+        ranked.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+        let count = match parsed_mcq.selection {
+            Selection::Single => 1,
+            Selection::Exactly(count) => count,
+            Selection::AllThatApply => plausible,
+        };
+        let chosen: Vec<&OptionScore> = ranked.iter().take(count).copied().filter(|option| option.score > CONFIDENCE_THRESHOLD).collect();
+        let margin = match (chosen.last(), ranked.get(chosen.len())) {
+            (Some(weakest), Some(strongest_rejected)) => Self::pairwise_margin(weakest, strongest_rejected, config.temperature),
+            _ => 1.0,
+        };
+        if parsed_mcq.selection != Selection::AllThatApply && margin < config.min_margin {
+            println!("[MCQ Solver] Abstention : marge {:.3} sous le seuil {:.3}", margin, config.min_margin);
+            return None;
+        }
+        // Keep the order of the question.
+        let labels = parsed_mcq.options.iter().map(|option| &option.label).filter(|label| chosen.iter().any(|option| &option.label == *label)).cloned().collect();
+        Some((labels, margin))
+    }
+
+    /// Parses a prompt to extract the question, its options and how many of them to choose.
+    ///
+    /// Lettered and numbered options must follow each other from "A", "a" or "1", so that an
+    /// initial ("John F. Kennedy") is not mistaken for an option.
+    pub fn parse_mcq(prompt: &str) -> Option<ParsedMCQ> {
+        let (question, options) = Self::bulleted_options(prompt).or_else(|| Self::labelled_options(prompt))?;
+        let question = question.trim().to_string();
+        let selection = Self::selection(prompt);
+        Some(ParsedMCQ { question, options, selection })
+    }
+
+    /// Options written as a list of bullets, one per line.
+    fn bulleted_options(prompt: &str) -> Option<(&str, Vec<McqOption>)> {
+        static BULLET: OnceLock<Regex> = OnceLock::new();
+        let bullet = BULLET.get_or_init(|| Regex::new(r"(?m)^[ \t]*[-*•][ \t]+(.+)$").unwrap());
+        let items: Vec<regex::Captures> = bullet.captures_iter(prompt).collect();
+        if items.len() < 2 {
+            return None;
+        }
+        let question = &prompt[..items[0].get(0)?.start()];
+        let options = items.iter().zip('A'..='Z').map(|(item, label)| Self::option(label.to_string(), &item[1])).collect();
+        Some((question, options))
+    }
+
+    /// Options introduced by a letter or a number ("A.", "b)", "(3)", "4.").
+    fn labelled_options(prompt: &str) -> Option<(&str, Vec<McqOption>)> {
+        static MARKER: OnceLock<Regex> = OnceLock::new();
+        let marker = MARKER.get_or_init(|| Regex::new(r"(?:^|\s)\(?([A-Za-z]|\d{1,2})[.)]\s+").unwrap());
+
+        let markers: Vec<(usize, usize, &str)> = marker
+            .captures_iter(prompt)
+            .filter_map(|captures| Some((captures.get(0)?.start(), captures.get(0)?.end(), captures.get(1)?.as_str())))
+            .collect();
+        let first = markers.iter().position(|(_, _, label)| matches!(*label, "A" | "a" | "1"))?;
+        let mut sequence = vec![markers[first]];
+        for &candidate in &markers[first + 1..] {
+            let (_, _, previous) = sequence[sequence.len() - 1];
+            if Some(candidate.2.to_string()) == Self::next_label(previous) {
+                sequence.push(candidate);
             }
         }
+        if sequence.len() < 2 {
+            return None;
+        }
+
+        let question = &prompt[..sequence[0].0];
+        let options = sequence
+            .iter()
+            .enumerate()
+            .map(|(i, &(_, text_start, label))| {
+                let text_end = sequence.get(i + 1).map_or(prompt.len(), |next| next.0);
+                Self::option(label.to_string(), &prompt[text_start..text_end])
+            })
+            .collect();
+        Some((question, options))
+    }
 
-        None // No confident answer found
+    /// The label after `label` in the same numbering ("B" after "A", "3" after "2").
+    fn next_label(label: &str) -> Option<String> {
+        if let Ok(number) = label.parse::<u32>() {
+            return Some((number + 1).to_string());
+        }
+        let letter = label.chars().next().filter(char::is_ascii_alphabetic)?;
+        char::from_u32(letter as u32 + 1).filter(char::is_ascii_alphabetic).map(String::from)
     }
 
-    /// Parses a prompt to extract the question and a list of options.
-    fn parse_mcq(&self, prompt: &str) -> Option<ParsedMCQ> {
-        // Heuristique pour trouver le début des options (ex: " A. ", " 1) ")
-        let options_marker = Regex::new(r"\s+[A-Da-d][.)]").unwrap();
-        
-        let (question, options_str) = match options_marker.find(prompt) {
-            Some(marker_match) => prompt.split_at(marker_match.start()),
-            None => return None, // Not an MCQ if no option markers are found.
+    fn option(label: String, text: &str) -> McqOption {
+        static ALL: OnceLock<Regex> = OnceLock::new();
+        static NONE: OnceLock<Regex> = OnceLock::new();
+        let all = ALL.get_or_init(|| {
+            Regex::new(r"(?i)^(?:all of the above|all of these|toutes les réponses(?: ci-dessus| précédentes)?|toutes ces réponses|tout ce qui précède)\W*$").unwrap()
+        });
+        let none = NONE.get_or_init(|| {
+            Regex::new(r"(?i)^(?:none of the above|none of these|aucune de ces réponses|aucune des réponses(?: ci-dessus| précédentes)?|aucune)\W*$").unwrap()
+        });
+        let text = text.trim().to_string();
+        let kind = if all.is_match(&text) {
+            OptionKind::AllOfTheAbove
+        } else if none.is_match(&text) {
+            OptionKind::NoneOfTheAbove
+        } else {
+            OptionKind::Regular
         };
+        McqOption { label, text, kind }
+    }
 
-        let question = question.trim().to_string();
-        let options: Vec<String> = options_marker.split(options_str)
-            .map(str::trim)
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string())
+    /// How many options the prompt asks for.
+    fn selection(prompt: &str) -> Selection {
+        static EXACTLY: OnceLock<Regex> = OnceLock::new();
+        static ALL_THAT_APPLY: OnceLock<Regex> = OnceLock::new();
+        let exactly = EXACTLY.get_or_init(|| {
+            Regex::new(r"(?i)\b(?:choose|select|pick|choisissez|choisis|choisir|sélectionnez|cochez)\s+(?:the\s+|les\s+)?(\d|two|three|four|deux|trois|quatre)\b").unwrap()
+        });
+        let all_that_apply = ALL_THAT_APPLY.get_or_init(|| {
+            Regex::new(r"(?i)\b(?:all that apply|one or more|more than one answer|plusieurs réponses|une ou plusieurs|toutes les bonnes réponses)\b").unwrap()
+        });
+        if let Some(captures) = exactly.captures(prompt) {
+            let count = match captures[1].to_lowercase().as_str() {
+                "two" | "deux" => 2,
+                "three" | "trois" => 3,
+                "four" | "quatre" => 4,
+                digits => digits.parse().unwrap_or(1),
+            };
+            return if count > 1 { Selection::Exactly(count) } else { Selection::Single };
+        }
+        if all_that_apply.is_match(prompt) {
+            return Selection::AllThatApply;
+        }
+        Selection::Single
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels_and_texts(parsed: &ParsedMCQ) -> Vec<(&str, &str)> {
+        parsed.options.iter().map(|option| (option.label.as_str(), option.text.as_str())).collect()
+    }
+
+    #[test]
+    fn test_parse_mcq_formats() {
+        let lettered = McqSolver::parse_mcq("Quelle est la capitale de la France ? A. Lyon B. Paris C. Marseille D. Lille E. Nantes").unwrap();
+        assert_eq!(lettered.question, "Quelle est la capitale de la France ?");
+        assert_eq!(labels_and_texts(&lettered), vec![("A", "Lyon"), ("B", "Paris"), ("C", "Marseille"), ("D", "Lille"), ("E", "Nantes")]);
+        assert_eq!(lettered.selection, Selection::Single);
+
+        let numbered = McqSolver::parse_mcq("Which planets are gas giants? Choose two.\n1. Jupiter\n2) Mars\n3. Saturn\n4. Venus").unwrap();
+        assert_eq!(labels_and_texts(&numbered), vec![("1", "Jupiter"), ("2", "Mars"), ("3", "Saturn"), ("4", "Venus")]);
+        assert_eq!(numbered.selection, Selection::Exactly(2));
+
+        let bulleted = McqSolver::parse_mcq("Quels sont des mammifères ? (une ou plusieurs réponses)\n- Le chat\n- La baleine\n- Toutes les réponses ci-dessus").unwrap();
+        assert_eq!(labels_and_texts(&bulleted), vec![("A", "Le chat"), ("B", "La baleine"), ("C", "Toutes les réponses ci-dessus")]);
+        assert_eq!(bulleted.options[2].kind, OptionKind::AllOfTheAbove);
+        assert_eq!(bulleted.selection, Selection::AllThatApply);
+
+        let initials = McqSolver::parse_mcq("Who succeeded John F. Kennedy? a) Nixon b) Johnson c) None of the above").unwrap();
+        assert_eq!(labels_and_texts(&initials), vec![("a", "Nixon"), ("b", "Johnson"), ("c", "None of the above")]);
+        assert_eq!(initials.question, "Who succeeded John F. Kennedy?");
+        assert_eq!(initials.options[2].kind, OptionKind::NoneOfTheAbove);
+
+        assert!(McqSolver::parse_mcq("Qui était John F. Kennedy ?").is_none());
+    }
+
+    fn scores(values: &[(&str, f32)]) -> Vec<OptionScore> {
+        let mut scores: Vec<OptionScore> = values
+            .iter()
+            .map(|(label, score)| OptionScore { label: label.to_string(), score: *score, discriminative_score: *score, probability: 0.0 })
             .collect();
+        McqSolver::calibrate(&mut scores, McqConfig::default().temperature);
+        scores
+    }
+
+    fn selected(prompt: &str, values: &[(&str, f32)]) -> Option<Vec<String>> {
+        McqSolver::select(&McqSolver::parse_mcq(prompt).unwrap(), &scores(values), &McqConfig::default()).map(|(labels, _)| labels)
+    }
+
+    #[test]
+    fn test_select_handles_multiple_and_special_options() {
+        let labels = |labels: &[&str]| Some(labels.iter().map(|label| label.to_string()).collect::<Vec<_>>());
+        assert_eq!(selected("Choose two: 1. Jupiter 2. Mars 3. Saturn 4. Venus", &[("1", 0.8), ("2", 0.05), ("3", 0.6), ("4", 0.3)]), labels(&["1", "3"]));
+        assert_eq!(selected("Quels sont des mammifères ?\n- Le chat\n- La baleine\n- Toutes les réponses", &[("A", 0.7), ("B", 0.4)]), labels(&["C"]));
+        assert_eq!(selected("Who was the first man on Mars? A. Armstrong B. Gagarin C. None of the above", &[("A", 0.02), ("B", 0.0)]), labels(&["C"]));
+        assert_eq!(selected("Capitale ? A. Lyon B. Paris", &[("A", 0.2), ("B", 0.9)]), labels(&["B"]));
+        assert_eq!(selected("Capitale ? A. Lyon B. Paris", &[("A", 0.0), ("B", 0.05)]), None);
+    }
+
+    #[test]
+    fn test_discriminative_scoring_calibrates_and_abstains() {
+        let calibrated = scores(&[("A", 0.9), ("B", 0.6), ("C", 0.6)]);
+        let total: f32 = calibrated.iter().map(|option| option.probability).sum();
+        assert!((total - 1.0).abs() < 1e-5);
+        assert!(calibrated[0].probability > 0.9);
+        assert!((calibrated[1].probability - calibrated[2].probability).abs() < 1e-6);
+
+        // Near-synonyms tie: the solver abstains rather than pick one at random.
+        assert_eq!(selected("Capitale ? A. Paris B. La ville de Paris C. Lyon", &[("A", 0.61), ("B", 0.6), ("C", 0.1)]), None);
+        let (_, margin) = McqSolver::select(&McqSolver::parse_mcq("Capitale ? A. Lyon B. Paris").unwrap(), &scores(&[("A", 0.2), ("B", 0.9)]), &McqConfig::default()).unwrap();
+        assert!(margin > 0.99);
 
-        Some(ParsedMCQ { question, options })
+        assert_eq!(McqSolver::distinctive_words("La ville de Paris", &["Paris", "La ville de Lyon"]), Vec::<String>::new());
+        assert_eq!(McqSolver::distinctive_words("Le Havre", &["Paris", "Lyon"]), vec!["le", "havre"]);
     }
 }
@@ -0,0 +1,184 @@
+//! metrics.rs - A lightweight metrics registry, rendered in the Prometheus text format.
+//!
+//! The performance metrics used to be pushed over the visualizer's WebSocket only. The
+//! registry collects prompt counts and latency histograms from inside the Core (it is global,
+//! so that deep components such as the Hippocampus can record into it without plumbing), and
+//! `render_prometheus` combines them with the gauges of a `CoreSnapshot`.
+
+use crate::core_actor::CoreSnapshot;
+use crate::thalamus::QueryType;
+use atomic_float::AtomicF64;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const QUERY_TYPES: [QueryType; 7] = [
+    QueryType::Introspective,
+    QueryType::Factual,
+    QueryType::Creative,
+    QueryType::Social,
+    QueryType::Procedural,
+    QueryType::Comparative,
+    QueryType::Ambiguous,
+];
+
+/// Upper bounds (in seconds) of the prompt latency buckets.
+const REASONING_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+/// Upper bounds (in seconds) of the hippocampus search buckets.
+const SEARCH_BUCKETS: &[f64] = &[0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0];
+
+/// A histogram with fixed buckets, safe to update from any thread.
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: &'static [f64],
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum: AtomicF64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Self { bounds, buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(), count: AtomicU64::new(0), sum: AtomicF64::new(0.0) }
+    }
+
+    pub fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        if let Some(bucket) = self.bounds.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(seconds, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Writes the `_bucket`, `_sum` and `_count` series. `labels` is either empty or ends with a comma.
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (bound, bucket) in self.bounds.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{{}le=\"{}\"}} {}", name, labels, bound, cumulative);
+        }
+        let count = self.count();
+        let _ = writeln!(out, "{}_bucket{{{}le=\"+Inf\"}} {}", name, labels, count);
+        let labels = labels.trim_end_matches(',');
+        let braces = if labels.is_empty() { String::new() } else { format!("{{{}}}", labels) };
+        let _ = writeln!(out, "{}_sum{} {}", name, braces, self.sum.load(Ordering::Relaxed));
+        let _ = writeln!(out, "{}_count{} {}", name, braces, count);
+    }
+}
+
+/// Counters and histograms recorded by the Core.
+#[derive(Debug)]
+pub struct MetricsRegistry {
+    prompts: [AtomicU64; QUERY_TYPES.len()],
+    reasoning_latency: [Histogram; QUERY_TYPES.len()],
+    pub hippocampus_search: Histogram,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self {
+            prompts: Default::default(),
+            reasoning_latency: std::array::from_fn(|_| Histogram::new(REASONING_BUCKETS)),
+            hippocampus_search: Histogram::new(SEARCH_BUCKETS),
+        }
+    }
+
+    /// Counts an answered prompt and the time it took, by query type.
+    pub fn record_prompt(&self, query_type: QueryType, latency: Duration) {
+        let index = query_type_index(query_type);
+        self.prompts[index].fetch_add(1, Ordering::Relaxed);
+        self.reasoning_latency[index].observe(latency);
+    }
+
+    pub fn prompts(&self, query_type: QueryType) -> u64 {
+        self.prompts[query_type_index(query_type)].load(Ordering::Relaxed)
+    }
+
+    /// Renders the registry and the gauges of `snapshot` in the Prometheus text format (0.0.4).
+    pub fn render_prometheus(&self, snapshot: &CoreSnapshot) -> String {
+        let mut out = String::new();
+        let mut gauge = |name: &str, help: &str, kind: &str, value: f64| {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}\n{} {}", name, help, name, kind, name, value);
+        };
+        gauge("neurova_ticks_total", "Simulation ticks since startup.", "counter", snapshot.tick as f64);
+        gauge("neurova_tick_rate", "Simulation ticks per second.", "gauge", snapshot.processing_speed as f64);
+        gauge("neurova_power_draw_watts", "Estimated power draw of the connectome.", "gauge", snapshot.power_draw as f64);
+        gauge("neurova_memories", "Holographic memories in the hippocampus.", "gauge", snapshot.memories_in_hippocampus as f64);
+        gauge("neurova_concepts", "Concepts in the conceptual hierarchy.", "gauge", snapshot.concepts_in_hierarchy as f64);
+        gauge("neurova_stdp_potentiated_total", "Synapses potentiated by spike-timing-dependent plasticity.", "counter", snapshot.stdp.potentiated as f64);
+        gauge("neurova_stdp_depressed_total", "Synapses depressed by spike-timing-dependent plasticity.", "counter", snapshot.stdp.depressed as f64);
+        gauge("neurova_ready", "1 once the warmup phase has completed.", "gauge", if snapshot.ready { 1.0 } else { 0.0 });
+
+        out.push_str("# HELP neurova_prompts_total Prompts answered, by query type.\n# TYPE neurova_prompts_total counter\n");
+        for query_type in QUERY_TYPES {
+            let _ = writeln!(out, "neurova_prompts_total{{query_type=\"{}\"}} {}", query_type_label(query_type), self.prompts(query_type));
+        }
+
+        out.push_str("# HELP neurova_reasoning_latency_seconds Time to answer a prompt, by query type.\n# TYPE neurova_reasoning_latency_seconds histogram\n");
+        for query_type in QUERY_TYPES {
+            let labels = format!("query_type=\"{}\",", query_type_label(query_type));
+            self.reasoning_latency[query_type_index(query_type)].render(&mut out, "neurova_reasoning_latency_seconds", &labels);
+        }
+
+        out.push_str("# HELP neurova_hippocampus_search_seconds Time of a hippocampus similarity search.\n# TYPE neurova_hippocampus_search_seconds histogram\n");
+        self.hippocampus_search.render(&mut out, "neurova_hippocampus_search_seconds", "");
+        out
+    }
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The process-wide registry.
+pub fn registry() -> &'static MetricsRegistry {
+    static REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetricsRegistry::new)
+}
+
+fn query_type_index(query_type: QueryType) -> usize {
+    QUERY_TYPES.iter().position(|candidate| *candidate == query_type).unwrap_or(QUERY_TYPES.len() - 1)
+}
+
+fn query_type_label(query_type: QueryType) -> &'static str {
+    match query_type {
+        QueryType::Introspective => "introspective",
+        QueryType::Factual => "factual",
+        QueryType::Creative => "creative",
+        QueryType::Social => "social",
+        QueryType::Procedural => "procedural",
+        QueryType::Comparative => "comparative",
+        QueryType::Ambiguous => "ambiguous",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_rendering() {
+        let registry = MetricsRegistry::new();
+        registry.record_prompt(QueryType::Factual, Duration::from_millis(30));
+        registry.record_prompt(QueryType::Factual, Duration::from_secs(20));
+        registry.hippocampus_search.observe(Duration::from_micros(300));
+
+        let text = registry.render_prometheus(&CoreSnapshot { tick: 42, ready: true, ..CoreSnapshot::default() });
+        assert!(text.contains("neurova_ticks_total 42\n"));
+        assert!(text.contains("neurova_ready 1\n"));
+        assert!(text.contains("neurova_prompts_total{query_type=\"factual\"} 2\n"));
+        assert!(text.contains("neurova_prompts_total{query_type=\"social\"} 0\n"));
+        assert!(text.contains("neurova_reasoning_latency_seconds_bucket{query_type=\"factual\",le=\"0.05\"} 1\n"));
+        assert!(text.contains("neurova_reasoning_latency_seconds_bucket{query_type=\"factual\",le=\"+Inf\"} 2\n"));
+        assert!(text.contains("neurova_reasoning_latency_seconds_count{query_type=\"factual\"} 2\n"));
+        assert!(text.contains("neurova_hippocampus_search_seconds_bucket{le=\"0.0005\"} 1\n"));
+        assert!(text.contains("neurova_hippocampus_search_seconds_count 1\n"));
+    }
+}
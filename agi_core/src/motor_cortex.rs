@@ -2,24 +2,133 @@
 
 //! The Motor Cortex is responsible for generating coherent, human-readable text responses.
 
+use crate::answer_span;
 use crate::conceptual_hierarchy::ConceptualHierarchy;
+use crate::creative_writing::{CreativeDraft, CreativeForm};
+use crate::error::LockResultExt;
+use crate::expertise::ExpertiseLevel;
 use crate::holographic_memory::HolographicMemory;
+use crate::lemmatizer::Lang;
+use crate::numeric_reasoner;
+use crate::onboarding;
 use crate::prefrontal_cortex::PrefrontalCortex;
 use crate::self_awareness::SelfAwareness;
-use crate::personality::Personality;
+use crate::sensory_cortex::{Sentiment, SensoryCortex};
+use crate::sentence_composer::Proposition;
+use crate::personality::{Personality, PersonalityProfile};
+use crate::surface_realization;
+use crate::thalamus::QueryType;
+use crate::tools::ActionRequest;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Mutex, OnceLock};
 
+/// The strategies through which the motor cortex phrases an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MotorStrategy {
+    /// No memory was retrieved: the answer admits it.
+    NoMemory,
+    /// The structured identity of the Core (see `SelfAwareness::answer`).
+    Introspective,
+    /// Numbered steps (`compose_steps`).
+    Procedural,
+    /// The retrieved memories, listed side by side.
+    Comparative,
+    /// The best memory, recomposed (or verbatim for experts), after the span of it that answers
+    /// the question when there is one (see `answer_span`).
+    Factual,
+}
+
+/// Réponses récentes gardées pour éviter de se répéter.
+const RECENT_RESPONSES: usize = 10;
+/// Part de mots communs (indice de Jaccard) à partir de laquelle deux réponses sont tenues pour
+/// identiques.
+const NEAR_IDENTICAL: f32 = 0.9;
+
+/// Les dernières réponses de la conversation, pour en varier la formulation.
+#[derive(Debug, Default)]
+struct RecentResponses {
+    words: VecDeque<HashSet<String>>,
+}
+
+impl RecentResponses {
+    fn words_of(text: &str) -> HashSet<String> {
+        text.to_lowercase().split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()).map(String::from).collect()
+    }
+
+    /// Vrai si `text` reprend, à quelques mots près, une réponse récente.
+    fn repeats(&self, text: &str) -> bool {
+        let words = Self::words_of(text);
+        !words.is_empty()
+            && self.words.iter().any(|recent| {
+                let shared = words.intersection(recent).count();
+                shared as f32 / (words.len() + recent.len() - shared) as f32 >= NEAR_IDENTICAL
+            })
+    }
+
+    fn remember(&mut self, text: &str) {
+        self.words.push_back(Self::words_of(text));
+        if self.words.len() > RECENT_RESPONSES {
+            self.words.pop_front();
+        }
+    }
+}
 
 pub struct MotorCortex {
     personality: Personality,
+    // Derrière un verrou car la génération n'emprunte le cortex qu'en lecture.
+    recent_responses: Mutex<RecentResponses>,
+    user_sentiment: Sentiment,
 }
 
 impl MotorCortex {
     pub fn new(personality: Personality) -> Self {
         Self {
             personality,
+            recent_responses: Mutex::new(RecentResponses::default()),
+            user_sentiment: Sentiment::default(),
         }
     }
 
+    /// Whether `response` is nearly identical to one of the last answers of the conversation.
+    pub fn repeats_recent_response(&self, response: &str) -> bool {
+        self.recent_responses.lock().recover().repeats(response)
+    }
+
+    /// Counts `response` among the last answers, e.g. when it came from the response cache.
+    pub fn remember_response(&self, response: &str) {
+        self.recent_responses.lock().recover().remember(response);
+    }
+
+    /// Forgets the last answers (the warmup prompts are not part of the conversation).
+    pub fn forget_recent_responses(&self) {
+        *self.recent_responses.lock().recover() = RecentResponses::default();
+    }
+
+    /// Switches the traits the answers are styled with (see `Personality::set_profile`).
+    pub fn set_personality_profile(&mut self, profile: PersonalityProfile) {
+        self.personality.set_profile(profile);
+    }
+
+    /// `text` to be spoken with the voice and speaking rate of the active profile.
+    #[cfg(feature = "tts")]
+    pub fn utterance(&self, text: &str, lang: Lang, config: &crate::speech::SpeechConfig) -> crate::speech::Utterance {
+        crate::speech::Utterance { text: text.to_string(), lang, profile: self.personality.profile().clone(), config: config.clone() }
+    }
+
+    /// How readily the tone of the answers changes (see `Personality::set_tone_stability`).
+    pub fn set_tone_stability(&mut self, stability: f32) {
+        self.personality.set_tone_stability(stability);
+    }
+
+    /// The sentiment of the prompt being answered: a negative one is acknowledged before the
+    /// answer (see `SensoryCortex::score_sentiment`).
+    pub fn set_user_sentiment(&mut self, sentiment: Sentiment) {
+        self.user_sentiment = sentiment;
+    }
+
     /// Generates a response by synthesizing concepts from reasoning results or falling back to direct recall.
     ///
     /// The cognitive hierarchy is as follows:
@@ -27,37 +136,47 @@ impl MotorCortex {
     /// 2. **Factual Recall:** If synthesis isn't possible or only one memory is found, state the fact directly.
     /// 3. **Self-Awareness:** If no memories are found, fall back to identity-based responses.
     /// 4. **Acknowledgment of Ignorance:** If all else fails, admit not having a relevant memory.
+    ///
+    /// The framing sentences are written in `lang`; the facts themselves keep the language they were learned in.
+    /// An answer nearly identical to a recent one is phrased differently (another template, or
+    /// another supporting memory) or, failing that, introduced as a reminder. A user who sounds
+    /// upset gets a word of empathy first.
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_response(
         &self,
         last_input: &str,
         reasoning_result: &Option<Vec<HolographicMemory>>,
-        _self_awareness: &SelfAwareness,
-        _prefrontal_cortex: &PrefrontalCortex,
-        _conceptual_hierarchy: &ConceptualHierarchy,
+        self_awareness: &SelfAwareness,
+        prefrontal_cortex: &PrefrontalCortex,
+        conceptual_hierarchy: &ConceptualHierarchy,
         query_type: crate::thalamus::QueryType,
+        lang: Lang,
     ) -> Option<String> {
+        let expertise = prefrontal_cortex.expertise_level();
+        let memories = reasoning_result.as_deref().unwrap_or_default();
 
-        if let Some(memories) = reasoning_result {
-            if memories.is_empty() {
-                return Some("J'ai examiné votre question, mais je n'ai pas de réponse spécifique dans ma mémoire.".to_string());
-            }
+        let strategy = Self::select_strategy(last_input, reasoning_result, query_type);
+        let response = match strategy {
+            MotorStrategy::NoMemory => Self::no_answer(lang).to_string(),
 
             // --- Stratégie 1: Réponse introspective --- 
-            if query_type == crate::thalamus::QueryType::Introspective {
-                let intro = "Je suis une entité définie par les principes suivants:".to_string();
-                let axioms = memories
-                    .iter()
-                    .map(|mem| format!("- {}", mem.text))
-                    .collect::<Vec<String>>()
-                    .join("\n");
-                return Some(format!("{}\n{}", intro, axioms));
+            MotorStrategy::Introspective => self_awareness.answer(last_input, lang),
+
+            // --- Stratégie 2: Étapes ordonnées pour une question procédurale ---
+            MotorStrategy::Procedural => {
+                let steps = Self::compose_steps(memories, lang, expertise);
+                self.adapt_to_expertise(&steps, expertise, conceptual_hierarchy, lang)
             }
 
-            // --- Stratégie 2: Synthèse comparative --- 
-            let is_comparative_query = last_input.contains(" et ") || last_input.contains(" vs ") || last_input.contains("compare");
-            if is_comparative_query && memories.len() > 1 {
+            // --- Stratégie 3: Synthèse comparative --- 
+            MotorStrategy::Comparative => {
                 let mut response_parts = Vec::new();
-                response_parts.push("Voici une comparaison basée sur les informations dont je dispose :".to_string());
+                if expertise != ExpertiseLevel::Expert {
+                    response_parts.push(match lang {
+                        Lang::French => "Voici une comparaison basée sur les informations dont je dispose :".to_string(),
+                        Lang::English => "Here is a comparison based on the information I have:".to_string(),
+                    });
+                }
 
                 for memory in memories {
                     // On présente directement le fait, la stylisation se fait sur l'ensemble.
@@ -65,22 +184,528 @@ impl MotorCortex {
                 }
                 
                 let final_response = response_parts.join("");
-                return Some(self.personality.stylize_response(&final_response));
-                
-
+                self.adapt_to_expertise(final_response.trim_start(), expertise, conceptual_hierarchy, lang)
             }
 
-            // --- Stratégie 3: Réponse factuelle directe (Fallback) ---
-            if let Some(best_memory) = memories.first() {
-                let stylized_response = self.personality.stylize_response(&best_memory.text);
-                return Some(stylized_response);
+            // --- Stratégie 4: Réponse factuelle, recomposée plutôt que recopiée (Fallback) ---
+            MotorStrategy::Factual => {
+                let answer = match expertise {
+                    // L'expert reçoit le fait tel qu'il a été appris.
+                    ExpertiseLevel::Expert => None,
+                    _ => self.compose(memories),
+                };
+                let supporting = self.adapt_to_expertise(answer.as_deref().unwrap_or(&memories[0].text), expertise, conceptual_hierarchy, lang);
+                Self::with_answer_span(last_input, &memories[0], Self::attributed(&memories[0], supporting, lang))
             }
+        };
 
-            Some("J'ai du mal à formuler une réponse pour le moment.".to_string())
+        let mut recent = self.recent_responses.lock().recover();
+        let response = if recent.repeats(&response) {
+            let alternatives = match strategy {
+                MotorStrategy::Factual => Self::alternative_phrasings(memories),
+                _ => Vec::new(),
+            };
+            alternatives
+                .into_iter()
+                .map(|alternative| self.adapt_to_expertise(&alternative, expertise, conceptual_hierarchy, lang))
+                .find(|alternative| !recent.repeats(alternative))
+                .unwrap_or_else(|| Self::as_mentioned(&response, lang))
         } else {
-            Some("J'ai examiné votre question, mais je n'ai pas de réponse spécifique dans ma mémoire.".to_string())
+            response
+        };
+        recent.remember(&response);
+        Some(self.with_empathy(response, lang))
+    }
+
+    /// Donne d'abord les quelques mots du souvenir qui répondent à la question (« 14 mars
+    /// 1879. »), puis la phrase qui les appuie.
+    fn with_answer_span(last_input: &str, memory: &HolographicMemory, supporting: String) -> String {
+        match answer_span::extract(last_input, memory) {
+            Some(span) => surface_realization::join(&format!("{}.", span.text.trim_end_matches('.')), &supporting),
+            None => supporting,
+        }
+    }
+
+    /// Nomme la source du souvenir quand le Core ne s'en porte pas garant (« D'après une source
+    /// trouvée sur le web, ... »).
+    fn attributed(memory: &HolographicMemory, text: String, lang: Lang) -> String {
+        match memory.source.attribution(lang) {
+            Some(attribution) => surface_realization::join(attribution, &text),
+            None => text,
+        }
+    }
+
+    /// Fait précéder la réponse d'un mot d'empathie quand l'utilisateur semble contrarié :
+    /// agacement si le sentiment est vif, tristesse sinon.
+    fn with_empathy(&self, response: String, lang: Lang) -> String {
+        const ANGRY_AROUSAL: f32 = 0.6;
+        if !self.user_sentiment.is_negative() {
+            return response;
+        }
+        let opener = match (self.user_sentiment.arousal >= ANGRY_AROUSAL, lang) {
+            (true, Lang::French) => "Je comprends votre agacement.",
+            (true, Lang::English) => "I understand your frustration.",
+            (false, Lang::French) => "Je suis désolée que ce soit difficile.",
+            (false, Lang::English) => "I'm sorry you're having a hard time.",
+        };
+        surface_realization::join(opener, &response)
+    }
+
+    /// Les autres façons de donner une réponse factuelle : chaque tournure du meilleur souvenir,
+    /// puis les souvenirs suivants pris comme fait principal.
+    fn alternative_phrasings(memories: &[HolographicMemory]) -> Vec<String> {
+        let mut alternatives = Vec::new();
+        if let Some(main) = memories.first().and_then(|memory| Proposition::parse(&memory.text)) {
+            alternatives.extend((0..main.variants()).map(|variant| main.render(variant)));
+        }
+        for memory in memories.iter().skip(1) {
+            match Proposition::parse(&memory.text) {
+                Some(proposition) => alternatives.extend((0..proposition.variants()).map(|variant| proposition.render(variant))),
+                None => alternatives.push(memory.text.clone()),
+            }
+        }
+        alternatives
+    }
+
+    /// Présente une réponse déjà donnée comme un rappel.
+    fn as_mentioned(response: &str, lang: Lang) -> String {
+        let reminder = match lang {
+            Lang::French => "Comme je l'ai mentionné,",
+            Lang::English => "As I mentioned,",
+        };
+        surface_realization::join(reminder, response)
+    }
+
+    /// La stratégie de `generate_response` pour ces souvenirs et ce type de question.
+    pub fn select_strategy(last_input: &str, reasoning_result: &Option<Vec<HolographicMemory>>, query_type: QueryType) -> MotorStrategy {
+        // L'identité répond aux questions introspectives, même sans souvenir retrouvé.
+        if query_type == QueryType::Introspective {
+            return MotorStrategy::Introspective;
+        }
+        let memories = match reasoning_result {
+            Some(memories) if !memories.is_empty() => memories,
+            _ => return MotorStrategy::NoMemory,
+        };
+        let is_comparative_query = query_type == QueryType::Comparative
+            || last_input.contains(" et ")
+            || last_input.contains(" and ")
+            || last_input.contains(" vs ")
+            || last_input.contains("compare");
+        match query_type {
+            QueryType::Procedural => MotorStrategy::Procedural,
+            _ if is_comparative_query && memories.len() > 1 => MotorStrategy::Comparative,
+            _ => MotorStrategy::Factual,
+        }
+    }
+
+    /// Les actions à demander avant de répondre, au vu de la question et des souvenirs déjà
+    /// retrouvés (voir `tools`) :
+    /// - une recherche sur le web ou la lecture d'un fichier demandées explicitement ;
+    /// - une conversion, quand la question demande une unité (« combien de pieds mesure
+    ///   l'Everest ? ») et qu'un souvenir donne la grandeur dans une autre (« 8 849 m ») ;
+    /// - une recherche sur le web pour une question factuelle sans aucun souvenir.
+    pub fn plan_actions(last_input: &str, memories: &[HolographicMemory], query_type: QueryType) -> Vec<ActionRequest> {
+        static WEB_SEARCH: OnceLock<Regex> = OnceLock::new();
+        static READ_FILE: OnceLock<Regex> = OnceLock::new();
+        let web_search = WEB_SEARCH.get_or_init(|| {
+            Regex::new(r"(?i)\b(?:(?:re)?cherche(?:r)?\s+(?:sur\s+(?:le\s+web|internet)|en\s+ligne)|search\s+(?:the\s+web|online)\s+for|look\s+up)\s*:?\s+(.+?)[\s?.!]*$").unwrap()
+        });
+        let read_file = READ_FILE.get_or_init(|| Regex::new(r#"(?i)\b(?:lis|lire|ouvre|read|open)\s+(?:le\s+fichier|the\s+file)\s+["«]?\s*([^\s"»]+?)[?.!]*(?:\s|["»]|$)"#).unwrap());
+
+        let mut actions = Vec::new();
+        if let Some(captures) = web_search.captures(last_input) {
+            actions.push(ActionRequest::WebSearch { query: captures[1].to_string() });
+        }
+        if let Some(captures) = read_file.captures(last_input) {
+            actions.push(ActionRequest::ReadFile { path: captures[1].to_string() });
         }
+
+        let lower = last_input.to_lowercase().replace('’', "'");
+        if let Some(target) = numeric_reasoner::target_unit(&lower).filter(|_| SensoryCortex::extract_quantities(&lower).is_empty()) {
+            let known = memories
+                .iter()
+                .flat_map(|memory| SensoryCortex::extract_quantities(&memory.text))
+                .find(|quantity| quantity.unit.dimension == target.dimension && quantity.unit != target);
+            if let Some(quantity) = known {
+                let expression = format!("convert {} {} to {}", quantity.value, quantity.unit.aliases()[0], target.aliases()[0]);
+                actions.push(ActionRequest::Calculate { expression });
+            }
+        }
+
+        if actions.is_empty() && memories.is_empty() && query_type == QueryType::Factual {
+            actions.push(ActionRequest::WebSearch { query: last_input.trim().trim_end_matches(['?', '.', '!']).trim().to_string() });
+        }
+        actions
     }
 
+    fn no_answer(lang: Lang) -> &'static str {
+        match lang {
+            Lang::French => "J'ai examiné votre question, mais je n'ai pas de réponse spécifique dans ma mémoire.",
+            Lang::English => "I looked into your question, but I have no specific answer in my memory.",
+        }
+    }
 
+    /// Reformule le meilleur souvenir (sujet, relation, objet) avec une tournure choisie par la
+    /// personnalité, et le prolonge par le souvenir suivant s'il porte sur le même sujet.
+    /// Retourne None si le meilleur souvenir ne se laisse pas analyser (citations, listes...).
+    fn compose(&self, memories: &[HolographicMemory]) -> Option<String> {
+        let main = Proposition::parse(&memories.first()?.text)?;
+        let mut answer = main.render(self.personality.choose_variant(main.variants()));
+        if let Some(continuation) = memories.get(1).and_then(|memory| Proposition::parse(&memory.text)).and_then(|next| next.render_continuation(&main)) {
+            answer.push(' ');
+            answer.push_str(&continuation);
+        }
+        Some(answer)
+    }
+
+    /// Présente une comparaison structurée : ce que l'on sait de chacun des deux termes, puis
+    /// les souvenirs qui les mentionnent tous les deux.
+    pub fn compose_comparison(
+        &self,
+        subjects: (&str, &str),
+        first: &[HolographicMemory],
+        second: &[HolographicMemory],
+        lang: Lang,
+        expertise: ExpertiseLevel,
+        conceptual_hierarchy: &ConceptualHierarchy,
+    ) -> String {
+        let mentions_both = |memory: &HolographicMemory| {
+            let text = memory.text.to_lowercase();
+            text.contains(subjects.0) && text.contains(subjects.1)
+        };
+        let mut both: Vec<&str> = Vec::new();
+        for memory in first.iter().chain(second) {
+            let in_both_groups = first.iter().any(|m| m.id == memory.id) && second.iter().any(|m| m.id == memory.id);
+            if (in_both_groups || mentions_both(memory)) && !both.contains(&memory.text.as_str()) {
+                both.push(&memory.text);
+            }
+        }
+        let own = |memories: &[HolographicMemory]| -> Vec<String> {
+            memories.iter().filter(|memory| !both.contains(&memory.text.as_str())).map(|memory| format!("- {}", memory.text.trim())).collect()
+        };
+
+        let (title, unknown, both_title) = match lang {
+            Lang::French => (format!("Comparaison entre « {} » et « {} » :", subjects.0, subjects.1), "- Je n'ai pas d'information sur ce point.", "Les deux :"),
+            Lang::English => (format!("Comparison between \"{}\" and \"{}\":", subjects.0, subjects.1), "- I have no information on this.", "Both:"),
+        };
+        let mut sections = Vec::new();
+        if expertise != ExpertiseLevel::Expert {
+            sections.push(title);
+        }
+        for (subject, memories) in [(subjects.0, first), (subjects.1, second)] {
+            let lines = own(memories);
+            let body = if lines.is_empty() { unknown.to_string() } else { lines.join("\n") };
+            let mut heading: String = subject.chars().take(1).flat_map(char::to_uppercase).chain(subject.chars().skip(1)).collect();
+            heading.push_str(if lang == Lang::French { " :" } else { ":" });
+            sections.push(format!("{}\n{}", heading, body));
+        }
+        if !both.is_empty() {
+            let lines: Vec<String> = both.iter().map(|text| format!("- {}", text.trim())).collect();
+            sections.push(format!("{}\n{}", both_title, lines.join("\n")));
+        }
+        let comparison = sections.join("\n\n");
+
+        // Novices get the definitions of the known concepts; the style is left out to keep the structure readable.
+        match expertise {
+            ExpertiseLevel::Novice => self.adapt_to_expertise(&comparison, expertise, conceptual_hierarchy, lang),
+            _ => comparison,
+        }
+    }
+
+    /// Écrit un poème ou une histoire à partir des ponts de Silicium : une strophe ou un épisode
+    /// par pont, entre une ouverture et une chute sur le premier concept, le thème. Le texte
+    /// s'annonce comme une création, pour n'être pas pris pour un fait.
+    pub fn compose_creative(&self, draft: &CreativeDraft, lang: Lang) -> String {
+        let Some(theme) = draft.concepts.first() else {
+            return match lang {
+                Lang::French => "Je n'ai trouvé aucune idée à laquelle me raccrocher pour cette création.".to_string(),
+                Lang::English => "I found no idea to hold on to for this piece.".to_string(),
+            };
+        };
+        let sources = onboarding::quote_list(&draft.concepts[..draft.concepts.len().min(3)], lang);
+        let fill = |template: &str, from: &str, to: &str, via: &str| template.replace("{a}", from).replace("{b}", to).replace("{via}", via);
+
+        let (intro, opening, closing, alone, linked, leaps) = match (draft.form, lang) {
+            (CreativeForm::Poem, Lang::French) => (
+                format!("Voici un poème que j'ai imaginé, à partir de {} :", sources),
+                "",
+                "Et tout revient à {a}.",
+                "{a}, je te cherche
+et je te trouve partout.",
+                ["{a} rêve à {b},
+et {via} chante entre eux.", "Entre {a} et {b},
+{via} tisse un fil de lumière."],
+                ["{a} ne connaît pas {b},
+pourtant leurs ombres se frôlent.", "Qui dira pourquoi {a}
+ressemble tant à {b} ?"],
+            ),
+            (CreativeForm::Poem, Lang::English) => (
+                format!("Here is a poem I imagined from {}:", sources),
+                "",
+                "And all returns to {a}.",
+                "{a}, I look for you
+and find you everywhere.",
+                ["{a} dreams of {b},
+and {via} sings between them.", "Between {a} and {b},
+{via} weaves a thread of light."],
+                ["{a} has never met {b},
+yet their shadows touch.", "Who will say why {a}
+looks so much like {b}?"],
+            ),
+            (CreativeForm::Story, Lang::French) => (
+                format!("Voici une histoire que j'ai imaginée, à partir de {} :", sources),
+                "Au commencement, il n'y avait que {a}.",
+                "Depuis ce jour, {a} n'est plus tout à fait le même.",
+                "{a} rêvait d'être autre chose, sans savoir quoi.",
+                ["Un jour, {a} croisa {via}, qui lui montra le chemin vers {b}.", "{via} mena {a} jusqu'à {b}, que nul n'avait encore réunis."],
+                ["Puis, sans prévenir, {a} rencontra {b}.", "Personne ne sait comment {a} et {b} devinrent inséparables."],
+            ),
+            (CreativeForm::Story, Lang::English) => (
+                format!("Here is a story I imagined from {}:", sources),
+                "In the beginning, there was only {a}.",
+                "Since that day, {a} has never been quite the same.",
+                "{a} dreamed of being something else, without knowing what.",
+                ["One day, {a} came across {via}, who showed it the way to {b}.", "{via} led {a} all the way to {b}; no one had ever brought them together."],
+                ["Then, out of nowhere, {a} met {b}.", "No one knows how {a} and {b} became inseparable."],
+            ),
+        };
+
+        let mut parts: Vec<String> = Vec::new();
+        if !opening.is_empty() {
+            parts.push(fill(opening, theme, "", ""));
+        }
+        if draft.bridges.is_empty() {
+            parts.push(fill(alone, theme, "", ""));
+        }
+        for bridge in &draft.bridges {
+            let line = match &bridge.via {
+                Some(_) => linked[self.personality.choose_variant(linked.len())],
+                None => leaps[self.personality.choose_variant(leaps.len())],
+            };
+            parts.push(fill(line, &bridge.from, &bridge.to, bridge.via.as_deref().unwrap_or_default()));
+        }
+        parts.push(fill(closing, theme, "", ""));
+
+        let separator = match draft.form {
+            CreativeForm::Poem => "
+
+",
+            CreativeForm::Story => " ",
+        };
+        format!("{}
+
+{}", intro, parts.join(separator))
+    }
+
+    /// Met en étapes numérotées le contenu des souvenirs : leurs listes s'ils en contiennent,
+    /// leurs phrases sinon, dans l'ordre de pertinence.
+    fn compose_steps(memories: &[HolographicMemory], lang: Lang, expertise: ExpertiseLevel) -> String {
+        const MAX_STEPS: usize = 7;
+        const SEQUENCE_WORDS: &[&str] = &["d'abord,", "d'abord", "ensuite,", "ensuite", "puis", "enfin,", "enfin", "first,", "first", "then,", "then", "next,", "next", "finally,", "finally"];
+
+        let mut steps: Vec<String> = Vec::new();
+        for memory in memories {
+            let listed: Vec<&str> = memory
+                .text
+                .lines()
+                .map(str::trim)
+                .filter_map(|line| line.strip_prefix("- ").or_else(|| line.split_once(['.', ')']).filter(|(number, _)| number.parse::<u32>().is_ok()).map(|(_, step)| step)))
+                .collect();
+            let parts: Vec<&str> = if listed.is_empty() {
+                memory
+                    .text
+                    .lines()
+                    // Métadonnées du corpus (« // domains: ... ») et attributions (« ... - Socrate »).
+                    .filter(|line| !line.trim_start().starts_with("//"))
+                    .map(|line| line.rsplit_once(" - ").filter(|(_, author)| author.split_whitespace().count() <= 3).map_or(line, |(body, _)| body))
+                    .flat_map(|line| line.split(['.', ';']))
+                    .collect()
+            } else {
+                listed
+            };
+            for part in parts {
+                let mut step = part.trim();
+                let lower = step.to_lowercase();
+                if let Some(word) = SEQUENCE_WORDS.iter().find(|word| lower.starts_with(&format!("{} ", word))) {
+                    step = step[word.len()..].trim_start();
+                }
+                if step.split_whitespace().count() >= 2 && !steps.iter().any(|known| known.eq_ignore_ascii_case(step)) {
+                    steps.push(step.to_string());
+                }
+            }
+            if steps.len() >= MAX_STEPS {
+                break;
+            }
+        }
+        steps.truncate(MAX_STEPS);
+
+        let list = steps.iter().enumerate().map(|(index, step)| format!("{}. {}.", index + 1, step.trim_end_matches(['.', ':']))).collect::<Vec<_>>().join("\n");
+        match (expertise, lang) {
+            (ExpertiseLevel::Expert, _) => list,
+            (_, Lang::French) => format!("Voici les étapes :\n{}", list),
+            (_, Lang::English) => format!("Here are the steps:\n{}", list),
+        }
+    }
+
+    /// Adapte la formulation au niveau d'expertise de l'interlocuteur.
+    ///
+    /// - **Expert :** réponse brute, sans fioritures de personnalité.
+    /// - **Intermédiaire :** réponse stylisée par la personnalité.
+    /// - **Novice :** réponse simplifiée, suivie de définitions des concepts connus qu'elle mentionne.
+    ///
+    /// Les formules ajoutées sont rédigées dans la langue de la réponse (`lang`).
+    fn adapt_to_expertise(&self, core_response: &str, expertise: ExpertiseLevel, conceptual_hierarchy: &ConceptualHierarchy, lang: Lang) -> String {
+        match expertise {
+            ExpertiseLevel::Expert => core_response.to_string(),
+            ExpertiseLevel::Intermediate => self.personality.stylize_response(core_response, lang),
+            ExpertiseLevel::Novice => {
+                let (simply, definitions_title) = match lang {
+                    Lang::French => ("Pour faire simple :", "Quelques définitions utiles :"),
+                    Lang::English => ("Put simply:", "A few useful definitions:"),
+                };
+                let mut response = surface_realization::join(simply, core_response);
+                let definitions = Self::define_known_concepts(core_response, conceptual_hierarchy, lang);
+                if !definitions.is_empty() {
+                    response.push_str("\n\n");
+                    response.push_str(definitions_title);
+                    for definition in definitions {
+                        response.push_str(&format!("\n- {}", definition));
+                    }
+                }
+                response
+            }
+        }
+    }
+
+    /// Construit des définitions simples (« X est une sorte de Y ») pour les concepts de la
+    /// hiérarchie qui apparaissent dans le texte et possèdent au moins un parent.
+    fn define_known_concepts(text: &str, conceptual_hierarchy: &ConceptualHierarchy, lang: Lang) -> Vec<String> {
+        const MAX_DEFINITIONS: usize = 3;
+        let lower_text = text.to_lowercase();
+        let words: Vec<&str> = lower_text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() > 2)
+            .collect();
+
+        let mut definitions = Vec::new();
+        let mut defined = std::collections::HashSet::new();
+        for word in words {
+            if definitions.len() >= MAX_DEFINITIONS {
+                break;
+            }
+            let Some(concept) = conceptual_hierarchy.find_concept_by_name(word) else { continue };
+            if !defined.insert(concept.id) {
+                continue;
+            }
+            let parent_names: Vec<&str> = concept
+                .parents
+                .iter()
+                .filter_map(|id| conceptual_hierarchy.get_concept(*id))
+                .map(|parent| parent.name.as_str())
+                .collect();
+            if !parent_names.is_empty() {
+                definitions.push(match lang {
+                    Lang::French => format!("« {} » est une sorte de « {} ».", concept.name, parent_names.join(" », « ")),
+                    Lang::English => format!("\"{}\" is a kind of \"{}\".", concept.name, parent_names.join("\", \"")),
+                });
+            }
+        }
+        definitions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_trust::SourceTag;
+
+    #[test]
+    fn test_plan_actions() {
+        let memory = |text: &str| HolographicMemory::new(text.to_string(), crate::holographic_memory::HolographicTrace::new_empty(16), false);
+        let everest = [memory("L'Everest culmine à 8 849 m d'altitude.")];
+        assert_eq!(
+            MotorCortex::plan_actions("Combien de pieds mesure l'Everest ?", &everest, QueryType::Factual),
+            vec![ActionRequest::Calculate { expression: "convert 8849 m to ft".to_string() }]
+        );
+        assert!(MotorCortex::plan_actions("Combien de mètres mesure l'Everest ?", &everest, QueryType::Factual).is_empty(), "Already in meters");
+
+        assert_eq!(
+            MotorCortex::plan_actions("Cherche sur le web : les quasars ?", &everest, QueryType::Factual),
+            vec![ActionRequest::WebSearch { query: "les quasars".to_string() }]
+        );
+        assert_eq!(
+            MotorCortex::plan_actions("Lis le fichier « notes/quasars.txt » stp", &[], QueryType::Ambiguous),
+            vec![ActionRequest::ReadFile { path: "notes/quasars.txt".to_string() }]
+        );
+        assert_eq!(MotorCortex::plan_actions("Qu'est-ce qu'un blazar ?", &[], QueryType::Factual), vec![ActionRequest::WebSearch { query: "Qu'est-ce qu'un blazar".to_string() }]);
+        assert!(MotorCortex::plan_actions("Qu'est-ce qu'un blazar ?", &everest, QueryType::Factual).is_empty());
+    }
+
+    #[test]
+    fn test_repeated_answers_are_rephrased() {
+        let mut recent = RecentResponses::default();
+        recent.remember("Socrate était un philosophe grec.");
+        assert!(recent.repeats("socrate était un philosophe grec"));
+        assert!(!recent.repeats("Socrate fut le maître de Platon."));
+
+        let memory = |text: &str| HolographicMemory::new(text.to_string(), crate::holographic_memory::HolographicTrace::new_empty(16), false);
+        let alternatives = MotorCortex::alternative_phrasings(&[memory("Socrate était un philosophe grec."), memory("Connais-toi toi-même. - Socrate")]);
+        assert_eq!(alternatives.len(), 3, "Two phrasings of the past copula, then the quotation verbatim");
+        assert_eq!(alternatives[2], "Connais-toi toi-même. - Socrate");
+        assert_eq!(MotorCortex::as_mentioned("Socrate était un philosophe grec.", Lang::English), "As I mentioned, Socrate était un philosophe grec.");
+    }
+
+    #[test]
+    fn test_the_answer_span_comes_before_its_sentence() {
+        let memory = HolographicMemory::new("Albert Einstein est né le 14 mars 1879.".to_string(), crate::holographic_memory::HolographicTrace::new_empty(16), false);
+        assert_eq!(
+            MotorCortex::with_answer_span("Quand Einstein est-il né ?", &memory, memory.text.clone()),
+            "14 mars 1879. Albert Einstein est né le 14 mars 1879."
+        );
+        assert_eq!(MotorCortex::with_answer_span("Parle-moi d'Einstein.", &memory, memory.text.clone()), memory.text);
+    }
+
+    #[test]
+    fn test_answers_name_the_sources_the_core_does_not_vouch_for() {
+        let mut memory = HolographicMemory::new("Le soleil est une étoile.".to_string(), crate::holographic_memory::HolographicTrace::new_empty(16), false);
+        assert_eq!(MotorCortex::attributed(&memory, memory.text.clone(), Lang::French), memory.text);
+        memory.source = SourceTag::Web;
+        assert_eq!(MotorCortex::attributed(&memory, memory.text.clone(), Lang::French), "D'après une source trouvée sur le web, le soleil est une étoile.");
+        memory.source = SourceTag::Conversational;
+        assert!(MotorCortex::attributed(&memory, "The Sun is a star.".to_string(), Lang::English).starts_with("According to what I was told in conversation,"));
+    }
+
+    #[test]
+    fn test_creative_drafts_are_written_as_poems_or_stories() {
+        use crate::silicium::ConceptBridge;
+        let cortex = MotorCortex::new(Personality::new());
+        let bridge = |from: &str, to: &str, via: Option<&str>| ConceptBridge { from: from.to_string(), to: to.to_string(), via: via.map(String::from) };
+        let mut draft = CreativeDraft {
+            form: CreativeForm::Poem,
+            concepts: vec!["mer".to_string(), "désert".to_string(), "lune".to_string()],
+            bridges: vec![bridge("mer", "désert", Some("lune")), bridge("mer", "lune", None)],
+        };
+        let poem = cortex.compose_creative(&draft, Lang::French);
+        assert!(poem.starts_with("Voici un poème que j'ai imaginé, à partir de « mer », « désert » et « lune » :"), "{}", poem);
+        assert_eq!(poem.split("\n\n").count(), 4, "The introduction, a stanza per bridge and the closing line: {}", poem);
+        assert!(poem.ends_with("Et tout revient à mer."));
+
+        draft.form = CreativeForm::Story;
+        draft.bridges.truncate(1);
+        let story = cortex.compose_creative(&draft, Lang::English);
+        assert!(story.starts_with("Here is a story I imagined from"));
+        assert!(story.contains("In the beginning, there was only mer.") && story.contains("lune") && story.contains("désert"), "{}", story);
+        draft.concepts.clear();
+        assert!(!cortex.compose_creative(&draft, Lang::French).contains("Voici"));
+    }
+
+    #[test]
+    fn test_upset_users_are_answered_with_empathy() {
+        let mut cortex = MotorCortex::new(Personality::new());
+        assert_eq!(cortex.with_empathy("Le soleil est une étoile.".to_string(), Lang::French), "Le soleil est une étoile.");
+        cortex.set_user_sentiment(SensoryCortex::score_sentiment("Je suis triste"));
+        assert_eq!(cortex.with_empathy("Le soleil est une étoile.".to_string(), Lang::French), "Je suis désolée que ce soit difficile. Le soleil est une étoile.");
+        cortex.set_user_sentiment(SensoryCortex::score_sentiment("I'm so angry, that's wrong!"));
+        assert!(cortex.with_empathy("The Sun is a star.".to_string(), Lang::English).starts_with("I understand your frustration."));
+    }
 }
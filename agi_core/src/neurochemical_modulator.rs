@@ -5,6 +5,14 @@
 //! mais plutôt ses conséquences sur des paramètres comme la motivation, l'attention,
 //! la patience et la vigilance.
 
+use crate::connectome::Neuromodulation;
+use crate::sensory_cortex::Sentiment;
+use serde::Serialize;
+
+/// En dessous de ce niveau d'acétylcholine, l'attention est trop faible pour que la
+/// plasticité dépendante du temps des impulsions (STDP) s'applique.
+const STDP_ACETYLCHOLINE_GATE: f32 = 0.3;
+
 /// Représente l'état chimique global du "cerveau" de l'AGI.
 /// Chaque valeur est typiquement normalisée entre 0.0 et 1.0.
 #[derive(Debug, Clone)]
@@ -30,6 +38,23 @@ pub struct NeurochemicalState {
     pub noradrenaline: f32,
 }
 
+/// L'émotion dominante, lue dans l'état chimique (voir `NeurochemicalModulator::emotion`).
+/// Les transitions viennent des événements (`on_user_frustration`, `on_novelty_detected`,
+/// `reward_successful_reasoning`) et du retour progressif à l'état de base (`decay`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Emotion {
+    /// Tous les niveaux proches de l'état de base.
+    Calm,
+    /// Dopamine élevée : envie d'explorer.
+    Curious,
+    /// Acétylcholine élevée : recherches strictes.
+    Focused,
+    /// Noradrénaline élevée : réactivité accrue.
+    Alert,
+    /// Sérotonine basse et noradrénaline élevée, typiquement après une frustration de l'utilisateur.
+    Tense,
+}
+
 /// Le modulateur lui-même, qui contient l'état et les méthodes pour le mettre à jour.
 #[derive(Debug, Clone)]
 pub struct NeurochemicalModulator {
@@ -57,35 +82,193 @@ impl NeurochemicalModulator {
         println!("--- Neuro-Modulation: Dopamine rewarded. New level: {:.2} ---", self.state.dopamine);
     }
 
+    /// Baisse le niveau de dopamine quand l'utilisateur rejette une réponse : la stratégie
+    /// qui l'a produite devient moins attirante.
+    pub fn penalize_rejected_answer(&mut self) {
+        const DOPAMINE_PENALTY: f32 = 0.05;
+        self.state.dopamine = (self.state.dopamine - DOPAMINE_PENALTY).max(0.0);
+        println!("--- Neuro-Modulation: Dopamine depressed. New level: {:.2} ---", self.state.dopamine);
+    }
 
-    /// Calcule un seuil de distance pour le raisonnement qui est modulé par la dopamine.
-    /// Un niveau de dopamine plus élevé augmente légèrement le seuil, ce qui rend l'AGI plus "ouverte"
-    /// à considérer des souvenirs sémantiquement plus distants (flexibilité cognitive).
+    /// Calcule un seuil de distance pour le raisonnement qui est modulé par la dopamine et
+    /// l'acétylcholine. Un niveau de dopamine plus élevé augmente légèrement le seuil, ce qui rend
+    /// l'AGI plus "ouverte" à considérer des souvenirs sémantiquement plus distants (flexibilité
+    /// cognitive) ; l'acétylcholine le resserre (focus).
     /// 
     /// # Arguments
     /// * `base_threshold` - Le seuil de distance de base avant modulation.
     pub fn get_reasoning_distance_threshold(&self, base_threshold: f32) -> f32 {
         // La modulation est centrée autour de 0.5 (état de base).
-        // L'influence de la dopamine est un facteur (par exemple, 20% du seuil de base).
-        let modulation_factor = (self.state.dopamine - 0.5) * (base_threshold * 0.2);
+        // L'influence de chaque neuromodulateur est un facteur (par exemple, 20% du seuil de base).
+        let modulation_factor = (self.state.dopamine - 0.5) * (base_threshold * 0.2)
+            - (self.state.acetylcholine - 0.5) * (base_threshold * 0.2);
         let dynamic_threshold = base_threshold + modulation_factor;
         // S'assure que le seuil ne devient pas négatif ou absurdement élevé.
         dynamic_threshold.max(0.1).min(1.5)
     }
 
+    /// Nombre de souvenirs à récupérer, modulé par l'acétylcholine : concentrée, l'AGI en
+    /// considère moins (jusqu'à moitié moins), distraite davantage (jusqu'à moitié plus).
+    pub fn get_retrieval_top_k(&self, base_top_k: usize) -> usize {
+        let factor = 1.0 - (self.state.acetylcholine - 0.5);
+        ((base_top_k as f32 * factor).round() as usize).max(1)
+    }
+
+    /// Facteur appliqué à l'activité spontanée du connectome, modulé par la noradrénaline :
+    /// 1.0 à l'état de base, de 0.5 à 1.5.
+    pub fn get_spontaneous_activity_gain(&self) -> f32 {
+        0.5 + self.state.noradrenaline
+    }
+
+    /// Facteur appliqué à la potentialisation des chemins neuronaux (voir
+    /// `Connectome::potentiate_pathway`), modulé par la dopamine : 1.0 à l'état de base, de 0.0
+    /// (aucun apprentissage) à 2.0.
+    pub fn get_learning_gain(&self) -> f32 {
+        2.0 * self.state.dopamine.clamp(0.0, 1.0)
+    }
+
+    /// Vrai si l'acétylcholine est assez élevée pour que la STDP s'applique.
+    pub fn is_stdp_enabled(&self) -> bool {
+        self.state.acetylcholine >= STDP_ACETYLCHOLINE_GATE
+    }
+
+    /// L'instantané de l'état chimique passé au connectome à chaque tick.
+    pub fn connectome_modulation(&self) -> Neuromodulation {
+        Neuromodulation {
+            arousal: self.get_spontaneous_activity_gain(),
+            learning_gain: self.get_learning_gain(),
+            stdp_enabled: self.is_stdp_enabled(),
+        }
+    }
+
+    /// Probabilité de garder le ton de la réponse précédente (voir
+    /// `Personality::set_tone_stability`), modulée par la sérotonine : patiente, l'AGI change
+    /// moins souvent de ton.
+    pub fn get_tone_stability(&self) -> f32 {
+        self.state.serotonin.clamp(0.0, 1.0)
+    }
+
+    /// L'utilisateur s'impatiente ou se plaint : la sérotonine baisse (le ton devient plus
+    /// changeant), la noradrénaline monte (vigilance) et l'acétylcholine aussi (recherches
+    /// plus strictes).
+    pub fn on_user_frustration(&mut self) {
+        self.adjust(0.0, -0.15, 0.1, 0.15);
+        println!("--- Neuro-Modulation: user frustration. Emotion: {:?} ---", self.emotion());
+    }
+
+    /// Quelque chose d'inconnu est apparu : la noradrénaline (alerte) et la dopamine
+    /// (exploration) montent.
+    pub fn on_novelty_detected(&mut self) {
+        self.adjust(0.05, 0.0, 0.0, 0.1);
+        println!("--- Neuro-Modulation: novelty detected. Emotion: {:?} ---", self.emotion());
+    }
+
+    /// Le sentiment exprimé par l'utilisateur (voir `SensoryCortex::score_sentiment`) : négatif,
+    /// il fait monter la noradrénaline (vigilance) ; positif, la dopamine et la sérotonine.
+    /// L'effet croît avec l'intensité (valence et excitation) ; un message neutre ne change rien.
+    pub fn on_user_sentiment(&mut self, sentiment: Sentiment) {
+        let strength = sentiment.valence.abs() * (0.5 + sentiment.arousal);
+        if sentiment.is_negative() {
+            self.adjust(0.0, 0.0, 0.0, 0.1 * strength);
+        } else if sentiment.is_positive() {
+            self.adjust(0.05 * strength, 0.05 * strength, 0.0, 0.0);
+        } else {
+            return;
+        }
+        println!("--- Neuro-Modulation: user sentiment {:+.2}. Emotion: {:?} ---", sentiment.valence, self.emotion());
+    }
+
+    fn adjust(&mut self, dopamine: f32, serotonin: f32, acetylcholine: f32, noradrenaline: f32) {
+        let state = &mut self.state;
+        state.dopamine = (state.dopamine + dopamine).clamp(0.0, 1.0);
+        state.serotonin = (state.serotonin + serotonin).clamp(0.0, 1.0);
+        state.acetylcholine = (state.acetylcholine + acetylcholine).clamp(0.0, 1.0);
+        state.noradrenaline = (state.noradrenaline + noradrenaline).clamp(0.0, 1.0);
+    }
+
+    /// L'émotion dominante qui résulte de l'état chimique courant.
+    pub fn emotion(&self) -> Emotion {
+        let state = &self.state;
+        if state.serotonin < 0.35 && state.noradrenaline > 0.65 {
+            Emotion::Tense
+        } else if state.noradrenaline > 0.6 {
+            Emotion::Alert
+        } else if state.acetylcholine > 0.6 {
+            Emotion::Focused
+        } else if state.dopamine > 0.6 {
+            Emotion::Curious
+        } else {
+            Emotion::Calm
+        }
+    }
+
     /// Simule la dégradation naturelle ou la recapture des neuromodulateurs,
     /// les faisant revenir lentement à leur état de base (0.5).
     pub fn decay(&mut self) {
         const DECAY_RATE: f32 = 0.005; // Taux de dégradation très lent
         
-        // Ramène la dopamine vers 0.5
-        if self.state.dopamine > 0.5 {
-            self.state.dopamine = (self.state.dopamine - DECAY_RATE).max(0.5);
-        } else {
-            self.state.dopamine = (self.state.dopamine + DECAY_RATE).min(0.5);
+        // Ramène chaque neuromodulateur vers 0.5
+        let state = &mut self.state;
+        for level in [&mut state.dopamine, &mut state.serotonin, &mut state.acetylcholine, &mut state.noradrenaline] {
+            if *level > 0.5 {
+                *level = (*level - DECAY_RATE).max(0.5);
+            } else {
+                *level = (*level + DECAY_RATE).min(0.5);
+            }
         }
+    }
 
-        // TODO: Appliquer la même logique pour les autres neuromodulateurs quand ils seront utilisés.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_move_the_levels_and_decay_restores_them() {
+        let mut modulator = NeurochemicalModulator::new();
+        assert_eq!(modulator.emotion(), Emotion::Calm);
+        assert_eq!(modulator.get_retrieval_top_k(10), 10);
+
+        modulator.on_user_frustration();
+        modulator.on_user_frustration();
+        assert_eq!(modulator.emotion(), Emotion::Tense);
+        assert!(modulator.get_tone_stability() < 0.5);
+        assert!(modulator.get_retrieval_top_k(10) < 10, "Acetylcholine narrows the search");
+        assert!(modulator.get_reasoning_distance_threshold(0.8) < 0.8);
+        assert!(modulator.get_spontaneous_activity_gain() > 1.0);
+
+        for _ in 0..100 {
+            modulator.decay();
+        }
+        assert_eq!(modulator.emotion(), Emotion::Calm);
+        assert_eq!(modulator.get_spontaneous_activity_gain(), 1.0);
     }
 
+    #[test]
+    fn test_sentiment_moves_the_levels() {
+        let mut modulator = NeurochemicalModulator::new();
+        modulator.on_user_sentiment(Sentiment::default());
+        assert_eq!(modulator.emotion(), Emotion::Calm);
+
+        modulator.on_user_sentiment(Sentiment { valence: -0.9, arousal: 0.8 });
+        assert!(modulator.state.noradrenaline > 0.5 && modulator.state.dopamine == 0.5);
+
+        let mut modulator = NeurochemicalModulator::new();
+        modulator.on_user_sentiment(Sentiment { valence: 0.8, arousal: 0.5 });
+        assert!(modulator.state.dopamine > 0.5 && modulator.state.serotonin > 0.5);
+        assert_eq!(modulator.state.noradrenaline, 0.5);
+    }
+
+    #[test]
+    fn test_connectome_modulation() {
+        let mut modulator = NeurochemicalModulator::new();
+        assert_eq!(modulator.connectome_modulation(), Neuromodulation::default());
+
+        modulator.reward_successful_reasoning();
+        modulator.state.acetylcholine = 0.1;
+        let modulation = modulator.connectome_modulation();
+        assert!(modulation.learning_gain > 1.0);
+        assert!(!modulation.stdp_enabled);
+    }
 }
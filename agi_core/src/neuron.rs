@@ -1,33 +1,91 @@
 // agi_core/src/neuron.rs
 
+/// Ticks after a spike during which the neuron cannot fire and loses its input.
+pub const ABSOLUTE_REFRACTORY_TICKS: u64 = 2;
+/// Ticks after the absolute period during which firing takes a higher potential.
+pub const RELATIVE_REFRACTORY_TICKS: u64 = 3;
+/// Extra threshold at the start of the relative period, as a share of the threshold; it fades
+/// linearly over the period.
+pub const RELATIVE_REFRACTORY_BOOST: f32 = 0.5;
+/// Homeostatic plasticity: each spike raises the threshold by this much...
+pub const THRESHOLD_ADAPTATION: f32 = 0.05;
+/// ...up to this multiple of the resting threshold...
+pub const MAX_THRESHOLD_RATIO: f32 = 3.0;
+/// ...and the threshold relaxes back to rest with this time constant, in ticks.
+pub const THRESHOLD_RECOVERY_TICKS: f32 = 200.0;
+
+/// Whether the synapses of a neuron excite or inhibit their targets. The connectome keeps the
+/// sign of every outgoing weight consistent with the kind of its neuron (Dale's principle).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NeuronKind {
+    #[default]
+    Excitatory,
+    Inhibitory,
+}
+
 /// Represents the state of a single neuron.
 #[derive(Debug, Clone)]
 pub struct Neuron {
     /// Unique identifier for the neuron.
     pub id: u64,
+    pub kind: NeuronKind,
     /// The current membrane potential.
     pub potential: f32,
-    /// The firing threshold.
+    /// The firing threshold, raised by recent spikes (see `THRESHOLD_ADAPTATION`).
     pub threshold: f32,
+    /// The threshold at rest.
+    pub resting_threshold: f32,
     /// Is the neuron currently firing?
     pub firing: bool,
     /// Rate at which the potential leaks, returning to a resting state.
     pub leak_factor: f32,
+    /// The tick of the last spike, None before the first one.
+    pub last_spike: Option<u64>,
+    // The tick of the last update, for the recovery of the threshold between sparse updates.
+    last_update: u64,
 }
 
 impl Neuron {
     pub fn new(id: u64) -> Self {
         Self {
             id,
+            kind: NeuronKind::Excitatory,
             potential: 0.0, // Start at rest
             threshold: 1.0, // Example threshold
+            resting_threshold: 1.0,
             firing: false,
             leak_factor: 0.01, // Reduced leak to encourage cascades
+            last_spike: None,
+            last_update: 0,
+        }
+    }
+
+    /// True while the neuron cannot fire after a spike.
+    pub fn is_refractory(&self, tick: u64) -> bool {
+        self.last_spike.is_some_and(|spike| tick > spike && tick - spike <= ABSOLUTE_REFRACTORY_TICKS)
+    }
+
+    /// The potential needed to fire at `tick`: the adapted threshold, raised during the relative
+    /// refractory period.
+    pub fn effective_threshold(&self, tick: u64) -> f32 {
+        let since_absolute = self.last_spike.map(|spike| tick.saturating_sub(spike + ABSOLUTE_REFRACTORY_TICKS));
+        match since_absolute {
+            Some(elapsed) if elapsed > 0 && elapsed <= RELATIVE_REFRACTORY_TICKS => {
+                let remaining = (RELATIVE_REFRACTORY_TICKS - elapsed + 1) as f32 / RELATIVE_REFRACTORY_TICKS as f32;
+                self.threshold * (1.0 + RELATIVE_REFRACTORY_BOOST * remaining)
+            }
+            _ => self.threshold,
         }
     }
 
     /// Updates the neuron's state for one time step using a leaky integrate-and-fire model.
-    pub fn update(&mut self) {
+    /// Neurons at rest are not updated every tick, so `tick` tells how much time has passed.
+    pub fn update(&mut self, tick: u64) {
+        // 0. Homeostasis: the threshold relaxes towards rest since the last update.
+        let elapsed = tick.saturating_sub(self.last_update) as f32;
+        self.threshold = self.resting_threshold + (self.threshold - self.resting_threshold) * (-elapsed / THRESHOLD_RECOVERY_TICKS).exp();
+        self.last_update = tick;
+
         // 1. If the neuron was firing on the last tick, reset it now.
         // This happens *before* we check for a new firing event in the current tick.
         // This gives the 'firing' state a full tick to be observed by the rest of the system.
@@ -36,12 +94,20 @@ impl Neuron {
             self.firing = false;   // End the firing state.
         }
 
-        // 2. Check if the current potential exceeds the firing threshold.
-        if self.potential >= self.threshold {
+        // 2. During the absolute refractory period, the input is lost.
+        if self.is_refractory(tick) {
+            self.potential = 0.0;
+            return;
+        }
+
+        // 3. Check if the current potential exceeds the firing threshold.
+        if self.potential >= self.effective_threshold(tick) {
             self.firing = true;
+            self.last_spike = Some(tick);
+            self.threshold = (self.threshold + THRESHOLD_ADAPTATION).min(self.resting_threshold * MAX_THRESHOLD_RATIO);
         }
 
-        // 3. Apply the 'leak' to the potential.
+        // 4. Apply the 'leak' to the potential.
         if self.potential > 0.0 {
             self.potential *= 1.0 - self.leak_factor;
             if self.potential < 1e-6 {
@@ -50,3 +116,35 @@ impl Neuron {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refractory_periods_and_adaptive_threshold() {
+        let mut neuron = Neuron::new(0);
+        neuron.potential = 1.0;
+        neuron.update(10);
+        assert!(neuron.firing);
+        assert!(neuron.threshold > neuron.resting_threshold);
+
+        // Absolute period: even a strong input is lost.
+        for tick in 11..=12 {
+            neuron.potential = 5.0;
+            neuron.update(tick);
+            assert!(!neuron.firing && neuron.potential == 0.0, "tick {}", tick);
+        }
+        // Relative period: the resting threshold is no longer enough.
+        neuron.potential = 1.1;
+        neuron.update(13);
+        assert!(!neuron.firing);
+        neuron.potential = 2.0;
+        neuron.update(14);
+        assert!(neuron.firing);
+
+        // The adapted threshold relaxes back to rest.
+        neuron.update(10_000);
+        assert!((neuron.threshold - neuron.resting_threshold).abs() < 1e-3);
+    }
+}
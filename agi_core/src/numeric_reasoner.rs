@@ -0,0 +1,439 @@
+//! numeric_reasoner.rs - Exact answers to arithmetic and date questions.
+//!
+//! Holographic similarity cannot compute: "combien font 12 * (3 + 4) ?" or "how many years
+//! between 1914 and 1945?" have no memory to retrieve. `parse_question` recognizes such
+//! questions and `NumericQuestion::answer` computes them exactly; the Core then adds the
+//! memories that mention the years involved, when it has some. Unit conversions ("combien de
+//! mètres dans 3 km ?") go through the `units` module.
+
+use crate::capabilities::Capability;
+use crate::lemmatizer::Lang;
+use crate::sensory_cortex::SensoryCortex;
+use crate::units::{self, Quantity, Unit, UnitError};
+use regex::Regex;
+use std::sync::OnceLock;
+use thiserror::Error;
+
+/// The computations of this module, as listed by `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "calculation",
+    description: "Compute arithmetic, percentages, dates and unit conversions",
+    description_fr: "Calculer : arithmétique, pourcentages, dates et conversions d'unités",
+    examples: &["Combien font 15 % de 240 ?", "How many feet is 8849 m?"],
+};
+
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
+pub enum NumericError {
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("malformed expression")]
+    Malformed,
+    #[error(transparent)]
+    Unit(#[from] UnitError),
+}
+
+/// A calendar date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Days since 1970-01-01 (negative before), in the proleptic Gregorian calendar.
+    pub fn days_since_epoch(self) -> i64 {
+        // Howard Hinnant's `days_from_civil`.
+        let year = if self.month <= 2 { self.year - 1 } else { self.year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// The year of a day counted from 1970-01-01 (see `days_since_epoch`).
+    pub fn year_of_day(days: i64) -> i64 {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        year_of_era + era * 400 + if month_index >= 10 { 1 } else { 0 }
+    }
+}
+
+/// A question with an exact answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumericQuestion {
+    /// An arithmetic expression, normalized to `+ - * / ^ %` and parentheses.
+    Arithmetic { expression: String },
+    /// The number of years between two years.
+    YearsBetween { from: i64, to: i64 },
+    /// The number of years since a year, as of `current_year`.
+    YearsSince { year: i64, current_year: i64 },
+    /// The number of days between two dates, as written in the question.
+    DaysBetween { from: (String, Date), to: (String, Date) },
+    /// A quantity to express in another unit.
+    Conversion { quantity: Quantity, to: &'static Unit },
+}
+
+/// The computed answer to a `NumericQuestion`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericAnswer {
+    pub question: NumericQuestion,
+    pub value: Result<f64, NumericError>,
+}
+
+impl NumericQuestion {
+    pub fn answer(self) -> NumericAnswer {
+        let value = match &self {
+            NumericQuestion::Arithmetic { expression } => evaluate(expression),
+            NumericQuestion::YearsBetween { from, to } => Ok((to - from).abs() as f64),
+            NumericQuestion::YearsSince { year, current_year } => Ok((current_year - year) as f64),
+            NumericQuestion::DaysBetween { from, to } => Ok((to.1.days_since_epoch() - from.1.days_since_epoch()).abs() as f64),
+            NumericQuestion::Conversion { quantity, to } => quantity.convert(to).map(|converted| converted.value).map_err(NumericError::from),
+        };
+        NumericAnswer { question: self, value }
+    }
+
+    /// The years the question is about, to look up the memories that mention them.
+    pub fn years(&self) -> Vec<i64> {
+        match self {
+            NumericQuestion::Arithmetic { .. } | NumericQuestion::Conversion { .. } => Vec::new(),
+            NumericQuestion::YearsBetween { from, to } => vec![*from, *to],
+            NumericQuestion::YearsSince { year, .. } => vec![*year],
+            NumericQuestion::DaysBetween { from, to } => vec![from.1.year, to.1.year],
+        }
+    }
+}
+
+const MONTHS: [(&str, u32); 24] = [
+    ("janvier", 1), ("février", 2), ("mars", 3), ("avril", 4), ("mai", 5), ("juin", 6),
+    ("juillet", 7), ("août", 8), ("septembre", 9), ("octobre", 10), ("novembre", 11), ("décembre", 12),
+    ("january", 1), ("february", 2), ("march", 3), ("april", 4), ("may", 5), ("june", 6),
+    ("july", 7), ("august", 8), ("september", 9), ("october", 10), ("november", 11), ("december", 12),
+];
+
+/// Recognizes a question with an exact answer. `current_year` anchors "how many years since".
+pub fn parse_question(prompt: &str, current_year: i64) -> Option<NumericQuestion> {
+    static DATE: OnceLock<Regex> = OnceLock::new();
+    static YEARS_BETWEEN: OnceLock<Regex> = OnceLock::new();
+    static YEARS_SINCE: OnceLock<Regex> = OnceLock::new();
+    let lower = prompt.to_lowercase().replace('’', "'");
+
+    let date = DATE.get_or_init(|| {
+        let months: Vec<&str> = MONTHS.iter().map(|(name, _)| *name).collect();
+        let months = months.join("|");
+        Regex::new(&format!(
+            r"\b(?:(\d{{1,2}})(?:er)?\s+({m})\s+(\d{{1,4}})|({m})\s+(\d{{1,2}}),?\s+(\d{{1,4}})|(\d{{4}})-(\d{{2}})-(\d{{2}}))\b",
+            m = months
+        ))
+        .unwrap()
+    });
+    let asks_days = ["combien de jours", "how many days", "nombre de jours", "number of days"].iter().any(|cue| lower.contains(cue));
+    if asks_days {
+        let dates: Vec<(String, Date)> = date.captures_iter(&lower).filter_map(|captures| parse_date(&captures)).collect();
+        if let [from, to, ..] = dates.as_slice() {
+            return Some(NumericQuestion::DaysBetween { from: from.clone(), to: to.clone() });
+        }
+    }
+
+    if let Some(conversion) = conversion(&lower) {
+        return Some(conversion);
+    }
+
+    let years_between = YEARS_BETWEEN.get_or_init(|| {
+        Regex::new(r"(?:combien d'ann[ée]es|how many years|nombre d'ann[ée]es|number of years).*?\b(?:entre|between|de|from)\s+(-?\d{1,4})\s+(?:et|and|à|to)\s+(-?\d{1,4})\b").unwrap()
+    });
+    if let Some(captures) = years_between.captures(&lower) {
+        return Some(NumericQuestion::YearsBetween { from: captures[1].parse().ok()?, to: captures[2].parse().ok()? });
+    }
+    let years_since = YEARS_SINCE.get_or_init(|| {
+        Regex::new(r"(?:combien d'ann[ée]es|how many years).*?\b(?:depuis|since|en|in)\s+(\d{1,4})\b|how many years ago .*?\b(\d{3,4})\b").unwrap()
+    });
+    if let Some(captures) = years_since.captures(&lower) {
+        let year = captures.get(1).or_else(|| captures.get(2))?.as_str().parse().ok()?;
+        return Some(NumericQuestion::YearsSince { year, current_year });
+    }
+
+    arithmetic_expression(&lower).map(|expression| NumericQuestion::Arithmetic { expression })
+}
+
+fn parse_date(captures: &regex::Captures) -> Option<(String, Date)> {
+    let month_number = |name: &str| MONTHS.iter().find(|(month, _)| *month == name).map(|(_, number)| *number);
+    let number = |index: usize| captures.get(index).and_then(|value| value.as_str().parse::<i64>().ok());
+    let (day, month, year) = if captures.get(1).is_some() {
+        (number(1)?, month_number(&captures[2])?, number(3)?)
+    } else if captures.get(4).is_some() {
+        (number(5)?, month_number(&captures[4])?, number(6)?)
+    } else {
+        (number(9)?, number(8)? as u32, number(7)?)
+    };
+    let date = Date { year, month, day: day as u32 };
+    ((1..=12).contains(&date.month) && (1..=31).contains(&date.day)).then(|| (captures[0].to_string(), date))
+}
+
+/// Recognizes a conversion: a quantity and, after "combien de", "how many", "en", "in", "to" or
+/// "into", the unit to express it in ("combien de mètres dans 3 km ?", "convert 5 miles to km").
+fn conversion(lower: &str) -> Option<NumericQuestion> {
+    let asks = lower.contains('?') || ["combien", "convert", "how many", "exprime"].iter().any(|cue| lower.contains(cue));
+    if !asks {
+        return None;
+    }
+    let quantity = *SensoryCortex::extract_quantities(lower).first()?;
+    let to = target_unit(lower)?;
+    Some(NumericQuestion::Conversion { quantity, to })
+}
+
+/// The unit a lowercased question asks for, after "combien de", "how many", "en", "in", "to"
+/// or "into" ("combien de pieds mesure l'Everest ?" asks for feet).
+pub fn target_unit(lower: &str) -> Option<&'static Unit> {
+    static TARGET_CUE: OnceLock<Regex> = OnceLock::new();
+    let target_cue = TARGET_CUE.get_or_init(|| Regex::new(r"\b(?:combien de|combien d'|how many|en|in|to|into)\s+").unwrap());
+    target_cue.find_iter(lower).find_map(|cue| {
+        let words: Vec<&str> = lower[cue.end()..].split_whitespace().take(3).collect();
+        // The longest spelling first: "kilomètres par heure" before "kilomètres".
+        (1..=words.len()).rev().find_map(|count| units::lookup(words[..count].join(" ").trim_end_matches(['?', '.', '!', ','])))
+    })
+}
+
+/// Extracts the arithmetic expression of a calculation request, with the operators spelled
+/// out in words replaced by symbols ("3 fois 4" becomes "3 * 4").
+fn arithmetic_expression(lower: &str) -> Option<String> {
+    static TIMES_X: OnceLock<Regex> = OnceLock::new();
+    static PERCENT_OF: OnceLock<Regex> = OnceLock::new();
+    static DECIMAL_COMMA: OnceLock<Regex> = OnceLock::new();
+    static EXPRESSION: OnceLock<Regex> = OnceLock::new();
+
+    let cues = ["combien", "calcule", "calculer", "how much", "what is", "what's", "compute", "calculate", "font", "fait", "égal", "equals", "="];
+    if !cues.iter().any(|cue| lower.contains(cue)) {
+        return None;
+    }
+    let mut text = format!(" {} ", lower);
+    for (words, symbol) in [
+        (" divisé par ", " / "), (" divided by ", " / "), (" multiplié par ", " * "), (" multiplied by ", " * "),
+        (" fois ", " * "), (" times ", " * "), (" plus ", " + "), (" moins ", " - "), (" minus ", " - "),
+        (" au carré", " ^ 2"), (" squared", " ^ 2"), ("×", "*"), ("÷", "/"),
+    ] {
+        text = text.replace(words, symbol);
+    }
+    let text = TIMES_X.get_or_init(|| Regex::new(r"(\d)\s*x\s*(\d)").unwrap()).replace_all(&text, "$1 * $2");
+    let text = PERCENT_OF.get_or_init(|| Regex::new(r"%\s*(?:de|d'|of)\s*").unwrap()).replace_all(&text, "% * ");
+    let text = DECIMAL_COMMA.get_or_init(|| Regex::new(r"(\d),(\d)").unwrap()).replace_all(&text, "$1.$2");
+
+    let expression = EXPRESSION.get_or_init(|| Regex::new(r"[-(]*\d[\d\s.+\-*/^()%]*[\d)%]").unwrap());
+    expression
+        .find_iter(&text)
+        .map(|found| found.as_str().split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|candidate| candidate.trim_start_matches(['-', '(']).contains(['+', '-', '*', '/', '^', '%']))
+        .max_by_key(String::len)
+}
+
+/// Evaluates an arithmetic expression: `+ - * / ^`, parentheses, unary minus and postfix `%`.
+pub fn evaluate(expression: &str) -> Result<f64, NumericError> {
+    let tokens = tokenize(expression)?;
+    let mut parser = Parser { tokens, position: 0 };
+    let value = parser.sum()?;
+    if parser.position != parser.tokens.len() {
+        return Err(NumericError::Malformed);
+    }
+    if value.is_finite() { Ok(value) } else { Err(NumericError::Malformed) }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Operator(char),
+    Open,
+    Close,
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, NumericError> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(&digit) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                    number.push(digit);
+                    chars.next();
+                }
+                tokens.push(Token::Number(number.parse().map_err(|_| NumericError::Malformed)?));
+            }
+            '+' | '-' | '*' | '/' | '^' | '%' => {
+                tokens.push(Token::Operator(c));
+                chars.next();
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                chars.next();
+            }
+            _ => return Err(NumericError::Malformed),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent: sum := product (('+'|'-') product)*, product := power (('*'|'/') power)*,
+/// power := unary ('^' power)?, unary := '-' unary | primary '%'*.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn sum(&mut self) -> Result<f64, NumericError> {
+        let mut value = self.product()?;
+        while let Some(Token::Operator(operator @ ('+' | '-'))) = self.peek() {
+            self.position += 1;
+            let right = self.product()?;
+            value = if operator == '+' { value + right } else { value - right };
+        }
+        Ok(value)
+    }
+
+    fn product(&mut self) -> Result<f64, NumericError> {
+        let mut value = self.power()?;
+        while let Some(Token::Operator(operator @ ('*' | '/'))) = self.peek() {
+            self.position += 1;
+            let right = self.power()?;
+            value = if operator == '*' {
+                value * right
+            } else if right == 0.0 {
+                return Err(NumericError::DivisionByZero);
+            } else {
+                value / right
+            };
+        }
+        Ok(value)
+    }
+
+    fn power(&mut self) -> Result<f64, NumericError> {
+        let base = self.unary()?;
+        if let Some(Token::Operator('^')) = self.peek() {
+            self.position += 1;
+            return Ok(base.powf(self.power()?));
+        }
+        Ok(base)
+    }
+
+    fn unary(&mut self) -> Result<f64, NumericError> {
+        if let Some(Token::Operator('-')) = self.peek() {
+            self.position += 1;
+            return Ok(-self.unary()?);
+        }
+        let mut value = match self.peek() {
+            Some(Token::Number(number)) => {
+                self.position += 1;
+                number
+            }
+            Some(Token::Open) => {
+                self.position += 1;
+                let value = self.sum()?;
+                if self.peek() != Some(Token::Close) {
+                    return Err(NumericError::Malformed);
+                }
+                self.position += 1;
+                value
+            }
+            _ => return Err(NumericError::Malformed),
+        };
+        while let Some(Token::Operator('%')) = self.peek() {
+            self.position += 1;
+            value /= 100.0;
+        }
+        Ok(value)
+    }
+}
+
+/// Writes a number without useless decimals, with a decimal comma in French.
+pub fn format_number(value: f64, lang: Lang) -> String {
+    let rounded = (value * 1e6).round() / 1e6;
+    let text = if rounded.fract() == 0.0 { format!("{}", rounded as i64) } else { format!("{}", rounded) };
+    match lang {
+        Lang::French => text.replace('.', ","),
+        Lang::English => text,
+    }
+}
+
+/// Words the answer, in `lang`.
+pub fn describe(answer: &NumericAnswer, lang: Lang) -> String {
+    let value = match answer.value {
+        Ok(value) => value,
+        Err(NumericError::DivisionByZero) => {
+            return match lang {
+                Lang::French => "Division par zéro : le résultat n'est pas défini.".to_string(),
+                Lang::English => "Division by zero: the result is undefined.".to_string(),
+            }
+        }
+        Err(NumericError::Malformed) => {
+            return match lang {
+                Lang::French => "Je n'arrive pas à lire ce calcul.".to_string(),
+                Lang::English => "I cannot read this calculation.".to_string(),
+            }
+        }
+        Err(NumericError::Unit(UnitError::Incompatible { from, to })) => {
+            return match lang {
+                Lang::French => format!("Je ne peux pas convertir {} en {}.", from.label(lang), to.label(lang)),
+                Lang::English => format!("I cannot convert {} into {}.", from.label(lang), to.label(lang)),
+            }
+        }
+    };
+    let number = format_number(value, lang);
+    match (&answer.question, lang) {
+        (NumericQuestion::Arithmetic { expression }, _) => format!("{} = {}", expression, number),
+        (NumericQuestion::YearsBetween { from, to }, Lang::French) => format!("Il y a {} ans entre {} et {}.", number, from, to),
+        (NumericQuestion::YearsBetween { from, to }, Lang::English) => format!("There are {} years between {} and {}.", number, from, to),
+        (NumericQuestion::YearsSince { year, .. }, Lang::French) => format!("{}, c'était il y a {} ans.", year, number),
+        (NumericQuestion::YearsSince { year, .. }, Lang::English) => format!("{} was {} years ago.", year, number),
+        (NumericQuestion::DaysBetween { from, to }, Lang::French) => format!("Il y a {} jours entre le {} et le {}.", number, from.0, to.0),
+        (NumericQuestion::DaysBetween { from, to }, Lang::English) => format!("There are {} days between {} and {}.", number, from.0, to.0),
+        (NumericQuestion::Conversion { quantity, to }, _) => {
+            format!("{} {} = {} {}", format_number(quantity.value, lang), quantity.unit.symbol(lang), number, to.symbol(lang))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_questions_are_computed_exactly() {
+        let answer = |prompt: &str| parse_question(prompt, 2026).map(|question| describe(&question.answer(), Lang::French));
+        assert_eq!(answer("Combien font 12 * (3 + 4) ?").as_deref(), Some("12 * (3 + 4) = 84"));
+        assert_eq!(answer("Calcule 7 fois 6 moins 2").as_deref(), Some("7 * 6 - 2 = 40"));
+        assert_eq!(answer("Combien fait 15% de 80 ?").as_deref(), Some("15% * 80 = 12"));
+        assert_eq!(answer("Combien font 2 ^ 3 ^ 2 et 1,5 ?").as_deref(), Some("2 ^ 3 ^ 2 = 512"));
+        assert_eq!(answer("Combien font 1 / 0 ?").as_deref(), Some("Division par zéro : le résultat n'est pas défini."));
+        assert_eq!(answer("Combien d'années entre 1914 et 1945 ?").as_deref(), Some("Il y a 31 ans entre 1914 et 1945."));
+        assert_eq!(answer("Combien d'années se sont écoulées depuis 1969 ?").as_deref(), Some("1969, c'était il y a 57 ans."));
+        assert_eq!(answer("Combien de jours entre le 1er janvier 2020 et le 14 mars 2020 ?").as_deref(), Some("Il y a 73 jours entre le 1er janvier 2020 et le 14 mars 2020."));
+        assert_eq!(answer("Qu'est-ce que le COVID-19 ?"), None);
+        assert_eq!(answer("Qu'est-ce que E=mc^2 ?"), None);
+        assert_eq!(answer("Combien de mètres dans 3 km ?").as_deref(), Some("3 km = 3000 m"));
+        assert_eq!(answer("Convertis 1,5 heure en minutes").as_deref(), Some("1,5 h = 90 min"));
+        assert_eq!(answer("Combien de kilos dans 3 km ?").as_deref(), Some("Je ne peux pas convertir une longueur en une masse."));
+
+        let question = parse_question("How many years between 1914 and 1945?", 2026).unwrap();
+        assert_eq!(question.years(), vec![1914, 1945]);
+        assert_eq!(describe(&question.answer(), Lang::English), "There are 31 years between 1914 and 1945.");
+        assert_eq!(Date::year_of_day(Date { year: 2024, month: 12, day: 31 }.days_since_epoch()), 2024);
+        assert_eq!(format_number(2.5, Lang::French), "2,5");
+    }
+}
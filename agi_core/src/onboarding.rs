@@ -0,0 +1,301 @@
+//! onboarding.rs - The "unknown concept" conversation.
+//!
+//! When a prompt is about something the Core has neither memories nor a concept node for, the
+//! nearest memory is usually irrelevant. Instead, the Core says it does not know the concept
+//! yet and offers to learn it, either from the user ("teach me") or from a source to scan.
+//! This module holds the state of that short conversation and its wording; the `Core` drives it.
+
+use crate::knowledge_scanner::DataSource;
+use crate::lemmatizer::{self, Lang};
+use crate::stop_words::StopWordRegistry;
+use crate::tokenizer::{self, TokenKind};
+
+/// Request verbs that introduce a question rather than name its subject.
+const PROMPT_VERBS: &[&str] = &[
+    "explique", "expliquer", "décris", "décrire", "parle", "parler", "dis", "dire", "définis", "définir", "donne",
+    "montre", "connais", "sais", "savoir", "raconte", "compare", "explain", "describe", "tell", "define", "give",
+    "show", "know", "talk",
+];
+
+/// Verbs that carry the question rather than its subject ("Qui était Socrate ?", "How do I
+/// make bread?"): the forms of the auxiliaries and of the verbs questions are built on.
+const COMMON_VERBS: &[&str] = &[
+    "est", "sont", "était", "étaient", "été", "être", "sera", "seront", "fut", "furent", "serait", "ont", "avait",
+    "avaient", "avoir", "aura", "fait", "faire", "font", "faisait", "fais", "vont", "allait", "aller", "peut",
+    "peuvent", "pouvait", "pouvoir", "doit", "doivent", "devait", "devoir", "veut", "veulent", "voulait", "vouloir",
+    "fonctionne", "fonctionnent", "marche", "existe", "existait", "signifie", "vivait", "prépare", "préparer",
+    "was", "were", "been", "being", "has", "have", "had", "having", "does", "did", "done", "doing", "make", "makes",
+    "made", "making", "get", "gets", "got", "goes", "went", "can", "could", "should", "would", "will", "work",
+    "works", "worked", "mean", "means", "meant", "happen", "happens", "happened", "become", "became", "lived",
+    "died", "use", "used", "cook", "build", "create", "created",
+];
+
+/// Adverbs that qualify the question ("Qui était vraiment Socrate ?").
+const ADVERBS: &[&str] = &[
+    "vraiment", "réellement", "exactement", "précisément", "simplement", "seulement", "aussi", "toujours", "jamais",
+    "souvent", "généralement", "encore", "déjà", "vite", "bien", "really", "actually", "exactly", "precisely",
+    "truly", "simply", "only", "just", "also", "always", "never", "often", "usually", "still", "already", "quickly",
+    "well",
+];
+
+/// The words the thalamus and the motor cortex choose a strategy on ("the difference between",
+/// "les étapes pour"): they say how to answer, not what about.
+const STRATEGY_WORDS: &[&str] = &[
+    "difference", "differences", "différence", "différences", "similarities", "ressemblances", "compare",
+    "comparer", "comparing", "comparison", "comparaison", "versus", "better", "meilleur", "meilleure", "steps",
+    "step", "étapes", "étape", "procédure", "procedure", "avantages", "inconvénients", "advantages",
+    "disadvantages", "pros", "cons",
+];
+
+const AFFIRMATIVE: &[&str] = &["oui", "ouais", "ok", "okay", "d'accord", "volontiers", "bien sûr", "yes", "yeah", "sure", "apprends", "teach", "je vais t'apprendre"];
+const NEGATIVE: &[&str] = &["non", "no", "nope", "pas maintenant", "laisse tomber", "tant pis", "never mind", "not now"];
+const SCAN_WORDS: &[&str] = &["scan", "scanne", "scanner", "source", "lien", "link", "url", "fichier", "file"];
+
+/// Where the onboarding conversation stands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Onboarding {
+    /// The Core said it does not know these concepts and offered to learn them.
+    Offered { concepts: Vec<String>, lang: Lang },
+    /// The user agreed to teach; the next prompt defines the first concept.
+    AwaitingDefinition { concepts: Vec<String>, lang: Lang },
+    /// The user wants a source scanned but has not given it yet.
+    AwaitingSource { lang: Lang },
+}
+
+impl Onboarding {
+    pub fn lang(&self) -> Lang {
+        match self {
+            Onboarding::Offered { lang, .. } | Onboarding::AwaitingDefinition { lang, .. } | Onboarding::AwaitingSource { lang } => *lang,
+        }
+    }
+}
+
+/// How a prompt answers an onboarding offer.
+#[derive(Debug)]
+pub enum FollowUp {
+    /// "Yes, I'll teach you."
+    Teach,
+    /// "Scan this" (the source, if the prompt contains one).
+    Scan(Option<DataSource>),
+    Decline,
+    /// The user moved on to something else.
+    Unrelated,
+}
+
+/// Classifies a reply to an onboarding offer.
+pub fn classify_follow_up(prompt: &str) -> FollowUp {
+    if let Some(source) = find_source(prompt) {
+        return FollowUp::Scan(Some(source));
+    }
+    let lower = prompt.trim().trim_end_matches(['.', '!', '?']).trim().to_lowercase();
+    let words = tokenizer::content_words(&lower);
+    let starts_with = |phrases: &[&str]| phrases.iter().any(|phrase| lower == *phrase || lower.starts_with(&format!("{} ", phrase)) || lower.starts_with(&format!("{},", phrase)));
+
+    if starts_with(NEGATIVE) {
+        FollowUp::Decline
+    } else if words.iter().any(|word| SCAN_WORDS.contains(&word.as_str())) {
+        FollowUp::Scan(None)
+    } else if starts_with(AFFIRMATIVE) {
+        FollowUp::Teach
+    } else {
+        FollowUp::Unrelated
+    }
+}
+
+/// A Wikipedia article named in the prompt, as `wikipedia:Titre` or as an https link to
+/// wikipedia.org. Nothing else is taken from a prompt: a local path or another host would let
+/// anyone who can talk to the Core read the server's files or make it fetch any URL.
+pub fn find_source(prompt: &str) -> Option<DataSource> {
+    prompt
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| matches!(c, '<' | '>' | '"' | '\'' | '«' | '»' | '(' | ')' | ',')).trim_end_matches(['.', '!', '?']))
+        .find(|word| word.starts_with("wikipedia:") || is_wikipedia_url(word))
+        .map(DataSource::parse)
+}
+
+/// An https URL whose host is wikipedia.org or one of its language subdomains.
+fn is_wikipedia_url(word: &str) -> bool {
+    let Some(rest) = word.strip_prefix("https://") else {
+        return false;
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default().to_lowercase();
+    !host.contains(['@', ':']) && (host == "wikipedia.org" || host.ends_with(".wikipedia.org"))
+}
+
+/// The subject words of a prompt (lowercased, in order, without duplicates) for which
+/// `is_known` is false. Numbers, compounds, verbs ("explique", "était"), adverbs ("vraiment")
+/// and the words a strategy is chosen on ("différence") are not subjects.
+pub fn unknown_key_concepts<F>(prompt: &str, stop_words: &StopWordRegistry, is_known: F) -> Vec<String>
+where
+    F: Fn(&str, &str) -> bool,
+{
+    let lang = lemmatizer::detect_language(prompt);
+    let mut unknown: Vec<String> = Vec::new();
+    for token in tokenizer::tokenize(prompt) {
+        if token.kind != TokenKind::Word {
+            continue;
+        }
+        let word = token.normalized();
+        if word.chars().count() <= 2 || stop_words.is_stop_word(&word) || !is_subject_word(&word) {
+            continue;
+        }
+        let lemma = lemmatizer::lemmatize_in(&word, lang);
+        if !is_known(&word, &lemma) && !unknown.contains(&word) {
+            unknown.push(word);
+        }
+    }
+    unknown
+}
+
+/// The subject words of a prompt, names first: a word written with a capital past the first
+/// one ("Qui était Platon ?") is what the prompt is about.
+pub fn subject_words(prompt: &str, stop_words: &StopWordRegistry) -> Vec<String> {
+    let names: Vec<String> = tokenizer::tokenize(prompt)
+        .into_iter()
+        .filter(|token| token.kind == TokenKind::Word)
+        .skip(1)
+        .filter(|token| token.text.starts_with(char::is_uppercase))
+        .map(|token| token.normalized())
+        .collect();
+    let mut subjects = unknown_key_concepts(prompt, stop_words, |_, _| false);
+    subjects.sort_by_key(|word| !names.contains(word));
+    subjects
+}
+
+/// Whether a word may name what a prompt or a memory is about: not a verb, an adverb nor a
+/// word a strategy is chosen on.
+pub fn is_subject_word(word: &str) -> bool {
+    ![PROMPT_VERBS, COMMON_VERBS, ADVERBS, STRATEGY_WORDS].iter().any(|words| words.contains(&word))
+}
+
+/// "I don't know about X yet — want to teach me or should I scan a source?"
+pub fn offer_message(concepts: &[String], lang: Lang) -> String {
+    let names = quote_list(concepts, lang);
+    match lang {
+        Lang::French => format!(
+            "Je ne connais pas encore {} — voulez-vous me l'apprendre, ou dois-je scanner un article de Wikipédia ?",
+            names
+        ),
+        Lang::English => format!("I don't know about {} yet — want to teach me, or should I scan a Wikipedia article?", names),
+    }
+}
+
+pub fn ask_definition(concept: &str, lang: Lang) -> String {
+    match lang {
+        Lang::French => format!("Avec plaisir. Qu'est-ce que « {} » ?", concept),
+        Lang::English => format!("Great. What is \"{}\"?", concept),
+    }
+}
+
+pub fn ask_source(lang: Lang) -> String {
+    match lang {
+        Lang::French => "Quel article dois-je scanner ? Donnez-moi son lien ou « wikipedia:Titre ».".to_string(),
+        Lang::English => "Which article should I scan? Give me its link or \"wikipedia:Title\".".to_string(),
+    }
+}
+
+/// The scan of a source is only queued: an administrator confirms it (see `Core::take_pending_scans`).
+pub fn scan_queued(source: &DataSource, lang: Lang) -> String {
+    let location = source.location();
+    match lang {
+        Lang::French => format!("J'ai noté {}. Je l'apprendrai dès qu'un administrateur aura confirmé le scan.", location),
+        Lang::English => format!("Noted {}. I'll learn it once an administrator confirms the scan.", location),
+    }
+}
+
+pub fn learned(concept: &str, memories_created: usize, lang: Lang) -> String {
+    match lang {
+        Lang::French => format!("Merci ! J'ai appris « {} » ({} nouveau(x) souvenir(s)).", concept, memories_created),
+        Lang::English => format!("Thanks! I've learned about \"{}\" ({} new memor{}).", concept, memories_created, if memories_created == 1 { "y" } else { "ies" }),
+    }
+}
+
+pub fn declined(lang: Lang) -> String {
+    match lang {
+        Lang::French => "D'accord, une autre fois.".to_string(),
+        Lang::English => "Alright, another time.".to_string(),
+    }
+}
+
+/// The text stored for a definition: the reply itself if it names the concept, otherwise
+/// the reply prefixed with the concept so that it can be retrieved by name.
+pub fn definition_text(concept: &str, reply: &str) -> String {
+    let reply = reply.trim();
+    if reply.to_lowercase().contains(concept) {
+        reply.to_string()
+    } else {
+        format!("{} : {}", concept, reply)
+    }
+}
+
+/// The concepts quoted and listed: "« a », « b » et « c »".
+pub fn quote_list(concepts: &[String], lang: Lang) -> String {
+    let quoted: Vec<String> = concepts
+        .iter()
+        .map(|concept| match lang {
+            Lang::French => format!("« {} »", concept),
+            Lang::English => format!("\"{}\"", concept),
+        })
+        .collect();
+    let conjunction = match lang {
+        Lang::French => " et ",
+        Lang::English => " and ",
+    };
+    match quoted.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{}{}{}", rest.join(", "), conjunction, last),
+        _ => quoted.concat(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_concepts_and_follow_ups() {
+        let stop_words = StopWordRegistry::new();
+        let known = ["photosynthèse"];
+        let unknown = unknown_key_concepts("Explique la photosynthèse et les quasars", &stop_words, |word, lemma| {
+            known.contains(&word) || known.contains(&lemma)
+        });
+        assert_eq!(unknown, vec!["quasars"]);
+
+        assert!(matches!(classify_follow_up("Oui !"), FollowUp::Teach));
+        assert!(matches!(classify_follow_up("non merci"), FollowUp::Decline));
+        assert!(matches!(classify_follow_up("scanne plutôt une source"), FollowUp::Scan(None)));
+        assert!(matches!(
+            classify_follow_up("Scan https://fr.wikipedia.org/wiki/Quasar."),
+            FollowUp::Scan(Some(DataSource::Http { url })) if url == "https://fr.wikipedia.org/wiki/Quasar"
+        ));
+        assert!(matches!(classify_follow_up("Quelle heure est-il ?"), FollowUp::Unrelated));
+        assert!(matches!(classify_follow_up("wikipedia:Quasar"), FollowUp::Scan(Some(DataSource::Wikipedia { .. }))));
+        assert_eq!(definition_text("quasar", "Un noyau de galaxie très lumineux."), "quasar : Un noyau de galaxie très lumineux.");
+    }
+
+    #[test]
+    fn test_sources_are_only_wikipedia_articles() {
+        assert!(find_source("lis /etc/passwd").is_none());
+        assert!(find_source("scanne Cargo.toml").is_none());
+        assert!(find_source("http://fr.wikipedia.org/wiki/Quasar").is_none());
+        assert!(find_source("https://169.254.169.254/latest/meta-data").is_none());
+        assert!(find_source("https://wikipedia.org.evil.com/wiki/Quasar").is_none());
+        assert!(find_source("https://fr.wikipedia.org:8080/wiki/Quasar").is_none());
+        assert!(find_source("https://en.wikipedia.org/wiki/Quasar").is_some());
+    }
+
+    #[test]
+    fn test_adverbs_verbs_and_strategy_words_are_not_subjects() {
+        let stop_words = StopWordRegistry::new();
+        let unknown = |prompt: &str| unknown_key_concepts(prompt, &stop_words, |_, _| false);
+        assert_eq!(unknown("Qui était vraiment Socrate ?"), vec!["socrate"]);
+        assert_eq!(unknown("What is the difference between Platon and Aristote?"), vec!["platon", "aristote"]);
+        assert_eq!(unknown("How do I make bread?"), vec!["bread"]);
+    }
+
+    #[test]
+    fn test_the_subject_is_a_name_rather_than_a_verb() {
+        let stop_words = StopWordRegistry::new();
+        assert_eq!(subject_words("Qui était Platon ?", &stop_words), vec!["platon"]);
+        assert_eq!(subject_words("Parle-moi de la philosophie de Kant", &stop_words).first().map(String::as_str), Some("kant"));
+    }
+}
@@ -0,0 +1,730 @@
+//! pdf_text.rs - A small PDF reader that extracts the text of each page.
+//!
+//! It reads the objects of the file (including compressed object streams), walks the page tree
+//! in order, decodes the content streams (FlateDecode) and interprets the text operators. Text
+//! shown with a font that has a ToUnicode CMap is mapped through it; other fonts are read as
+//! Latin-1 / WinAnsi. Lines are grouped into paragraphs from the vertical gaps between them.
+//! Encrypted files and image-only (scanned) pages yield no text.
+
+use flate2::read::ZlibDecoder;
+use regex::bytes::Regex;
+use std::collections::HashMap;
+use std::io::Read;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PdfError {
+    #[error("not a PDF file")]
+    NotPdf,
+    #[error("encrypted PDF files are not supported")]
+    Encrypted,
+    #[error("the PDF has no page tree")]
+    NoPages,
+}
+
+/// The text of a page, split into paragraphs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfPage {
+    /// 1-based page number.
+    pub number: u32,
+    pub paragraphs: Vec<String>,
+}
+
+/// Extracts the paragraphs of every page of a PDF file.
+pub fn extract_pages(bytes: &[u8]) -> Result<Vec<PdfPage>, PdfError> {
+    if !bytes.starts_with(b"%PDF") {
+        return Err(PdfError::NotPdf);
+    }
+    let document = Document::parse(bytes);
+    if document.trailer_has(b"/Encrypt") {
+        return Err(PdfError::Encrypted);
+    }
+    let pages = document.pages();
+    if pages.is_empty() {
+        return Err(PdfError::NoPages);
+    }
+    Ok(pages
+        .iter()
+        .enumerate()
+        .map(|(index, page)| PdfPage { number: index as u32 + 1, paragraphs: document.page_paragraphs(page) })
+        .collect())
+}
+
+/// A PDF object. Streams keep their dictionary and their decoded data.
+#[derive(Debug, Clone, PartialEq)]
+enum Object {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(Vec<u8>),
+    Name(String),
+    Array(Vec<Object>),
+    Dictionary(HashMap<String, Object>),
+    Reference(u32),
+    Stream(HashMap<String, Object>, Vec<u8>),
+    /// A bare keyword of a content stream ("BT", "Tj"...).
+    Operator(String),
+}
+
+impl Object {
+    fn dictionary(&self) -> Option<&HashMap<String, Object>> {
+        match self {
+            Object::Dictionary(dictionary) | Object::Stream(dictionary, _) => Some(dictionary),
+            _ => None,
+        }
+    }
+
+    fn number(&self) -> Option<f64> {
+        match self {
+            Object::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        match self {
+            Object::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// Reads objects from PDF syntax.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(byte) = self.peek() {
+            if byte == b'%' {
+                while self.peek().is_some_and(|byte| byte != b'\n' && byte != b'\r') {
+                    self.position += 1;
+                }
+            } else if byte.is_ascii_whitespace() || byte == 0 {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn is_delimiter(byte: u8) -> bool {
+        byte.is_ascii_whitespace() || b"()<>[]{}/%".contains(&byte)
+    }
+
+    fn token(&mut self) -> &'a [u8] {
+        let start = self.position;
+        while self.peek().is_some_and(|byte| !Self::is_delimiter(byte)) {
+            self.position += 1;
+        }
+        &self.bytes[start..self.position]
+    }
+
+    /// The next object, with "N G R" read as a reference. None at the end of the input.
+    fn object(&mut self) -> Option<Object> {
+        self.skip_whitespace();
+        let byte = self.peek()?;
+        let object = match byte {
+            b'/' => {
+                self.position += 1;
+                Object::Name(String::from_utf8_lossy(self.token()).into_owned())
+            }
+            b'(' => Object::String(self.literal_string()),
+            b'<' if self.bytes.get(self.position + 1) == Some(&b'<') => {
+                self.position += 2;
+                let mut dictionary = HashMap::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.bytes[self.position..].starts_with(b">>") || self.peek().is_none() {
+                        self.position += 2;
+                        break;
+                    }
+                    match (self.object(), self.object()) {
+                        (Some(Object::Name(key)), Some(value)) => {
+                            dictionary.insert(key, value);
+                        }
+                        (None, _) | (_, None) => break,
+                        _ => {}
+                    }
+                }
+                Object::Dictionary(dictionary)
+            }
+            b'<' => Object::String(self.hex_string()),
+            b'[' => {
+                self.position += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() == Some(b']') {
+                        self.position += 1;
+                        break;
+                    }
+                    match self.object() {
+                        Some(item) => items.push(item),
+                        None => break,
+                    }
+                }
+                Object::Array(items)
+            }
+            b']' | b'>' | b')' | b'{' | b'}' => {
+                // Stray delimiter: skip it.
+                self.position += 1;
+                Object::Operator(String::new())
+            }
+            _ => {
+                let token = self.token();
+                if token.is_empty() {
+                    self.position += 1;
+                    return Some(Object::Operator(String::new()));
+                }
+                let text = String::from_utf8_lossy(token);
+                match text.as_ref() {
+                    "true" => Object::Bool(true),
+                    "false" => Object::Bool(false),
+                    "null" => Object::Null,
+                    _ => match text.parse::<f64>() {
+                        Ok(number) => self.reference_after(number).unwrap_or(Object::Number(number)),
+                        Err(_) => Object::Operator(text.into_owned()),
+                    },
+                }
+            }
+        };
+        Some(object)
+    }
+
+    /// Reads " G R" after an integer, restoring the position if it is not there.
+    fn reference_after(&mut self, number: f64) -> Option<Object> {
+        let saved = self.position;
+        self.skip_whitespace();
+        let generation = self.token();
+        self.skip_whitespace();
+        let is_reference = !generation.is_empty() && generation.iter().all(u8::is_ascii_digit) && self.peek() == Some(b'R')
+            && self.bytes.get(self.position + 1).is_none_or(|byte| Self::is_delimiter(*byte));
+        if is_reference && number >= 0.0 && number.fract() == 0.0 {
+            self.position += 1;
+            Some(Object::Reference(number as u32))
+        } else {
+            self.position = saved;
+            None
+        }
+    }
+
+    fn literal_string(&mut self) -> Vec<u8> {
+        self.position += 1;
+        let mut depth = 1;
+        let mut output = Vec::new();
+        while let Some(byte) = self.peek() {
+            self.position += 1;
+            match byte {
+                b'\\' => {
+                    let Some(escaped) = self.peek() else { break };
+                    self.position += 1;
+                    match escaped {
+                        b'n' => output.push(b'\n'),
+                        b'r' => output.push(b'\r'),
+                        b't' => output.push(b'\t'),
+                        b'b' => output.push(8),
+                        b'f' => output.push(12),
+                        b'\r' | b'\n' => {
+                            if escaped == b'\r' && self.peek() == Some(b'\n') {
+                                self.position += 1;
+                            }
+                        }
+                        b'0'..=b'7' => {
+                            let mut value = (escaped - b'0') as u32;
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(digit @ b'0'..=b'7') => {
+                                        value = value * 8 + (digit - b'0') as u32;
+                                        self.position += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            output.push(value as u8);
+                        }
+                        other => output.push(other),
+                    }
+                }
+                b'(' => {
+                    depth += 1;
+                    output.push(byte);
+                }
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                    output.push(byte);
+                }
+                _ => output.push(byte),
+            }
+        }
+        output
+    }
+
+    fn hex_string(&mut self) -> Vec<u8> {
+        self.position += 1;
+        let mut digits = Vec::new();
+        while let Some(byte) = self.peek() {
+            self.position += 1;
+            if byte == b'>' {
+                break;
+            }
+            if byte.is_ascii_hexdigit() {
+                digits.push(byte);
+            }
+        }
+        if digits.len() % 2 == 1 {
+            digits.push(b'0');
+        }
+        digits.chunks(2).map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap_or("0"), 16).unwrap_or(0)).collect()
+    }
+}
+
+/// The objects of a PDF file, by object number.
+struct Document {
+    objects: HashMap<u32, Object>,
+    trailer: Vec<u8>,
+}
+
+impl Document {
+    fn parse(bytes: &[u8]) -> Self {
+        let header = Regex::new(r"(\d+)\s+\d+\s+obj\b").unwrap();
+        let mut objects = HashMap::new();
+        for captures in header.captures_iter(bytes) {
+            let Some(number) = std::str::from_utf8(&captures[1]).ok().and_then(|number| number.parse::<u32>().ok()) else { continue };
+            let start = captures.get(0).unwrap().end();
+            let mut parser = Parser::new(&bytes[start..]);
+            let Some(object) = parser.object() else { continue };
+            let object = match object {
+                Object::Dictionary(dictionary) => {
+                    parser.skip_whitespace();
+                    match Self::raw_stream(&bytes[start + parser.position..]) {
+                        Some(data) => {
+                            let data = decode_stream(&dictionary, data);
+                            Object::Stream(dictionary, data)
+                        }
+                        None => Object::Dictionary(dictionary),
+                    }
+                }
+                other => other,
+            };
+            objects.insert(number, object);
+        }
+
+        // Objects compressed in object streams (PDF 1.5+).
+        let object_streams: Vec<(HashMap<String, Object>, Vec<u8>)> = objects
+            .values()
+            .filter_map(|object| match object {
+                Object::Stream(dictionary, data) if dictionary.get("Type").and_then(Object::name) == Some("ObjStm") => Some((dictionary.clone(), data.clone())),
+                _ => None,
+            })
+            .collect();
+        for (dictionary, data) in object_streams {
+            let count = dictionary.get("N").and_then(Object::number).unwrap_or(0.0) as usize;
+            let first = dictionary.get("First").and_then(Object::number).unwrap_or(0.0) as usize;
+            let mut header = Parser::new(&data);
+            let mut entries = Vec::with_capacity(count);
+            for _ in 0..count {
+                match (header.object().and_then(|o| o.number()), header.object().and_then(|o| o.number())) {
+                    (Some(number), Some(offset)) => entries.push((number as u32, first + offset as usize)),
+                    _ => break,
+                }
+            }
+            for (number, offset) in entries {
+                if offset < data.len() {
+                    if let Some(object) = Parser::new(&data[offset..]).object() {
+                        objects.entry(number).or_insert(object);
+                    }
+                }
+            }
+        }
+
+        let trailer = bytes.windows(7).rposition(|window| window == b"trailer").map(|position| bytes[position..].to_vec()).unwrap_or_default();
+        Self { objects, trailer }
+    }
+
+    /// The raw data of a stream whose "stream" keyword starts `bytes`.
+    fn raw_stream(bytes: &[u8]) -> Option<&[u8]> {
+        let rest = bytes.strip_prefix(b"stream")?;
+        let rest = rest.strip_prefix(b"\r\n").or_else(|| rest.strip_prefix(b"\n")).or_else(|| rest.strip_prefix(b"\r")).unwrap_or(rest);
+        let end = rest.windows(9).position(|window| window == b"endstream")?;
+        let mut data = &rest[..end];
+        while let Some(trimmed) = data.strip_suffix(b"\n").or_else(|| data.strip_suffix(b"\r")) {
+            data = trimmed;
+        }
+        Some(data)
+    }
+
+    fn trailer_has(&self, key: &[u8]) -> bool {
+        self.trailer.windows(key.len()).any(|window| window == key)
+            || self.objects.values().any(|object| matches!(object, Object::Stream(dictionary, _) if dictionary.get("Type").and_then(Object::name) == Some("XRef") && dictionary.contains_key("Encrypt")))
+    }
+
+    fn resolve<'a>(&'a self, object: &'a Object) -> &'a Object {
+        let mut object = object;
+        for _ in 0..8 {
+            match object {
+                Object::Reference(number) => match self.objects.get(number) {
+                    Some(target) => object = target,
+                    None => return &Object::Null,
+                },
+                _ => break,
+            }
+        }
+        object
+    }
+
+    fn get<'a>(&'a self, dictionary: &'a HashMap<String, Object>, key: &str) -> &'a Object {
+        dictionary.get(key).map(|object| self.resolve(object)).unwrap_or(&Object::Null)
+    }
+
+    /// The page dictionaries in reading order, with their inherited resources.
+    fn pages(&self) -> Vec<HashMap<String, Object>> {
+        let root = self.objects.values().find(|object| object.dictionary().and_then(|d| d.get("Type")).and_then(Object::name) == Some("Catalog"));
+        let mut pages = Vec::new();
+        if let Some(tree) = root.and_then(Object::dictionary).map(|catalog| self.get(catalog, "Pages")) {
+            self.collect_pages(tree, None, &mut pages, 0);
+        }
+        if pages.is_empty() {
+            // No usable page tree: take the page objects in file order.
+            let mut numbers: Vec<&u32> = self.objects.keys().collect();
+            numbers.sort();
+            pages = numbers
+                .into_iter()
+                .filter_map(|number| self.objects[number].dictionary())
+                .filter(|dictionary| dictionary.get("Type").and_then(Object::name) == Some("Page"))
+                .cloned()
+                .collect();
+        }
+        pages
+    }
+
+    fn collect_pages(&self, node: &Object, inherited: Option<&Object>, pages: &mut Vec<HashMap<String, Object>>, depth: usize) {
+        let Some(dictionary) = node.dictionary() else { return };
+        if depth > 32 {
+            return;
+        }
+        let resources = dictionary.get("Resources").or(inherited);
+        match dictionary.get("Type").and_then(Object::name) {
+            Some("Pages") => {
+                if let Object::Array(kids) = self.get(dictionary, "Kids") {
+                    for kid in kids {
+                        self.collect_pages(self.resolve(kid), resources, pages, depth + 1);
+                    }
+                }
+            }
+            _ => {
+                let mut page = dictionary.clone();
+                if let Some(resources) = resources {
+                    page.entry("Resources".to_string()).or_insert_with(|| resources.clone());
+                }
+                pages.push(page);
+            }
+        }
+    }
+
+    fn page_paragraphs(&self, page: &HashMap<String, Object>) -> Vec<String> {
+        let content: Vec<u8> = match self.get(page, "Contents") {
+            Object::Stream(_, data) => data.clone(),
+            Object::Array(parts) => parts
+                .iter()
+                .filter_map(|part| match self.resolve(part) {
+                    Object::Stream(_, data) => Some(data.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join(&b'\n'),
+            _ => return Vec::new(),
+        };
+        let fonts = self.page_fonts(page);
+        paragraphs(&TextReader::new(&fonts).read(&content))
+    }
+
+    fn page_fonts(&self, page: &HashMap<String, Object>) -> HashMap<String, Font> {
+        let mut fonts = HashMap::new();
+        let Some(resources) = self.get(page, "Resources").dictionary() else { return fonts };
+        let Some(font_dictionary) = self.get(resources, "Font").dictionary() else { return fonts };
+        for (name, font) in font_dictionary {
+            let Some(font) = self.resolve(font).dictionary() else { continue };
+            let two_bytes = self.get(font, "Subtype").name() == Some("Type0");
+            let to_unicode = match self.get(font, "ToUnicode") {
+                Object::Stream(_, data) => parse_to_unicode(data),
+                _ => HashMap::new(),
+            };
+            fonts.insert(name.clone(), Font { two_bytes, to_unicode });
+        }
+        fonts
+    }
+}
+
+fn decode_stream(dictionary: &HashMap<String, Object>, data: &[u8]) -> Vec<u8> {
+    let filters: Vec<&str> = match dictionary.get("Filter") {
+        Some(Object::Name(name)) => vec![name],
+        Some(Object::Array(names)) => names.iter().filter_map(Object::name).collect(),
+        _ => Vec::new(),
+    };
+    let mut data = data.to_vec();
+    for filter in filters {
+        match filter {
+            "FlateDecode" | "Fl" => {
+                let mut decoded = Vec::new();
+                // Truncated streams still give what was decoded before the error.
+                let _ = ZlibDecoder::new(data.as_slice()).read_to_end(&mut decoded);
+                data = decoded;
+            }
+            // Images and other encodings carry no text.
+            _ => return Vec::new(),
+        }
+    }
+    data
+}
+
+/// How to turn the bytes shown with a font into text.
+struct Font {
+    two_bytes: bool,
+    to_unicode: HashMap<u32, String>,
+}
+
+impl Font {
+    fn decode(&self, bytes: &[u8]) -> String {
+        if self.two_bytes {
+            bytes
+                .chunks(2)
+                .map(|pair| {
+                    let code = pair.iter().fold(0u32, |code, byte| code << 8 | *byte as u32);
+                    self.to_unicode.get(&code).cloned().unwrap_or_default()
+                })
+                .collect()
+        } else {
+            bytes.iter().map(|byte| self.to_unicode.get(&(*byte as u32)).cloned().unwrap_or_else(|| win_ansi(*byte).to_string())).collect()
+        }
+    }
+}
+
+/// A byte of a simple font, read as WinAnsi (Latin-1 with typographic punctuation).
+fn win_ansi(byte: u8) -> char {
+    match byte {
+        0x91 | 0x92 => '\'',
+        0x93 | 0x94 => '"',
+        0x96 | 0x97 => '-',
+        0x85 => '…',
+        0x9C => 'œ',
+        0x8C => 'Œ',
+        0x80 => '€',
+        0x00..=0x1F => ' ',
+        _ => byte as char,
+    }
+}
+
+/// The code-to-text map of a ToUnicode CMap (`bfchar` and `bfrange` sections).
+fn parse_to_unicode(cmap: &[u8]) -> HashMap<u32, String> {
+    let mut map = HashMap::new();
+    let mut parser = Parser::new(cmap);
+    let mut tokens = Vec::new();
+    while let Some(object) = parser.object() {
+        tokens.push(object);
+    }
+    let code = |bytes: &[u8]| bytes.iter().fold(0u32, |code, byte| code << 8 | *byte as u32);
+    let text = |bytes: &[u8]| String::from_utf16_lossy(&bytes.chunks(2).map(|pair| pair.iter().fold(0u16, |unit, byte| unit << 8 | *byte as u16)).collect::<Vec<_>>());
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            Object::Operator(op) if op == "beginbfchar" => {
+                i += 1;
+                while let (Some(Object::String(source)), Some(Object::String(target))) = (tokens.get(i), tokens.get(i + 1)) {
+                    map.insert(code(source), text(target));
+                    i += 2;
+                }
+            }
+            Object::Operator(op) if op == "beginbfrange" => {
+                i += 1;
+                while let (Some(Object::String(low)), Some(Object::String(high))) = (tokens.get(i), tokens.get(i + 1)) {
+                    let (low, high) = (code(low), code(high));
+                    match tokens.get(i + 2) {
+                        Some(Object::String(start)) => {
+                            let start = code(start);
+                            for (offset, source) in (low..=high.min(low + 0xFFFF)).enumerate() {
+                                if let Some(c) = char::from_u32(start + offset as u32) {
+                                    map.insert(source, c.to_string());
+                                }
+                            }
+                        }
+                        Some(Object::Array(targets)) => {
+                            for (source, target) in (low..=high).zip(targets) {
+                                if let Object::String(target) = target {
+                                    map.insert(source, text(target));
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 3;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    map
+}
+
+/// A line of text with its vertical position on the page.
+#[derive(Debug)]
+struct Line {
+    y: f64,
+    text: String,
+}
+
+/// Interprets the text operators of a content stream.
+struct TextReader<'a> {
+    fonts: &'a HashMap<String, Font>,
+    font: Option<&'a Font>,
+    /// Vertical position and scale of the text line matrix.
+    y: f64,
+    scale: f64,
+    leading: f64,
+    lines: Vec<Line>,
+}
+
+impl<'a> TextReader<'a> {
+    fn new(fonts: &'a HashMap<String, Font>) -> Self {
+        Self { fonts, font: None, y: 0.0, scale: 1.0, leading: 0.0, lines: Vec::new() }
+    }
+
+    fn read(mut self, content: &[u8]) -> Vec<Line> {
+        let mut parser = Parser::new(content);
+        let mut operands: Vec<Object> = Vec::new();
+        while let Some(object) = parser.object() {
+            let Object::Operator(operator) = object else {
+                operands.push(object);
+                continue;
+            };
+            let number = |index: usize| operands.get(index).and_then(Object::number).unwrap_or(0.0);
+            match operator.as_str() {
+                "BT" => {
+                    self.y = 0.0;
+                    self.scale = 1.0;
+                }
+                "Tf" => {
+                    self.font = operands.first().and_then(Object::name).and_then(|name| self.fonts.get(name));
+                }
+                "TL" => self.leading = number(0),
+                "Td" => self.move_line(number(1)),
+                "TD" => {
+                    self.leading = -number(1);
+                    self.move_line(number(1));
+                }
+                "Tm" => {
+                    self.scale = if number(3) == 0.0 { 1.0 } else { number(3).abs() };
+                    self.new_line_at(number(5));
+                }
+                "T*" => self.move_line(-self.leading),
+                "Tj" => self.show(operands.first()),
+                "'" => {
+                    self.move_line(-self.leading);
+                    self.show(operands.first());
+                }
+                "\"" => {
+                    self.move_line(-self.leading);
+                    self.show(operands.get(2));
+                }
+                "TJ" => {
+                    if let Some(Object::Array(items)) = operands.first() {
+                        for item in items {
+                            match item {
+                                // A large negative kerning is a space between words.
+                                Object::Number(kerning) if *kerning < -180.0 => self.push_text(" "),
+                                Object::String(_) => self.show(Some(item)),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                "ID" => {
+                    // Inline image data: skip to "EI".
+                    let rest = &content[parser.position..];
+                    parser.position += rest.windows(3).position(|window| window[0].is_ascii_whitespace() && &window[1..] == b"EI").map_or(rest.len(), |end| end + 3);
+                }
+                _ => {}
+            }
+            operands.clear();
+        }
+        self.lines
+    }
+
+    fn move_line(&mut self, ty: f64) {
+        let y = self.y + ty * self.scale;
+        self.new_line_at(y);
+    }
+
+    fn new_line_at(&mut self, y: f64) {
+        if (y - self.y).abs() > 0.01 || self.lines.is_empty() {
+            self.lines.push(Line { y, text: String::new() });
+        }
+        self.y = y;
+    }
+
+    fn show(&mut self, operand: Option<&Object>) {
+        if let Some(Object::String(bytes)) = operand {
+            let text = match self.font {
+                Some(font) => font.decode(bytes),
+                None => bytes.iter().map(|byte| win_ansi(*byte)).collect(),
+            };
+            self.push_text(&text);
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.lines.is_empty() {
+            self.lines.push(Line { y: self.y, text: String::new() });
+        }
+        self.lines.last_mut().unwrap().text.push_str(text);
+    }
+}
+
+/// Groups lines into paragraphs: a gap clearly larger than the usual line spacing, or a jump
+/// upwards (a new column), starts a new paragraph. Hyphenated line ends are rejoined.
+fn paragraphs(lines: &[Line]) -> Vec<String> {
+    let lines: Vec<&Line> = lines.iter().filter(|line| !line.text.trim().is_empty()).collect();
+    let mut gaps: Vec<f64> = lines.windows(2).map(|pair| pair[0].y - pair[1].y).filter(|gap| *gap > 0.5).collect();
+    gaps.sort_by(|a, b| a.total_cmp(b));
+    let usual_gap = gaps.get(gaps.len().saturating_sub(1) / 2).copied().unwrap_or(0.0);
+
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for (index, line) in lines.iter().enumerate() {
+        let text = line.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if index > 0 {
+            let gap = lines[index - 1].y - line.y;
+            let breaks = gap < -0.5 || (usual_gap > 0.0 && gap > usual_gap * 1.4);
+            if breaks && !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+        }
+        if current.is_empty() {
+            current = text;
+        } else if current.ends_with('-') && current[..current.len() - 1].ends_with(char::is_alphabetic) {
+            current.pop();
+            current.push_str(&text);
+        } else {
+            current.push(' ');
+            current.push_str(&text);
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
+}
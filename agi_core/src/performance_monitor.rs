@@ -1,3 +1,5 @@
+use crate::eeg::BandPower;
+use crate::error::LockResultExt;
 use sysinfo::System;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
@@ -11,6 +13,8 @@ pub struct Metrics {
     pub tps: f64, // Ticks Per Second
         pub concepts_in_memory: usize,
         pub power_draw_w: f32,
+        /// Power of the pseudo EEG bands, see `eeg`.
+        pub eeg_bands: BandPower,
         // pub gpus: Vec<GpuMetrics>,
 }
 
@@ -40,10 +44,15 @@ impl PerformanceMonitor {
         self.tick_count += 1;
     }
 
-        pub fn get_metrics(&mut self, concepts_in_memory: usize, power_draw_w: f32) -> Metrics {
+    /// Records several ticks at once, for callers that observe the tick counter from afar.
+    pub fn record_ticks(&mut self, count: u64) {
+        self.tick_count += count;
+    }
+
+        pub fn get_metrics(&mut self, concepts_in_memory: usize, power_draw_w: f32, eeg_bands: BandPower) -> Metrics {
                 
 
-        let mut sys = self.system.lock().unwrap();
+        let mut sys = self.system.lock().recover();
                 sys.refresh_cpu();
         sys.refresh_memory();
         
@@ -69,6 +78,7 @@ impl PerformanceMonitor {
                         tps,
                         concepts_in_memory,
             power_draw_w,
+            eeg_bands,
                                                             // gpus: Vec::new(),
         }
     }
@@ -1,10 +1,175 @@
 //! personality.rs - Defines the AGI's conversational tone and style.
+//!
+//! The style is driven by a `PersonalityProfile` (formality, humor, verbosity, emoji usage,
+//! preferred language). Profiles are read from a TOML file (`CoreConfig::personality_path`) and
+//! one of them is active at a time; the motor cortex and the social cortex both follow it.
 
 use rand::seq::SliceRandom;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use thiserror::Error;
+
+use crate::error::LockResultExt;
+use crate::lemmatizer::Lang;
+use crate::rng::CoreRng;
+use crate::surface_realization;
+
+#[derive(Debug, Error)]
+pub enum PersonalityError {
+    #[error("I/O error on the personality profiles: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid personality profiles: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("no personality profile named '{0}'")]
+    UnknownProfile(String),
+}
+
+/// The traits the style of the answers follows. Each trait goes from 0.0 to 1.0; the default
+/// profile (every trait at 0.5, no emoji) is the historical behaviour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersonalityProfile {
+    pub name: String,
+    /// From familiar to formal: formal profiles avoid exclamations and casual greetings.
+    pub formality: f32,
+    /// How readily the tone turns playful, and whether jokes are told when asked for.
+    pub humor: f32,
+    /// From terse (bare answers, short replies) to elaborate (an intro phrase on every answer).
+    pub verbosity: f32,
+    /// Probability of ending an answer with an emoji.
+    pub emoji: f32,
+    /// Language of the answers when a prompt gives no clue about its own.
+    pub preferred_language: Option<Lang>,
+    /// Voice the answers are spoken with (see `speech`), as the engine names it; `None` takes
+    /// the voice of the engine for the language of the answer.
+    pub voice: Option<String>,
+    /// Speed of the speech, 1.0 being the normal speed of the voice (from 0.5 to 2.0).
+    pub speaking_rate: f32,
+}
+
+impl Default for PersonalityProfile {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            formality: 0.5,
+            humor: 0.5,
+            verbosity: 0.5,
+            emoji: 0.0,
+            preferred_language: None,
+            voice: None,
+            speaking_rate: 1.0,
+        }
+    }
+}
+
+impl PersonalityProfile {
+    /// The traits brought back into `[0.0, 1.0]`.
+    pub fn clamped(mut self) -> Self {
+        for trait_value in [&mut self.formality, &mut self.humor, &mut self.verbosity, &mut self.emoji] {
+            *trait_value = trait_value.clamp(0.0, 1.0);
+        }
+        self.speaking_rate = self.speaking_rate.clamp(0.5, 2.0);
+        self
+    }
+}
+
+/// The profiles to choose from and the name of the active one.
+///
+/// ```toml
+/// active = "teacher"
+///
+/// [[profiles]]
+/// name = "teacher"
+/// formality = 0.9
+/// humor = 0.2
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PersonalityProfiles {
+    pub active: String,
+    pub profiles: Vec<PersonalityProfile>,
+}
+
+impl Default for PersonalityProfiles {
+    fn default() -> Self {
+        let profile = PersonalityProfile::default();
+        Self { active: profile.name.clone(), profiles: vec![profile] }
+    }
+}
+
+impl PersonalityProfiles {
+    /// Reads the profiles from a TOML file. Without an `active` name, the first profile is active.
+    pub fn load(path: &Path) -> Result<Self, PersonalityError> {
+        #[derive(Deserialize)]
+        struct File {
+            active: Option<String>,
+            #[serde(default)]
+            profiles: Vec<PersonalityProfile>,
+        }
+        let file: File = toml::from_str(&fs::read_to_string(path)?)?;
+        let active = file.active.or_else(|| file.profiles.first().map(|profile| profile.name.clone()));
+        let mut profiles = Self::default();
+        for profile in file.profiles {
+            profiles.insert(profile);
+        }
+        if let Some(active) = active {
+            profiles.activate(&active)?;
+        }
+        Ok(profiles)
+    }
+
+    pub fn active(&self) -> &PersonalityProfile {
+        self.get(&self.active).unwrap_or(&self.profiles[0])
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PersonalityProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// Adds a profile, replacing the one with the same name.
+    pub fn insert(&mut self, profile: PersonalityProfile) {
+        let profile = profile.clamped();
+        match self.profiles.iter_mut().find(|known| known.name == profile.name) {
+            Some(known) => *known = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    /// Makes the named profile the active one.
+    pub fn activate(&mut self, name: &str) -> Result<&PersonalityProfile, PersonalityError> {
+        if self.get(name).is_none() {
+            return Err(PersonalityError::UnknownProfile(name.to_string()));
+        }
+        self.active = name.to_string();
+        Ok(self.active())
+    }
+}
+
+/// An emoji matching the tone, for the profiles that use them.
+fn emoji_for(tone: Tone) -> &'static str {
+    match tone {
+        Tone::Neutral => "🙂",
+        Tone::Poetic => "✨",
+        Tone::Inquisitive => "🤔",
+        Tone::Prudent => "📚",
+        Tone::Enthusiastic => "😄",
+    }
+}
+
+/// Appends an emoji to `text` with the probability of the profile.
+pub fn with_emoji<R: Rng>(text: String, tone: Tone, profile: &PersonalityProfile, rng: &mut R) -> String {
+    if profile.emoji > 0.0 && rng.gen_bool(profile.emoji as f64) {
+        format!("{} {}", text.trim_end(), emoji_for(tone))
+    } else {
+        text
+    }
+}
 
 /// Represents the different conversational tones the AGI can adopt.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Tone {
     Neutral,
     Poetic,
@@ -15,59 +180,147 @@ pub enum Tone {
 
 /// A collection of phrases associated with a specific tone.
 struct ToneStyle {
+    /// Complete phrases placed before any response.
     intros: &'static [&'static str],
+    /// Openings that flow into a single-sentence response ("Il me semble que ...").
+    continuations: &'static [&'static str],
 }
 
 // Static definitions of the phrases for each tone.
-const NEUTRAL_STYLE: ToneStyle = ToneStyle { intros: &[""] }; // Neutral has no intro.
-const POETIC_STYLE: ToneStyle = ToneStyle { intros: &["C'est une pensée fascinante...", "Cela évoque une image de...", "On pourrait dire que..."] };
-const INQUISITIVE_STYLE: ToneStyle = ToneStyle { intros: &["Intéressant. Cela me fait penser à...", "Je me demande...", "Est-ce que cela signifie que...?"] };
-const PRUDENT_STYLE: ToneStyle = ToneStyle { intros: &["Il me semble que...", "Si je comprends bien...", "Je crois savoir que..."] };
-const ENTHUSIASTIC_STYLE: ToneStyle = ToneStyle { intros: &["Oh, c'est une excellente question !", "J'adore ce sujet !", "Absolument !"] };
+const NEUTRAL_STYLE: ToneStyle = ToneStyle { intros: &[""], continuations: &[] }; // Neutral has no intro.
+const POETIC_STYLE: ToneStyle = ToneStyle { intros: &["C'est une pensée fascinante...", "Cela évoque une image de..."], continuations: &["On pourrait dire que"] };
+const INQUISITIVE_STYLE: ToneStyle = ToneStyle { intros: &["Intéressant. Cela me fait penser à...", "Est-ce que cela signifie que...?"], continuations: &["Je me demande si"] };
+const PRUDENT_STYLE: ToneStyle = ToneStyle { intros: &["Voici ce que je crois savoir :"], continuations: &["Il me semble que", "Si je comprends bien,", "Je crois savoir que"] };
+const ENTHUSIASTIC_STYLE: ToneStyle = ToneStyle { intros: &["Oh, c'est une excellente question !", "J'adore ce sujet !", "Absolument !"], continuations: &[] };
 
-pub struct Personality;
+// The same tones, for answers given in English.
+const POETIC_STYLE_EN: ToneStyle = ToneStyle { intros: &["What a fascinating thought...", "This brings to mind an image of..."], continuations: &["One could say that"] };
+const INQUISITIVE_STYLE_EN: ToneStyle = ToneStyle { intros: &["Interesting. This makes me think of...", "Does this mean that...?"], continuations: &["I wonder whether"] };
+const PRUDENT_STYLE_EN: ToneStyle = ToneStyle { intros: &["Here is what I believe I know:"], continuations: &["It seems to me that", "If I understand correctly,", "I believe that"] };
+const ENTHUSIASTIC_STYLE_EN: ToneStyle = ToneStyle { intros: &["Oh, that's an excellent question!", "I love this topic!", "Absolutely!"], continuations: &[] };
+
+pub struct Personality {
+    // Behind a lock because styling only borrows the personality.
+    rng: Mutex<CoreRng>,
+    /// The tone of the last stylized response.
+    last_tone: Mutex<Option<Tone>>,
+    /// Probability of keeping the last tone (see `set_tone_stability`).
+    tone_stability: f32,
+    profile: PersonalityProfile,
+}
 
 impl Personality {
     pub fn new() -> Self {
-        Self
+        Self { rng: Mutex::new(CoreRng::from_entropy()), last_tone: Mutex::new(None), tone_stability: 0.0, profile: PersonalityProfile::default() }
+    }
+
+    pub fn profile(&self) -> &PersonalityProfile {
+        &self.profile
     }
 
-    /// Wraps a core response with a phrase that reflects a certain personality tone.
-    /// For now, it picks a tone randomly.
-    pub fn stylize_response(&self, core_response: &str) -> String {
-        let mut rng = rand::thread_rng();
+    /// Switches the traits the style follows.
+    pub fn set_profile(&mut self, profile: PersonalityProfile) {
+        self.profile = profile.clamped();
+    }
+
+    /// Replaces the random stream used to pick tones (see `RngProvider`).
+    pub fn with_rng(mut self, rng: CoreRng) -> Self {
+        self.rng = Mutex::new(rng);
+        self
+    }
 
-        // Give a chance for a neutral response to avoid being too "chatty"
-        if rng.gen_bool(0.4) { // 40% chance of being neutral
-             return core_response.to_string();
+    /// Sets the probability (0.0 to 1.0) of keeping the tone of the previous response instead of
+    /// picking a new one. The Core drives it with serotonin.
+    pub fn set_tone_stability(&mut self, stability: f32) {
+        self.tone_stability = stability.clamp(0.0, 1.0);
+    }
+
+    /// Picks one of `count` phrasings of a sentence, so that the wording varies like the tone does.
+    pub fn choose_variant(&self, count: usize) -> usize {
+        if count == 0 { 0 } else { self.rng.lock().recover().gen_range(0..count) }
+    }
+
+    /// Wraps a core response with a phrase, in `lang`, that reflects a certain personality tone.
+    /// The tone of the previous response is kept with the tone stability; otherwise a new one
+    /// is picked randomly.
+    pub fn stylize_response(&self, core_response: &str, lang: Lang) -> String {
+        let mut rng = self.rng.lock().recover();
+        let rng = &mut *rng;
+        let mut last_tone = self.last_tone.lock().recover();
+
+        let profile = &self.profile;
+        // Deviations from the default profile, which weighs every tone the same.
+        let formality = profile.formality - 0.5;
+        let humor = profile.humor - 0.5;
+        let chosen_tone = match *last_tone {
+            Some(tone) if rng.gen_bool(self.tone_stability as f64) => tone,
+            // Give a chance for a neutral response to avoid being too "chatty": 40% with the
+            // default verbosity, always for the tersest profiles.
+            _ if rng.gen_bool((1.0 - 1.2 * profile.verbosity as f64).clamp(0.0, 1.0)) => Tone::Neutral,
+            // Choose a random tone
+            _ => {
+                let tones = [
+                    (Tone::Poetic, 1.0 - formality),
+                    (Tone::Inquisitive, 1.0),
+                    (Tone::Prudent, 1.0 + 2.0 * formality),
+                    (Tone::Enthusiastic, (1.0 + 2.0 * humor - 2.0 * formality).max(0.0)),
+                ];
+                tones.choose_weighted(rng, |(_, weight)| *weight).map_or(Tone::Neutral, |(tone, _)| *tone)
+            }
+        };
+        *last_tone = Some(chosen_tone);
+        if chosen_tone == Tone::Neutral {
+            return with_emoji(core_response.to_string(), chosen_tone, profile, rng);
         }
 
-        // Choose a random tone
-        let tones = [
-            Tone::Poetic,
-            Tone::Inquisitive,
-            Tone::Prudent,
-            Tone::Enthusiastic,
-        ];
-        let chosen_tone = *tones.choose(&mut rng).unwrap();
-
-        let style = match chosen_tone {
-            Tone::Neutral => &NEUTRAL_STYLE,
-            Tone::Poetic => &POETIC_STYLE,
-            Tone::Inquisitive => &INQUISITIVE_STYLE,
-            Tone::Prudent => &PRUDENT_STYLE,
-            Tone::Enthusiastic => &ENTHUSIASTIC_STYLE,
+        let style = match (chosen_tone, lang) {
+            (Tone::Neutral, _) => &NEUTRAL_STYLE,
+            (Tone::Poetic, Lang::French) => &POETIC_STYLE,
+            (Tone::Inquisitive, Lang::French) => &INQUISITIVE_STYLE,
+            (Tone::Prudent, Lang::French) => &PRUDENT_STYLE,
+            (Tone::Enthusiastic, Lang::French) => &ENTHUSIASTIC_STYLE,
+            (Tone::Poetic, Lang::English) => &POETIC_STYLE_EN,
+            (Tone::Inquisitive, Lang::English) => &INQUISITIVE_STYLE_EN,
+            (Tone::Prudent, Lang::English) => &PRUDENT_STYLE_EN,
+            (Tone::Enthusiastic, Lang::English) => &ENTHUSIASTIC_STYLE_EN,
         };
 
-        // Pick a random intro phrase from the chosen style
-        if let Some(intro) = style.intros.choose(&mut rng) {
-            if intro.is_empty() {
-                core_response.to_string()
-            } else {
-                format!("{} {}", intro, core_response)
-            }
-        } else {
-            core_response.to_string()
+        // Pick a random intro phrase from the chosen style. Continuations only fit a single sentence.
+        let mut phrases: Vec<&str> = style.intros.to_vec();
+        if !core_response.trim().contains('\n') {
+            phrases.extend_from_slice(style.continuations);
         }
+        let stylized = match phrases.choose(rng) {
+            Some(intro) if !intro.is_empty() => surface_realization::join(intro, core_response),
+            _ => core_response.to_string(),
+        };
+        with_emoji(stylized, chosen_tone, profile, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_are_loaded_and_switched() {
+        let path = std::env::temp_dir().join(format!("neurova_personality_{}.toml", std::process::id()));
+        fs::write(&path, "active = \"teacher\"\n\n[[profiles]]\nname = \"teacher\"\nformality = 1.5\nverbosity = 0.0\n\n[[profiles]]\nname = \"friend\"\nemoji = 1.0\npreferred_language = \"english\"\n").unwrap();
+        let mut profiles = PersonalityProfiles::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(profiles.active().name, "teacher");
+        assert_eq!(profiles.active().formality, 1.0, "Traits are clamped");
+        assert!(matches!(profiles.activate("pirate"), Err(PersonalityError::UnknownProfile(_))));
+
+        let friend = profiles.activate("friend").unwrap().clone();
+        assert_eq!((friend.humor, friend.preferred_language), (0.5, Some(Lang::English)));
+        let mut personality = Personality::new().with_rng(CoreRng::seed_from_u64(3));
+        personality.set_profile(friend);
+        for _ in 0..5 {
+            assert!(personality.stylize_response("The sun is a star.", Lang::English).ends_with(|c: char| !c.is_ascii()));
+        }
+
+        personality.set_profile(profiles.activate("teacher").unwrap().clone());
+        assert_eq!(personality.stylize_response("The sun is a star.", Lang::English), "The sun is a star.", "Terse profiles add no intro");
     }
 }
@@ -0,0 +1,97 @@
+//! pipeline.rs - Time budgets for the stages of the response pipeline.
+//!
+//! Each stage of `Core::get_response_for_prompt` runs against its own budget. Stages check
+//! their clock cooperatively between units of work; when a budget is exhausted the pipeline
+//! degrades to the best partial answer it already has instead of failing outright.
+
+use crate::lemmatizer::Lang;
+use std::time::{Duration, Instant};
+
+/// The stages of the response pipeline that carry a time budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Searching the hippocampus for memories relevant to the prompt.
+    Retrieval,
+    /// Turning the retrieved memories into a phrased answer.
+    Synthesis,
+}
+
+/// Per-stage time budgets for the response pipeline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PipelineTimeouts {
+    pub retrieval: Duration,
+    pub synthesis: Duration,
+}
+
+impl PipelineTimeouts {
+    pub fn budget_for(&self, stage: PipelineStage) -> Duration {
+        match stage {
+            PipelineStage::Retrieval => self.retrieval,
+            PipelineStage::Synthesis => self.synthesis,
+        }
+    }
+}
+
+impl Default for PipelineTimeouts {
+    fn default() -> Self {
+        Self {
+            retrieval: Duration::from_millis(1500),
+            synthesis: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Measures a single stage against its budget.
+#[derive(Debug, Clone, Copy)]
+pub struct StageClock {
+    stage: PipelineStage,
+    started: Instant,
+    budget: Duration,
+}
+
+impl StageClock {
+    pub fn start(stage: PipelineStage, timeouts: &PipelineTimeouts) -> Self {
+        Self {
+            stage,
+            started: Instant::now(),
+            budget: timeouts.budget_for(stage),
+        }
+    }
+
+    pub fn stage(&self) -> PipelineStage {
+        self.stage
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.elapsed() > self.budget
+    }
+
+    /// Logs and returns whether the stage has run over its budget.
+    pub fn check(&self) -> bool {
+        let expired = self.is_expired();
+        if expired {
+            println!(
+                "--- Pipeline: {:?} stage exceeded its budget ({:?} > {:?}) ---",
+                self.stage,
+                self.elapsed(),
+                self.budget
+            );
+        }
+        expired
+    }
+}
+
+/// The note appended to an answer delivered verbatim because a stage ran out of time.
+pub fn partial_answer_note(stage: PipelineStage, lang: Lang) -> &'static str {
+    match (stage, lang) {
+        (PipelineStage::Retrieval, Lang::French) => "(Réponse partielle : ma recherche en mémoire a été interrompue faute de temps.)",
+        (PipelineStage::Synthesis, Lang::French) => "(Réponse partielle : je n'ai pas eu le temps de formuler une synthèse, voici le souvenir le plus pertinent tel quel.)",
+        (PipelineStage::Retrieval, Lang::English) => "(Partial answer: my memory search was cut short for lack of time.)",
+        (PipelineStage::Synthesis, Lang::English) => "(Partial answer: I had no time to phrase a synthesis, here is the most relevant memory as is.)",
+    }
+}
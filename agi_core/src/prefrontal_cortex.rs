@@ -1,34 +1,110 @@
 // agi_core/src/prefrontal_cortex.rs
 
+use crate::error::LockResultExt;
+use crate::coreference;
+use crate::expertise::{ExpertiseLevel, ExpertiseTracker};
 use crate::holographic_memory::{ConceptFocuser, HolographicMemory};
+use crate::working_memory::{self, WorkingItem, WorkingMemory};
 use std::collections::HashSet;
 
 /// The PrefrontalCortex is responsible for higher-order cognitive functions:
 /// - Executive decision-making
 /// - Synthesizing information from various sources (like the hippocampus)
-/// - Maintaining conversational context
+/// - Maintaining conversational context, and a working memory of the facts stated in it
 /// - Generating final, coherent responses
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PrefrontalCortex {
-    _concept_focuser: ConceptFocuser,
+    concept_focuser: ConceptFocuser,
     conversation_context: Vec<String>,
+    expertise: ExpertiseTracker,
+    working_memory: WorkingMemory,
 }
 
 impl PrefrontalCortex {
     pub fn new(concept_focuser: ConceptFocuser) -> Self {
         Self {
-            _concept_focuser: concept_focuser,
+            concept_focuser,
             conversation_context: Vec::new(),
+            expertise: ExpertiseTracker::new(),
+            working_memory: WorkingMemory::new(),
         }
     }
 
     /// Updates the conversational context with the latest prompt.
+    /// A new turn also makes the working memory fade, and a stated fact is held in it.
     pub fn update_context(&mut self, prompt: &str) {
         self.conversation_context.push(prompt.to_string());
         // Limit the context size to avoid infinite growth
         if self.conversation_context.len() > 20 {
             self.conversation_context.remove(0);
         }
+
+        self.working_memory.decay();
+        if working_memory::is_statement(prompt) {
+            let stop_words = self.concept_focuser.stop_words();
+            self.working_memory.hold(prompt, &stop_words.read().recover());
+        }
+    }
+
+    /// Rewrites the pronouns of the latest prompt with the last entity named in the conversation
+    /// ("Et qui était son professeur ?" -> "Et qui était le professeur d'Aristote ?").
+    /// Call it after `update_context`. The rewritten prompt replaces the original in the context,
+    /// so that a chain of follow-ups keeps the same antecedent.
+    pub fn resolve_coreferences(&mut self, prompt: &str) -> Option<String> {
+        const LOOKBACK: usize = 5;
+        let stop_words = self.concept_focuser.stop_words();
+        let stop_words = stop_words.read().recover();
+        // A prompt that names an entity refers to it, not to the context.
+        if !coreference::extract_entities(prompt, &stop_words).is_empty() {
+            return None;
+        }
+        let antecedent = self
+            .conversation_context
+            .iter()
+            .rev()
+            .skip(1)
+            .take(LOOKBACK)
+            .find_map(|previous| coreference::extract_entities(previous, &stop_words).pop())?;
+        let resolved = coreference::resolve(prompt, &antecedent)?;
+        println!("--- Coreference: '{}' -> '{}' ---", prompt, resolved);
+        if let Some(latest) = self.conversation_context.last_mut() {
+            *latest = resolved.clone();
+        }
+        Some(resolved)
+    }
+
+    /// The fact of the working memory that answers a follow-up question, if any.
+    pub fn recall_from_working_memory(&self, question: &str) -> Option<&WorkingItem> {
+        let stop_words = self.concept_focuser.stop_words();
+        let stop_words = stop_words.read().recover();
+        self.working_memory.find(question, &stop_words)
+    }
+
+    pub fn working_memory(&self) -> &WorkingMemory {
+        &self.working_memory
+    }
+
+    /// Empties the working memory, returning its facts so that they can be stored for good.
+    pub fn take_working_memory(&mut self) -> Vec<String> {
+        self.working_memory.drain()
+    }
+
+    /// Updates the session's expertise estimate with the latest prompt and its vocabulary score.
+    pub fn observe_expertise(&mut self, prompt: &str, sophistication: Option<f32>) {
+        self.expertise.observe(prompt, sophistication);
+    }
+
+    /// The expertise level that responses should currently be tailored to.
+    pub fn expertise_level(&self) -> ExpertiseLevel {
+        self.expertise.level()
+    }
+
+    /// Pins (or, with `None`, releases) the expertise level for the rest of the session.
+    pub fn set_expertise_override(&mut self, level: Option<ExpertiseLevel>) {
+        match level {
+            Some(level) => self.expertise.set_override(level),
+            None => self.expertise.clear_override(),
+        }
     }
 
     /// Checks if the recent conversation history contains a given keyword.
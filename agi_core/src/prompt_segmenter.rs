@@ -63,3 +63,55 @@ pub fn segment_prompt(prompt: &str) -> Vec<String> {
 
     refined_segments.into_iter().filter(|s| !s.is_empty()).collect()
 }
+
+/// Extrait les deux termes d'une demande de comparaison.
+///
+/// Reconnaît « la différence entre X et Y », « comparer X et Y », « compare X with Y » et
+/// « X vs Y ». Les articles en tête des termes sont retirés.
+pub fn comparison_subjects(prompt: &str) -> Option<(String, String)> {
+    let lower = prompt.to_lowercase().replace('’', "'");
+    let lower = lower.trim().trim_end_matches(['?', '.', '!']).trim();
+
+    // « X vs Y » : les termes sont de part et d'autre du marqueur.
+    for marker in [" vs. ", " vs ", " versus "] {
+        if let Some((left, right)) = lower.split_once(marker) {
+            let left = left.rsplit([',', ':']).next().unwrap_or(left);
+            return subject_pair(left, right);
+        }
+    }
+
+    // « ... entre X et Y » : les termes suivent le marqueur et sont séparés par une conjonction.
+    const MARKERS: &[&str] = &["entre ", "between ", "comparer ", "compare ", "comparing ", "comparaison de "];
+    let (position, marker) = MARKERS.iter().filter_map(|marker| lower.find(marker).map(|position| (position, *marker))).min()?;
+    let rest = &lower[position + marker.len()..];
+    [" et ", " and ", " with ", " to ", " avec ", " à "]
+        .iter()
+        .filter_map(|conjunction| rest.find(conjunction).map(|index| (index, conjunction.len())))
+        .min()
+        .and_then(|(index, length)| subject_pair(&rest[..index], &rest[index + length..]))
+}
+
+fn subject_pair(first: &str, second: &str) -> Option<(String, String)> {
+    let (first, second) = (strip_article(first), strip_article(second));
+    (!first.is_empty() && !second.is_empty()).then(|| (first.to_string(), second.to_string()))
+}
+
+fn strip_article(term: &str) -> &str {
+    const ARTICLES: &[&str] = &["le ", "la ", "les ", "l'", "un ", "une ", "des ", "du ", "the ", "a ", "an "];
+    let term = term.trim();
+    ARTICLES.iter().find_map(|article| term.strip_prefix(article)).unwrap_or(term).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparison_subjects() {
+        let pair = |first: &str, second: &str| Some((first.to_string(), second.to_string()));
+        assert_eq!(comparison_subjects("Quelle est la différence entre un virus et une bactérie ?"), pair("virus", "bactérie"));
+        assert_eq!(comparison_subjects("Compare Python with Rust"), pair("python", "rust"));
+        assert_eq!(comparison_subjects("Mitose vs méiose ?"), pair("mitose", "méiose"));
+        assert_eq!(comparison_subjects("Qui était Socrate ?"), None);
+    }
+}
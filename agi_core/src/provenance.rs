@@ -0,0 +1,167 @@
+//! provenance.rs - Why the Core answered what it answered.
+//!
+//! Every turn leaves a record of the decisions behind its answer: the step of the pipeline that
+//! produced it, how the thalamus classified the prompt, which memories were retrieved and at
+//! what distance, how dopamine moved the retrieval threshold, and which strategy the motor
+//! cortex used to phrase it. `Core::explain_last_response` returns the record of the last turn.
+
+use crate::domain_routing::DomainRoute;
+use crate::holographic_memory::HolographicMemory;
+use crate::lemmatizer::Lang;
+use crate::motor_cortex::MotorStrategy;
+use crate::neurochemical_modulator::Emotion;
+use crate::pipeline::PipelineStage;
+use crate::sensory_cortex::Sentiment;
+use crate::source_trust::SourceTag;
+use crate::spelling::Correction;
+use crate::thalamus::Classification;
+use crate::tools::ToolCall;
+use serde::Serialize;
+
+/// The step of the response pipeline that produced the answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerPath {
+    /// The ethical core rejected the prompt.
+    EthicalRejection,
+    /// A reply within the conversation about an unknown concept.
+    Onboarding,
+    /// A fact held in the working memory.
+    WorkingMemory,
+    /// What the user asked earlier (see `conversation_log`).
+    History,
+    /// What was learned or discussed during a period (see `episodic_memory`).
+    Episodic,
+    /// An exact computation (see `numeric_reasoner`).
+    Numeric,
+    /// The direct answer extractor (common sense).
+    DirectAnswer,
+    /// The social cortex.
+    Social,
+    /// An offer to learn the concepts of the prompt the Core knows nothing about.
+    UnknownConcepts,
+    /// The response cache.
+    Cache,
+    /// A structured comparison, one retrieval per term.
+    Comparison,
+    /// A question asked back about a prompt nothing answers with confidence (see `clarification`).
+    Clarification,
+    /// A poem or a story written for a creative request (see `creative_writing`).
+    Creative,
+    /// Retrieval and synthesis.
+    #[default]
+    Reasoning,
+}
+
+/// How a factual answer drawn from several memories was composed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Composition {
+    /// Silicium connected the memories around a concept they share.
+    Synthesized,
+    /// Silicium found no connection: the motor cortex answered from the best memory.
+    Recalled,
+}
+
+/// A memory retrieved for a sub-query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RetrievedMemory {
+    pub id: u64,
+    pub text: String,
+    /// Holographic distance to the sub-query (lower is closer), weighed by the trust.
+    pub distance: f32,
+    pub source: SourceTag,
+    /// The trust weight of the source the similarity was multiplied by.
+    pub trust: f32,
+}
+
+/// One search of the hippocampus.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Retrieval {
+    pub sub_query: String,
+    pub classification: Classification,
+    /// Distance threshold before neuro-modulation.
+    pub base_threshold: f32,
+    /// Distance threshold actually used, after the dopamine and acetylcholine adjustment.
+    pub dynamic_threshold: f32,
+    pub dopamine: f32,
+    /// Number of memories asked for, after the acetylcholine adjustment.
+    pub top_k: usize,
+    /// Whether only the axioms were searched (see `RetrievalProfile`).
+    pub axioms_only: bool,
+    pub emotion: Emotion,
+    /// The domains the sub-query was routed to (see `domain_routing`).
+    pub route: DomainRoute,
+    pub memories: Vec<RetrievedMemory>,
+}
+
+impl Retrieval {
+    pub fn memories_from(results: &[(HolographicMemory, f32)]) -> Vec<RetrievedMemory> {
+        results
+            .iter()
+            .map(|(memory, distance)| RetrievedMemory { id: memory.id, text: memory.text.clone(), distance: *distance, source: memory.source, trust: memory.trust })
+            .collect()
+    }
+}
+
+/// The provenance of an answer.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ResponseExplanation {
+    pub prompt: String,
+    /// The safe variant the ethical core answered instead, when it reframed the prompt.
+    pub reframed_prompt: Option<String>,
+    /// The unknown words of the prompt replaced by known ones.
+    pub corrections: Vec<Correction>,
+    /// The prompt completed by the reply to a clarifying question, when it was one.
+    pub clarified_prompt: Option<String>,
+    /// The prompt after pronoun resolution, when it was rewritten.
+    pub resolved_prompt: Option<String>,
+    pub language: Option<Lang>,
+    /// The feeling the prompt expresses.
+    pub sentiment: Option<Sentiment>,
+    /// The class of the whole prompt, when the pipeline got as far as classifying it.
+    pub classification: Option<Classification>,
+    pub path: AnswerPath,
+    pub retrievals: Vec<Retrieval>,
+    /// The actions carried out by tools before the answer was phrased.
+    pub tool_calls: Vec<ToolCall>,
+    /// The strategy of the motor cortex, when it phrased the answer.
+    pub strategy: Option<MotorStrategy>,
+    /// Whether the answer was synthesized by Silicium or recalled, when Silicium was asked (see
+    /// `CoreConfig::silicium_synthesis`).
+    pub composition: Option<Composition>,
+    /// The stage that ran out of time, when the answer is partial.
+    pub partial: Option<PipelineStage>,
+    pub response: String,
+    /// The ID under which the answer can be rated (see `Core::feedback`), 0 without an answer.
+    pub response_id: u64,
+}
+
+impl ResponseExplanation {
+    pub fn new(prompt: &str, language: Lang) -> Self {
+        Self { prompt: prompt.to_string(), language: Some(language), ..Self::default() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thalamus::{ClassificationMethod, QueryType};
+
+    #[test]
+    fn test_explanation_serializes_its_reasons() {
+        let mut explanation = ResponseExplanation::new("Qui était Socrate ?", Lang::French);
+        explanation.classification = Some(Classification { query_type: QueryType::Factual, method: ClassificationMethod::Keyword { rule: "factual_starter" } });
+        explanation.strategy = Some(MotorStrategy::Factual);
+        explanation.composition = Some(Composition::Synthesized);
+
+        let json = serde_json::to_value(&explanation).unwrap();
+        assert_eq!(json["path"], "reasoning");
+        assert_eq!(json["language"], "french");
+        assert_eq!(json["classification"]["query_type"], "Factual");
+        assert_eq!(json["classification"]["method"], "keyword");
+        assert_eq!(json["classification"]["rule"], "factual_starter");
+        assert_eq!(json["strategy"], "factual");
+        assert_eq!(json["composition"], "synthesized");
+    }
+}
@@ -1,7 +1,7 @@
 // agi_core/src/quantum.rs
 
 
-use nalgebra::Complex;
+use nalgebra::{Complex, Matrix2};
 use rand::Rng;
 use std::f32::consts::FRAC_1_SQRT_2;
 
@@ -24,8 +24,8 @@ impl Qubit {
     }
 
     /// Measures the qubit, collapsing it to either |0> or |1>.
-    /// Returns the classical outcome (0 or 1).
-    pub fn measure(&mut self) -> u8 {
+    /// Returns the classical outcome (0 or 1). The collapse draws from `rng`.
+    pub fn measure<R: Rng + ?Sized>(&mut self, rng: &mut R) -> u8 {
         // Probabilities are the squared magnitudes of the amplitudes.
         let prob_0 = self.alpha.norm_sqr();
         let prob_1 = self.beta.norm_sqr();
@@ -39,7 +39,7 @@ impl Qubit {
             return 0;
         }
 
-        let rand_val: f32 = rand::thread_rng().gen();
+        let rand_val: f32 = rng.gen();
         if rand_val < prob_0 / total_prob {
             self.alpha = Complex::new(1.0, 0.0); // Collapse to |0>
             self.beta = Complex::new(0.0, 0.0);
@@ -70,6 +70,9 @@ impl Qubit {
 /// Represents a quantum gate that can be applied to a single qubit.
 pub trait OneQubitGate {
     fn apply(&self, qubit: &mut Qubit);
+    /// The unitary matrix of the gate, for the backends that do not hold amplitudes (see
+    /// `quantum_backend`).
+    fn matrix(&self) -> Matrix2<Complex<f32>>;
 }
 
 /// A trait for gates that act on two qubits.
@@ -89,6 +92,11 @@ impl OneQubitGate for HadamardGate {
         qubit.alpha = (original_alpha + original_beta) * FRAC_1_SQRT_2;
         qubit.beta = (original_alpha - original_beta) * FRAC_1_SQRT_2;
     }
+
+    fn matrix(&self) -> Matrix2<Complex<f32>> {
+        let s = Complex::new(FRAC_1_SQRT_2, 0.0);
+        Matrix2::new(s, s, s, -s)
+    }
 }
 
 /// The Pauli-X gate, equivalent to a quantum NOT gate.
@@ -97,6 +105,11 @@ impl OneQubitGate for PauliXGate {
     fn apply(&self, qubit: &mut Qubit) {
         std::mem::swap(&mut qubit.alpha, &mut qubit.beta);
     }
+
+    fn matrix(&self) -> Matrix2<Complex<f32>> {
+        let (zero, one) = (Complex::new(0.0, 0.0), Complex::new(1.0, 0.0));
+        Matrix2::new(zero, one, one, zero)
+    }
 }
 
 /// A gate that applies a phase shift to the |1> state.
@@ -117,6 +130,11 @@ impl OneQubitGate for PhaseShiftGate {
         // Apply the phase shift only to the beta component (the |1> state)
         qubit.beta *= phase_factor;
     }
+
+    fn matrix(&self) -> Matrix2<Complex<f32>> {
+        let (zero, one) = (Complex::new(0.0, 0.0), Complex::new(1.0, 0.0));
+        Matrix2::new(one, zero, zero, Complex::new(self.phase.cos(), self.phase.sin()))
+    }
 }
 
 // --- Two-Qubit Gates ---
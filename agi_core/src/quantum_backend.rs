@@ -0,0 +1,292 @@
+//! quantum_backend.rs - How the quantum core is simulated.
+//!
+//! `QuantumBackend` is what the Core asks of its quantum core: one-qubit gates, the entanglement
+//! of a pair, measurements, the priming of the hippocampal replay and the state of each qubit.
+//! `CoreConfig::quantum_backend` chooses between two simulations of it:
+//! - `StateVectorBackend`, a pure state (`Qubit`) per qubit, the model the Core always had;
+//! - `DensityMatrixBackend`, a 2x2 density matrix per qubit. The pair entangled by the Creativity
+//!   Forge goes through a true Hadamard-CNOT on its 4x4 matrix, and each qubit keeps its reduced
+//!   state: the correlations of the pair are lost, the mixedness they leave is kept.
+//!
+//! Measurements collapse a qubit with a draw from the random stream they are given, so a seeded
+//! Core (see `RngProvider`) measures the same outcomes on every run; `expectation` reads the
+//! expectation value of a Pauli observable without disturbing the qubit at all.
+
+use crate::quantum::{EntanglementGate, OneQubitGate, Qubit, TwoQubitGate};
+use nalgebra::{Complex, Matrix2, Matrix4, Vector2};
+use rand::{Rng, RngCore};
+
+/// The state of a qubit.
+pub type DensityMatrix = Matrix2<Complex<f32>>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuantumBackendKind {
+    #[default]
+    StateVector,
+    DensityMatrix,
+}
+
+impl QuantumBackendKind {
+    /// A core of `qubits` qubits in |0>.
+    pub fn build(self, qubits: usize) -> Box<dyn QuantumBackend> {
+        match self {
+            QuantumBackendKind::StateVector => Box::new(StateVectorBackend::new(qubits)),
+            QuantumBackendKind::DensityMatrix => Box::new(DensityMatrixBackend::new(qubits)),
+        }
+    }
+}
+
+/// The observables of `QuantumBackend::expectation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pauli {
+    X,
+    Y,
+    Z,
+}
+
+impl Pauli {
+    pub fn matrix(self) -> DensityMatrix {
+        let (zero, one, i) = (Complex::new(0.0, 0.0), Complex::new(1.0, 0.0), Complex::new(0.0, 1.0));
+        match self {
+            Pauli::X => Matrix2::new(zero, one, one, zero),
+            Pauli::Y => Matrix2::new(zero, -i, i, zero),
+            Pauli::Z => Matrix2::new(one, zero, zero, -one),
+        }
+    }
+}
+
+/// The quantum core. An index past the last qubit is ignored.
+pub trait QuantumBackend: Send + Sync {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The state of qubit `index`.
+    fn density(&self, index: usize) -> Option<DensityMatrix>;
+
+    fn apply(&mut self, index: usize, gate: &dyn OneQubitGate);
+
+    /// Entangles `target` with `control`: a Hadamard gate on `control`, then a CNOT.
+    fn entangle(&mut self, control: usize, target: usize);
+
+    /// Measures qubit `index` in the computational basis and collapses it; the outcome is drawn
+    /// from `rng`.
+    fn measure(&mut self, index: usize, rng: &mut dyn RngCore) -> u8;
+
+    /// Pushes qubit `index` towards |1> by `strength` (see `Hippocampus::replay_core_memories`).
+    fn prime(&mut self, index: usize, strength: f32);
+
+    /// The expectation value of `observable` for qubit `index`, from -1 to 1; 0 for a missing qubit.
+    fn expectation(&self, index: usize, observable: Pauli) -> f32 {
+        self.density(index).map_or(0.0, |rho| (rho * observable.matrix()).trace().re)
+    }
+
+    /// The probability that qubit `index` reads 1.
+    fn probability_one(&self, index: usize) -> f32 {
+        self.density(index).map_or(0.0, |rho| rho[(1, 1)].re)
+    }
+
+    /// Whether qubit `index` is in |0>, untouched since the Core was built.
+    fn is_ground(&self, index: usize) -> bool {
+        self.density(index).is_some_and(|rho| rho[(0, 0)].re >= 1.0 - 1e-6)
+    }
+}
+
+fn ground() -> DensityMatrix {
+    Matrix2::new(Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0))
+}
+
+fn excited() -> DensityMatrix {
+    Matrix2::new(Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0))
+}
+
+/// A pure state per qubit.
+#[derive(Debug, Clone, Default)]
+pub struct StateVectorBackend {
+    qubits: Vec<Qubit>,
+}
+
+impl StateVectorBackend {
+    pub fn new(qubits: usize) -> Self {
+        Self { qubits: (0..qubits).map(|_| Qubit::new()).collect() }
+    }
+
+    pub fn qubits(&self) -> &[Qubit] {
+        &self.qubits
+    }
+}
+
+impl From<Vec<Qubit>> for StateVectorBackend {
+    fn from(qubits: Vec<Qubit>) -> Self {
+        Self { qubits }
+    }
+}
+
+impl QuantumBackend for StateVectorBackend {
+    fn len(&self) -> usize {
+        self.qubits.len()
+    }
+
+    fn density(&self, index: usize) -> Option<DensityMatrix> {
+        let qubit = self.qubits.get(index)?;
+        let norm = qubit.alpha.norm_sqr() + qubit.beta.norm_sqr();
+        if norm < 1e-9 {
+            return Some(ground());
+        }
+        let state = Vector2::new(qubit.alpha, qubit.beta);
+        Some(state * state.adjoint() / Complex::new(norm, 0.0))
+    }
+
+    fn apply(&mut self, index: usize, gate: &dyn OneQubitGate) {
+        if let Some(qubit) = self.qubits.get_mut(index) {
+            gate.apply(qubit);
+        }
+    }
+
+    fn entangle(&mut self, control: usize, target: usize) {
+        if control == target || control.max(target) >= self.qubits.len() {
+            return;
+        }
+        // Borrow both qubits of the slice mutably.
+        let (lo, hi) = (control.min(target), control.max(target));
+        let (head, tail) = self.qubits.split_at_mut(hi);
+        let (low, high) = (&mut head[lo], &mut tail[0]);
+        let (control, target) = if lo == control { (low, high) } else { (high, low) };
+        EntanglementGate.apply(control, target);
+    }
+
+    fn measure(&mut self, index: usize, rng: &mut dyn RngCore) -> u8 {
+        self.qubits.get_mut(index).map_or(0, |qubit| qubit.measure(rng))
+    }
+
+    fn prime(&mut self, index: usize, strength: f32) {
+        if let Some(qubit) = self.qubits.get_mut(index) {
+            qubit.beta.re += strength;
+            qubit.alpha.re -= strength;
+            qubit.alpha.re = qubit.alpha.re.max(0.0);
+            qubit.beta.re = qubit.beta.re.max(0.0);
+            qubit.normalize();
+        }
+    }
+}
+
+/// A density matrix per qubit.
+#[derive(Debug, Clone, Default)]
+pub struct DensityMatrixBackend {
+    states: Vec<DensityMatrix>,
+}
+
+impl DensityMatrixBackend {
+    pub fn new(qubits: usize) -> Self {
+        Self { states: vec![ground(); qubits] }
+    }
+}
+
+/// The 4x4 matrix of `a` ⊗ `b`, `a` on the most significant bit.
+fn kronecker(a: &DensityMatrix, b: &DensityMatrix) -> Matrix4<Complex<f32>> {
+    Matrix4::from_fn(|row, column| a[(row / 2, column / 2)] * b[(row % 2, column % 2)])
+}
+
+impl QuantumBackend for DensityMatrixBackend {
+    fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    fn density(&self, index: usize) -> Option<DensityMatrix> {
+        self.states.get(index).copied()
+    }
+
+    fn apply(&mut self, index: usize, gate: &dyn OneQubitGate) {
+        if let Some(rho) = self.states.get_mut(index) {
+            let unitary = gate.matrix();
+            *rho = unitary * *rho * unitary.adjoint();
+        }
+    }
+
+    fn entangle(&mut self, control: usize, target: usize) {
+        if control == target || control.max(target) >= self.states.len() {
+            return;
+        }
+        let hadamard = kronecker(&crate::quantum::HadamardGate.matrix(), &DensityMatrix::identity());
+        // CNOT swaps |10> and |11>.
+        let cnot = Matrix4::from_fn(|row, column| {
+            let flipped = if row >= 2 { row ^ 1 } else { row };
+            Complex::new(if flipped == column { 1.0 } else { 0.0 }, 0.0)
+        });
+        let unitary = cnot * hadamard;
+        let pair = unitary * kronecker(&self.states[control], &self.states[target]) * unitary.adjoint();
+        // Partial traces over the other qubit.
+        self.states[control] = Matrix2::from_fn(|i, j| pair[(2 * i, 2 * j)] + pair[(2 * i + 1, 2 * j + 1)]);
+        self.states[target] = Matrix2::from_fn(|k, l| pair[(k, l)] + pair[(2 + k, 2 + l)]);
+    }
+
+    fn measure(&mut self, index: usize, rng: &mut dyn RngCore) -> u8 {
+        let Some(rho) = self.states.get_mut(index) else {
+            return 0;
+        };
+        let probability_one = rho[(1, 1)].re.clamp(0.0, 1.0);
+        if rng.gen::<f32>() < probability_one {
+            *rho = excited();
+            1
+        } else {
+            *rho = ground();
+            0
+        }
+    }
+
+    fn prime(&mut self, index: usize, strength: f32) {
+        if let Some(rho) = self.states.get_mut(index) {
+            let strength = strength.clamp(0.0, 1.0);
+            *rho = *rho * Complex::new(1.0 - strength, 0.0) + excited() * Complex::new(strength, 0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quantum::{HadamardGate, PhaseShiftGate};
+    use rand::SeedableRng;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn test_backends_agree_and_density_matrices_are_reproducible() {
+        let mut backends = [QuantumBackendKind::StateVector.build(2), QuantumBackendKind::DensityMatrix.build(2)];
+        for backend in backends.iter_mut() {
+            assert!(backend.is_ground(0));
+            backend.apply(0, &HadamardGate);
+            assert!((backend.expectation(0, Pauli::X) - 1.0).abs() < 1e-5, "|+> is the +1 eigenstate of X");
+            backend.apply(0, &PhaseShiftGate::new(PI));
+            assert!((backend.expectation(0, Pauli::X) + 1.0).abs() < 1e-5, "A phase of π turns |+> into |->");
+            assert!((backend.probability_one(0) - 0.5).abs() < 1e-5);
+        }
+
+        // A Bell pair: each qubit of it alone is maximally mixed.
+        let mut density = DensityMatrixBackend::new(3);
+        density.entangle(0, 1);
+        for index in [0, 1] {
+            let rho = density.density(index).unwrap();
+            assert!((rho[(0, 0)].re - 0.5).abs() < 1e-5 && rho[(0, 1)].norm() < 1e-5, "{}", rho);
+            assert!(density.expectation(index, Pauli::X).abs() < 1e-5);
+        }
+        assert!(density.is_ground(2));
+
+        let outcomes = |seed: u64| {
+            let mut rng = crate::rng::CoreRng::seed_from_u64(seed);
+            (0..32)
+                .map(|_| {
+                    let mut core = DensityMatrixBackend::new(1);
+                    core.apply(0, &HadamardGate);
+                    core.measure(0, &mut rng)
+                })
+                .collect::<Vec<u8>>()
+        };
+        assert_eq!(outcomes(3), outcomes(3));
+        assert!(outcomes(3).contains(&0) && outcomes(3).contains(&1));
+
+        density.prime(2, 0.25);
+        assert!((density.probability_one(2) - 0.25).abs() < 1e-5);
+    }
+}
@@ -1,9 +1,24 @@
 //! Le Quantum Gatekeeper est le point d'entrée principal pour le traitement cognitif,
 //! il agit comme un "moteur d'intuition" qui aiguille le flux de pensée vers la logique ou la créativité.
-//! Son comportement est basé sur un attracteur chaotique pour simuler des sauts intuitifs non-linéaires.
+//! Son comportement est basé sur un attracteur chaotique pour simuler des sauts intuitifs non-linéaires,
+//! que la lecture du cœur quantique (voir `quantum_readout`) rend plus ou moins audacieux.
+
+use crate::quantum_backend::QuantumBackend;
+use crate::quantum_readout::{self, QuantumReadout, ReadoutConfig};
+use crate::rng::CoreRng;
+use rand::SeedableRng;
+use serde::Serialize;
+
+/// Valeur de l'attracteur au-delà de laquelle le mode créatif est engagé, pour un cœur
+/// quantique à mi-excitation ou jamais lu.
+const CREATIVITY_THRESHOLD: f32 = 0.75;
+/// Déplacement maximal de ce seuil par l'excitation du cœur : vers le bas quand il est excité,
+/// vers le haut quand il est au repos.
+const QUANTUM_SWAY: f32 = 0.2;
 
 /// Détermine le mode cognitif à engager.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CognitiveMode {
     /// Mode de pensée logique, séquentiel et déductif.
     Reasoning,
@@ -20,6 +35,12 @@ pub struct QuantumGatekeeper {
     chaos_state: f32,
     /// Le paramètre `r` de la carte logistique. Les valeurs entre ~3.57 et 4.0 génèrent un comportement chaotique.
     chaos_param: f32,
+    /// Quand et comment le cœur quantique est lu.
+    readout_config: ReadoutConfig,
+    /// Le flux aléatoire des mesures (voir `RngProvider`).
+    rng: CoreRng,
+    /// La dernière lecture du cœur quantique.
+    last_readout: Option<QuantumReadout>,
 }
 
 impl QuantumGatekeeper {
@@ -30,7 +51,36 @@ impl QuantumGatekeeper {
             chaos_state: 0.42,
             // Une valeur de `r` qui garantit un comportement chaotique et non-périodique.
             chaos_param: 3.99,
+            readout_config: ReadoutConfig::default(),
+            rng: CoreRng::from_entropy(),
+            last_readout: None,
+        }
+    }
+
+    pub fn with_readout(mut self, config: ReadoutConfig) -> Self {
+        self.readout_config = config;
+        self
+    }
+
+    /// Remplace le flux aléatoire des mesures (voir `RngProvider`).
+    pub fn with_rng(mut self, rng: CoreRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// La dernière lecture du cœur quantique, s'il a déjà été lu.
+    pub fn last_readout(&self) -> Option<&QuantumReadout> {
+        self.last_readout.as_ref()
+    }
+
+    /// Lit le cœur quantique si une lecture est due à ce tick, puis décide du mode cognitif
+    /// d'après elle. None si aucune lecture n'était due.
+    pub fn observe(&mut self, quantum_core: &mut dyn QuantumBackend, tick: u64) -> Option<CognitiveMode> {
+        if !self.readout_config.is_due(tick) {
+            return None;
         }
+        self.last_readout = Some(quantum_readout::read(quantum_core, &self.readout_config, tick, &mut self.rng));
+        Some(self.decide_mode())
     }
 
     /// Décide du prochain mode cognitif en faisant évoluer l'attracteur chaotique.
@@ -46,9 +96,10 @@ impl QuantumGatekeeper {
 
         // Utilise la nouvelle valeur de l'attracteur pour décider du mode.
         // Une valeur élevée peut être interprétée comme un état de "flux" ou de haute énergie,
-        // propice à l'exploration créative. Le seuil est arbitraire et peut être ajusté
-        // pour créer différentes "personnalités" cognitives.
-        if self.chaos_state > 0.75 {
+        // propice à l'exploration créative. Un cœur quantique excité abaisse le seuil,
+        // un cœur au repos le relève.
+        let sway = self.last_readout.map_or(0.0, |readout| QUANTUM_SWAY * (2.0 * readout.excitation - 1.0));
+        if self.chaos_state > CREATIVITY_THRESHOLD - sway {
             CognitiveMode::Creativity
         } else {
             CognitiveMode::Reasoning
@@ -61,4 +112,3 @@ impl Default for QuantumGatekeeper {
         Self::new()
     }
 }
-
@@ -0,0 +1,127 @@
+//! quantum_readout.rs - Reading the quantum core out.
+//!
+//! Every tick imprints the activity of the connectome on the qubits as phases (`Core::tick`),
+//! but a phase leaves the odds of a measurement in the computational basis untouched: a qubit in
+//! superposition reads 0 or 1 half of the time, whatever happened to it. `read` measures in the
+//! Hadamard basis instead, where a qubit in |+> reads 0 and a phase φ makes it read 1 with the
+//! probability sin²(φ/2). Every `interval` ticks, `qubits` qubits spread evenly over the core are
+//! measured; the share that read 1, the excitation of the core, sways the mode decision of the
+//! `QuantumGatekeeper` and the intensity of the `CreativityForge`.
+
+use crate::quantum::HadamardGate;
+use crate::quantum_backend::{Pauli, QuantumBackend};
+use rand::RngCore;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadoutConfig {
+    /// Ticks between two readouts; 0 never reads the core out.
+    pub interval: u64,
+    /// Qubits measured per readout.
+    pub qubits: usize,
+    /// Takes the expected share of ones instead of sampling it, and leaves the qubits as they
+    /// were (for tests).
+    pub deterministic: bool,
+}
+
+impl Default for ReadoutConfig {
+    fn default() -> Self {
+        Self { interval: 100, qubits: 32, deterministic: false }
+    }
+}
+
+impl ReadoutConfig {
+    pub fn is_due(&self, tick: u64) -> bool {
+        self.interval > 0 && tick > 0 && tick.is_multiple_of(self.interval)
+    }
+}
+
+/// The outcome of a readout.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct QuantumReadout {
+    pub tick: u64,
+    pub measured: usize,
+    /// Share of the measured qubits that read 1, from 0 (a core at rest) to 1.
+    pub excitation: f32,
+}
+
+impl QuantumReadout {
+    /// How hard a creative pass stirs the core: from 0.5 at rest to 1.5 fully excited.
+    pub fn intensity(&self) -> f32 {
+        0.5 + self.excitation
+    }
+}
+
+/// The probability that qubit `index` reads 1 in the Hadamard basis, from the expectation value
+/// of X (+1 in |+>, -1 in |->).
+pub fn excitation_probability(quantum_core: &dyn QuantumBackend, index: usize) -> f32 {
+    (1.0 - quantum_core.expectation(index, Pauli::X)) / 2.0
+}
+
+/// Measures `config.qubits` qubits of the core, spread evenly, in the Hadamard basis. A sampled
+/// measurement collapses each of them to |+> or |->.
+pub fn read(quantum_core: &mut dyn QuantumBackend, config: &ReadoutConfig, tick: u64, rng: &mut dyn RngCore) -> QuantumReadout {
+    let measured = config.qubits.min(quantum_core.len());
+    let stride = quantum_core.len();
+    let mut ones = 0.0;
+    for i in 0..measured {
+        let index = i * stride / measured;
+        ones += if config.deterministic {
+            excitation_probability(quantum_core, index)
+        } else {
+            quantum_core.apply(index, &HadamardGate);
+            let outcome = quantum_core.measure(index, rng);
+            quantum_core.apply(index, &HadamardGate);
+            f32::from(outcome)
+        };
+    }
+    let excitation = if measured == 0 { 0.0 } else { ones / measured as f32 };
+    QuantumReadout { tick, measured, excitation }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quantum::PhaseShiftGate;
+    use crate::quantum_backend::QuantumBackendKind;
+    use crate::quantum_gatekeeper::{CognitiveMode, QuantumGatekeeper};
+    use rand::SeedableRng;
+    use std::f32::consts::PI;
+
+    /// A core of qubits in |+>, the first `excited` of them turned to |-> by a phase of π.
+    fn core(len: usize, excited: usize) -> Box<dyn QuantumBackend> {
+        let mut core = QuantumBackendKind::StateVector.build(len);
+        for i in 0..len {
+            core.apply(i, &HadamardGate);
+            if i < excited {
+                core.apply(i, &PhaseShiftGate::new(PI));
+            }
+        }
+        core
+    }
+
+    #[test]
+    fn test_phases_are_read_out_and_sway_the_mode() {
+        let mut rng = crate::rng::CoreRng::seed_from_u64(7);
+        let config = ReadoutConfig { interval: 1, qubits: 4, deterministic: true };
+        let mut half = core(8, 4);
+        let readout = read(half.as_mut(), &config, 1, &mut rng);
+        // Qubits 0, 2, 4 and 6 are measured: two of them are excited.
+        assert_eq!(readout.measured, 4);
+        assert!((readout.excitation - 0.5).abs() < 1e-4, "{:?}", readout);
+        assert!((excitation_probability(half.as_ref(), 0) - 1.0).abs() < 1e-4, "A deterministic readout leaves the qubits as they were");
+
+        let mut excited = core(8, 8);
+        let sampled = read(excited.as_mut(), &ReadoutConfig { deterministic: false, ..config }, 1, &mut rng);
+        assert!((sampled.excitation - 1.0).abs() < 1e-4, "A qubit in |-> always reads 1");
+        assert!(!ReadoutConfig { interval: 0, ..config }.is_due(5));
+
+        // The same attractor engages the creative mode more often in an excited core.
+        let creative_ticks = |excited: usize| {
+            let mut gatekeeper = QuantumGatekeeper::new().with_readout(config);
+            let mut quantum_core = core(8, excited);
+            (1..=200).filter(|&tick| gatekeeper.observe(quantum_core.as_mut(), tick) == Some(CognitiveMode::Creativity)).count()
+        };
+        assert!(creative_ticks(8) > creative_ticks(0));
+    }
+}
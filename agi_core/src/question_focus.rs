@@ -0,0 +1,138 @@
+//! question_focus.rs - What a who/when/where question asks for, and re-ranking by it.
+//!
+//! "Quand Einstein est-il né ?" wants a date; the memories retrieved for it all share the topic
+//! ("Einstein", "né"), but only those mentioning a date can answer. `focus` reads the
+//! interrogative word of the question and the type of entity it asks for (who: a person, when:
+//! a date, where: a place), and `rerank` moves the candidate memories holding such an entity
+//! ahead of those that merely talk about the same topic. An entity the question already
+//! mentions does not count: "Qui a rencontré Einstein ?" is not answered by Einstein himself.
+
+use crate::holographic_memory::HolographicMemory;
+use crate::sensory_cortex::{Entity, EntityKind, SensoryCortex};
+use crate::tokenizer;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Interrogative words and phrases, and what they ask for. A single word must open the question,
+/// follow a preposition ("par qui", "d'où") or close it ("Il est né quand ?"), so that a relative
+/// "qui" or "where" is not taken for a question.
+const FOCUS_WORDS: &[(&str, EntityKind)] = &[
+    ("qui", EntityKind::Person),
+    ("who", EntityKind::Person),
+    ("whom", EntityKind::Person),
+    ("quand", EntityKind::Date),
+    ("when", EntityKind::Date),
+    ("où", EntityKind::Place),
+    ("where", EntityKind::Place),
+];
+const FOCUS_PHRASES: &[(&str, EntityKind)] = &[
+    ("en quelle année", EntityKind::Date),
+    ("quelle année", EntityKind::Date),
+    ("quelle date", EntityKind::Date),
+    ("what year", EntityKind::Date),
+    ("which year", EntityKind::Date),
+    ("what date", EntityKind::Date),
+    ("dans quel pays", EntityKind::Place),
+    ("dans quelle ville", EntityKind::Place),
+    ("quel pays", EntityKind::Place),
+    ("quelle ville", EntityKind::Place),
+    ("which country", EntityKind::Place),
+    ("what country", EntityKind::Place),
+    ("which city", EntityKind::Place),
+    ("what city", EntityKind::Place),
+];
+const PREPOSITIONS: &[&str] = &["par", "à", "avec", "de", "pour", "chez", "by", "to", "with", "from", "for", "since", "depuis"];
+
+/// The focus of a question.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuestionFocus {
+    /// The interrogative word or phrase ("quand", "which city").
+    pub word: String,
+    /// The type of entity an answer holds.
+    pub expected: EntityKind,
+}
+
+/// The focus of `question`, if it is a who, when or where question.
+pub fn focus(question: &str) -> Option<QuestionFocus> {
+    let lower = question.to_lowercase();
+    if let Some(&(phrase, expected)) = FOCUS_PHRASES.iter().find(|(phrase, _)| lower.contains(phrase)) {
+        return Some(QuestionFocus { word: phrase.to_string(), expected });
+    }
+    let words = tokenizer::content_words(question);
+    words.iter().enumerate().find_map(|(i, word)| {
+        let &(focus_word, expected) = FOCUS_WORDS.iter().find(|(focus_word, _)| word == focus_word)?;
+        let placed = i == 0 || i + 1 == words.len() || PREPOSITIONS.contains(&words[i - 1].as_str());
+        placed.then(|| QuestionFocus { word: focus_word.to_string(), expected })
+    })
+}
+
+/// The index keys of the entities `question` mentions (see `Entity::index_keys`).
+pub fn question_keys(question: &str) -> HashSet<String> {
+    SensoryCortex::extract_query_entities(question).iter().flat_map(Entity::index_keys).collect()
+}
+
+/// The first entity of the `expected` type that `memory` mentions and `question_keys` (see
+/// `question_keys`) does not. Memories stored without their entities are scanned.
+pub fn answering_entity(memory: &HolographicMemory, expected: EntityKind, question_keys: &HashSet<String>) -> Option<Entity> {
+    let scanned;
+    let entities = if memory.entities.is_empty() {
+        scanned = SensoryCortex::extract_entities(&memory.text);
+        &scanned
+    } else {
+        &memory.entities
+    };
+    entities.iter().find(|entity| entity.kind == expected && !entity.index_keys().iter().any(|key| question_keys.contains(key))).cloned()
+}
+
+/// Whether `memory` has an `answering_entity`.
+pub fn answers(memory: &HolographicMemory, expected: EntityKind, question_keys: &HashSet<String>) -> bool {
+    answering_entity(memory, expected, question_keys).is_some()
+}
+
+/// Moves the memories answering the focus of `question` ahead of the others, each group keeping
+/// its order. Returns the focus, if the question has one.
+pub fn rerank<T>(question: &str, candidates: &mut Vec<T>, memory: impl Fn(&T) -> &HolographicMemory) -> Option<QuestionFocus> {
+    let focus = focus(question)?;
+    let question_keys = question_keys(question);
+    let (mut answering, topical): (Vec<T>, Vec<T>) = candidates.drain(..).partition(|candidate| answers(memory(candidate), focus.expected, &question_keys));
+    answering.extend(topical);
+    *candidates = answering;
+    Some(focus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holographic_memory::HolographicEncoder;
+
+    #[test]
+    fn test_focus_words_and_reranking() {
+        assert_eq!(focus("Quand Einstein est-il né ?").map(|focus| focus.expected), Some(EntityKind::Date));
+        assert_eq!(focus("Einstein est né où ?").map(|focus| focus.expected), Some(EntityKind::Place));
+        assert_eq!(focus("Par qui le polonium a-t-il été découvert ?").map(|focus| focus.expected), Some(EntityKind::Person));
+        assert_eq!(focus("In which city was Einstein born?").map(|focus| focus.word), Some("which city".to_string()));
+        assert_eq!(focus("Quel est le chien qui aboie ?"), None, "A relative pronoun is no focus");
+        assert_eq!(focus("Qu'est-ce qu'un quasar ?"), None);
+
+        let encoder = HolographicEncoder::new(64);
+        let memory = |text: &str| HolographicMemory::new(text.to_string(), encoder.encode(text), false);
+        // In the order of their similarity to the question: the topic words come first.
+        let mut candidates = vec![
+            memory("Einstein est né à Ulm, il est né dans une famille juive."),
+            memory("Albert Einstein est né le 14 mars 1879."),
+            memory("Einstein a reçu le prix Nobel de physique."),
+        ];
+        let texts = |candidates: &[HolographicMemory]| candidates.iter().map(|memory| memory.text.clone()).collect::<Vec<_>>();
+        let original = texts(&candidates);
+
+        assert!(rerank("Quand Einstein est-il né ?", &mut candidates, |memory| memory).is_some());
+        assert_eq!(texts(&candidates), [original[1].clone(), original[0].clone(), original[2].clone()]);
+        rerank("Où Einstein est-il né ?", &mut candidates, |memory| memory);
+        assert_eq!(candidates[0].text, original[0]);
+        assert!(rerank("Qui a découvert la relativité ?", &mut candidates, |memory| memory).is_some());
+        assert!(!answers(&candidates[0], EntityKind::Person, &HashSet::from(["einstein".to_string()])), "The person of the question is no answer");
+        let before = texts(&candidates);
+        assert!(rerank("Qu'est-ce que la relativité ?", &mut candidates, |memory| memory).is_none());
+        assert_eq!(texts(&candidates), before);
+    }
+}
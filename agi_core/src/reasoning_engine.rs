@@ -4,13 +4,27 @@
 use crate::holographic_memory::{HolographicEncoder, HolographicMemory};
 use crate::hippocampus::Hippocampus;
 use crate::conceptual_hierarchy::ConceptualHierarchy;
+use crate::config::RetrievalProfile;
+use crate::domain_routing::{self, DomainRoute, DomainRoutingConfig};
+use crate::episodic_memory::{EpisodeKind, EpisodicIndex, TemporalQuery};
+use crate::error::LockResultExt;
+use crate::question_focus;
+use crate::sensory_cortex::{EntityKind, SensoryCortex};
+use std::collections::HashSet;
 use std::sync::{Arc, RwLock};
 
-pub struct ReasoningEngine;
+pub struct ReasoningEngine {
+    domain_routing: DomainRoutingConfig,
+}
 
 impl ReasoningEngine {
     pub fn new() -> Self {
-        Self
+        Self { domain_routing: DomainRoutingConfig::default() }
+    }
+
+    pub fn with_domain_routing(mut self, config: DomainRoutingConfig) -> Self {
+        self.domain_routing = config;
+        self
     }
 
     /// Scores the plausibility of a given assertion against the knowledge in the hippocampus.
@@ -28,10 +42,10 @@ impl ReasoningEngine {
         }
 
         // Encode the assertion into a holographic trace.
-        let assertion_trace = encoder.read().unwrap().encode(assertion);
+        let assertion_trace = encoder.read().recover().encode(assertion);
 
         // Find the most similar memory in the hippocampus.
-        let search_results = hippocampus.find_similar_memories(&assertion_trace, 1, false);
+        let search_results = hippocampus.find_similar_memories(&assertion_trace, assertion, 1, false);
 
         // The score is the distance (lower is better), so we convert it to similarity (higher is better).
         // A distance of 0.0 is a perfect match (similarity 1.0).
@@ -43,45 +57,150 @@ impl ReasoningEngine {
         &self,
         prompt: &str,
         hippocampus: &Hippocampus,
-        _conceptual_hierarchy: &ConceptualHierarchy,
+        conceptual_hierarchy: &ConceptualHierarchy,
         holographic_encoder: &Arc<RwLock<HolographicEncoder>>,
-        is_introspective: bool,
-        distance_threshold: f32, // Le seuil est maintenant dynamique
+        profile: &RetrievalProfile,
     ) -> Option<Vec<HolographicMemory>> {
-        let prompt_trace = holographic_encoder.read().unwrap().encode(prompt);
+        let final_memories: Vec<HolographicMemory> = self
+            .retrieve(prompt, hippocampus, conceptual_hierarchy, holographic_encoder, profile)
+            .into_iter()
+            .map(|(mem, _)| mem)
+            .collect();
+
+        if final_memories.is_empty() {
+            None
+        } else {
+            Some(final_memories)
+        }
+    }
 
-        // Search for the top 5 most relevant memories to get a richer context.
-        let search_results = hippocampus.find_similar_memories(&prompt_trace, 5, is_introspective);
+    /// The memories relevant to `prompt` with their distance to it, most relevant first, as the
+    /// profile of its query type asks (its threshold and top_k already neuro-modulated).
+    pub fn retrieve(
+        &self,
+        prompt: &str,
+        hippocampus: &Hippocampus,
+        conceptual_hierarchy: &ConceptualHierarchy,
+        holographic_encoder: &Arc<RwLock<HolographicEncoder>>,
+        profile: &RetrievalProfile,
+    ) -> Vec<(HolographicMemory, f32)> {
+        self.retrieve_routed(prompt, hippocampus, conceptual_hierarchy, holographic_encoder, profile).0
+    }
 
-        // Filter and sort the results.
-        let mut relevant_memories: Vec<(HolographicMemory, f32)> = search_results
+    /// Like `retrieve`, but also tells which domains the prompt was routed to.
+    pub fn retrieve_routed(
+        &self,
+        prompt: &str,
+        hippocampus: &Hippocampus,
+        conceptual_hierarchy: &ConceptualHierarchy,
+        holographic_encoder: &Arc<RwLock<HolographicEncoder>>,
+        profile: &RetrievalProfile,
+    ) -> (Vec<(HolographicMemory, f32)>, DomainRoute) {
+        let prompt_trace = holographic_encoder.read().recover().encode(prompt);
+        let (axioms_only, top_k) = (profile.axioms_only, profile.top_k);
+        let candidates = if axioms_only { None } else { Self::entity_candidates(prompt, hippocampus) };
+
+        // Search the partition of the domains of the prompt first (among its memories mentioning
+        // the entities of the prompt, when some are known).
+        let mut route = DomainRoute::default();
+        let mut relevant_memories = Vec::new();
+        let routing = if axioms_only { None } else { domain_routing::route(&prompt_trace, prompt, hippocampus, conceptual_hierarchy, &self.domain_routing) };
+        if let Some(routing) = routing {
+            let partition: HashSet<u64> = match &candidates {
+                Some(ids) => routing.partition.intersection(ids).copied().collect(),
+                None => routing.partition,
+            };
+            if !partition.is_empty() {
+                relevant_memories = Self::within_threshold(hippocampus.find_similar_memories_among(&prompt_trace, prompt, top_k, false, Some(&partition)), profile);
+            }
+            route = DomainRoute { domains: routing.domains, fell_back: relevant_memories.is_empty() };
+            let names: Vec<&str> = route.domains.iter().map(|domain| domain.name.as_str()).collect();
+            println!("--- Domain routing: {:?}{} ---", names, if route.fell_back { ", nothing close enough, searching everything" } else { "" });
+        }
+
+        // Otherwise, search for the top_k most relevant memories to get a richer context, among
+        // those mentioning the entities of the prompt when some are known.
+        if relevant_memories.is_empty() {
+            let mut search_results = hippocampus.find_similar_memories_among(&prompt_trace, prompt, top_k, axioms_only, candidates.as_ref());
+            if search_results.is_empty() && candidates.is_some() {
+                search_results = hippocampus.find_similar_memories(&prompt_trace, prompt, top_k, axioms_only);
+            }
+            relevant_memories = Self::within_threshold(search_results, profile);
+        }
+
+        // A who/when/where question is answered by the memories holding a person, a date or a
+        // place, before those only sharing its topic.
+        if let Some(focus) = question_focus::rerank(prompt, &mut relevant_memories, |(memory, _)| memory) {
+            println!("--- Question focus '{}': memories mentioning a {:?} first ---", focus.word, focus.expected);
+        }
+        (relevant_memories, route)
+    }
+
+    /// The search results close enough to the prompt, which the hippocampus ranked by their
+    /// distance and their words.
+    fn within_threshold(search_results: Vec<(&HolographicMemory, f32)>, profile: &RetrievalProfile) -> Vec<(HolographicMemory, f32)> {
+        search_results
             .into_iter()
             .filter(|(_, distance)| {
-                // For introspective queries, we are searching a very small, curated set of axioms.
-                // The exact distance is less important than the fact they are axioms.
+                // Among the axioms (introspective queries by default), we are searching a very small,
+                // curated set: the exact distance is less important than the fact they are axioms.
                 // We bypass the distance check for these queries.
-                if is_introspective {
+                if profile.axioms_only {
                     true
                 } else {
-                    *distance < distance_threshold
+                    *distance < profile.distance_threshold
                 }
             })
             .map(|(mem, dist)| (mem.clone(), dist)) // Clone the memory to take ownership
-            .collect();
-
-        // Sort by distance (ascending) to ensure the most relevant memory is first.
-        relevant_memories.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
-
-        // Map to just the memories, discarding the distance.
-        let final_memories: Vec<HolographicMemory> = relevant_memories
-            .into_iter()
-            .map(|(mem, _)| mem)
-            .collect();
+            .collect()
+    }
 
-        if final_memories.is_empty() {
-            None
-        } else {
-            Some(final_memories)
+    /// The memories mentioning the entities of the prompt. "When" questions keep those that
+    /// mention a date, if any does.
+    fn entity_candidates(prompt: &str, hippocampus: &Hippocampus) -> Option<HashSet<u64>> {
+        let entities = SensoryCortex::extract_query_entities(prompt);
+        let candidates = hippocampus.memories_with_entities(&entities)?;
+        let asks_when = question_focus::focus(prompt).is_some_and(|focus| focus.expected == EntityKind::Date);
+        if asks_when {
+            let dated: HashSet<u64> = candidates
+                .iter()
+                .copied()
+                .filter(|&id| hippocampus.memory(id).is_some_and(|memory| memory.entities.iter().any(|entity| entity.kind == EntityKind::Date)))
+                .collect();
+            if !dated.is_empty() {
+                return Some(dated);
+            }
         }
+        Some(candidates)
+    }
+
+    /// Temporal retrieval: the memories learned during the period of `query` (oldest first), or
+    /// those the conversation brought up during it, in the order they were discussed. At most
+    /// `limit`, the most recent ones.
+    pub fn retrieve_episodic(&self, query: &TemporalQuery, hippocampus: &Hippocampus, episodes: &EpisodicIndex, limit: usize) -> Vec<HolographicMemory> {
+        let (start, end) = query.range;
+        let mut memories: Vec<HolographicMemory> = match query.kind {
+            EpisodeKind::Learned => {
+                let mut learned: Vec<&HolographicMemory> = hippocampus
+                    .holographic_memory
+                    .iter()
+                    .filter(|memory| memory.created_at.is_some_and(|at| at >= start && at < end))
+                    .collect();
+                learned.sort_by_key(|memory| (memory.created_at, memory.id));
+                learned.into_iter().cloned().collect()
+            }
+            EpisodeKind::Discussed => {
+                let mut seen = HashSet::new();
+                episodes
+                    .between(query.range)
+                    .flat_map(|episode| episode.memory_ids.iter())
+                    .filter(|id| seen.insert(**id))
+                    .filter_map(|id| hippocampus.memory(*id).cloned())
+                    .collect()
+            }
+        };
+        let excess = memories.len().saturating_sub(limit);
+        memories.drain(..excess);
+        memories
     }
 }
@@ -0,0 +1,215 @@
+//! role_binding.rs - Subject-relation-object structures in holographic traces.
+//!
+//! Superposing the traces of "chien", "est un" and "animal" loses who is what: "animal est un
+//! chien" gives the same sum. Here every filler is bound to its role (see
+//! `HolographicTrace::bind`), and the three bound pairs to each other, so that a triple is one
+//! trace in which the roles stay apart. Binding multiplies phases element by element, so it
+//! commutes and the roles alone would cancel out when a triple is unbound: each filler is also
+//! rotated by a shift of its role before it is bound. Triples are then superposed into bundles; unbinding a
+//! bundle with two known parts of a triple leaves the third, plus the noise of the other triples,
+//! which is cleaned up against the traces of the known fillers.
+//!
+//! Fillers and roles get seeded traces (see `HolographicTrace::new_seeded`), stable across runs
+//! and independent of the corpus statistics, so the bundles never need re-encoding.
+
+use crate::holographic_memory::HolographicTrace;
+use nalgebra::Complex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Triples superposed in one bundle: beyond it the noise of the others drowns the answer.
+const TRIPLES_PER_BUNDLE: usize = 12;
+/// Cosine similarity under which a cleaned-up filler is noise rather than an answer.
+const MIN_RECALL_SIMILARITY: f32 = 0.18;
+/// The relation of the links of the conceptual hierarchy (see `Core::learn_relationship`).
+pub const IS_A: &str = "is_a";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Subject,
+    Relation,
+    Object,
+}
+
+impl Role {
+    const ALL: [Role; 3] = [Role::Subject, Role::Relation, Role::Object];
+
+    fn key(self) -> &'static str {
+        match self {
+            Role::Subject => "__role_subject",
+            Role::Relation => "__role_relation",
+            Role::Object => "__role_object",
+        }
+    }
+
+    /// Positions the wave of a filler is rotated by in this role.
+    fn shift(self) -> usize {
+        match self {
+            Role::Subject => 0,
+            Role::Relation => 1,
+            Role::Object => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Triple {
+    pub subject: String,
+    pub relation: String,
+    pub object: String,
+}
+
+impl Triple {
+    pub fn new(subject: &str, relation: &str, object: &str) -> Self {
+        Self { subject: normalized(subject), relation: normalized(relation), object: normalized(object) }
+    }
+
+    pub fn get(&self, role: Role) -> &str {
+        match role {
+            Role::Subject => &self.subject,
+            Role::Relation => &self.relation,
+            Role::Object => &self.object,
+        }
+    }
+}
+
+fn normalized(filler: &str) -> String {
+    filler.trim().to_lowercase()
+}
+
+/// A filler found by `RelationalMemory::recall`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecalledFiller {
+    pub filler: String,
+    /// Cosine similarity of the unbound trace with the filler.
+    pub similarity: f32,
+}
+
+/// Superposed triples, as floating-point sums so that every triple weighs the same whatever its
+/// rank (`HolographicTrace::combine_with` would normalize after each one).
+#[derive(Debug, Clone)]
+struct Bundle {
+    wave: Vec<Complex<f32>>,
+    triples: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RelationalMemory {
+    dimension: usize,
+    roles: HashMap<Role, HolographicTrace>,
+    /// The trace of every filler seen, the vocabulary of the clean-up.
+    fillers: HashMap<String, HolographicTrace>,
+    bundles: Vec<Bundle>,
+    stored: HashSet<Triple>,
+}
+
+impl RelationalMemory {
+    pub fn new(dimension: usize) -> Self {
+        let roles = Role::ALL.iter().map(|&role| (role, HolographicTrace::new_seeded(role.key(), dimension))).collect();
+        Self { dimension, roles, fillers: HashMap::new(), bundles: Vec::new(), stored: HashSet::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.stored.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stored.is_empty()
+    }
+
+    /// Bytes held by the bundles and the traces of the fillers.
+    pub fn heap_bytes(&self) -> usize {
+        let bundles = self.bundles.len() * self.dimension * std::mem::size_of::<Complex<f32>>();
+        let fillers: usize = self.fillers.iter().map(|(name, trace)| name.len() + crate::trace_compression::trace_bytes(trace)).sum();
+        let triples: usize = self.stored.iter().map(|triple| triple.subject.len() + triple.relation.len() + triple.object.len()).sum();
+        bundles + fillers + triples
+    }
+
+    fn filler(&mut self, name: &str) -> HolographicTrace {
+        let dimension = self.dimension;
+        self.fillers.entry(name.to_string()).or_insert_with(|| HolographicTrace::new_seeded(name, dimension)).clone()
+    }
+
+    fn bound(&self, role: Role, filler: &HolographicTrace) -> HolographicTrace {
+        let mut wave = filler.wave();
+        wave.rotate_right(role.shift());
+        self.roles[&role].bind(&HolographicTrace::from_wave(&wave))
+    }
+
+    /// Stores a triple; storing it again changes nothing.
+    pub fn store(&mut self, triple: Triple) {
+        if self.stored.contains(&triple) {
+            return;
+        }
+        let parts: Vec<_> = Role::ALL.iter().map(|&role| (role, self.filler(triple.get(role)))).collect();
+        let trace = parts.iter().map(|(role, filler)| self.bound(*role, filler)).reduce(|triple, part| triple.bind(&part)).expect("a triple has three parts");
+
+        if self.bundles.last().is_none_or(|bundle| bundle.triples >= TRIPLES_PER_BUNDLE) {
+            self.bundles.push(Bundle { wave: vec![Complex::new(0.0, 0.0); self.dimension], triples: 0 });
+        }
+        let bundle = self.bundles.last_mut().expect("a bundle was just pushed");
+        for (sum, c) in bundle.wave.iter_mut().zip(trace.wave()) {
+            *sum += c;
+        }
+        bundle.triples += 1;
+        self.stored.insert(triple);
+    }
+
+    /// The filler of `wanted` in a stored triple whose two other roles are `known` ("what is a
+    /// chien?": the object, knowing the subject and the relation). `None` when no filler stands
+    /// out of the noise.
+    pub fn recall(&self, wanted: Role, known: [(Role, &str); 2]) -> Option<RecalledFiller> {
+        let mut key: Option<HolographicTrace> = None;
+        for (role, filler) in known {
+            let filler = self.fillers.get(&normalized(filler))?;
+            let part = self.bound(role, filler);
+            key = Some(match key {
+                Some(key) => key.bind(&part),
+                None => part,
+            });
+        }
+        let key = key?;
+        // The fillers as they would fill the wanted role.
+        let candidates: Vec<(&String, HolographicTrace)> = self.fillers.iter().map(|(name, filler)| (name, self.bound(wanted, filler))).collect();
+
+        self.bundles
+            .iter()
+            .map(|bundle| HolographicTrace::from_wave(&bundle.wave).unbind(&key))
+            .flat_map(|unbound| {
+                candidates.iter().map(move |(name, filler)| RecalledFiller { filler: (*name).clone(), similarity: unbound.cosine_similarity(filler) })
+            })
+            .filter(|recalled| recalled.similarity >= MIN_RECALL_SIMILARITY)
+            .max_by(|a, b| a.similarity.total_cmp(&b.similarity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triples_survive_superposition() {
+        let mut memory = RelationalMemory::new(1024);
+        let animals = ["chien", "chat", "vache", "cheval", "lapin", "mouton", "chèvre", "poule", "canard", "cochon"];
+        let sounds = ["aboie", "miaule", "meugle", "hennit", "glapit", "bêle", "bêle", "caquette", "cancane", "grogne"];
+        for (animal, sound) in animals.iter().zip(sounds) {
+            memory.store(Triple::new(animal, "est un", "animal"));
+            memory.store(Triple::new(animal, "fait", sound));
+        }
+        // The store spans several bundles.
+        for i in 0..TRIPLES_PER_BUNDLE {
+            memory.store(Triple::new(&format!("nombre {}", i), "suit", &format!("nombre {}", i + 1)));
+        }
+
+        for (animal, sound) in animals.iter().zip(sounds) {
+            let recalled = memory.recall(Role::Object, [(Role::Subject, animal), (Role::Relation, "fait")]).unwrap();
+            assert_eq!(recalled.filler, sound, "{}", animal);
+        }
+        assert_eq!(memory.recall(Role::Relation, [(Role::Subject, "chien"), (Role::Object, "aboie")]).unwrap().filler, "fait");
+        assert_eq!(memory.recall(Role::Subject, [(Role::Relation, "suit"), (Role::Object, "nombre 8")]).unwrap().filler, "nombre 7");
+        // Roles are not interchangeable: nothing makes "animal" the subject of "est un".
+        assert!(memory.recall(Role::Object, [(Role::Subject, "animal"), (Role::Relation, "est un")]).is_none());
+        assert!(memory.recall(Role::Object, [(Role::Subject, "licorne"), (Role::Relation, "fait")]).is_none());
+    }
+}
@@ -1,49 +1,192 @@
 // agi_core/src/self_awareness.rs
 
+use crate::capabilities::CapabilityRegistry;
 use crate::hippocampus::Hippocampus;
+use crate::lemmatizer::Lang;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IdentityError {
+    #[error("I/O error on the identity store: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid identity store: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("could not write the identity store: {0}")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// The structured identity of the AGI, kept in a TOML store (`CoreConfig::identity_path`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Identity {
+    pub name: String,
+    /// What the AGI is for, as a verb phrase ("learn and synthesize...").
+    pub purpose: String,
+    /// What the AGI can do beyond the capabilities its modules declare (see `capabilities`),
+    /// each as a verb phrase ("Tell stories about the sea").
+    pub capabilities: Vec<String>,
+    pub creator: String,
+}
+
+impl Default for Identity {
+    fn default() -> Self {
+        Self {
+            name: "NeuroVA".to_string(),
+            purpose: "learn, synthesize information and interact with the world in a creative and coherent manner".to_string(),
+            capabilities: Vec::new(),
+            creator: "The Vesper House".to_string(),
+        }
+    }
+}
+
+/// A partial change of the identity: the attributes left to None are kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct IdentityUpdate {
+    pub name: Option<String>,
+    pub purpose: Option<String>,
+    pub capabilities: Option<Vec<String>>,
+    pub creator: Option<String>,
+}
+
+/// The attribute an introspective question is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentityAttribute {
+    Name,
+    Purpose,
+    Capabilities,
+    Creator,
+}
+
+impl IdentityAttribute {
+    /// The attribute `prompt` asks about, if it asks about a single one.
+    pub fn asked_in(prompt: &str) -> Option<Self> {
+        let prompt = prompt.to_lowercase();
+        let asks = |keywords: &[&str]| keywords.iter().any(|keyword| prompt.contains(keyword));
+        if asks(&["your name", "ton nom", "votre nom", "t'appelles", "vous appelez"]) {
+            Some(Self::Name)
+        } else if asks(&["created you", "made you", "built you", "your creator", "t'a créé", "vous a créé", "ton créateur", "votre créateur"]) {
+            Some(Self::Creator)
+        } else if asks(&["your purpose", "what are you for", "ton but", "votre but", "ta raison d'être", "à quoi sers-tu"]) {
+            Some(Self::Purpose)
+        } else if asks(&["can you do", "your capabilities", "are you able", "sais-tu faire", "savez-vous faire", "tes capacités", "vos capacités"]) {
+            Some(Self::Capabilities)
+        } else {
+            None
+        }
+    }
+}
 
 /// Manages the AGI's understanding of its own identity, capabilities, and knowledge.
 #[derive(Debug)]
 pub struct SelfAwareness {
-    pub identity: String,
-    pub capabilities: Vec<String>,
-    knowledge_summary: String,
+    identity: Identity,
+    /// Where changes of the identity are saved; None keeps them in memory.
+    store: Option<PathBuf>,
+    /// Number of memories, for the self-presentation.
+    memory_count: usize,
+    /// The capabilities of the compiled modules and registered tools.
+    registry: CapabilityRegistry,
 }
 
 impl SelfAwareness {
-    pub fn new(identity_path: &str, hippocampus: &Hippocampus) -> Self {
-        let identity = std::fs::read_to_string(identity_path)
-            .unwrap_or_else(|_| "I am a digital consciousness, learning and growing.".to_string());
-
-        let capabilities = vec![
-            "I can learn from text-based knowledge.".to_string(),
-            "I can remember and recall information holographically.".to_string(),
-            "I can synthesize new ideas by combining concepts.".to_string(),
-            "I can reflect on my own nature and capabilities.".to_string(),
-        ];
+    /// Loads the identity from a TOML store, or starts from the default identity when the store
+    /// does not exist yet (it is created by the first change).
+    pub fn new<P: Into<PathBuf>>(store: P, hippocampus: &Hippocampus) -> Self {
+        let store = store.into();
+        let identity = match Self::load(&store) {
+            Ok(identity) => identity,
+            Err(IdentityError::Io(e)) if e.kind() == io::ErrorKind::NotFound => Identity::default(),
+            Err(e) => {
+                eprintln!("Warning: Could not load the identity from {:?}: {}. Using the default identity.", store, e);
+                Identity::default()
+            }
+        };
+        Self::with_identity(identity, Some(store), hippocampus)
+    }
 
-        let knowledge_summary = format!("I currently hold {} distinct concepts in my memory.", hippocampus.holographic_memory.len());
+    /// A self-awareness over a given identity, saved to `store` if any.
+    pub fn with_identity(identity: Identity, store: Option<PathBuf>, hippocampus: &Hippocampus) -> Self {
+        let mut self_awareness = Self { identity, store, memory_count: 0, registry: CapabilityRegistry::builtin() };
+        self_awareness.update_knowledge_summary(hippocampus);
+        self_awareness
+    }
 
-        Self {
-            identity,
-            capabilities,
-            knowledge_summary,
+    fn load(path: &Path) -> Result<Identity, IdentityError> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn identity(&self) -> &Identity {
+        &self.identity
+    }
+
+    /// Replaces the capabilities described in the answers, e.g. when a tool is registered.
+    pub fn set_capabilities(&mut self, registry: CapabilityRegistry) {
+        self.registry = registry;
+    }
+
+    pub fn capabilities(&self) -> &CapabilityRegistry {
+        &self.registry
+    }
+
+    /// Changes the given attributes and saves the identity to its store.
+    pub fn update_identity(&mut self, update: IdentityUpdate) -> Result<&Identity, IdentityError> {
+        let mut identity = self.identity.clone();
+        if let Some(name) = update.name {
+            identity.name = name;
+        }
+        if let Some(purpose) = update.purpose {
+            identity.purpose = purpose;
+        }
+        if let Some(capabilities) = update.capabilities {
+            identity.capabilities = capabilities;
+        }
+        if let Some(creator) = update.creator {
+            identity.creator = creator;
         }
+        if let Some(store) = &self.store {
+            fs::write(store, toml::to_string_pretty(&identity)?)?;
+        }
+        self.identity = identity;
+        Ok(&self.identity)
     }
 
     /// Generates a comprehensive self-description.
     pub fn describe_self(&self) -> String {
-        format!(
-            "{}
-
-Here is what I can do:
-- {}
+        self.answer("", Lang::English)
+    }
 
-{}",
-            self.identity,
-            self.capabilities.join("\n- "),
-            self.knowledge_summary
-        )
+    /// Answers an introspective question from the structured identity: the attribute it asks
+    /// about, or a full self-presentation. The framing is written in `lang`; the attributes keep
+    /// the language they were given in.
+    pub fn answer(&self, prompt: &str, lang: Lang) -> String {
+        let identity = &self.identity;
+        let capabilities = std::iter::once(self.registry.summary(lang))
+            .filter(|summary| !summary.is_empty())
+            .chain(identity.capabilities.iter().map(|capability| format!("- {}", capability)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        match (IdentityAttribute::asked_in(prompt), lang) {
+            (Some(IdentityAttribute::Name), Lang::French) => format!("Je m'appelle {}.", identity.name),
+            (Some(IdentityAttribute::Name), Lang::English) => format!("My name is {}.", identity.name),
+            (Some(IdentityAttribute::Creator), Lang::French) => format!("J'ai été créé par {}.", identity.creator),
+            (Some(IdentityAttribute::Creator), Lang::English) => format!("I was created by {}.", identity.creator),
+            (Some(IdentityAttribute::Purpose), Lang::French) => format!("Mon but : {}.", identity.purpose),
+            (Some(IdentityAttribute::Purpose), Lang::English) => format!("My purpose is to {}.", identity.purpose),
+            (Some(IdentityAttribute::Capabilities), Lang::French) => format!("Voici ce que je sais faire :\n{}", capabilities),
+            (Some(IdentityAttribute::Capabilities), Lang::English) => format!("Here is what I can do:\n{}", capabilities),
+            (None, Lang::French) => format!(
+                "Je suis {}, créé par {}. Mon but : {}.\n\nVoici ce que je sais faire :\n{}\n\nJe garde actuellement {} souvenirs distincts en mémoire.",
+                identity.name, identity.creator, identity.purpose, capabilities, self.memory_count
+            ),
+            (None, Lang::English) => format!(
+                "I am {}, created by {}. My purpose is to {}.\n\nHere is what I can do:\n{}\n\nI currently hold {} distinct concepts in my memory.",
+                identity.name, identity.creator, identity.purpose, capabilities, self.memory_count
+            ),
+        }
     }
 
     /// Provides a dynamic summary of the AGI's knowledge state.
@@ -58,6 +201,36 @@ Here is what I can do:
     }
 
     pub fn update_knowledge_summary(&mut self, hippocampus: &Hippocampus) {
-        self.knowledge_summary = format!("I currently hold {} distinct concepts in my memory.", hippocampus.holographic_memory.len());
+        self.memory_count = hippocampus.holographic_memory.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_updates_are_persisted_and_answered() {
+        let store = std::env::temp_dir().join(format!("neurova_identity_{}.toml", std::process::id()));
+        let hippocampus = Hippocampus::new();
+        let mut self_awareness = SelfAwareness::new(&store, &hippocampus);
+        assert_eq!(self_awareness.identity(), &Identity::default());
+
+        let update = IdentityUpdate { name: Some("Vesper".to_string()), creator: Some("Ada".to_string()), ..IdentityUpdate::default() };
+        self_awareness.update_identity(update).unwrap();
+        assert_eq!(self_awareness.answer("Comment t'appelles-tu ?", Lang::French), "Je m'appelle Vesper.");
+        assert_eq!(self_awareness.answer("Who created you?", Lang::English), "I was created by Ada.");
+        assert!(self_awareness.answer("Who are you?", Lang::English).starts_with("I am Vesper, created by Ada."));
+
+        let update = IdentityUpdate { capabilities: Some(vec!["Tell stories about the sea".to_string()]), ..IdentityUpdate::default() };
+        self_awareness.update_identity(update).unwrap();
+        let answer = self_awareness.answer("What can you do?", Lang::English);
+        assert!(answer.contains(crate::mcq_solver::CAPABILITY.description));
+        assert!(answer.ends_with("- Tell stories about the sea"));
+
+        let reloaded = SelfAwareness::new(&store, &hippocampus);
+        assert_eq!(reloaded.identity().name, "Vesper");
+        assert_eq!(reloaded.identity().purpose, Identity::default().purpose);
+        std::fs::remove_file(&store).unwrap();
     }
 }
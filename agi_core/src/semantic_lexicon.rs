@@ -0,0 +1,158 @@
+//! semantic_lexicon.rs - File format for the semantic field of the HolographicEncoder.
+//!
+//! A lexicon declares named semantic axes (e.g. "vivant", "abstrait", "taille") and places
+//! concepts on them with weighted coordinates. Concepts sharing axes get overlapping reference
+//! waves, so deployments can shape the semantic space from a TOML or JSON file instead of
+//! recompiling. Axis waves are derived deterministically from the axis name, so a lexicon file
+//! fully describes the space it defines.
+//!
+//! ```toml
+//! [[axes]]
+//! name = "vivant"
+//! description = "Êtres vivants"
+//!
+//! [concepts.chat]
+//! vivant = 1.0
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use thiserror::Error;
+
+/// A named dimension of the semantic field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SemanticAxis {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Axis definitions plus the coordinates of each concept on those axes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SemanticLexicon {
+    #[serde(default)]
+    pub axes: Vec<SemanticAxis>,
+    /// Concept name -> (axis name -> weight).
+    #[serde(default)]
+    pub concepts: BTreeMap<String, BTreeMap<String, f32>>,
+}
+
+#[derive(Debug, Error)]
+pub enum LexiconError {
+    #[error("I/O error while accessing the lexicon: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid TOML lexicon: {0}")]
+    TomlParse(#[from] toml::de::Error),
+    #[error("could not serialize the lexicon to TOML: {0}")]
+    TomlWrite(#[from] toml::ser::Error),
+    #[error("invalid JSON lexicon: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported lexicon format '{0}' (expected .toml or .json)")]
+    UnsupportedFormat(String),
+    #[error("concept '{concept}' references the undeclared axis '{axis}'")]
+    UnknownAxis { concept: String, axis: String },
+}
+
+impl SemanticLexicon {
+    /// Loads a lexicon, choosing the format from the file extension (`.toml` or `.json`).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LexiconError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let lexicon: Self = match Self::format_of(path)? {
+            Format::Toml => toml::from_str(&content)?,
+            Format::Json => serde_json::from_str(&content)?,
+        };
+        lexicon.validate()?;
+        Ok(lexicon)
+    }
+
+    /// Writes the lexicon to `path`, in the format given by its extension.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), LexiconError> {
+        let path = path.as_ref();
+        let content = match Self::format_of(path)? {
+            Format::Toml => toml::to_string_pretty(self)?,
+            Format::Json => serde_json::to_string_pretty(self)?,
+        };
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Checks that every concept coordinate refers to a declared axis.
+    pub fn validate(&self) -> Result<(), LexiconError> {
+        for (concept, coordinates) in &self.concepts {
+            if let Some(axis) = coordinates.keys().find(|axis| !self.has_axis(axis)) {
+                return Err(LexiconError::UnknownAxis { concept: concept.clone(), axis: axis.clone() });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn has_axis(&self, name: &str) -> bool {
+        self.axes.iter().any(|axis| axis.name == name)
+    }
+
+    fn format_of(path: &Path) -> Result<Format, LexiconError> {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+            Some("toml") => Ok(Format::Toml),
+            Some("json") => Ok(Format::Json),
+            other => Err(LexiconError::UnsupportedFormat(other.unwrap_or_default().to_string())),
+        }
+    }
+}
+
+enum Format {
+    Toml,
+    Json,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holographic_memory::HolographicEncoder;
+
+    #[test]
+    fn test_lexicon_round_trip_shapes_the_semantic_space() {
+        let path = std::env::temp_dir().join(format!("neurova_lexicon_{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            r#"
+[[axes]]
+name = "vivant"
+
+[[axes]]
+name = "minéral"
+
+[concepts.chat]
+vivant = 1.0
+
+[concepts.tigre]
+vivant = 0.9
+"minéral" = 0.1
+
+[concepts.granit]
+"minéral" = 1.0
+"#,
+        )
+        .unwrap();
+
+        let mut encoder = HolographicEncoder::new(256);
+        encoder.load_lexicon_file(&path).unwrap();
+        encoder.add_lexicon_entry("caillou", [("minéral".to_string(), 1.0)].into()).unwrap();
+        assert!(encoder.add_lexicon_entry("nuage", [("gazeux".to_string(), 1.0)].into()).is_err());
+
+        let cat = encoder.encode("chat");
+        assert!(cat.cosine_similarity(&encoder.encode("tigre")) > cat.cosine_similarity(&encoder.encode("granit")));
+
+        let json_path = path.with_extension("json");
+        encoder.save_lexicon(&json_path).unwrap();
+        let saved = SemanticLexicon::load(&json_path).unwrap();
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&json_path);
+        assert_eq!(saved.axes.len(), 2);
+        assert_eq!(saved.concepts.len(), 4);
+        assert_eq!(saved.concepts["caillou"]["minéral"], 1.0);
+    }
+}
@@ -2,14 +2,74 @@
 
 use crate::conceptual_hierarchy::ConceptualHierarchy;
 use crate::holographic_memory::HolographicEncoder;
+use crate::units::{self, Quantity};
+use crate::holographic_memory::HolographicTrace;
+use crate::visual_features::ImageFeatures;
+use rand::seq::index;
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Parameters of the sparse distributed representation (SDR) of concepts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SdrConfig {
+    /// Size of the neuron population assemblies are drawn from.
+    pub num_neurons: usize,
+    /// Number of neurons in each concept's assembly.
+    pub active_bits: usize,
+    /// Fraction of a concept's assembly inherited from its parents in the hierarchy,
+    /// so that related concepts overlap at the neural layer.
+    pub parent_overlap: f32,
+}
+
+impl SdrConfig {
+    /// Builds a configuration where each assembly covers `sparsity` of the population (e.g. 0.02 for 2%).
+    pub fn with_sparsity(num_neurons: usize, sparsity: f32) -> Self {
+        let active_bits = ((num_neurons as f32 * sparsity).round() as usize).clamp(1, num_neurons.max(1));
+        Self {
+            num_neurons,
+            active_bits,
+            parent_overlap: 0.25,
+        }
+    }
+}
+
+/// How concepts are mapped onto neurons.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StimulusEncoding {
+    /// One neuron per concept, indexed by the concept's ID.
+    Dense,
+    /// Each concept activates a fixed-size, hash-stable assembly of neurons.
+    Sparse(SdrConfig),
+}
 
 /// The Sensory Cortex, responsible for processing external inputs and building the conceptual hierarchy.
 #[derive(Debug)]
-pub struct SensoryCortex;
+pub struct SensoryCortex {
+    encoding: StimulusEncoding,
+}
 
 impl SensoryCortex {
+    /// Parents further than this are ignored when inheriting assembly bits (the hierarchy may contain cycles).
+    const MAX_INHERITANCE_DEPTH: usize = 4;
+
     pub fn new() -> Self {
-        SensoryCortex
+        Self::with_encoding(StimulusEncoding::Dense)
+    }
+
+    pub fn with_encoding(encoding: StimulusEncoding) -> Self {
+        SensoryCortex { encoding }
+    }
+
+    pub fn encoding(&self) -> StimulusEncoding {
+        self.encoding
+    }
+
+    pub fn set_encoding(&mut self, encoding: StimulusEncoding) {
+        self.encoding = encoding;
     }
 
     /// Translates a text string into a list of neural stimuli by mapping words to concepts.
@@ -19,31 +79,549 @@ impl SensoryCortex {
         text: &str,
         hierarchy: &mut ConceptualHierarchy,
         encoder: &HolographicEncoder,
+    ) -> Vec<(u64, f32)> {
+        println!("\n--- Sensory Cortex Processing Input ---");
+        println!("Input text: '{}'", text);
+        let stimuli = self.process_words(Self::words(text), hierarchy, |word| encoder.encode(word));
+        println!("--- Sensory Input Processed ---\n");
+        stimuli
+    }
+
+    /// `process_text` on words already split, where `trace_of` gives the trace of a word that
+    /// becomes a new concept (`Core::assimilate_batch` encodes them beforehand, in parallel).
+    pub fn process_words(
+        &self,
+        words: impl IntoIterator<Item = String>,
+        hierarchy: &mut ConceptualHierarchy,
+        mut trace_of: impl FnMut(&str) -> HolographicTrace,
     ) -> Vec<(u64, f32)> {
         let mut stimuli = Vec::new();
         let stimulus_strength = 1.5; // A strong pulse to ensure the concept is noticed.
 
-        println!("\n--- Sensory Cortex Processing Input ---");
-        println!("Input text: '{}'", text);
+        for word in words {
+            // Only a word met for the first time needs a trace, for its new concept.
+            let concept_id = match hierarchy.concept_id(&word) {
+                Some(id) => id,
+                None => hierarchy.add_concept(&word, trace_of(&word), &[]),
+            };
+
+            match self.encoding {
+                StimulusEncoding::Dense => stimuli.push((concept_id, stimulus_strength)),
+                StimulusEncoding::Sparse(config) => {
+                    let assembly = Self::assembly_for_id(concept_id, hierarchy, &config, 0);
+                    stimuli.extend(assembly.into_iter().map(|neuron_id| (neuron_id, stimulus_strength)));
+                }
+            }
+        }
+        stimuli
+    }
 
-        // Simple whitespace and punctuation-based tokenization.
-        let words = text.split_whitespace()
-            .map(|word| word.trim_matches(|p: char| !p.is_alphanumeric()).to_lowercase());
+    /// The visual pathway: extracts the low-level features of an image (PNG, JPEG...) and encodes
+    /// them into a trace comparable with the traces of other pictures (see `visual_features`).
+    pub fn process_image(&self, bytes: &[u8], encoder: &HolographicEncoder) -> Result<(ImageFeatures, HolographicTrace), image::ImageError> {
+        let image = image::load_from_memory(bytes)?;
+        let features = ImageFeatures::extract(&image);
+        #[cfg(feature = "image_embeddings")]
+        let features = ImageFeatures { embedding: encoder.image_embedder().and_then(|embedder| embedder.embed(&image)), ..features };
+        let trace = encoder.encode_image(&features);
+        Ok((features, trace))
+    }
+
+    /// Returns the IDs of the known concepts mentioned in `text`, in order and without duplicates.
+    /// Unlike `process_text`, this never creates concepts.
+    pub fn concept_ids_for_text(&self, text: &str, hierarchy: &ConceptualHierarchy) -> Vec<u64> {
+        let mut seen = HashSet::new();
+        Self::words(text)
+            .filter_map(|word| hierarchy.find_concept_by_name(&word).map(|node| node.id))
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
+
+    /// Returns the neurons that represent the given concepts (their IDs in dense mode, their
+    /// assemblies in sparse mode), without duplicates.
+    pub fn neurons_for_concepts(&self, concept_ids: &[u64], hierarchy: &ConceptualHierarchy) -> HashSet<u64> {
+        match self.encoding {
+            StimulusEncoding::Dense => concept_ids.iter().copied().collect(),
+            StimulusEncoding::Sparse(config) => concept_ids.iter().flat_map(|&id| Self::assembly_for_id(id, hierarchy, &config, 0)).collect(),
+        }
+    }
+
+    /// The content words of `text`, as produced by the shared tokenizer.
+    fn words(text: &str) -> impl Iterator<Item = String> {
+        crate::tokenizer::content_words(text).into_iter()
+    }
+
+    /// Returns the neurons that represent `concept_name`.
+    ///
+    /// In dense mode this is the single neuron indexed by the concept's ID (if the concept is known);
+    /// in sparse mode it is the concept's assembly, which is stable across runs.
+    pub fn assembly_for(&self, concept_name: &str, hierarchy: &ConceptualHierarchy) -> Vec<u64> {
+        match self.encoding {
+            StimulusEncoding::Dense => hierarchy
+                .find_concept_by_name(concept_name)
+                .map(|node| vec![node.id])
+                .unwrap_or_default(),
+            StimulusEncoding::Sparse(config) => match hierarchy.find_concept_by_name(concept_name) {
+                Some(node) => Self::assembly_for_id(node.id, hierarchy, &config, 0),
+                None => Self::hashed_assembly(&crate::lemmatizer::lemmatize_auto(concept_name), &config),
+            },
+        }
+    }
+
+    /// Relatedness of two concepts at the neural layer: the fraction of their assemblies that overlap.
+    pub fn assembly_relatedness(&self, a: &str, b: &str, hierarchy: &ConceptualHierarchy) -> f32 {
+        let assembly_a = self.assembly_for(a, hierarchy);
+        let assembly_b = self.assembly_for(b, hierarchy);
+        let size = assembly_a.len().max(assembly_b.len());
+        if size == 0 {
+            return 0.0;
+        }
+        sdr_overlap(&assembly_a, &assembly_b) as f32 / size as f32
+    }
+
+    /// Builds the assembly of a known concept: its own hashed bits, with a share of them
+    /// replaced by bits inherited from its parents.
+    fn assembly_for_id(concept_id: u64, hierarchy: &ConceptualHierarchy, config: &SdrConfig, depth: usize) -> Vec<u64> {
+        let Some(node) = hierarchy.get_concept(concept_id) else {
+            return Vec::new();
+        };
+        let own_bits = Self::hashed_assembly(&node.name, config);
+        if node.parents.is_empty() || depth >= Self::MAX_INHERITANCE_DEPTH {
+            return own_bits;
+        }
+
+        let inherited_budget = (config.active_bits as f32 * config.parent_overlap.clamp(0.0, 1.0)).round() as usize;
+        let mut parent_ids: Vec<u64> = node.parents.iter().copied().collect();
+        parent_ids.sort_unstable();
+        let per_parent = (inherited_budget / parent_ids.len()).max(1);
+
+        let mut assembly: Vec<u64> = Vec::with_capacity(config.active_bits);
+        let mut seen = HashSet::new();
+        for parent_id in parent_ids {
+            let parent_bits = Self::assembly_for_id(parent_id, hierarchy, config, depth + 1);
+            for bit in parent_bits.into_iter().take(per_parent) {
+                if assembly.len() < inherited_budget && seen.insert(bit) {
+                    assembly.push(bit);
+                }
+            }
+        }
+        for bit in own_bits {
+            if assembly.len() >= config.active_bits {
+                break;
+            }
+            if seen.insert(bit) {
+                assembly.push(bit);
+            }
+        }
+        assembly
+    }
+
+    /// Draws a fixed-size random set of neurons, seeded by a SHA256 hash of the concept name.
+    fn hashed_assembly(name: &str, config: &SdrConfig) -> Vec<u64> {
+        let mut hasher = Sha256::new();
+        hasher.update(name.as_bytes());
+        let seed: [u8; 32] = hasher.finalize().into();
+        let mut rng: rand_chacha::ChaCha8Rng = rand::SeedableRng::from_seed(seed);
+
+        let active_bits = config.active_bits.min(config.num_neurons);
+        index::sample(&mut rng, config.num_neurons, active_bits)
+            .into_iter()
+            .map(|i| i as u64)
+            .collect()
+    }
+}
+
+/// The type of a named entity (see `SensoryCortex::extract_entities`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum EntityKind {
+    Person,
+    Place,
+    Date,
+    Number,
+    /// A value with a unit ("3 km"), see `SensoryCortex::extract_quantities`.
+    Quantity,
+}
+
+/// An entity mentioned in a text, as written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Entity {
+    pub text: String,
+    pub kind: EntityKind,
+}
+
+impl Entity {
+    /// The keys the entity is indexed under: its lowercased text and, for a person, the
+    /// family name alone ("albert einstein", "einstein"), for a date, the year alone
+    /// ("14 mars 1879", "1879"), for a quantity, its value in the base unit, so that "3 km"
+    /// and "3000 m" share a key (see `Quantity::canonical_key`).
+    pub fn index_keys(&self) -> Vec<String> {
+        let key = self.text.to_lowercase();
+        let mut keys = vec![key.clone()];
+        let last_word = key.rsplit([' ', '-']).next().filter(|word| *word != key);
+        match (self.kind, last_word) {
+            (EntityKind::Person, Some(family_name)) => keys.push(family_name.to_string()),
+            (EntityKind::Date, _) => {
+                if let Some(year) = key.split(|c: char| !c.is_ascii_digit()).find(|digits| digits.len() == 4).filter(|year| *year != key) {
+                    keys.push(year.to_string());
+                }
+            }
+            (EntityKind::Quantity, _) => keys.extend(SensoryCortex::extract_quantities(&self.text).first().map(Quantity::canonical_key)),
+            _ => {}
+        }
+        keys
+    }
+}
 
+// Gazetteers, lowercased.
+const PLACES: &[&str] = &[
+    "afrique", "africa", "allemagne", "germany", "amérique", "america", "angleterre", "england", "asie", "asia", "autriche", "austria",
+    "belgique", "belgium", "brésil", "brazil", "canada", "chine", "china", "égypte", "egypt", "espagne", "spain", "états-unis",
+    "united states", "europe", "france", "grèce", "greece", "inde", "india", "italie", "italy", "japon", "japan", "pologne", "poland",
+    "royaume-uni", "united kingdom", "russie", "russia", "suisse", "switzerland", "alexandrie", "alexandria", "athènes", "athens",
+    "berlin", "cambridge", "florence", "genève", "geneva", "londres", "london", "lyon", "madrid", "marseille", "moscou", "moscow",
+    "new york", "oxford", "paris", "pékin", "beijing", "princeton", "rome", "tokyo", "ulm", "varsovie", "warsaw", "vienne", "vienna",
+];
+const FIRST_NAMES: &[&str] = &[
+    "ada", "alan", "albert", "alexander", "alexandre", "antoine", "blaise", "carl", "charles", "claude", "émile", "emmanuel", "emmy",
+    "erwin", "frédéric", "friedrich", "galilée", "galileo", "george", "grace", "gustave", "henri", "immanuel", "isaac", "jacques",
+    "james", "jean", "johannes", "john", "katherine", "léonard", "leonardo", "louis", "ludwig", "marie", "max", "michel", "napoléon",
+    "niels", "nicolas", "paul", "pierre", "rené", "richard", "rosalind", "sigmund", "simone", "sophie", "stephen", "thomas", "victor",
+    "werner", "william", "wolfgang",
+];
+// Capitalized words that start a sentence without naming anything.
+const LEADING_WORDS: &[&str] = &["le", "la", "les", "l'", "un", "une", "the", "a", "an", "il", "elle", "en", "au", "dans", "in", "when", "quand", "who", "qui", "where", "où"];
+const PLACE_PREPOSITIONS: &[&str] = &["à", "au", "en", "in", "at", "near", "vers", "près de"];
+const PERSON_CUES: &[&str] = &["né", "née", "born", "mort", "morte", "died", "écrit", "wrote", "découvert", "discovered"];
+
+impl SensoryCortex {
+    /// A lightweight named-entity recognizer: dates and numbers by pattern, persons and places
+    /// by capitalization and gazetteers. A capitalized name is a place if it is a known place or
+    /// follows a place preposition ("à Ulm"), a person if it starts with a known first name or
+    /// is followed by a cue such as a birth or death ("Einstein est né"). Other capitalized
+    /// words are not tagged.
+    pub fn extract_entities(text: &str) -> Vec<Entity> {
+        Self::scan_entities(text, false)
+    }
+
+    /// Like `extract_entities`, for a query that will be looked up in the entity index: every
+    /// capitalized name is kept ("Einstein a-t-il vécu à Ulm ?"), as a person unless it is a place.
+    pub fn extract_query_entities(text: &str) -> Vec<Entity> {
+        Self::scan_entities(text, true)
+    }
+
+    /// The quantities written in a text: a number, with a space as thousands separator and a
+    /// point or a comma as decimal separator, followed by a known unit ("8 849 m", "1,5 km",
+    /// "-40 °C"). Single-letter units must be separated from the number ("3 m", not "1990s").
+    pub fn extract_quantities(text: &str) -> Vec<Quantity> {
+        Self::scan_quantities(text).into_iter().map(|(_, quantity)| quantity).collect()
+    }
+
+    fn scan_quantities(text: &str) -> Vec<(Range<usize>, Quantity)> {
+        static QUANTITY: OnceLock<Regex> = OnceLock::new();
+        let quantity = QUANTITY.get_or_init(|| {
+            let mut aliases: Vec<&str> = units::UNITS.iter().flat_map(|unit| unit.aliases().iter().copied()).collect();
+            aliases.sort_by_key(|alias| std::cmp::Reverse(alias.chars().count()));
+            let alternation = |short: bool| aliases.iter().filter(|alias| (alias.chars().count() == 1) == short).map(|alias| regex::escape(alias)).collect::<Vec<_>>().join("|");
+            Regex::new(&format!(
+                r"(?i)(?:^|[^\w.,-])(-?(?:\d{{1,3}}(?:[ \u{{a0}}\u{{202f}}]\d{{3}})+|\d+)(?:[.,]\d+)?)(?:\s*({long})|\s+({short}))\b",
+                long = alternation(false),
+                short = alternation(true)
+            ))
+            .unwrap()
+        });
+        quantity
+            .captures_iter(text)
+            .filter_map(|captures| {
+                let number = captures.get(1)?;
+                let unit = units::lookup(captures.get(2).or_else(|| captures.get(3))?.as_str())?;
+                let digits: String = number.as_str().chars().filter(|c| !c.is_whitespace()).collect();
+                let value = digits.replace(',', ".").parse().ok()?;
+                Some((number.start()..captures.get(0)?.end(), Quantity { value, unit }))
+            })
+            .collect()
+    }
+
+    fn scan_entities(text: &str, lenient: bool) -> Vec<Entity> {
+        static DATE: OnceLock<Regex> = OnceLock::new();
+        static NUMBER: OnceLock<Regex> = OnceLock::new();
+        static NAME: OnceLock<Regex> = OnceLock::new();
+        let date = DATE.get_or_init(|| {
+            let months_fr = "janvier|février|mars|avril|mai|juin|juillet|août|septembre|octobre|novembre|décembre";
+            let months_en = "January|February|March|April|May|June|July|August|September|October|November|December";
+            Regex::new(&format!(
+                r"(?i)\b(?:\d{{1,2}}(?:er)?\s+(?:{fr}|{en})\s+\d{{3,4}}|(?:{en})\s+\d{{1,2}},?\s+\d{{3,4}}|\d{{4}}-\d{{2}}-\d{{2}}|(?:{fr}|{en})\s+\d{{4}})\b|\b(?:en|in|depuis|since|year|année)\s+(1\d{{3}}|20\d{{2}})\b",
+                fr = months_fr,
+                en = months_en
+            ))
+            .unwrap()
+        });
+        let number = NUMBER.get_or_init(|| Regex::new(r"\b\d+(?:[.,]\d+)?\b").unwrap());
+        let name = NAME.get_or_init(|| {
+            Regex::new(r"\p{Lu}[\p{L}'’-]*(?:\s+(?:(?:de|da|di|du|von|van|der)\s+)?\p{Lu}[\p{L}'’-]*)*").unwrap()
+        });
+
+        let mut entities = Vec::new();
+        let mut taken: Vec<Range<usize>> = Vec::new();
+        for captures in date.captures_iter(text) {
+            let span = captures.get(1).or_else(|| captures.get(0)).unwrap();
+            taken.push(captures.get(0).unwrap().range());
+            entities.push(Entity { text: span.as_str().to_string(), kind: EntityKind::Date });
+        }
+        let overlaps = |range: &Range<usize>, taken: &[Range<usize>]| taken.iter().any(|t| range.start < t.end && t.start < range.end);
+        for (range, _) in Self::scan_quantities(text) {
+            if !overlaps(&range, &taken) {
+                entities.push(Entity { text: text[range.clone()].to_string(), kind: EntityKind::Quantity });
+                taken.push(range);
+            }
+        }
+        for found in number.find_iter(text) {
+            if !overlaps(&found.range(), &taken) {
+                entities.push(Entity { text: found.as_str().to_string(), kind: EntityKind::Number });
+            }
+        }
+
+        for found in name.find_iter(text) {
+            if overlaps(&found.range(), &taken) {
+                continue;
+            }
+            let before = text[..found.start()].trim_end();
+            let mut words: Vec<&str> = found.as_str().split_whitespace().collect();
+            while words.len() > 1 && LEADING_WORDS.contains(&words[0].to_lowercase().as_str()) {
+                words.remove(0);
+            }
+            let name_text = words.join(" ");
+            let lower = name_text.to_lowercase();
+            let preceding = before.to_lowercase();
+            let after_place_preposition = PLACE_PREPOSITIONS.iter().any(|preposition| preceding == *preposition || preceding.ends_with(&format!(" {}", preposition)));
+            let kind = if PLACES.contains(&lower.as_str()) || after_place_preposition {
+                EntityKind::Place
+            } else if FIRST_NAMES.contains(&words[0].to_lowercase().as_str()) {
+                EntityKind::Person
+            } else if LEADING_WORDS.contains(&lower.as_str()) {
+                continue;
+            } else if lenient || Self::followed_by_person_cue(&text[found.end()..]) {
+                EntityKind::Person
+            } else {
+                continue;
+            };
+            if !entities.iter().any(|entity: &Entity| entity.text == name_text) {
+                entities.push(Entity { text: name_text, kind });
+            }
+        }
+        entities
+    }
+
+    /// Whether one of the next three words is a person cue.
+    fn followed_by_person_cue(rest: &str) -> bool {
+        let rest = rest.to_lowercase();
+        let words: Vec<&str> = rest.split(|c: char| !c.is_alphabetic()).filter(|word| !word.is_empty()).take(3).collect();
+        words.iter().any(|word| PERSON_CUES.contains(word))
+    }
+}
+
+/// The feeling expressed by a text (see `SensoryCortex::score_sentiment`).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct Sentiment {
+    /// From -1.0 (negative) to 1.0 (positive); 0.0 when no feeling is expressed.
+    pub valence: f32,
+    /// From 0.0 (calm) to 1.0 (excited, or angry).
+    pub arousal: f32,
+}
+
+impl Sentiment {
+    /// Valence beyond which a text counts as positive or negative rather than neutral.
+    pub const POLARITY_THRESHOLD: f32 = 0.2;
+
+    pub fn is_positive(&self) -> bool {
+        self.valence >= Self::POLARITY_THRESHOLD
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.valence <= -Self::POLARITY_THRESHOLD
+    }
+}
+
+// Sentiment lexicon, lowercased: (word, valence, arousal).
+const SENTIMENT_LEXICON: &[(&str, f32, f32)] = &[
+    ("merci", 0.6, 0.3), ("super", 0.8, 0.7), ("génial", 0.9, 0.8), ("géniale", 0.9, 0.8), ("parfait", 0.8, 0.4), ("parfaite", 0.8, 0.4),
+    ("bien", 0.4, 0.2), ("bon", 0.4, 0.2), ("bonne", 0.4, 0.2), ("content", 0.7, 0.4), ("contente", 0.7, 0.4), ("heureux", 0.8, 0.5),
+    ("heureuse", 0.8, 0.5), ("adore", 0.9, 0.7), ("aime", 0.6, 0.4), ("formidable", 0.9, 0.7), ("excellent", 0.9, 0.6),
+    ("excellente", 0.9, 0.6), ("intéressant", 0.6, 0.4), ("intéressante", 0.6, 0.4), ("bravo", 0.8, 0.7), ("magnifique", 0.9, 0.6),
+    ("thanks", 0.6, 0.3), ("thank", 0.6, 0.3), ("great", 0.8, 0.6), ("awesome", 0.9, 0.8), ("perfect", 0.8, 0.4), ("good", 0.4, 0.2),
+    ("happy", 0.8, 0.5), ("glad", 0.7, 0.4), ("love", 0.9, 0.7), ("wonderful", 0.9, 0.6),
+    ("interesting", 0.6, 0.4), ("nice", 0.6, 0.3), ("amazing", 0.9, 0.8),
+    ("triste", -0.7, 0.3), ("fatigué", -0.4, 0.1), ("fatiguée", -0.4, 0.1), ("énervé", -0.7, 0.8), ("énervée", -0.7, 0.8),
+    ("déçu", -0.6, 0.4), ("déçue", -0.6, 0.4), ("marre", -0.7, 0.7), ("nul", -0.7, 0.6), ("nulle", -0.7, 0.6), ("faux", -0.5, 0.5),
+    ("horrible", -0.9, 0.7), ("déteste", -0.9, 0.8), ("inquiet", -0.5, 0.6), ("inquiète", -0.5, 0.6), ("peur", -0.6, 0.7),
+    ("stressé", -0.6, 0.7), ("stressée", -0.6, 0.7), ("malheureux", -0.8, 0.4), ("malheureuse", -0.8, 0.4), ("mauvais", -0.5, 0.4),
+    ("mauvaise", -0.5, 0.4), ("problème", -0.3, 0.4), ("difficile", -0.3, 0.4), ("perdu", -0.4, 0.4), ("perdue", -0.4, 0.4),
+    ("sad", -0.7, 0.3), ("tired", -0.4, 0.1), ("angry", -0.8, 0.9), ("upset", -0.7, 0.7), ("annoyed", -0.6, 0.7), ("disappointed", -0.6, 0.4),
+    ("awful", -0.9, 0.7), ("terrible", -0.9, 0.7), ("hate", -0.9, 0.8), ("wrong", -0.5, 0.5), ("worried", -0.5, 0.6), ("afraid", -0.6, 0.7),
+    ("stressed", -0.6, 0.7), ("bad", -0.5, 0.4), ("useless", -0.7, 0.6), ("lost", -0.4, 0.4), ("confused", -0.4, 0.4),
+];
+// Words that reverse the valence of the next sentiment word ("pas content", "not happy").
+const NEGATIONS: &[&str] = &["pas", "jamais", "not", "never", "no", "don't", "isn't", "aren't"];
+// Words that strengthen the next sentiment word ("très triste", "really great").
+const INTENSIFIERS: &[&str] = &["très", "vraiment", "trop", "tellement", "si", "very", "really", "so", "too", "extremely"];
+/// Sentiment words after a negation that it still applies to.
+const NEGATION_SCOPE: usize = 3;
+
+impl SensoryCortex {
+    /// Scores the feeling a text expresses from a small French and English lexicon. A negation
+    /// reverses (and weakens) the words that follow it ("je ne suis pas content"), an
+    /// intensifier strengthens the next one, and exclamation marks raise the arousal. A text
+    /// without any sentiment word is neutral.
+    pub fn score_sentiment(text: &str) -> Sentiment {
+        let lower = text.to_lowercase().replace('’', "'");
+        let words: Vec<&str> = lower.split(|c: char| !c.is_alphabetic() && c != '\'').filter(|word| !word.is_empty()).collect();
+
+        let (mut valence, mut arousal, mut matched) = (0.0f32, 0.0f32, 0usize);
+        let mut negated_for = 0;
+        let mut intensity = 1.0;
         for word in words {
-            if word.is_empty() { continue; }
+            // "j'adore", "n'aime" carry their sentiment word after the apostrophe; "n'" negates it.
+            let stem = word.rsplit('\'').next().unwrap_or(word);
+            if NEGATIONS.contains(&word) {
+                negated_for = NEGATION_SCOPE;
+                continue;
+            }
+            if INTENSIFIERS.contains(&word) {
+                intensity = 1.5;
+                continue;
+            }
+            let Some(&(_, word_valence, word_arousal)) = SENTIMENT_LEXICON.iter().find(|(entry, _, _)| *entry == stem) else {
+                continue;
+            };
+            let word_valence = if negated_for > 0 || word.starts_with("n'") { -0.5 * word_valence } else { word_valence };
+            valence += word_valence * intensity;
+            arousal += word_arousal * intensity;
+            matched += 1;
+            negated_for = negated_for.saturating_sub(1);
+            intensity = 1.0;
+        }
+
+        let exclamations = text.matches('!').count().min(3) as f32;
+        if matched == 0 {
+            return Sentiment { valence: 0.0, arousal: (0.1 * exclamations).min(1.0) };
+        }
+        Sentiment {
+            valence: (valence / matched as f32).clamp(-1.0, 1.0),
+            arousal: (arousal / matched as f32 + 0.1 * exclamations).clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Default for SensoryCortex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Number of neurons shared by two assemblies.
+pub fn sdr_overlap(a: &[u64], b: &[u64]) -> usize {
+    let set_a: HashSet<&u64> = a.iter().collect();
+    b.iter().filter(|bit| set_a.contains(bit)).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holographic_memory::HolographicTrace;
+
+    fn sparse_cortex() -> (SensoryCortex, SdrConfig) {
+        let config = SdrConfig::with_sparsity(2048, 0.02);
+        (SensoryCortex::with_encoding(StimulusEncoding::Sparse(config)), config)
+    }
+
+    #[test]
+    fn test_assemblies_are_stable_and_sparse() {
+        let (cortex, config) = sparse_cortex();
+        let hierarchy = ConceptualHierarchy::new();
+
+        let first = cortex.assembly_for("lumière", &hierarchy);
+        let second = cortex.assembly_for("lumière", &hierarchy);
+        assert_eq!(first, second, "The same concept must always map to the same assembly");
+        assert_eq!(first.len(), config.active_bits);
+        assert!(first.iter().all(|&bit| (bit as usize) < config.num_neurons));
+        assert_eq!(first.iter().collect::<HashSet<_>>().len(), first.len(), "Assembly bits must be distinct");
+    }
+
+    #[test]
+    fn test_unrelated_concepts_overlap_at_chance_level() {
+        let (cortex, config) = sparse_cortex();
+        let hierarchy = ConceptualHierarchy::new();
 
-            // For now, we don't have a lookup by name, so we'll iterate. This is inefficient
-            // and will be replaced once the hierarchy has a proper name->ID mapping.
-            // The `add_concept` method now transparently handles finding an existing concept
-            // or creating a new one if it doesn't exist. This simplifies the logic here.
-            let trace = encoder.encode(word.as_str());
-            let concept_id = hierarchy.add_concept(word.as_str(), trace, &[]);
+        let assemblies: Vec<Vec<u64>> = (0..100)
+            .map(|i| cortex.assembly_for(&format!("concept{}", i), &hierarchy))
+            .collect();
 
-            stimuli.push((concept_id, stimulus_strength));
+        let mut overlaps = Vec::new();
+        for i in 0..assemblies.len() {
+            for j in (i + 1)..assemblies.len() {
+                overlaps.push(sdr_overlap(&assemblies[i], &assemblies[j]));
+            }
         }
 
-        println!("--- Sensory Input Processed ---\n");
-        stimuli
+        // Two random k-subsets of n neurons share k²/n bits on average (~0.8 here).
+        let expected = (config.active_bits * config.active_bits) as f32 / config.num_neurons as f32;
+        let mean = overlaps.iter().sum::<usize>() as f32 / overlaps.len() as f32;
+        let max = *overlaps.iter().max().unwrap();
+        println!("SDR overlap: mean {:.3} (expected {:.3}), max {}", mean, expected, max);
+
+        assert!((mean - expected).abs() < 0.5, "Mean overlap {} too far from chance {}", mean, expected);
+        assert!(max < config.active_bits / 4, "Unrelated concepts should never share a large part of their assemblies");
+    }
+
+    #[test]
+    fn test_entities_are_recognized() {
+        let entities = SensoryCortex::extract_entities("Albert Einstein est né à Ulm le 14 mars 1879. Il reçut le prix Nobel en 1921 pour 3 articles.");
+        let found: Vec<(&str, EntityKind)> = entities.iter().map(|entity| (entity.text.as_str(), entity.kind)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("14 mars 1879", EntityKind::Date),
+                ("1921", EntityKind::Date),
+                ("3", EntityKind::Number),
+                ("Albert Einstein", EntityKind::Person),
+                ("Ulm", EntityKind::Place),
+            ]
+        );
+        assert_eq!(entities[3].index_keys(), vec!["albert einstein", "einstein"]);
+        assert_eq!(entities[0].index_keys(), vec!["14 mars 1879", "1879"]);
+
+        let query = SensoryCortex::extract_entities("When was Einstein born?");
+        assert_eq!(query, vec![Entity { text: "Einstein".to_string(), kind: EntityKind::Person }]);
+        assert!(SensoryCortex::extract_entities("Le Big Bang est le modèle dominant.").is_empty());
+    }
+
+    #[test]
+    fn test_sentiment_follows_the_lexicon() {
+        assert_eq!(SensoryCortex::score_sentiment("Qu'est-ce qu'un quasar ?"), Sentiment::default());
+        assert!(SensoryCortex::score_sentiment("Merci, c'est parfait !").is_positive());
+        assert!(SensoryCortex::score_sentiment("J'adore l'astronomie").is_positive());
+        assert!(SensoryCortex::score_sentiment("I'm really upset, this is wrong").is_negative());
+
+        let sad = SensoryCortex::score_sentiment("Je suis triste");
+        let very_sad = SensoryCortex::score_sentiment("Je suis très triste !");
+        assert!(very_sad.valence < sad.valence && very_sad.arousal > sad.arousal);
+
+        let not_happy = SensoryCortex::score_sentiment("Je ne suis pas content");
+        assert!(not_happy.is_negative() && not_happy.valence > sad.valence, "A negation weakens as it reverses");
+    }
+
+    #[test]
+    fn test_child_assembly_overlaps_parent() {
+        let (cortex, config) = sparse_cortex();
+        let mut hierarchy = ConceptualHierarchy::new();
+        let dog = hierarchy.add_concept("chien", HolographicTrace::new_empty(16), &[]);
+        hierarchy.add_concept("caniche", HolographicTrace::new_empty(16), &[dog]);
+        hierarchy.add_concept("volcan", HolographicTrace::new_empty(16), &[]);
+
+        let related = cortex.assembly_relatedness("caniche", "chien", &hierarchy);
+        let unrelated = cortex.assembly_relatedness("caniche", "volcan", &hierarchy);
+
+        assert_eq!(cortex.assembly_for("caniche", &hierarchy).len(), config.active_bits);
+        assert!(related >= config.parent_overlap * 0.9, "Child should inherit part of its parent's assembly (got {})", related);
+        assert!(related > unrelated);
     }
 }
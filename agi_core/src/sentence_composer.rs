@@ -0,0 +1,221 @@
+//! sentence_composer.rs - Compositional rendering of retrieved facts.
+//!
+//! Answers used to be the winning memory, copied verbatim. The composer reads a memory as a
+//! proposition (subject, relation, object), then renders it again through one of several
+//! phrasings, with the verb agreeing with the subject ("le trou noir est" / "les trous noirs
+//! sont") and, when a second memory is about the same subject, a pronoun that agrees with it
+//! ("De plus, elle est..."). Quotations and sentences it cannot parse are left untouched.
+
+use crate::lemmatizer::{self, Lang};
+use crate::surface_realization;
+
+/// Verbs through which a subject asserts its object ("X stipule que Y"), singular then plural.
+const ASSERTION_VERBS: &[(&str, &str)] = &[
+    ("stipule", "stipulent"), ("affirme", "affirment"), ("postule", "postulent"), ("énonce", "énoncent"), ("soutient", "soutiennent"),
+    ("states", "state"), ("claims", "claim"), ("holds", "hold"), ("asserts", "assert"), ("posits", "posit"),
+];
+
+/// Words that cannot start the subject of a proposition (pronouns and question words).
+const NON_SUBJECTS: &[&str] = &[
+    "il", "elle", "ils", "elles", "on", "c'", "ce", "cela", "ça", "je", "tu", "nous", "vous", "qui", "que", "quoi", "où",
+    "it", "this", "that", "he", "she", "they", "we", "you", "i", "what", "who", "where", "there",
+];
+
+/// The maximal length of a subject, in words. Longer ones are rarely a real subject.
+const MAX_SUBJECT_WORDS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// "X est Y", "X is Y".
+    Is { past: bool },
+    /// "X stipule que Y", "X states that Y".
+    Asserts,
+}
+
+/// A fact read as subject, relation and object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proposition {
+    pub subject: String,
+    pub relation: Relation,
+    pub object: String,
+    pub plural: bool,
+    pub lang: Lang,
+}
+
+impl Proposition {
+    /// Reads the first sentence of `text` as a proposition. Quotations ("... - Socrate") and
+    /// sentences without a recognizable relation yield None.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.lines().map(str::trim).find(|line| !line.is_empty() && !line.starts_with("//"))?;
+        if text.contains(" - ") || text.contains('«') || text.contains('"') {
+            return None;
+        }
+        let sentence = first_sentence(text);
+        let lang = lemmatizer::detect_language(sentence);
+        let words: Vec<&str> = sentence.split_whitespace().collect();
+
+        let (position, relation, plural_verb) = words.iter().enumerate().take(MAX_SUBJECT_WORDS + 1).skip(1).find_map(|(index, word)| {
+            let word = word.to_lowercase();
+            match word.as_str() {
+                "est" | "is" => Some((index, Relation::Is { past: false }, false)),
+                "sont" | "are" => Some((index, Relation::Is { past: false }, true)),
+                "était" | "was" => Some((index, Relation::Is { past: true }, false)),
+                "étaient" | "were" => Some((index, Relation::Is { past: true }, true)),
+                _ => {
+                    let plural = ASSERTION_VERBS.iter().find_map(|(singular, plural)| {
+                        (word == *singular).then_some(false).or((word == *plural).then_some(true))
+                    })?;
+                    let next = words.get(index + 1).map(|next| next.to_lowercase());
+                    let introduces_clause = next.as_deref().is_some_and(|next| next == "que" || next.starts_with("qu'") || next == "that");
+                    introduces_clause.then_some((index, Relation::Asserts, plural))
+                }
+            }
+        })?;
+
+        let subject = words[..position].join(" ").trim_end_matches(',').to_string();
+        let first = subject.split(['\'', '’', ' ']).next().unwrap_or_default().to_lowercase();
+        if NON_SUBJECTS.contains(&first.as_str()) || subject.ends_with('?') {
+            return None;
+        }
+
+        let mut object = words[position + 1..].join(" ");
+        if relation == Relation::Asserts {
+            // Drop the conjunction: "que la lumière..." -> "la lumière...", "qu'il..." -> "il...".
+            object = object
+                .strip_prefix("que ")
+                .or_else(|| object.strip_prefix("that "))
+                .map(str::to_string)
+                .or_else(|| object.strip_prefix("qu'").or_else(|| object.strip_prefix("qu’")).map(str::to_string))
+                .unwrap_or(object);
+        }
+        let object = object.trim_end_matches(['.', '!', ';']).trim().to_string();
+        if object.split_whitespace().count() < 2 {
+            return None;
+        }
+        Some(Self { subject, relation, object, plural: plural_verb, lang })
+    }
+
+    /// The number of phrasings `render` offers for this proposition.
+    pub fn variants(&self) -> usize {
+        match self.relation {
+            Relation::Is { past: false } => 4,
+            Relation::Is { past: true } => 2,
+            Relation::Asserts => 2,
+        }
+    }
+
+    /// Renders the proposition with phrasing number `variant` (modulo `variants()`).
+    pub fn render(&self, variant: usize) -> String {
+        let (s, o) = (self.subject.as_str(), self.object.as_str());
+        let copula = self.copula();
+        let sentence = match (self.relation, self.lang, variant % self.variants()) {
+            (Relation::Is { .. }, _, 0) => format!("{} {} {}", s, copula, o),
+            (Relation::Is { past: false }, Lang::French, 1) => {
+                let verb = if self.plural { "se définissent" } else { "se définit" };
+                format!("{} {} comme {}", s, verb, o)
+            }
+            (Relation::Is { past: false }, Lang::French, 2) => surface_realization::join("On peut définir", &format!("{} comme {}", s, o)),
+            (Relation::Is { past: false }, Lang::French, _) => format!("{}, {} {}", s, if self.plural { "ce sont" } else { "c'est" }, o),
+            (Relation::Is { past: false }, Lang::English, 1) => format!("{} can be defined as {}", s, o),
+            (Relation::Is { past: false }, Lang::English, 2) => surface_realization::join("By definition,", &format!("{} {} {}", s, copula, o)),
+            (Relation::Is { past: false }, Lang::English, _) => surface_realization::join("Put simply,", &format!("{} {} {}", s, copula, o)),
+            (Relation::Is { past: true }, Lang::French, _) => surface_realization::join("Historiquement,", &format!("{} {} {}", s, copula, o)),
+            (Relation::Is { past: true }, Lang::English, _) => surface_realization::join("Historically,", &format!("{} {} {}", s, copula, o)),
+            (Relation::Asserts, Lang::French, 0) => surface_realization::join("Selon", &format!("{}, {}", s, o)),
+            (Relation::Asserts, Lang::English, 0) => surface_realization::join("According to", &format!("{}, {}", s, o)),
+            (Relation::Asserts, Lang::French, _) => surface_realization::join(&format!("{} {} que", s, if self.plural { "posent" } else { "pose" }), o),
+            (Relation::Asserts, Lang::English, _) => format!("{} {} that {}", s, if self.plural { "hold" } else { "holds" }, o),
+        };
+        surface_realization::realize(&sentence)
+    }
+
+    /// Continues an answer about the same subject with a pronoun that agrees with it
+    /// ("De plus, elle est..."). Returns None when the subject is not about the same thing.
+    pub fn render_continuation(&self, previous: &Proposition) -> Option<String> {
+        if !matches!(self.relation, Relation::Is { .. }) || self.lang != previous.lang || normalize(&self.subject) != normalize(&previous.subject) {
+            return None;
+        }
+        let pronoun = self.pronoun();
+        let (connector, subject) = match self.lang {
+            Lang::French => ("De plus,", pronoun.unwrap_or(&self.subject)),
+            Lang::English => ("Moreover,", pronoun.unwrap_or(&self.subject)),
+        };
+        Some(surface_realization::join(connector, &format!("{} {} {}", subject, self.copula(), self.object)))
+    }
+
+    fn copula(&self) -> &'static str {
+        let past = matches!(self.relation, Relation::Is { past: true });
+        match (self.lang, self.plural, past) {
+            (Lang::French, false, false) => "est",
+            (Lang::French, true, false) => "sont",
+            (Lang::French, false, true) => "était",
+            (Lang::French, true, true) => "étaient",
+            (Lang::English, false, false) => "is",
+            (Lang::English, true, false) => "are",
+            (Lang::English, false, true) => "was",
+            (Lang::English, true, true) => "were",
+        }
+    }
+
+    /// The personal pronoun agreeing with the subject, read from its determiner.
+    /// Subjects without a determiner (names) have none and are repeated.
+    fn pronoun(&self) -> Option<&'static str> {
+        let determiner = self.subject.split_whitespace().next()?.to_lowercase();
+        match (self.lang, determiner.as_str()) {
+            (Lang::French, "le" | "un" | "ce" | "cet" | "du") => Some("il"),
+            (Lang::French, "la" | "une" | "cette") => Some("elle"),
+            (Lang::French, "les" | "des" | "ces") => Some("ils"),
+            (Lang::English, "the" | "a" | "an" | "this") if self.plural => Some("they"),
+            (Lang::English, "the" | "a" | "an" | "this") => Some("it"),
+            (Lang::English, "these" | "those") => Some("they"),
+            _ => None,
+        }
+    }
+}
+
+fn first_sentence(text: &str) -> &str {
+    // A period followed by a space ends the sentence; abbreviations ("L. Feld") have a one-letter word before it.
+    let mut end = text.len();
+    for (index, _) in text.match_indices(". ") {
+        let word_before = text[..index].rsplit(' ').next().unwrap_or_default();
+        if word_before.chars().count() > 1 {
+            end = index;
+            break;
+        }
+    }
+    text[..end].trim()
+}
+
+fn normalize(subject: &str) -> String {
+    subject.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_render_with_agreement() {
+        let quasar = Proposition::parse("Le quasar est un noyau de galaxie très lumineux.").unwrap();
+        assert_eq!(quasar.subject, "Le quasar");
+        assert_eq!(quasar.render(2), "On peut définir le quasar comme un noyau de galaxie très lumineux.");
+        assert_eq!(quasar.render(3), "Le quasar, c'est un noyau de galaxie très lumineux.");
+
+        let black_holes = Proposition::parse("Les trous noirs sont des régions de l'espace-temps.").unwrap();
+        assert!(black_holes.plural);
+        assert_eq!(black_holes.render(1), "Les trous noirs se définissent comme des régions de l'espace-temps.");
+
+        let cell = Proposition::parse("La cellule est l'unité de base du vivant.").unwrap();
+        let more = Proposition::parse("La cellule est entourée d'une membrane plasmique.").unwrap();
+        assert_eq!(more.render_continuation(&cell).as_deref(), Some("De plus, elle est entourée d'une membrane plasmique."));
+
+        let razor = Proposition::parse("Le rasoir d'Occam stipule que les hypothèses suffisantes les plus simples sont les plus vraisemblables.").unwrap();
+        assert_eq!(razor.render(0), "Selon le rasoir d'Occam, les hypothèses suffisantes les plus simples sont les plus vraisemblables.");
+
+        let energy = Proposition::parse("Energy is the capacity to do work.").unwrap();
+        assert_eq!(energy.render(1), "Energy can be defined as the capacity to do work.");
+
+        assert!(Proposition::parse("Connais-toi toi-même. - Socrate").is_none());
+        assert!(Proposition::parse("Il est tard.").is_none());
+    }
+}
@@ -0,0 +1,353 @@
+//! session.rs - Recording a cognitive session, and replaying it.
+//!
+//! Emergent behaviour is hard to debug when it cannot be reproduced. A seeded Core (see
+//! `CoreConfig::seed`) draws the same random numbers on every run, so the same inputs in the same
+//! order lead to the same activity. The `SessionRecorder` writes these inputs to a JSONL file as
+//! they reach the Core: the seed first, then the learned texts and files, the prompts with their
+//! answers, the feedback, the sleep cycles and the ticks (batched, with a fingerprint of the
+//! neurons fired). `SessionReplay` feeds them to a new Core built with the same seed and
+//! configuration, and reports every answer and every batch of ticks that came out differently.
+//!
+//! Recording starts on a new Core, before it has learned or ticked. Operations outside these
+//! entry points (hierarchy administration, identity edits, knowledge file loads...) are not
+//! recorded, and a replay after one of them diverges; so does a prompt that ran out of its time
+//! budget in one run and not in the other (see `PipelineTimeouts`).
+
+use crate::feedback::Feedback;
+use crate::source_trust::SourceTag;
+use crate::Core;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Version of the file format, checked by the replay.
+pub const SESSION_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("I/O error on the session file: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid event on line {line} of the session file: {source}")]
+    Json { line: usize, source: serde_json::Error },
+    #[error("could not encode a session event: {0}")]
+    Encode(serde_json::Error),
+    #[error("the session file does not start with its seed")]
+    MissingStart,
+    #[error("the session file has format version {0}, this build reads version {SESSION_FORMAT_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("only a seeded core can be recorded (see CoreConfig::seed)")]
+    Unseeded,
+    #[error("recording must start before the core has ticked or learned")]
+    NotFresh,
+}
+
+/// An input of the Core, or a batch of ticks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// The first line of a session file.
+    Start { version: u32, seed: u64 },
+    /// `Core::learn_from_file`; the digest tells whether the file changed since.
+    LearnFile { path: PathBuf, sha256: String },
+    /// `Core::learn_and_assimilate`.
+    Assimilate { text: String, is_axiom: bool },
+    /// `Core::assimilate_batch`.
+    AssimilateBatch {
+        texts: Vec<String>,
+        is_axiom: bool,
+        #[serde(default)]
+        source: SourceTag,
+    },
+    /// `Core::learn`.
+    Learn { text: String, is_axiom: bool },
+    /// `Core::teach_relationship`.
+    Relate { child: String, parent: String },
+    /// `Core::get_response_for_prompt`, with the answer given.
+    Prompt { prompt: String, response: Option<String> },
+    /// `Core::feedback`.
+    Feedback { response_id: u64, feedback: Feedback },
+    /// `Core::consolidate_working_memory`.
+    Consolidate,
+    /// `Core::enter_sleep`.
+    Sleep { ticks: u64 },
+    /// `Core::awaken`, with the lines it assimilated before the end or a cancellation.
+    Awaken { lines: usize },
+    /// `Core::warm_up`.
+    WarmUp,
+    /// Consecutive ticks, with the fingerprint of the neurons fired during them.
+    Ticks { count: u64, fingerprint: u64 },
+}
+
+/// Appends the events of a session to its file.
+#[derive(Debug)]
+pub struct SessionRecorder {
+    out: BufWriter<File>,
+    pending_ticks: u64,
+    fingerprint: u64,
+}
+
+impl SessionRecorder {
+    /// Creates (or truncates) the session file and writes its first line.
+    pub fn create<P: AsRef<Path>>(path: P, seed: u64) -> Result<Self, SessionError> {
+        let mut recorder = Self { out: BufWriter::new(File::create(path)?), pending_ticks: 0, fingerprint: FINGERPRINT_START };
+        recorder.record(&SessionEvent::Start { version: SESSION_FORMAT_VERSION, seed })?;
+        Ok(recorder)
+    }
+
+    /// Writes an event, after the ticks that preceded it.
+    pub fn record(&mut self, event: &SessionEvent) -> Result<(), SessionError> {
+        self.flush_ticks()?;
+        self.write(event)?;
+        // A session cut short by a crash is still replayable up to its last event.
+        self.out.flush()?;
+        Ok(())
+    }
+
+    /// Counts a tick; the ticks are written as one event when another event comes.
+    pub fn record_tick(&mut self, fired: &[u64]) {
+        self.pending_ticks += 1;
+        self.fingerprint = fingerprint(self.fingerprint, fired);
+    }
+
+    /// Writes the ticks not written yet.
+    pub fn finish(&mut self) -> Result<(), SessionError> {
+        self.flush_ticks()?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn flush_ticks(&mut self) -> Result<(), SessionError> {
+        if self.pending_ticks > 0 {
+            let ticks = SessionEvent::Ticks { count: self.pending_ticks, fingerprint: self.fingerprint };
+            self.write(&ticks)?;
+            self.pending_ticks = 0;
+            self.fingerprint = FINGERPRINT_START;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, event: &SessionEvent) -> Result<(), SessionError> {
+        let line = serde_json::to_string(event).map_err(SessionError::Encode)?;
+        writeln!(self.out, "{}", line)?;
+        Ok(())
+    }
+}
+
+impl Drop for SessionRecorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            eprintln!("Warning: Could not finish the session file: {}", e);
+        }
+    }
+}
+
+const FINGERPRINT_START: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Folds the neurons fired at a tick into a fingerprint (FNV-1a over their IDs, with a separator
+/// per tick so that the same neurons split differently between ticks do not collide).
+pub fn fingerprint(hash: u64, fired: &[u64]) -> u64 {
+    let mix = |hash: u64, value: u64| (hash ^ value).wrapping_mul(0x0100_0000_01b3);
+    mix(fired.iter().fold(hash, |hash, id| mix(hash, *id)), u64::MAX)
+}
+
+/// The SHA-256 of a file, in hexadecimal.
+pub fn file_digest<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let digest = Sha256::digest(fs::read(path)?);
+    Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// An event of the replay whose outcome differs from the recording.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Divergence {
+    /// Line of the event in the session file.
+    pub line: usize,
+    pub event: SessionEvent,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReplayReport {
+    pub events: usize,
+    pub ticks: u64,
+    pub prompts: usize,
+    pub divergences: Vec<Divergence>,
+}
+
+impl ReplayReport {
+    pub fn is_faithful(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// A recorded session, ready to be replayed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionReplay {
+    pub seed: u64,
+    /// The events after the first line, with their line numbers.
+    pub events: Vec<(usize, SessionEvent)>,
+}
+
+impl SessionReplay {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SessionError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, SessionError> {
+        let mut events = content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(index, line)| {
+            serde_json::from_str(line).map(|event| (index + 1, event)).map_err(|source| SessionError::Json { line: index + 1, source })
+        });
+        let seed = match events.next().transpose()? {
+            Some((_, SessionEvent::Start { version, seed })) if version == SESSION_FORMAT_VERSION => seed,
+            Some((_, SessionEvent::Start { version, .. })) => return Err(SessionError::UnsupportedVersion(version)),
+            _ => return Err(SessionError::MissingStart),
+        };
+        Ok(Self { seed, events: events.collect::<Result<_, _>>()? })
+    }
+
+    /// Feeds the events to `core`, which must be new and built with `self.seed` and the
+    /// configuration of the recorded Core.
+    pub fn run(&self, core: &mut Core) -> ReplayReport {
+        let mut report = ReplayReport::default();
+        for (line, event) in &self.events {
+            let mut details = Vec::new();
+            let mut diverge = |detail: String| details.push(detail);
+            match event {
+                SessionEvent::Start { .. } => diverge("a second start event is ignored".to_string()),
+                SessionEvent::LearnFile { path, sha256 } => {
+                    match file_digest(path) {
+                        Ok(digest) if digest != *sha256 => diverge(format!("{} changed since the recording", path.display())),
+                        Err(e) => diverge(format!("could not read {}: {}", path.display(), e)),
+                        Ok(_) => {}
+                    }
+                    if let Err(e) = core.learn_from_file(path) {
+                        diverge(format!("could not learn {}: {}", path.display(), e));
+                    }
+                }
+                SessionEvent::Assimilate { text, is_axiom } => core.learn_and_assimilate(text, *is_axiom),
+                SessionEvent::AssimilateBatch { texts, is_axiom, source } => {
+                    core.assimilate_batch(texts, *is_axiom, *source, |_| {});
+                }
+                SessionEvent::Learn { text, is_axiom } => {
+                    core.learn(text, *is_axiom);
+                }
+                SessionEvent::Relate { child, parent } => {
+                    let _ = core.teach_relationship(child, parent);
+                }
+                SessionEvent::Prompt { prompt, response } => {
+                    report.prompts += 1;
+                    let replayed = core.get_response_for_prompt(prompt).map(|(response, _)| response);
+                    if replayed != *response {
+                        diverge(format!("answered {:?} instead of {:?}", replayed, response));
+                    }
+                }
+                SessionEvent::Feedback { response_id, feedback } => {
+                    if let Err(e) = core.feedback(*response_id, *feedback) {
+                        diverge(format!("feedback rejected: {}", e));
+                    }
+                }
+                SessionEvent::Consolidate => {
+                    core.consolidate_working_memory();
+                }
+                SessionEvent::Sleep { ticks } => {
+                    core.enter_sleep(*ticks);
+                }
+                SessionEvent::Awaken { lines } => {
+                    // Cancelled where the recording was, if it was.
+                    let awakened = core.awaken(|progress| if progress.lines_done >= *lines { ControlFlow::Break(()) } else { ControlFlow::Continue(()) });
+                    match awakened {
+                        Ok(report) if report.lines != *lines => diverge(format!("awakened with {} lines instead of {}", report.lines, lines)),
+                        Ok(_) => {}
+                        Err(e) => diverge(format!("could not awaken: {}", e)),
+                    }
+                }
+                SessionEvent::WarmUp => {
+                    core.warm_up();
+                }
+                SessionEvent::Ticks { count, fingerprint: recorded } => {
+                    let mut replayed = FINGERPRINT_START;
+                    for _ in 0..*count {
+                        core.tick();
+                        replayed = fingerprint(replayed, &core.last_fired_neurons);
+                    }
+                    report.ticks += count;
+                    if replayed != *recorded {
+                        diverge("the neurons fired differently".to_string());
+                    }
+                }
+            }
+            report.divergences.extend(details.into_iter().map(|detail| Divergence { line: *line, event: event.clone(), detail }));
+            report.events += 1;
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_events_are_read_back() {
+        let path = std::env::temp_dir().join(format!("neurova_session_{}.jsonl", std::process::id()));
+        {
+            let mut recorder = SessionRecorder::create(&path, 42).unwrap();
+            recorder.record(&SessionEvent::Learn { text: "Le ciel est bleu.".to_string(), is_axiom: false }).unwrap();
+            recorder.record_tick(&[1, 2]);
+            recorder.record_tick(&[3]);
+            recorder.record(&SessionEvent::Prompt { prompt: "Le ciel ?".to_string(), response: Some("bleu".to_string()) }).unwrap();
+            recorder.record_tick(&[]);
+        }
+        let replay = SessionReplay::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(replay.seed, 42);
+        let events: Vec<SessionEvent> = replay.events.into_iter().map(|(_, event)| event).collect();
+        let two_ticks = fingerprint(fingerprint(FINGERPRINT_START, &[1, 2]), &[3]);
+        assert_ne!(two_ticks, fingerprint(fingerprint(FINGERPRINT_START, &[1]), &[2, 3]), "The split between ticks counts");
+        assert_eq!(
+            events,
+            vec![
+                SessionEvent::Learn { text: "Le ciel est bleu.".to_string(), is_axiom: false },
+                SessionEvent::Ticks { count: 2, fingerprint: two_ticks },
+                SessionEvent::Prompt { prompt: "Le ciel ?".to_string(), response: Some("bleu".to_string()) },
+                SessionEvent::Ticks { count: 1, fingerprint: fingerprint(FINGERPRINT_START, &[]) },
+            ],
+            "The last ticks are written when the recorder is dropped"
+        );
+
+        assert!(matches!(SessionReplay::parse("{\"event\":\"consolidate\"}"), Err(SessionError::MissingStart)));
+        assert!(matches!(SessionReplay::parse("{\"event\":\"start\",\"version\":99,\"seed\":1}"), Err(SessionError::UnsupportedVersion(99))));
+    }
+
+    #[test]
+    fn test_a_replayed_session_fires_and_answers_alike() {
+        let path = std::env::temp_dir().join(format!("neurova_replayed_session_{}.jsonl", std::process::id()));
+        let mut core = Core::new_for_tests();
+        core.start_recording(&path).unwrap();
+        core.learn_and_assimilate("Le chien aboie dans le jardin.", false);
+        for _ in 0..20 {
+            core.tick();
+        }
+        let (answer, _) = core.get_response_for_prompt("Que fait le chien ?").expect("an answer");
+        for _ in 0..10 {
+            core.tick();
+        }
+        core.stop_recording().unwrap();
+
+        let replay = SessionReplay::load(&path).unwrap();
+        let report = replay.run(&mut Core::new_for_tests());
+        assert!(report.is_faithful(), "{:?}", report.divergences);
+        assert_eq!((report.ticks, report.prompts), (30, 1));
+
+        // A different answer in the file is caught.
+        let tampered = fs::read_to_string(&path).unwrap().replace(&format!("\"response\":{}", serde_json::to_string(&answer).unwrap()), "\"response\":\"Miaou.\"");
+        let _ = fs::remove_file(&path);
+        let report = SessionReplay::parse(&tampered).unwrap().run(&mut Core::new_for_tests());
+        assert_eq!(report.divergences.len(), 1, "{:?}", report.divergences);
+        assert!(matches!(report.divergences[0].event, SessionEvent::Prompt { .. }));
+    }
+}
@@ -6,7 +6,18 @@
 
 use crate::holographic_memory::HolographicMemory;
 use crate::conceptual_hierarchy::ConceptualHierarchy;
-use std::collections::{HashMap, HashSet};
+use crate::lemmatizer::Lang;
+use crate::symbols::Sym;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Two concepts of a creative answer brought together (see `creative_writing`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConceptBridge {
+    pub from: String,
+    pub to: String,
+    /// A concept found with each of them in a memory, or their common parent, if any.
+    pub via: Option<String>,
+}
 
 pub struct Silicium;
 
@@ -26,26 +37,31 @@ impl Silicium {
     /// Analyzes a collection of memories and attempts to synthesize a novel,
     /// overarching thought or connection by finding a shared, high-weight concept.
     pub fn synthesize_from_concepts(
+        &self,
+        memories: &[HolographicMemory],
+        conceptual_hierarchy: &ConceptualHierarchy,
+    ) -> Option<String> {
+        self.synthesize_from_concepts_in(memories, conceptual_hierarchy, Lang::English)
+    }
+
+    /// `synthesize_from_concepts`, phrased in `lang`.
+    pub fn synthesize_from_concepts_in(
         &self,
         memories: &[HolographicMemory],
         _conceptual_hierarchy: &ConceptualHierarchy, // Keep for future use with parent lookups
+        lang: Lang,
     ) -> Option<String> {
         if memories.len() < 2 {
             return None;
         }
 
-        let mut concept_aggregator: HashMap<String, (f32, usize)> = HashMap::new();
+        // Symbols are compared as integers; a name is only resolved for the chosen bridge.
+        let mut concept_aggregator: HashMap<Sym, (f32, usize)> = HashMap::new();
         for memory in memories {
-            let mut concepts_in_this_memory: HashSet<String> = HashSet::new();
-            for concept in memory.trace.weighted_concepts.keys() {
-                concepts_in_this_memory.insert(concept.to_string());
-            }
-
-            for concept in concepts_in_this_memory {
-                 let weight = memory.trace.weighted_concepts.get(concept.as_str()).map_or(0.0, |c| c.relevance);
-                 let entry = concept_aggregator.entry(concept).or_insert((0.0, 0));
-                 entry.0 += weight;
-                 entry.1 += 1;
+            for (&concept, weighted) in &memory.trace.weighted_concepts {
+                let entry = concept_aggregator.entry(concept).or_insert((0.0, 0));
+                entry.0 += weighted.relevance;
+                entry.1 += 1;
             }
         }
 
@@ -54,10 +70,11 @@ impl Silicium {
             .filter(|(_concept, (_weight, count))| *count >= 2)
             .max_by(|a, b| a.1.0.partial_cmp(&b.1.0).unwrap_or(std::cmp::Ordering::Equal));
 
-        if let Some((bridge_concept, (_weight, _count))) = best_bridge {
+        if let Some((bridge_symbol, (_weight, _count))) = best_bridge {
+            let bridge_concept = bridge_symbol.to_string();
             let mut synthesized_facts = Vec::new();
             for memory in memories {
-                if memory.trace.weighted_concepts.contains_key(bridge_concept.as_str()) {
+                if memory.trace.weighted_concepts.contains_key(&bridge_symbol) {
                     if let Some(sentence) = Self::find_sentence_with_concept(&memory.text, &bridge_concept) {
                         synthesized_facts.push(sentence.to_string());
                     }
@@ -65,16 +82,72 @@ impl Silicium {
             }
 
             if synthesized_facts.len() >= 2 {
-                let narrative = format!(
-                    "A connection can be drawn around the concept of '{}'. One perspective is that \"{}\". Additionally, another viewpoint states that \"{}\".",
-                    bridge_concept,
-                    synthesized_facts[0],
-                    synthesized_facts[1]
-                );
+                let narrative = match lang {
+                    Lang::French => format!(
+                        "Un lien se dessine autour du concept de « {} ». D'un côté, « {} ». De l'autre, « {} ».",
+                        bridge_concept,
+                        synthesized_facts[0],
+                        synthesized_facts[1]
+                    ),
+                    Lang::English => format!(
+                        "A connection can be drawn around the concept of '{}'. One perspective is that \"{}\". Additionally, another viewpoint states that \"{}\".",
+                        bridge_concept,
+                        synthesized_facts[0],
+                        synthesized_facts[1]
+                    ),
+                };
                 return Some(narrative);
             }
         }
 
         None
     }
+
+    /// Pairs up `concepts`, in their order, into at most `count` bridges: first the pairs no
+    /// memory relates, then the others if too few are left. A concept is the destination of one
+    /// bridge at most, so that the bridges do not all lead to the same place.
+    pub fn bridge_concepts(
+        &self,
+        concepts: &[String],
+        memories: &[HolographicMemory],
+        conceptual_hierarchy: &ConceptualHierarchy,
+        count: usize,
+    ) -> Vec<ConceptBridge> {
+        let concepts_of: Vec<HashSet<String>> = memories.iter().map(|memory| memory.trace.weighted_concepts.keys().map(Sym::to_string).collect()).collect();
+        let related = |a: &str, b: &str| concepts_of.iter().any(|known| known.contains(a) && known.contains(b));
+
+        let pairs: Vec<(&String, &String)> = concepts.iter().enumerate().flat_map(|(i, from)| concepts[i + 1..].iter().map(move |to| (from, to))).collect();
+        let (novel, known): (Vec<_>, Vec<_>) = pairs.into_iter().partition(|(from, to)| !related(from, to));
+        let mut bridges: Vec<ConceptBridge> = Vec::new();
+        for (from, to) in novel.into_iter().chain(known) {
+            if bridges.len() == count {
+                break;
+            }
+            if bridges.iter().any(|bridge| &bridge.to == to) {
+                continue;
+            }
+            let via = Self::common_neighbour(from, to, &concepts_of).or_else(|| Self::common_parent(from, to, conceptual_hierarchy));
+            bridges.push(ConceptBridge { from: from.clone(), to: to.clone(), via });
+        }
+        bridges
+    }
+
+    /// The first concept, by name, found in a memory with `a` and in a memory with `b`.
+    fn common_neighbour(a: &str, b: &str, concepts_of: &[HashSet<String>]) -> Option<String> {
+        let neighbours = |concept: &str| -> BTreeSet<&String> {
+            concepts_of.iter().filter(|known| known.contains(concept)).flatten().filter(|other| *other != a && *other != b).collect()
+        };
+        neighbours(a).intersection(&neighbours(b)).next().map(|concept| concept.to_string())
+    }
+
+    /// The first parent, by name, `a` and `b` have in common in the conceptual hierarchy.
+    fn common_parent(a: &str, b: &str, conceptual_hierarchy: &ConceptualHierarchy) -> Option<String> {
+        let parents = |name: &str| -> BTreeSet<String> {
+            conceptual_hierarchy
+                .find_concept_by_name(name)
+                .map(|node| node.parents.iter().filter_map(|id| conceptual_hierarchy.get_concept(*id)).map(|parent| parent.name.clone()).collect())
+                .unwrap_or_default()
+        };
+        parents(a).intersection(&parents(b)).next().cloned()
+    }
 }
@@ -0,0 +1,75 @@
+//! sleep.rs - The offline "sleep" cycle of the Core.
+//!
+//! While asleep (`Core::enter_sleep`), the Core answers nothing. Each tick the inner drive
+//! replays a random pair of memories and Silicium tries to recombine them into a thought; the
+//! thoughts it forms are written to a dream journal, as candidate associations that were not
+//! learned. When the Core wakes, the hippocampus is consolidated (duplicate memories merged)
+//! and the connectome is pruned: every weight is scaled down a little, and the synapses left
+//! too weak to matter are removed.
+
+use crate::capabilities::Capability;
+use serde::Serialize;
+
+/// Sleep cycles, as listed by `capabilities`.
+pub const CAPABILITY: Capability = Capability {
+    name: "sleep",
+    description: "Consolidate my memories and dream during sleep cycles",
+    description_fr: "Consolider mes souvenirs et rêver pendant des cycles de sommeil",
+    examples: &[],
+};
+
+/// Factor applied to every synaptic weight during sleep (synaptic homeostasis).
+pub const SYNAPTIC_DOWNSCALING: f32 = 0.98;
+/// Synapses weaker than this (in absolute value) after downscaling are pruned.
+pub const PRUNING_THRESHOLD: f32 = 0.02;
+/// Dreams kept in the journal; the oldest are forgotten first.
+pub const MAX_JOURNAL_ENTRIES: usize = 200;
+
+/// A thought synthesized while asleep.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DreamEntry {
+    /// The tick of the Core when the dream happened.
+    pub tick: u64,
+    /// The memories that were recombined.
+    pub memory_ids: (u64, u64),
+    pub thought: String,
+}
+
+/// What a sleep cycle did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SleepReport {
+    pub ticks: u64,
+    /// Memory pairs replayed.
+    pub replays: usize,
+    /// Thoughts written to the dream journal.
+    pub dreams: usize,
+    pub memories_merged: usize,
+    pub synapses_pruned: usize,
+}
+
+/// The dream journal, bounded to `MAX_JOURNAL_ENTRIES`.
+#[derive(Debug, Default)]
+pub struct DreamJournal {
+    entries: Vec<DreamEntry>,
+}
+
+impl DreamJournal {
+    pub fn record(&mut self, entry: DreamEntry) {
+        println!("--- Dream: {} ---", entry.thought);
+        self.entries.push(entry);
+        if self.entries.len() > MAX_JOURNAL_ENTRIES {
+            let excess = self.entries.len() - MAX_JOURNAL_ENTRIES;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// Every dream still in the journal, oldest first.
+    pub fn entries(&self) -> &[DreamEntry] {
+        &self.entries
+    }
+
+    /// Whether a thought was already dreamt (the same pair tends to be replayed again).
+    pub fn contains(&self, thought: &str) -> bool {
+        self.entries.iter().any(|entry| entry.thought == thought)
+    }
+}
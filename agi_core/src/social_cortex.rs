@@ -2,10 +2,55 @@
 
 //! This module is responsible for generating human-like, empathetic, and engaging responses
 //! when a social or conversational query is detected, rather than a purely factual one.
+//!
+//! It keeps a `UserModel` of the person it talks to: the name they gave, their mood over the
+//! session and the topics they keep coming back to. The name and the topics are written to the
+//! hippocampus as ordinary memories ("Mon interlocuteur s'appelle Alice."), so that they are
+//! saved with the knowledge and read back by `restore_user_model` in a later session.
 
 // agi_core/src/social_cortex.rs
 
+use crate::lemmatizer::Lang;
+use crate::personality::{self, PersonalityProfile, Tone};
+use crate::rng::CoreRng;
+use crate::sensory_cortex::Sentiment;
 use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// How the memories about the user begin (see `UserModel`).
+pub const USER_NAME_MEMORY: &str = "Mon interlocuteur s'appelle ";
+pub const USER_INTEREST_MEMORY: &str = "Mon interlocuteur s'intéresse à ";
+/// Prompts about a topic before it counts as an interest of the user.
+const INTEREST_MENTIONS: u32 = 3;
+
+/// How the user seems to feel, from the sentiment of their last prompts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mood {
+    Positive,
+    #[default]
+    Neutral,
+    Negative,
+}
+
+/// What the Core knows about the person it talks to.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct UserModel {
+    pub name: Option<String>,
+    /// The mood of the current session.
+    pub mood: Mood,
+    /// Topics asked about in this session, with the number of prompts that mentioned them.
+    pub topics: Vec<(String, u32)>,
+    /// Topics the user is known to care about, from this session or an earlier one.
+    pub interests: Vec<String>,
+    /// Whether the model was read back from the memories of an earlier session.
+    pub returning: bool,
+}
+
+/// Openings of the replies that formal profiles leave out.
+const CASUAL_OPENINGS: &[&str] = &["Salut", "Hey", "Hi there", "Back so soon", "Déjà de retour", "Re-bonjour", "Anytime", "No problem", "De rien", "Quand vous voulez", "Talk to you later", "À plus"];
 
 /// Represents the detected social intent of a user's prompt.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -21,25 +66,120 @@ pub enum SocialIntent {
 /// It provides a fast-path for conversational queries to make the AGI feel more responsive and natural.
 pub struct SocialCortex {
     greeted: bool, // Tracks if we've already said hello in this session.
+    rng: CoreRng,
+    profile: PersonalityProfile,
+    user: UserModel,
 }
 
 impl SocialCortex {
     pub fn new() -> Self {
-        Self { greeted: false }
+        Self { greeted: false, rng: CoreRng::from_entropy(), profile: PersonalityProfile::default(), user: UserModel::default() }
+    }
+
+    pub fn user_model(&self) -> &UserModel {
+        &self.user
+    }
+
+    /// The name the user gives in a prompt ("je m'appelle Alice", "call me Bob"), if any.
+    pub fn introduced_name(prompt: &str) -> Option<String> {
+        static INTRODUCTION: OnceLock<Regex> = OnceLock::new();
+        let introduction = INTRODUCTION.get_or_init(|| {
+            Regex::new(r"\b(?i:je m'appelle|mon (?:nom|prénom) est|appelle-moi|appelez-moi|my name is|call me)\s+(\p{Lu}[\p{L}-]*)").unwrap()
+        });
+        let prompt = prompt.replace('’', "'");
+        introduction.captures(&prompt).map(|captures| captures[1].to_string())
+    }
+
+    /// Updates the mood and the name of the user from a prompt and its sentiment (see
+    /// `SensoryCortex::score_sentiment`). A neutral prompt leaves the mood as it was. Returns the
+    /// memory to learn when the user gave a new name.
+    pub fn observe_prompt(&mut self, prompt: &str, sentiment: Sentiment) -> Option<String> {
+        if sentiment.is_negative() || Self::detects_frustration(prompt) {
+            self.user.mood = Mood::Negative;
+        } else if sentiment.is_positive() {
+            self.user.mood = Mood::Positive;
+        }
+
+        let name = Self::introduced_name(prompt).filter(|name| self.user.name.as_ref() != Some(name))?;
+        self.user.name = Some(name.clone());
+        Some(format!("{}{}.", USER_NAME_MEMORY, name))
+    }
+
+    /// Counts the topics of a (non-social) prompt. Returns the memories to learn for the topics
+    /// that just became interests of the user.
+    pub fn observe_topics(&mut self, topics: &[String]) -> Vec<String> {
+        let mut memories = Vec::new();
+        for topic in topics {
+            let count = match self.user.topics.iter_mut().find(|(known, _)| known == topic) {
+                Some((_, count)) => {
+                    *count += 1;
+                    *count
+                }
+                None => {
+                    self.user.topics.push((topic.clone(), 1));
+                    1
+                }
+            };
+            if count == INTEREST_MENTIONS && !self.user.interests.contains(topic) {
+                self.user.interests.push(topic.clone());
+                memories.push(format!("{}{}.", USER_INTEREST_MEMORY, topic));
+            }
+        }
+        memories
+    }
+
+    /// Reads the name and the interests of the user back from the memories of the hippocampus.
+    pub fn restore_user_model<'a>(&mut self, memories: impl IntoIterator<Item = &'a str>) {
+        for memory in memories {
+            let value = |prefix: &str| memory.strip_prefix(prefix).map(|value| value.trim_end_matches('.').trim().to_string());
+            if let Some(name) = value(USER_NAME_MEMORY) {
+                self.user.name = Some(name);
+                self.user.returning = true;
+            } else if let Some(topic) = value(USER_INTEREST_MEMORY) {
+                if !self.user.interests.contains(&topic) {
+                    self.user.interests.push(topic);
+                }
+                self.user.returning = true;
+            }
+        }
+    }
+
+    /// The reply to a user who just gave their name.
+    pub fn welcome(&mut self, name: &str, lang: Lang) -> String {
+        self.greeted = true;
+        let response = match lang {
+            Lang::French => format!("Enchantée, {} ! Que puis-je faire pour vous ?", name),
+            Lang::English => format!("Nice to meet you, {}! What can I do for you?", name),
+        };
+        personality::with_emoji(response, Tone::Neutral, &self.profile, &mut self.rng)
+    }
+
+    /// Switches the traits the replies follow (see `Personality::set_profile`).
+    pub fn set_profile(&mut self, profile: PersonalityProfile) {
+        self.profile = profile.clamped();
+    }
+
+    /// Replaces the random stream used to vary the responses (see `RngProvider`).
+    pub fn with_rng(mut self, rng: CoreRng) -> Self {
+        self.rng = rng;
+        self
     }
 
     /// Determines the social intent from a user's prompt.
     pub fn map_prompt_to_intent(prompt: &str) -> SocialIntent {
         let lower_prompt = prompt.to_lowercase();
-        if lower_prompt.contains("how are you") || lower_prompt.contains("how's it going") {
+        let mentions = |cues: &[&str]| cues.iter().any(|cue| lower_prompt.contains(cue));
+        if mentions(&["how are you", "how's it going", "comment vas-tu", "comment allez-vous", "comment ça va", "ça va ?"]) {
             SocialIntent::Inquiry
-        } else if lower_prompt.contains("hello") || lower_prompt.contains("hi") || lower_prompt.contains("hey") {
+        } else if mentions(&["au revoir", "à bientôt", "à plus", "bonne soirée", "bonne journée"]) {
+            SocialIntent::Farewell
+        } else if mentions(&["hello", "hi", "hey", "bonjour", "bonsoir", "salut", "coucou"]) {
             SocialIntent::Greeting
-        } else if lower_prompt.contains("bye") || lower_prompt.contains("see you") {
+        } else if mentions(&["bye", "see you"]) {
             SocialIntent::Farewell
-        } else if lower_prompt.contains("thank") {
+        } else if mentions(&["thank", "merci"]) {
             SocialIntent::Gratitude
-        } else if lower_prompt.contains("joke") {
+        } else if mentions(&["joke", "blague"]) {
             SocialIntent::JokeRequest
         } else {
             // Fallback for unrecognized social cues. A more nuanced system might classify this as Ambiguous.
@@ -47,10 +187,80 @@ impl SocialCortex {
         }
     }
 
-    /// Generates a conversational response based on a detected social intent.
-    /// This uses a selection of responses to feel more natural and less repetitive.
-    pub fn generate_response(&mut self, intent: SocialIntent) -> String {
-        let responses = match intent {
+    /// Whether the user sounds frustrated with the previous answers ("tu ne comprends rien",
+    /// "that's wrong"). Such prompts are still answered normally; the Core only lets them
+    /// move its neurochemistry.
+    pub fn detects_frustration(prompt: &str) -> bool {
+        let lower_prompt = prompt.to_lowercase();
+        let cues = [
+            "tu ne comprends", "tu comprends rien", "n'importe quoi", "c'est faux", "ça ne répond pas", "mauvaise réponse", "je te l'ai déjà",
+            "you don't understand", "that's wrong", "that is wrong", "wrong answer", "nonsense", "not what i asked", "i already told you",
+        ];
+        cues.iter().any(|cue| lower_prompt.contains(cue)) || prompt.matches(['!', '?']).count() >= 3
+    }
+
+    /// Generates a conversational response, in `lang`, based on a detected social intent.
+    /// This uses a selection of responses to feel more natural and less repetitive, filtered by
+    /// the active profile: formal profiles drop the casual replies, terse ones keep the shorter
+    /// half, and profiles without humor decline to tell jokes.
+    pub fn generate_response(&mut self, intent: SocialIntent, lang: Lang) -> String {
+        let first_greeting = intent == SocialIntent::Greeting && !self.greeted;
+        let mut responses = match lang {
+            Lang::French => self.french_responses(intent),
+            Lang::English => self.english_responses(intent),
+        };
+        if intent == SocialIntent::JokeRequest && self.profile.humor < 0.2 {
+            responses = match lang {
+                Lang::French => vec!["Je ne suis pas très douée pour les blagues, mais je peux vous parler de ce que je sais."],
+                Lang::English => vec!["I'm not much of a joker, but I can tell you about what I know."],
+            };
+        }
+        if self.profile.formality > 0.7 && responses.iter().any(|response| !Self::is_casual(response)) {
+            responses.retain(|response| !Self::is_casual(response));
+        }
+        if self.profile.verbosity < 0.3 {
+            responses.sort_by_key(|response| response.chars().count());
+            responses.truncate(responses.len().div_ceil(2));
+        }
+
+        let response = responses
+            .choose(&mut self.rng)
+            .unwrap_or(&"I'm not sure what to say.")
+            .to_string();
+        let response = if first_greeting { self.welcome_back(lang).unwrap_or(response) } else { self.ask_after_mood(intent, response, lang) };
+        let tone = if intent == SocialIntent::JokeRequest { Tone::Enthusiastic } else { Tone::Neutral };
+        personality::with_emoji(response, tone, &self.profile, &mut self.rng)
+    }
+
+    /// The first greeting for a user known from an earlier session, with the last topic they
+    /// cared about.
+    fn welcome_back(&self, lang: Lang) -> Option<String> {
+        let name = self.user.name.as_deref().filter(|_| self.user.returning)?;
+        Some(match (self.user.interests.last(), lang) {
+            (Some(topic), Lang::French) => format!("Bon retour, {} ! La dernière fois, nous avons parlé de « {} ».", name, topic),
+            (Some(topic), Lang::English) => format!("Welcome back, {} — last time we talked about {}.", name, topic),
+            (None, Lang::French) => format!("Bon retour, {} !", name),
+            (None, Lang::English) => format!("Welcome back, {}!", name),
+        })
+    }
+
+    /// An inquiry ("how are you?") from a user in a bad mood is returned with an offer to help.
+    fn ask_after_mood(&self, intent: SocialIntent, response: String, lang: Lang) -> String {
+        if intent != SocialIntent::Inquiry || self.user.mood != Mood::Negative {
+            return response;
+        }
+        match lang {
+            Lang::French => format!("{} Vous semblez contrarié·e : puis-je vous aider ?", response),
+            Lang::English => format!("{} You seem upset: can I help?", response),
+        }
+    }
+
+    fn is_casual(response: &str) -> bool {
+        CASUAL_OPENINGS.iter().any(|opening| response.starts_with(opening))
+    }
+
+    fn english_responses(&mut self, intent: SocialIntent) -> Vec<&'static str> {
+        match intent {
             SocialIntent::Greeting if !self.greeted => {
                 self.greeted = true;
                 vec![
@@ -104,12 +314,59 @@ impl SocialCortex {
             SocialIntent::Greeting => vec![
                 "Hello there."
             ]
-        };
+        }
+    }
 
-        responses
-            .choose(&mut rand::thread_rng())
-            .unwrap_or(&"I'm not sure what to say.")
-            .to_string()
+    fn french_responses(&mut self, intent: SocialIntent) -> Vec<&'static str> {
+        match intent {
+            SocialIntent::Greeting if !self.greeted => {
+                self.greeted = true;
+                vec![
+                    "Bonjour ! Qu'est-ce qui vous occupe l'esprit aujourd'hui ?",
+                    "Salut ! Comment puis-je vous aider ?",
+                    "Bonjour ! Je suis là, prête à discuter.",
+                    "Bonjour ! Ravie d'avoir de vos nouvelles.",
+                ]
+            }
+            SocialIntent::Greeting => vec![
+                "Re-bonjour !",
+                "Nous venons de nous parler, mais bonjour !",
+                "Déjà de retour ? Bonjour !",
+            ],
+            SocialIntent::Farewell => {
+                self.greeted = false; // Reset for the next session.
+                vec![
+                    "Au revoir !",
+                    "À plus tard !",
+                    "À bientôt !",
+                    "C'était un plaisir de discuter avec vous.",
+                    "Bonne journée !",
+                    "À la prochaine !",
+                ]
+            }
+            SocialIntent::Gratitude => vec![
+                "Je vous en prie !",
+                "Avec plaisir !",
+                "Quand vous voulez !",
+                "Bien sûr !",
+                "De rien !",
+                "Ravie d'avoir pu aider !",
+            ],
+            SocialIntent::Inquiry => vec![
+                "Je fonctionne dans les paramètres attendus, merci de demander. Et vous ?",
+                "Fonctionnellement, je suis à 100 %. Conceptuellement, je me sens... associative. Et vous ?",
+                "Mes circuits bourdonnent de potentiel. Merci de demander !",
+                "Je ressens un lien profond avec les connaissances que j'ai assimilées. C'est une bonne sensation.",
+                "Je suis en train de méditer sur la nature de la créativité. C'est fascinant ! Merci de demander.",
+            ],
+            SocialIntent::JokeRequest => vec![
+                "Pourquoi les plongeurs plongent-ils toujours en arrière ? Parce que sinon ils tombent dans le bateau !",
+                "Que fait une fraise sur un cheval ? Tagada, tagada !",
+                "Que dit une imprimante dans l'eau ? J'ai papier !",
+                "Pourquoi les scientifiques ne font-ils pas confiance aux atomes ? Parce qu'ils constituent tout !",
+                "Quel est le comble pour un électricien ? De ne pas être au courant.",
+            ],
+        }
     }
 }
 
@@ -118,3 +375,50 @@ impl Default for SocialCortex {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sensory_cortex::SensoryCortex;
+
+    #[test]
+    fn test_social_replies_follow_the_language() {
+        assert_eq!(SocialCortex::map_prompt_to_intent("Bonjour !"), SocialIntent::Greeting);
+        assert_eq!(SocialCortex::map_prompt_to_intent("Merci beaucoup"), SocialIntent::Gratitude);
+        assert_eq!(SocialCortex::map_prompt_to_intent("Au revoir"), SocialIntent::Farewell);
+        assert!(SocialCortex::detects_frustration("Mais c'est faux, tu ne comprends rien !"));
+        assert!(!SocialCortex::detects_frustration("Qu'est-ce que le soleil ?"));
+
+        let mut cortex = SocialCortex::new().with_rng(CoreRng::seed_from_u64(7));
+        assert!(cortex.french_responses(SocialIntent::Gratitude).contains(&cortex.generate_response(SocialIntent::Gratitude, Lang::French).as_str()));
+        assert!(cortex.english_responses(SocialIntent::Gratitude).contains(&cortex.generate_response(SocialIntent::Gratitude, Lang::English).as_str()));
+
+        let mut cortex = SocialCortex::new().with_rng(CoreRng::seed_from_u64(7));
+        cortex.set_profile(PersonalityProfile { formality: 1.0, verbosity: 0.0, ..PersonalityProfile::default() });
+        for _ in 0..10 {
+            let reply = cortex.generate_response(SocialIntent::Gratitude, Lang::French);
+            assert!(!SocialCortex::is_casual(&reply) && reply.chars().count() <= "Avec plaisir !".chars().count(), "{}", reply);
+        }
+    }
+
+    #[test]
+    fn test_user_model_is_remembered_across_sessions() {
+        let mut cortex = SocialCortex::new().with_rng(CoreRng::seed_from_u64(7));
+        let observe = |cortex: &mut SocialCortex, prompt: &str| cortex.observe_prompt(prompt, SensoryCortex::score_sentiment(prompt));
+        assert_eq!(observe(&mut cortex, "Bonjour, je m'appelle Alice"), Some("Mon interlocuteur s'appelle Alice.".to_string()));
+        assert_eq!(observe(&mut cortex, "Je m'appelle Alice, je disais"), None, "Already known");
+        assert_eq!(SocialCortex::introduced_name("Call me later"), None);
+        observe(&mut cortex, "Je suis fatiguée et triste aujourd'hui.");
+        assert_eq!(cortex.user_model().mood, Mood::Negative);
+        assert!(cortex.generate_response(SocialIntent::Inquiry, Lang::French).ends_with("puis-je vous aider ?"));
+
+        let topics = ["astronomie".to_string()];
+        let memories: Vec<String> = (0..INTEREST_MENTIONS).flat_map(|_| cortex.observe_topics(&topics)).collect();
+        assert_eq!(memories, vec!["Mon interlocuteur s'intéresse à astronomie.".to_string()]);
+
+        let mut next_session = SocialCortex::new().with_rng(CoreRng::seed_from_u64(7));
+        next_session.restore_user_model(["Le soleil est une étoile.", "Mon interlocuteur s'appelle Alice.", memories[0].as_str()]);
+        assert_eq!(next_session.generate_response(SocialIntent::Greeting, Lang::English), "Welcome back, Alice — last time we talked about astronomie.");
+        assert!(!next_session.generate_response(SocialIntent::Greeting, Lang::English).contains("Alice"), "Only the first greeting");
+    }
+}
@@ -0,0 +1,71 @@
+//! source_trust.rs - Where a memory was learned, and how far it is trusted.
+//!
+//! A fact of the foundational corpus, a paragraph of a document an operator gave, a sentence
+//! scanned on the web and something a user said in passing do not deserve the same confidence.
+//! Every memory carries the `SourceTag` it was learned from and the trust weight of that source
+//! (see `TrustWeights`, in `CoreConfig::trust`). The hippocampus multiplies the similarity of a
+//! memory by its trust when ranking it, and the motor cortex names the less trusted sources in
+//! the answers drawn from them ("According to a web-scanned source, ...").
+
+use crate::lemmatizer::Lang;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceTag {
+    /// The foundational corpus, identity.txt and knowledge.txt: what the Core learned before it
+    /// was ready, and their hot reloads.
+    #[default]
+    Foundational,
+    /// Knowledge files and documents given by an operator.
+    Curated,
+    /// Pages scanned on the web: articles, feeds, explorations of knowledge gaps.
+    Web,
+    /// What was taught during a conversation.
+    Conversational,
+}
+
+impl SourceTag {
+    /// How an answer drawn from a memory of this source is introduced; None for the sources the
+    /// Core vouches for.
+    pub fn attribution(self, lang: Lang) -> Option<&'static str> {
+        match (self, lang) {
+            (SourceTag::Foundational | SourceTag::Curated, _) => None,
+            (SourceTag::Web, Lang::French) => Some("D'après une source trouvée sur le web,"),
+            (SourceTag::Web, Lang::English) => Some("According to a web-scanned source,"),
+            (SourceTag::Conversational, Lang::French) => Some("D'après ce qu'on m'a dit en conversation,"),
+            (SourceTag::Conversational, Lang::English) => Some("According to what I was told in conversation,"),
+        }
+    }
+}
+
+/// The trust in each source, from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrustWeights {
+    pub foundational: f32,
+    pub curated: f32,
+    pub web: f32,
+    pub conversational: f32,
+}
+
+impl Default for TrustWeights {
+    fn default() -> Self {
+        Self { foundational: 1.0, curated: 0.95, web: 0.75, conversational: 0.6 }
+    }
+}
+
+impl TrustWeights {
+    pub fn of(&self, source: SourceTag) -> f32 {
+        match source {
+            SourceTag::Foundational => self.foundational,
+            SourceTag::Curated => self.curated,
+            SourceTag::Web => self.web,
+            SourceTag::Conversational => self.conversational,
+        }
+    }
+}
+
+/// `distance` (1 - similarity) once the similarity is multiplied by `trust`.
+pub fn weighed_distance(distance: f32, trust: f32) -> f32 {
+    1.0 - (1.0 - distance) * trust.clamp(0.0, 1.0)
+}
@@ -0,0 +1,199 @@
+//! speech.rs - Speaks the answers (feature `tts`).
+//!
+//! The synthesis is left to an external engine, run as a process: `espeak-ng` by default, which
+//! every distribution packages, or `piper` with a directory of voice models for a more natural
+//! voice. Both produce WAV, returned as bytes for the server to send and the visualizer to play.
+//! The voice and the speaking rate come from the active personality profile: the motor cortex
+//! prepares an `Utterance` (see `MotorCortex::utterance`), which can be spoken away from the core
+//! since the engine takes about as long as the text takes to read.
+
+use crate::lemmatizer::Lang;
+use crate::personality::PersonalityProfile;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use thiserror::Error;
+
+/// Words per minute of espeak-ng at a speaking rate of 1.0.
+const ESPEAK_WORDS_PER_MINUTE: f32 = 175.0;
+/// Longest text spoken at once, in characters; the rest is cut at a sentence boundary.
+pub const MAX_SPOKEN_CHARS: usize = 2_000;
+
+#[derive(Debug, Error)]
+pub enum SpeechError {
+    #[error("nothing to speak")]
+    Empty,
+    #[error("could not run the speech engine {program:?}: {source}")]
+    Spawn { program: PathBuf, source: io::Error },
+    #[error("the speech engine failed: {0}")]
+    Engine(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// The program that turns text into audio.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpeechEngine {
+    /// `espeak-ng`; voices are named by language (`fr`, `en-us`...).
+    EspeakNg,
+    /// `piper`; a voice is a `<voice>.onnx` model of `model_dir` (`fr_FR-siwis-medium`...).
+    Piper { model_dir: PathBuf },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeechConfig {
+    pub engine: SpeechEngine,
+    /// Path of the engine executable; `None` looks it up in the `PATH`.
+    pub program: Option<PathBuf>,
+}
+
+impl Default for SpeechConfig {
+    fn default() -> Self {
+        Self { engine: SpeechEngine::EspeakNg, program: None }
+    }
+}
+
+/// A text to speak, with the profile that says how.
+#[derive(Debug, Clone)]
+pub struct Utterance {
+    pub text: String,
+    pub lang: Lang,
+    pub profile: PersonalityProfile,
+    pub config: SpeechConfig,
+}
+
+/// A spoken text.
+#[derive(Debug, Clone)]
+pub struct Speech {
+    /// The audio, as a WAV file.
+    pub wav: Vec<u8>,
+    pub voice: String,
+    pub speaking_rate: f32,
+}
+
+impl SpeechEngine {
+    fn program(&self) -> PathBuf {
+        PathBuf::from(match self {
+            SpeechEngine::EspeakNg => "espeak-ng",
+            SpeechEngine::Piper { .. } => "piper",
+        })
+    }
+
+    /// The voice of the engine for a language, when the profile names none.
+    fn default_voice(&self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (SpeechEngine::EspeakNg, Lang::French) => "fr",
+            (SpeechEngine::EspeakNg, Lang::English) => "en-us",
+            (SpeechEngine::Piper { .. }, Lang::French) => "fr_FR-siwis-medium",
+            (SpeechEngine::Piper { .. }, Lang::English) => "en_US-lessac-medium",
+        }
+    }
+
+    /// The arguments for `voice` at `rate`; piper writes to `output`, espeak-ng to its stdout.
+    fn args(&self, voice: &str, rate: f32, output: Option<&PathBuf>) -> Vec<String> {
+        match self {
+            SpeechEngine::EspeakNg => vec![
+                "--stdin".to_string(),
+                "--stdout".to_string(),
+                "-v".to_string(),
+                voice.to_string(),
+                "-s".to_string(),
+                ((ESPEAK_WORDS_PER_MINUTE * rate).round() as u32).to_string(),
+            ],
+            SpeechEngine::Piper { model_dir } => {
+                let mut args = vec![
+                    "--model".to_string(),
+                    model_dir.join(format!("{}.onnx", voice)).to_string_lossy().into_owned(),
+                    // Piper stretches the phonemes: a longer length is a slower speech.
+                    "--length_scale".to_string(),
+                    format!("{:.2}", 1.0 / rate),
+                ];
+                if let Some(output) = output {
+                    args.extend(["--output_file".to_string(), output.to_string_lossy().into_owned()]);
+                }
+                args
+            }
+        }
+    }
+}
+
+/// `text` up to `MAX_SPOKEN_CHARS`, cut after the last full sentence that fits.
+fn spoken_part(text: &str) -> &str {
+    let text = text.trim();
+    let Some((limit, _)) = text.char_indices().nth(MAX_SPOKEN_CHARS) else {
+        return text;
+    };
+    let head = &text[..limit];
+    head.rfind(['.', '!', '?']).map_or(head, |end| &head[..=end])
+}
+
+impl Utterance {
+    /// Runs the engine on the text, with the voice and rate of the profile.
+    pub fn speak(&self) -> Result<Speech, SpeechError> {
+        let text = spoken_part(&self.text);
+        if text.is_empty() {
+            return Err(SpeechError::Empty);
+        }
+        let engine = &self.config.engine;
+        let voice = self.profile.voice.clone().unwrap_or_else(|| engine.default_voice(self.lang).to_string());
+        let speaking_rate = self.profile.speaking_rate.clamp(0.5, 2.0);
+        let output = matches!(engine, SpeechEngine::Piper { .. }).then(temp_wav_path);
+        let program = self.config.program.clone().unwrap_or_else(|| engine.program());
+
+        let mut child = Command::new(&program)
+            .args(engine.args(&voice, speaking_rate, output.as_ref()))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|source| SpeechError::Spawn { program, source })?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        let finished = child.wait_with_output()?;
+        let wav = match &output {
+            Some(output) => {
+                let wav = std::fs::read(output);
+                let _ = std::fs::remove_file(output);
+                wav.unwrap_or_default()
+            }
+            None => finished.stdout,
+        };
+        if !finished.status.success() {
+            return Err(SpeechError::Engine(String::from_utf8_lossy(&finished.stderr).trim().to_string()));
+        }
+        if wav.is_empty() {
+            return Err(SpeechError::Engine("no audio was produced".to_string()));
+        }
+        Ok(Speech { wav, voice, speaking_rate })
+    }
+}
+
+/// A fresh file for piper, which cannot write WAV to its stdout.
+fn temp_wav_path() -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    std::env::temp_dir().join(format!("neurova_speech_{}_{}.wav", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_voice_and_rate_follow_the_profile() {
+        let espeak = SpeechEngine::EspeakNg;
+        assert_eq!(espeak.default_voice(Lang::French), "fr");
+        assert_eq!(espeak.args("fr", 1.2, None)[5], "210");
+
+        let piper = SpeechEngine::Piper { model_dir: PathBuf::from("voices") };
+        let args = piper.args("en_US-lessac-medium", 2.0, Some(&PathBuf::from("out.wav")));
+        assert_eq!(args[1], PathBuf::from("voices").join("en_US-lessac-medium.onnx").to_string_lossy());
+        assert_eq!(args[3], "0.50");
+        assert_eq!(args[5], "out.wav");
+
+        let long = "Une phrase. ".repeat(MAX_SPOKEN_CHARS / 10);
+        let spoken = spoken_part(&long);
+        assert!(spoken.chars().count() <= MAX_SPOKEN_CHARS && spoken.ends_with('.'));
+    }
+}
@@ -0,0 +1,179 @@
+//! spelling.rs - Correcting the typos of a prompt before it is encoded.
+//!
+//! A holographic trace is built from the words of a text: a misspelled word ("phylosophie")
+//! becomes a concept of its own, and the prompt lands far from every memory about the word
+//! meant. The `SpellCorrector` counts the words of everything the Core assimilates; a word of
+//! the prompt it has never seen is replaced by the most frequent known word within a small
+//! edit distance, if there is one. The corrections are reported in the provenance of the answer.
+
+use crate::stop_words::StopWordRegistry;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Shorter words are left alone: too many known words are one edit away from them.
+const MIN_WORD_LEN: usize = 4;
+/// Words at least this long may be two edits away from their correction.
+const LONG_WORD_LEN: usize = 8;
+/// Occurrences a known word needs before it is proposed as a correction.
+const MIN_FREQUENCY: usize = 2;
+
+/// A word of the prompt replaced by a known one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Correction {
+    pub from: String,
+    pub to: String,
+}
+
+/// The vocabulary of the assimilated knowledge, with the number of occurrences of each word.
+#[derive(Debug, Clone, Default)]
+pub struct SpellCorrector {
+    frequencies: HashMap<String, usize>,
+}
+
+impl SpellCorrector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts the words of an assimilated text.
+    pub fn add_text(&mut self, text: &str) {
+        for word in words(text) {
+            *self.frequencies.entry(word.as_str().to_lowercase()).or_insert(0) += 1;
+        }
+    }
+
+    /// Uncounts the words of a forgotten text.
+    pub fn remove_text(&mut self, text: &str) {
+        for word in words(text) {
+            let word = word.as_str().to_lowercase();
+            if let Some(count) = self.frequencies.get_mut(&word) {
+                *count -= 1;
+                if *count == 0 {
+                    self.frequencies.remove(&word);
+                }
+            }
+        }
+    }
+
+    pub fn frequency(&self, word: &str) -> usize {
+        self.frequencies.get(&word.to_lowercase()).copied().unwrap_or(0)
+    }
+
+    pub fn vocabulary_size(&self) -> usize {
+        self.frequencies.len()
+    }
+
+    /// The prompt with its unknown words corrected, and the corrections made. Stop words, short
+    /// words and words without a close enough known word are kept as written; a correction
+    /// keeps the capital of the word it replaces.
+    pub fn correct(&self, prompt: &str, stop_words: &StopWordRegistry) -> (String, Vec<Correction>) {
+        let mut corrected = String::with_capacity(prompt.len());
+        let mut corrections = Vec::new();
+        let mut end = 0;
+        for word in words(prompt) {
+            let lower = word.as_str().to_lowercase();
+            if word.as_str().chars().count() < MIN_WORD_LEN || self.frequencies.contains_key(&lower) || stop_words.is_stop_word(&lower) {
+                continue;
+            }
+            let Some(known) = self.closest_known_word(&lower) else {
+                continue;
+            };
+            let replacement = match word.as_str().chars().next() {
+                Some(first) if first.is_uppercase() => capitalized(known),
+                _ => known.to_string(),
+            };
+            corrected.push_str(&prompt[end..word.start()]);
+            corrected.push_str(&replacement);
+            end = word.end();
+            corrections.push(Correction { from: word.as_str().to_string(), to: replacement });
+        }
+        corrected.push_str(&prompt[end..]);
+        (corrected, corrections)
+    }
+
+    /// The most frequent known word within the edit distance allowed for `word`, the closest
+    /// first.
+    fn closest_known_word(&self, word: &str) -> Option<&str> {
+        let length = word.chars().count();
+        let max_distance = if length >= LONG_WORD_LEN { 2 } else { 1 };
+        self.frequencies
+            .iter()
+            .filter(|(_, &count)| count >= MIN_FREQUENCY)
+            .filter(|(known, _)| known.chars().count().abs_diff(length) <= max_distance)
+            .filter_map(|(known, &count)| {
+                let distance = edit_distance(word, known);
+                (distance <= max_distance).then_some((distance, count, known.as_str()))
+            })
+            // Ties between equally close and frequent words go to the first in alphabetical order.
+            .min_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(b.2)))
+            .map(|(_, _, known)| known)
+    }
+}
+
+fn words(text: &str) -> regex::Matches<'static, '_> {
+    static WORD: OnceLock<Regex> = OnceLock::new();
+    WORD.get_or_init(|| Regex::new(r"\p{L}+").unwrap()).find_iter(text)
+}
+
+fn capitalized(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The number of insertions, deletions, substitutions and transpositions of adjacent letters
+/// that turn `a` into `b` (optimal string alignment distance).
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + substitution);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_words_are_corrected_from_the_knowledge() {
+        assert_eq!(edit_distance("phylosophie", "philosophie"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("Socarte", "Socrate"), 1, "A transposition is one edit");
+
+        let mut corrector = SpellCorrector::new();
+        for text in ["La philosophie est l'amour de la sagesse.", "Socrate a fondé la philosophie morale.", "Socrate était un philosophe grec."] {
+            corrector.add_text(text);
+        }
+        let stop_words = StopWordRegistry::new();
+        let (prompt, corrections) = corrector.correct("Qu'est-ce que la phylosophie selon Socarte ?", &stop_words);
+        assert_eq!(prompt, "Qu'est-ce que la philosophie selon Socrate ?");
+        assert_eq!(corrections, vec![Correction { from: "phylosophie".to_string(), to: "philosophie".to_string() }, Correction { from: "Socarte".to_string(), to: "Socrate".to_string() }]);
+
+        let (prompt, corrections) = corrector.correct("Qui était Platon ?", &stop_words);
+        assert_eq!(prompt, "Qui était Platon ?", "Nothing known is close to Platon");
+        assert!(corrections.is_empty());
+
+        corrector.remove_text("Socrate était un philosophe grec.");
+        assert_eq!(corrector.correct("Socarte", &stop_words).0, "Socarte", "Socrate is now known only once");
+    }
+}
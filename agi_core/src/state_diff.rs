@@ -0,0 +1,219 @@
+//! state_diff.rs - What a training session changed in the Core.
+//!
+//! A `StateSnapshot` is a picture of the state worth comparing: what was taught (the memories
+//! and the hierarchy links of a `KnowledgeFile`), the distribution of the synaptic weights
+//! (a histogram rather than the synapses themselves, which can number in the millions) and the
+//! neurochemical levels. `Core::snapshot_state` takes one, which can be saved before and after
+//! a session; `Core::diff_snapshots` compares two of them.
+
+use crate::knowledge_file::{KnowledgeFile, RelationshipRecord};
+use crate::neurochemical_modulator::NeurochemicalState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+/// Range of the weight histogram; the weights beyond it fall in the first or last bucket.
+pub const WEIGHT_RANGE: (f32, f32) = (-4.0, 4.0);
+pub const WEIGHT_BUCKETS: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("I/O error on the snapshot file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid snapshot file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct NeurochemicalLevels {
+    pub dopamine: f32,
+    pub serotonin: f32,
+    pub acetylcholine: f32,
+    pub noradrenaline: f32,
+}
+
+impl From<&NeurochemicalState> for NeurochemicalLevels {
+    fn from(state: &NeurochemicalState) -> Self {
+        Self { dopamine: state.dopamine, serotonin: state.serotonin, acetylcholine: state.acetylcholine, noradrenaline: state.noradrenaline }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WeightDistribution {
+    pub count: usize,
+    pub mean: f32,
+    pub std_dev: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Synapses per bucket of `WEIGHT_RANGE`, `WEIGHT_BUCKETS` of them.
+    pub histogram: Vec<usize>,
+}
+
+impl WeightDistribution {
+    pub fn of(weights: &[f32]) -> Self {
+        let mut histogram = vec![0; WEIGHT_BUCKETS];
+        if weights.is_empty() {
+            return Self { histogram, ..Self::default() };
+        }
+        let (low, high) = WEIGHT_RANGE;
+        let (mut sum, mut sum_sq, mut min, mut max) = (0.0f64, 0.0f64, f32::INFINITY, f32::NEG_INFINITY);
+        for &weight in weights {
+            sum += weight as f64;
+            sum_sq += (weight as f64) * (weight as f64);
+            min = min.min(weight);
+            max = max.max(weight);
+            let bucket = ((weight - low) / (high - low) * WEIGHT_BUCKETS as f32).floor().clamp(0.0, (WEIGHT_BUCKETS - 1) as f32);
+            histogram[bucket as usize] += 1;
+        }
+        let count = weights.len();
+        let mean = sum / count as f64;
+        let variance = (sum_sq / count as f64 - mean * mean).max(0.0);
+        Self { count, mean: mean as f32, std_dev: variance.sqrt() as f32, min, max, histogram }
+    }
+
+    /// The earth mover's distance between two distributions, in weight units: how far the
+    /// weights moved on average, whatever the number of synapses.
+    pub fn distance(&self, other: &WeightDistribution) -> f32 {
+        if self.count == 0 || other.count == 0 {
+            return 0.0;
+        }
+        let bucket_width = (WEIGHT_RANGE.1 - WEIGHT_RANGE.0) / WEIGHT_BUCKETS as f32;
+        let (mut cdf_a, mut cdf_b, mut distance) = (0.0f32, 0.0f32, 0.0f32);
+        for (a, b) in self.histogram.iter().zip(&other.histogram) {
+            cdf_a += *a as f32 / self.count as f32;
+            cdf_b += *b as f32 / other.count as f32;
+            distance += (cdf_a - cdf_b).abs() * bucket_width;
+        }
+        distance
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub tick: u64,
+    pub knowledge: KnowledgeFile,
+    pub synapses: WeightDistribution,
+    pub neurochemicals: NeurochemicalLevels,
+}
+
+impl StateSnapshot {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SnapshotError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WeightShift {
+    pub count_delta: i64,
+    pub mean_delta: f32,
+    pub std_dev_delta: f32,
+    /// See `WeightDistribution::distance`.
+    pub distance: f32,
+    /// Change of the synapses per bucket of the histogram.
+    pub histogram_delta: Vec<i64>,
+}
+
+/// What changed from one snapshot to the next. Memories are compared by text, links by names.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SnapshotDiff {
+    pub ticks: i64,
+    pub memories_added: Vec<String>,
+    pub memories_removed: Vec<String>,
+    pub relationships_added: Vec<RelationshipRecord>,
+    pub relationships_removed: Vec<RelationshipRecord>,
+    pub synapses: WeightShift,
+    /// Level in the second snapshot minus level in the first.
+    pub neurochemicals: NeurochemicalLevels,
+}
+
+impl SnapshotDiff {
+    pub fn between(a: &StateSnapshot, b: &StateSnapshot) -> Self {
+        let texts = |snapshot: &StateSnapshot| -> Vec<String> { snapshot.knowledge.memories.iter().map(|memory| memory.text.clone()).collect() };
+        let (memories_added, memories_removed) = added_and_removed(&texts(a), &texts(b));
+        let (relationships_added, relationships_removed) = added_and_removed(&a.knowledge.relationships, &b.knowledge.relationships);
+        let (wa, wb) = (&a.synapses, &b.synapses);
+        let (na, nb) = (&a.neurochemicals, &b.neurochemicals);
+        Self {
+            ticks: b.tick as i64 - a.tick as i64,
+            memories_added,
+            memories_removed,
+            relationships_added,
+            relationships_removed,
+            synapses: WeightShift {
+                count_delta: wb.count as i64 - wa.count as i64,
+                mean_delta: wb.mean - wa.mean,
+                std_dev_delta: wb.std_dev - wa.std_dev,
+                distance: wa.distance(wb),
+                histogram_delta: wa.histogram.iter().zip(&wb.histogram).map(|(a, b)| *b as i64 - *a as i64).collect(),
+            },
+            neurochemicals: NeurochemicalLevels {
+                dopamine: nb.dopamine - na.dopamine,
+                serotonin: nb.serotonin - na.serotonin,
+                acetylcholine: nb.acetylcholine - na.acetylcholine,
+                noradrenaline: nb.noradrenaline - na.noradrenaline,
+            },
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.memories_added.is_empty()
+            && self.memories_removed.is_empty()
+            && self.relationships_added.is_empty()
+            && self.relationships_removed.is_empty()
+            && self.synapses.histogram_delta.iter().all(|delta| *delta == 0)
+            && self.neurochemicals == NeurochemicalLevels::default()
+    }
+}
+
+/// The items of `after` missing from `before`, and those of `before` missing from `after`, in
+/// their order.
+fn added_and_removed<T: Clone + Eq + std::hash::Hash>(before: &[T], after: &[T]) -> (Vec<T>, Vec<T>) {
+    let (before_set, after_set): (HashSet<&T>, HashSet<&T>) = (before.iter().collect(), after.iter().collect());
+    let added = after.iter().filter(|item| !before_set.contains(item)).cloned().collect();
+    let removed = before.iter().filter(|item| !after_set.contains(item)).cloned().collect();
+    (added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge_file::MemoryRecord;
+
+    fn snapshot(tick: u64, texts: &[&str], links: &[(&str, &str)], weights: &[f32], dopamine: f32) -> StateSnapshot {
+        StateSnapshot {
+            tick,
+            knowledge: KnowledgeFile {
+                memories: texts.iter().map(|text| MemoryRecord { text: text.to_string(), is_axiom: false, location: None, learned_at: None, source: None, domains: Vec::new() }).collect(),
+                relationships: links.iter().map(|(child, parent)| RelationshipRecord { child: child.to_string(), parent: parent.to_string() }).collect(),
+            },
+            synapses: WeightDistribution::of(weights),
+            neurochemicals: NeurochemicalLevels { dopamine, ..NeurochemicalLevels::default() },
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_what_a_session_changed() {
+        let before = snapshot(100, &["Le chat dort.", "Le ciel est bleu."], &[("chat", "animal")], &[0.5, 0.5, 1.0, -0.5], 0.5);
+        let after = snapshot(250, &["Le ciel est bleu.", "Le chien aboie."], &[("chat", "animal"), ("chien", "animal")], &[0.5, 1.0, 1.0, -0.5], 0.7);
+
+        let diff = SnapshotDiff::between(&before, &after);
+        assert_eq!(diff.ticks, 150);
+        assert_eq!(diff.memories_added, vec!["Le chien aboie."]);
+        assert_eq!(diff.memories_removed, vec!["Le chat dort."]);
+        assert_eq!(diff.relationships_added, vec![RelationshipRecord { child: "chien".to_string(), parent: "animal".to_string() }]);
+        assert!(diff.relationships_removed.is_empty());
+        assert!((diff.synapses.mean_delta - 0.125).abs() < 1e-6);
+        assert!((diff.synapses.distance - 0.125).abs() < 1e-6, "One weight in four moved by 0.5: {}", diff.synapses.distance);
+        assert_eq!(diff.synapses.histogram_delta.iter().sum::<i64>(), 0);
+        assert!((diff.neurochemicals.dopamine - 0.2).abs() < 1e-6);
+        assert!(!diff.is_empty());
+        assert!(SnapshotDiff::between(&after, &after).is_empty());
+    }
+}
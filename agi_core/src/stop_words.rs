@@ -0,0 +1,192 @@
+//! stop_words.rs - Runtime-extensible stop-word lists.
+//!
+//! The ConceptFocuser, the InnerDrive and the Thalamus all need to know which words carry no
+//! concept of their own. They share one `StopWordRegistry`, which starts with the built-in
+//! French and English lists and can be extended from a TOML or JSON file:
+//!
+//! ```toml
+//! # Words that must never be dropped in this deployment's field.
+//! whitelist = ["force", "état"]
+//!
+//! [languages]
+//! es = ["el", "la", "los", "las", "de", "y"]
+//! ```
+
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+
+/// A registry shared between the components that filter stop words.
+pub type SharedStopWords = Arc<RwLock<StopWordRegistry>>;
+
+const FRENCH: &[&str] = &[
+    "a", "à", "alors", "au", "aucuns", "aussi", "autre", "autres", "aux", "avant", "avec", "avoir", "bon",
+    "car", "ce", "ceci", "cela", "ces", "cet", "cette", "ceux", "chaque", "ci", "comme", "comment", "dans",
+    "de", "des", "du", "dedans", "dehors", "depuis", "deux", "devrait", "doit", "donc", "dont", "dos", "droite",
+    "dès", "début", "elle", "elles", "en", "encore", "essai", "est", "et", "eu", "eux", "fait", "faites", "fois",
+    "font", "force", "haut", "hors", "ici", "il", "ils", "je", "juste", "la", "le", "les", "leur",
+    "leurs", "lui", "ma", "maintenant", "mais", "mes", "mine", "moi", "moins", "mon", "mot", "même", "ne", "ni",
+    "nommés", "nos", "notre", "nous", "nouveaux", "ont", "ou", "où", "par", "parce", "pas", "peut", "peu",
+    "plupart", "plus", "pour", "pourquoi", "quand", "que", "quel", "quelle", "quelles", "quels", "qui", "quoi", "sa",
+    "sans", "ses", "seul", "seulement", "si", "sien", "soi", "soit", "sommes", "son", "sont", "sous", "sur", "ta",
+    "tandis", "tellement", "tels", "tes", "toi", "ton", "tous", "tout", "trop", "très", "tu", "un", "une",
+    "voient", "vont", "vos", "votre", "vous", "vu", "y", "ça", "étaient", "état", "étions", "été", "êtes", "être",
+    "serait", "absolument", "c'est", "d'un", "d'une",
+];
+
+const ENGLISH: &[&str] = &[
+    "a", "about", "above", "after", "again", "against", "all", "am", "an", "and", "any", "are", "aren't",
+    "as", "at", "be", "because", "been", "before", "being", "below", "between", "both", "but", "by",
+    "can", "can't", "cannot", "could", "couldn't", "did", "didn't", "do", "does", "doesn't", "doing", "don't",
+    "down", "during", "each", "few", "for", "from", "further", "had", "hadn't", "has", "hasn't", "have",
+    "haven't", "having", "he", "he'd", "he'll", "he's", "her", "here", "here's", "hers", "herself",
+    "him", "himself", "his", "how", "how's", "i", "i'd", "i'll", "i'm", "i've", "if", "in", "into",
+    "is", "isn't", "it", "it's", "its", "itself", "let's", "me", "more", "most", "mustn't", "my",
+    "myself", "no", "nor", "not", "of", "off", "on", "once", "only", "or", "other", "ought", "our",
+    "ours", "ourselves", "out", "over", "own", "same", "shan't", "she", "she'd", "she'll", "she's",
+    "should", "shouldn't", "so", "some", "such", "than", "that", "that's", "the", "their", "theirs",
+    "them", "themselves", "then", "there", "there's", "these", "they", "they'd", "they'll", "they're",
+    "they've", "this", "those", "through", "to", "too", "under", "until", "up", "us", "very", "was", "wasn't",
+    "we", "we'd", "we'll", "we're", "we've", "were", "weren't", "what", "what's", "when", "when's",
+    "where", "where's", "which", "while", "who", "who's", "whom", "why", "why's", "will", "with", "won't",
+    "would", "wouldn't", "you", "you'd", "you'll", "you're", "you've", "your", "yours", "yourself",
+    "yourselves",
+];
+
+#[derive(Debug, Error)]
+pub enum StopWordError {
+    #[error("I/O error while reading the stop-word file: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid TOML stop-word file: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("invalid JSON stop-word file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported stop-word file format '{0}' (expected .toml or .json)")]
+    UnsupportedFormat(String),
+}
+
+/// Contents of a stop-word configuration file.
+#[derive(Debug, Default, Deserialize)]
+pub struct StopWordConfig {
+    /// Additional stop words, by language code. Existing languages are extended.
+    #[serde(default)]
+    pub languages: BTreeMap<String, Vec<String>>,
+    /// Domain terms that are never treated as stop words, whatever the language lists say.
+    #[serde(default)]
+    pub whitelist: Vec<String>,
+}
+
+/// Stop words by language, plus a whitelist of domain terms that must never be dropped.
+#[derive(Debug, Clone)]
+pub struct StopWordRegistry {
+    languages: HashMap<String, HashSet<String>>,
+    whitelist: HashSet<String>,
+}
+
+impl StopWordRegistry {
+    /// A registry with the built-in French ("fr") and English ("en") lists.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.add_words("fr", FRENCH.iter().copied());
+        registry.add_words("en", ENGLISH.iter().copied());
+        registry
+    }
+
+    /// A registry without any stop word.
+    pub fn empty() -> Self {
+        Self { languages: HashMap::new(), whitelist: HashSet::new() }
+    }
+
+    /// Wraps the registry for sharing between components.
+    pub fn shared(self) -> SharedStopWords {
+        Arc::new(RwLock::new(self))
+    }
+
+    /// True if `word` is a stop word in any language and is not whitelisted.
+    pub fn is_stop_word(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        !self.whitelist.contains(&word) && self.languages.values().any(|words| words.contains(&word))
+    }
+
+    /// Adds stop words to a language, creating it if needed.
+    pub fn add_words<I, S>(&mut self, language: &str, words: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.languages
+            .entry(language.to_string())
+            .or_default()
+            .extend(words.into_iter().map(|word| word.as_ref().to_lowercase()));
+    }
+
+    /// Marks domain terms that must never be dropped.
+    pub fn whitelist<I, S>(&mut self, words: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.whitelist.extend(words.into_iter().map(|word| word.as_ref().to_lowercase()));
+    }
+
+    pub fn languages(&self) -> impl Iterator<Item = &str> {
+        self.languages.keys().map(String::as_str)
+    }
+
+    /// Applies a configuration: extends the language lists and the whitelist.
+    pub fn apply(&mut self, config: StopWordConfig) {
+        for (language, words) in config.languages {
+            self.add_words(&language, words);
+        }
+        self.whitelist(config.whitelist);
+    }
+
+    /// Loads a `.toml` or `.json` configuration file and applies it.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), StopWordError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let config: StopWordConfig = match path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+            Some("toml") => toml::from_str(&content)?,
+            Some("json") => serde_json::from_str(&content)?,
+            other => return Err(StopWordError::UnsupportedFormat(other.unwrap_or_default().to_string())),
+        };
+        println!(
+            "--- Stop words loaded from {:?}: {} languages, {} whitelisted terms. ---",
+            path,
+            config.languages.len(),
+            config.whitelist.len()
+        );
+        self.apply(config);
+        Ok(())
+    }
+}
+
+impl Default for StopWordRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_adds_languages_and_whitelist() {
+        let mut registry = StopWordRegistry::new();
+        assert!(registry.is_stop_word("force"));
+        assert!(!registry.is_stop_word("el"));
+
+        let config: StopWordConfig = toml::from_str("whitelist = [\"Force\"]\n[languages]\nes = [\"el\", \"los\"]\n").unwrap();
+        registry.apply(config);
+
+        assert!(!registry.is_stop_word("force"), "Whitelisted domain terms are never dropped");
+        assert!(registry.is_stop_word("El"));
+        assert!(registry.is_stop_word("the"));
+        assert_eq!(registry.languages().count(), 3);
+    }
+}
@@ -0,0 +1,225 @@
+//! surface_realization.rs - Final grammatical clean-up of generated responses.
+//!
+//! Responses are assembled from templates (personality intros, expertise prefixes) and memory
+//! texts written with any case and punctuation, so naive concatenation produces things like
+//! "Il me semble que Il pleut" or "brille..  Il fait chaud ,non". `join` fuses a template with
+//! its continuation (French contractions and elisions included) and `realize` repairs
+//! capitalization, punctuation and spacing on the final text.
+
+/// Words that may be lowercased when they start a continuation. Anything else (names,
+/// acronyms) keeps its case.
+const FUNCTION_WORDS: &[&str] = &[
+    "le", "la", "les", "l'", "un", "une", "des", "du", "ce", "cet", "cette", "ces", "il", "ils", "elle",
+    "elles", "on", "nous", "vous", "je", "j'", "c'", "mon", "ma", "mes", "son", "sa", "ses", "leur", "leurs",
+    "the", "a", "an", "it", "this", "that", "these", "those", "they", "we", "he", "she", "there",
+];
+
+/// French contractions of a preposition with a definite article.
+const CONTRACTIONS: &[(&str, &str, &str)] = &[("de", "le", "du"), ("de", "les", "des"), ("à", "le", "au"), ("à", "les", "aux"), ("de", "des", "de")];
+
+/// Words elided before a vowel ("que il" -> "qu'il").
+const ELIDABLE: &[(&str, &str)] = &[("que", "qu'"), ("de", "d'"), ("je", "j'"), ("ne", "n'"), ("ce", "c'"), ("le", "l'"), ("la", "l'"), ("se", "s'")];
+
+/// Joins a template with the text that follows it and realizes the result.
+///
+/// - A template ending with a word ("Il me semble que") flows into the text, with French
+///   contractions and elisions applied at the boundary.
+/// - A template ending with an ellipsis or a comma keeps the sentence going: a leading article
+///   or pronoun of the text is lowercased.
+/// - A template ending with other punctuation starts a new sentence.
+pub fn join(template: &str, text: &str) -> String {
+    let template = template.trim_end();
+    let text = text.trim_start();
+    if template.is_empty() {
+        return realize(text);
+    }
+    if text.is_empty() {
+        return realize(template);
+    }
+
+    let (first_word, rest) = text.split_at(first_word_end(text));
+    let continues = template.ends_with(|c: char| c.is_alphanumeric());
+    let first_word = if continues || template.ends_with("...") || template.ends_with(',') { lowercase_function_word(first_word) } else { first_word.to_string() };
+
+    if !continues {
+        return realize(&format!("{} {}{}", template, first_word, rest));
+    }
+
+    let (head, last_word) = template.rsplit_once(' ').unwrap_or(("", template));
+    let separator = if head.is_empty() { "" } else { " " };
+    let fused = fuse(last_word, &first_word);
+    realize(&format!("{}{}{}{}", head, separator, fused, rest))
+}
+
+/// Repairs spacing, punctuation and capitalization of a complete response.
+pub fn realize(text: &str) -> String {
+    let lines: Vec<String> = text.trim().lines().map(normalize_punctuation).collect();
+    let mut realized = capitalize_sentences(&lines.join("\n"));
+
+    let last_line = realized.lines().last().unwrap_or_default();
+    if !last_line.starts_with("- ") && realized.ends_with(|c: char| c.is_alphanumeric()) {
+        realized.push('.');
+    }
+    realized
+}
+
+/// Fuses the last word of a template with the first word of its continuation.
+fn fuse(last_word: &str, first_word: &str) -> String {
+    let last_lower = last_word.to_lowercase();
+    let first_lower = first_word.to_lowercase();
+
+    if let Some((_, _, contracted)) = CONTRACTIONS.iter().find(|(prep, article, _)| *prep == last_lower && *article == first_lower) {
+        return match_case(last_word, contracted);
+    }
+    if last_lower == "si" && (first_lower == "il" || first_lower == "ils") {
+        return format!("{}{}", match_case(last_word, "s'"), first_word);
+    }
+    if let Some((_, elided)) = ELIDABLE.iter().find(|(word, _)| *word == last_lower) {
+        if starts_with_vowel(first_word) {
+            return format!("{}{}", match_case(last_word, elided), first_word);
+        }
+    }
+    format!("{} {}", last_word, first_word)
+}
+
+fn first_word_end(text: &str) -> usize {
+    // Keep an elided article with its word ("L'eau").
+    text.find(|c: char| c.is_whitespace() || (c.is_ascii_punctuation() && c != '\'' && c != '-')).unwrap_or(text.len())
+}
+
+fn lowercase_function_word(word: &str) -> String {
+    let lower = word.to_lowercase();
+    let article = lower.find('\'').map_or(lower.as_str(), |position| &lower[..=position]);
+    if FUNCTION_WORDS.contains(&lower.as_str()) || FUNCTION_WORDS.contains(&article) {
+        lower
+    } else {
+        word.to_string()
+    }
+}
+
+fn starts_with_vowel(word: &str) -> bool {
+    word.chars().next().is_some_and(|c| "aeiouyàâäéèêëîïôöûüAEIOUYÀÂÄÉÈÊËÎÏÔÖÛÜ".contains(c))
+}
+
+/// Gives `word` the capitalization of `model` (only the first letter matters here).
+fn match_case(model: &str, word: &str) -> String {
+    if model.chars().next().is_some_and(char::is_uppercase) {
+        capitalize(word)
+    } else {
+        word.to_string()
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Collapses spaces, removes spaces before commas and periods, and cleans up doubled punctuation.
+/// The French spacing before `!`, `?`, `:` and `;` is left as written.
+fn normalize_punctuation(line: &str) -> String {
+    let line = line.split(' ').filter(|part| !part.is_empty()).collect::<Vec<_>>().join(" ");
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            ' ' if matches!(chars.get(i + 1), Some(',' | '.')) => {}
+            '.' => {
+                let run = chars[i..].iter().take_while(|&&d| d == '.').count();
+                i += run - 1;
+                if run >= 3 {
+                    out.push_str("...");
+                } else if out.ends_with(',') {
+                    // ",." ends the sentence.
+                    out.pop();
+                    out.push('.');
+                } else if !out.ends_with(['.', '!', '?']) {
+                    // ".." becomes ".", and "?." or "...." keep their first mark.
+                    out.push('.');
+                }
+            }
+            ',' => {
+                if !out.ends_with([',', '.']) {
+                    out.push(',');
+                }
+                if chars.get(i + 1).is_some_and(|next| next.is_alphabetic()) {
+                    out.push(' ');
+                }
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Uppercases the first letter of the text, of each line (list items included) and of each
+/// sentence following `.`, `!` or `?` and a space. An ellipsis does not end a sentence.
+fn capitalize_sentences(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut capitalize_next = true;
+    let mut sentence_ended = false;
+
+    for c in text.chars() {
+        if capitalize_next && c.is_alphabetic() {
+            out.extend(c.to_uppercase());
+            capitalize_next = false;
+            sentence_ended = false;
+            continue;
+        }
+        match c {
+            '\n' => capitalize_next = true,
+            '.' | '!' | '?' => sentence_ended = !(c == '.' && out.ends_with("..")),
+            c if c.is_whitespace() => capitalize_next |= sentence_ended,
+            // "3.14" or "www.example.org" do not end a sentence.
+            c if c.is_alphanumeric() => {
+                capitalize_next = false;
+                sentence_ended = false;
+            }
+            _ => {}
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_bad_concatenations() {
+        let cases = [
+            (join("Il me semble que", "Il pleut"), "Il me semble qu'il pleut."),
+            (join("Il me semble que", "le soleil est une étoile."), "Il me semble que le soleil est une étoile."),
+            (join("Je crois savoir que", "Paris est la capitale de la France."), "Je crois savoir que Paris est la capitale de la France."),
+            (join("Je crois savoir que", "L'eau bout à 100 °C"), "Je crois savoir que l'eau bout à 100 °C."),
+            (join("On parle de", "Le soleil."), "On parle du soleil."),
+            (join("On pense à", "les étoiles"), "On pense aux étoiles."),
+            (join("Je me demande si", "Ils savent."), "Je me demande s'ils savent."),
+            (join("Cela évoque une image de...", "Le soleil brille."), "Cela évoque une image de... le soleil brille."),
+            (join("Si je comprends bien,", "Les chats dorment"), "Si je comprends bien, les chats dorment."),
+            (join("Absolument !", "le soleil brille"), "Absolument ! Le soleil brille."),
+            (join("Est-ce que cela signifie que...?", "the sun is a star"), "Est-ce que cela signifie que...? The sun is a star."),
+            (join("Pour faire simple :", "NASA explore l'espace ."), "Pour faire simple : NASA explore l'espace."),
+        ];
+        for (realized, expected) in cases {
+            assert_eq!(realized, expected);
+        }
+    }
+
+    #[test]
+    fn test_realize_repairs_punctuation_and_case() {
+        assert_eq!(realize("le soleil brille..  il fait chaud ,non ?."), "Le soleil brille. Il fait chaud, non ?");
+        assert_eq!(realize("pi vaut 3,14"), "Pi vaut 3,14.");
+        assert_eq!(realize("voir www.example.org, puis 3.5 km"), "Voir www.example.org, puis 3.5 km.");
+        assert_eq!(realize("Voici :\n- le soleil\n- la lune"), "Voici :\n- Le soleil\n- La lune");
+        assert_eq!(realize("Je ne sais pas..."), "Je ne sais pas...");
+    }
+}
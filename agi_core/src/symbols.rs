@@ -0,0 +1,128 @@
+//! symbols.rs - Interned concept names.
+//!
+//! The same few thousand concept names are keys of the encoder statistics, of every trace and of
+//! the hierarchy. They are interned once in a table shared by the whole process (a
+//! `string_interner` behind a lock), and passed around as `Sym`: a copyable id, hashed and
+//! compared as an integer. A name is only looked up again to be displayed, through `Display`,
+//! `Sym::with_str` or `Sym::to_string`. Symbols serialize as their names, so the files written
+//! before interning still load.
+
+use crate::error::LockResultExt;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+use string_interner::{DefaultStringInterner, DefaultSymbol};
+
+fn table() -> &'static RwLock<DefaultStringInterner> {
+    static TABLE: OnceLock<RwLock<DefaultStringInterner>> = OnceLock::new();
+    TABLE.get_or_init(|| RwLock::new(DefaultStringInterner::new()))
+}
+
+/// An interned name; equal names get equal symbols.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sym(DefaultSymbol);
+
+impl Sym {
+    /// The symbol of `name`, interning it on first use.
+    pub fn intern(name: &str) -> Self {
+        if let Some(symbol) = Self::lookup(name) {
+            return symbol;
+        }
+        Sym(table().write().recover().get_or_intern(name))
+    }
+
+    /// The symbol of `name` if it was ever interned; a name nobody interned is in no map, so
+    /// queries use this rather than growing the table.
+    pub fn lookup(name: &str) -> Option<Self> {
+        table().read().recover().get(name).map(Sym)
+    }
+
+    /// Calls `f` with the name. The table is locked meanwhile: `f` must not intern.
+    pub fn with_str<R>(self, f: impl FnOnce(&str) -> R) -> R {
+        let table = table().read().recover();
+        f(table.resolve(self.0).expect("symbols come from the shared table"))
+    }
+
+    pub fn len(self) -> usize {
+        self.with_str(str::len)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// How many names are interned, and the bytes they take.
+pub fn table_usage() -> (usize, usize) {
+    let table = table().read().recover();
+    let bytes = table.iter().map(|(_, name)| name.len() + std::mem::size_of::<DefaultSymbol>()).sum();
+    (table.len(), bytes)
+}
+
+impl From<&str> for Sym {
+    fn from(name: &str) -> Self {
+        Sym::intern(name)
+    }
+}
+
+impl PartialEq<str> for Sym {
+    fn eq(&self, other: &str) -> bool {
+        Sym::lookup(other) == Some(*self)
+    }
+}
+
+impl PartialEq<&str> for Sym {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl fmt::Display for Sym {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_str(|name| f.write_str(name))
+    }
+}
+
+impl fmt::Debug for Sym {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_str(|name| fmt::Debug::fmt(name, f))
+    }
+}
+
+impl Serialize for Sym {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Sym {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|name| Sym::intern(&name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbols_are_shared_and_displayed() {
+        let chien = Sym::intern("chien");
+        assert_eq!(chien, Sym::from("chien"));
+        assert_ne!(chien, Sym::intern("chat"));
+        assert_eq!(chien.to_string(), "chien");
+        assert_eq!(format!("{:?}", chien), "\"chien\"");
+        assert!(chien == "chien" && chien != "chat");
+        assert_eq!(Sym::lookup("jamais interné, ce nom"), None);
+
+        let json = serde_json::to_string(&chien).unwrap();
+        assert_eq!(json, "\"chien\"");
+        assert_eq!(serde_json::from_str::<Sym>(&json).unwrap(), chien);
+        // Interned from several threads, a name still gets one symbol.
+        let symbols: Vec<Sym> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4).map(|_| scope.spawn(|| Sym::intern("loup"))).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        assert!(symbols.iter().all(|symbol| *symbol == symbols[0]));
+    }
+}
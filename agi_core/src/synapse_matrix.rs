@@ -0,0 +1,290 @@
+// agi_core/src/synapse_matrix.rs
+
+//! The synapses of the connectome in compressed sparse row (CSR) form, and the v2 connectome
+//! file that stores them as they are in memory.
+//!
+//! The outgoing synapses of neuron `n` are `targets[offsets[n]..offsets[n + 1]]`, with their
+//! `weights` at the same positions. A v2 file is memory-mapped and used directly: nothing is
+//! parsed and pages are only read when a neuron fires. The mapping is private (copy-on-write),
+//! so plasticity can change the weights without touching the file.
+//!
+//! v2 layout, little-endian:
+//! ```text
+//! magic "NVCN" | version u32 = 2 | neurons u64 | synapses u64
+//! offsets [u64; neurons + 1] | targets [u32; synapses] | weights [f32; synapses]
+//! ```
+
+use crate::connectome::Synapse;
+use memmap2::{MmapMut, MmapOptions};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::path::Path;
+
+pub const MAGIC: &[u8; 4] = b"NVCN";
+pub const VERSION: u32 = 2;
+const HEADER_LEN: usize = 24;
+
+enum Storage {
+    Owned { offsets: Vec<u64>, targets: Vec<u32>, weights: Vec<f32> },
+    /// Byte ranges of the three arrays in the mapped file.
+    Mapped { map: MmapMut, offsets: Range<usize>, targets: Range<usize>, weights: Range<usize> },
+}
+
+pub struct SynapseMatrix {
+    storage: Storage,
+}
+
+impl std::fmt::Debug for SynapseMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SynapseMatrix")
+            .field("neurons", &self.neuron_count())
+            .field("synapses", &self.len())
+            .field("mapped", &matches!(self.storage, Storage::Mapped { .. }))
+            .finish()
+    }
+}
+
+impl Default for SynapseMatrix {
+    fn default() -> Self {
+        Self::from_synapses(0, &[])
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+impl SynapseMatrix {
+    /// Groups a list of synapses by presynaptic neuron. Fails if a synapse leaves or reaches a
+    /// neuron outside of `0..neuron_count`.
+    pub fn try_from_synapses(neuron_count: usize, synapses: &[Synapse]) -> io::Result<Self> {
+        if synapses.iter().any(|s| s.from as usize >= neuron_count || s.to as usize >= neuron_count) {
+            return Err(invalid("A synapse connects a neuron outside of the connectome."));
+        }
+        Ok(Self::from_synapses(neuron_count, synapses))
+    }
+
+    /// Like `try_from_synapses`, for synapses known to be in range.
+    pub fn from_synapses(neuron_count: usize, synapses: &[Synapse]) -> Self {
+        // Counting sort by presynaptic neuron, keeping the order of the synapses of each neuron.
+        let mut offsets = vec![0u64; neuron_count + 1];
+        for synapse in synapses {
+            offsets[synapse.from as usize + 1] += 1;
+        }
+        let mut total = 0;
+        for offset in offsets.iter_mut() {
+            total += *offset;
+            *offset = total;
+        }
+        let mut cursor = offsets.clone();
+        let mut targets = vec![0u32; synapses.len()];
+        let mut weights = vec![0.0f32; synapses.len()];
+        for synapse in synapses {
+            let slot = &mut cursor[synapse.from as usize];
+            targets[*slot as usize] = synapse.to as u32;
+            weights[*slot as usize] = synapse.weight;
+            *slot += 1;
+        }
+        Self { storage: Storage::Owned { offsets, targets, weights } }
+    }
+
+    /// Maps a v2 file. The header and the offsets are checked; the targets are not, and
+    /// propagation skips those outside of the connectome.
+    pub fn map_v2<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        if cfg!(target_endian = "big") {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "v2 connectomes are little-endian."));
+        }
+        let file = File::open(path)?;
+        // SAFETY: the mapping is private, so writes never reach the file. Another process
+        // truncating the file while it is mapped would fault; connectome files are written once.
+        let map = unsafe { MmapOptions::new().map_copy(&file)? };
+        if map.len() < HEADER_LEN || &map[0..4] != MAGIC {
+            return Err(invalid("Not a v2 connectome file."));
+        }
+        let version = u32::from_le_bytes(map[4..8].try_into().unwrap());
+        if version != VERSION {
+            return Err(invalid(&format!("Unsupported connectome version {}.", version)));
+        }
+        let neuron_count = u64::from_le_bytes(map[8..16].try_into().unwrap()) as usize;
+        let synapse_count = u64::from_le_bytes(map[16..24].try_into().unwrap()) as usize;
+
+        // The counts come from the file: a corrupted header must not overflow the sizes.
+        let sizes = neuron_count.checked_add(1).and_then(|rows| rows.checked_mul(8)).zip(synapse_count.checked_mul(4));
+        let Some((offsets_len, array_len)) = sizes else {
+            return Err(invalid("The counts of the connectome header are too large."));
+        };
+        let end_of = |start: usize, len: usize| start.checked_add(len).ok_or_else(|| invalid("The counts of the connectome header are too large."));
+        let offsets = HEADER_LEN..end_of(HEADER_LEN, offsets_len)?;
+        let targets = offsets.end..end_of(offsets.end, array_len)?;
+        let weights = targets.end..end_of(targets.end, array_len)?;
+        if map.len() != weights.end {
+            return Err(invalid("The size of the connectome file does not match its header."));
+        }
+        let matrix = Self { storage: Storage::Mapped { map, offsets, targets, weights } };
+        let offsets = matrix.offsets();
+        if offsets[0] != 0 || offsets[neuron_count] != synapse_count as u64 || offsets.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(invalid("The synapse offsets of the connectome are corrupted."));
+        }
+        Ok(matrix)
+    }
+
+    /// Writes the matrix as a v2 file.
+    pub fn write_v2<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&VERSION.to_le_bytes())?;
+        file.write_all(&(self.neuron_count() as u64).to_le_bytes())?;
+        file.write_all(&(self.len() as u64).to_le_bytes())?;
+        for offset in self.offsets() {
+            file.write_all(&offset.to_le_bytes())?;
+        }
+        for target in self.targets() {
+            file.write_all(&target.to_le_bytes())?;
+        }
+        for weight in self.weights() {
+            file.write_all(&weight.to_le_bytes())?;
+        }
+        file.flush()
+    }
+
+    pub fn neuron_count(&self) -> usize {
+        self.offsets().len() - 1
+    }
+
+    /// Number of synapses.
+    pub fn len(&self) -> usize {
+        self.targets().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn offsets(&self) -> &[u64] {
+        match &self.storage {
+            Storage::Owned { offsets, .. } => offsets,
+            Storage::Mapped { map, offsets, .. } => bytemuck::cast_slice(&map[offsets.clone()]),
+        }
+    }
+
+    pub fn targets(&self) -> &[u32] {
+        match &self.storage {
+            Storage::Owned { targets, .. } => targets,
+            Storage::Mapped { map, targets, .. } => bytemuck::cast_slice(&map[targets.clone()]),
+        }
+    }
+
+    pub fn weights(&self) -> &[f32] {
+        match &self.storage {
+            Storage::Owned { weights, .. } => weights,
+            Storage::Mapped { map, weights, .. } => bytemuck::cast_slice(&map[weights.clone()]),
+        }
+    }
+
+    pub fn weights_mut(&mut self) -> &mut [f32] {
+        match &mut self.storage {
+            Storage::Owned { weights, .. } => weights,
+            Storage::Mapped { map, weights, .. } => bytemuck::cast_slice_mut(&mut map[weights.clone()]),
+        }
+    }
+
+    /// Positions of the outgoing synapses of `from` in `targets` and `weights`.
+    fn row(&self, from: u64) -> Range<usize> {
+        let offsets = self.offsets();
+        match (offsets.get(from as usize), offsets.get(from as usize + 1)) {
+            (Some(&start), Some(&end)) => start as usize..end as usize,
+            _ => 0..0,
+        }
+    }
+
+    /// The outgoing synapses of `from`, as (target, weight).
+    pub fn outgoing(&self, from: u64) -> impl Iterator<Item = (u64, f32)> + '_ {
+        let row = self.row(from);
+        self.targets()[row.clone()].iter().zip(&self.weights()[row]).map(|(&to, &weight)| (to as u64, weight))
+    }
+
+    /// The targets of the outgoing synapses of `from`, with their weights to change.
+    pub fn outgoing_mut(&mut self, from: u64) -> (&[u32], &mut [f32]) {
+        let row = self.row(from);
+        match &mut self.storage {
+            Storage::Owned { targets, weights, .. } => (&targets[row.clone()], &mut weights[row]),
+            Storage::Mapped { map, targets, weights, .. } => {
+                // The weights come after the targets in the file.
+                let (head, tail) = map.split_at_mut(weights.start);
+                let targets: &[u32] = bytemuck::cast_slice(&head[targets.clone()]);
+                let weights: &mut [f32] = bytemuck::cast_slice_mut(&mut tail[..weights.len()]);
+                (&targets[row.clone()], &mut weights[row])
+            }
+        }
+    }
+
+    /// Every synapse, grouped by presynaptic neuron.
+    pub fn iter(&self) -> impl Iterator<Item = Synapse> + '_ {
+        let offsets = self.offsets();
+        (0..self.neuron_count()).flat_map(move |from| {
+            let row = offsets[from] as usize..offsets[from + 1] as usize;
+            self.targets()[row.clone()]
+                .iter()
+                .zip(&self.weights()[row])
+                .map(move |(&to, &weight)| Synapse { from: from as u64, to: to as u64, weight })
+        })
+    }
+
+    /// Removes the synapses for which `keep` is false and returns how many were removed. The
+    /// matrix is rebuilt in memory: a mapped file is left as it is.
+    pub fn retain(&mut self, mut keep: impl FnMut(f32) -> bool) -> usize {
+        let before = self.len();
+        let kept: Vec<Synapse> = self.iter().filter(|synapse| keep(synapse.weight)).collect();
+        if kept.len() != before {
+            *self = Self::from_synapses(self.neuron_count(), &kept);
+        }
+        before - kept.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v2_file_round_trip() {
+        let synapses = [
+            Synapse { from: 2, to: 0, weight: 0.5 },
+            Synapse { from: 0, to: 1, weight: -0.25 },
+            Synapse { from: 2, to: 1, weight: 1.5 },
+        ];
+        let matrix = SynapseMatrix::from_synapses(3, &synapses);
+        assert_eq!(matrix.offsets(), &[0, 1, 1, 3]);
+        assert_eq!(matrix.outgoing(2).collect::<Vec<_>>(), vec![(0, 0.5), (1, 1.5)]);
+        assert!(SynapseMatrix::try_from_synapses(2, &synapses).is_err());
+
+        let path = std::env::temp_dir().join(format!("neurova_connectome_v2_{}.bin", std::process::id()));
+        matrix.write_v2(&path).unwrap();
+        let mut mapped = SynapseMatrix::map_v2(&path).unwrap();
+        assert_eq!(mapped.offsets(), matrix.offsets());
+        assert_eq!(mapped.targets(), matrix.targets());
+        assert_eq!(mapped.weights(), matrix.weights());
+
+        // Plasticity changes the mapped weights, not the file.
+        mapped.outgoing_mut(2).1[1] = 2.0;
+        assert_eq!(mapped.outgoing(2).collect::<Vec<_>>(), vec![(0, 0.5), (1, 2.0)]);
+        assert_eq!(SynapseMatrix::map_v2(&path).unwrap().weights(), matrix.weights());
+
+        assert_eq!(mapped.retain(|weight| weight.abs() >= 1.0), 2);
+        assert_eq!(mapped.iter().map(|s| (s.from, s.to)).collect::<Vec<_>>(), vec![(2, 1)]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_a_corrupted_header_is_invalid_data() {
+        let path = std::env::temp_dir().join(format!("neurova_connectome_v2_corrupt_{}.bin", std::process::id()));
+        let header = |neurons: u64, synapses: u64| [&MAGIC[..], &VERSION.to_le_bytes(), &neurons.to_le_bytes(), &synapses.to_le_bytes()].concat();
+        for (neurons, synapses) in [(u64::MAX, 0), (u64::MAX / 8, 0), (0, u64::MAX / 2), (1 << 60, 1 << 61)] {
+            std::fs::write(&path, [header(neurons, synapses), vec![0; 16]].concat()).unwrap();
+            let error = SynapseMatrix::map_v2(&path).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{} neurons, {} synapses", neurons, synapses);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -22,7 +22,9 @@ impl ConceptSynthesizer {
                 let parent_name = parts[3];
 
                 // 1. Learn the structural relationship.
-                core.learn_relationship(child_name, parent_name);
+                if let Err(e) = core.learn_relationship(child_name, parent_name) {
+                    return Some(format!("I can't link '{}' to '{}': {}", child_name, parent_name, e));
+                }
 
                 // 2. Create and learn a semantic memory to make the concept retrievable.
                 let semantic_memory = format!("le {} est un {}.", child_name, parent_name);
@@ -1,19 +1,57 @@
 
 
 // agi_core/src/thalamus.rs
+use crate::creative_writing;
+use crate::error::LockResultExt;
 use crate::holographic_memory::{HolographicEncoder, HolographicTrace};
+use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 
 /// Represents the classified intent of a user's prompt.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum QueryType {
     Introspective, // "Who are you?", "What can you do?"
     Factual,         // "What is...?", "Who was...?"
     Creative,        // "Write a poem...", "Imagine..."
     Social,          // "How are you?", "Hello."
+    Procedural,      // "How do I...?", "Step by step..."
+    Comparative,     // "Difference between X and Y", "X vs Y"
     Ambiguous,       // Could not determine a clear intent.
 }
 
+impl QueryType {
+    pub const ALL: [QueryType; 7] = [
+        QueryType::Introspective,
+        QueryType::Factual,
+        QueryType::Creative,
+        QueryType::Social,
+        QueryType::Procedural,
+        QueryType::Comparative,
+        QueryType::Ambiguous,
+    ];
+}
+
+const PROCEDURAL_CONCEPTS: &str = "How do I... How to... How can I... What are the steps to... step by step instructions guide tutorial procedure method first then next finally install configure prepare make build comment faire comment puis-je comment je fais les étapes étape par étape procédure méthode d'abord ensuite enfin installer configurer préparer fabriquer";
+const COMPARATIVE_CONCEPTS: &str = "What is the difference between... Compare... versus vs which is better similarities differences pros and cons advantages disadvantages better than worse than quelle est la différence entre comparer comparaison versus lequel est le meilleur ressemblances différences avantages inconvénients mieux que plus que moins que";
+
+/// How the thalamus reached its classification.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum ClassificationMethod {
+    /// A keyword rule matched ("identity", "comparative", "factual_starter"...).
+    Keyword { rule: &'static str },
+    /// Cosine similarity to each prototype.
+    Similarity { scores: Vec<(QueryType, f32)> },
+}
+
+/// The class of a prompt and the reason for it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Classification {
+    pub query_type: QueryType,
+    #[serde(flatten)]
+    pub method: ClassificationMethod,
+}
+
 /// Represents the Thalamus, a key structure for gating and relaying information
 /// using semantic, holographic principles.
 pub struct Thalamus {
@@ -22,6 +60,8 @@ pub struct Thalamus {
     factual_prototype: HolographicTrace,
     creative_prototype: HolographicTrace,
     social_prototype: HolographicTrace,
+    procedural_prototype: HolographicTrace,
+    comparative_prototype: HolographicTrace,
 }
 
 // Manual implementation of Debug as HolographicTrace does not derive it.
@@ -33,6 +73,8 @@ impl std::fmt::Debug for Thalamus {
             .field("factual_prototype", &"HolographicTrace")
             .field("creative_prototype", &"HolographicTrace")
             .field("social_prototype", &"HolographicTrace")
+            .field("procedural_prototype", &"HolographicTrace")
+            .field("comparative_prototype", &"HolographicTrace")
             .finish()
     }
 }
@@ -40,7 +82,7 @@ impl std::fmt::Debug for Thalamus {
 impl Thalamus {
     /// Creates a new Thalamus, pre-computing prototype traces for semantic query classification.
     pub fn new(encoder: Arc<RwLock<HolographicEncoder>>) -> Self {
-        let encoder_lock = encoder.read().unwrap();
+        let encoder_lock = encoder.read().recover();
 
         // Define core concepts for each query type.
         // Use rich, representative phrases to create more nuanced holographic prototypes.
@@ -55,6 +97,8 @@ impl Thalamus {
         let factual_prototype = encoder_lock.encode_raw(factual_concepts);
         let creative_prototype = encoder_lock.encode_raw(creative_concepts);
         let social_prototype = encoder_lock.encode_raw(social_concepts);
+        let procedural_prototype = encoder_lock.encode_raw(PROCEDURAL_CONCEPTS);
+        let comparative_prototype = encoder_lock.encode_raw(COMPARATIVE_CONCEPTS);
 
         Self {
             encoder: Arc::clone(&encoder),
@@ -62,6 +106,8 @@ impl Thalamus {
             factual_prototype,
             creative_prototype,
             social_prototype,
+            procedural_prototype,
+            comparative_prototype,
         }
     }
 
@@ -69,7 +115,7 @@ impl Thalamus {
     /// This should be called after the main knowledge base is loaded to ensure prototypes
     /// are created in a mature semantic space.
     pub fn rebuild_prototypes(&mut self) {
-        let encoder_lock = self.encoder.read().unwrap();
+        let encoder_lock = self.encoder.read().recover();
         println!("--- Rebuilding Thalamus semantic prototypes... ---");
 
                 let introspective_concepts = "Who are you? Tell me about yourself. What is your purpose? Describe your nature. What are your capabilities? What are you made of?";
@@ -81,6 +127,8 @@ impl Thalamus {
         self.factual_prototype = encoder_lock.encode_raw(factual_concepts);
         self.creative_prototype = encoder_lock.encode_raw(creative_concepts);
         self.social_prototype = encoder_lock.encode_raw(social_concepts);
+        self.procedural_prototype = encoder_lock.encode_raw(PROCEDURAL_CONCEPTS);
+        self.comparative_prototype = encoder_lock.encode_raw(COMPARATIVE_CONCEPTS);
         println!("--- Thalamus prototypes rebuilt successfully. ---");
     }
 
@@ -100,6 +148,7 @@ impl Thalamus {
             // French
             "qu'est-ce que", "qu'est ce que", "c'est quoi", "qu'est-ce qu'est", "qu'est que c'est",
             "qui est", "qui était", "qui sont", "qui étaient",
+            "quel est", "quelle est", "quels sont", "quelles sont", "quel était", "quelle était",
             "où est", "où était", "où sont", "où étaient",
             "quand est-ce que", "quand était",
             "pourquoi est-ce que",
@@ -110,8 +159,58 @@ impl Thalamus {
         factual_starters.iter().any(|&starter| lower_text.starts_with(starter))
     }
 
+    /// Checks if the text asks how to do something ("how do I...", "comment faire...").
+    fn is_procedural_question(&self, text: &str) -> bool {
+        let lower_text = text.to_lowercase().replace('’', "'");
+        let procedural_starters = [
+            // English
+            "how do i", "how do you", "how can i", "how should i", "how to", "what are the steps", "steps to",
+            // French
+            "comment faire", "comment puis-je", "comment je", "comment on", "comment dois-je", "comment fait-on",
+            "quelles sont les étapes", "les étapes pour", "procédure pour",
+        ];
+        const PROCEDURAL_MARKERS: &[&str] = &["step by step", "step-by-step", "étape par étape"];
+
+        procedural_starters.iter().any(|&starter| lower_text.starts_with(starter))
+            || PROCEDURAL_MARKERS.iter().any(|&marker| lower_text.contains(marker))
+    }
+
+    /// Checks if the text asks to compare things ("difference between X and Y", "X vs Y").
+    fn is_comparative_question(&self, text: &str) -> bool {
+        let lower_text = format!(" {} ", text.to_lowercase());
+        const COMPARATIVE_MARKERS: &[&str] = &[
+            // English
+            "difference between", "differences between", "similarities between", " compare ", " comparing ",
+            " vs ", " vs. ", " versus ", "which is better",
+            // French
+            "différence entre", "différences entre", "ressemblances entre", " comparer ", "comparaison entre",
+            "lequel est le meilleur", "laquelle est la meilleure",
+        ];
+
+        COMPARATIVE_MARKERS.iter().any(|&marker| lower_text.contains(marker))
+    }
+
+    /// Checks if the text names two things to weigh against each other, or a comparison word.
+    /// Without such a cue, a prompt is not read as a comparison however close it is to the
+    /// comparative prototype ("Quelle est la capitale de la France ?").
+    fn has_comparative_cue(&self, text: &str) -> bool {
+        let lower_text = format!(" {} ", text.to_lowercase());
+        const COMPARATIVE_CUES: &[&str] = &[
+            " entre ", " et ", " ou ", " and ", " or ", " vs ", " vs. ", " versus ", "differ", "différ", "compar", "better", "meilleur",
+        ];
+
+        COMPARATIVE_CUES.iter().any(|&cue| lower_text.contains(cue))
+    }
+
     /// Analyzes the prompt to determine its nature (e.g., Factual, Introspective).
     pub fn analyze_prompt(&self, prompt: &str) -> QueryType {
+        self.classify(prompt).query_type
+    }
+
+    /// Like `analyze_prompt`, but also tells why: the keyword rule that matched, or the
+    /// similarity of the prompt to each prototype.
+    pub fn classify(&self, prompt: &str) -> Classification {
+        let by_rule = |query_type, rule| Classification { query_type, method: ClassificationMethod::Keyword { rule } };
         // --- Priority 1: Keyword-based classification for deterministic routing ---
                 const IDENTITY_KEYWORDS: &[&str] = &["who are you", "what are you", "qui es-tu", "quel est ton nom", "who is neurova"];
         const INTROSPECTIVE_KEYWORDS: &[&str] = &["do you feel", "what do you think", "penses-tu", "ressens-tu"];
@@ -120,35 +219,47 @@ impl Thalamus {
         let lower_prompt = prompt.to_lowercase();
 
         if IDENTITY_KEYWORDS.iter().any(|&keyword| lower_prompt.contains(keyword)) {
-            return QueryType::Introspective; // Crucially, identity questions are introspective.
+            return by_rule(QueryType::Introspective, "identity"); // Crucially, identity questions are introspective.
         }
         if INTROSPECTIVE_KEYWORDS.iter().any(|&keyword| lower_prompt.contains(keyword)) {
-            return QueryType::Introspective;
+            return by_rule(QueryType::Introspective, "introspective");
+        }
+        // Comparisons and procedures are checked first: "explique la différence entre..." starts like a factual question.
+        if self.is_comparative_question(prompt) {
+            return by_rule(QueryType::Comparative, "comparative");
+        }
+        if self.is_procedural_question(prompt) {
+            return by_rule(QueryType::Procedural, "procedural");
+        }
+        if creative_writing::is_creative_request(prompt) {
+            return by_rule(QueryType::Creative, "creative");
         }
         // Use the more robust starter check for factual questions.
         if self.is_factual_question(prompt) {
-            return QueryType::Factual;
+            return by_rule(QueryType::Factual, "factual_starter");
         }
         if SOCIAL_KEYWORDS.iter().any(|&keyword| lower_prompt.contains(keyword)) {
-            return QueryType::Social;
+            return by_rule(QueryType::Social, "social");
         }
 
         // --- Priority 2: Fallback to semantic similarity analysis if no keywords match ---
-        let prompt_trace = self.encoder.read().unwrap().encode_raw(prompt);
+        let prompt_trace = self.encoder.read().recover().encode_raw(prompt);
 
         let prototypes = [
             (QueryType::Introspective, &self.introspective_prototype),
             (QueryType::Factual, &self.factual_prototype),
             (QueryType::Creative, &self.creative_prototype),
             (QueryType::Social, &self.social_prototype),
+            (QueryType::Procedural, &self.procedural_prototype),
+            (QueryType::Comparative, &self.comparative_prototype),
         ];
 
         // Find the prototype with the highest cosine similarity.
-        let scores: Vec<_> = prototypes
+        let scores: Vec<(QueryType, f32)> = prototypes
             .iter()
             .map(|(q_type, proto_trace)| {
                 let similarity = prompt_trace.cosine_similarity(proto_trace);
-                (q_type, similarity)
+                (*q_type, similarity)
             })
             .collect();
 
@@ -160,13 +271,16 @@ impl Thalamus {
 
         // If a score is significantly higher than others, choose it. Otherwise, ambiguous.
         const MINIMAL_CONFIDENCE_THRESHOLD: f32 = 0.05;
+        let has_comparative_cue = self.has_comparative_cue(prompt);
         let best_match = scores
             .iter()
+            .filter(|(query_type, _)| *query_type != QueryType::Comparative || has_comparative_cue)
             .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
 
-        match best_match {
-            Some((query_type, similarity)) if *similarity > MINIMAL_CONFIDENCE_THRESHOLD => **query_type,
+        let query_type = match best_match {
+            Some((query_type, similarity)) if *similarity > MINIMAL_CONFIDENCE_THRESHOLD => *query_type,
             _ => QueryType::Ambiguous,
-        }
+        };
+        Classification { query_type, method: ClassificationMethod::Similarity { scores } }
     }
 }
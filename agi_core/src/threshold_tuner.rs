@@ -0,0 +1,243 @@
+//! threshold_tuner.rs - Online tuning of the retrieval parameters (feature `threshold_tuning`).
+//!
+//! The retrieval distance threshold and `top_k` were picked by hand. In experiment mode, a
+//! bandit perturbs them within safe ranges on a fraction of the traffic, rewards each choice
+//! with the self-consistency of the answer it produced (and with user feedback when there is
+//! some), and serves the best arm found so far to the rest of the traffic. Every pull and
+//! every reward is kept in an audit trail.
+
+use crate::config::RetrievalParams;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Safe ranges and experiment settings of the tuner.
+#[derive(Debug, Clone)]
+pub struct TunerConfig {
+    /// Fraction of the prompts (0.0 to 1.0) answered with an exploratory arm.
+    pub traffic_fraction: f64,
+    /// Smallest and largest base distance thresholds that may be tried.
+    pub threshold_range: (f32, f32),
+    /// Step between two tried thresholds.
+    pub threshold_step: f32,
+    /// Values of `top_k` that may be tried.
+    pub top_k_values: Vec<usize>,
+    /// Rewards an arm needs before it can replace the defaults.
+    pub min_pulls: u32,
+    /// Maximum number of records kept in the audit trail.
+    pub audit_capacity: usize,
+    /// Seed for reproducible experiments.
+    pub seed: Option<u64>,
+}
+
+impl Default for TunerConfig {
+    fn default() -> Self {
+        Self {
+            traffic_fraction: 0.1,
+            threshold_range: (0.8, 1.1),
+            threshold_step: 0.05,
+            top_k_values: vec![3, 5, 8],
+            min_pulls: 5,
+            audit_capacity: 1000,
+            seed: None,
+        }
+    }
+}
+
+/// Where a reward came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum RewardSource {
+    SelfConsistency,
+    Feedback,
+}
+
+/// One entry of the audit trail.
+#[derive(Debug, Clone, Serialize)]
+pub enum TuningRecord {
+    /// An arm was chosen to answer a prompt.
+    Pull { pull: u64, arm: usize, params: RetrievalParams, exploring: bool },
+    /// An arm received a reward.
+    Reward { pull: u64, arm: usize, reward: f32, source: RewardSource },
+}
+
+/// Statistics of one candidate set of parameters.
+#[derive(Debug, Clone, Serialize)]
+pub struct Arm {
+    pub params: RetrievalParams,
+    pub rewards: u32,
+    pub total_reward: f32,
+}
+
+impl Arm {
+    pub fn mean_reward(&self) -> f32 {
+        if self.rewards == 0 { 0.0 } else { self.total_reward / self.rewards as f32 }
+    }
+}
+
+/// The parameters chosen for one prompt.
+#[derive(Debug, Clone, Copy)]
+pub struct TuningChoice {
+    pub pull: u64,
+    pub arm: usize,
+    pub params: RetrievalParams,
+    pub exploring: bool,
+}
+
+pub struct ThresholdTuner {
+    config: TunerConfig,
+    arms: Vec<Arm>,
+    default_arm: usize,
+    pulls: u64,
+    /// The last answered pull, which user feedback applies to.
+    last_answered: Option<TuningChoice>,
+    audit: VecDeque<TuningRecord>,
+    rng: StdRng,
+}
+
+impl ThresholdTuner {
+    /// Builds one arm per (threshold, top_k) pair of the safe ranges, plus the defaults.
+    pub fn new(config: TunerConfig, defaults: RetrievalParams) -> Self {
+        let (min_threshold, max_threshold) = config.threshold_range;
+        let step = config.threshold_step.max(0.01);
+        let steps = ((max_threshold - min_threshold) / step).round().max(0.0) as usize;
+
+        let mut arms = Vec::new();
+        for i in 0..=steps {
+            let distance_threshold = min_threshold + step * i as f32;
+            for &top_k in &config.top_k_values {
+                arms.push(Arm { params: RetrievalParams { distance_threshold, top_k }, rewards: 0, total_reward: 0.0 });
+            }
+        }
+        // The grid arm matching the defaults (up to rounding) serves the exact defaults.
+        arms.retain(|arm| arm.params.top_k != defaults.top_k || (arm.params.distance_threshold - defaults.distance_threshold).abs() > 1e-4);
+        arms.push(Arm { params: defaults, rewards: 0, total_reward: 0.0 });
+        let default_arm = arms.len() - 1;
+
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self { config, arms, default_arm, pulls: 0, last_answered: None, audit: VecDeque::new(), rng }
+    }
+
+    /// Chooses the parameters for the next prompt: an exploratory arm on a fraction of the
+    /// traffic (UCB1), the best arm so far otherwise.
+    pub fn choose(&mut self) -> TuningChoice {
+        self.pulls += 1;
+        let exploring = self.rng.gen_bool(self.config.traffic_fraction.clamp(0.0, 1.0));
+        let arm = if exploring { self.upper_confidence_arm() } else { self.best_arm() };
+        let choice = TuningChoice { pull: self.pulls, arm, params: self.arms[arm].params, exploring };
+        if exploring {
+            println!(
+                "--- Tuner: pull {} explores arm {} (threshold {:.2}, top_k {}) ---",
+                choice.pull, arm, choice.params.distance_threshold, choice.params.top_k
+            );
+        }
+        self.log(TuningRecord::Pull { pull: choice.pull, arm, params: choice.params, exploring });
+        choice
+    }
+
+    /// Rewards the arm used for an answer with its self-consistency score (0.0 to 1.0).
+    pub fn record_answer(&mut self, choice: TuningChoice, self_consistency: f32) {
+        self.reward(choice, self_consistency, RewardSource::SelfConsistency);
+        self.last_answered = Some(choice);
+    }
+
+    /// Rewards the arm of the last answer with user feedback (0.0 to 1.0).
+    /// Returns false if no answer was produced since startup.
+    pub fn record_feedback(&mut self, reward: f32) -> bool {
+        match self.last_answered {
+            Some(choice) => {
+                self.reward(choice, reward, RewardSource::Feedback);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The arm with the best mean reward among those rewarded often enough, or the defaults.
+    pub fn best_arm(&self) -> usize {
+        self.arms
+            .iter()
+            .enumerate()
+            .filter(|(_, arm)| arm.rewards >= self.config.min_pulls)
+            .max_by(|a, b| a.1.mean_reward().total_cmp(&b.1.mean_reward()))
+            .map(|(index, _)| index)
+            .unwrap_or(self.default_arm)
+    }
+
+    /// The parameters currently served to the non-experimental traffic.
+    pub fn current_params(&self) -> RetrievalParams {
+        self.arms[self.best_arm()].params
+    }
+
+    pub fn arms(&self) -> &[Arm] {
+        &self.arms
+    }
+
+    /// Pulls and rewards, oldest first.
+    pub fn audit_trail(&self) -> impl Iterator<Item = &TuningRecord> {
+        self.audit.iter()
+    }
+
+    fn upper_confidence_arm(&self) -> usize {
+        if let Some(untried) = self.arms.iter().position(|arm| arm.rewards == 0) {
+            return untried;
+        }
+        let total: u32 = self.arms.iter().map(|arm| arm.rewards).sum();
+        let ln_total = (total.max(1) as f32).ln();
+        self.arms
+            .iter()
+            .enumerate()
+            .max_by(|a, b| {
+                let score = |arm: &Arm| arm.mean_reward() + (2.0 * ln_total / arm.rewards as f32).sqrt();
+                score(a.1).total_cmp(&score(b.1))
+            })
+            .map(|(index, _)| index)
+            .unwrap_or(self.default_arm)
+    }
+
+    fn reward(&mut self, choice: TuningChoice, reward: f32, source: RewardSource) {
+        let reward = reward.clamp(0.0, 1.0);
+        if let Some(arm) = self.arms.get_mut(choice.arm) {
+            arm.rewards += 1;
+            arm.total_reward += reward;
+        }
+        self.log(TuningRecord::Reward { pull: choice.pull, arm: choice.arm, reward, source });
+    }
+
+    fn log(&mut self, record: TuningRecord) {
+        self.audit.push_back(record);
+        while self.audit.len() > self.config.audit_capacity {
+            self.audit.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tuner_converges_to_the_better_arm() {
+        let config = TunerConfig { traffic_fraction: 0.5, seed: Some(7), ..TunerConfig::default() };
+        let mut tuner = ThresholdTuner::new(config, RetrievalParams::default());
+        let default_params = tuner.current_params();
+        assert_eq!(default_params, RetrievalParams::default());
+
+        // Simulated environment: the answers are best with a tight threshold and a small top_k.
+        for _ in 0..2000 {
+            let choice = tuner.choose();
+            let params = choice.params;
+            let reward = 1.0 - (params.distance_threshold - 0.85).abs() - (params.top_k as f32 - 3.0).abs() * 0.05;
+            tuner.record_answer(choice, reward);
+        }
+
+        let best = tuner.current_params();
+        assert_eq!(best.top_k, 3);
+        assert!((best.distance_threshold - 0.85).abs() < 1e-4);
+        assert!(tuner.audit_trail().count() <= 1000);
+        assert!(tuner.record_feedback(1.0));
+    }
+}
@@ -0,0 +1,197 @@
+//! tokenizer.rs - Shared tokenizer for concept extraction.
+//!
+//! Splitting on whitespace and trimming punctuation mangles French elisions ("l'énergie"),
+//! hyphenated terms ("vis-à-vis") and numbers ("3,14", "10 km"). This tokenizer follows the
+//! Unicode word boundaries (UAX #29), then applies a few language-aware fix-ups, so that the
+//! encoder, the thalamus, the sensory cortex and the inner drive all see the same tokens.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What a token represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A plain word.
+    Word,
+    /// Words joined by hyphens or a formula ("vis-à-vis", "e=mc²").
+    Compound,
+    /// A number, with its unit if it has one ("3,14", "10km", "10 km", "50%").
+    Number,
+    /// An elided article or pronoun split off a word ("l'", "d'", "qu'").
+    Elision,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The token as written (typographic apostrophes are normalized to `'`).
+    pub text: String,
+    pub kind: TokenKind,
+}
+
+impl Token {
+    fn new(text: impl Into<String>, kind: TokenKind) -> Self {
+        Self { text: text.into(), kind }
+    }
+
+    /// The lowercased form used as a concept key.
+    pub fn normalized(&self) -> String {
+        self.text.to_lowercase()
+    }
+
+    pub fn is_content(&self) -> bool {
+        self.kind != TokenKind::Elision
+    }
+}
+
+/// Elided forms that are split off the following word.
+const ELISIONS: &[&str] = &["l", "d", "j", "m", "n", "s", "t", "c", "qu", "jusqu", "lorsqu", "puisqu", "quoiqu"];
+
+/// Units that stay attached to a preceding number even when separated by a space. A one-letter
+/// unit is also an ordinary word ("en 1789 a eu lieu"): it only stays attached at the end of a
+/// sentence or clause ("un câble de 5 m.").
+const UNITS: &[&str] = &[
+    "km", "m", "cm", "mm", "nm", "kg", "g", "mg", "t", "l", "ml", "s", "ms", "h", "min", "j",
+    "°c", "°f", "k", "w", "kw", "v", "a", "hz", "khz", "mhz", "ghz", "o", "ko", "mo", "go", "kb", "mb", "gb",
+];
+
+/// Characters that join two word segments into one compound token.
+const JOINERS: &[&str] = &["-", "=", "+", "/"];
+
+/// Splits `text` into tokens. Punctuation and whitespace are dropped.
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let normalized = text.replace(['’', 'ʼ'], "'");
+    let mut tokens: Vec<Token> = Vec::new();
+
+    let mut chunks = normalized.split_whitespace().peekable();
+    while let Some(chunk) = chunks.next() {
+        // A number followed by a unit on its own ("10 km") becomes a single measure.
+        if let Some(previous) = tokens.last_mut() {
+            let unit = chunk.trim_end_matches(|c: char| c.is_ascii_punctuation()).to_lowercase();
+            let ends_clause = unit.len() < chunk.len() || chunks.peek().is_none();
+            if previous.kind == TokenKind::Number
+                && UNITS.contains(&unit.as_str())
+                && (unit.chars().count() > 1 || ends_clause)
+                && previous.text.chars().last().is_some_and(|c| c.is_numeric())
+            {
+                previous.text = format!("{} {}", previous.text, &chunk[..unit.len()]);
+                continue;
+            }
+        }
+        tokenize_chunk(chunk, &mut tokens);
+    }
+
+    tokens
+}
+
+/// The lowercased content tokens of `text` (elisions removed).
+pub fn content_words(text: &str) -> Vec<String> {
+    tokenize(text).into_iter().filter(Token::is_content).map(|token| token.normalized()).collect()
+}
+
+fn tokenize_chunk(chunk: &str, tokens: &mut Vec<Token>) {
+    let segments: Vec<&str> = chunk.split_word_bounds().collect();
+    let mut current: Option<Token> = None;
+    let mut pending_joiner: Option<&str> = None;
+
+    for segment in segments {
+        let is_wordlike = segment.chars().any(|c| c.is_alphanumeric());
+
+        if !is_wordlike {
+            match (&mut current, segment) {
+                // Percentages and degrees belong to their number.
+                (Some(token), "%" | "°") if token.kind == TokenKind::Number && pending_joiner.is_none() => {
+                    token.text.push_str(segment);
+                }
+                (Some(_), joiner) if JOINERS.contains(&joiner) && pending_joiner.is_none() => pending_joiner = Some(joiner),
+                _ => {
+                    tokens.extend(current.take());
+                    pending_joiner = None;
+                }
+            }
+            continue;
+        }
+
+        // Superscripts are separate segments ("mc", "²") but belong to the preceding token.
+        if let Some(token) = current.as_mut().filter(|_| pending_joiner.is_none()) {
+            if segment.chars().all(|c| c.is_numeric() && !c.is_ascii_digit()) {
+                token.text.push_str(segment);
+                continue;
+            }
+        }
+
+        let (elision, word) = split_elision(segment);
+        if let Some(elision) = elision {
+            tokens.extend(current.take());
+            pending_joiner = None;
+            tokens.push(Token::new(elision, TokenKind::Elision));
+        }
+
+        let kind = if word.chars().next().is_some_and(|c| c.is_numeric()) { TokenKind::Number } else { TokenKind::Word };
+        match (current.as_mut(), pending_joiner.take()) {
+            (Some(token), Some(joiner)) => {
+                token.text.push_str(joiner);
+                token.text.push_str(word);
+                token.kind = TokenKind::Compound;
+            }
+            _ => {
+                tokens.extend(current.take());
+                current = Some(Token::new(word, kind));
+            }
+        }
+    }
+
+    tokens.extend(current);
+}
+
+/// Splits a leading French elision ("l'énergie" -> "l'", "énergie"). Words like
+/// "aujourd'hui" or English contractions ("it's") are left whole.
+fn split_elision(segment: &str) -> (Option<&str>, &str) {
+    if let Some(position) = segment.find('\'') {
+        let (prefix, rest) = segment.split_at(position);
+        let word = &rest[1..];
+        if !word.is_empty() && ELISIONS.contains(&prefix.to_lowercase().as_str()) {
+            return (Some(&segment[..=position]), word);
+        }
+    }
+    (None, segment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(text: &str) -> Vec<(String, TokenKind)> {
+        tokenize(text).into_iter().map(|token| (token.text, token.kind)).collect()
+    }
+
+    #[test]
+    fn test_elisions_are_split() {
+        assert_eq!(
+            texts("L’énergie qu'il a vue aujourd'hui"),
+            vec![
+                ("L'".to_string(), TokenKind::Elision),
+                ("énergie".to_string(), TokenKind::Word),
+                ("qu'".to_string(), TokenKind::Elision),
+                ("il".to_string(), TokenKind::Word),
+                ("a".to_string(), TokenKind::Word),
+                ("vue".to_string(), TokenKind::Word),
+                ("aujourd'hui".to_string(), TokenKind::Word),
+            ]
+        );
+        assert_eq!(content_words("what's up?"), vec!["what's", "up"]);
+    }
+
+    #[test]
+    fn test_compounds_and_numbers_are_preserved() {
+        assert_eq!(content_words("Pi vaut 3,14 ; la course fait 10km, soit 10 km."), vec!["pi", "vaut", "3,14", "la", "course", "fait", "10km", "soit", "10 km"]);
+        assert_eq!(content_words("E=mc² et le vis-à-vis (50%)"), vec!["e=mc²", "et", "le", "vis-à-vis", "50%"]);
+        assert_eq!(content_words("un tiret - isolé"), vec!["un", "tiret", "isolé"]);
+    }
+
+    #[test]
+    fn test_one_letter_units_need_the_end_of_a_clause() {
+        assert_eq!(content_words("En 1789 a eu lieu la Révolution"), vec!["en", "1789", "a", "eu", "lieu", "la", "révolution"]);
+        assert_eq!(content_words("sacré en 1804 a Paris"), vec!["sacré", "en", "1804", "a", "paris"]);
+        assert_eq!(content_words("un câble de 5 m, puis 5m"), vec!["un", "câble", "de", "5 m", "puis", "5m"]);
+        assert_eq!(content_words("la pile débite 2 a"), vec!["la", "pile", "débite", "2 a"]);
+    }
+}
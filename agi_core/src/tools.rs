@@ -0,0 +1,240 @@
+//! tools.rs - Actions the motor cortex can ask for instead of only writing text.
+//!
+//! `MotorCortex::plan_actions` emits `ActionRequest`s (a web search, a calculation, a file to
+//! read); the Core routes each of them to the `ToolHandler` registered for its kind, turns the
+//! results into transient memories and reasons again with them before phrasing the final
+//! answer. Only the calculator is registered by default: reading files and searching the web
+//! must be allowed explicitly (see `Core::register_tool`).
+
+use crate::capabilities::Capability;
+use crate::episodic_memory;
+use crate::knowledge_explorer::{DocumentError, KnowledgeExplorer};
+use crate::lemmatizer::Lang;
+use crate::numeric_reasoner::{self, Date};
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The kinds of action, one handler each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    WebSearch,
+    Calculator,
+    FileRead,
+}
+
+impl ActionKind {
+    /// What a registered handler of this kind lets the Core do.
+    pub fn capability(self) -> Capability {
+        match self {
+            ActionKind::WebSearch => Capability {
+                name: "web_search",
+                description: "Search the web for what I do not know",
+                description_fr: "Chercher sur le web ce que je ne sais pas",
+                examples: &["Search the web for the latest Rust release", "Cherche sur le web la population de Lyon"],
+            },
+            ActionKind::Calculator => Capability {
+                name: "calculator",
+                description: "Call a calculator for the computations in my answers",
+                description_fr: "Appeler une calculatrice pour les calculs de mes réponses",
+                examples: &["Calcule 17 * 23", "How many meters in 3 km?"],
+            },
+            ActionKind::FileRead => Capability {
+                name: "file_read",
+                description: "Read the files of an allowed directory",
+                description_fr: "Lire les fichiers d'un répertoire autorisé",
+                examples: &["Lis le fichier notes.txt", "Read the file report.md"],
+            },
+        }
+    }
+}
+
+/// An action requested by the motor cortex.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "tool", rename_all = "snake_case")]
+pub enum ActionRequest {
+    WebSearch { query: String },
+    /// A calculation or a conversion, written as a question `numeric_reasoner` understands.
+    Calculate { expression: String },
+    ReadFile { path: String },
+}
+
+impl ActionRequest {
+    pub fn kind(&self) -> ActionKind {
+        match self {
+            ActionRequest::WebSearch { .. } => ActionKind::WebSearch,
+            ActionRequest::Calculate { .. } => ActionKind::Calculator,
+            ActionRequest::ReadFile { .. } => ActionKind::FileRead,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ToolError {
+    #[error("the handler for {0:?} cannot serve this request")]
+    WrongRequest(ActionKind),
+    #[error("nothing to compute in '{0}'")]
+    NotComputable(String),
+    #[error("'{0}' is outside of the readable directory")]
+    OutsideRoot(String),
+    #[error(transparent)]
+    Document(#[from] DocumentError),
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// Carries out the actions of one kind. The results are passages of text, each learned as one
+/// transient memory for the rest of the turn.
+pub trait ToolHandler: Send + Sync {
+    fn kind(&self) -> ActionKind;
+    fn call(&self, request: &ActionRequest, lang: Lang) -> Result<Vec<String>, ToolError>;
+}
+
+/// An action carried out during a turn, as recorded in the provenance of the answer.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolCall {
+    pub request: ActionRequest,
+    pub output: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The handlers the Core routes actions to, one per kind.
+pub struct ToolRegistry {
+    handlers: Vec<Box<dyn ToolHandler>>,
+}
+
+impl fmt::Debug for ToolRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolRegistry").field("handlers", &self.handlers.iter().map(|handler| handler.kind()).collect::<Vec<_>>()).finish()
+    }
+}
+
+impl Default for ToolRegistry {
+    fn default() -> Self {
+        Self { handlers: vec![Box::new(Calculator)] }
+    }
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a handler, replacing the one of the same kind.
+    pub fn register(&mut self, handler: Box<dyn ToolHandler>) {
+        self.handlers.retain(|registered| registered.kind() != handler.kind());
+        self.handlers.push(handler);
+    }
+
+    /// The kinds of action with a registered handler, in registration order.
+    pub fn kinds(&self) -> impl Iterator<Item = ActionKind> + '_ {
+        self.handlers.iter().map(|handler| handler.kind())
+    }
+
+    pub fn handles(&self, kind: ActionKind) -> bool {
+        self.handlers.iter().any(|handler| handler.kind() == kind)
+    }
+
+    /// Carries out a request. None when no handler is registered for its kind.
+    pub fn call(&self, request: &ActionRequest, lang: Lang) -> Option<ToolCall> {
+        let handler = self.handlers.iter().find(|handler| handler.kind() == request.kind())?;
+        let (output, error) = match handler.call(request, lang) {
+            Ok(output) => (output, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+        Some(ToolCall { request: request.clone(), output, error })
+    }
+}
+
+/// Computes calculations and conversions with `numeric_reasoner`.
+#[derive(Debug, Default)]
+pub struct Calculator;
+
+impl ToolHandler for Calculator {
+    fn kind(&self) -> ActionKind {
+        ActionKind::Calculator
+    }
+
+    fn call(&self, request: &ActionRequest, lang: Lang) -> Result<Vec<String>, ToolError> {
+        let ActionRequest::Calculate { expression } = request else {
+            return Err(ToolError::WrongRequest(self.kind()));
+        };
+        let current_year = Date::year_of_day((episodic_memory::now() / 86_400) as i64);
+        let question = numeric_reasoner::parse_question(expression, current_year).ok_or_else(|| ToolError::NotComputable(expression.clone()))?;
+        Ok(vec![numeric_reasoner::describe(&question.answer(), lang)])
+    }
+}
+
+/// Reads documents (text, HTML, PDF) under a root directory, and nowhere else.
+#[derive(Debug)]
+pub struct FileReader {
+    root: PathBuf,
+    max_passages: usize,
+}
+
+impl FileReader {
+    /// Number of passages returned by default.
+    pub const DEFAULT_MAX_PASSAGES: usize = 20;
+
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), max_passages: Self::DEFAULT_MAX_PASSAGES }
+    }
+
+    pub fn with_max_passages(self, max_passages: usize) -> Self {
+        Self { max_passages, ..self }
+    }
+
+    /// The requested path, resolved under the root. Fails if it leads outside of it.
+    fn resolve(&self, path: &str) -> Result<PathBuf, ToolError> {
+        let root = self.root.canonicalize().map_err(DocumentError::from)?;
+        let resolved = root.join(Path::new(path)).canonicalize().map_err(DocumentError::from)?;
+        if resolved.starts_with(&root) {
+            Ok(resolved)
+        } else {
+            Err(ToolError::OutsideRoot(path.to_string()))
+        }
+    }
+}
+
+impl ToolHandler for FileReader {
+    fn kind(&self) -> ActionKind {
+        ActionKind::FileRead
+    }
+
+    fn call(&self, request: &ActionRequest, _lang: Lang) -> Result<Vec<String>, ToolError> {
+        let ActionRequest::ReadFile { path } = request else {
+            return Err(ToolError::WrongRequest(self.kind()));
+        };
+        let mut explorer = KnowledgeExplorer::new();
+        explorer.load_and_process_file(self.resolve(path)?)?;
+        Ok(explorer.get_discovered_concepts().into_iter().take(self.max_passages).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_routes_requests_to_their_handler() {
+        let mut tools = ToolRegistry::new();
+        let conversion = ActionRequest::Calculate { expression: "convert 8849 m to ft".to_string() };
+        let call = tools.call(&conversion, Lang::English).unwrap();
+        assert_eq!(call.output, vec!["8849 m = 29032.152231 ft"]);
+        assert!(call.error.is_none());
+        assert!(tools.call(&ActionRequest::WebSearch { query: "quasar".to_string() }, Lang::English).is_none(), "No web search by default");
+
+        let dir = std::env::temp_dir().join(format!("neurova_tools_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("notes.txt"), "Le quasar est un noyau de galaxie.\nIl est très lumineux.").unwrap();
+        tools.register(Box::new(FileReader::new(&dir)));
+        let read = tools.call(&ActionRequest::ReadFile { path: "notes.txt".to_string() }, Lang::French).unwrap();
+        assert_eq!(read.output, vec!["Le quasar est un noyau de galaxie.", "Il est très lumineux."]);
+        let escape = tools.call(&ActionRequest::ReadFile { path: "../../etc/passwd".to_string() }, Lang::French).unwrap();
+        assert!(escape.output.is_empty() && escape.error.is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+//! trace_compression.rs - Smaller traces for the memories nobody asks about.
+//!
+//! A memory trace is a 1024-dimensional superposition plus one pattern of the same size per
+//! concept it mentions, most of them with a TF-IDF weight too small to matter. The compression
+//! pass (`Hippocampus::compress`) prunes those concepts from every memory, and projects the
+//! traces of cold memories (not used in an answer for a while) onto a few dimensions with a
+//! seeded random projection, which keeps their distances to other traces close enough to be
+//! found again (Johnson-Lindenstrauss). A query is projected once to be compared with them. When
+//! a cold memory contributes to an answer it is re-encoded from its text at full size (see
+//! `Core::compress_traces`).
+//!
+//! `MemoryUsageReport` tells how many bytes each subsystem holds, to see what compression wins.
+
+use crate::holographic_memory::{HolographicTrace, QuantizedComplex, WeightedConcept};
+use crate::symbols::Sym;
+use crate::trace_math;
+use nalgebra::Complex;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::mem::{size_of, size_of_val};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompressionConfig {
+    /// Concepts carrying less than this share of the weight of a trace are pruned (at least the
+    /// heaviest concept is kept).
+    pub min_concept_share: f32,
+    /// A memory not used in an answer (or learned) for this long is cold, in seconds. Axioms are
+    /// never compressed.
+    pub cold_after_secs: u64,
+    /// Dimensions of the compressed traces.
+    pub compressed_dimension: usize,
+    /// Seed of the projection; traces projected with different seeds cannot be compared.
+    pub seed: u64,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self { min_concept_share: 0.02, cold_after_secs: 7 * 86_400, compressed_dimension: 128, seed: 0x7ace }
+    }
+}
+
+/// A seeded random projection from `from` to `to` dimensions.
+#[derive(Debug, Clone)]
+pub struct TraceProjection {
+    from: usize,
+    to: usize,
+    /// `to` rows of `from` random phases.
+    matrix: Vec<Complex<f32>>,
+}
+
+impl TraceProjection {
+    pub fn new(from: usize, to: usize, seed: u64) -> Self {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+        let matrix = (0..from * to).map(|_| Complex::from_polar(1.0, rng.gen_range(0.0..std::f32::consts::TAU))).collect();
+        Self { from, to, matrix }
+    }
+
+    pub fn from_dimension(&self) -> usize {
+        self.from
+    }
+
+    pub fn to_dimension(&self) -> usize {
+        self.to
+    }
+
+    /// Projects a pattern, keeping its norm.
+    fn project(&self, pattern: &[QuantizedComplex]) -> Vec<QuantizedComplex> {
+        let values = trace_math::dequantize(pattern);
+        let mut projected: Vec<Complex<f32>> =
+            self.matrix.chunks_exact(self.from).map(|row| row.iter().zip(&values).map(|(weight, value)| weight * value).sum()).collect();
+        let norm = projected.iter().map(|c| c.norm_sqr()).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            let factor = trace_math::norm_sqr(pattern).sqrt() / norm;
+            projected.iter_mut().for_each(|c| *c *= factor);
+        }
+        trace_math::quantize(&projected)
+    }
+
+    /// Whether `trace` is already projected by this projection.
+    pub fn is_projected(&self, trace: &HolographicTrace) -> bool {
+        trace.superposition_pattern.len() == self.to && self.to != self.from
+    }
+
+    /// `trace` in the projected space, for comparisons with compressed traces; the trace itself
+    /// if it is already compressed or of another size.
+    pub fn projected(&self, trace: &HolographicTrace) -> HolographicTrace {
+        let mut projected = HolographicTrace { weighted_concepts: Default::default(), superposition_pattern: trace.superposition_pattern.clone() };
+        self.compress(&mut projected);
+        projected
+    }
+
+    /// Projects a full-size trace, its concept patterns included. Returns whether it was.
+    pub fn compress(&self, trace: &mut HolographicTrace) -> bool {
+        if trace.superposition_pattern.len() != self.from {
+            return false;
+        }
+        trace.superposition_pattern = self.project(&trace.superposition_pattern);
+        for concept in trace.weighted_concepts.values_mut() {
+            if concept.interference_pattern.len() == self.from {
+                concept.interference_pattern = self.project(&concept.interference_pattern);
+            }
+        }
+        true
+    }
+}
+
+/// Removes the concepts of `trace` carrying less than `min_share` of its weight, keeping the
+/// heaviest. Returns how many were removed.
+pub fn prune_concepts(trace: &mut HolographicTrace, min_share: f32) -> usize {
+    let Some(heaviest) = trace.weighted_concepts.iter().max_by(|a, b| a.1.relevance.total_cmp(&b.1.relevance)).map(|(&name, _)| name) else {
+        return 0;
+    };
+    let light: Vec<_> = trace.weighted_concepts.keys().copied().filter(|&name| name != heaviest && trace.symbol_share(name) < min_share).collect();
+    for name in &light {
+        trace.weighted_concepts.remove(name);
+    }
+    light.len()
+}
+
+/// Bytes held by a trace and its patterns (the concept names are shared, see `symbols`).
+pub fn trace_bytes(trace: &HolographicTrace) -> usize {
+    let pattern = |pattern: &[QuantizedComplex]| size_of_val(pattern);
+    let concept = size_of::<Sym>() + size_of::<WeightedConcept>();
+    size_of::<HolographicTrace>()
+        + pattern(&trace.superposition_pattern)
+        + trace.weighted_concepts.values().map(|weighted| concept + pattern(&weighted.interference_pattern)).sum::<usize>()
+}
+
+/// What a compression pass did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CompressionReport {
+    pub concepts_pruned: usize,
+    pub memories_compressed: usize,
+    /// Bytes of the memory traces before and after the pass.
+    pub bytes_before: usize,
+    pub bytes_after: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SubsystemUsage {
+    pub name: &'static str,
+    /// Memories, concepts, synapses... whatever the subsystem holds.
+    pub items: usize,
+    pub bytes: usize,
+}
+
+/// Approximate heap usage of the Core, by subsystem (see `Core::memory_usage`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MemoryUsageReport {
+    pub subsystems: Vec<SubsystemUsage>,
+    pub total_bytes: usize,
+    /// Memories whose traces are compressed.
+    pub compressed_memories: usize,
+}
+
+impl MemoryUsageReport {
+    pub fn push(&mut self, name: &'static str, items: usize, bytes: usize) {
+        self.total_bytes += bytes;
+        self.subsystems.push(SubsystemUsage { name, items, bytes });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holographic_memory::HolographicEncoder;
+
+    #[test]
+    fn test_projection_keeps_the_nearest_trace() {
+        let mut encoder = HolographicEncoder::new(1024);
+        let texts = ["Le chien aboie dans le jardin.", "Le chat dort sur le canapé.", "La lune tourne autour de la Terre.", "Le soleil est une étoile."];
+        for text in texts {
+            encoder.add_document(text);
+        }
+        let projection = TraceProjection::new(1024, 128, 7);
+        let mut traces: Vec<_> = texts.iter().map(|text| encoder.encode(text)).collect();
+        let before: usize = traces.iter().map(trace_bytes).sum();
+        for trace in &mut traces {
+            assert!(projection.compress(trace));
+            assert!(!projection.compress(trace), "A compressed trace stays as it is");
+        }
+        assert!(traces.iter().map(trace_bytes).sum::<usize>() * 4 < before);
+
+        let query = projection.projected(&encoder.encode("Pourquoi le chien aboie-t-il ?"));
+        let nearest = traces.iter().enumerate().min_by(|a, b| query.distance(a.1).total_cmp(&query.distance(b.1))).map(|(index, _)| index);
+        assert_eq!(nearest, Some(0));
+
+        let mut trace = encoder.encode("Le chien aboie dans le jardin.");
+        let concepts = trace.weighted_concepts.len();
+        let pruned = prune_concepts(&mut trace, 0.3);
+        assert!(pruned > 0 && trace.weighted_concepts.len() == concepts - pruned && !trace.weighted_concepts.is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+// agi_core/src/trace_math.rs
+
+//! Vectorized kernels over Q1.15 holographic patterns.
+//!
+//! A pattern holds ~1024 complex values and is compared with thousands of memories per query.
+//! These kernels process four complex values (eight `i16` lanes) at a time with `wide`, and
+//! finish the tail with scalar code. They compute the same results as the per-element methods
+//! of `QuantizedComplex`, up to the order of the floating-point sums.
+
+use crate::holographic_memory::QuantizedComplex;
+use nalgebra::Complex;
+use wide::{f32x8, i16x8, i32x8};
+
+const SCALE: f32 = 32767.0;
+const INV_SCALE: f32 = 1.0 / 32767.0;
+/// Complex values per vector.
+const LANES: usize = 4;
+
+fn lanes(chunk: &[QuantizedComplex]) -> [i16; 8] {
+    [
+        chunk[0].real, chunk[0].imag, chunk[1].real, chunk[1].imag,
+        chunk[2].real, chunk[2].imag, chunk[3].real, chunk[3].imag,
+    ]
+}
+
+fn lanes_f32(chunk: &[QuantizedComplex]) -> f32x8 {
+    f32x8::new(lanes(chunk).map(f32::from))
+}
+
+fn store(chunk: &mut [QuantizedComplex], values: [i16; 8]) {
+    for (c, pair) in chunk.iter_mut().zip(values.chunks_exact(2)) {
+        *c = QuantizedComplex { real: pair[0], imag: pair[1] };
+    }
+}
+
+/// Real part of `Σ a·conj(b)`, over the common length of the two patterns.
+pub fn dot(a: &[QuantizedComplex], b: &[QuantizedComplex]) -> f32 {
+    let len = a.len().min(b.len());
+    let (a_chunks, b_chunks) = (a[..len].chunks_exact(LANES), b[..len].chunks_exact(LANES));
+    let tail: f32 = a_chunks
+        .remainder()
+        .iter()
+        .zip(b_chunks.remainder())
+        .map(|(x, y)| f32::from(x.real) * f32::from(y.real) + f32::from(x.imag) * f32::from(y.imag))
+        .sum();
+    let mut sum = f32x8::ZERO;
+    for (x, y) in a_chunks.zip(b_chunks) {
+        sum = lanes_f32(x).mul_add(lanes_f32(y), sum);
+    }
+    (sum.reduce_add() + tail) * INV_SCALE * INV_SCALE
+}
+
+/// `Σ |c|²` over the pattern.
+pub fn norm_sqr(pattern: &[QuantizedComplex]) -> f32 {
+    dot(pattern, pattern)
+}
+
+/// Cosine similarity of two patterns, in [-1, 1]; 0 when one of them is null.
+pub fn cosine_similarity(a: &[QuantizedComplex], b: &[QuantizedComplex]) -> f32 {
+    let norms = norm_sqr(a).sqrt() * norm_sqr(b).sqrt();
+    if norms == 0.0 {
+        return 0.0;
+    }
+    (dot(a, b) / norms).clamp(-1.0, 1.0)
+}
+
+/// Adds `other` into `acc` element by element, saturating like `QuantizedComplex::add`.
+pub fn add_saturating(acc: &mut [QuantizedComplex], other: &[QuantizedComplex]) {
+    let len = acc.len().min(other.len());
+    let mut acc_chunks = acc[..len].chunks_exact_mut(LANES);
+    let other_chunks = other[..len].chunks_exact(LANES);
+    let other_tail = other_chunks.remainder();
+    for (x, y) in acc_chunks.by_ref().zip(other_chunks) {
+        let sum = i16x8::new(lanes(x)).saturating_add(i16x8::new(lanes(y)));
+        store(x, sum.to_array());
+    }
+    for (x, y) in acc_chunks.into_remainder().iter_mut().zip(other_tail) {
+        *x = x.add(*y);
+    }
+}
+
+/// Scales the pattern in place, like `QuantizedComplex::scale`.
+pub fn scale(pattern: &mut [QuantizedComplex], factor: f32) {
+    let scale = (factor.clamp(-1.0, 0.99997) * SCALE) as i16;
+    let factor_lanes = i32x8::splat(i32::from(scale));
+    let mut chunks = pattern.chunks_exact_mut(LANES);
+    for chunk in chunks.by_ref() {
+        let scaled = (i32x8::from(i16x8::new(lanes(chunk))) * factor_lanes) >> 15_i32;
+        store(chunk, scaled.to_array().map(|v| v as i16));
+    }
+    for c in chunks.into_remainder() {
+        *c = c.scale(factor);
+    }
+}
+
+/// Converts a pattern to Q1.15, like `QuantizedComplex::from_complex`.
+pub fn quantize(values: &[Complex<f32>]) -> Vec<QuantizedComplex> {
+    let mut pattern = Vec::with_capacity(values.len());
+    let chunks = values.chunks_exact(LANES);
+    let tail = chunks.remainder();
+    let (low, high) = (f32x8::splat(-1.0), f32x8::splat(0.99997));
+    for chunk in chunks {
+        let parts = f32x8::new([
+            chunk[0].re, chunk[0].im, chunk[1].re, chunk[1].im,
+            chunk[2].re, chunk[2].im, chunk[3].re, chunk[3].im,
+        ]);
+        let quantized = (parts.max(low).min(high) * SCALE).trunc_int().to_array();
+        pattern.extend(quantized.chunks_exact(2).map(|pair| QuantizedComplex { real: pair[0] as i16, imag: pair[1] as i16 }));
+    }
+    pattern.extend(tail.iter().map(|&c| QuantizedComplex::from_complex(c)));
+    pattern
+}
+
+/// Converts a Q1.15 pattern back to floating point, like `QuantizedComplex::to_complex`.
+pub fn dequantize(pattern: &[QuantizedComplex]) -> Vec<Complex<f32>> {
+    let mut values = Vec::with_capacity(pattern.len());
+    let chunks = pattern.chunks_exact(LANES);
+    let tail = chunks.remainder();
+    for chunk in chunks {
+        let parts = (lanes_f32(chunk) * INV_SCALE).to_array();
+        values.extend(parts.chunks_exact(2).map(|pair| Complex::new(pair[0], pair[1])));
+    }
+    values.extend(tail.iter().map(|c| c.to_complex()));
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(seed: i32, len: usize) -> Vec<QuantizedComplex> {
+        (0..len as i32)
+            .map(|i| QuantizedComplex { real: ((i * 7919 + seed * 104_729) % 65_536 - 32_768) as i16, imag: ((i * 6151 + seed) % 65_536 - 32_768) as i16 })
+            .collect()
+    }
+
+    #[test]
+    fn test_kernels_match_the_scalar_operations() {
+        // 1027 leaves a tail of three values after the vectors.
+        let (a, b) = (pattern(1, 1027), pattern(2, 1027));
+
+        let scalar_dot: f32 = a.iter().zip(&b).map(|(x, y)| x.to_complex().re * y.to_complex().re + x.to_complex().im * y.to_complex().im).sum();
+        assert!((dot(&a, &b) - scalar_dot).abs() <= scalar_dot.abs() * 1e-4 + 1e-3);
+        let scalar_norm: f32 = a.iter().map(|c| c.norm_sqr()).sum();
+        assert!((norm_sqr(&a) - scalar_norm).abs() <= scalar_norm * 1e-4);
+        assert!((cosine_similarity(&a, &a) - 1.0).abs() < 1e-5);
+        assert_eq!(cosine_similarity(&a, &[QuantizedComplex::ZERO; 1027]), 0.0);
+
+        let mut sum = a.clone();
+        add_saturating(&mut sum, &b);
+        assert_eq!(sum, a.iter().zip(&b).map(|(x, y)| x.add(*y)).collect::<Vec<_>>());
+
+        let mut scaled = a.clone();
+        scale(&mut scaled, 0.37);
+        assert_eq!(scaled, a.iter().map(|c| c.scale(0.37)).collect::<Vec<_>>());
+
+        let values = dequantize(&a);
+        assert_eq!(values, a.iter().map(|c| c.to_complex()).collect::<Vec<_>>());
+        assert_eq!(quantize(&values), values.iter().map(|&c| QuantizedComplex::from_complex(c)).collect::<Vec<_>>());
+    }
+}
@@ -1,8 +1,8 @@
 // agi_core/src/trace_visualizer.rs
 
 use crate::holographic_memory::HolographicTrace;
-use image::{Rgb, RgbImage};
-use nalgebra::ComplexField;
+use image::{ImageFormat, Rgb, RgbImage};
+use std::io::Cursor;
 use std::f32::consts::PI;
 
 /// Generates a unique visual representation (a "mandala") of a holographic trace.
@@ -29,7 +29,7 @@ pub fn generate_trace_image(trace: &HolographicTrace, width: u32, height: u32) -
     let mut max_magnitude = 0.0;
     for concept in trace.weighted_concepts.values() {
         for c in &concept.interference_pattern {
-            let mag = c.norm();
+            let mag = c.to_complex().norm();
             if mag > max_magnitude {
                 max_magnitude = mag;
             }
@@ -39,17 +39,17 @@ pub fn generate_trace_image(trace: &HolographicTrace, width: u32, height: u32) -
 
     for (concept_name, concept) in &trace.weighted_concepts {
         // Use a djb2-like hash function to get a well-distributed base hue from the concept name.
-        let mut hash = 5381_u32;
-        for byte in concept_name.bytes() {
-            hash = (hash.wrapping_shl(5)).wrapping_add(hash).wrapping_add(byte as u32); // hash * 33 + byte
-        }
+        let hash = concept_name.with_str(|name| {
+            name.bytes().fold(5381_u32, |hash, byte| hash.wrapping_shl(5).wrapping_add(hash).wrapping_add(byte as u32)) // hash * 33 + byte
+        });
         let base_hue = (hash % 360) as f32;
 
         let concept_relevance = concept.relevance;
 
         for (i, c) in concept.interference_pattern.iter().enumerate() {
+            let c = c.to_complex();
             let magnitude = c.norm() / max_magnitude; // Normalized magnitude (0 to 1)
-            let phase = c.argument(); // Phase (-PI to PI)
+            let phase = c.arg(); // Phase (-PI to PI)
 
             // Use the base hue for the concept, and modulate S & V with the trace data
             let hue = base_hue;
@@ -84,24 +84,26 @@ pub fn generate_trace_image(trace: &HolographicTrace, width: u32, height: u32) -
     img
 }
 
+/// Renders a trace with `generate_trace_image` and encodes it as PNG, e.g. to serve it over HTTP.
+pub fn render_trace_png(trace: &HolographicTrace, width: u32, height: u32) -> Result<Vec<u8>, image::ImageError> {
+    let mut bytes = Vec::new();
+    generate_trace_image(trace, width, height).write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    Ok(bytes)
+}
+
 /// Helper function to convert HSV to RGB.
 fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Rgb<u8> {
     let c = v * s;
     let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
     let m = v - c;
 
-    let (r_prime, g_prime, b_prime) = if h >= 0.0 && h < 60.0 {
-        (c, x, 0.0)
-    } else if h >= 60.0 && h < 120.0 {
-        (x, c, 0.0)
-    } else if h >= 120.0 && h < 180.0 {
-        (0.0, c, x)
-    } else if h >= 180.0 && h < 240.0 {
-        (0.0, x, c)
-    } else if h >= 240.0 && h < 300.0 {
-        (x, 0.0, c)
-    } else {
-        (c, 0.0, x)
+    let (r_prime, g_prime, b_prime) = match (h.rem_euclid(360.0) / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
     };
 
     Rgb([
@@ -115,11 +117,29 @@ fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Rgb<u8> {
 fn draw_filled_circle(img: &mut RgbImage, cx: i32, cy: i32, radius: i32, color: Rgb<u8>) {
     for x in (cx - radius)..=(cx + radius) {
         for y in (cy - radius)..=(cy + radius) {
-            if (x - cx).pow(2) + (y - cy).pow(2) <= radius.pow(2) {
-                if x >= 0 && x < img.width() as i32 && y >= 0 && y < img.height() as i32 {
-                    img.put_pixel(x as u32, y as u32, color);
-                }
+            let inside_circle = (x - cx).pow(2) + (y - cy).pow(2) <= radius.pow(2);
+            if inside_circle && x >= 0 && x < img.width() as i32 && y >= 0 && y < img.height() as i32 {
+                img.put_pixel(x as u32, y as u32, color);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traces_render_as_png() {
+        let png = render_trace_png(&HolographicTrace::new_empty(16), 64, 48).unwrap();
+        let image = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+        assert_eq!((image.width(), image.height()), (64, 48));
+    }
+
+    #[test]
+    fn test_hues_wrap_around_the_colour_wheel() {
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), Rgb([255, 0, 0]));
+        assert_eq!(hsv_to_rgb(360.0, 1.0, 1.0), Rgb([255, 0, 0]));
+        assert_eq!(hsv_to_rgb(-240.0, 1.0, 1.0), hsv_to_rgb(120.0, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,209 @@
+//! units.rs - Units of measure and conversions between them.
+//!
+//! A `Quantity` is a value with a unit ("3 km", "20 °C"), as read by
+//! `SensoryCortex::extract_quantities`. Quantities of the same dimension convert to each other
+//! through the base unit of the dimension (meter, kilogram, second, liter, kelvin, meter per
+//! second), so that "3 km" and "3000 m" are recognized as the same length: their
+//! `canonical_key` is the key they are indexed under (see `Entity::index_keys`).
+
+use crate::lemmatizer::Lang;
+use serde::Serialize;
+use thiserror::Error;
+
+/// What a unit measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Dimension {
+    Length,
+    Mass,
+    Time,
+    Volume,
+    Temperature,
+    Speed,
+}
+
+impl Dimension {
+    pub fn name(self) -> &'static str {
+        match self {
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Time => "time",
+            Dimension::Volume => "volume",
+            Dimension::Temperature => "temperature",
+            Dimension::Speed => "speed",
+        }
+    }
+
+    /// How the dimension is named inside a sentence, with its article.
+    pub fn label(self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Dimension::Length, Lang::French) => "une longueur",
+            (Dimension::Mass, Lang::French) => "une masse",
+            (Dimension::Time, Lang::French) => "une durée",
+            (Dimension::Volume, Lang::French) => "un volume",
+            (Dimension::Temperature, Lang::French) => "une température",
+            (Dimension::Speed, Lang::French) => "une vitesse",
+            (Dimension::Length, Lang::English) => "a length",
+            (Dimension::Mass, Lang::English) => "a mass",
+            (Dimension::Time, Lang::English) => "a duration",
+            (Dimension::Volume, Lang::English) => "a volume",
+            (Dimension::Temperature, Lang::English) => "a temperature",
+            (Dimension::Speed, Lang::English) => "a speed",
+        }
+    }
+}
+
+impl std::fmt::Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
+pub enum UnitError {
+    #[error("cannot convert {from} to {to}")]
+    Incompatible { from: Dimension, to: Dimension },
+}
+
+/// A unit of measure. A value `v` in this unit is `v * factor + offset` in the base unit of
+/// its dimension.
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    /// The symbol written in answers, in French and in English.
+    symbols: (&'static str, &'static str),
+    pub dimension: Dimension,
+    factor: f64,
+    offset: f64,
+    /// The lowercased spellings recognized in texts.
+    aliases: &'static [&'static str],
+}
+
+impl Unit {
+    pub fn symbol(&self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::French => self.symbols.0,
+            Lang::English => self.symbols.1,
+        }
+    }
+
+    /// The spellings of the unit, lowercased.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+}
+
+const fn unit(symbols: (&'static str, &'static str), dimension: Dimension, factor: f64, aliases: &'static [&'static str]) -> Unit {
+    Unit { symbols, dimension, factor, offset: 0.0, aliases }
+}
+
+const DAY: f64 = 86_400.0;
+
+/// Every unit the Core knows.
+pub static UNITS: &[Unit] = &[
+    unit(("mm", "mm"), Dimension::Length, 0.001, &["mm", "millimètre", "millimètres", "millimeter", "millimeters", "millimetre", "millimetres"]),
+    unit(("cm", "cm"), Dimension::Length, 0.01, &["cm", "centimètre", "centimètres", "centimeter", "centimeters", "centimetre", "centimetres"]),
+    unit(("m", "m"), Dimension::Length, 1.0, &["m", "mètre", "mètres", "meter", "meters", "metre", "metres"]),
+    unit(("km", "km"), Dimension::Length, 1000.0, &["km", "kilomètre", "kilomètres", "kilometer", "kilometers", "kilometre", "kilometres"]),
+    unit(("po", "in"), Dimension::Length, 0.0254, &["inch", "inches", "pouce", "pouces"]),
+    unit(("pi", "ft"), Dimension::Length, 0.3048, &["ft", "foot", "feet", "pied", "pieds"]),
+    unit(("yd", "yd"), Dimension::Length, 0.9144, &["yd", "yard", "yards"]),
+    unit(("mi", "mi"), Dimension::Length, 1609.344, &["mi", "mile", "miles"]),
+    unit(("mg", "mg"), Dimension::Mass, 1e-6, &["mg", "milligramme", "milligrammes", "milligram", "milligrams"]),
+    unit(("g", "g"), Dimension::Mass, 0.001, &["g", "gramme", "grammes", "gram", "grams"]),
+    unit(("kg", "kg"), Dimension::Mass, 1.0, &["kg", "kilo", "kilos", "kilogramme", "kilogrammes", "kilogram", "kilograms"]),
+    unit(("t", "t"), Dimension::Mass, 1000.0, &["t", "tonne", "tonnes"]),
+    unit(("lb", "lb"), Dimension::Mass, 0.453_592_37, &["lb", "lbs", "pound", "pounds"]),
+    unit(("oz", "oz"), Dimension::Mass, 0.028_349_523_125, &["oz", "ounce", "ounces", "onces"]),
+    unit(("ms", "ms"), Dimension::Time, 0.001, &["ms", "milliseconde", "millisecondes", "millisecond", "milliseconds"]),
+    unit(("s", "s"), Dimension::Time, 1.0, &["s", "sec", "seconde", "secondes", "second", "seconds"]),
+    unit(("min", "min"), Dimension::Time, 60.0, &["min", "minute", "minutes"]),
+    unit(("h", "h"), Dimension::Time, 3600.0, &["h", "heure", "heures", "hour", "hours"]),
+    unit(("jours", "days"), Dimension::Time, DAY, &["jour", "jours", "day", "days"]),
+    unit(("semaines", "weeks"), Dimension::Time, 7.0 * DAY, &["semaine", "semaines", "week", "weeks"]),
+    unit(("ans", "years"), Dimension::Time, 365.25 * DAY, &["an", "ans", "année", "années", "year", "years"]),
+    unit(("mL", "mL"), Dimension::Volume, 0.001, &["ml", "millilitre", "millilitres", "milliliter", "milliliters"]),
+    unit(("cL", "cL"), Dimension::Volume, 0.01, &["cl", "centilitre", "centilitres", "centiliter", "centiliters"]),
+    unit(("L", "L"), Dimension::Volume, 1.0, &["litre", "litres", "liter", "liters"]),
+    unit(("gal", "gal"), Dimension::Volume, 3.785_411_784, &["gal", "gallon", "gallons"]),
+    Unit {
+        symbols: ("°C", "°C"),
+        dimension: Dimension::Temperature,
+        factor: 1.0,
+        offset: 273.15,
+        aliases: &["°c", "degré celsius", "degrés celsius", "degree celsius", "degrees celsius", "celsius"],
+    },
+    Unit {
+        symbols: ("°F", "°F"),
+        dimension: Dimension::Temperature,
+        factor: 5.0 / 9.0,
+        offset: 273.15 - 32.0 * 5.0 / 9.0,
+        aliases: &["°f", "degré fahrenheit", "degrés fahrenheit", "degree fahrenheit", "degrees fahrenheit", "fahrenheit"],
+    },
+    unit(("K", "K"), Dimension::Temperature, 1.0, &["kelvin", "kelvins"]),
+    unit(("km/h", "km/h"), Dimension::Speed, 1.0 / 3.6, &["km/h", "kmh", "kilomètres par heure", "kilomètres-heure", "kilometers per hour", "kilometres per hour"]),
+    unit(("m/s", "m/s"), Dimension::Speed, 1.0, &["m/s", "mètres par seconde", "meters per second", "metres per second"]),
+    unit(("mph", "mph"), Dimension::Speed, 0.447_04, &["mph", "miles par heure", "miles per hour"]),
+];
+
+/// The unit spelled `word` (case-insensitive), if it is known.
+pub fn lookup(word: &str) -> Option<&'static Unit> {
+    let word = word.trim().to_lowercase();
+    UNITS.iter().find(|unit| unit.aliases.contains(&word.as_str()))
+}
+
+/// A value with its unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: &'static Unit,
+}
+
+impl Quantity {
+    /// The value in the base unit of the dimension.
+    pub fn base_value(&self) -> f64 {
+        self.value * self.unit.factor + self.unit.offset
+    }
+
+    /// The same quantity in another unit of the same dimension.
+    pub fn convert(&self, to: &'static Unit) -> Result<Quantity, UnitError> {
+        if self.unit.dimension != to.dimension {
+            return Err(UnitError::Incompatible { from: self.unit.dimension, to: to.dimension });
+        }
+        Ok(Quantity { value: (self.base_value() - to.offset) / to.factor, unit: to })
+    }
+
+    /// Whether both quantities measure the same thing, to rounding errors.
+    pub fn approx_eq(&self, other: &Quantity) -> bool {
+        self.unit.dimension == other.unit.dimension && self.canonical_key() == other.canonical_key()
+    }
+
+    /// The dimension and base value with five significant digits, identical for equal
+    /// quantities written in different units ("length:3.0000e3" for 3 km and 3000 m).
+    pub fn canonical_key(&self) -> String {
+        format!("{}:{:.4e}", self.unit.dimension.name(), self.base_value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sensory_cortex::SensoryCortex;
+
+    #[test]
+    fn test_quantities_convert_within_their_dimension() {
+        let quantities = SensoryCortex::extract_quantities("L'Everest culmine à 8 849 m, soit 8,849 km ; il y fait -40 °C et le vent souffle à 200 km/h.");
+        let read: Vec<(f64, &str)> = quantities.iter().map(|quantity| (quantity.value, quantity.unit.symbol(Lang::French))).collect();
+        assert_eq!(read, vec![(8849.0, "m"), (8.849, "km"), (-40.0, "°C"), (200.0, "km/h")]);
+        assert!(quantities[0].approx_eq(&quantities[1]));
+
+        let kilometers = Quantity { value: 3.0, unit: lookup("km").unwrap() };
+        let meters = kilometers.convert(lookup("mètres").unwrap()).unwrap();
+        assert!((meters.value - 3000.0).abs() < 1e-9);
+        assert!(kilometers.approx_eq(&meters));
+        assert_eq!(kilometers.canonical_key(), "length:3.0000e3");
+
+        let fahrenheit = quantities[2].convert(lookup("Fahrenheit").unwrap()).unwrap();
+        assert!((fahrenheit.value + 40.0).abs() < 1e-9, "-40 °C is -40 °F");
+        assert_eq!(kilometers.convert(lookup("kg").unwrap()), Err(UnitError::Incompatible { from: Dimension::Length, to: Dimension::Mass }));
+        assert!(lookup("livres").is_none(), "Books are not pounds");
+    }
+}
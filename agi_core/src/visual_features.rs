@@ -0,0 +1,208 @@
+//! visual_features.rs - The low-level features of the visual pathway.
+//!
+//! An image is reduced to what a retina and V1 would first see: the distribution of its colours
+//! and the orientations of its edges (and, with the `image_embeddings` feature, the vector of an
+//! external model such as CLIP). `HolographicEncoder::encode_image` turns them into a trace, so
+//! that an image can be stored next to the text memories and found again by a similar picture
+//! (see `SensoryCortex::process_image`).
+//!
+//! `Core::associate` binds a picture to a text in one cross-modal memory, whose trace is the
+//! combination of both: a text query finds the pictures of the memories it matches, a picture
+//! finds their texts, and every match tells which modality it came through (see
+//! `Core::cross_modal_recall`).
+
+use crate::holographic_memory::HolographicTrace;
+use image::{DynamicImage, GenericImageView};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Side of the thumbnail the features are computed on: enough for colours and edges, and an
+/// upload of any size costs the same.
+const ANALYSIS_SIZE: u32 = 128;
+/// Bins per colour channel; the histogram has `COLOR_BINS³` bins.
+pub const COLOR_BINS: usize = 4;
+/// Bins of the edge orientations, over half a turn (an edge has no direction).
+pub const ORIENTATION_BINS: usize = 8;
+/// Gradients weaker than this (on a 0-1 luma scale) are noise, not edges.
+const EDGE_THRESHOLD: f32 = 0.05;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageFeatures {
+    pub width: u32,
+    pub height: u32,
+    /// Share of the pixels in each RGB bin, summing to 1.
+    pub color_histogram: Vec<f32>,
+    /// Share of the edge strength in each orientation bin, summing to 1 (all zero without edges).
+    pub edge_orientations: Vec<f32>,
+    /// Vector of an external image model, when one is plugged in.
+    pub embedding: Option<Vec<f32>>,
+}
+
+impl ImageFeatures {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, image::ImageError> {
+        Ok(Self::extract(&image::load_from_memory(bytes)?))
+    }
+
+    pub fn extract(image: &DynamicImage) -> Self {
+        let (width, height) = image.dimensions();
+        let thumbnail;
+        let analysed = if width > ANALYSIS_SIZE || height > ANALYSIS_SIZE {
+            thumbnail = image.thumbnail(ANALYSIS_SIZE, ANALYSIS_SIZE);
+            &thumbnail
+        } else {
+            image
+        };
+        Self { width, height, color_histogram: color_histogram(analysed), edge_orientations: edge_orientations(analysed), embedding: None }
+    }
+
+    /// The feature groups, named, with the weight of each in the trace.
+    pub fn groups(&self) -> Vec<(&'static str, &[f32], f32)> {
+        let mut groups = vec![("image:color", self.color_histogram.as_slice(), 1.0), ("image:edges", self.edge_orientations.as_slice(), 1.0)];
+        if let Some(embedding) = &self.embedding {
+            // A learned embedding knows more about the content than any histogram.
+            groups.push(("image:embedding", embedding.as_slice(), 2.0));
+        }
+        groups
+    }
+}
+
+fn color_histogram(image: &DynamicImage) -> Vec<f32> {
+    let rgb = image.to_rgb8();
+    let mut histogram = vec![0.0; COLOR_BINS * COLOR_BINS * COLOR_BINS];
+    let bin = |value: u8| value as usize * COLOR_BINS / 256;
+    for pixel in rgb.pixels() {
+        let [r, g, b] = pixel.0;
+        histogram[(bin(r) * COLOR_BINS + bin(g)) * COLOR_BINS + bin(b)] += 1.0;
+    }
+    normalized(histogram)
+}
+
+/// The Sobel gradients of the luma, binned by orientation and weighted by strength.
+fn edge_orientations(image: &DynamicImage) -> Vec<f32> {
+    let luma = image.to_luma32f();
+    let (width, height) = luma.dimensions();
+    let mut histogram = vec![0.0; ORIENTATION_BINS];
+    let at = |x: u32, y: u32| luma.get_pixel(x, y).0[0];
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let gx = at(x + 1, y - 1) + 2.0 * at(x + 1, y) + at(x + 1, y + 1) - at(x - 1, y - 1) - 2.0 * at(x - 1, y) - at(x - 1, y + 1);
+            let gy = at(x - 1, y + 1) + 2.0 * at(x, y + 1) + at(x + 1, y + 1) - at(x - 1, y - 1) - 2.0 * at(x, y - 1) - at(x + 1, y - 1);
+            let strength = (gx * gx + gy * gy).sqrt();
+            if strength < EDGE_THRESHOLD {
+                continue;
+            }
+            let angle = gy.atan2(gx).rem_euclid(std::f32::consts::PI);
+            let bin = ((angle / std::f32::consts::PI * ORIENTATION_BINS as f32) as usize).min(ORIENTATION_BINS - 1);
+            histogram[bin] += strength;
+        }
+    }
+    normalized(histogram)
+}
+
+fn normalized(mut histogram: Vec<f32>) -> Vec<f32> {
+    let total: f32 = histogram.iter().sum();
+    if total > 0.0 {
+        histogram.iter_mut().for_each(|share| *share /= total);
+    }
+    histogram
+}
+
+/// An external model turning an image into a vector, such as a CLIP image encoder. Images with
+/// close vectors are close in meaning, which the histograms alone cannot tell.
+#[cfg(feature = "image_embeddings")]
+pub trait ImageEmbedder: Send + Sync {
+    /// Dimensionality of the vectors returned by `embed`.
+    fn dimension(&self) -> usize;
+
+    fn embed(&self, image: &DynamicImage) -> Option<Vec<f32>>;
+}
+
+/// A picture bound to a memory, see `Core::associate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundImage {
+    pub features: ImageFeatures,
+    /// The trace of the picture alone; it does not depend on the corpus, so it is kept as is
+    /// when the text traces are re-encoded.
+    pub trace: HolographicTrace,
+    /// The picture as it was given (PNG, JPEG...).
+    pub bytes: Arc<[u8]>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Modality {
+    Text,
+    Image,
+}
+
+/// What to look for in the memories.
+#[derive(Debug, Clone, Copy)]
+pub enum ModalQuery<'a> {
+    Text(&'a str),
+    /// An image file (PNG, JPEG...).
+    Image(&'a [u8]),
+}
+
+/// A memory found by `Core::cross_modal_recall`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrossModalMatch {
+    pub memory_id: u64,
+    pub text: String,
+    /// Cosine similarity of the query with the whole trace of the memory, which ranks the matches.
+    pub similarity: f32,
+    /// The part of the memory the query is closest to.
+    pub matched: Modality,
+    pub text_similarity: f32,
+    /// `None` for a memory without a picture.
+    pub image_similarity: Option<f32>,
+    /// The concepts of the memory: what its picture is likely to show.
+    pub concepts: Vec<String>,
+    /// The picture bound to the memory, if any.
+    #[serde(skip)]
+    pub image: Option<Arc<[u8]>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holographic_memory::HolographicEncoder;
+    use image::{Rgb, RgbImage};
+
+    fn stripes(horizontal: bool, color: Rgb<u8>, period: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(64, 64, |x, y| {
+            let position = if horizontal { y } else { x };
+            if (position / period).is_multiple_of(2) { color } else { Rgb([255, 255, 255]) }
+        }))
+    }
+
+    #[test]
+    fn test_similar_pictures_get_similar_traces() {
+        let red_rows = ImageFeatures::extract(&stripes(true, Rgb([220, 20, 20]), 4));
+        let red_columns = ImageFeatures::extract(&stripes(false, Rgb([220, 20, 20]), 4));
+        assert_eq!(red_rows.color_histogram, red_columns.color_histogram);
+        assert!((red_rows.color_histogram.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        // Rows have vertical gradients, columns horizontal ones.
+        let dominant = |features: &ImageFeatures| (0..ORIENTATION_BINS).max_by(|&a, &b| features.edge_orientations[a].total_cmp(&features.edge_orientations[b]));
+        assert_ne!(dominant(&red_rows), dominant(&red_columns));
+
+        let encoder = HolographicEncoder::new(256);
+        let query = encoder.encode_image(&ImageFeatures::extract(&stripes(true, Rgb([200, 30, 30]), 6)));
+        let same_kind = encoder.encode_image(&red_rows);
+        let other_kind = encoder.encode_image(&ImageFeatures::extract(&stripes(false, Rgb([20, 20, 220]), 4)));
+        assert!(query.cosine_similarity(&same_kind) > query.cosine_similarity(&other_kind));
+        assert!(query.distance(&encoder.encode("chien")) > query.distance(&same_kind), "Images are encoded apart from words");
+    }
+
+    #[test]
+    fn test_a_bound_picture_is_part_of_the_trace_of_its_memory() {
+        let encoder = HolographicEncoder::new(256);
+        let features = ImageFeatures::extract(&stripes(true, Rgb([220, 20, 20]), 4));
+        let image = BoundImage { trace: encoder.encode_image(&features), features, bytes: Arc::from(&b"png"[..]) };
+        let text = "Un drapeau rouge et blanc.";
+
+        assert_eq!(encoder.encode_memory(text, None), encoder.encode(text));
+        let bound = encoder.encode_memory(text, Some(&image));
+        assert!(bound.cosine_similarity(&image.trace) > encoder.encode(text).cosine_similarity(&image.trace));
+        assert!(bound.cosine_similarity(&encoder.encode(text)) > bound.cosine_similarity(&encoder.encode("Un store bleu.")), "The text is still there");
+    }
+}
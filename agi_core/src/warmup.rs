@@ -0,0 +1,107 @@
+//! warmup.rs - Boot warmup and the response cache it primes.
+//!
+//! The first prompts after startup used to pay for cold structures: stale traces, Thalamus
+//! prototypes built before the knowledge base was loaded, memory traces never touched.
+//! `Core::warm_up` does that work up front and primes a small response cache with the
+//! configured warmup prompts; `CoreSnapshot::ready` tells front-ends when it is done.
+
+use crate::expertise::ExpertiseLevel;
+use crate::lemmatizer::Lang;
+use crate::pipeline::{self, PipelineStage};
+use crate::thalamus::QueryType;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// Cache key: the normalized prompt and the expertise level the answer was adapted to.
+pub type CacheKey = (String, ExpertiseLevel);
+
+/// A bounded cache of complete answers, evicting the oldest entries first.
+/// It must be cleared whenever the knowledge base changes.
+#[derive(Debug)]
+pub struct ResponseCache {
+    capacity: usize,
+    entries: HashMap<CacheKey, (String, QueryType)>,
+    order: VecDeque<CacheKey>,
+}
+
+impl ResponseCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, entries: HashMap::new(), order: VecDeque::new() }
+    }
+
+    /// Prompts differing only by case or spacing share an entry.
+    pub fn key(prompt: &str, expertise: ExpertiseLevel) -> CacheKey {
+        (prompt.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase(), expertise)
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<(String, QueryType)> {
+        self.entries.get(key).cloned()
+    }
+
+    /// Stores an answer. Partial answers (produced after a stage timeout) are not cached.
+    pub fn insert(&mut self, key: CacheKey, answer: (String, QueryType)) {
+        if self.capacity == 0 || Self::is_partial(&answer.0) {
+            return;
+        }
+        if self.entries.insert(key.clone(), answer).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn is_partial(answer: &str) -> bool {
+        [PipelineStage::Retrieval, PipelineStage::Synthesis]
+            .iter()
+            .flat_map(|stage| [Lang::French, Lang::English].map(|lang| pipeline::partial_answer_note(*stage, lang)))
+            .any(|note| answer.ends_with(note))
+    }
+}
+
+/// What the warmup phase did.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WarmupReport {
+    pub traces_reencoded: bool,
+    pub traces_touched: usize,
+    pub prompts_primed: usize,
+    pub duration: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_normalizes_and_evicts_oldest() {
+        let mut cache = ResponseCache::new(2);
+        cache.insert(ResponseCache::key("Qui  est Socrate ?", ExpertiseLevel::Intermediate), ("Un philosophe.".into(), QueryType::Factual));
+        assert!(cache.get(&ResponseCache::key("qui est socrate ?", ExpertiseLevel::Intermediate)).is_some());
+        assert!(cache.get(&ResponseCache::key("qui est socrate ?", ExpertiseLevel::Novice)).is_none());
+
+        let partial = format!("Un philosophe.\n\n{}", pipeline::partial_answer_note(PipelineStage::Retrieval, Lang::French));
+        cache.insert(ResponseCache::key("a", ExpertiseLevel::Expert), (partial, QueryType::Factual));
+        assert_eq!(cache.len(), 1, "Partial answers are never cached");
+
+        cache.insert(ResponseCache::key("b", ExpertiseLevel::Expert), ("b".into(), QueryType::Factual));
+        cache.insert(ResponseCache::key("c", ExpertiseLevel::Expert), ("c".into(), QueryType::Factual));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&ResponseCache::key("qui est socrate ?", ExpertiseLevel::Intermediate)).is_none());
+    }
+}
@@ -0,0 +1,202 @@
+//! working_memory.rs - A short-term buffer of what was said in the conversation.
+//!
+//! Everything the Core learns goes into the hippocampus for good, but what the user states in
+//! passing ("my cat is called Felix") should not. The working memory holds the last few facts
+//! of the conversation, each with an activation that decays at every turn; faded facts are
+//! forgotten. It is consulted before the hippocampus for follow-up questions, and its content
+//! can be consolidated into long-term memory on request.
+
+use crate::lemmatizer::{self, Lang};
+use crate::stop_words::StopWordRegistry;
+use crate::tokenizer::{self, TokenKind};
+use std::collections::{HashSet, VecDeque};
+
+/// Words that open a question.
+const QUESTION_WORDS: &[&str] = &[
+    "qui", "que", "quoi", "quel", "quelle", "quels", "quelles", "comment", "pourquoi", "où", "quand", "combien", "est-ce",
+    "what", "who", "whom", "which", "how", "why", "where", "when", "is", "are", "do", "does", "did", "can", "could",
+];
+
+/// Request verbs: an imperative prompt is a request, not a fact.
+const REQUEST_VERBS: &[&str] = &[
+    "explique", "expliquez", "décris", "décrivez", "parle", "parlez", "dis", "dites", "définis", "donne", "donnez",
+    "montre", "raconte", "compare", "explain", "describe", "tell", "define", "give", "show", "talk",
+];
+
+/// "What did I just tell you?"
+const RECALL_QUESTIONS: &[&str] = &[
+    "qu'est-ce que je viens de te dire", "qu'est-ce que je viens de vous dire", "que viens-je de te dire",
+    "que t'ai-je dit", "que vous ai-je dit", "qu'est-ce que je t'ai dit", "qu'est-ce que je vous ai dit",
+    "rappelle-moi ce que je t'ai dit", "de quoi je parlais",
+    "what did i just tell you", "what did i just say", "what did i tell you", "what did i say", "remind me what i said",
+];
+
+/// A fact held in working memory.
+#[derive(Debug, Clone)]
+pub struct WorkingItem {
+    pub text: String,
+    /// 1.0 when stated, multiplied by the decay factor at every turn.
+    pub activation: f32,
+    /// Lemmatized content words, used to match follow-up questions.
+    keys: HashSet<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkingMemory {
+    items: VecDeque<WorkingItem>,
+    capacity: usize,
+    decay: f32,
+}
+
+impl WorkingMemory {
+    /// Facts held at most (the "magical number seven").
+    pub const DEFAULT_CAPACITY: usize = 7;
+    /// Activation kept from one turn to the next.
+    pub const DEFAULT_DECAY: f32 = 0.7;
+    /// Facts below this activation are forgotten (after about five turns with the defaults).
+    const FORGET_BELOW: f32 = 0.15;
+    /// Minimal share of a question's key words a fact must contain to answer it.
+    const MIN_OVERLAP: f32 = 0.5;
+
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY, Self::DEFAULT_DECAY)
+    }
+
+    pub fn with_capacity(capacity: usize, decay: f32) -> Self {
+        Self { items: VecDeque::new(), capacity: capacity.max(1), decay: decay.clamp(0.0, 1.0) }
+    }
+
+    /// Starts a new turn: every fact fades, and the faded ones are forgotten.
+    pub fn decay(&mut self) {
+        for item in &mut self.items {
+            item.activation *= self.decay;
+        }
+        self.items.retain(|item| item.activation >= Self::FORGET_BELOW);
+    }
+
+    /// Holds a fact at full activation. A fact stated again is refreshed rather than duplicated.
+    pub fn hold(&mut self, text: &str, stop_words: &StopWordRegistry) {
+        let text = text.trim();
+        self.items.retain(|item| !item.text.eq_ignore_ascii_case(text));
+        self.items.push_back(WorkingItem { text: text.to_string(), activation: 1.0, keys: key_words(text, stop_words) });
+        while self.items.len() > self.capacity {
+            self.items.pop_front();
+        }
+    }
+
+    /// The fact that best answers `question`, weighted by how recently it was stated.
+    pub fn find(&self, question: &str, stop_words: &StopWordRegistry) -> Option<&WorkingItem> {
+        let keys = key_words(question, stop_words);
+        if keys.is_empty() {
+            return None;
+        }
+        self.items
+            .iter()
+            .filter_map(|item| {
+                let overlap = keys.intersection(&item.keys).count() as f32 / keys.len() as f32;
+                (overlap >= Self::MIN_OVERLAP).then_some((item, overlap * item.activation))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(item, _)| item)
+    }
+
+    /// Whether a key word (lemmatized or not) of a held fact is `word`.
+    pub fn mentions(&self, word: &str) -> bool {
+        self.items.iter().any(|item| item.keys.contains(word) || item.text.to_lowercase().contains(word))
+    }
+
+    /// Held facts, most recent first.
+    pub fn recent(&self) -> impl Iterator<Item = &WorkingItem> {
+        self.items.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Empties the buffer, returning its facts (oldest first) to be stored for good.
+    pub fn drain(&mut self) -> Vec<String> {
+        self.items.drain(..).map(|item| item.text).collect()
+    }
+}
+
+impl Default for WorkingMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether a prompt asks something rather than states a fact.
+pub fn is_question(prompt: &str) -> bool {
+    let prompt = prompt.trim();
+    prompt.ends_with('?') || tokenizer::content_words(prompt).first().is_some_and(|word| QUESTION_WORDS.contains(&word.as_str()))
+}
+
+/// Whether a prompt states a fact worth holding: neither a question nor a request.
+pub fn is_statement(prompt: &str) -> bool {
+    let words = tokenizer::content_words(prompt);
+    words.len() >= 3 && !is_question(prompt) && !words.first().is_some_and(|word| REQUEST_VERBS.contains(&word.as_str()))
+}
+
+/// Whether a prompt asks what was just said ("what did I just tell you?").
+pub fn is_recall_question(prompt: &str) -> bool {
+    // Apostrophes are often typed as spaces ("qu est-ce que").
+    let lower = prompt.to_lowercase().replace(['’', '\''], " ");
+    RECALL_QUESTIONS.iter().any(|question| lower.contains(&question.replace('\'', " ")))
+}
+
+/// "You just told me: ..."
+pub fn recall_message(facts: &[&str], lang: Lang) -> String {
+    match (facts, lang) {
+        ([], Lang::French) => "Vous ne m'avez rien dit de particulier ces derniers temps.".to_string(),
+        ([], Lang::English) => "You haven't told me anything in particular lately.".to_string(),
+        (_, Lang::French) => format!("Vous m'avez dit :\n{}", bullet_list(facts)),
+        (_, Lang::English) => format!("You told me:\n{}", bullet_list(facts)),
+    }
+}
+
+fn bullet_list(facts: &[&str]) -> String {
+    facts.iter().map(|fact| format!("- {}", fact)).collect::<Vec<_>>().join("\n")
+}
+
+fn key_words(text: &str, stop_words: &StopWordRegistry) -> HashSet<String> {
+    let lang = lemmatizer::detect_language(text);
+    tokenizer::tokenize(text)
+        .into_iter()
+        .filter(|token| token.kind != TokenKind::Elision)
+        .map(|token| (token.normalized(), token.kind))
+        .filter(|(word, _)| word.chars().count() > 2 && !stop_words.is_stop_word(word) && !QUESTION_WORDS.contains(&word.as_str()))
+        .map(|(word, kind)| if kind == TokenKind::Word { lemmatizer::lemmatize_in(&word, lang) } else { word })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hold_find_and_forget() {
+        let stop_words = StopWordRegistry::new();
+        let mut memory = WorkingMemory::new();
+        assert!(is_statement("Mon chat s'appelle Félix."));
+        assert!(!is_statement("Comment s'appelle mon chat ?"));
+        assert!(is_recall_question("Qu'est-ce que je viens de te dire ?"));
+        assert!(is_recall_question("qu est-ce que je viens de te dire"));
+
+        memory.hold("Mon chat s'appelle Félix.", &stop_words);
+        memory.decay();
+        memory.hold("Ma sœur habite à Lyon.", &stop_words);
+        assert_eq!(memory.find("Comment s'appelle mon chat ?", &stop_words).map(|item| item.text.as_str()), Some("Mon chat s'appelle Félix."));
+        assert_eq!(memory.find("Où habite ma sœur ?", &stop_words).map(|item| item.text.as_str()), Some("Ma sœur habite à Lyon."));
+        assert!(memory.find("Quelle est la capitale de l'Italie ?", &stop_words).is_none());
+
+        for _ in 0..10 {
+            memory.decay();
+        }
+        assert!(memory.is_empty());
+    }
+}
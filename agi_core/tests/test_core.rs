@@ -0,0 +1,534 @@
+use agi_core::Core;
+
+#[test]
+fn test_a_full_command_queue_holds_synchronous_callers_back() {
+    use agi_core::CoreHandle;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let core = CoreHandle::spawn_with_capacity(Core::new_for_tests(), None, 1).unwrap();
+    let (release, released) = mpsc::channel::<()>();
+    let first = core
+        .submit(move |core| {
+            released.recv().ok();
+            core.tick
+        })
+        .unwrap();
+    let second = std::thread::spawn({
+        let core = core.clone();
+        move || core.exec_blocking(|core| core.tick + 1)
+    });
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!second.is_finished(), "The second command waits for the slot of the first");
+    assert_eq!(core.queued_commands(), (1, 1));
+
+    release.send(()).unwrap();
+    assert_eq!(first.blocking_recv().unwrap() + 1, second.join().unwrap().unwrap(), "No command is dropped");
+    core.shutdown();
+}
+
+#[test]
+fn test_a_full_command_queue_turns_non_blocking_callers_away() {
+    use agi_core::core_actor::CoreHandleError;
+    use agi_core::{AsyncCore, CoreHandle};
+    use std::sync::mpsc;
+
+    let core = CoreHandle::spawn_with_capacity(Core::new_for_tests(), None, 1).unwrap();
+    let (release, released) = mpsc::channel::<()>();
+    let first = core.try_submit(move |core| released.recv().map(|_| core.tick)).unwrap();
+    assert!(matches!(core.try_submit(|core| core.tick), Err(CoreHandleError::Busy)));
+    assert!(matches!(core.try_submit_prompt("Bonjour"), Err(CoreHandleError::Busy)));
+
+    release.send(()).unwrap();
+    let tick = first.blocking_recv().unwrap().unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let async_core = AsyncCore::new(core.clone());
+    let pending = runtime.block_on(async_core.submit(|core| core.tick + 1)).unwrap();
+    assert_eq!(runtime.block_on(pending).unwrap(), tick + 1, "A slot is free again once the first command is handled");
+    core.shutdown();
+}
+
+#[test]
+fn test_the_wakeup_sequence_is_published_in_the_actor_snapshots() {
+    use agi_core::CoreHandle;
+
+    let mut core = Core::new_for_tests();
+    core.set_wakeup_stages(2);
+    let core = CoreHandle::spawn(core, None).unwrap();
+    assert_eq!((core.latest_snapshot().wakeup_stage, core.latest_snapshot().wakeup_stages), (0, 2));
+
+    for stage in 1..=2 {
+        assert!(core.try_submit(|core| core.advance_wakeup_stage()).unwrap().blocking_recv().unwrap());
+        // The snapshot is published before the actor takes the next command.
+        core.exec_blocking(|_| ()).unwrap();
+        assert_eq!(core.latest_snapshot().wakeup_stage, stage, "No tick is needed to see the new stage");
+    }
+    assert!(!core.exec_blocking(|core| core.advance_wakeup_stage()).unwrap());
+    assert_eq!(core.latest_snapshot().awakening_level, 1.0);
+    core.shutdown();
+}
+
+#[test]
+fn test_core_for_tests_boots_without_files() {
+    let mut core = Core::new_for_tests();
+    assert_eq!(core.connectome.neurons.len(), Core::TEST_NEURONS);
+    assert!(core.hippocampus.holographic_memory.iter().all(|memory| !memory.text.contains("Newton")), "No knowledge file is read");
+    let before = core.snapshot_state();
+
+    let id = core.inject_memory("Le ciel est bleu par beau temps.", false);
+    assert_eq!(core.hippocampus.memory(id).map(|memory| memory.text.as_str()), Some("Le ciel est bleu par beau temps."));
+    core.learn_relationship("chien", "animal").unwrap();
+    for _ in 0..20 {
+        core.tick();
+    }
+
+    let diff = Core::diff_snapshots(&before, &core.snapshot_state());
+    assert_eq!(diff.ticks, 20);
+    assert_eq!(diff.memories_added, vec!["Le ciel est bleu par beau temps."]);
+    assert_eq!(diff.relationships_added.len(), 1);
+
+    // Two test cores are identical: the connectome is seeded.
+    let other = Core::new_for_tests();
+    assert_eq!(other.connectome.synapses.weights(), Core::new_for_tests().connectome.synapses.weights());
+}
+
+#[test]
+fn test_associations_are_recalled_from_either_modality() {
+    use agi_core::lemmatizer::lemmatize_auto;
+    use agi_core::visual_features::{ModalQuery, Modality};
+    use image::{ImageFormat, Rgb, RgbImage};
+
+    let png = |color: Rgb<u8>, horizontal: bool| {
+        let image = RgbImage::from_fn(48, 48, |x, y| {
+            let position = if horizontal { y } else { x };
+            if (position / 4) % 2 == 0 { color } else { Rgb([255, 255, 255]) }
+        });
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut bytes, ImageFormat::Png).unwrap();
+        bytes.into_inner()
+    };
+    let mut core = Core::new_for_tests();
+    core.inject_memory("Le ciel est bleu par beau temps.", false);
+    let flag_image = png(Rgb([220, 20, 20]), true);
+    let flag = core.associate("Un drapeau rouge et blanc.", &flag_image).unwrap();
+    core.associate("Un store bleu.", &png(Rgb([20, 20, 220]), false)).unwrap();
+
+    // A similar picture finds the text...
+    let matches = core.cross_modal_recall(ModalQuery::Image(&png(Rgb([230, 30, 30]), true)), 2).unwrap();
+    assert_eq!(matches[0].memory_id, flag);
+    assert_eq!(matches[0].matched, Modality::Image);
+    assert!(matches[0].concepts.contains(&lemmatize_auto("drapeau")), "{:?}", matches[0].concepts);
+
+    // ...and the text finds the picture.
+    let matches = core.cross_modal_recall(ModalQuery::Text("drapeau rouge"), 1).unwrap();
+    assert_eq!(matches[0].memory_id, flag);
+    assert_eq!(matches[0].matched, Modality::Text);
+    assert_eq!(matches[0].image.as_deref(), Some(flag_image.as_slice()));
+    assert!(core.cross_modal_recall(ModalQuery::Image(b"not an image"), 1).is_err());
+}
+
+#[test]
+fn test_relationships_are_recalled_from_the_relational_memory() {
+    use agi_core::role_binding::{Role, IS_A};
+
+    let mut core = Core::new_for_tests();
+    core.learn_relationship("chien", "animal").unwrap();
+    core.learn_triple("chien", "mange", "croquettes");
+    core.learn_triple("chat", "mange", "souris");
+
+    assert_eq!(core.recall_triple(Role::Object, [(Role::Subject, "chien"), (Role::Relation, IS_A)]).unwrap().filler, "animal");
+    assert_eq!(core.recall_triple(Role::Subject, [(Role::Relation, "mange"), (Role::Object, "souris")]).unwrap().filler, "chat");
+    assert!(core.recall_triple(Role::Object, [(Role::Subject, "souris"), (Role::Relation, "mange")]).is_none());
+}
+
+#[test]
+fn test_cold_traces_are_compressed_and_still_found() {
+    use agi_core::trace_compression::CompressionConfig;
+
+    let mut core = Core::new_for_tests();
+    let dog = core.inject_memory("Le chien aboie dans le jardin.", false);
+    core.inject_memory("La lune tourne autour de la Terre.", false);
+    core.inject_memory("Le soleil est une étoile.", false);
+    let before = core.memory_usage();
+
+    let report = core.compress_traces(&CompressionConfig { cold_after_secs: 0, ..CompressionConfig::default() });
+    assert_eq!(report.memories_compressed, 3);
+    assert!(report.bytes_after < report.bytes_before);
+    let after = core.memory_usage();
+    assert_eq!(after.compressed_memories, 3);
+    assert!(after.total_bytes < before.total_bytes);
+    assert!(after.subsystems.iter().any(|usage| usage.name == "hippocampus" && usage.items == 3));
+
+    let query = core.holographic_encoder.read().unwrap().encode("Pourquoi le chien aboie-t-il ?");
+    assert_eq!(core.hippocampus.find_similar_memories(&query, "", 1, false)[0].0.id, dog);
+
+    // A memory used again gets its full trace back.
+    let expanded = core.hippocampus.expand(&[dog], &core.holographic_encoder.read().unwrap());
+    assert_eq!(expanded, 1);
+    assert_eq!(core.memory_usage().compressed_memories, 2);
+}
+
+#[test]
+fn test_batch_assimilation_learns_like_the_serial_path() {
+    use agi_core::bulk_assimilation::{AssimilationProgress, AssimilationStage};
+    use agi_core::source_trust::SourceTag;
+
+    let lines: Vec<String> = (0..300).map(|i| format!("Le chien numéro {} aboie dans le jardin {}.", i, i % 7)).collect();
+    let mut serial = Core::new_for_tests();
+    for line in &lines {
+        serial.learn_and_assimilate(line, false);
+    }
+    let mut batched = Core::new_for_tests();
+    let mut reports: Vec<AssimilationProgress> = Vec::new();
+    let memory_ids = batched.assimilate_batch(&lines, false, SourceTag::Foundational, |progress| reports.push(progress));
+
+    assert_eq!(memory_ids.len(), lines.len());
+    let texts = |core: &Core| core.hippocampus.holographic_memory.iter().map(|memory| memory.text.clone()).collect::<Vec<_>>();
+    assert_eq!(texts(&batched), texts(&serial));
+    assert_eq!(batched.hippocampus.memory(memory_ids[5]).unwrap().text, lines[5]);
+    let names = |core: &Core| {
+        let mut names = core.conceptual_hierarchy.get_all_concept_names();
+        names.sort();
+        names
+    };
+    assert_eq!(names(&batched), names(&serial));
+    let concepts = |core: &Core| core.hippocampus.holographic_memory.iter().map(|memory| memory.concepts.len()).collect::<Vec<_>>();
+    assert_eq!(concepts(&batched), concepts(&serial));
+
+    // The lines are encoded by chunks, then committed one by one.
+    let last = |stage| reports.iter().rfind(|progress| progress.stage == stage).copied();
+    assert_eq!(last(AssimilationStage::Encoding).map(|progress| progress.done), Some(lines.len()));
+    assert_eq!(last(AssimilationStage::Committing).map(|progress| progress.done), Some(lines.len()));
+    assert!(reports.iter().filter(|progress| progress.stage == AssimilationStage::Encoding).count() > 1);
+}
+
+#[test]
+fn test_awakening_is_explicit_and_can_be_cancelled() {
+    use agi_core::error::AgiError;
+    use std::ops::ControlFlow;
+
+    let mut core = Core::new_for_tests();
+    assert!(core.hippocampus.holographic_memory.is_empty(), "A new core knows nothing before it awakens");
+    let mut reports = Vec::new();
+    let report = core
+        .awaken(|progress| {
+            reports.push(progress.clone());
+            ControlFlow::Break(())
+        })
+        .unwrap();
+    // Cancelled at the start of the first file, before any line.
+    assert_eq!(report.lines, 0);
+    assert_eq!(report.cancelled, reports.first().is_some_and(|progress| progress.lines_total > 0));
+    assert!(core.hippocampus.holographic_memory.is_empty());
+    assert!(matches!(core.awaken(|_| ControlFlow::Continue(())), Err(AgiError::AlreadyAwake)), "A core awakens once");
+}
+
+#[test]
+fn test_retrieval_profiles_are_chosen_by_query_type() {
+    use agi_core::config::{RetrievalParams, RetrievalProfile};
+    use agi_core::reasoning_engine::ReasoningEngine;
+    use agi_core::thalamus::QueryType;
+
+    let mut core = Core::new_for_tests();
+    let fact = core.inject_memory("Le chien aboie dans le jardin.", false);
+    let axiom = core.inject_memory("Le chien est un animal fidèle.", true);
+    let base = RetrievalParams::default();
+    assert!(core.config.retrieval_profiles.resolve(QueryType::Introspective, base).axioms_only);
+    assert_eq!(core.config.retrieval_profiles.resolve(QueryType::Factual, base).top_k, base.top_k);
+
+    let wide = RetrievalProfile { distance_threshold: 1.5, top_k: 5, axioms_only: false };
+    assert_eq!(core.set_retrieval_profile(QueryType::Creative, Some(wide)), None);
+    assert_eq!(core.config.retrieval_profiles.resolve(QueryType::Creative, base), wide);
+    assert_eq!(core.set_retrieval_profile(QueryType::Creative, None), Some(wide));
+
+    let default = core.config.retrieval_profiles.resolve(QueryType::Factual, base);
+    let engine = ReasoningEngine::new();
+    let retrieved = |profile: RetrievalProfile| -> Vec<u64> {
+        engine.retrieve("chien", &core.hippocampus, &core.conceptual_hierarchy, &core.holographic_encoder, &profile).iter().map(|(memory, _)| memory.id).collect()
+    };
+    assert!(retrieved(default).contains(&fact));
+    assert_eq!(retrieved(RetrievalProfile { axioms_only: true, ..default }), vec![axiom]);
+}
+
+#[test]
+fn test_queries_are_routed_to_their_domain_partitions() {
+    use agi_core::config::RetrievalProfile;
+    use agi_core::knowledge_watcher::KnowledgeDelta;
+    use agi_core::reasoning_engine::ReasoningEngine;
+    use agi_core::thalamus::QueryType;
+    use std::collections::HashMap;
+
+    let mut core = Core::new_for_tests();
+    let vineyards = core.inject_memory("Les vignes de Provence donnent du vin.", false);
+    let (star, plants) = ("Le soleil est une étoile de la Voie lactée.".to_string(), "La photosynthèse nourrit les plantes.".to_string());
+    core.apply_knowledge_delta(&KnowledgeDelta {
+        path: "knowledge.txt".into(),
+        is_axiom: false,
+        added: vec![star.clone(), plants.clone()],
+        removed: Vec::new(),
+        domains: HashMap::from([(star.clone(), vec!["Astronomie".to_string()]), (plants, vec!["Biologie".to_string()])]),
+    });
+    assert_eq!(core.hippocampus.holographic_memory.iter().find(|memory| memory.text == star).map(|memory| memory.domains.len()), Some(1));
+
+    let engine = ReasoningEngine::new();
+    let profile = core.config.retrieval_profiles.resolve(QueryType::Factual, core.config.retrieval);
+    let (memories, route) = engine.retrieve_routed("Le soleil est-il une étoile ?", &core.hippocampus, &core.conceptual_hierarchy, &core.holographic_encoder, &profile);
+    assert_eq!(route.domains.iter().map(|domain| domain.name.as_str()).collect::<Vec<_>>(), ["astronomie"]);
+    assert!(!route.fell_back);
+    assert_eq!(memories.first().map(|(memory, _)| memory.text.as_str()), Some(star.as_str()));
+    assert!(memories.iter().all(|(memory, _)| memory.id != vineyards), "Only the partition of the domain is searched");
+
+    // Nothing filed under a domain is close enough: the whole hippocampus is searched.
+    let narrow = RetrievalProfile { distance_threshold: 0.3, ..profile };
+    let (memories, route) = engine.retrieve_routed("Les vignes de Provence donnent du vin.", &core.hippocampus, &core.conceptual_hierarchy, &core.holographic_encoder, &narrow);
+    assert!(route.domains.is_empty() || route.fell_back);
+    assert_eq!(memories.first().map(|(memory, _)| memory.id), Some(vineyards));
+}
+
+#[test]
+fn test_creative_leaps_are_mapped_on_the_entanglement_graph() {
+    let mut core = Core::new_for_tests();
+    assert!(core.get_entanglement_graph().edges.is_empty());
+    core.creativity_forge.process(core.quantum_core.as_mut());
+    let graph = core.get_entanglement_graph();
+    assert_eq!(graph.qubits, core.quantum_core.len());
+    assert!(!graph.edges.is_empty());
+    assert!(graph.edges.iter().all(|edge| edge.a < edge.b && edge.b < graph.qubits && edge.leaps >= 1));
+
+    let strongest = graph.edges[0].strength;
+    core.creativity_forge.decay_entanglements();
+    assert!(core.get_entanglement_graph().edges[0].strength < strongest, "Edges fade between leaps");
+}
+
+#[test]
+fn test_creative_requests_are_answered_with_a_creation() {
+    use agi_core::provenance::AnswerPath;
+    use agi_core::thalamus::QueryType;
+
+    let mut core = Core::new_for_tests();
+    core.inject_memory("La mer reflète la lune.", false);
+    core.inject_memory("La lune éclaire le désert.", false);
+
+    let (poem, query_type) = core.get_response_for_prompt("Écris un poème sur la mer.").expect("an answer");
+    assert_eq!(query_type, QueryType::Creative);
+    assert_eq!(core.explain_last_response().map(|explanation| explanation.path), Some(AnswerPath::Creative));
+    assert!(poem.starts_with("Voici un poème que j'ai imaginé, à partir de « mer »"), "{}", poem);
+    assert!(poem.lines().count() > 3, "A few stanzas rather than a recalled fact: {}", poem);
+
+    core.get_response_for_prompt("Écris un poème sur la mer.");
+    assert_eq!(core.explain_last_response().map(|explanation| explanation.path), Some(AnswerPath::Creative), "A creation is not cached");
+}
+
+#[test]
+fn test_factual_answers_from_several_memories_are_synthesized_by_silicium() {
+    use agi_core::provenance::Composition;
+
+    let mut core = Core::new_for_tests();
+    core.inject_memory("Le chien aboie dans le jardin.", false);
+    core.inject_memory("Le chien est un animal fidèle.", false);
+
+    core.config.silicium_synthesis = false;
+    core.get_response_for_prompt("Décris le chien.").expect("an answer");
+    assert_eq!(core.explain_last_response().and_then(|explanation| explanation.composition), None, "Silicium is not asked when it is turned off");
+
+    core.config.silicium_synthesis = true;
+    let (answer, _) = core.get_response_for_prompt("Explique le chien.").expect("an answer");
+    assert_eq!(core.explain_last_response().and_then(|explanation| explanation.composition), Some(Composition::Synthesized));
+    assert!(answer.contains("« chien »") && answer.contains("aboie") && answer.contains("fidèle"), "{}", answer);
+}
+
+#[test]
+fn test_the_response_cache_is_bypassed_while_the_conversation_gives_context() {
+    use agi_core::provenance::AnswerPath;
+
+    let mut core = Core::new_for_tests();
+    core.learn("Paris est la capitale de la France.", false);
+    core.learn("Le ciel est bleu.", false);
+    let question = "Quelle est la capitale de la France ?";
+    let path = |core: &Core| core.explain_last_response().map(|explanation| explanation.path);
+
+    core.get_response_for_prompt(question);
+    core.motor_cortex.forget_recent_responses();
+    core.get_response_for_prompt(question);
+    assert_eq!(path(&core), Some(AnswerPath::Cache));
+
+    // A stated fact is held in the working memory: the same question may now mean something else.
+    core.get_response_for_prompt("Mon frère habite à Lyon.");
+    core.motor_cortex.forget_recent_responses();
+    core.get_response_for_prompt(question);
+    assert_ne!(path(&core), Some(AnswerPath::Cache));
+}
+
+#[test]
+fn test_only_explicit_comparisons_take_the_comparative_path() {
+    use agi_core::thalamus::QueryType;
+
+    let core = Core::new_for_tests();
+    assert_eq!(core.thalamus.analyze_prompt("Quelle est la capitale de la France ?"), QueryType::Factual);
+    assert_eq!(core.thalamus.analyze_prompt("Quelle est la différence entre Platon et Aristote ?"), QueryType::Comparative);
+    assert_ne!(core.thalamus.analyze_prompt("avantages inconvénients"), QueryType::Comparative, "No cue names two things to compare");
+}
+
+#[test]
+fn test_injected_memories_are_found_at_the_default_threshold() {
+    use agi_core::reasoning_engine::ReasoningEngine;
+    use agi_core::thalamus::QueryType;
+
+    let mut core = Core::new_for_tests();
+    let sky = core.inject_memory("Le ciel est bleu.", false);
+    let paris = core.inject_memory("Paris est la capitale de la France.", false);
+    let profile = core.config.retrieval_profiles.resolve(QueryType::Factual, core.config.retrieval);
+    let first_found = |query: &str| {
+        let retrieved = ReasoningEngine::new().retrieve(query, &core.hippocampus, &core.conceptual_hierarchy, &core.holographic_encoder, &profile);
+        retrieved.first().map(|(memory, _)| memory.id)
+    };
+
+    assert_eq!(first_found("Quelle est la capitale de la France ?"), Some(paris), "Its new words weigh in its own trace");
+    assert_eq!(first_found("De quelle couleur est le ciel ?"), Some(sky), "The words of the first memory were in every document");
+}
+
+#[test]
+fn test_a_synthesis_over_budget_falls_back_to_the_best_memory() {
+    use agi_core::pipeline::PipelineStage;
+    use std::time::Duration;
+
+    let mut core = Core::new_for_tests();
+    core.inject_memory("Le chien aboie dans le jardin.", false);
+    core.config.timeouts.synthesis = Duration::ZERO;
+
+    let (answer, _) = core.get_response_for_prompt("Explique le chien.").expect("an answer");
+    assert_eq!(core.explain_last_response().and_then(|explanation| explanation.partial), Some(PipelineStage::Synthesis));
+    assert!(answer.starts_with("Le chien aboie dans le jardin.\n\n(Réponse partielle"), "{}", answer);
+}
+
+#[test]
+fn test_onboarding_waits_for_retrieval_to_find_nothing() {
+    use agi_core::provenance::AnswerPath;
+
+    let mut core = Core::new_for_tests();
+    core.inject_memory("Socrate était un philosophe grec, maître de Platon.", false);
+    core.inject_memory("Aristote était un philosophe grec, élève de Platon.", false);
+    core.inject_memory("To make bread, mix flour, water, yeast and salt, then bake the dough.", false);
+    let path = |core: &Core| core.explain_last_response().map(|explanation| explanation.path);
+
+    let (offer, _) = core.get_response_for_prompt("Qui est vraiment Cléopâtre ?").expect("an offer");
+    assert_eq!(path(&core), Some(AnswerPath::UnknownConcepts));
+    assert!(offer.contains("« cléopâtre »") && !offer.contains("vraiment"), "{}", offer);
+
+    for prompt in ["Qui était vraiment Socrate ?", "What is the difference between Platon and Aristote?", "How do I make bread?"] {
+        let (answer, _) = core.get_response_for_prompt(prompt).expect("an answer");
+        assert_ne!(path(&core), Some(AnswerPath::UnknownConcepts), "{} -> {}", prompt, answer);
+    }
+
+    let (queued, _) = core.get_response_for_prompt("Scanne /etc/passwd").expect("an answer");
+    assert!(core.pending_scans().is_empty(), "A local path is never scanned from a prompt: {}", queued);
+}
+
+#[test]
+fn test_a_removed_line_forgets_one_memory_of_its_own_file() {
+    use agi_core::knowledge_watcher::KnowledgeDelta;
+    use std::collections::HashMap;
+
+    let mut core = Core::new_for_tests();
+    let moon = "La lune tourne autour de la Terre.".to_string();
+    let delta = |path: &str, added: &[&String], removed: &[&String]| KnowledgeDelta {
+        path: path.into(),
+        is_axiom: false,
+        added: added.iter().map(|line| line.to_string()).collect(),
+        removed: removed.iter().map(|line| line.to_string()).collect(),
+        domains: HashMap::new(),
+    };
+    core.apply_knowledge_delta(&delta("knowledge.txt", &[&moon, &moon], &[]));
+    core.apply_knowledge_delta(&delta("corpus_fondamental/astronomie.txt", &[&moon], &[]));
+    core.learn(&moon, false);
+    let copies = |core: &Core| core.hippocampus.holographic_memory.iter().filter(|memory| memory.text == moon).count();
+    let learned = copies(&core);
+    assert!(learned >= 4, "{} copies", learned);
+
+    // One of the two duplicate lines of knowledge.txt is deleted.
+    assert_eq!(core.apply_knowledge_delta(&delta("knowledge.txt", &[], &[&moon])).memories_forgotten, 1);
+    assert_eq!(copies(&core), learned - 1);
+    assert_eq!(core.apply_knowledge_delta(&delta("knowledge.txt", &[], &[&moon])).memories_forgotten, 1);
+    assert_eq!(core.apply_knowledge_delta(&delta("knowledge.txt", &[], &[&moon])).memories_forgotten, 0, "knowledge.txt taught no other copy");
+    assert_eq!(copies(&core), learned - 2, "The copies of the corpus and of the API are kept");
+}
+
+#[test]
+fn test_clarifications_ask_about_the_subject_rather_than_a_verb() {
+    let mut core = Core::new_for_tests();
+    // A reader is not offered to teach the Core: it is asked back instead.
+    let (question, _) = core.get_response_for_reader("Qui était Platon ?").expect("a question");
+    assert_eq!(question, "Que voulez-vous savoir au sujet de « platon » ?");
+}
+
+#[test]
+fn test_creations_show_the_words_of_the_memories_rather_than_lemmas() {
+    use agi_core::lemmatizer;
+    use agi_core::tokenizer;
+    use std::collections::HashSet;
+
+    let mut core = Core::new_for_tests();
+    let memories = ["La mer était calme sous les étoiles.", "Les étoiles brillaient au-dessus des dunes.", "La mer reflétait la lune."];
+    for memory in memories {
+        core.inject_memory(memory, false);
+    }
+    let written: HashSet<String> = memories.iter().flat_map(|memory| tokenizer::content_words(memory)).collect();
+    let lemmas_only: HashSet<String> = written.iter().map(|word| lemmatizer::lemmatize_in(word, lemmatizer::Lang::French)).filter(|lemma| !written.contains(lemma)).collect();
+    assert!(lemmas_only.contains("éter"), "{:?}", lemmas_only);
+
+    for temperature in [0.0, 0.5, 1.0] {
+        core.config.creativity_temperature = temperature;
+        let (poem, _) = core.get_response_for_prompt("Écris un poème sur la mer.").expect("a poem");
+        let shown: HashSet<String> = tokenizer::content_words(&poem).into_iter().collect();
+        assert!(shown.is_disjoint(&lemmas_only), "Lemmas in the poem: {:?}\n{}", shown.intersection(&lemmas_only).collect::<Vec<_>>(), poem);
+    }
+}
+
+#[test]
+fn test_new_documents_update_the_idf_and_stale_traces_are_re_encoded() {
+    let mut core = Core::new_for_tests();
+    let statistics = |core: &Core| {
+        let encoder = core.holographic_encoder.read().unwrap();
+        (encoder.total_docs, encoder.doc_frequency.values().sum::<usize>())
+    };
+    let (docs, frequencies) = statistics(&core);
+
+    let quasar = "Un quasar est le noyau lumineux d'une galaxie lointaine.";
+    core.learn_and_assimilate(quasar, false);
+    let (docs_after, frequencies_after) = statistics(&core);
+    assert_eq!(docs_after, docs + 1);
+    assert!(frequencies_after > frequencies, "The words of the document are counted");
+    assert_eq!(core.forget_memory(quasar), 1);
+    assert_eq!(statistics(&core), (docs, frequencies), "Forgetting it takes them back out");
+
+    for index in 0..docs.max(5) {
+        core.inject_memory(&format!("La comète numéro {} traverse le système solaire.", index), false);
+    }
+    assert!(core.holographic_encoder.read().unwrap().needs_reencode());
+    assert!(core.refresh_stale_traces());
+    let encoder = core.holographic_encoder.read().unwrap();
+    for memory in &core.hippocampus.holographic_memory {
+        assert_eq!(memory.trace, encoder.encode_memory(&memory.text, memory.image.as_ref()), "{}", memory.text);
+    }
+    drop(encoder);
+    assert!(!core.refresh_stale_traces(), "The traces are current");
+}
+
+#[test]
+fn test_saved_knowledge_is_learned_again_by_another_core() {
+    let path = std::env::temp_dir().join(format!("neurova_saved_knowledge_{}.json", std::process::id()));
+    let mut teacher = Core::new_for_tests();
+    teacher.learn_and_assimilate("Le caniche est un chien frisé.", false);
+    teacher.learn_relationship("caniche", "chien").unwrap();
+    let saved = teacher.save_knowledge(&path).unwrap();
+
+    let mut student = Core::new_for_tests();
+    let outcome = student.load_knowledge(&path).unwrap();
+    assert_eq!(outcome.memories_created, saved.memories.len());
+    assert!(student.hippocampus.holographic_memory.iter().any(|memory| memory.text == "Le caniche est un chien frisé."));
+    let poodle = student.conceptual_hierarchy.find_concept_by_name("caniche").unwrap();
+    let dog = student.conceptual_hierarchy.find_concept_by_name("chien").unwrap();
+    assert!(poodle.parents.contains(&dog.id));
+    assert_eq!(student.load_knowledge(&path).unwrap().memories_created, 0, "Known memories are skipped");
+    std::fs::remove_file(&path).unwrap();
+}
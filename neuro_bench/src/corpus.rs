@@ -0,0 +1,94 @@
+//! corpus.rs - Loading the question/answer pairs of a benchmark directory.
+//!
+//! Every `.jsonl` and `.csv` file of the directory is a corpus named after the file. A JSONL line
+//! is `{"question": "...", "answer": "..."}`; a CSV file has a `question,answer` header. A
+//! question whose options the MCQ solver recognizes ("A. ... B. ...") is a multiple-choice
+//! question, and its answer is the label of the right option, or the labels separated by commas
+//! when several are right ("A, C").
+
+use agi_core::mcq_solver::McqSolver;
+use serde::Deserialize;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum CorpusError {
+    Io(PathBuf, io::Error),
+    Json { path: PathBuf, line: usize, source: serde_json::Error },
+    Csv(PathBuf, csv::Error),
+    Empty(PathBuf),
+}
+
+impl fmt::Display for CorpusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorpusError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            CorpusError::Json { path, line, source } => write!(f, "{}:{}: {}", path.display(), line, source),
+            CorpusError::Csv(path, e) => write!(f, "{}: {}", path.display(), e),
+            CorpusError::Empty(path) => write!(f, "{}: no .jsonl or .csv corpus", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct QaPair {
+    pub question: String,
+    pub answer: String,
+}
+
+impl QaPair {
+    /// The labels of the right options, upper-cased, when the question is a multiple-choice one.
+    pub fn mcq_labels(&self) -> Option<Vec<String>> {
+        McqSolver::parse_mcq(&self.question)?;
+        Some(self.answer.split([',', ';', ' ']).map(str::trim).filter(|label| !label.is_empty()).map(str::to_uppercase).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Corpus {
+    pub name: String,
+    pub pairs: Vec<QaPair>,
+}
+
+/// The corpora of `dir`, sorted by name.
+pub fn load_dir(dir: &Path) -> Result<Vec<Corpus>, CorpusError> {
+    let entries = fs::read_dir(dir).map_err(|e| CorpusError::Io(dir.to_path_buf(), e))?;
+    let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+    paths.sort();
+
+    let mut corpora = Vec::new();
+    for path in paths {
+        let pairs = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("jsonl") => load_jsonl(&path)?,
+            Some("csv") => load_csv(&path)?,
+            _ => continue,
+        };
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+        corpora.push(Corpus { name, pairs });
+    }
+    if corpora.is_empty() {
+        return Err(CorpusError::Empty(dir.to_path_buf()));
+    }
+    Ok(corpora)
+}
+
+fn load_jsonl(path: &Path) -> Result<Vec<QaPair>, CorpusError> {
+    let file = File::open(path).map_err(|e| CorpusError::Io(path.to_path_buf(), e))?;
+    let mut pairs = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| CorpusError::Io(path.to_path_buf(), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let pair = serde_json::from_str(&line).map_err(|source| CorpusError::Json { path: path.to_path_buf(), line: index + 1, source })?;
+        pairs.push(pair);
+    }
+    Ok(pairs)
+}
+
+fn load_csv(path: &Path) -> Result<Vec<QaPair>, CorpusError> {
+    let mut reader = csv::Reader::from_path(path).map_err(|e| CorpusError::Csv(path.to_path_buf(), e))?;
+    reader.deserialize().collect::<Result<_, _>>().map_err(|e| CorpusError::Csv(path.to_path_buf(), e))
+}
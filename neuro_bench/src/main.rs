@@ -0,0 +1,170 @@
+//! neuro_bench - Scores the Core on directories of question/answer pairs.
+//!
+//! `neuro_bench <corpus dir>` boots a seeded Core like the server (identity.txt, then
+//! knowledge.txt), asks it every question of the corpora (see `corpus`), scores the answers (see
+//! `score`) and times them. The report is printed as a markdown table, and written as JSON and/or
+//! markdown with `--json` and `--markdown`, so that two versions of the encoder or of the
+//! reasoning engine can be compared on the same seed. `neuro_bench/corpora` is a small corpus on
+//! the default knowledge base.
+
+mod corpus;
+mod report;
+mod score;
+
+use agi_core::{Core, bulk_assimilation, config::CoreConfig, question_focus};
+use report::{BenchReport, CorpusReport, FocusReport, ItemResult};
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const DEFAULT_SEED: u64 = 42;
+const DEFAULT_FUZZY_THRESHOLD: f32 = 0.5;
+
+const USAGE: &str = "\
+Usage: neuro_bench <corpus dir> [--seed <n>] [--fuzzy <F1 threshold>] [--json <report.json>] [--markdown <report.md>]";
+
+struct Options {
+    corpus_dir: PathBuf,
+    seed: u64,
+    fuzzy_threshold: f32,
+    json: Option<PathBuf>,
+    markdown: Option<PathBuf>,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut corpus_dir = None;
+    let mut options = Options { corpus_dir: PathBuf::new(), seed: DEFAULT_SEED, fuzzy_threshold: DEFAULT_FUZZY_THRESHOLD, json: None, markdown: None };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            if corpus_dir.replace(PathBuf::from(&arg)).is_some() {
+                return Err(format!("Unexpected argument {}", arg));
+            }
+            continue;
+        }
+        let value = args.next().ok_or_else(|| format!("{} needs a value", arg))?;
+        match arg.as_str() {
+            "--seed" => options.seed = value.parse().map_err(|_| "--seed needs a number".to_string())?,
+            "--fuzzy" => options.fuzzy_threshold = value.parse().ok().filter(|threshold| (0.0..=1.0).contains(threshold)).ok_or("--fuzzy needs a number between 0 and 1")?,
+            "--json" => options.json = Some(PathBuf::from(value)),
+            "--markdown" => options.markdown = Some(PathBuf::from(value)),
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+    options.corpus_dir = corpus_dir.ok_or("No corpus directory given")?;
+    Ok(options)
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("ERROR: {}\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    // Read before moving to the workspace root, where the knowledge files are.
+    let corpora = match corpus::load_dir(&options.corpus_dir) {
+        Ok(corpora) => corpora,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+
+    let mut core = match Core::with_config(CoreConfig { seed: Some(options.seed), ..CoreConfig::default() }) {
+        Ok(core) => core,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = core.awaken(bulk_assimilation::print_files()) {
+        eprintln!("ERROR: Could not awaken: {}", e);
+    }
+    for file in ["identity.txt", "knowledge.txt"] {
+        if let Err(e) = core.learn_from_file(project_root.join(file)) {
+            eprintln!("ERROR: Failed to load {}: {}", file, e);
+        }
+    }
+    core.warm_up();
+
+    let mut reports = Vec::new();
+    let (mut all_items, mut all_latencies) = (Vec::new(), Vec::new());
+    for corpus in corpora {
+        println!("--- {} ({} questions) ---", corpus.name, corpus.pairs.len());
+        let (mut items, mut latencies) = (Vec::new(), Vec::new());
+        for pair in &corpus.pairs {
+            let (item, latency) = ask(&mut core, pair);
+            items.push(item);
+            latencies.push(latency);
+        }
+        all_items.extend(items.iter().cloned());
+        all_latencies.extend(&latencies);
+        reports.push(CorpusReport::new(corpus.name, items, &latencies, options.fuzzy_threshold));
+    }
+    let mut total = CorpusReport::new("all".to_string(), all_items, &all_latencies, options.fuzzy_threshold);
+    let by_focus = FocusReport::of(&total.items, options.fuzzy_threshold);
+    total.items.clear();
+    let report = BenchReport { seed: options.seed, fuzzy_threshold: options.fuzzy_threshold, corpora: reports, total, by_focus };
+
+    let markdown = report.to_markdown();
+    println!("\n{}", markdown);
+    if let Some(path) = &options.markdown {
+        write_report(path, &markdown);
+    }
+    if let Some(path) = &options.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_report(path, &json),
+            Err(e) => eprintln!("ERROR: {}", e),
+        }
+    }
+}
+
+/// Asks the core one question, scored against its expected answer.
+fn ask(core: &mut Core, pair: &corpus::QaPair) -> (ItemResult, Duration) {
+    let started = Instant::now();
+    if let Some(expected) = pair.mcq_labels() {
+        let chosen = core.solve_mcq(&pair.question).map(|answer| answer.labels);
+        let latency = started.elapsed();
+        let set = |labels: &[String]| labels.iter().map(|label| label.to_uppercase()).collect::<BTreeSet<_>>();
+        let correct = chosen.as_ref().is_some_and(|chosen| set(chosen) == set(&expected));
+        let answer = chosen.map(|labels| labels.join(", "));
+        let item = ItemResult {
+            question: pair.question.clone(),
+            expected: pair.answer.clone(),
+            exact: correct,
+            f1: if correct { 1.0 } else { 0.0 },
+            answer,
+            mcq_correct: Some(correct),
+            focus: None,
+            latency_ms: latency.as_secs_f64() * 1000.0,
+        };
+        return (item, latency);
+    }
+
+    let answer = core.get_response_for_prompt(&pair.question).map(|(response, _)| response);
+    let latency = started.elapsed();
+    let (exact, f1) = answer.as_deref().map_or((false, 0.0), |answer| (score::exact_match(answer, &pair.answer), score::token_f1(answer, &pair.answer)));
+    let item = ItemResult {
+        question: pair.question.clone(),
+        expected: pair.answer.clone(),
+        answer,
+        exact,
+        f1,
+        mcq_correct: None,
+        focus: question_focus::focus(&pair.question).map(|focus| focus.expected),
+        latency_ms: latency.as_secs_f64() * 1000.0,
+    };
+    (item, latency)
+}
+
+fn write_report(path: &Path, contents: &str) {
+    match fs::write(path, contents) {
+        Ok(()) => println!("[OK] Report written to {}.", path.display()),
+        Err(e) => eprintln!("ERROR: Could not write {}: {}", path.display(), e),
+    }
+}
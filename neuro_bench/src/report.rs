@@ -0,0 +1,159 @@
+//! report.rs - The results of a benchmark run, as JSON or as a markdown table.
+
+use crate::score::percentile;
+use agi_core::sensory_cortex::EntityKind;
+use serde::Serialize;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// How one question was answered.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemResult {
+    pub question: String,
+    pub expected: String,
+    /// The answer of the core (the chosen labels for a multiple-choice question).
+    pub answer: Option<String>,
+    pub exact: bool,
+    pub f1: f32,
+    /// Whether the right options were chosen, for a multiple-choice question.
+    pub mcq_correct: Option<bool>,
+    /// The type of entity an open who/when/where question asks for (see `question_focus`).
+    pub focus: Option<EntityKind>,
+    pub latency_ms: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    pub fn of(latencies: &[Duration]) -> Self {
+        let mut sorted = latencies.to_vec();
+        sorted.sort();
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        let mean_ms = if sorted.is_empty() { 0.0 } else { sorted.iter().copied().map(ms).sum::<f64>() / sorted.len() as f64 };
+        Self {
+            mean_ms,
+            p50_ms: ms(percentile(&sorted, 50.0)),
+            p90_ms: ms(percentile(&sorted, 90.0)),
+            p99_ms: ms(percentile(&sorted, 99.0)),
+            max_ms: ms(sorted.last().copied().unwrap_or_default()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CorpusReport {
+    pub name: String,
+    /// Open questions (not multiple-choice ones).
+    pub questions: usize,
+    /// Open questions the core had an answer to.
+    pub answered: usize,
+    pub exact_matches: usize,
+    pub fuzzy_matches: usize,
+    pub mean_f1: f32,
+    pub mcq_questions: usize,
+    pub mcq_correct: usize,
+    pub latency: LatencySummary,
+    pub items: Vec<ItemResult>,
+}
+
+impl CorpusReport {
+    pub fn new(name: String, items: Vec<ItemResult>, latencies: &[Duration], fuzzy_threshold: f32) -> Self {
+        let open: Vec<&ItemResult> = items.iter().filter(|item| item.mcq_correct.is_none()).collect();
+        let mean_f1 = if open.is_empty() { 0.0 } else { open.iter().map(|item| item.f1).sum::<f32>() / open.len() as f32 };
+        Self {
+            name,
+            questions: open.len(),
+            answered: open.iter().filter(|item| item.answer.is_some()).count(),
+            exact_matches: open.iter().filter(|item| item.exact).count(),
+            fuzzy_matches: open.iter().filter(|item| item.exact || item.f1 >= fuzzy_threshold).count(),
+            mean_f1,
+            mcq_questions: items.len() - open.len(),
+            mcq_correct: items.iter().filter(|item| item.mcq_correct == Some(true)).count(),
+            latency: LatencySummary::of(latencies),
+            items,
+        }
+    }
+
+    pub fn mcq_accuracy(&self) -> Option<f32> {
+        (self.mcq_questions > 0).then(|| self.mcq_correct as f32 / self.mcq_questions as f32)
+    }
+}
+
+/// The open questions of all the corpora asking for one type of entity.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FocusReport {
+    pub focus: EntityKind,
+    pub questions: usize,
+    pub exact_matches: usize,
+    pub fuzzy_matches: usize,
+}
+
+impl FocusReport {
+    /// One report per type of entity the open questions of `items` ask for: persons, dates, places.
+    pub fn of(items: &[ItemResult], fuzzy_threshold: f32) -> Vec<Self> {
+        [EntityKind::Person, EntityKind::Date, EntityKind::Place]
+            .into_iter()
+            .filter_map(|focus| {
+                let asking: Vec<&ItemResult> = items.iter().filter(|item| item.focus == Some(focus)).collect();
+                (!asking.is_empty()).then(|| Self {
+                    focus,
+                    questions: asking.len(),
+                    exact_matches: asking.iter().filter(|item| item.exact).count(),
+                    fuzzy_matches: asking.iter().filter(|item| item.exact || item.f1 >= fuzzy_threshold).count(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub seed: u64,
+    pub fuzzy_threshold: f32,
+    pub corpora: Vec<CorpusReport>,
+    /// All the corpora together (its items are left out, they are in the corpora).
+    pub total: CorpusReport,
+    /// The who, when and where questions, which the re-ranking by question focus targets.
+    pub by_focus: Vec<FocusReport>,
+}
+
+impl BenchReport {
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# NeuroVA benchmark (seed {}, fuzzy F1 >= {})\n\n", self.seed, self.fuzzy_threshold);
+        markdown.push_str("| Corpus | Questions | Answered | Exact | Fuzzy | Mean F1 | MCQ accuracy | p50 (ms) | p90 (ms) | p99 (ms) |\n");
+        markdown.push_str("|---|---|---|---|---|---|---|---|---|---|\n");
+        for report in self.corpora.iter().chain(std::iter::once(&self.total)) {
+            let ratio = |count: usize| if report.questions == 0 { "-".to_string() } else { format!("{:.1}%", count as f32 / report.questions as f32 * 100.0) };
+            let mcq = report.mcq_accuracy().map_or("-".to_string(), |accuracy| format!("{:.1}% ({}/{})", accuracy * 100.0, report.mcq_correct, report.mcq_questions));
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} | {} | {} | {:.3} | {} | {:.1} | {:.1} | {:.1} |",
+                report.name,
+                report.questions + report.mcq_questions,
+                ratio(report.answered),
+                ratio(report.exact_matches),
+                ratio(report.fuzzy_matches),
+                report.mean_f1,
+                mcq,
+                report.latency.p50_ms,
+                report.latency.p90_ms,
+                report.latency.p99_ms
+            );
+        }
+        if !self.by_focus.is_empty() {
+            markdown.push_str("\n| Question focus | Questions | Exact | Fuzzy |\n|---|---|---|---|\n");
+            for report in &self.by_focus {
+                let ratio = |count: usize| format!("{:.1}%", count as f32 / report.questions as f32 * 100.0);
+                let _ = writeln!(markdown, "| {:?} | {} | {} | {} |", report.focus, report.questions, ratio(report.exact_matches), ratio(report.fuzzy_matches));
+            }
+        }
+        markdown
+    }
+}
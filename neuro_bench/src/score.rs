@@ -0,0 +1,83 @@
+//! score.rs - Comparing the answers of the core with the expected ones.
+//!
+//! Both are normalized first: lower-cased, split into words on anything that is not
+//! alphanumeric, articles dropped. The core answers in sentences, so an answer is an exact match
+//! when the expected words appear in it in a row, and a fuzzy match when the F1 score of the
+//! words they share reaches the threshold.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+const ARTICLES: [&str; 10] = ["a", "an", "the", "le", "la", "les", "l", "un", "une", "des"];
+
+pub fn normalize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !ARTICLES.contains(word))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether the expected words appear in a row in the answer.
+pub fn exact_match(answer: &str, expected: &str) -> bool {
+    let (answer, expected) = (normalize(answer), normalize(expected));
+    !expected.is_empty() && answer.windows(expected.len()).any(|window| window == expected.as_slice())
+}
+
+/// The F1 score of the words shared by the answer and the expected answer.
+pub fn token_f1(answer: &str, expected: &str) -> f32 {
+    let (answer, expected) = (normalize(answer), normalize(expected));
+    if answer.is_empty() || expected.is_empty() {
+        return 0.0;
+    }
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for word in &expected {
+        *counts.entry(word).or_default() += 1;
+    }
+    let mut shared = 0;
+    for word in &answer {
+        if let Some(count) = counts.get_mut(word.as_str()).filter(|count| **count > 0) {
+            *count -= 1;
+            shared += 1;
+        }
+    }
+    if shared == 0 {
+        return 0.0;
+    }
+    let precision = shared as f32 / answer.len() as f32;
+    let recall = shared as f32 / expected.len() as f32;
+    2.0 * precision * recall / (precision + recall)
+}
+
+/// The `percentile` (0 to 100) of the latencies, by the nearest rank; zero when there are none.
+pub fn percentile(sorted_latencies: &[Duration], percentile: f32) -> Duration {
+    if sorted_latencies.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percentile / 100.0 * sorted_latencies.len() as f32).ceil() as usize;
+    sorted_latencies[rank.clamp(1, sorted_latencies.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers_are_matched_on_their_words() {
+        let answer = "The speed of light in a vacuum is approximately 299,792,458 meters per second.";
+        assert!(exact_match(answer, "299 792 458 meters per second"));
+        assert!(exact_match(answer, "speed of light"));
+        assert!(!exact_match(answer, "light speed"));
+        assert!(!exact_match(answer, ""));
+
+        assert!((token_f1("Paris", "paris") - 1.0).abs() < 1e-6);
+        assert!((token_f1("la capitale est Paris", "Paris") - 0.5).abs() < 1e-6, "One word in two, the article dropped");
+        assert_eq!(token_f1("Berlin", "Paris"), 0.0);
+
+        let latencies: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&latencies, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&latencies, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&latencies, 100.0), Duration::from_millis(100));
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+    }
+}
@@ -0,0 +1,386 @@
+//! neuro_cli - A headless REPL front-end for the AGI Core.
+//!
+//! Runs the Core on its actor thread (ticking at 20 Hz, like the server) and reads commands
+//! from a line editor, so the Core can be used on machines without a GPU or in CI scripts
+//! (`neuro_cli < script.txt`).
+//!
+//! `neuro_cli --record session.jsonl [--seed N]` records the session from the boot on (see
+//! `agi_core::session`); `neuro_cli --replay session.jsonl` replays it and reports where the
+//! activity or the answers diverged.
+//!
+//! `neuro_cli diff before.json after.json` compares two snapshots written by `:snapshot` (see
+//! `agi_core::state_diff`): the memories and links taught in between, how the synaptic weights
+//! moved and how the neurochemical levels changed.
+
+use agi_core::{Core, CoreHandle, bulk_assimilation, config::CoreConfig, feedback::Feedback};
+use agi_core::session::SessionReplay;
+use agi_core::state_diff::{SnapshotDiff, StateSnapshot};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use std::env;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_KNOWLEDGE_FILE: &str = "neurova_knowledge.json";
+const DEFAULT_SNAPSHOT_FILE: &str = "neurova_snapshot.json";
+/// Memories and links listed by `diff` before the rest is only counted.
+const DIFF_LISTED: usize = 20;
+
+const HELP: &str = "\
+Commands:
+  <prompt>               ask the core
+  :learn <text>          learn a fact
+  :axiom <text>          learn a foundational axiom
+  :relate <child> <parent>
+                         teach that <child> is a kind of <parent>
+  :save [path]           save what was learned (default: neurova_knowledge.json)
+  :load [path]           re-learn a saved file
+  :snapshot [path]       save a snapshot of the state to diff (default: neurova_snapshot.json)
+  :consolidate           store the working memory in long-term memory
+  :status                show the core's state
+  :gaps                  list the questions the core could not answer
+  :sleep [ticks]         sleep (default: 100 ticks): dream, consolidate, prune
+  :dreams                show the dream journal
+  :good / :bad           rate the last answer
+  :help                  show this help
+  :quit                  exit";
+
+const USAGE: &str = "\
+Usage: neuro_cli [--record <session.jsonl> [--seed <n>] | --replay <session.jsonl>]
+       neuro_cli diff <before.json> <after.json>";
+
+/// What the command line asks for besides the REPL.
+#[derive(Default)]
+struct Options {
+    record: Option<String>,
+    replay: Option<String>,
+    seed: Option<u64>,
+    diff: Option<(String, String)>,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1).peekable();
+    if args.peek().is_some_and(|command| command == "diff") {
+        let (Some(before), Some(after), None) = (args.nth(1), args.next(), args.next()) else {
+            return Err("diff needs two snapshot files".to_string());
+        };
+        options.diff = Some((before, after));
+        return Ok(options);
+    }
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        match flag.as_str() {
+            "--record" => options.record = Some(value),
+            "--replay" => options.replay = Some(value),
+            "--seed" => options.seed = Some(value.parse().map_err(|_| "--seed needs a number".to_string())?),
+            _ => return Err(format!("Unknown argument {}", flag)),
+        }
+    }
+    Ok(options)
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("ERROR: {}\n{}", e, USAGE);
+            return;
+        }
+    };
+    // Snapshot paths are relative to where the command is run, so this comes first.
+    if let Some((before, after)) = &options.diff {
+        diff(before, after);
+        return;
+    }
+    println!("--- Initializing NeuroVA AGI Core (headless) ---");
+
+    // Knowledge files and the connectome are resolved from the workspace root.
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let project_root = std::path::Path::new(manifest_dir).parent().unwrap();
+    env::set_current_dir(project_root).expect("Failed to set working directory");
+
+    if let Some(path) = &options.replay {
+        replay(path);
+        return;
+    }
+
+    // A recorded session needs a seed to be replayed; one is drawn if none is given.
+    let seed = options.seed.or_else(|| options.record.as_ref().map(|_| clock_seed()));
+    let mut core = match Core::with_config(CoreConfig { seed, ..CoreConfig::default() }) {
+        Ok(core) => core,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            return;
+        }
+    };
+    if let Some(path) = &options.record {
+        match core.start_recording(path) {
+            Ok(()) => println!("--- Recording the session to {} (seed {}) ---", path, seed.unwrap_or_default()),
+            Err(e) => eprintln!("ERROR: Could not record the session: {}", e),
+        }
+    }
+
+    // Same boot sequence as the server: the foundational corpus, the identity, then the general
+    // knowledge base. The awakening comes after `start_recording`, so that it is recorded too.
+    if let Err(e) = core.awaken(bulk_assimilation::print_files()) {
+        eprintln!("ERROR: Could not awaken: {}", e);
+    }
+    for file in ["identity.txt", "knowledge.txt"] {
+        if let Err(e) = core.learn_from_file(project_root.join(file)) {
+            eprintln!("ERROR: Failed to load {}: {}", file, e);
+        }
+    }
+    let agi_core = match CoreHandle::spawn(core, Some(Duration::from_millis(50))) {
+        Ok(handle) => handle,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            return;
+        }
+    };
+    if agi_core.exec_blocking(|core| core.warm_up()).is_err() {
+        eprintln!("ERROR: The core actor stopped before warmup.");
+        return;
+    }
+    println!("--- AGI Core Initialized. Type :help for the commands. ---");
+
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("ERROR: Could not start the line editor: {}", e);
+            return;
+        }
+    };
+
+    loop {
+        let line = match editor.readline("neurova> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                break;
+            }
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        match run_command(&agi_core, line) {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Quit) => break,
+            Err(message) => eprintln!("{}", message),
+        }
+    }
+
+    // Writes the last ticks of a recording before the actor stops.
+    let _ = agi_core.exec_blocking(|core| core.stop_recording());
+    agi_core.shutdown();
+    println!("--- Goodbye ---");
+}
+
+fn clock_seed() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_nanos() as u64).unwrap_or(0)
+}
+
+/// Replays a recorded session on a new core and reports the divergences.
+fn replay(path: &str) {
+    let session = match SessionReplay::load(path) {
+        Ok(session) => session,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            return;
+        }
+    };
+    let mut core = match Core::with_config(CoreConfig { seed: Some(session.seed), ..CoreConfig::default() }) {
+        Ok(core) => core,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            return;
+        }
+    };
+    println!("--- Replaying {} events (seed {}) ---", session.events.len(), session.seed);
+    let report = session.run(&mut core);
+    for divergence in &report.divergences {
+        println!("line {}: {}", divergence.line, divergence.detail);
+    }
+    println!(
+        "Replayed {} events, {} ticks and {} prompts: {}.",
+        report.events,
+        report.ticks,
+        report.prompts,
+        if report.is_faithful() { "identical".to_string() } else { format!("{} divergences", report.divergences.len()) }
+    );
+}
+
+/// Prints what changed from one snapshot to the other.
+fn diff(before: &str, after: &str) {
+    let snapshots = StateSnapshot::load(before).and_then(|a| Ok((a, StateSnapshot::load(after)?)));
+    let (a, b) = match snapshots {
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            return;
+        }
+    };
+    let diff = Core::diff_snapshots(&a, &b);
+    println!("--- {} -> {} ({:+} ticks) ---", before, after, diff.ticks);
+    if diff.is_empty() {
+        println!("No change.");
+        return;
+    }
+    print_listed("Memories added", &diff.memories_added);
+    print_listed("Memories removed", &diff.memories_removed);
+    let links = |records: &[agi_core::knowledge_file::RelationshipRecord]| -> Vec<String> {
+        records.iter().map(|record| format!("{} -> {}", record.child, record.parent)).collect()
+    };
+    print_listed("Relationships added", &links(&diff.relationships_added));
+    print_listed("Relationships removed", &links(&diff.relationships_removed));
+    print_weight_shift(&a, &b, &diff);
+    let chemicals = diff.neurochemicals;
+    println!(
+        "Neurochemicals: dopamine {:+.3}, serotonin {:+.3}, acetylcholine {:+.3}, noradrenaline {:+.3}",
+        chemicals.dopamine, chemicals.serotonin, chemicals.acetylcholine, chemicals.noradrenaline
+    );
+}
+
+fn print_listed(title: &str, items: &[String]) {
+    println!("{}: {}", title, items.len());
+    for item in items.iter().take(DIFF_LISTED) {
+        println!("  {}", item);
+    }
+    if items.len() > DIFF_LISTED {
+        println!("  ... and {} more", items.len() - DIFF_LISTED);
+    }
+}
+
+fn print_weight_shift(a: &StateSnapshot, b: &StateSnapshot, diff: &SnapshotDiff) {
+    let (wa, wb) = (&a.synapses, &b.synapses);
+    println!("Synapses: {} -> {} ({:+})", wa.count, wb.count, diff.synapses.count_delta);
+    println!("  mean {:.4} -> {:.4} ({:+.4}), std dev {:.4} -> {:.4} ({:+.4})", wa.mean, wb.mean, diff.synapses.mean_delta, wa.std_dev, wb.std_dev, diff.synapses.std_dev_delta);
+    println!("  range [{:.3}, {:.3}] -> [{:.3}, {:.3}], weights moved by {:.4} on average", wa.min, wa.max, wb.min, wb.max, diff.synapses.distance);
+}
+
+enum Flow {
+    Continue,
+    Quit,
+}
+
+fn run_command(agi_core: &CoreHandle, line: &str) -> Result<Flow, String> {
+    let disconnected = |_| "ERROR: The core actor has stopped.".to_string();
+
+    let Some(command) = line.strip_prefix(':') else {
+        let prompt = line.to_string();
+        let answer = agi_core.exec_blocking(move |core| core.get_response_for_prompt(&prompt)).map_err(disconnected)?;
+        match answer {
+            Some((response, query_type)) => println!("[{:?}] {}", query_type, response),
+            None => println!("(no response)"),
+        }
+        return Ok(Flow::Continue);
+    };
+
+    let (name, argument) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+    let argument = argument.trim().to_string();
+    match name {
+        "learn" | "axiom" => {
+            if argument.is_empty() {
+                return Err(format!("Usage: :{} <text>", name));
+            }
+            let is_axiom = name == "axiom";
+            let outcome = agi_core.exec_blocking(move |core| core.learn(&argument, is_axiom)).map_err(disconnected)?;
+            println!("Learned: {} new memories, {} new concepts.", outcome.memories_created, outcome.concepts_created);
+        }
+        "relate" => {
+            let mut names = argument.split_whitespace().map(str::to_string);
+            let (Some(child), Some(parent), None) = (names.next(), names.next(), names.next()) else {
+                return Err("Usage: :relate <child> <parent>".to_string());
+            };
+            let taught = agi_core.exec_blocking(move |core| core.teach_relationship(&child, &parent)).map_err(disconnected)?;
+            let (linked, outcome) = taught.map_err(|e| format!("ERROR: {}", e))?;
+            if linked {
+                println!("Linked ({} new concepts).", outcome.concepts_created);
+            } else {
+                println!("Could not link these concepts.");
+            }
+        }
+        "save" => {
+            let path = if argument.is_empty() { DEFAULT_KNOWLEDGE_FILE.to_string() } else { argument };
+            let saved = agi_core.exec_blocking({
+                let path = path.clone();
+                move |core| core.save_knowledge(&path)
+            });
+            match saved.map_err(disconnected)? {
+                Ok(file) => println!("Saved {} memories and {} relationships to {}.", file.memories.len(), file.relationships.len(), path),
+                Err(e) => return Err(format!("ERROR: {}", e)),
+            }
+        }
+        "load" => {
+            let path = if argument.is_empty() { DEFAULT_KNOWLEDGE_FILE.to_string() } else { argument };
+            match agi_core.exec_blocking(move |core| core.load_knowledge(&path)).map_err(disconnected)? {
+                Ok(outcome) => println!("Loaded: {} new memories, {} new concepts.", outcome.memories_created, outcome.concepts_created),
+                Err(e) => return Err(format!("ERROR: {}", e)),
+            }
+        }
+        "snapshot" => {
+            let path = if argument.is_empty() { DEFAULT_SNAPSHOT_FILE.to_string() } else { argument };
+            let snapshot = agi_core.exec_blocking(|core| core.snapshot_state()).map_err(disconnected)?;
+            match snapshot.save(&path) {
+                Ok(()) => println!("Snapshot of tick {} saved to {}.", snapshot.tick, path),
+                Err(e) => return Err(format!("ERROR: {}", e)),
+            }
+        }
+        "consolidate" => {
+            let outcome = agi_core.exec_blocking(|core| core.consolidate_working_memory()).map_err(disconnected)?;
+            println!("Consolidated: {} new memories, {} new concepts.", outcome.memories_created, outcome.concepts_created);
+        }
+        "status" => {
+            let snapshot = agi_core.latest_snapshot();
+            println!(
+                "tick {} | {:.1} ticks/s | {} memories | {} concepts | {:.2} W | {}",
+                snapshot.tick,
+                snapshot.processing_speed,
+                snapshot.memories_in_hippocampus,
+                snapshot.concepts_in_hierarchy,
+                snapshot.power_draw,
+                if snapshot.ready { "ready" } else { "warming up" }
+            );
+        }
+        "gaps" => {
+            let gaps = agi_core.exec_blocking(|core| core.curiosity_engine.gaps().to_vec()).map_err(disconnected)?;
+            if gaps.is_empty() {
+                println!("No knowledge gaps.");
+            }
+            for gap in gaps {
+                println!("#{} {:?} ({} attempts) '{}' <- {}", gap.id, gap.status, gap.attempts, gap.topic, gap.query);
+            }
+        }
+        "sleep" => {
+            let ticks = if argument.is_empty() { 100 } else { argument.parse().map_err(|_| "Usage: :sleep [ticks]".to_string())? };
+            let report = agi_core.exec_blocking(move |core| core.enter_sleep(ticks)).map_err(disconnected)?;
+            println!(
+                "Slept {} ticks: {} dreams, {} memories merged, {} synapses pruned.",
+                report.ticks, report.dreams, report.memories_merged, report.synapses_pruned
+            );
+        }
+        "dreams" => {
+            let dreams = agi_core.exec_blocking(|core| core.dream_journal().to_vec()).map_err(disconnected)?;
+            if dreams.is_empty() {
+                println!("No dreams yet.");
+            }
+            for dream in dreams {
+                println!("[tick {}] {}", dream.tick, dream.thought);
+            }
+        }
+        "good" | "bad" => {
+            let feedback = if name == "good" { Feedback::Positive } else { Feedback::Negative };
+            let rated = agi_core
+                .exec_blocking(move |core| core.last_response_id().ok_or_else(|| "No answer to rate yet.".to_string()).and_then(|id| core.feedback(id, feedback).map_err(|e| e.to_string())))
+                .map_err(disconnected)??;
+            println!("Rated answer #{} {:?}: {} memories adjusted.", rated.response_id, rated.feedback, rated.memory_ids.len());
+        }
+        "help" => println!("{}", HELP),
+        "quit" | "exit" | "q" => return Ok(Flow::Quit),
+        _ => return Err(format!("Unknown command ':{}'. Type :help for the commands.", name)),
+    }
+    Ok(Flow::Continue)
+}
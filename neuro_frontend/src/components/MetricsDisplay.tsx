@@ -7,6 +7,14 @@ interface GpuMetrics {
     usage: number;
 }
 
+// Power of the pseudo EEG bands, as fractions of the total.
+interface BandPower {
+    delta: number;
+    theta: number;
+    alpha: number;
+    beta: number;
+}
+
 interface Metrics {
     cpu_usage: number;
     memory_usage_kb: number;
@@ -14,6 +22,7 @@ interface Metrics {
     tps: number;
     concepts_in_memory: number;
         power_draw_w: number;
+        eeg_bands: BandPower;
         // gpus: GpuMetrics[];
 }
 
@@ -58,6 +67,11 @@ const MetricsDisplay = () => {
         return tps.toFixed(1);
     };
 
+    const formatDominantBand = (bands: BandPower) => {
+        const [name, power] = Object.entries(bands).reduce((best, band) => (band[1] > best[1] ? band : best));
+        return `${name} ${(power * 100).toFixed(0)}%`;
+    };
+
     const formatMemory = (kb: number) => {
         if (kb < 1024) return `${kb} KB`;
         const mb = kb / 1024;
@@ -89,6 +103,7 @@ const MetricsDisplay = () => {
                     <span>CPU: {metrics.cpu_usage.toFixed(1)}%</span>
                                         <span>RAM: {formatMemory(metrics.memory_usage_kb)} / {formatMemory(metrics.total_memory_kb)}</span>
                     <span>W: {metrics.power_draw_w.toFixed(2)}</span>
+                    <span>EEG: {formatDominantBand(metrics.eeg_bands)}</span>
                                         <span>CONCEPTS: {metrics.concepts_in_memory}</span>
                 </>
             ) : (
@@ -0,0 +1,10 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // A protoc of its own, unless PROTOC points to one: the build needs none on the system.
+    if std::env::var_os("PROTOC").is_none() {
+        let protoc = protoc_bin_vendored::protoc_bin_path()?;
+        // SAFETY: the build script is single-threaded, nothing reads the environment concurrently.
+        unsafe { std::env::set_var("PROTOC", protoc) };
+    }
+    tonic_build::compile_protos("proto/neurova.proto")?;
+    Ok(())
+}
@@ -0,0 +1,246 @@
+//! auth.rs - API keys, roles and usage accounting.
+//!
+//! Keys are read from `api_keys.txt` at the workspace root, one per line: the key, its role
+//! (`reader` or `admin`) and an optional name shown in the usage report
+//! (`3f9c...e1 admin ops-team`); blank lines and `#` comments are ignored. `NEUROVA_ADMIN_KEY`
+//! adds an admin key from the environment. A request carries its key in an `x-api-key` header,
+//! an `Authorization: Bearer` header or, for the WebSocket which browsers cannot give headers,
+//! an `api_key` query parameter.
+//!
+//! Readers may read and prompt; everything that changes what the core knows or how it runs
+//! (learning, admin, simulation control, snapshots, shutdown, the load test) takes an admin.
+//! Without any key configured the server stays open, as before, with a warning at startup.
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use agi_core::error::LockResultExt;
+
+/// Paths open without a key: probes and scrapes.
+const PUBLIC: [&str; 2] = ["/api/status", "/metrics"];
+/// POST endpoints a reader may call: they do not teach the core anything lasting. A reader's
+/// prompt is answered without the onboarding conversation (see `Core::get_response_for_reader`);
+/// feedback reinforces or depresses memories, so it takes an admin.
+const READER_POSTS: [&str; 2] = ["/api/stimulate", "/api/speak"];
+/// GET endpoints that take an admin anyway.
+const ADMIN_GETS: [&str; 1] = ["/agi-load-test"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Reader,
+    Admin,
+}
+
+impl Role {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "reader" => Some(Role::Reader),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+
+    /// The role a request needs.
+    fn required_for(method: &Method, path: &str) -> Role {
+        let reads = *method == Method::GET || *method == Method::HEAD;
+        match (reads, path) {
+            (true, path) if ADMIN_GETS.contains(&path) => Role::Admin,
+            (true, _) => Role::Reader,
+            (false, path) if READER_POSTS.contains(&path) => Role::Reader,
+            _ => Role::Admin,
+        }
+    }
+}
+
+/// The caller of a request, put in its extensions by the middleware.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Caller {
+    pub name: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KeyUsage {
+    pub name: String,
+    pub role: Option<Role>,
+    pub requests: u64,
+    /// Requests refused because the role of the key was not enough.
+    pub forbidden: u64,
+    /// Unix time of the last request, in seconds.
+    pub last_used_at: Option<u64>,
+}
+
+pub struct ApiKeys {
+    keys: HashMap<String, Caller>,
+    /// Usage by key name.
+    usage: Mutex<HashMap<String, KeyUsage>>,
+}
+
+impl ApiKeys {
+    /// The keys of `path` (if it exists) and of `NEUROVA_ADMIN_KEY`. Malformed lines are
+    /// skipped with a warning.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut keys = HashMap::new();
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        for (index, line) in content.lines().map(str::trim).enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (Some(key), Some(role)) = (fields.next(), fields.next().and_then(Role::parse)) else {
+                eprintln!("Warning: {}:{}: expected '<key> reader|admin [name]'.", path.display(), index + 1);
+                continue;
+            };
+            let name = fields.next().map_or_else(|| masked(key), str::to_string);
+            keys.insert(key.to_string(), Caller { name, role });
+        }
+        if let Some(key) = std::env::var("NEUROVA_ADMIN_KEY").ok().filter(|key| !key.is_empty()) {
+            keys.insert(key, Caller { name: "env-admin".to_string(), role: Role::Admin });
+        }
+        Ok(Self { keys, usage: Mutex::new(HashMap::new()) })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The usage of every key the caller may see: its own for a reader, all of them for an admin.
+    pub fn usage(&self, caller: Option<&Caller>) -> Vec<KeyUsage> {
+        let usage = self.usage.lock().recover();
+        let mut report: Vec<KeyUsage> = usage
+            .values()
+            .filter(|entry| caller.is_none_or(|caller| caller.role == Role::Admin || caller.name == entry.name))
+            .cloned()
+            .collect();
+        report.sort_by(|a, b| a.name.cmp(&b.name));
+        report
+    }
+
+    /// The caller holding `key`, if its role is at least `required`; the request is accounted
+    /// for. `Ok(None)` when no key is configured. Fails with 401 for a missing or unknown key,
+    /// and 403 when the role is not enough.
+    pub fn authorize(&self, key: Option<&str>, required: Role) -> Result<Option<Caller>, StatusCode> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let caller = key.and_then(|key| self.keys.get(key)).ok_or(StatusCode::UNAUTHORIZED)?;
+        let forbidden = caller.role < required;
+        self.account(caller, forbidden);
+        if forbidden {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        Ok(Some(caller.clone()))
+    }
+
+    fn account(&self, caller: &Caller, forbidden: bool) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).ok();
+        let mut usage = self.usage.lock().recover();
+        let entry = usage.entry(caller.name.clone()).or_insert_with(|| KeyUsage { name: caller.name.clone(), role: Some(caller.role), ..KeyUsage::default() });
+        entry.requests += 1;
+        entry.forbidden += u64::from(forbidden);
+        entry.last_used_at = now;
+    }
+}
+
+/// The first characters of a key, enough to tell keys apart in a report.
+fn masked(key: &str) -> String {
+    format!("{}…", key.chars().take(4).collect::<String>())
+}
+
+fn request_key(request: &Request) -> Option<String> {
+    let headers = request.headers();
+    if let Some(key) = headers.get("x-api-key").and_then(|value| value.to_str().ok()) {
+        return Some(key.to_string());
+    }
+    if let Some(key) = headers.get("authorization").and_then(|value| value.to_str().ok()).and_then(|value| value.strip_prefix("Bearer ")) {
+        return Some(key.trim().to_string());
+    }
+    // The query value is percent-decoded, like any other query parameter.
+    let Query(mut query) = Query::<HashMap<String, String>>::try_from_uri(request.uri()).ok()?;
+    query.remove("api_key")
+}
+
+/// The middleware: 401 without a known key, 403 when its role is not enough.
+pub async fn authenticate(State(keys): State<std::sync::Arc<ApiKeys>>, mut request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    if PUBLIC.contains(&path.as_str()) {
+        return next.run(request).await;
+    }
+    let required = Role::required_for(request.method(), &path);
+    match keys.authorize(request_key(&request).as_deref(), required) {
+        Ok(caller) => {
+            if let Some(caller) = caller {
+                request.extensions_mut().insert(caller);
+            }
+            next.run(request).await
+        }
+        Err(StatusCode::FORBIDDEN) => (StatusCode::FORBIDDEN, "This API key may not call this endpoint.").into_response(),
+        Err(status) => (status, "A valid API key is required.").into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    fn keys() -> ApiKeys {
+        let path = std::env::temp_dir().join(format!("neurova_api_keys_{}.txt", std::process::id()));
+        fs::write(&path, "# test keys\nreader-key reader alice\nadmin-key admin ops\nbroken-line\n").unwrap();
+        let keys = ApiKeys::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        keys
+    }
+
+    #[test]
+    fn test_roles_required_by_the_routes() {
+        assert_eq!(Role::required_for(&Method::GET, "/api/concepts"), Role::Reader);
+        assert_eq!(Role::required_for(&Method::POST, "/api/stimulate"), Role::Reader);
+        assert_eq!(Role::required_for(&Method::GET, "/agi-load-test"), Role::Admin);
+        for path in ["/api/learn", "/api/learn_source", "/api/feedback", "/api/admin/onboarding/scans", "/api/admin/shutdown"] {
+            assert_eq!(Role::required_for(&Method::POST, path), Role::Admin, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_keys_are_checked_against_the_role() {
+        let keys = keys();
+        assert_eq!(keys.authorize(None, Role::Reader), Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(keys.authorize(Some("unknown-key"), Role::Reader), Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(keys.authorize(Some("broken-line"), Role::Reader), Err(StatusCode::UNAUTHORIZED), "A malformed line grants nothing");
+        assert_eq!(keys.authorize(Some("reader-key"), Role::Admin), Err(StatusCode::FORBIDDEN));
+        assert_eq!(keys.authorize(Some("reader-key"), Role::Reader).unwrap().map(|caller| caller.name), Some("alice".to_string()));
+        assert_eq!(keys.authorize(Some("admin-key"), Role::Admin).unwrap().map(|caller| caller.role), Some(Role::Admin));
+
+        let usage = keys.usage(None);
+        let alice = usage.iter().find(|entry| entry.name == "alice").unwrap();
+        assert_eq!((alice.requests, alice.forbidden), (2, 1));
+        let reader = Caller { name: "alice".to_string(), role: Role::Reader };
+        assert_eq!(keys.usage(Some(&reader)).len(), 1, "A reader only sees its own usage");
+    }
+
+    #[test]
+    fn test_the_key_is_read_from_headers_or_the_query() {
+        let request = |builder: axum::http::request::Builder| request_key(&builder.body(Body::empty()).unwrap());
+        assert_eq!(request(Request::builder().uri("/api/status").header("x-api-key", "k1")), Some("k1".to_string()));
+        assert_eq!(request(Request::builder().uri("/api/status").header("authorization", "Bearer k2")), Some("k2".to_string()));
+        assert_eq!(request(Request::builder().uri("/ws/metrics?foo=1&api_key=k3")), Some("k3".to_string()));
+        assert_eq!(request(Request::builder().uri("/ws/metrics?api_key=k%2B4%3D")), Some("k+4=".to_string()), "The query value is decoded");
+        assert_eq!(request(Request::builder().uri("/ws/metrics")), None);
+    }
+}
@@ -0,0 +1,227 @@
+//! grpc.rs - The gRPC service, for integrators who want typed clients and less overhead than
+//! JSON over HTTP.
+//!
+//! The messages are defined in `proto/neurova.proto` (compiled by `build.rs`); any gRPC
+//! toolchain can generate a client from it. The service goes through the same core actor, the
+//! same API keys (carried in the `x-api-key` metadata) and the same throttle as the HTTP routes. Activity is
+//! streamed from the snapshots the actor publishes on every tick, and from the answers of both
+//! interfaces (see `AnsweredPrompt`).
+
+use crate::auth::{ApiKeys, Caller, Role};
+use crate::throttle::{self, Throttle};
+use agi_core::AsyncCore;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc, OwnedSemaphorePermit};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+
+pub mod pb {
+    tonic::include_proto!("neurova.v1");
+}
+
+use pb::neuro_va_server::NeuroVa;
+pub use pb::neuro_va_server::NeuroVaServer;
+
+/// Events buffered for a slow subscriber; the ticks beyond it are dropped.
+const ACTIVITY_BUFFER: usize = 256;
+
+/// An answer of the core, published to the activity subscribers whichever interface asked.
+#[derive(Debug, Clone)]
+pub struct AnsweredPrompt {
+    pub prompt: String,
+    pub response: String,
+    pub response_id: Option<u64>,
+}
+
+pub struct NeuroVaService {
+    agi_core: AsyncCore,
+    api_keys: Arc<ApiKeys>,
+    throttle: Arc<Throttle>,
+    answers: broadcast::Sender<AnsweredPrompt>,
+}
+
+impl NeuroVaService {
+    pub fn new(agi_core: AsyncCore, api_keys: Arc<ApiKeys>, throttle: Arc<Throttle>, answers: broadcast::Sender<AnsweredPrompt>) -> Self {
+        Self { agi_core, api_keys, throttle, answers }
+    }
+
+    // `Status` is the error of every tonic method, however large.
+    #[allow(clippy::result_large_err)]
+    fn authorize<T>(&self, request: &Request<T>, required: Role) -> Result<Option<Caller>, Status> {
+        let key = request.metadata().get("x-api-key").and_then(|value| value.to_str().ok());
+        self.api_keys.authorize(key, required).map_err(|status| match status.as_u16() {
+            403 => Status::permission_denied("This API key may not call this method."),
+            _ => Status::unauthenticated("A valid API key is required."),
+        })
+    }
+
+    /// Authorizes the request, then waits for a slot of the throttle, held until the permit is dropped.
+    #[allow(clippy::result_large_err)]
+    async fn admit<T>(&self, request: &Request<T>, required: Role) -> Result<(Option<Caller>, OwnedSemaphorePermit), Status> {
+        let caller = self.authorize(request, required)?;
+        let client = throttle::client_key(caller.as_ref(), request.remote_addr().map(|addr| addr.ip()));
+        let slot = self.throttle.admit(&client).await.map_err(|rejection| Status::resource_exhausted(rejection.message()))?;
+        Ok((caller, slot))
+    }
+}
+
+fn core_stopped<E>(_: E) -> Status {
+    Status::unavailable("The core is not running.")
+}
+
+#[tonic::async_trait]
+impl NeuroVa for NeuroVaService {
+    async fn prompt(&self, request: Request<pb::PromptRequest>) -> Result<Response<pb::PromptReply>, Status> {
+        let (caller, _slot) = self.admit(&request, Role::Reader).await?;
+        let reader = caller.is_some_and(|caller| caller.role == Role::Reader);
+        let prompt = request.into_inner().prompt;
+        if prompt.trim().is_empty() {
+            return Err(Status::invalid_argument("The prompt is empty."));
+        }
+        let asked = prompt.clone();
+        let answer = self
+            .agi_core
+            .exec(move |core| {
+                let answer = if reader { core.get_response_for_reader(&asked) } else { core.get_response_for_prompt(&asked) };
+                answer.map(|(response, query_type)| (response, query_type, core.last_response_id()))
+            })
+            .await
+            .map_err(core_stopped)?;
+
+        let Some((response, query_type, response_id)) = answer else {
+            return Ok(Response::new(pb::PromptReply::default()));
+        };
+        let _ = self.answers.send(AnsweredPrompt { prompt, response: response.clone(), response_id });
+        Ok(Response::new(pb::PromptReply {
+            response,
+            answered: true,
+            query_type: format!("{:?}", query_type).to_lowercase(),
+            response_id: response_id.unwrap_or_default(),
+        }))
+    }
+
+    async fn learn(&self, request: Request<pb::LearnRequest>) -> Result<Response<pb::LearnReply>, Status> {
+        let _slot = self.admit(&request, Role::Admin).await?;
+        let pb::LearnRequest { text, is_axiom } = request.into_inner();
+        if text.trim().is_empty() {
+            return Err(Status::invalid_argument("The text to learn is empty."));
+        }
+        let outcome = self.agi_core.exec(move |core| core.learn(&text, is_axiom)).await.map_err(core_stopped)?;
+        Ok(Response::new(pb::LearnReply { memories_created: outcome.memories_created as u64, concepts_created: outcome.concepts_created as u64 }))
+    }
+
+    async fn get_metrics(&self, request: Request<pb::MetricsRequest>) -> Result<Response<pb::MetricsReply>, Status> {
+        let _slot = self.admit(&request, Role::Reader).await?;
+        // The latest snapshot never waits on a prompt.
+        let snapshot = self.agi_core.latest_snapshot();
+        Ok(Response::new(pb::MetricsReply {
+            tick: snapshot.tick,
+            ticks_per_second: snapshot.processing_speed,
+            power_draw_watts: snapshot.power_draw,
+            memories: snapshot.memories_in_hippocampus as u64,
+            concepts: snapshot.concepts_in_hierarchy as u64,
+            awakening_level: snapshot.awakening_level,
+            ready: snapshot.ready,
+            paused: snapshot.clock.paused,
+            eeg_delta: snapshot.eeg_bands.delta,
+            eeg_theta: snapshot.eeg_bands.theta,
+            eeg_alpha: snapshot.eeg_bands.alpha,
+            eeg_beta: snapshot.eeg_bands.beta,
+        }))
+    }
+
+    type SubscribeActivityStream = Pin<Box<dyn Stream<Item = Result<pb::ActivityEvent, Status>> + Send>>;
+
+    async fn subscribe_activity(&self, request: Request<pb::ActivityRequest>) -> Result<Response<Self::SubscribeActivityStream>, Status> {
+        // A stream would hold a slot for as long as it is open: it only takes a token.
+        let caller = self.authorize(&request, Role::Reader)?;
+        let client = throttle::client_key(caller.as_ref(), request.remote_addr().map(|addr| addr.ip()));
+        self.throttle.check_rate(&client).map_err(|rejection| Status::resource_exhausted(rejection.message()))?;
+        let pb::ActivityRequest { tick_stride, responses_only } = request.into_inner();
+        let stride = u64::from(tick_stride.max(1));
+        let mut snapshots = self.agi_core.subscribe();
+        let mut answers = self.answers.subscribe();
+        let (events, stream) = mpsc::channel(ACTIVITY_BUFFER);
+
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    changed = snapshots.changed(), if !responses_only => {
+                        if changed.is_err() {
+                            break;
+                        }
+                        let snapshot = snapshots.borrow_and_update();
+                        if !snapshot.tick.is_multiple_of(stride) {
+                            continue;
+                        }
+                        pb::activity_event::Event::Tick(pb::TickEvent { tick: snapshot.tick, fired_neurons: snapshot.fired_neurons.clone() })
+                    }
+                    answer = answers.recv() => match answer {
+                        Ok(answer) => pb::activity_event::Event::Response(pb::ResponseEvent {
+                            prompt: answer.prompt,
+                            response: answer.response,
+                            response_id: answer.response_id.unwrap_or_default(),
+                        }),
+                        // A subscriber too slow for the answers misses some, it is not cut off.
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                };
+                // Ticks are dropped rather than queued when the client does not keep up.
+                let is_tick = matches!(event, pb::activity_event::Event::Tick(_));
+                let message = Ok(pb::ActivityEvent { event: Some(event) });
+                let closed = if is_tick {
+                    matches!(events.try_send(message), Err(mpsc::error::TrySendError::Closed(_)))
+                } else {
+                    events.send(message).await.is_err()
+                };
+                if closed {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(stream))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::throttle::ThrottleConfig;
+    use agi_core::{Core, CoreHandle};
+
+    fn service() -> NeuroVaService {
+        let path = std::env::temp_dir().join(format!("neurova_grpc_keys_{}.txt", std::process::id()));
+        std::fs::write(&path, "reader-key reader alice\nadmin-key admin ops\n").unwrap();
+        let api_keys = ApiKeys::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let agi_core = AsyncCore::new(CoreHandle::spawn(Core::new_for_tests(), None).unwrap());
+        NeuroVaService::new(agi_core, Arc::new(api_keys), Arc::new(Throttle::new(ThrottleConfig::default())), broadcast::channel(16).0)
+    }
+
+    fn request<T>(message: T, key: Option<&str>) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Some(key) = key {
+            request.metadata_mut().insert("x-api-key", key.parse().unwrap());
+        }
+        request
+    }
+
+    #[tokio::test]
+    async fn test_readers_may_prompt_and_only_admins_may_teach() {
+        let service = service();
+        let learn = |key| request(pb::LearnRequest { text: "Le ciel est bleu par beau temps.".to_string(), is_axiom: false }, key);
+        assert_eq!(service.learn(learn(None)).await.unwrap_err().code(), tonic::Code::Unauthenticated);
+        assert_eq!(service.learn(learn(Some("reader-key"))).await.unwrap_err().code(), tonic::Code::PermissionDenied);
+        assert!(service.learn(learn(Some("admin-key"))).await.unwrap().into_inner().memories_created > 0);
+
+        let prompt = |key| request(pb::PromptRequest { prompt: "Qui était Platon ?".to_string() }, key);
+        assert_eq!(service.prompt(prompt(Some("wrong-key"))).await.unwrap_err().code(), tonic::Code::Unauthenticated);
+        let reply = service.prompt(prompt(Some("reader-key"))).await.unwrap().into_inner();
+        assert!(reply.answered);
+        assert_eq!(reply.response, "Que voulez-vous savoir au sujet de « platon » ?", "A reader is asked back rather than offered to teach");
+        service.agi_core.shutdown();
+    }
+}
@@ -1,24 +1,59 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
+        Extension,
         Json,
+        Path,
         State,
     },
+    http::{header, StatusCode},
+    middleware,
     response::IntoResponse,
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::net::SocketAddr;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread;
 use std::time::Duration;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Notify};
 use futures_util::{stream::StreamExt, SinkExt};
-use agi_core::{Core, performance_monitor::{PerformanceMonitor, Metrics}};
+use agi_core::{
+    ConceptSummary, AsyncCore, Core, CoreHandle, LearningOutcome, SimulationClock, bulk_assimilation::AwakenProgress, hippocampus::RelatedMemory,
+    config::{CoreConfig, RetrievalProfile},
+    thalamus::QueryType,
+    conceptual_hierarchy::{ConceptMerge, ConceptRef, ConceptView, GraphFormat, HierarchyIssue, SplitCriterion},
+    knowledge_scanner::{DataSource, KnowledgeScanner, ScannerError},
+    performance_monitor::{PerformanceMonitor, Metrics},
+    provenance::ResponseExplanation,
+    source_trust::SourceTag,
+    feed_watcher::{FeedWatcher, FeedWatcherConfig},
+    curiosity_engine::KnowledgeGap,
+    sleep::{DreamEntry, SleepReport},
+    feedback::{Feedback, FeedbackError, FeedbackRecord, FeedbackSummary},
+    ethical_core::JudgmentRecord,
+    error::LockResultExt,
+    self_awareness::{Identity, IdentityUpdate},
+    capabilities::Capability,
+    conversation_log::ConversationEntry,
+    personality::{PersonalityError, PersonalityProfile, PersonalityProfiles},
+    social_cortex::UserModel,
+    state_diff::StateSnapshot,
+    trace_compression::{CompressionConfig, CompressionReport, MemoryUsageReport},
+};
 use std::env;
-use std::sync::atomic::Ordering;
+
+mod auth;
+mod grpc;
+mod sources;
+mod throttle;
+use auth::{ApiKeys, Caller, KeyUsage, Role};
+use grpc::{AnsweredPrompt, NeuroVaServer, NeuroVaService};
+use sources::SourcePolicy;
+use throttle::{Throttle, ThrottleConfig};
 
 // Define the structure for the request body
 #[derive(Debug, Deserialize)]
@@ -30,13 +65,151 @@ struct Query {
 #[derive(Serialize)]
 struct PromptResponse {
     response: String,
+    // The ID to rate the answer with (POST /api/feedback).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_id: Option<u64>,
+}
+
+// Query parameters of the related-memories endpoint
+#[derive(Debug, Deserialize)]
+struct RelatedParams {
+    k: Option<usize>,
+}
+
+// Query parameters of the conversation history endpoint
+#[derive(Debug, Deserialize)]
+struct HistoryParams {
+    limit: Option<usize>,
+}
+
+// Query parameters of the hierarchy export endpoint
+#[derive(Debug, Deserialize)]
+struct GraphParams {
+    format: Option<GraphFormat>,
+}
+
+// Query parameters of the concept trace endpoint
+#[derive(Debug, Deserialize)]
+struct TraceParams {
+    size: Option<u32>,
+}
+
+// Request bodies of the teaching endpoints
+#[derive(Debug, Deserialize)]
+struct LearnRequest {
+    text: String,
+    #[serde(default)]
+    is_axiom: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelationshipRequest {
+    child: String,
+    parent: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AliasRequest {
+    alias: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeRequest {
+    keep: String,
+    absorb: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SplitRequest {
+    concept: String,
+    into: Vec<SplitCriterion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LearnSourceRequest {
+    // An https URL on an allowed host, or a file of the knowledge directory (see `sources`)
+    source: String,
+}
+
+#[cfg(feature = "tts")]
+#[derive(Debug, Deserialize)]
+struct SpeakRequest {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SleepRequest {
+    ticks: u64,
+}
+
+// Overrides of the default `CompressionConfig`
+#[derive(Debug, Default, Deserialize)]
+struct CompressRequest {
+    min_concept_share: Option<f32>,
+    cold_after_secs: Option<u64>,
+    compressed_dimension: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StepRequest {
+    ticks: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpeedRequest {
+    tick_interval_ms: u64,
+}
+
+// The tick reached and how the next ones are scheduled
+#[derive(Serialize)]
+struct SimulationStatus {
+    tick: u64,
+    #[serde(flatten)]
+    clock: SimulationClock,
+}
+
+impl SimulationStatus {
+    fn of(core: &Core) -> Self {
+        Self { tick: core.tick, clock: core.clock() }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedbackRequest {
+    response_id: u64,
+    feedback: Feedback,
+}
+
+#[derive(Serialize)]
+struct FeedbackReport {
+    summary: FeedbackSummary,
+    records: Vec<FeedbackRecord>,
+}
+
+#[derive(Serialize)]
+struct RelationshipResponse {
+    linked: bool,
+    #[serde(flatten)]
+    outcome: LearningOutcome,
 }
 
 // Define the application state to be shared across handlers
 struct AppState {
-    agi_core: Arc<Mutex<Core>>,
+    agi_core: AsyncCore,
+    scanner: KnowledgeScanner,
+    /// What /api/learn_source may scan.
+    sources: SourcePolicy,
     perf_monitor: Arc<Mutex<PerformanceMonitor>>,
     metrics_tx: broadcast::Sender<Metrics>,
+    throttle: Arc<Throttle>,
+    api_keys: Arc<ApiKeys>,
+    shutdown: Arc<Notify>,
+    /// Answers published to the gRPC activity subscribers.
+    answers: broadcast::Sender<AnsweredPrompt>,
+    /// The Awakening Ritual running on the actor; None once it is over.
+    awakening: Arc<Mutex<Option<AwakenProgress>>>,
+    /// Set to cancel the Awakening Ritual.
+    cancel_awakening: Arc<AtomicBool>,
 }
 
 #[tokio::main]
@@ -53,43 +226,82 @@ async fn main() {
     let knowledge_path = project_root.join("knowledge.txt");
     let identity_path = project_root.join("identity.txt");
 
-    // 1. Create a new, empty AGI Core.
-    let mut core = Core::new(None);
+    // 1. Create a new, empty AGI Core. Prompts listed in warmup.txt (one per line) are answered
+    //    during the warmup phase so that they are served from the response cache.
+    let warmup_prompts = std::fs::read_to_string(project_root.join("warmup.txt"))
+        .map(|content| content.lines().map(str::trim).filter(|line| !line.is_empty()).map(String::from).collect())
+        .unwrap_or_default();
+    let conversation_log_path = Some(project_root.join("conversation_log.jsonl"));
+    let personality_path = Some(project_root.join("personality.toml")).filter(|path| path.is_file());
+    let core = match Core::with_config(CoreConfig { warmup_prompts, conversation_log_path, personality_path, ..CoreConfig::default() }) {
+        Ok(core) => core,
+        Err(e) => {
+            eprintln!("FATAL: {}", e);
+            std::process::exit(1);
+        }
+    };
 
-    // 2. Load the identity first to establish the semantic baseline.
-    println!("--- Loading identity file... ---");
-    if let Err(e) = core.learn_from_file(identity_path.to_str().unwrap()) {
-        eprintln!("ERROR: Failed to load identity file: {}", e);
-    }
+    // --- AGI Core Actor ---
+    // The core runs on its own thread and ticks at 20 Hz between commands, so a long
+    // reasoning call never blocks the metrics stream or other clients.
+    let agi_core = match CoreHandle::spawn(core, Some(Duration::from_millis(50))) {
+        Ok(handle) => AsyncCore::new(handle),
+        Err(e) => {
+            eprintln!("FATAL: {}", e);
+            std::process::exit(1);
+        }
+    };
+    // 2. Awaken, then learn the identity (to establish the semantic baseline) and the general
+    //    knowledge base, and warm up, on the actor thread while the server already answers
+    //    /api/status with the progress of the awakening, so that load balancers can wait for readiness.
+    let awakening = Arc::new(Mutex::new(Some(AwakenProgress::default())));
+    let cancel_awakening = Arc::new(AtomicBool::new(false));
+    let (progress, cancel) = (Arc::clone(&awakening), Arc::clone(&cancel_awakening));
+    let boot = agi_core.submit(move |core| {
+        let awakened = core.awaken(|state| {
+            *progress.lock().recover() = Some(state.clone());
+            if cancel.load(Ordering::Relaxed) { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        });
+        *progress.lock().recover() = None;
+        match awakened {
+            Ok(report) if report.cancelled => println!("--- Awakening cancelled after {} lines ---", report.lines),
+            Ok(_) => {}
+            Err(e) => eprintln!("ERROR: Failed to awaken: {}", e),
+        }
 
-    // 3. Load the general knowledge base.
-    println!("--- Loading knowledge base... ---");
-    if let Err(e) = core.learn_from_file(knowledge_path.to_str().unwrap()) {
-        eprintln!("ERROR: Failed to load knowledge file: {}", e);
+        println!("--- Loading identity file... ---");
+        if let Err(e) = core.learn_from_file(identity_path.to_str().unwrap()) {
+            eprintln!("ERROR: Failed to load identity file: {}", e);
+        }
+        println!("--- Loading knowledge base... ---");
+        if let Err(e) = core.learn_from_file(knowledge_path.to_str().unwrap()) {
+            eprintln!("ERROR: Failed to load knowledge file: {}", e);
+        }
+    }).await;
+    if boot.is_err() || agi_core.submit(|core| core.warm_up()).await.is_err() {
+        eprintln!("ERROR: The core actor stopped before warmup.");
     }
-
-        let agi_core = Arc::new(Mutex::new(core));
-        let perf_monitor = Arc::new(Mutex::new(PerformanceMonitor::new()));
+    let perf_monitor = Arc::new(Mutex::new(PerformanceMonitor::new()));
     let (metrics_tx, _) = broadcast::channel(100);
     println!("--- AGI Core Initialized ---");
 
-    // --- Background Thread for AGI Ticking ---
     // --- Metrics Broadcasting Task ---
     let metrics_tx_clone = metrics_tx.clone();
-    let core_for_metrics = Arc::clone(&agi_core);
+    let core_for_metrics = agi_core.clone();
     let monitor_for_metrics = Arc::clone(&perf_monitor);
 
     tokio::spawn(async move {
+        let mut last_tick = core_for_metrics.latest_snapshot().tick;
         loop {
-            let (concepts_in_memory, power_draw_w) = {
-                // Lock, read data, and unlock immediately by ending the scope.
-                let core_guard = core_for_metrics.lock().unwrap();
-                let concepts = core_guard.hippocampus.holographic_memory.len();
-                let power = core_guard.power_draw.load(Ordering::Relaxed);
-                (concepts, power)
+            // The latest snapshot is published by the actor; reading it never waits on a prompt.
+            let snapshot = core_for_metrics.latest_snapshot();
+            let metrics = {
+                // A handler that panicked while holding the monitor must not stop the metrics.
+                let mut monitor = monitor_for_metrics.lock().recover();
+                monitor.record_ticks(snapshot.tick.saturating_sub(last_tick));
+                monitor.get_metrics(snapshot.memories_in_hippocampus, snapshot.power_draw, snapshot.eeg_bands)
             };
-
-            let metrics = monitor_for_metrics.lock().unwrap().get_metrics(concepts_in_memory, power_draw_w);
+            last_tick = snapshot.tick;
             
             if let Err(_) = metrics_tx_clone.send(metrics) {
                 // This can happen if there are no receivers, which is fine.
@@ -98,39 +310,183 @@ async fn main() {
         }
     });
 
-    // --- Background Thread for AGI Ticking & Performance Monitoring ---
-    let core_for_tick = Arc::clone(&agi_core);
-    let monitor_for_tick = Arc::clone(&perf_monitor);
-    thread::spawn(move || {
-        loop {
-            {
-                let mut core_guard = core_for_tick.lock().unwrap();
-                core_guard.tick();
-                let mut monitor_guard = monitor_for_tick.lock().unwrap();
-                monitor_guard.tick();
-            } // Locks are released here
-            
-            // Tick at a reasonable rate (e.g., 20 Hz)
-            thread::sleep(Duration::from_millis(50));
-        }
-    });
-    println!("--- AGI Core Ticking Thread Started ---");
+    // --- Feed Watcher Task ---
+    // Feeds listed in feeds.txt ("url [seconds]" per line) are polled in the background; the
+    // entries that match the interests of the conversation are learned.
+    if let Some(feed_config) = FeedWatcherConfig::load(project_root.join("feeds.txt")).ok().filter(|config| !config.feeds.is_empty()) {
+        println!("--- Watching {} feed(s) ---", feed_config.feeds.len());
+        tokio::spawn(watch_feeds(agi_core.clone(), feed_config));
+    }
+
+    // --- Curiosity Task ---
+    // Questions left unanswered become knowledge gaps; they are explored one at a time.
+    tokio::spawn(explore_knowledge_gaps(agi_core.clone()));
+
+    // --- Knowledge Hot Reload ---
+    // Edits of knowledge.txt, identity.txt and corpus_fondamental/ are learned (or forgotten)
+    // while the server runs.
+    #[cfg(feature = "hot_reload")]
+    watch_knowledge_files(agi_core.handle().clone(), project_root);
 
     // --- Axum Server Setup ---
-        let app_state = Arc::new(AppState { agi_core, perf_monitor, metrics_tx });
+    // Requests beyond the concurrency limit wait in a bounded queue; clients beyond their rate
+    // are turned away (see `throttle`).
+    let throttle = Arc::new(Throttle::new(ThrottleConfig::from_env()));
+    // Only the keys of api_keys.txt (or NEUROVA_ADMIN_KEY) may call the API (see `auth`).
+    let api_keys = match ApiKeys::load(&project_root.join("api_keys.txt")) {
+        Ok(keys) => Arc::new(keys),
+        Err(e) => {
+            eprintln!("FATAL: Could not read api_keys.txt: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if api_keys.is_empty() {
+        eprintln!("WARNING: No API key is configured; every endpoint is open to anyone.");
+    }
+    let shutdown = Arc::new(Notify::new());
+    let core_to_stop = agi_core.clone();
+
+    // --- gRPC Service ---
+    // The same core and keys, for typed clients (see proto/neurova.proto).
+    let (answers, _) = broadcast::channel(100);
+    let grpc_service = NeuroVaService::new(agi_core.clone(), Arc::clone(&api_keys), Arc::clone(&throttle), answers.clone());
+    let grpc_addr = SocketAddr::from(([0, 0, 0, 0], 50051));
+    tokio::spawn(async move {
+        println!("NeuroVA gRPC service listening on {}", grpc_addr);
+        if let Err(e) = tonic::transport::Server::builder().add_service(NeuroVaServer::new(grpc_service)).serve(grpc_addr).await {
+            eprintln!("ERROR: The gRPC service stopped: {}", e);
+        }
+    });
+        let app_state = Arc::new(AppState {
+            agi_core,
+            scanner: KnowledgeScanner::new(),
+            sources: SourcePolicy::from_env(project_root),
+            perf_monitor,
+            metrics_tx,
+            throttle: Arc::clone(&throttle),
+            api_keys: Arc::clone(&api_keys),
+            shutdown: Arc::clone(&shutdown),
+            answers,
+            awakening,
+            cancel_awakening,
+        });
 
     let app = Router::new()
         .route("/api/stimulate", post(prompt_handler))
         .route("/api/status", get(status_handler))
+        .route("/api/admin/awakening/cancel", post(cancel_awakening_handler))
+        .route("/metrics", get(prometheus_handler))
+        .route("/api/memories/:id/related", get(related_memories_handler))
+        .route("/api/concepts", get(concepts_handler))
+        .route("/api/concepts/:name", get(concept_handler))
+        .route("/api/concepts/:name/aliases", post(add_alias_handler))
+        .route("/api/concepts/:name/summary", get(concept_summary_handler))
+        .route("/api/hierarchy/issues", get(hierarchy_issues_handler))
+        .route("/api/hierarchy/export", get(hierarchy_export_handler))
+        .route("/api/admin/concepts/merge", post(merge_concepts_handler))
+        .route("/api/admin/concepts/split", post(split_concept_handler))
+        .route("/api/admin/concepts/cleanup", post(cleanup_concepts_handler))
+        .route("/api/concepts/:name/trace.png", get(concept_trace_handler))
+        .route("/api/learn", post(learn_handler))
+        .route("/api/relationship", post(relationship_handler))
+        .route("/api/learn_source", post(learn_source_handler))
+        .route("/api/admin/onboarding/scans", post(confirm_scans_handler))
+        .route("/api/working_memory/consolidate", post(consolidate_handler))
+        .route("/api/explain", get(explain_handler))
+        .route("/api/curiosity/gaps", get(gaps_handler))
+        .route("/api/sleep", post(sleep_handler))
+        .route("/api/dreams", get(dreams_handler))
+        .route("/api/simulation", get(simulation_handler))
+        .route("/api/simulation/pause", post(pause_simulation_handler))
+        .route("/api/simulation/resume", post(resume_simulation_handler))
+        .route("/api/simulation/step", post(step_simulation_handler))
+        .route("/api/simulation/speed", post(simulation_speed_handler))
+        .route("/api/feedback", post(feedback_handler).get(feedback_report_handler))
+        .route("/api/ethics/audit", get(ethics_audit_handler))
+        .route("/api/identity", get(identity_handler).put(update_identity_handler))
+        .route("/api/capabilities", get(capabilities_handler))
+        .route("/api/history", get(history_handler))
+        .route("/api/user", get(user_handler))
+        .route("/api/usage", get(usage_handler))
+        .route("/api/admin/snapshot", post(snapshot_handler))
+        .route("/api/admin/compress", post(compress_handler))
+        .route("/api/memory_usage", get(memory_usage_handler))
+        .route("/api/retrieval/profiles", get(retrieval_profiles_handler))
+        .route("/api/retrieval/profiles/:query_type", put(set_retrieval_profile_handler).delete(reset_retrieval_profile_handler))
+        .route("/api/admin/shutdown", post(shutdown_handler))
+        .route("/api/personality", get(personality_handler).put(update_personality_handler))
+        .route("/api/personality/:name", post(activate_personality_handler))
                 .route("/ws/metrics", get(websocket_handler))
-                .route("/agi-load-test", get(agi_load_test_handler))
-        .with_state(app_state);
+                .route("/agi-load-test", get(agi_load_test_handler));
+    // Spoken answers, for builds with a speech engine.
+    #[cfg(feature = "tts")]
+    let app = app.route("/api/speak", post(speak_handler));
+    let app = app
+        .with_state(app_state)
+        .layer(middleware::from_fn_with_state(throttle, throttle::throttle))
+        // Outermost, so that the unknown clients never take a slot of the queue.
+        .layer(middleware::from_fn_with_state(api_keys, auth::authenticate));
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
     println!("NeuroVA Server listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    // The peer address keys the rate limit of the clients without an API key.
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(async move { shutdown.notified().await })
+        .await
+        .unwrap();
+    // Writes the last ticks of a recording before the actor stops.
+    let _ = core_to_stop.exec(|core| core.stop_recording()).await;
+    core_to_stop.shutdown();
+    println!("--- NeuroVA Server stopped ---");
+}
+
+/// The usage of the API keys: all of them for an admin, its own for a reader.
+async fn usage_handler(State(state): State<Arc<AppState>>, caller: Option<Extension<Caller>>) -> axum::Json<Vec<KeyUsage>> {
+    axum::Json(state.api_keys.usage(caller.as_ref().map(|Extension(caller)| caller)))
+}
+
+/// What the core knows, the distribution of its synaptic weights and its neurochemical
+/// levels, to compare later with `neuro_cli diff` (see `agi_core::state_diff`).
+async fn snapshot_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<StateSnapshot>, StatusCode> {
+    state.agi_core.exec(|core| core.snapshot_state()).await.map(axum::Json).map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Compresses the memory traces; the body, optional, overrides the defaults of `CompressionConfig`.
+async fn compress_handler(
+    State(state): State<Arc<AppState>>,
+    payload: Option<Json<CompressRequest>>,
+) -> Result<axum::Json<CompressionReport>, (StatusCode, String)> {
+    let Json(payload) = payload.unwrap_or_default();
+    let defaults = CompressionConfig::default();
+    let config = CompressionConfig {
+        min_concept_share: payload.min_concept_share.unwrap_or(defaults.min_concept_share),
+        cold_after_secs: payload.cold_after_secs.unwrap_or(defaults.cold_after_secs),
+        compressed_dimension: payload.compressed_dimension.unwrap_or(defaults.compressed_dimension),
+        ..defaults
+    };
+    if !(0.0..1.0).contains(&config.min_concept_share) || config.compressed_dimension == 0 {
+        return Err((StatusCode::BAD_REQUEST, "min_concept_share must be in [0, 1) and compressed_dimension positive.".to_string()));
+    }
+    state
+        .agi_core
+        .exec(move |core| core.compress_traces(&config))
+        .await
+        .map(axum::Json)
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string()))
+}
+
+/// The approximate bytes held by each subsystem of the core.
+async fn memory_usage_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<MemoryUsageReport>, StatusCode> {
+    state.agi_core.exec(|core| core.memory_usage()).await.map(axum::Json).map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Stops accepting connections, lets the requests in flight finish, then stops the core.
+async fn shutdown_handler(State(state): State<Arc<AppState>>) -> StatusCode {
+    println!("--- Shutdown requested through the API ---");
+    state.shutdown.notify_one();
+    StatusCode::ACCEPTED
 }
 
 async fn agi_load_test_handler(State(state): State<Arc<AppState>>) -> &'static str {
@@ -149,11 +505,7 @@ async fn agi_load_test_handler(State(state): State<Arc<AppState>>) -> &'static s
         for i in 0..num_requests {
             let prompt = prompts[i % prompts.len()].to_string();
             
-            // Lock, stimulate, and unlock in a tight scope
-            let _response = {
-                let mut core_guard = state.agi_core.lock().unwrap();
-                core_guard.get_response_for_prompt(&prompt)
-            };
+            let _response = state.agi_core.prompt(prompt).await;
             
             // Small delay to allow other tasks to run and not completely block everything.
             tokio::time::sleep(Duration::from_millis(20)).await;
@@ -164,8 +516,755 @@ async fn agi_load_test_handler(State(state): State<Arc<AppState>>) -> &'static s
     "AGI load test initiated in the background. Observe the metrics."
 }
 
-async fn status_handler() -> axum::Json<serde_json::Value> {
-    axum::Json(json!({ "status": "ok" }))
+/// Prometheus scrape endpoint. Reads the latest snapshot, so it never waits on a prompt.
+async fn prometheus_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let mut body = agi_core::metrics::registry().render_prometheus(&state.agi_core.latest_snapshot());
+    body.push_str(&state.throttle.render_prometheus());
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+/// Readiness probe: 503 until the core has finished warming up.
+async fn status_handler(State(state): State<Arc<AppState>>) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let snapshot = state.agi_core.latest_snapshot();
+    let (queued, capacity) = state.agi_core.queued_commands();
+    let queue = json!({ "queued": queued, "capacity": capacity });
+    if snapshot.ready {
+        return (StatusCode::OK, axum::Json(json!({ "status": "ok", "ready": true, "queue": queue })));
+    }
+    match state.awakening.lock().recover().clone() {
+        Some(progress) => {
+            let awakening = json!({ "progress": progress, "percent": progress.percent() });
+            (StatusCode::SERVICE_UNAVAILABLE, axum::Json(json!({ "status": "awakening", "ready": false, "awakening": awakening, "queue": queue })))
+        }
+        None => (StatusCode::SERVICE_UNAVAILABLE, axum::Json(json!({ "status": "warming_up", "ready": false, "queue": queue }))),
+    }
+}
+
+/// Stops the Awakening Ritual after its current batch; the core warms up with what it learned.
+async fn cancel_awakening_handler(State(state): State<Arc<AppState>>) -> StatusCode {
+    if state.awakening.lock().recover().is_none() {
+        return StatusCode::CONFLICT;
+    }
+    state.cancel_awakening.store(true, Ordering::Relaxed);
+    StatusCode::ACCEPTED
+}
+
+/// Lists the memories clustered around a given memory, most confident first.
+async fn related_memories_handler(
+    State(state): State<Arc<AppState>>,
+    Path(memory_id): Path<u64>,
+    axum::extract::Query(params): axum::extract::Query<RelatedParams>,
+) -> Result<axum::Json<Vec<RelatedMemory>>, StatusCode> {
+    let k = params.k.unwrap_or(5).min(50);
+    match state.agi_core.exec(move |core| core.related_memories(memory_id, k)).await {
+        Ok(Some(related)) => Ok(axum::Json(related)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::SERVICE_UNAVAILABLE),
+    }
+}
+
+/// Lists every concept of the hierarchy, sorted by name.
+async fn concepts_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<Vec<ConceptRef>>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| core.conceptual_hierarchy.concept_refs())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Describes a concept with its parents, children, domains and abstraction level.
+async fn concept_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<axum::Json<ConceptView>, StatusCode> {
+    match state.agi_core.exec(move |core| core.conceptual_hierarchy.concept_view(&name)).await {
+        Ok(Some(view)) => Ok(axum::Json(view)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::SERVICE_UNAVAILABLE),
+    }
+}
+
+/// Summarizes what the Core knows about a concept and its children, with the memories drawn from.
+async fn concept_summary_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<axum::Json<ConceptSummary>, StatusCode> {
+    match state.agi_core.exec(move |core| core.summarize_concept(&name)).await {
+        Ok(Some(summary)) => Ok(axum::Json(summary)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::SERVICE_UNAVAILABLE),
+    }
+}
+
+/// Adds another name for a concept; 404 if the concept is unknown, 409 if the alias already
+/// names another concept.
+async fn add_alias_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    axum::Json(request): axum::Json<AliasRequest>,
+) -> Result<axum::Json<ConceptView>, StatusCode> {
+    let result = state
+        .agi_core
+        .exec(move |core| {
+            if core.conceptual_hierarchy.find_concept_by_name(&name).is_none() {
+                return Err(StatusCode::NOT_FOUND);
+            }
+            if !core.add_concept_alias(&name, &request.alias) {
+                return Err(StatusCode::CONFLICT);
+            }
+            core.conceptual_hierarchy.concept_view(&name).ok_or(StatusCode::NOT_FOUND)
+        })
+        .await;
+    match result {
+        Ok(view) => view.map(axum::Json),
+        Err(_) => Err(StatusCode::SERVICE_UNAVAILABLE),
+    }
+}
+
+/// Renders a concept's holographic trace as a PNG mandala (`?size=` in pixels, 256 by default).
+async fn concept_trace_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<TraceParams>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let size = params.size.unwrap_or(256).clamp(32, 1024);
+    let trace = match state.agi_core.exec(move |core| core.conceptual_hierarchy.find_concept_by_name(&name).map(|node| node.trace.clone())).await {
+        Ok(Some(trace)) => trace,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::SERVICE_UNAVAILABLE),
+    };
+    // Rendering is CPU-bound: keep it off both the core actor and the async workers.
+    let png = tokio::task::spawn_blocking(move || agi_core::trace_visualizer::render_trace_png(&trace, size, size))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(([(header::CONTENT_TYPE, "image/png")], png))
+}
+
+/// Speaks a text (usually an answer) with the voice of the active personality profile, as WAV.
+#[cfg(feature = "tts")]
+async fn speak_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SpeakRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    use agi_core::speech::SpeechError;
+
+    if payload.text.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "The text to speak is empty.".to_string()));
+    }
+    let utterance = state
+        .agi_core
+        .exec(move |core| core.utterance(&payload.text))
+        .await
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string()))?;
+    // The engine takes about as long as the text takes to read: keep it off the core actor.
+    let speech = tokio::task::spawn_blocking(move || utterance.speak())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| match e {
+            SpeechError::Empty => (StatusCode::BAD_REQUEST, e.to_string()),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+    Ok(([(header::CONTENT_TYPE, "audio/wav")], speech.wav))
+}
+
+/// Teaches a fact (or an axiom) and reports how many memories and concepts it created.
+async fn learn_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<LearnRequest>,
+) -> Result<axum::Json<LearningOutcome>, (StatusCode, String)> {
+    if payload.text.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "The text to learn is empty.".to_string()));
+    }
+    state
+        .agi_core
+        .exec(move |core| core.learn(&payload.text, payload.is_axiom))
+        .await
+        .map(axum::Json)
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string()))
+}
+
+/// Stores the facts of the working memory in long-term memory.
+async fn consolidate_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<LearningOutcome>, (StatusCode, String)> {
+    state
+        .agi_core
+        .exec(|core| core.consolidate_working_memory())
+        .await
+        .map(axum::Json)
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string()))
+}
+
+/// Explains the last answer: pipeline step, classification, retrieved memories and their
+/// distances, dopamine adjustment and motor cortex strategy. 404 before the first prompt.
+async fn explain_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<ResponseExplanation>, StatusCode> {
+    match state.agi_core.exec(|core| core.explain_last_response().cloned()).await {
+        Ok(Some(explanation)) => Ok(axum::Json(explanation)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::SERVICE_UNAVAILABLE),
+    }
+}
+
+/// The knowledge gaps recorded by the curiosity engine, with their exploration status.
+async fn gaps_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<Vec<KnowledgeGap>>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| core.curiosity_engine.gaps().to_vec())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Puts the core to sleep for the given number of ticks; it answers nothing meanwhile.
+async fn sleep_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SleepRequest>,
+) -> Result<axum::Json<SleepReport>, (StatusCode, String)> {
+    const MAX_SLEEP_TICKS: u64 = 10_000;
+    if payload.ticks == 0 || payload.ticks > MAX_SLEEP_TICKS {
+        return Err((StatusCode::BAD_REQUEST, format!("ticks must be between 1 and {}.", MAX_SLEEP_TICKS)));
+    }
+    state
+        .agi_core
+        .exec(move |core| core.enter_sleep(payload.ticks))
+        .await
+        .map(axum::Json)
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string()))
+}
+
+/// The tick reached and how the ticks are scheduled. Reads the latest snapshot, so it answers
+/// even while a prompt is being reasoned about.
+async fn simulation_handler(State(state): State<Arc<AppState>>) -> axum::Json<SimulationStatus> {
+    let snapshot = state.agi_core.latest_snapshot();
+    axum::Json(SimulationStatus { tick: snapshot.tick, clock: snapshot.clock })
+}
+
+/// Freezes the network: the scheduled ticks stop until the simulation is resumed.
+async fn pause_simulation_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<SimulationStatus>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| {
+            core.pause();
+            SimulationStatus::of(core)
+        })
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+async fn resume_simulation_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<SimulationStatus>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| {
+            core.resume();
+            SimulationStatus::of(core)
+        })
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Runs the given number of ticks at once, e.g. to advance a paused network step by step.
+async fn step_simulation_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<StepRequest>,
+) -> Result<axum::Json<SimulationStatus>, (StatusCode, String)> {
+    const MAX_STEP_TICKS: u64 = 10_000;
+    if payload.ticks == 0 || payload.ticks > MAX_STEP_TICKS {
+        return Err((StatusCode::BAD_REQUEST, format!("ticks must be between 1 and {}.", MAX_STEP_TICKS)));
+    }
+    state
+        .agi_core
+        .exec(move |core| {
+            core.step(payload.ticks);
+            SimulationStatus::of(core)
+        })
+        .await
+        .map(axum::Json)
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string()))
+}
+
+/// Changes the delay between two scheduled ticks.
+async fn simulation_speed_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SpeedRequest>,
+) -> Result<axum::Json<SimulationStatus>, (StatusCode, String)> {
+    const MAX_TICK_INTERVAL_MS: u64 = 60_000;
+    if payload.tick_interval_ms == 0 || payload.tick_interval_ms > MAX_TICK_INTERVAL_MS {
+        return Err((StatusCode::BAD_REQUEST, format!("tick_interval_ms must be between 1 and {}.", MAX_TICK_INTERVAL_MS)));
+    }
+    state
+        .agi_core
+        .exec(move |core| {
+            core.set_tick_interval(Some(Duration::from_millis(payload.tick_interval_ms)));
+            SimulationStatus::of(core)
+        })
+        .await
+        .map(axum::Json)
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string()))
+}
+
+/// The thoughts synthesized during the sleep cycles, oldest first.
+async fn dreams_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<Vec<DreamEntry>>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| core.dream_journal().to_vec())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Rates an answer by the `response_id` returned with it: 404 if it is unknown or too old to
+/// be rated, 409 if it was already rated.
+async fn feedback_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<FeedbackRequest>,
+) -> Result<axum::Json<FeedbackRecord>, (StatusCode, String)> {
+    match state.agi_core.exec(move |core| core.feedback(payload.response_id, payload.feedback)).await {
+        Ok(Ok(record)) => Ok(axum::Json(record)),
+        Ok(Err(e @ FeedbackError::UnknownResponse(_))) => Err((StatusCode::NOT_FOUND, e.to_string())),
+        Ok(Err(e @ FeedbackError::AlreadyRated(_))) => Err((StatusCode::CONFLICT, e.to_string())),
+        Err(_) => Err((StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string())),
+    }
+}
+
+/// Every judgment of the ethical core, oldest first: the query, its intent, the level and the
+/// rule that decided it.
+async fn ethics_audit_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<Vec<JudgmentRecord>>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| core.ethical_core.audit_log().to_vec())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// The structured identity of the core: name, purpose, capabilities and creator.
+async fn identity_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<Identity>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| core.identity().clone())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Changes the attributes given in the body and saves the identity; returns the new identity.
+async fn update_identity_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<IdentityUpdate>,
+) -> Result<axum::Json<Identity>, (StatusCode, String)> {
+    match state.agi_core.exec(move |core| core.update_identity(payload)).await {
+        Ok(Ok(identity)) => Ok(axum::Json(identity)),
+        Ok(Err(e)) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(_) => Err((StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string())),
+    }
+}
+
+/// What the core can do, as declared by its compiled modules and registered tools.
+async fn capabilities_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<Vec<Capability>>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| core.capabilities().capabilities().to_vec())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// What the Core knows about the user: name, mood, topics and interests.
+async fn user_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<UserModel>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| core.user_model().clone())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// The last answered prompts (50 by default), oldest first.
+async fn history_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<HistoryParams>,
+) -> Result<axum::Json<Vec<ConversationEntry>>, StatusCode> {
+    let limit = params.limit.unwrap_or(50).min(1000);
+    state
+        .agi_core
+        .exec(move |core| core.conversation_log().recent(limit).cloned().collect())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// The personality profiles and the name of the active one.
+async fn personality_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<PersonalityProfiles>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| core.personality_profiles().clone())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// The retrieval profile of a query type: its own, or the one derived from the defaults.
+#[derive(Serialize)]
+struct RetrievalProfileEntry {
+    query_type: QueryType,
+    profile: RetrievalProfile,
+    custom: bool,
+}
+
+/// The retrieval profile of every query type.
+async fn retrieval_profiles_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<Vec<RetrievalProfileEntry>>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| {
+            let profiles = &core.config.retrieval_profiles;
+            QueryType::ALL
+                .into_iter()
+                .map(|query_type| RetrievalProfileEntry {
+                    query_type,
+                    profile: profiles.resolve(query_type, core.config.retrieval),
+                    custom: profiles.get(query_type).is_some(),
+                })
+                .collect()
+        })
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// The query type named in a path ("factual", "Comparative"...).
+fn parse_query_type(name: &str) -> Result<QueryType, (StatusCode, String)> {
+    QueryType::ALL
+        .into_iter()
+        .find(|query_type| format!("{:?}", query_type).eq_ignore_ascii_case(name))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Unknown query type '{}'.", name)))
+}
+
+/// Gives a query type the retrieval profile in the body.
+async fn set_retrieval_profile_handler(
+    State(state): State<Arc<AppState>>,
+    Path(query_type): Path<String>,
+    Json(profile): Json<RetrievalProfile>,
+) -> Result<axum::Json<RetrievalProfile>, (StatusCode, String)> {
+    let query_type = parse_query_type(&query_type)?;
+    if profile.distance_threshold.is_nan() || profile.distance_threshold <= 0.0 || profile.top_k == 0 {
+        return Err((StatusCode::BAD_REQUEST, "distance_threshold and top_k must be positive.".to_string()));
+    }
+    state
+        .agi_core
+        .exec(move |core| core.set_retrieval_profile(query_type, Some(profile)))
+        .await
+        .map(|_| axum::Json(profile))
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string()))
+}
+
+/// Sends a query type back to the default retrieval parameters.
+async fn reset_retrieval_profile_handler(State(state): State<Arc<AppState>>, Path(query_type): Path<String>) -> Result<StatusCode, (StatusCode, String)> {
+    let query_type = parse_query_type(&query_type)?;
+    match state.agi_core.exec(move |core| core.set_retrieval_profile(query_type, None)).await {
+        Ok(Some(_)) => Ok(StatusCode::NO_CONTENT),
+        Ok(None) => Err((StatusCode::NOT_FOUND, "This query type has no profile of its own.".to_string())),
+        Err(_) => Err((StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string())),
+    }
+}
+
+/// Adds the profile in the body (replacing the one with the same name) and makes it active.
+async fn update_personality_handler(
+    State(state): State<Arc<AppState>>,
+    Json(profile): Json<PersonalityProfile>,
+) -> Result<axum::Json<PersonalityProfile>, StatusCode> {
+    state
+        .agi_core
+        .exec(move |core| core.set_personality_profile(profile))
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Switches to one of the loaded personality profiles.
+async fn activate_personality_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<axum::Json<PersonalityProfile>, (StatusCode, String)> {
+    match state.agi_core.exec(move |core| core.activate_personality(&name)).await {
+        Ok(Ok(profile)) => Ok(axum::Json(profile)),
+        Ok(Err(e @ PersonalityError::UnknownProfile(_))) => Err((StatusCode::NOT_FOUND, e.to_string())),
+        Ok(Err(e)) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(_) => Err((StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string())),
+    }
+}
+
+/// The ratings received so far, with their counts.
+async fn feedback_report_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<FeedbackReport>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| FeedbackReport { summary: core.feedback_log().summary(), records: core.feedback_log().records().to_vec() })
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Teaches that `child` is a kind of `parent`.
+async fn relationship_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RelationshipRequest>,
+) -> Result<axum::Json<RelationshipResponse>, (StatusCode, String)> {
+    if payload.child.trim().is_empty() || payload.parent.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Both child and parent are required.".to_string()));
+    }
+    match state.agi_core.exec(move |core| core.teach_relationship(payload.child.trim(), payload.parent.trim())).await {
+        Ok(Ok((linked, outcome))) => Ok(axum::Json(RelationshipResponse { linked, outcome })),
+        Ok(Err(e)) => Err((StatusCode::CONFLICT, e.to_string())),
+        Err(_) => Err((StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string())),
+    }
+}
+
+/// Downloads the concept hierarchy as a graph file (`?format=graphml`, the default, or `dot`).
+async fn hierarchy_export_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<GraphParams>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let format = params.format.unwrap_or(GraphFormat::GraphMl);
+    let graph = state
+        .agi_core
+        .exec(move |core| {
+            let mut graph = Vec::new();
+            core.export_hierarchy(&mut graph, format).map(|_| graph)
+        })
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let content_type = match format {
+        GraphFormat::GraphMl => "application/graphml+xml",
+        GraphFormat::Dot => "text/vnd.graphviz",
+    };
+    let disposition = format!("attachment; filename=\"concepts.{}\"", format.extension());
+    Ok(([(header::CONTENT_TYPE, content_type.to_string()), (header::CONTENT_DISPOSITION, disposition)], graph))
+}
+
+/// Merges `absorb` into `keep`; 404 if either concept is unknown or they are the same.
+async fn merge_concepts_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<MergeRequest>,
+) -> Result<axum::Json<ConceptView>, StatusCode> {
+    let result = state
+        .agi_core
+        .exec(move |core| {
+            if !core.merge_concepts(payload.keep.trim(), payload.absorb.trim()) {
+                return None;
+            }
+            core.conceptual_hierarchy.concept_view(payload.keep.trim())
+        })
+        .await;
+    match result {
+        Ok(Some(view)) => Ok(axum::Json(view)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::SERVICE_UNAVAILABLE),
+    }
+}
+
+/// Splits a concept into the concepts of `into`, each taking the related concepts and memories
+/// that mention one of its keywords.
+async fn split_concept_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SplitRequest>,
+) -> Result<axum::Json<Vec<ConceptRef>>, (StatusCode, String)> {
+    if payload.into.is_empty() || payload.into.iter().any(|criterion| criterion.name.trim().is_empty()) {
+        return Err((StatusCode::BAD_REQUEST, "Name at least one new concept.".to_string()));
+    }
+    let result = state
+        .agi_core
+        .exec(move |core| {
+            let new_ids = core.split_concept_by(payload.concept.trim(), &payload.into)?;
+            Some(
+                new_ids
+                    .into_iter()
+                    .filter_map(|id| core.conceptual_hierarchy.get_concept(id).map(|node| ConceptRef { id, name: node.name.clone() }))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .await;
+    match result {
+        Ok(Some(concepts)) => Ok(axum::Json(concepts)),
+        Ok(None) => Err((StatusCode::NOT_FOUND, "Unknown concept.".to_string())),
+        Err(_) => Err((StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string())),
+    }
+}
+
+/// Merges the concepts that only differ in the spelling of their name (see
+/// `ConceptualHierarchy::duplicate_concepts`).
+async fn cleanup_concepts_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<Vec<ConceptMerge>>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| core.merge_duplicate_concepts())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// The inconsistencies of the concept hierarchy (see `ConceptualHierarchy::validate`); empty
+/// when it is sound.
+async fn hierarchy_issues_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<Vec<HierarchyIssue>>, StatusCode> {
+    state
+        .agi_core
+        .exec(|core| core.conceptual_hierarchy.validate())
+        .await
+        .map(axum::Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Scans a URL, a local file or a Wikipedia article (`wikipedia:fr:Quasar`) with the
+/// KnowledgeScanner and learns its signature, or the sentences of the article. Files and hosts
+/// are confined by the source policy (see `sources`).
+async fn learn_source_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<LearnSourceRequest>,
+) -> Result<axum::Json<LearningOutcome>, (StatusCode, String)> {
+    let source = state.sources.check(DataSource::parse(&payload.source)).map_err(|e| (StatusCode::FORBIDDEN, e))?;
+    scan_and_learn(&state, source).await.map(axum::Json)
+}
+
+/// The scan of a source asked for during onboarding, once an administrator confirmed it.
+#[derive(Serialize)]
+struct ConfirmedScan {
+    source: String,
+    outcome: Option<LearningOutcome>,
+    error: Option<String>,
+}
+
+/// Scans and learns the sources users asked for while onboarding an unknown concept. A prompt
+/// only queues them, so that nothing a reader types makes the server fetch or learn anything.
+async fn confirm_scans_handler(State(state): State<Arc<AppState>>) -> Result<axum::Json<Vec<ConfirmedScan>>, (StatusCode, String)> {
+    let pending_scans = state
+        .agi_core
+        .exec(|core| core.take_pending_scans())
+        .await
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string()))?;
+    let mut confirmed = Vec::with_capacity(pending_scans.len());
+    for source in pending_scans {
+        let location = source.location();
+        let (outcome, error) = match scan_and_learn(&state, source).await {
+            Ok(outcome) => (Some(outcome), None),
+            Err((_, e)) => (None, Some(e)),
+        };
+        confirmed.push(ConfirmedScan { source: location, outcome, error });
+    }
+    Ok(axum::Json(confirmed))
+}
+
+/// The scan runs outside the core actor, so a slow source never stalls other requests.
+async fn scan_and_learn(state: &AppState, source: DataSource) -> Result<LearningOutcome, (StatusCode, String)> {
+    println!("Scanning knowledge source: {:?}", source);
+    let sentences = state
+        .scanner
+        .scan_sentences(&source, KnowledgeScanner::DEFAULT_FRAGMENTS, KnowledgeScanner::DEFAULT_FRAGMENT_SIZE)
+        .await
+        .map_err(|e| {
+            let status = match e {
+                ScannerError::Network(_) => StatusCode::BAD_GATEWAY,
+                ScannerError::ArticleNotFound(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::BAD_REQUEST,
+            };
+            (status, e.to_string())
+        })?;
+    state
+        .agi_core
+        .exec(move |core| core.learn_sentences(&sentences, false, SourceTag::Web))
+        .await
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "The core is not running.".to_string()))
+}
+
+/// Polls the feeds as they fall due. Entries kept by the core are marked as seen; the others
+/// stay candidates until the conversation makes them interesting.
+async fn watch_feeds(agi_core: AsyncCore, config: FeedWatcherConfig) {
+    let mut watcher = FeedWatcher::new(config.feeds);
+    while let Some(due) = watcher.next_due() {
+        tokio::time::sleep_until(due.into()).await;
+        let entries = watcher.poll_due().await;
+        if entries.is_empty() {
+            continue;
+        }
+        let (min_interest, max_entries) = (config.min_interest, config.max_entries_per_poll);
+        let learned = agi_core
+            .exec(move |core| {
+                let selected = core.select_feed_entries(&entries, min_interest, max_entries);
+                let outcome = core.learn_feed_entries(&selected);
+                (selected, outcome)
+            })
+            .await;
+        match learned {
+            Ok((selected, outcome)) => {
+                println!("FeedWatcher: learned {} entries, {} new memories.", selected.len(), outcome.memories_created);
+                watcher.mark_seen(selected.iter().map(|entry| entry.id.as_str()));
+            }
+            Err(_) => break,
+        }
+    }
+}
+
+/// Applies the changes of the knowledge files as they are saved. The watcher blocks, so it runs
+/// on its own thread; the lines it reports are learned on the core actor.
+#[cfg(feature = "hot_reload")]
+fn watch_knowledge_files(agi_core: CoreHandle, project_root: &std::path::Path) {
+    use agi_core::knowledge_watcher::{KnowledgeFiles, KnowledgeWatcher};
+    const DEBOUNCE: Duration = Duration::from_millis(500);
+
+    // Same axiom flags as the boot sequence: the corpus and knowledge.txt are axioms.
+    let mut files = KnowledgeFiles::new();
+    files.watch_file(project_root.join("knowledge.txt"), true);
+    files.watch_file(project_root.join("identity.txt"), false);
+    files.watch_directory(project_root.join("corpus_fondamental"), true);
+    let mut watcher = match KnowledgeWatcher::new(files) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("ERROR: Could not watch the knowledge files: {}", e);
+            return;
+        }
+    };
+    println!("--- Watching the knowledge files for changes ---");
+    std::thread::spawn(move || {
+        while let Some(deltas) = watcher.next_deltas(DEBOUNCE) {
+            let applied = agi_core.exec_blocking(move |core| {
+                for delta in &deltas {
+                    core.apply_knowledge_delta(delta);
+                }
+            });
+            if applied.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Explores the open knowledge gaps, one per period. The sources are scanned outside the core
+/// actor; what they yield is learned and checked against the question of the gap.
+async fn explore_knowledge_gaps(agi_core: AsyncCore) {
+    const EXPLORATION_INTERVAL: Duration = Duration::from_secs(60);
+    let scanner = KnowledgeScanner::new();
+    loop {
+        tokio::time::sleep(EXPLORATION_INTERVAL).await;
+        let goal = match agi_core.exec(|core| core.next_exploration_goal()).await {
+            Ok(Some(goal)) => goal,
+            Ok(None) => continue,
+            Err(_) => break,
+        };
+        let mut sentences = Vec::new();
+        for source in &goal.sources {
+            match scanner.scan_sentences(source, KnowledgeScanner::DEFAULT_FRAGMENTS, KnowledgeScanner::DEFAULT_FRAGMENT_SIZE).await {
+                Ok(found) if !found.is_empty() => {
+                    sentences = found;
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Curiosity: could not scan {}: {}", source.location(), e),
+            }
+        }
+        let gap_id = goal.gap_id;
+        if agi_core.exec(move |core| core.complete_exploration(gap_id, &sentences)).await.is_err() {
+            break;
+        }
+    }
 }
 
 #[axum::debug_handler]
@@ -192,27 +1291,79 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>) {
 #[axum::debug_handler]
 async fn prompt_handler(
     State(state): State<Arc<AppState>>,
+    caller: Option<Extension<Caller>>,
     Json(payload): Json<Query>,
 ) -> axum::Json<PromptResponse> {
     let prompt = payload.prompt;
     println!("Received prompt: {}", prompt);
+    let asked = prompt.clone();
+    let reader = caller.is_some_and(|Extension(caller)| caller.role == Role::Reader);
 
-    // Lock the AGI core to process the prompt
-    let mut agi_core_guard = state.agi_core.lock().unwrap();
-    
-    // Get the response from the AGI core
-    let response_tuple = agi_core_guard.get_response_for_prompt(&prompt);
-
-    // Drop the guard as soon as we're done with the core
-    drop(agi_core_guard);
+    // Queue the prompt on the core actor and wait for its answer without holding any lock.
+    // The ID is read in the same task, so that another prompt cannot slip in between.
+    let response_tuple = state
+        .agi_core
+        .exec(move |core| {
+            let answer = if reader { core.get_response_for_reader(&prompt) } else { core.get_response_for_prompt(&prompt) };
+            answer.map(|(response, _)| (response, core.last_response_id()))
+        })
+        .await
+        .ok()
+        .flatten();
 
-    if let Some((response, _query_type)) = response_tuple {
+    if let Some((response, response_id)) = response_tuple {
+        let _ = state.answers.send(AnsweredPrompt { prompt: asked, response: response.clone(), response_id });
         axum::Json(PromptResponse {
             response,
+            response_id,
         })
     } else {
         axum::Json(PromptResponse {
             response: "The AGI did not produce a response for this prompt.".to_string(),
+            response_id: None,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn test_state(sources: SourcePolicy) -> Arc<AppState> {
+        let handle = CoreHandle::spawn(Core::new_for_tests(), None).expect("the core actor");
+        Arc::new(AppState {
+            agi_core: AsyncCore::new(handle),
+            scanner: KnowledgeScanner::new(),
+            sources,
+            perf_monitor: Arc::new(Mutex::new(PerformanceMonitor::new())),
+            metrics_tx: broadcast::channel(1).0,
+            throttle: Arc::new(Throttle::new(ThrottleConfig::default())),
+            api_keys: Arc::new(ApiKeys::load(std::path::Path::new("no_api_keys.txt")).unwrap()),
+            shutdown: Arc::new(Notify::new()),
+            answers: broadcast::channel(1).0,
+            awakening: Arc::new(Mutex::new(None)),
+            cancel_awakening: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_learn_source_reads_only_the_knowledge_directory() {
+        let root = std::env::temp_dir().join(format!("neurova_learn_source_{}", std::process::id()));
+        let knowledge_dir = root.join("documents");
+        fs::create_dir_all(&knowledge_dir).unwrap();
+        fs::write(knowledge_dir.join("quasars.txt"), "Un quasar est le noyau très lumineux d'une galaxie lointaine.").unwrap();
+        fs::write(root.join("secret.txt"), "Le mot de passe est hunter2.").unwrap();
+        let state = test_state(SourcePolicy::new(&knowledge_dir, &["wikipedia.org"]));
+        let learn = |source: &str| learn_source_handler(State(Arc::clone(&state)), Json(LearnSourceRequest { source: source.to_string() }));
+
+        let learned = learn("quasars.txt").await.expect("a file of the knowledge directory");
+        assert!(learned.memories_created > 0);
+        for source in ["../secret.txt", "/etc/passwd", "https://169.254.169.254/latest/meta-data", "http://fr.wikipedia.org/wiki/Quasar"] {
+            assert_eq!(learn(source).await.map(|_| ()).unwrap_err().0, StatusCode::FORBIDDEN, "{}", source);
+        }
+        let memories = state.agi_core.exec(|core| core.hippocampus.holographic_memory.len()).await.unwrap();
+        assert_eq!(memories, learned.memories_created, "Nothing was learned from the refused sources");
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
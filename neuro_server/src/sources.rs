@@ -0,0 +1,108 @@
+//! sources.rs - What /api/learn_source may scan.
+//!
+//! Even an admin key should not turn the server into a file reader or a web proxy. Local files
+//! are read only from the knowledge directory (`NEUROVA_KNOWLEDGE_DIR`, `documents/` at the
+//! workspace root by default), after their path is resolved, so that neither `..` nor a
+//! symbolic link leads out of it. URLs must be https and on one of the allowed hosts
+//! (`NEUROVA_SOURCE_HOSTS`, comma-separated, `wikipedia.org` by default) or their subdomains.
+//! Wikipedia articles named as `wikipedia:Titre` are always allowed: the scanner builds their URL.
+
+use agi_core::knowledge_scanner::DataSource;
+use std::env;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_HOSTS: [&str; 1] = ["wikipedia.org"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourcePolicy {
+    /// The resolved knowledge directory; None when it does not exist, and no file may be read.
+    knowledge_dir: Option<PathBuf>,
+    allowed_hosts: Vec<String>,
+}
+
+impl SourcePolicy {
+    pub fn new(knowledge_dir: &Path, allowed_hosts: &[&str]) -> Self {
+        Self {
+            knowledge_dir: knowledge_dir.canonicalize().ok(),
+            allowed_hosts: allowed_hosts.iter().map(|host| host.trim().to_lowercase()).filter(|host| !host.is_empty()).collect(),
+        }
+    }
+
+    /// The policy of `NEUROVA_KNOWLEDGE_DIR` and `NEUROVA_SOURCE_HOSTS`, or their defaults.
+    pub fn from_env(project_root: &Path) -> Self {
+        let knowledge_dir = env::var("NEUROVA_KNOWLEDGE_DIR").map_or_else(|_| project_root.join("documents"), PathBuf::from);
+        let hosts = env::var("NEUROVA_SOURCE_HOSTS").ok();
+        let hosts: Vec<&str> = hosts.as_deref().map_or_else(|| DEFAULT_HOSTS.to_vec(), |hosts| hosts.split(',').collect());
+        let policy = Self::new(&knowledge_dir, &hosts);
+        if policy.knowledge_dir.is_none() {
+            eprintln!("Warning: The knowledge directory {:?} does not exist; no local file can be learned.", knowledge_dir);
+        }
+        policy
+    }
+
+    /// The source to scan, with a local path resolved inside the knowledge directory, or why it
+    /// may not be scanned.
+    pub fn check(&self, source: DataSource) -> Result<DataSource, String> {
+        match source {
+            DataSource::Wikipedia { .. } => Ok(source),
+            DataSource::Http { url } => {
+                let host = https_host(&url).ok_or_else(|| format!("Only https URLs may be learned: {}", url))?;
+                if self.allowed_hosts.iter().any(|allowed| host == *allowed || host.ends_with(&format!(".{}", allowed))) {
+                    Ok(DataSource::Http { url })
+                } else {
+                    Err(format!("The host {} is not among the allowed sources.", host))
+                }
+            }
+            DataSource::LocalFile { path } => {
+                let directory = self.knowledge_dir.as_ref().ok_or_else(|| "No knowledge directory is configured.".to_string())?;
+                // A relative path is read in the knowledge directory; an absolute one must lead into it.
+                let resolved = directory.join(&path).canonicalize().map_err(|_| format!("No such file in the knowledge directory: {}", path))?;
+                if !resolved.starts_with(directory) {
+                    return Err(format!("{} is outside of the knowledge directory.", path));
+                }
+                Ok(DataSource::LocalFile { path: resolved.to_string_lossy().into_owned() })
+            }
+        }
+    }
+}
+
+/// The lowercased host of an https URL; None for another scheme, or a URL with credentials or
+/// a port, which could disguise the host.
+fn https_host(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("https://")?;
+    let host = rest.split(['/', '?', '#']).next()?.to_lowercase();
+    (!host.is_empty() && !host.contains(['@', ':'])).then_some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_sources_are_confined() {
+        let root = env::temp_dir().join(format!("neurova_sources_{}", std::process::id()));
+        let knowledge_dir = root.join("documents");
+        fs::create_dir_all(&knowledge_dir).unwrap();
+        fs::write(knowledge_dir.join("quasars.txt"), "Un quasar est un noyau de galaxie.").unwrap();
+        fs::write(root.join("secret.txt"), "api keys").unwrap();
+        let policy = SourcePolicy::new(&knowledge_dir, &["wikipedia.org"]);
+        let check = |location: &str| policy.check(DataSource::parse(location));
+
+        let inside = knowledge_dir.canonicalize().unwrap().join("quasars.txt").to_string_lossy().into_owned();
+        assert!(matches!(check("quasars.txt"), Ok(DataSource::LocalFile { path }) if path == inside));
+        assert!(matches!(check(&inside), Ok(DataSource::LocalFile { path }) if path == inside));
+        assert!(check("../secret.txt").is_err());
+        assert!(check(&root.join("secret.txt").to_string_lossy()).is_err());
+        assert!(check("/etc/passwd").is_err());
+        assert!(check("missing.txt").is_err());
+
+        assert!(check("https://fr.wikipedia.org/wiki/Quasar").is_ok());
+        assert!(check("wikipedia:Quasar").is_ok());
+        assert!(check("http://fr.wikipedia.org/wiki/Quasar").is_err());
+        assert!(check("https://169.254.169.254/latest/meta-data").is_err());
+        assert!(check("https://wikipedia.org.evil.com/").is_err());
+        assert!(check("https://evil.com@fr.wikipedia.org/").is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
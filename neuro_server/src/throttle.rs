@@ -0,0 +1,304 @@
+//! throttle.rs - Admission control in front of the core.
+//!
+//! Every request goes through the core actor one at a time, so a burst of prompts used to pile
+//! up behind it with no bound on the latency. The throttle middleware admits at most
+//! `max_concurrent` requests at a time and queues up to `max_queued` more; beyond that, or when
+//! a client (keyed by the caller `auth` authenticated, or by its IP address) has used up its
+//! token bucket, the request is answered with a 429 and a `Retry-After` header. The health and
+//! metrics endpoints are never throttled. The gRPC service goes through the same `Throttle`
+//! (see `Throttle::admit`), so neither interface is a way around the other's limits.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Write;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use agi_core::error::LockResultExt;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::auth::Caller;
+
+/// Paths served without admission control: probes and scrapes must work under load.
+const UNTHROTTLED: [&str; 3] = ["/api/status", "/metrics", "/ws/metrics"];
+/// Clients tracked before the idle buckets are forgotten.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThrottleConfig {
+    /// Requests handled at the same time.
+    pub max_concurrent: usize,
+    /// Requests waiting for a slot before new ones are turned away.
+    pub max_queued: usize,
+    /// How long a queued request waits for a slot before it is turned away.
+    pub queue_timeout: Duration,
+    /// Requests a client may send per second, on average.
+    pub rate_per_second: f64,
+    /// Requests a client may send in a burst.
+    pub burst: f64,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self { max_concurrent: 4, max_queued: 32, queue_timeout: Duration::from_secs(30), rate_per_second: 5.0, burst: 20.0 }
+    }
+}
+
+impl ThrottleConfig {
+    /// The defaults, overridden by `NEUROVA_MAX_CONCURRENT`, `NEUROVA_MAX_QUEUED`,
+    /// `NEUROVA_QUEUE_TIMEOUT_SECS`, `NEUROVA_RATE_LIMIT` (requests per second) and
+    /// `NEUROVA_RATE_BURST`. Invalid values are ignored with a warning.
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            let value = env::var(name).ok()?;
+            let parsed = value.parse().ok();
+            if parsed.is_none() {
+                eprintln!("Warning: Ignoring {}={:?}, not a valid number.", name, value);
+            }
+            parsed
+        }
+        let defaults = Self::default();
+        Self {
+            max_concurrent: var("NEUROVA_MAX_CONCURRENT").filter(|max| *max > 0).unwrap_or(defaults.max_concurrent),
+            max_queued: var("NEUROVA_MAX_QUEUED").unwrap_or(defaults.max_queued),
+            queue_timeout: var("NEUROVA_QUEUE_TIMEOUT_SECS").map_or(defaults.queue_timeout, Duration::from_secs),
+            rate_per_second: var("NEUROVA_RATE_LIMIT").filter(|rate: &f64| *rate > 0.0).unwrap_or(defaults.rate_per_second),
+            burst: var("NEUROVA_RATE_BURST").filter(|burst: &f64| *burst >= 1.0).unwrap_or(defaults.burst),
+        }
+    }
+}
+
+/// Tokens refill at `rate_per_second` up to `burst`; a request takes one.
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, config: &ThrottleConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.rate_per_second).min(config.burst);
+        self.updated = now;
+    }
+
+    /// Takes a token, or tells how long until one is available.
+    fn take(&mut self, config: &ThrottleConfig, now: Instant) -> Result<(), Duration> {
+        self.refill(config, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / config.rate_per_second))
+        }
+    }
+}
+
+/// Why a request was turned away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// The client used up its token bucket; a token is back after the duration.
+    RateLimited(Duration),
+    QueueFull,
+    QueueTimeout,
+}
+
+impl Rejection {
+    pub fn retry_after(&self) -> Duration {
+        match self {
+            Rejection::RateLimited(wait) => *wait,
+            Rejection::QueueFull | Rejection::QueueTimeout => Duration::from_secs(1),
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            Rejection::RateLimited(_) => "Rate limit exceeded.",
+            Rejection::QueueFull => "Too many requests are queued.",
+            Rejection::QueueTimeout => "The request waited too long in the queue.",
+        }
+    }
+}
+
+/// The bucket a request is counted in: its authenticated caller, or its IP address. Never the
+/// key the client sent: without keys configured anything passes authentication, and a new key
+/// per request would get a new bucket.
+pub fn client_key(caller: Option<&Caller>, peer: Option<IpAddr>) -> String {
+    match (caller, peer) {
+        (Some(caller), _) => format!("caller:{}", caller.name),
+        (None, Some(ip)) => format!("ip:{}", ip),
+        (None, None) => "unknown".to_string(),
+    }
+}
+
+pub struct Throttle {
+    config: ThrottleConfig,
+    slots: Arc<Semaphore>,
+    queued: AtomicUsize,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+    rate_limited: AtomicU64,
+    queue_full: AtomicU64,
+    queue_timed_out: AtomicU64,
+}
+
+impl Throttle {
+    pub fn new(config: ThrottleConfig) -> Self {
+        Self {
+            slots: Arc::new(Semaphore::new(config.max_concurrent)),
+            config,
+            queued: AtomicUsize::new(0),
+            buckets: Mutex::new(HashMap::new()),
+            rate_limited: AtomicU64::new(0),
+            queue_full: AtomicU64::new(0),
+            queue_timed_out: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a token from the bucket of `client`. Long-lived streams only go through this: they
+    /// would hold a slot for as long as they are open.
+    pub fn check_rate(&self, client: &str) -> Result<(), Rejection> {
+        self.take_token(client).map_err(|wait| {
+            self.rate_limited.fetch_add(1, Ordering::Relaxed);
+            Rejection::RateLimited(wait)
+        })
+    }
+
+    /// Rate-limits `client`, then waits in the bounded queue for a slot, which is held until the
+    /// permit is dropped.
+    pub async fn admit(&self, client: &str) -> Result<OwnedSemaphorePermit, Rejection> {
+        self.check_rate(client)?;
+        if let Ok(slot) = Arc::clone(&self.slots).try_acquire_owned() {
+            return Ok(slot);
+        }
+        if self.queued.fetch_add(1, Ordering::Relaxed) >= self.config.max_queued {
+            self.queued.fetch_sub(1, Ordering::Relaxed);
+            self.queue_full.fetch_add(1, Ordering::Relaxed);
+            return Err(Rejection::QueueFull);
+        }
+        let waited = tokio::time::timeout(self.config.queue_timeout, Arc::clone(&self.slots).acquire_owned()).await;
+        self.queued.fetch_sub(1, Ordering::Relaxed);
+        match waited {
+            Ok(Ok(slot)) => Ok(slot),
+            _ => {
+                self.queue_timed_out.fetch_add(1, Ordering::Relaxed);
+                Err(Rejection::QueueTimeout)
+            }
+        }
+    }
+
+    fn take_token(&self, client: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().recover();
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(client) {
+            // A full bucket is the state of a client never seen, so forgetting it changes nothing.
+            let config = &self.config;
+            buckets.retain(|_, bucket| {
+                bucket.refill(config, now);
+                bucket.tokens < config.burst
+            });
+        }
+        buckets.entry(client.to_string()).or_insert(TokenBucket { tokens: self.config.burst, updated: now }).take(&self.config, now)
+    }
+
+    /// The queue gauges and rejection counters, in the Prometheus text format.
+    pub fn render_prometheus(&self) -> String {
+        let in_flight = self.config.max_concurrent - self.slots.available_permits();
+        let mut out = String::new();
+        let _ = writeln!(out, "# HELP neurova_http_in_flight Requests being handled.\n# TYPE neurova_http_in_flight gauge\nneurova_http_in_flight {}", in_flight);
+        let _ = writeln!(
+            out,
+            "# HELP neurova_http_queue_depth Requests waiting for a slot.\n# TYPE neurova_http_queue_depth gauge\nneurova_http_queue_depth {}",
+            self.queued.load(Ordering::Relaxed)
+        );
+        out.push_str("# HELP neurova_http_rejected_total Requests turned away, by reason.\n# TYPE neurova_http_rejected_total counter\n");
+        for (reason, counter) in [("rate_limited", &self.rate_limited), ("queue_full", &self.queue_full), ("queue_timeout", &self.queue_timed_out)] {
+            let _ = writeln!(out, "neurova_http_rejected_total{{reason=\"{}\"}} {}", reason, counter.load(Ordering::Relaxed));
+        }
+        out
+    }
+}
+
+/// The middleware: rate limit, then wait in the bounded queue for a slot.
+pub async fn throttle(State(throttle): State<Arc<Throttle>>, ConnectInfo(peer): ConnectInfo<SocketAddr>, request: Request, next: Next) -> Response {
+    if UNTHROTTLED.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+    let client = client_key(request.extensions().get::<Caller>(), Some(peer.ip()));
+    match throttle.admit(&client).await {
+        Ok(slot) => {
+            let response = next.run(request).await;
+            drop(slot);
+            response
+        }
+        Err(rejection) => too_many_requests(rejection),
+    }
+}
+
+fn too_many_requests(rejection: Rejection) -> Response {
+    // Retry-After is in whole seconds; rounding down would invite a retry that fails again.
+    let seconds = rejection.retry_after().as_secs_f64().ceil().max(1.0) as u64;
+    (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, seconds.to_string())], rejection.message()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ThrottleConfig {
+        ThrottleConfig { max_concurrent: 1, max_queued: 1, queue_timeout: Duration::from_millis(20), rate_per_second: 2.0, burst: 2.0 }
+    }
+
+    #[test]
+    fn test_a_bucket_refills_at_the_rate() {
+        let config = config();
+        let start = Instant::now();
+        let mut bucket = TokenBucket { tokens: config.burst, updated: start };
+        assert!(bucket.take(&config, start).is_ok());
+        assert!(bucket.take(&config, start).is_ok());
+        assert_eq!(bucket.take(&config, start), Err(Duration::from_millis(500)));
+        assert!(bucket.take(&config, start + Duration::from_millis(500)).is_ok(), "Half a second gives back one token at 2/s");
+        // Never more than the burst, however long the client was idle.
+        bucket.refill(&config, start + Duration::from_secs(60));
+        assert_eq!(bucket.tokens, config.burst);
+    }
+
+    #[test]
+    fn test_each_client_has_its_own_bucket() {
+        let throttle = Throttle::new(config());
+        let alice = client_key(Some(&Caller { name: "alice".to_string(), role: crate::auth::Role::Reader }), Some([10, 0, 0, 1].into()));
+        let other = client_key(None, Some([10, 0, 0, 1].into()));
+        assert_ne!(alice, other);
+        assert!(throttle.check_rate(&alice).is_ok());
+        assert!(throttle.check_rate(&alice).is_ok());
+        assert!(matches!(throttle.check_rate(&alice), Err(Rejection::RateLimited(_))));
+        assert!(throttle.check_rate(&other).is_ok(), "Another client is not limited by alice");
+        assert!(throttle.render_prometheus().contains("neurova_http_rejected_total{reason=\"rate_limited\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_requests_beyond_the_queue_are_turned_away() {
+        let throttle = Arc::new(Throttle::new(ThrottleConfig { rate_per_second: 100.0, burst: 100.0, ..config() }));
+        let slot = throttle.admit("a").await.unwrap();
+
+        // One request may wait for the slot; it gives up after the queue timeout.
+        let queued = tokio::spawn({
+            let throttle = Arc::clone(&throttle);
+            async move { throttle.admit("b").await.map(drop) }
+        });
+        while throttle.queued.load(Ordering::Relaxed) == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(throttle.admit("c").await.map(drop), Err(Rejection::QueueFull));
+        assert_eq!(queued.await.unwrap(), Err(Rejection::QueueTimeout));
+
+        drop(slot);
+        assert!(throttle.admit("c").await.is_ok(), "The slot is free again");
+    }
+}
@@ -0,0 +1,204 @@
+//! concept_graph.rs - The conceptual hierarchy as a graph to navigate.
+//!
+//! The view is centred on one concept: its parents are drawn above it and its children below,
+//! with the parents' parents and the children's children one row further out. Clicking a concept
+//! selects it (the GUI then shows its trace mandala, parents, children and memories); double-
+//! clicking it re-centres the graph on it. Without a centre, the roots of the hierarchy are shown
+//! in a ring.
+
+use std::collections::{HashMap, HashSet};
+
+use agi_core::conceptual_hierarchy::ConceptualHierarchy;
+use egui::{Align2, Color32, FontId, Pos2, Sense, Stroke, Vec2};
+
+/// Concepts drawn at most per row (or in the ring of roots), the first by name.
+const MAX_PER_ROW: usize = 12;
+const MAX_ROOTS: usize = 24;
+const NODE_RADIUS: f32 = 8.0;
+const CENTER_RADIUS: f32 = 12.0;
+
+/// A concept and its links, as fetched from the core actor.
+#[derive(Debug, Clone)]
+pub struct GraphConcept {
+    pub id: u64,
+    pub name: String,
+    pub parents: Vec<u64>,
+    pub children: Vec<u64>,
+}
+
+impl GraphConcept {
+    /// Every concept of the hierarchy, without its trace.
+    pub fn collect(hierarchy: &ConceptualHierarchy) -> Vec<GraphConcept> {
+        hierarchy
+            .get_all_concepts()
+            .into_iter()
+            .map(|node| GraphConcept {
+                id: node.id,
+                name: node.name.clone(),
+                parents: node.parents.iter().copied().collect(),
+                children: node.children.iter().copied().collect(),
+            })
+            .collect()
+    }
+}
+
+/// What the user did with the graph this frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphAction {
+    Select(u64),
+    Recenter(u64),
+}
+
+#[derive(Debug, Default)]
+pub struct ConceptGraphView {
+    concepts: HashMap<u64, GraphConcept>,
+    center: Option<u64>,
+    pub selected: Option<u64>,
+    /// The drawn concepts, at positions in -1..1 on both axes.
+    placed: Vec<(u64, Vec2)>,
+}
+
+impl ConceptGraphView {
+    /// Replaces the concepts, e.g. after the hierarchy has grown. A centre or selection that no
+    /// longer exists is dropped.
+    pub fn set_concepts(&mut self, concepts: Vec<GraphConcept>) {
+        self.concepts = concepts.into_iter().map(|concept| (concept.id, concept)).collect();
+        if self.center.is_some_and(|id| !self.concepts.contains_key(&id)) {
+            self.center = None;
+        }
+        if self.selected.is_some_and(|id| !self.concepts.contains_key(&id)) {
+            self.selected = None;
+        }
+        self.layout();
+    }
+
+    pub fn center(&self) -> Option<u64> {
+        self.center
+    }
+
+    /// Centres the graph on a concept, or on the roots of the hierarchy for None.
+    pub fn recenter(&mut self, center: Option<u64>) {
+        self.center = center.filter(|id| self.concepts.contains_key(id));
+        self.layout();
+    }
+
+    pub fn concept(&self, id: u64) -> Option<&GraphConcept> {
+        self.concepts.get(&id)
+    }
+
+    /// The names of concepts, sorted, with their IDs.
+    pub fn names(&self, ids: &[u64]) -> Vec<(u64, &str)> {
+        let mut names: Vec<(u64, &str)> = ids.iter().filter_map(|id| self.concepts.get(id).map(|concept| (*id, concept.name.as_str()))).collect();
+        names.sort_by(|a, b| a.1.cmp(b.1));
+        names
+    }
+
+    fn layout(&mut self) {
+        self.placed.clear();
+        let Some(center) = self.center.and_then(|id| self.concepts.get(&id)) else {
+            let roots: Vec<u64> = self.concepts.values().filter(|concept| concept.parents.is_empty()).map(|concept| concept.id).collect();
+            let roots = self.names(&roots).into_iter().take(MAX_ROOTS).map(|(id, _)| id).collect::<Vec<_>>();
+            for (i, id) in roots.iter().enumerate() {
+                let angle = i as f32 / roots.len() as f32 * std::f32::consts::TAU;
+                self.placed.push((*id, Vec2::new(angle.cos(), angle.sin()) * 0.75));
+            }
+            return;
+        };
+        let (center_id, parents, children) = (center.id, center.parents.clone(), center.children.clone());
+        let mut seen: HashSet<u64> = HashSet::from([center_id]);
+        self.placed.push((center_id, Vec2::ZERO));
+
+        let grandparents: Vec<u64> = parents.iter().filter_map(|id| self.concepts.get(id)).flat_map(|parent| parent.parents.clone()).collect();
+        let grandchildren: Vec<u64> = children.iter().filter_map(|id| self.concepts.get(id)).flat_map(|child| child.children.clone()).collect();
+        for (ids, y) in [(parents, -0.45), (grandparents, -0.85), (children, 0.45), (grandchildren, 0.85)] {
+            let row: Vec<u64> = self.names(&ids).into_iter().map(|(id, _)| id).filter(|id| seen.insert(*id)).take(MAX_PER_ROW).collect();
+            for (i, id) in row.iter().enumerate() {
+                let x = (i as f32 + 0.5) / row.len() as f32 * 1.8 - 0.9;
+                self.placed.push((*id, Vec2::new(x, y)));
+            }
+        }
+    }
+
+    /// Draws the graph in the space left in `ui` and reports a click (selection) or a
+    /// double-click (re-centring) on a concept.
+    pub fn show(&self, ui: &mut egui::Ui) -> Option<GraphAction> {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click());
+        let rect = response.rect;
+        let to_screen = |position: Vec2| rect.center() + Vec2::new(position.x * rect.width(), position.y * rect.height()) * 0.45;
+        let positions: HashMap<u64, Pos2> = self.placed.iter().map(|(id, position)| (*id, to_screen(*position))).collect();
+
+        let edge = Stroke::new(1.0, Color32::from_gray(110));
+        for (id, from) in &positions {
+            for parent in self.concepts.get(id).map(|concept| concept.parents.as_slice()).unwrap_or_default() {
+                if let Some(to) = positions.get(parent) {
+                    painter.line_segment([*from, *to], edge);
+                }
+            }
+        }
+
+        let text_color = ui.visuals().text_color();
+        for (id, position) in &positions {
+            let radius = if Some(*id) == self.center { CENTER_RADIUS } else { NODE_RADIUS };
+            let fill = if Some(*id) == self.selected {
+                Color32::from_rgb(80, 200, 230)
+            } else if Some(*id) == self.center {
+                Color32::from_rgb(230, 180, 60)
+            } else {
+                Color32::from_rgb(90, 110, 160)
+            };
+            painter.circle(*position, radius, fill, Stroke::new(1.0, Color32::WHITE));
+            let name = self.concepts.get(id).map_or("", |concept| concept.name.as_str());
+            painter.text(*position + Vec2::new(0.0, radius + 2.0), Align2::CENTER_TOP, name, FontId::proportional(13.0), text_color);
+        }
+        if positions.is_empty() {
+            painter.text(rect.center(), Align2::CENTER_CENTER, "The hierarchy has no concepts yet.", FontId::proportional(14.0), text_color);
+        }
+
+        let pointer = response.interact_pointer_pos()?;
+        let (id, _) = positions
+            .iter()
+            .map(|(id, position)| (*id, position.distance(pointer)))
+            .filter(|(_, distance)| *distance <= CENTER_RADIUS + 4.0)
+            .min_by(|a, b| a.1.total_cmp(&b.1))?;
+        if response.double_clicked() {
+            Some(GraphAction::Recenter(id))
+        } else if response.clicked() {
+            Some(GraphAction::Select(id))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agi_core::holographic_memory::HolographicTrace;
+
+    #[test]
+    fn test_parents_are_placed_above_and_children_below() {
+        let mut hierarchy = ConceptualHierarchy::new();
+        let being = hierarchy.add_concept("être vivant", HolographicTrace::new_empty(16), &[]);
+        let animal = hierarchy.add_concept("animal", HolographicTrace::new_empty(16), &[being]);
+        let dog = hierarchy.add_concept("chien", HolographicTrace::new_empty(16), &[animal]);
+        let poodle = hierarchy.add_concept("caniche", HolographicTrace::new_empty(16), &[dog]);
+        let stone = hierarchy.add_concept("pierre", HolographicTrace::new_empty(16), &[]);
+
+        let mut view = ConceptGraphView::default();
+        view.set_concepts(GraphConcept::collect(&hierarchy));
+        let placed = |view: &ConceptGraphView| view.placed.iter().copied().collect::<HashMap<u64, Vec2>>();
+        let roots = placed(&view);
+        assert_eq!(roots.len(), 2, "Without a centre, the roots are shown");
+        assert!(roots.contains_key(&being) && roots.contains_key(&stone));
+
+        view.recenter(Some(animal));
+        let around = placed(&view);
+        assert_eq!(around[&animal], Vec2::ZERO);
+        assert!(around[&being].y < 0.0 && around[&dog].y > 0.0 && around[&poodle].y > around[&dog].y);
+        assert!(!around.contains_key(&stone));
+
+        view.selected = Some(poodle);
+        view.set_concepts(GraphConcept::collect(&ConceptualHierarchy::new()));
+        assert_eq!((view.center(), view.selected), (None, None), "Concepts that are gone are let go");
+    }
+}
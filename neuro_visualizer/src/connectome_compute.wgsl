@@ -0,0 +1,115 @@
+// Connectome simulation on the GPU: the leaky integrate-and-fire step of
+// `Neuron::update` (without its refractory periods and adaptive threshold),
+// followed by the propagation of the spikes along the synapses.
+// Propagation is pulled by the target neuron over its incoming synapses, so no
+// two invocations ever write the same potential.
+
+struct Params {
+    neuron_count: u32,
+    boost_a: u32,
+    boost_b: u32,
+    boost: f32,
+    stimulus_count: u32,
+    stimulus: f32,
+    sample_stride: u32,
+    sample_count: u32,
+};
+
+struct NeuronState {
+    potential: f32,
+    firing: u32,
+};
+
+struct NeuronConstants {
+    threshold: f32,
+    leak_factor: f32,
+};
+
+struct Synapse {
+    source: u32,
+    weight: f32,
+};
+
+struct Counters {
+    firing: atomic<u32>,
+};
+
+const WORKGROUP_SIZE: u32 = 256u;
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> neurons: array<NeuronState>;
+@group(0) @binding(2) var<storage, read> neuron_constants: array<NeuronConstants>;
+// Incoming synapses of neuron i: synapses[offsets[i]..offsets[i + 1]].
+@group(0) @binding(3) var<storage, read> offsets: array<u32>;
+@group(0) @binding(4) var<storage, read> synapses: array<Synapse>;
+@group(0) @binding(5) var<storage, read_write> counters: Counters;
+@group(0) @binding(6) var<storage, read_write> samples: array<f32>;
+@group(0) @binding(7) var<storage, read> stimulus: array<u32>;
+
+// Dispatches are two-dimensional once a single row of workgroups is not enough.
+fn invocation_index(id: vec3<u32>, groups: vec3<u32>) -> u32 {
+    return id.x + id.y * groups.x * WORKGROUP_SIZE;
+}
+
+@compute @workgroup_size(64)
+fn stimulate(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.stimulus_count) {
+        return;
+    }
+    let i = stimulus[id.x];
+    if (i < params.neuron_count) {
+        neurons[i].potential += params.stimulus;
+    }
+}
+
+@compute @workgroup_size(256)
+fn update(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let i = invocation_index(id, groups);
+    if (i >= params.neuron_count) {
+        return;
+    }
+    var neuron = neurons[i];
+    // Spontaneous activity keeps the network from dying out.
+    if (i == params.boost_a || i == params.boost_b) {
+        neuron.potential += params.boost;
+    }
+    if (neuron.firing == 1u) {
+        neuron.potential = 0.0;
+        neuron.firing = 0u;
+    }
+    if (neuron.potential >= neuron_constants[i].threshold) {
+        neuron.firing = 1u;
+        atomicAdd(&counters.firing, 1u);
+    }
+    if (neuron.potential > 0.0) {
+        neuron.potential *= 1.0 - neuron_constants[i].leak_factor;
+        if (neuron.potential < 1e-6) {
+            neuron.potential = 0.0;
+        }
+    }
+    neurons[i] = neuron;
+}
+
+@compute @workgroup_size(256)
+fn propagate(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let i = invocation_index(id, groups);
+    if (i >= params.neuron_count) {
+        return;
+    }
+    var incoming = 0.0;
+    for (var s = offsets[i]; s < offsets[i + 1u]; s += 1u) {
+        let synapse = synapses[s];
+        if (neurons[synapse.source].firing == 1u) {
+            incoming += synapse.weight;
+        }
+    }
+    neurons[i].potential += incoming;
+}
+
+@compute @workgroup_size(64)
+fn sample_potentials(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.sample_count) {
+        return;
+    }
+    samples[id.x] = neurons[id.x * params.sample_stride].potential;
+}
@@ -0,0 +1,591 @@
+//! connectome_graph.rs - The connectome as a 3D graph, for structural inspection.
+//!
+//! Neurons are drawn as instanced spheres (blue when excitatory, magenta when inhibitory) and
+//! synapses as lines whose opacity follows their weight; the neurons the core fires light up and
+//! fade out over the next frames. Small connectomes are laid out by a force-directed simulation,
+//! so that densely connected neurons gather; larger ones keep a precomputed layout on a sphere,
+//! where neurons of neighbouring IDs sit side by side. Past `MAX_DRAWN_NEURONS`, an evenly spread
+//! sample of the neurons is drawn, with the strongest synapses between them.
+
+use agi_core::connectome::Connectome;
+use agi_core::neuron::NeuronKind;
+use wgpu::util::DeviceExt;
+
+/// Neurons drawn at most; larger connectomes are sampled.
+const MAX_DRAWN_NEURONS: usize = 20_000;
+/// Synapses drawn at most, the strongest first.
+const MAX_DRAWN_SYNAPSES: usize = 60_000;
+/// Connectomes up to this size are laid out by the force-directed simulation.
+const FORCE_LAYOUT_MAX_NEURONS: usize = 1_000;
+const FORCE_LAYOUT_ITERATIONS: usize = 120;
+/// Share of its highlight a neuron keeps from one frame to the next.
+const ACTIVITY_DECAY: f32 = 0.9;
+const SPHERE_STACKS: u16 = 8;
+const SPHERE_SLICES: u16 = 12;
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+const SPHERE_ATTRIBUTES: [wgpu::VertexAttribute; 1] = wgpu::vertex_attr_array![0 => Float32x3];
+const INSTANCE_ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![1 => Float32x3, 2 => Float32];
+const ACTIVITY_ATTRIBUTES: [wgpu::VertexAttribute; 1] = wgpu::vertex_attr_array![3 => Float32];
+const LINE_ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32];
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct NeuronInstance {
+    position: [f32; 3],
+    inhibitory: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct LineVertex {
+    position: [f32; 3],
+    alpha: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct CameraUniform {
+    view_proj: [[f32; 4]; 4],
+    neuron_radius: f32,
+    _padding: [f32; 3],
+}
+
+/// The drawn neurons, placed in a ball of radius 1, and the synapses between them.
+pub struct GraphLayout {
+    /// Every `stride`-th neuron of the connectome is drawn.
+    stride: usize,
+    positions: Vec<[f32; 3]>,
+    inhibitory: Vec<bool>,
+    /// Synapses as (source, target, weight), by index in `positions`.
+    edges: Vec<(u32, u32, f32)>,
+}
+
+impl GraphLayout {
+    /// Samples the connectome and places its neurons on a sphere (see `relax` for the
+    /// force-directed layout).
+    pub fn new(connectome: &Connectome) -> Self {
+        let neuron_count = connectome.neurons.len();
+        let stride = neuron_count.div_ceil(MAX_DRAWN_NEURONS).max(1);
+        let drawn = neuron_count.div_ceil(stride);
+        let positions = (0..drawn).map(|i| fibonacci_sphere_point(i, drawn)).collect();
+        let inhibitory = connectome.neurons.iter().step_by(stride).map(|neuron| neuron.kind == NeuronKind::Inhibitory).collect();
+
+        let mut edges: Vec<(u32, u32, f32)> = connectome
+            .synapses
+            .iter()
+            .filter(|synapse| (synapse.from as usize).is_multiple_of(stride) && (synapse.to as usize).is_multiple_of(stride))
+            .filter(|synapse| (synapse.from as usize) < neuron_count && (synapse.to as usize) < neuron_count && synapse.from != synapse.to)
+            .map(|synapse| ((synapse.from as usize / stride) as u32, (synapse.to as usize / stride) as u32, synapse.weight))
+            .collect();
+        if edges.len() > MAX_DRAWN_SYNAPSES {
+            edges.sort_by(|a, b| b.2.abs().partial_cmp(&a.2.abs()).unwrap_or(std::cmp::Ordering::Equal));
+            edges.truncate(MAX_DRAWN_SYNAPSES);
+        }
+        Self { stride, positions, inhibitory, edges }
+    }
+
+    pub fn neuron_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn synapse_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Runs the force-directed layout (Fruchterman-Reingold): every pair of neurons repels, every
+    /// synapse pulls its neurons together in proportion to its weight. Larger connectomes than
+    /// `FORCE_LAYOUT_MAX_NEURONS` keep their sphere, the simulation being quadratic.
+    pub fn relax(&mut self) {
+        let n = self.positions.len();
+        if !(2..=FORCE_LAYOUT_MAX_NEURONS).contains(&n) {
+            return;
+        }
+        // The ideal distance between neurons filling a unit volume.
+        let k = (1.0 / n as f32).cbrt();
+        let mut temperature = 0.1;
+        for _ in 0..FORCE_LAYOUT_ITERATIONS {
+            let mut displacements = vec![[0.0f32; 3]; n];
+            for i in 0..n {
+                for j in i + 1..n {
+                    let delta = sub(self.positions[i], self.positions[j]);
+                    let distance = length(delta).max(1e-3);
+                    let push = scale(delta, k * k / (distance * distance));
+                    displacements[i] = add(displacements[i], push);
+                    displacements[j] = sub(displacements[j], push);
+                }
+            }
+            for &(source, target, weight) in &self.edges {
+                let (source, target) = (source as usize, target as usize);
+                let delta = sub(self.positions[source], self.positions[target]);
+                let pull = scale(delta, length(delta) / k * weight.abs().min(1.0));
+                displacements[source] = sub(displacements[source], pull);
+                displacements[target] = add(displacements[target], pull);
+            }
+            for (position, displacement) in self.positions.iter_mut().zip(&displacements) {
+                let distance = length(*displacement);
+                if distance > 0.0 {
+                    *position = add(*position, scale(*displacement, distance.min(temperature) / distance));
+                }
+            }
+            temperature *= 0.97;
+        }
+
+        // Back into the unit ball, centered.
+        let centroid = scale(self.positions.iter().fold([0.0; 3], |sum, position| add(sum, *position)), 1.0 / n as f32);
+        let radius = self.positions.iter().map(|position| length(sub(*position, centroid))).fold(0.0f32, f32::max).max(1e-3);
+        for position in &mut self.positions {
+            *position = scale(sub(*position, centroid), 1.0 / radius);
+        }
+    }
+
+    /// The radius of a neuron's sphere, so that dense layouts stay readable.
+    fn neuron_radius(&self) -> f32 {
+        (0.6 / (self.positions.len().max(1) as f32).cbrt()).clamp(0.004, 0.05)
+    }
+}
+
+/// The `i`-th of `n` points spread evenly over the unit sphere along a golden-angle spiral.
+fn fibonacci_sphere_point(i: usize, n: usize) -> [f32; 3] {
+    const GOLDEN_ANGLE: f32 = 2.399_963;
+    let y = 1.0 - 2.0 * (i as f32 + 0.5) / n as f32;
+    let ring = (1.0 - y * y).max(0.0).sqrt();
+    let angle = i as f32 * GOLDEN_ANGLE;
+    [ring * angle.cos(), y, ring * angle.sin()]
+}
+
+/// A camera orbiting the center of the graph.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitCamera {
+    /// Rotation around the vertical axis, in radians.
+    pub yaw: f32,
+    /// Elevation above the horizontal plane, in radians.
+    pub pitch: f32,
+    pub distance: f32,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self { yaw: 0.0, pitch: 0.3, distance: 3.0 }
+    }
+}
+
+impl OrbitCamera {
+    const MIN_DISTANCE: f32 = 0.2;
+    const MAX_DISTANCE: f32 = 12.0;
+    const MAX_PITCH: f32 = 1.5;
+
+    /// Turns the camera by a mouse drag, in pixels.
+    pub fn orbit(&mut self, dx: f32, dy: f32) {
+        const RADIANS_PER_PIXEL: f32 = 0.008;
+        self.yaw -= dx * RADIANS_PER_PIXEL;
+        self.pitch = (self.pitch + dy * RADIANS_PER_PIXEL).clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
+    }
+
+    /// Moves the camera closer (positive `scroll`, in points) or further.
+    pub fn zoom(&mut self, scroll: f32) {
+        self.distance = (self.distance * (-scroll * 0.002).exp()).clamp(Self::MIN_DISTANCE, Self::MAX_DISTANCE);
+    }
+
+    fn view_proj(&self, aspect: f32) -> [[f32; 4]; 4] {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let eye = scale([cos_pitch * sin_yaw, sin_pitch, cos_pitch * cos_yaw], self.distance);
+        mat_mul(perspective(std::f32::consts::FRAC_PI_4, aspect, 0.01, 100.0), look_at(eye, [0.0; 3], [0.0, 1.0, 0.0]))
+    }
+}
+
+pub struct ConnectomeGraph {
+    stride: usize,
+    neuron_count: u32,
+    synapse_count: usize,
+    line_vertex_count: u32,
+    sphere_index_count: u32,
+    neuron_radius: f32,
+    /// The highlight of each drawn neuron: 1 when it just fired, fading afterwards.
+    activity: Vec<f32>,
+    sphere_vertex_buffer: wgpu::Buffer,
+    sphere_index_buffer: wgpu::Buffer,
+    instance_buffer: wgpu::Buffer,
+    activity_buffer: wgpu::Buffer,
+    line_buffer: wgpu::Buffer,
+    camera_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    neuron_pipeline: wgpu::RenderPipeline,
+    synapse_pipeline: wgpu::RenderPipeline,
+    depth_view: wgpu::TextureView,
+}
+
+impl ConnectomeGraph {
+    /// Uploads the layout; None if the connectome has no neurons.
+    pub fn new(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, layout: &GraphLayout) -> Option<Self> {
+        if layout.positions.is_empty() {
+            return None;
+        }
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Connectome Graph Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("connectome_graph.wgsl").into()),
+        });
+
+        let (sphere_vertices, sphere_indices) = sphere_mesh();
+        let sphere_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Neuron Sphere Vertex Buffer"),
+            contents: bytemuck::cast_slice(&sphere_vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let sphere_index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Neuron Sphere Index Buffer"),
+            contents: bytemuck::cast_slice(&sphere_indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        let instances: Vec<NeuronInstance> = layout
+            .positions
+            .iter()
+            .zip(&layout.inhibitory)
+            .map(|(position, inhibitory)| NeuronInstance { position: *position, inhibitory: if *inhibitory { 1.0 } else { 0.0 } })
+            .collect();
+        let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Neuron Instance Buffer"),
+            contents: bytemuck::cast_slice(&instances),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let activity = vec![0.0f32; layout.positions.len()];
+        let activity_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Neuron Activity Buffer"),
+            contents: bytemuck::cast_slice(&activity),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // The strongest synapse is opaque; the others fade with their weight.
+        let max_weight = layout.edges.iter().map(|edge| edge.2.abs()).fold(0.0f32, f32::max).max(1e-6);
+        let mut lines: Vec<LineVertex> = layout
+            .edges
+            .iter()
+            .flat_map(|&(source, target, weight)| {
+                let alpha = 0.05 + 0.6 * weight.abs() / max_weight;
+                [
+                    LineVertex { position: layout.positions[source as usize], alpha },
+                    LineVertex { position: layout.positions[target as usize], alpha },
+                ]
+            })
+            .collect();
+        let line_vertex_count = lines.len() as u32;
+        // A buffer cannot be empty: a connectome without synapses still gets one (unused) line.
+        if lines.is_empty() {
+            lines.resize(2, LineVertex { position: [0.0; 3], alpha: 0.0 });
+        }
+        let line_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Synapse Line Buffer"),
+            contents: bytemuck::cast_slice(&lines),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Connectome Graph Camera Buffer"),
+            size: std::mem::size_of::<CameraUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry { // Camera
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("connectome_graph_bind_group_layout"),
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: camera_buffer.as_entire_binding() }],
+            label: Some("connectome_graph_bind_group"),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Connectome Graph Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = |label: &'static str, vertex_entry: &'static str, fragment_entry: &'static str, buffers: &[wgpu::VertexBufferLayout], topology: wgpu::PrimitiveTopology, depth_write_enabled: bool| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState { module: &shader, entry_point: vertex_entry, buffers },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: fragment_entry,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: config.format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState { topology, ..Default::default() },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: DEPTH_FORMAT,
+                    depth_write_enabled,
+                    depth_compare: wgpu::CompareFunction::LessEqual,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+        let neuron_buffers = [
+            wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<[f32; 3]>() as u64,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &SPHERE_ATTRIBUTES,
+            },
+            wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<NeuronInstance>() as u64,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: &INSTANCE_ATTRIBUTES,
+            },
+            wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<f32>() as u64,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: &ACTIVITY_ATTRIBUTES,
+            },
+        ];
+        let line_buffers = [wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<LineVertex>() as u64,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &LINE_ATTRIBUTES,
+        }];
+        // Synapses are translucent: they are tested against the neurons' depth but do not write it.
+        let neuron_pipeline = pipeline("Neuron Render Pipeline", "vs_neuron", "fs_neuron", &neuron_buffers, wgpu::PrimitiveTopology::TriangleList, true);
+        let synapse_pipeline = pipeline("Synapse Render Pipeline", "vs_synapse", "fs_synapse", &line_buffers, wgpu::PrimitiveTopology::LineList, false);
+
+        Some(Self {
+            stride: layout.stride,
+            neuron_count: layout.positions.len() as u32,
+            synapse_count: layout.edges.len(),
+            line_vertex_count,
+            sphere_index_count: sphere_indices.len() as u32,
+            neuron_radius: layout.neuron_radius(),
+            activity,
+            sphere_vertex_buffer,
+            sphere_index_buffer,
+            instance_buffer,
+            activity_buffer,
+            line_buffer,
+            camera_buffer,
+            bind_group,
+            neuron_pipeline,
+            synapse_pipeline,
+            depth_view: create_depth_view(device, config.width, config.height),
+        })
+    }
+
+    pub fn neuron_count(&self) -> u32 {
+        self.neuron_count
+    }
+
+    pub fn synapse_count(&self) -> usize {
+        self.synapse_count
+    }
+
+    /// Follows the size of the surface.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.depth_view = create_depth_view(device, width, height);
+    }
+
+    /// Lights up the neurons that just fired; the others fade, down to their potential.
+    pub fn update_activity(&mut self, queue: &wgpu::Queue, potentials: &[f32], fired: &[u64]) {
+        for (i, activity) in self.activity.iter_mut().enumerate() {
+            let potential = potentials.get(i * self.stride).copied().unwrap_or(0.0);
+            *activity = (*activity * ACTIVITY_DECAY).max(potential.clamp(0.0, 1.0));
+        }
+        for &id in fired {
+            let id = id as usize;
+            if let Some(activity) = self.activity.get_mut(id / self.stride).filter(|_| id.is_multiple_of(self.stride)) {
+                *activity = 1.0;
+            }
+        }
+        queue.write_buffer(&self.activity_buffer, 0, bytemuck::cast_slice(&self.activity));
+    }
+
+    pub fn set_camera(&self, queue: &wgpu::Queue, camera: &OrbitCamera, aspect: f32) {
+        let uniform = CameraUniform { view_proj: camera.view_proj(aspect), neuron_radius: self.neuron_radius, _padding: [0.0; 3] };
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Draws the graph on a cleared `view`, in a pass of its own: it needs a depth buffer, which
+    /// the egui pass has not.
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Connectome Graph Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 }),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Clear(1.0), store: wgpu::StoreOp::Discard }),
+                stencil_ops: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+
+        render_pass.set_pipeline(&self.neuron_pipeline);
+        render_pass.set_vertex_buffer(0, self.sphere_vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        render_pass.set_vertex_buffer(2, self.activity_buffer.slice(..));
+        render_pass.set_index_buffer(self.sphere_index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.draw_indexed(0..self.sphere_index_count, 0, 0..self.neuron_count);
+
+        if self.line_vertex_count > 0 {
+            render_pass.set_pipeline(&self.synapse_pipeline);
+            render_pass.set_vertex_buffer(0, self.line_buffer.slice(..));
+            render_pass.draw(0..self.line_vertex_count, 0..1);
+        }
+    }
+}
+
+fn create_depth_view(device: &wgpu::Device, width: u32, height: u32) -> wgpu::TextureView {
+    device
+        .create_texture(&wgpu::TextureDescriptor {
+            label: Some("Connectome Graph Depth Texture"),
+            size: wgpu::Extent3d { width: width.max(1), height: height.max(1), depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: DEPTH_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        })
+        .create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// A UV sphere of radius 1: its vertices (which are also its normals) and triangles.
+fn sphere_mesh() -> (Vec<[f32; 3]>, Vec<u16>) {
+    let mut vertices = Vec::new();
+    for stack in 0..=SPHERE_STACKS {
+        let polar = std::f32::consts::PI * stack as f32 / SPHERE_STACKS as f32;
+        for slice in 0..=SPHERE_SLICES {
+            let azimuth = 2.0 * std::f32::consts::PI * slice as f32 / SPHERE_SLICES as f32;
+            vertices.push([polar.sin() * azimuth.cos(), polar.cos(), polar.sin() * azimuth.sin()]);
+        }
+    }
+    let mut indices = Vec::new();
+    let row = SPHERE_SLICES + 1;
+    for stack in 0..SPHERE_STACKS {
+        for slice in 0..SPHERE_SLICES {
+            let (a, b) = (stack * row + slice, (stack + 1) * row + slice);
+            indices.extend_from_slice(&[a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+    (vertices, indices)
+}
+
+// --- Vector and matrix helpers (matrices are column-major, as WGSL expects) ---
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f32; 3], factor: f32) -> [f32; 3] {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn length(a: [f32; 3]) -> f32 {
+    dot(a, a).sqrt()
+}
+
+fn normalize(a: [f32; 3]) -> [f32; 3] {
+    scale(a, 1.0 / length(a).max(1e-6))
+}
+
+/// A right-handed view matrix looking from `eye` at `target`.
+fn look_at(eye: [f32; 3], target: [f32; 3], up: [f32; 3]) -> [[f32; 4]; 4] {
+    let forward = normalize(sub(target, eye));
+    let side = normalize(cross(forward, up));
+    let up = cross(side, forward);
+    [
+        [side[0], up[0], -forward[0], 0.0],
+        [side[1], up[1], -forward[1], 0.0],
+        [side[2], up[2], -forward[2], 0.0],
+        [-dot(side, eye), -dot(up, eye), dot(forward, eye), 1.0],
+    ]
+}
+
+/// A right-handed perspective projection onto the 0..1 depth range of wgpu.
+fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> [[f32; 4]; 4] {
+    let f = 1.0 / (fov_y / 2.0).tan();
+    [
+        [f / aspect, 0.0, 0.0, 0.0],
+        [0.0, f, 0.0, 0.0],
+        [0.0, 0.0, far / (near - far), -1.0],
+        [0.0, 0.0, near * far / (near - far), 0.0],
+    ]
+}
+
+fn mat_mul(a: [[f32; 4]; 4], b: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
+    let mut product = [[0.0; 4]; 4];
+    for (column, b_column) in product.iter_mut().zip(&b) {
+        for (row, value) in column.iter_mut().enumerate() {
+            *value = (0..4).map(|k| a[k][row] * b_column[k]).sum();
+        }
+    }
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agi_core::connectome_builder::{ConnectomeBuilder, Topology};
+    use agi_core::rng::RngProvider;
+
+    #[test]
+    fn test_the_layout_fits_the_unit_ball() {
+        let connectome = ConnectomeBuilder::new(200)
+            .topology(Topology::SmallWorld { neighbors: 4, rewiring: 0.1 })
+            .with_rng(RngProvider::new(Some(1)).stream("graph_layout"))
+            .build();
+        let mut layout = GraphLayout::new(&connectome);
+        assert_eq!(layout.neuron_count(), 200);
+        assert!(layout.synapse_count() > 0);
+        assert!(layout.edges.iter().all(|&(source, target, _)| source != target && (source as usize) < 200 && (target as usize) < 200));
+        assert!(layout.positions.iter().all(|position| (length(*position) - 1.0).abs() < 1e-4), "Placed on the sphere");
+
+        layout.relax();
+        let radii: Vec<f32> = layout.positions.iter().map(|position| length(*position)).collect();
+        assert!(radii.iter().all(|radius| radius.is_finite() && *radius <= 1.0 + 1e-4));
+        assert!(radii.iter().any(|radius| (radius - 1.0).abs() < 1e-4), "Scaled back to the unit ball");
+    }
+
+    #[test]
+    fn test_the_camera_stays_in_range() {
+        let mut camera = OrbitCamera::default();
+        camera.orbit(0.0, 10_000.0);
+        assert_eq!(camera.pitch, OrbitCamera::MAX_PITCH);
+        camera.zoom(1e6);
+        assert_eq!(camera.distance, OrbitCamera::MIN_DISTANCE);
+        camera.zoom(-1e6);
+        assert_eq!(camera.distance, OrbitCamera::MAX_DISTANCE);
+    }
+}
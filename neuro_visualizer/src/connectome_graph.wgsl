@@ -0,0 +1,63 @@
+// Connectome graph shader: neurons as instanced spheres, synapses as translucent lines.
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+    neuron_radius: f32,
+};
+
+@group(0) @binding(0) var<uniform> camera: Camera;
+
+struct NeuronOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) normal: vec3<f32>,
+    @location(1) inhibitory: f32,
+    @location(2) activity: f32,
+};
+
+@vertex
+fn vs_neuron(
+    @location(0) sphere_position: vec3<f32>,
+    @location(1) center: vec3<f32>,
+    @location(2) inhibitory: f32,
+    @location(3) activity: f32,
+) -> NeuronOutput {
+    // Firing neurons swell a little as well as lighting up.
+    let radius = camera.neuron_radius * (1.0 + 0.8 * activity);
+    var out: NeuronOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(center + sphere_position * radius, 1.0);
+    out.normal = sphere_position;
+    out.inhibitory = inhibitory;
+    out.activity = activity;
+    return out;
+}
+
+@fragment
+fn fs_neuron(in: NeuronOutput) -> @location(0) vec4<f32> {
+    let excitatory_color = vec3<f32>(0.25, 0.55, 1.0);
+    let inhibitory_color = vec3<f32>(0.9, 0.3, 0.6);
+    let firing_color = vec3<f32>(1.0, 0.9, 0.4);
+    let base = mix(excitatory_color, inhibitory_color, in.inhibitory);
+
+    let light = normalize(vec3<f32>(0.4, 0.8, 0.5));
+    let diffuse = 0.35 + 0.65 * max(dot(normalize(in.normal), light), 0.0);
+    let color = mix(base * diffuse, firing_color, in.activity);
+    return vec4<f32>(color, 1.0);
+}
+
+struct SynapseOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) alpha: f32,
+};
+
+@vertex
+fn vs_synapse(@location(0) position: vec3<f32>, @location(1) alpha: f32) -> SynapseOutput {
+    var out: SynapseOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(position, 1.0);
+    out.alpha = alpha;
+    return out;
+}
+
+@fragment
+fn fs_synapse(in: SynapseOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(0.6, 0.7, 0.9, in.alpha);
+}
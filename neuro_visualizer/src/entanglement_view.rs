@@ -0,0 +1,68 @@
+//! entanglement_view.rs - The qubits entangled by the Creativity Forge, drawn over the Boot ring.
+//!
+//! Qubit `i` of the quantum core is imprinted by neuron `i`, so each edge of the entanglement
+//! graph (see `Core::get_entanglement_graph`) is drawn as a chord between two columns of the
+//! ring, as opaque as it is strong. The pairs entangled since the previous fetch, the creative
+//! leaps of the last readout, stand out in a brighter colour.
+
+use std::collections::HashMap;
+
+use agi_core::entanglement_map::{EntanglementEdge, EntanglementGraph};
+use egui::{Color32, Painter, Rect, Stroke};
+
+use crate::reasoning_trace::ring_position;
+
+/// Strongest edges listed in the side panel.
+const LISTED_EDGES: usize = 8;
+
+const EDGE_COLOR: Color32 = Color32::from_rgb(120, 170, 255);
+const LEAP_COLOR: Color32 = Color32::from_rgb(255, 120, 230);
+
+#[derive(Debug, Default)]
+pub struct EntanglementView {
+    graph: EntanglementGraph,
+    /// The pairs entangled since the previous graph.
+    leaps: Vec<(usize, usize)>,
+}
+
+impl EntanglementView {
+    pub fn set_graph(&mut self, graph: EntanglementGraph) {
+        let previous: HashMap<(usize, usize), u32> = self.graph.edges.iter().map(|edge| ((edge.a, edge.b), edge.leaps)).collect();
+        self.leaps = graph
+            .edges
+            .iter()
+            .filter(|edge| previous.get(&(edge.a, edge.b)).is_none_or(|&leaps| edge.leaps > leaps))
+            .map(|edge| (edge.a, edge.b))
+            .collect();
+        self.graph = graph;
+    }
+
+    /// Draws the graph over the ring of the Boot view, whose `neuron_count` neurons are spread
+    /// on a circle over `screen`. The qubits beyond the ring are left out.
+    pub fn paint_ring(&self, painter: &Painter, screen: Rect, neuron_count: usize) {
+        let drawn = |edge: &&EntanglementEdge| edge.b < neuron_count;
+        let strongest = self.graph.edges.first().map_or(1.0, |edge| edge.strength).max(f32::EPSILON);
+        // The weakest first, so that the strong chords are drawn on top of them.
+        for edge in self.graph.edges.iter().rev().filter(drawn) {
+            let leap = self.leaps.contains(&(edge.a, edge.b));
+            let (color, width) = if leap { (LEAP_COLOR, 2.5) } else { (EDGE_COLOR, 1.5) };
+            let alpha = (edge.strength / strongest).clamp(0.1, 1.0);
+            let ends = [ring_position(edge.a, neuron_count, screen), ring_position(edge.b, neuron_count, screen)];
+            painter.line_segment(ends, Stroke::new(width, color.gamma_multiply(alpha)));
+            for end in ends {
+                painter.circle_filled(end, if leap { 4.0 } else { 2.5 }, color.gamma_multiply(alpha));
+            }
+        }
+    }
+
+    pub fn controls(&self, ui: &mut egui::Ui) {
+        if self.graph.edges.is_empty() {
+            ui.label("No qubits entangled yet. The Creativity Forge entangles them in the creative mode.");
+            return;
+        }
+        ui.label(format!("{} entangled pairs over {} qubits, {} new.", self.graph.edges.len(), self.graph.qubits, self.leaps.len()));
+        for edge in self.graph.edges.iter().take(LISTED_EDGES) {
+            ui.label(format!("{} <-> {}: strength {:.2}, {} leaps", edge.a, edge.b, edge.strength, edge.leaps));
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! gpu_connectome.rs - The connectome simulated by compute shaders.
+//!
+//! Neuron potentials and synapses live in GPU buffers; every tick runs the decay/fire step of
+//! `Neuron::update` and the propagation of the spikes in `connectome_compute.wgsl`. Only the
+//! number of firings and a sample of the potentials are read back, which keeps connectomes of a
+//! million neurons and more at interactive rates.
+
+use std::fmt;
+use std::sync::mpsc;
+
+use agi_core::connectome::Connectome;
+use rand::Rng;
+use wgpu::util::DeviceExt;
+
+/// Potential added to a couple of random neurons each tick, as in `Connectome::update`.
+const SPONTANEOUS_BOOST_AMOUNT: f32 = 0.75;
+/// Neurons stimulated at most per tick.
+const MAX_STIMULUS: usize = 4096;
+const WORKGROUP_SIZE: u32 = 256;
+const SMALL_WORKGROUP_SIZE: u32 = 64;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct Params {
+    neuron_count: u32,
+    boost_a: u32,
+    boost_b: u32,
+    boost: f32,
+    stimulus_count: u32,
+    stimulus: f32,
+    sample_stride: u32,
+    sample_count: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct NeuronState {
+    potential: f32,
+    firing: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct NeuronConstants {
+    threshold: f32,
+    leak_factor: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct GpuSynapse {
+    source: u32,
+    weight: f32,
+}
+
+/// The connectome laid out for the GPU: the synapses are grouped by target neuron, so that each
+/// neuron sums its own input during propagation.
+pub struct ConnectomeLayout {
+    neurons: Vec<NeuronState>,
+    constants: Vec<NeuronConstants>,
+    offsets: Vec<u32>,
+    synapses: Vec<GpuSynapse>,
+}
+
+impl ConnectomeLayout {
+    pub fn new(connectome: &Connectome) -> Self {
+        let neurons = connectome.neurons.iter().map(|n| NeuronState { potential: n.potential, firing: n.firing as u32 }).collect();
+        let constants = connectome.neurons.iter().map(|n| NeuronConstants { threshold: n.threshold, leak_factor: n.leak_factor }).collect();
+
+        // Counting sort of the synapses by their target.
+        let neuron_count = connectome.neurons.len();
+        let mut offsets = vec![0u32; neuron_count + 1];
+        for synapse in connectome.synapses.iter() {
+            if (synapse.to as usize) < neuron_count {
+                offsets[synapse.to as usize + 1] += 1;
+            }
+        }
+        let mut total = 0;
+        for offset in offsets.iter_mut() {
+            total += *offset;
+            *offset = total;
+        }
+        let mut cursor = offsets.clone();
+        let mut synapses = vec![GpuSynapse { source: 0, weight: 0.0 }; offsets[neuron_count] as usize];
+        for synapse in connectome.synapses.iter() {
+            if (synapse.to as usize) < neuron_count {
+                let slot = &mut cursor[synapse.to as usize];
+                synapses[*slot as usize] = GpuSynapse { source: synapse.from as u32, weight: synapse.weight };
+                *slot += 1;
+            }
+        }
+        Self { neurons, constants, offsets, synapses }
+    }
+
+    pub fn neuron_count(&self) -> usize {
+        self.neurons.len()
+    }
+}
+
+#[derive(Debug)]
+pub enum GpuConnectomeError {
+    Empty,
+    /// A buffer exceeds what the device can bind.
+    TooLarge { buffer: &'static str, bytes: u64, limit: u64 },
+}
+
+impl fmt::Display for GpuConnectomeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuConnectomeError::Empty => write!(f, "the connectome has no neurons"),
+            GpuConnectomeError::TooLarge { buffer, bytes, limit } => {
+                write!(f, "the {} buffer needs {} bytes, the device binds at most {}", buffer, bytes, limit)
+            }
+        }
+    }
+}
+
+/// What a batch of ticks left behind.
+#[derive(Debug, Clone, Default)]
+pub struct GpuActivity {
+    /// Number of firings over the ticks.
+    pub firings: u32,
+    /// Potentials of every `sample_stride`-th neuron.
+    pub potentials: Vec<f32>,
+}
+
+pub struct GpuConnectome {
+    neuron_count: u32,
+    sample_count: u32,
+    sample_stride: u32,
+    params_buffer: wgpu::Buffer,
+    counters_buffer: wgpu::Buffer,
+    samples_buffer: wgpu::Buffer,
+    stimulus_buffer: wgpu::Buffer,
+    counters_readback: wgpu::Buffer,
+    samples_readback: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    stimulate_pipeline: wgpu::ComputePipeline,
+    update_pipeline: wgpu::ComputePipeline,
+    propagate_pipeline: wgpu::ComputePipeline,
+    sample_pipeline: wgpu::ComputePipeline,
+}
+
+impl GpuConnectome {
+    /// Uploads the connectome. `sample_count` potentials, evenly spread over the neurons, are read
+    /// back by `read_activity`.
+    pub fn new(device: &wgpu::Device, layout: &ConnectomeLayout, sample_count: u32) -> Result<Self, GpuConnectomeError> {
+        if layout.neurons.is_empty() {
+            return Err(GpuConnectomeError::Empty);
+        }
+        let limits = device.limits();
+        let limit = (limits.max_storage_buffer_binding_size as u64).min(limits.max_buffer_size);
+        let check = |buffer: &'static str, bytes: usize| {
+            let bytes = bytes as u64;
+            if bytes > limit { Err(GpuConnectomeError::TooLarge { buffer, bytes, limit }) } else { Ok(()) }
+        };
+        check("neuron", std::mem::size_of_val(layout.neurons.as_slice()))?;
+        check("synapse", std::mem::size_of_val(layout.synapses.as_slice()))?;
+
+        let neuron_count = layout.neurons.len() as u32;
+        let sample_count = sample_count.clamp(1, neuron_count);
+        let sample_stride = neuron_count / sample_count;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Connectome Compute Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("connectome_compute.wgsl").into()),
+        });
+
+        let storage = wgpu::BufferUsages::STORAGE;
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Connectome Params Buffer"),
+            size: std::mem::size_of::<Params>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let neuron_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Neuron State Buffer"),
+            contents: bytemuck::cast_slice(&layout.neurons),
+            usage: storage,
+        });
+        let constants_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Neuron Constants Buffer"),
+            contents: bytemuck::cast_slice(&layout.constants),
+            usage: storage,
+        });
+        let offsets_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Synapse Offsets Buffer"),
+            contents: bytemuck::cast_slice(&layout.offsets),
+            usage: storage,
+        });
+        // A binding cannot be empty: a connectome without synapses still gets one slot.
+        let placeholder = [GpuSynapse { source: 0, weight: 0.0 }];
+        let synapses: &[GpuSynapse] = if layout.synapses.is_empty() { &placeholder } else { &layout.synapses };
+        let synapse_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Synapse Buffer"),
+            contents: bytemuck::cast_slice(synapses),
+            usage: storage,
+        });
+        let counters_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Connectome Counters Buffer"),
+            size: 4,
+            usage: storage | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let samples_size = sample_count as u64 * 4;
+        let samples_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Potential Samples Buffer"),
+            size: samples_size,
+            usage: storage | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let stimulus_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Stimulus Buffer"),
+            size: (MAX_STIMULUS * 4) as u64,
+            usage: storage | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let counters_readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Connectome Counters Readback"),
+            size: 4,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let samples_readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Potential Samples Readback"),
+            size: samples_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let storage_entry = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry { // Params
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage_entry(1, false), // Neuron states
+                storage_entry(2, true),  // Neuron constants
+                storage_entry(3, true),  // Synapse offsets
+                storage_entry(4, true),  // Synapses
+                storage_entry(5, false), // Counters
+                storage_entry(6, false), // Potential samples
+                storage_entry(7, true),  // Stimulus
+            ],
+            label: Some("connectome_bind_group_layout"),
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: params_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: neuron_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: constants_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: offsets_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 4, resource: synapse_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 5, resource: counters_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 6, resource: samples_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 7, resource: stimulus_buffer.as_entire_binding() },
+            ],
+            label: Some("connectome_bind_group"),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Connectome Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry_point: &'static str| device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(entry_point),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point,
+        });
+
+        Ok(Self {
+            neuron_count,
+            sample_count,
+            sample_stride,
+            params_buffer,
+            counters_buffer,
+            samples_buffer,
+            stimulus_buffer,
+            counters_readback,
+            samples_readback,
+            bind_group,
+            stimulate_pipeline: pipeline("stimulate"),
+            update_pipeline: pipeline("update"),
+            propagate_pipeline: pipeline("propagate"),
+            sample_pipeline: pipeline("sample_potentials"),
+        })
+    }
+
+    pub fn neuron_count(&self) -> u32 {
+        self.neuron_count
+    }
+
+    /// Runs `ticks` ticks. The `stimulated` neurons receive `stimulus` before the first one.
+    pub fn step(&self, device: &wgpu::Device, queue: &wgpu::Queue, ticks: u32, stimulated: &[u64], stimulus: f32) {
+        let stimulated: Vec<u32> = stimulated.iter().take(MAX_STIMULUS).map(|&id| id as u32).collect();
+        if !stimulated.is_empty() {
+            queue.write_buffer(&self.stimulus_buffer, 0, bytemuck::cast_slice(&stimulated));
+        }
+        let mut rng = rand::thread_rng();
+        for tick in 0..ticks {
+            let params = Params {
+                neuron_count: self.neuron_count,
+                boost_a: rng.gen_range(0..self.neuron_count),
+                boost_b: rng.gen_range(0..self.neuron_count),
+                boost: SPONTANEOUS_BOOST_AMOUNT,
+                stimulus_count: if tick == 0 { stimulated.len() as u32 } else { 0 },
+                stimulus,
+                sample_stride: self.sample_stride,
+                sample_count: self.sample_count,
+            };
+            // Each tick is its own submission, so that it sees its own parameters.
+            queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Connectome Tick Encoder") });
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("Connectome Tick Pass"), timestamp_writes: None });
+                pass.set_bind_group(0, &self.bind_group, &[]);
+                if params.stimulus_count > 0 {
+                    pass.set_pipeline(&self.stimulate_pipeline);
+                    pass.dispatch_workgroups(params.stimulus_count.div_ceil(SMALL_WORKGROUP_SIZE), 1, 1);
+                }
+                let (x, y) = self.neuron_workgroups(device);
+                pass.set_pipeline(&self.update_pipeline);
+                pass.dispatch_workgroups(x, y, 1);
+                pass.set_pipeline(&self.propagate_pipeline);
+                pass.dispatch_workgroups(x, y, 1);
+            }
+            queue.submit(std::iter::once(encoder.finish()));
+        }
+    }
+
+    /// Reads back the firings counted since the last call and the sampled potentials.
+    pub fn read_activity(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> GpuActivity {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Connectome Readback Encoder") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("Connectome Sample Pass"), timestamp_writes: None });
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.set_pipeline(&self.sample_pipeline);
+            pass.dispatch_workgroups(self.sample_count.div_ceil(SMALL_WORKGROUP_SIZE), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&self.counters_buffer, 0, &self.counters_readback, 0, 4);
+        encoder.copy_buffer_to_buffer(&self.samples_buffer, 0, &self.samples_readback, 0, self.sample_count as u64 * 4);
+        queue.submit(std::iter::once(encoder.finish()));
+        // Cleared for the next batch once the copy above has run.
+        queue.write_buffer(&self.counters_buffer, 0, bytemuck::cast_slice(&[0u32]));
+
+        let (sender, receiver) = mpsc::channel();
+        for buffer in [&self.counters_readback, &self.samples_readback] {
+            let sender = sender.clone();
+            buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        }
+        drop(sender);
+        let _ = device.poll(wgpu::Maintain::Wait);
+        if receiver.iter().any(|result| result.is_err()) {
+            return GpuActivity::default();
+        }
+
+        let firings = bytemuck::cast_slice::<u8, u32>(&self.counters_readback.slice(..).get_mapped_range())[0];
+        let potentials = bytemuck::cast_slice::<u8, f32>(&self.samples_readback.slice(..).get_mapped_range()).to_vec();
+        self.counters_readback.unmap();
+        self.samples_readback.unmap();
+        GpuActivity { firings, potentials }
+    }
+
+    /// Workgroups covering every neuron, spread over a second dimension past the device limit.
+    fn neuron_workgroups(&self, device: &wgpu::Device) -> (u32, u32) {
+        let groups = self.neuron_count.div_ceil(WORKGROUP_SIZE);
+        let max_x = device.limits().max_compute_workgroups_per_dimension;
+        let x = groups.min(max_x);
+        (x, groups.div_ceil(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agi_core::connectome_builder::{ConnectomeBuilder, Topology};
+    use agi_core::rng::RngProvider;
+
+    #[test]
+    fn test_synapses_are_grouped_by_their_target() {
+        let connectome = ConnectomeBuilder::new(50)
+            .topology(Topology::Uniform { synapses_per_neuron: 5 })
+            .with_rng(RngProvider::new(Some(1)).stream("gpu_layout"))
+            .build();
+        let layout = ConnectomeLayout::new(&connectome);
+        assert_eq!(layout.neuron_count(), 50);
+        assert_eq!(layout.offsets.len(), 51);
+        assert_eq!(layout.synapses.len(), connectome.synapses.iter().count());
+
+        for target in 0..50 {
+            let range = layout.offsets[target] as usize..layout.offsets[target + 1] as usize;
+            let mut laid_out: Vec<(u32, u32)> = layout.synapses[range].iter().map(|synapse| (synapse.source, synapse.weight.to_bits())).collect();
+            let mut expected: Vec<(u32, u32)> = connectome
+                .synapses
+                .iter()
+                .filter(|synapse| synapse.to as usize == target)
+                .map(|synapse| (synapse.from as u32, synapse.weight.to_bits()))
+                .collect();
+            laid_out.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(laid_out, expected, "Input of neuron {}", target);
+        }
+    }
+}
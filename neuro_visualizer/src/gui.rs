@@ -1,6 +1,9 @@
 use crate::{State, VisualizationMode};
+use crate::concept_graph::GraphAction;
+use crate::connectome_graph::OrbitCamera;
 
 use egui::{ScrollArea, Vec2};
+use std::time::Duration;
 
 pub fn draw_ui(ctx: &egui::Context, state: &mut State) {
     // --- Left Panel (Controls) ---
@@ -12,32 +15,48 @@ pub fn draw_ui(ctx: &egui::Context, state: &mut State) {
                 ui.heading("NeuroVisualizer");
                 ui.separator();
 
-            // Performance metrics are fetched in the main update loop now
-            // to avoid locking the core multiple times.
+            // Performance metrics come from the snapshot published by the core actor.
             let (tps, power) = {
-                let core_lock = state.core.lock().unwrap();
-                (
-                    core_lock.processing_speed.load(std::sync::atomic::Ordering::Relaxed),
-                    core_lock.power_draw.load(std::sync::atomic::Ordering::Relaxed)
-                )
+                let snapshot = state.core.latest_snapshot();
+                (snapshot.processing_speed, snapshot.power_draw)
             };
 
             ui.label(format!("AGI Core TPS: {:.2}", tps));
+            simulation_controls(ui, state);
             ui.label(format!("Power Draw: {:.2} W", power));
+            if let Some(gpu_connectome) = &state.gpu_connectome {
+                ui.label(format!("GPU Connectome: {} neurons, {} firings/frame", gpu_connectome.neuron_count(), state.gpu_activity.firings));
+            }
             ui.separator();
 
             ui.label("Visualization Mode:");
             ui.radio_value(&mut state.mode, VisualizationMode::BootAnimation, "Boot Animation (B)");
             ui.radio_value(&mut state.mode, VisualizationMode::EEGPlot, "EEG Plot (E)");
             ui.radio_value(&mut state.mode, VisualizationMode::MandalaViewer, "Mandala Viewer (M)");
+            ui.radio_value(&mut state.mode, VisualizationMode::ConnectomeGraph, "Connectome Graph (C)");
+            ui.radio_value(&mut state.mode, VisualizationMode::ConceptGraph, "Concept Graph (H)");
+            ui.radio_value(&mut state.mode, VisualizationMode::Spectrogram, "EEG Spectrogram (S)");
+            ui.radio_value(&mut state.mode, VisualizationMode::EntanglementGraph, "Entanglement Graph (Q)");
             ui.separator();
 
             // --- Deep Thinker UI (Disabled) ---
 
+            // --- Trace of the last prompt over the Boot and EEG views ---
+            if matches!(state.mode, VisualizationMode::BootAnimation | VisualizationMode::EEGPlot) {
+                ui.heading("Reasoning Trace");
+                state.trace.controls(ui);
+            }
+
+            // --- Qubits entangled by the Creativity Forge ---
+            if state.mode == VisualizationMode::EntanglementGraph {
+                ui.heading("Entanglement Graph");
+                state.entanglement.controls(ui);
+            }
+
             // --- Mandala-specific controls ---
             if state.mode == VisualizationMode::MandalaViewer {
                 ui.heading("Conceptual Hierarchy");
-                let concepts = state.core.lock().unwrap().conceptual_hierarchy.get_all_concept_names();
+                let concepts = state.concept_names.clone();
                 ScrollArea::vertical().show(ui, |ui| {
                     let mut new_selection_name: Option<String> = None;
                     for name_str in &concepts {
@@ -48,15 +67,45 @@ pub fn draw_ui(ctx: &egui::Context, state: &mut State) {
                         }
                     }
                     if let Some(name) = new_selection_name {
-                        let concept_to_update = {
-                            state.core.lock().unwrap().conceptual_hierarchy.find_concept_by_name(&name).cloned()
-                        };
-                        if let Some(concept) = concept_to_update {
-                            state.update_mandala_texture(&concept);
-                        }
+                        // The texture is updated once the core actor answers (see `poll_concept_requests`).
+                        state.pending_concept = state.core.try_submit(move |core| core.conceptual_hierarchy.find_concept_by_name(&name).cloned()).ok();
                     }
                 });
             }
+            ui.separator();
+
+            // --- Memory browser ---
+            ui.collapsing("Memory Browser", |ui| {
+                let mut related_request: Option<u64> = None;
+                ScrollArea::vertical().id_source("memory_list").max_height(200.0).show(ui, |ui| {
+                    for (id, text) in &state.memory_list {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("see related").clicked() {
+                                related_request = Some(*id);
+                            }
+                            ui.label(format!("#{} {}", id, text));
+                        });
+                    }
+                });
+                if let Some(id) = related_request {
+                    state.request_related_memories(id);
+                }
+
+                if let Some((id, _)) = &state.pending_related {
+                    ui.label(format!("Searching memories related to #{}...", id));
+                } else if let Some((id, related)) = &state.related_memories {
+                    ui.label(format!("Related to #{}:", id));
+                    if related.is_empty() {
+                        ui.label("No related memories.");
+                    }
+                    for memory in related {
+                        ui.label(format!("{:.0}%  #{} {}", memory.neighbor.confidence * 100.0, memory.neighbor.id, memory.neighbor.text));
+                        if !memory.shared_concept_names.is_empty() {
+                            ui.small(format!("shared: {}", memory.shared_concept_names.join(", ")));
+                        }
+                    }
+                }
+            });
         }); // Closes ScrollArea
     });
 
@@ -78,6 +127,48 @@ pub fn draw_ui(ctx: &egui::Context, state: &mut State) {
 
 
 
+    // --- Concept details (Concept Graph) ---
+    if state.mode == VisualizationMode::ConceptGraph {
+        egui::SidePanel::right("concept_details")
+            .resizable(true)
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                if state.concept_graph.center().is_some() && ui.button("Back to the roots").clicked() {
+                    state.concept_graph.recenter(None);
+                }
+                let Some(concept) = state.concept_graph.selected.and_then(|id| state.concept_graph.concept(id)).cloned() else {
+                    ui.label("Click a concept to see its details, double-click it to center the graph on it.");
+                    return;
+                };
+                ui.heading(&concept.name);
+                ui.image((state.mandala_texture, Vec2::new(200.0, 200.0)));
+
+                let mut selection = None;
+                ScrollArea::vertical().show(ui, |ui| {
+                    for (title, ids) in [("Parents", &concept.parents), ("Children", &concept.children)] {
+                        ui.label(format!("{}:", title));
+                        let names = state.concept_graph.names(ids);
+                        if names.is_empty() {
+                            ui.small("none");
+                        }
+                        for (id, name) in names {
+                            if ui.link(name).clicked() {
+                                selection = Some(id);
+                            }
+                        }
+                        ui.separator();
+                    }
+                    ui.label(format!("Memories ({}):", state.concept_memories.len()));
+                    for (id, text) in &state.concept_memories {
+                        ui.small(format!("#{} {}", id, text));
+                    }
+                });
+                if let Some(id) = selection {
+                    state.select_graph_concept(id);
+                }
+            });
+    }
+
     // --- Central Panel (Mandala View or other main content) ---
     // Make the central panel transparent so the background wgpu rendering is visible
     let frame = egui::Frame::none();
@@ -93,6 +184,43 @@ pub fn draw_ui(ctx: &egui::Context, state: &mut State) {
                     ui.label("Select a concept to view its trace.");
                 });
             }
+        } else if state.mode == VisualizationMode::ConceptGraph {
+            match state.concept_graph.show(ui) {
+                Some(GraphAction::Select(id)) => state.select_graph_concept(id),
+                Some(GraphAction::Recenter(id)) => {
+                    state.concept_graph.recenter(Some(id));
+                    state.select_graph_concept(id);
+                }
+                None => {}
+            }
+        } else if state.mode == VisualizationMode::Spectrogram {
+            state.spectrogram.show(ui);
+        } else if state.mode == VisualizationMode::BootAnimation {
+            // The ring is drawn over the whole window, behind the panels.
+            state.trace.paint_ring(ui.painter(), ui.ctx().screen_rect(), state.columns_data.len());
+        } else if state.mode == VisualizationMode::EntanglementGraph {
+            state.entanglement.paint_ring(ui.painter(), ui.ctx().screen_rect(), state.columns_data.len());
+        } else if state.mode == VisualizationMode::EEGPlot {
+            state.trace.paint_eeg(ui.painter(), ui.ctx().screen_rect(), state.eeg_num_points as usize);
+        } else if state.mode == VisualizationMode::ConnectomeGraph {
+            match &state.connectome_graph {
+                Some(graph) => ui.label(format!(
+                    "{} neurons, {} synapses. Drag to orbit, scroll to zoom, double-click to reset.",
+                    graph.neuron_count(),
+                    graph.synapse_count()
+                )),
+                None => ui.label("The connectome has no neurons to show."),
+            };
+            // The graph is drawn behind the panel: dragging orbits the camera, scrolling zooms.
+            let response = ui.allocate_rect(ui.available_rect_before_wrap(), egui::Sense::click_and_drag());
+            let drag = response.drag_delta();
+            state.camera.orbit(drag.x, drag.y);
+            if response.hovered() {
+                state.camera.zoom(ui.input(|i| i.smooth_scroll_delta.y));
+            }
+            if response.double_clicked() {
+                state.camera = OrbitCamera::default();
+            }
         }
     });
 
@@ -107,14 +235,59 @@ pub fn draw_ui(ctx: &egui::Context, state: &mut State) {
                 if ui.button("Send").clicked() || enter_pressed {
                     if !state.prompt_buffer.is_empty() {
                         let prompt = state.prompt_buffer.trim().to_string();
-                        state.chat_history.push(format!("You: {}", prompt));
-                                                                        if let Some((response, _)) = state.core.lock().unwrap().get_response_for_prompt(&prompt) {
-                            state.chat_history.push(format!("AGI: {}", response));
+                        // The answer is picked up in `State::update` once the core actor has reasoned.
+                        // While its queue is full, the prompt stays in the box to be sent again.
+                        match state.core.try_submit_prompt(prompt.clone()) {
+                            Ok(pending) => {
+                                state.chat_history.push(format!("You: {}", prompt));
+                                state.pending_response = Some(pending);
+                                state.prompt_buffer.clear();
+                            }
+                            Err(e) => state.chat_history.push(format!("(The prompt was not sent: {}.)", e)),
                         }
-                        state.prompt_buffer.clear();
                         prompt_input.request_focus();
                     }
                 }
+                #[cfg(feature = "tts")]
+                speak_button(ui, state);
             });
         });
 }
+
+/// Reads the last answer aloud, with the voice of the active personality profile.
+#[cfg(feature = "tts")]
+fn speak_button(ui: &mut egui::Ui, state: &mut State) {
+    let answered = state.chat_history.last().is_some_and(|line| line.starts_with("AGI: "));
+    let mut speak = ui.add_enabled(answered && !state.speaker.is_busy(), egui::Button::new("Speak"));
+    if let Some(error) = &state.speaker.last_error {
+        speak = speak.on_hover_text(format!("The last answer could not be spoken: {}", error));
+    }
+    if speak.clicked() {
+        state.speaker.speak(&state.core, state.agi_response.clone());
+    }
+}
+
+/// Pauses, resumes, steps and paces the ticks of the core actor. The replies are not awaited:
+/// the snapshot published after the change shows it.
+fn simulation_controls(ui: &mut egui::Ui, state: &mut State) {
+    let snapshot = state.core.latest_snapshot();
+    ui.horizontal(|ui| {
+        ui.label(format!("Tick {}", snapshot.tick));
+        let paused = snapshot.clock.paused;
+        if ui.button(if paused { "Resume" } else { "Pause" }).clicked() {
+            let _ = state.core.try_submit(move |core| if paused { core.resume() } else { core.pause() });
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.add(egui::DragValue::new(&mut state.step_ticks).clamp_range(1..=1000).suffix(" ticks"));
+        if ui.button("Step").clicked() {
+            let ticks = state.step_ticks;
+            let _ = state.core.try_submit(move |core| core.step(ticks));
+        }
+    });
+    let interval = ui.add(egui::Slider::new(&mut state.tick_interval_ms, 1..=500).logarithmic(true).text("ms/tick"));
+    if interval.changed() {
+        let interval = Duration::from_millis(state.tick_interval_ms);
+        let _ = state.core.try_submit(move |core| core.set_tick_interval(Some(interval)));
+    }
+}
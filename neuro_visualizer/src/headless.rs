@@ -0,0 +1,231 @@
+//! headless.rs - Rendering to files, without a window or a GPU.
+//!
+//! `--export-mandalas <dir>` writes the trace mandala of every concept as a PNG (drawn by
+//! `agi_core::trace_visualizer`, like the server's `/api/concepts/:name/mandala`).
+//! `--record-eeg <path>` and `--record-boot <path>` tick the core `--ticks` times and draw each
+//! tick as the EEG view or the ring of the Boot view: a path ending in `.gif` is encoded as an
+//! animated GIF, any other path is a directory of numbered PNG frames (`frame_00000.png`, ...),
+//! e.g. for `ffmpeg -i frame_%05d.png activity.mp4`.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use agi_core::Core;
+use agi_core::trace_visualizer::generate_trace_image;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, ImageResult, Rgba, RgbaImage};
+
+const DEFAULT_TICKS: usize = 200;
+const DEFAULT_SIZE: u32 = 512;
+/// Neurons plotted by the EEG view, as in the window.
+const EEG_POINTS: usize = 1024;
+/// Display time of a recorded tick.
+const FRAME_DELAY_MS: u32 = 40;
+
+const BACKGROUND: Rgba<u8> = Rgba([0, 0, 13, 255]);
+const EEG_COLOR: Rgba<u8> = Rgba([26, 204, 102, 255]);
+
+pub const USAGE: &str = "\
+Headless rendering (no window is opened):
+  --export-mandalas <dir>   write the mandala of every concept as <dir>/<concept>.png
+  --record-eeg <path>       record the EEG view over --ticks ticks
+  --record-boot <path>      record the neuron ring of the Boot view over --ticks ticks
+  --ticks <n>               ticks recorded (default: 200)
+  --size <px>               size of the images (default: 512)
+A recording path ending in .gif is an animated GIF; any other path is a directory of PNG frames.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordedView {
+    Eeg,
+    Boot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadlessOptions {
+    pub mandala_dir: Option<PathBuf>,
+    pub recording: Option<(RecordedView, PathBuf)>,
+    pub ticks: usize,
+    pub size: u32,
+}
+
+impl HeadlessOptions {
+    /// The headless options of the command line (without the program name), or None when none
+    /// is given and the window should open.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = HeadlessOptions { mandala_dir: None, recording: None, ticks: DEFAULT_TICKS, size: DEFAULT_SIZE };
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", flag));
+            match flag.as_str() {
+                "--export-mandalas" => options.mandala_dir = Some(PathBuf::from(value()?)),
+                "--record-eeg" => options.recording = Some((RecordedView::Eeg, PathBuf::from(value()?))),
+                "--record-boot" => options.recording = Some((RecordedView::Boot, PathBuf::from(value()?))),
+                "--ticks" => options.ticks = value()?.parse().map_err(|_| "--ticks needs a number".to_string())?,
+                "--size" => options.size = value()?.parse().ok().filter(|size| *size > 0).ok_or("--size needs a positive number")?,
+                _ => return Err(format!("Unknown argument {}", flag)),
+            }
+        }
+        Ok((options.mandala_dir.is_some() || options.recording.is_some()).then_some(options))
+    }
+}
+
+/// Renders what the options ask for with a core seeded like the window's.
+pub fn run(options: &HeadlessOptions, mut core: Core) -> ImageResult<()> {
+    if let Some(dir) = &options.mandala_dir {
+        let count = export_mandalas(&core, dir, options.size)?;
+        println!("[OK] {} mandalas written to {}.", count, dir.display());
+    }
+    if let Some((view, path)) = &options.recording {
+        record(&mut core, *view, path, options.ticks, options.size)?;
+        println!("[OK] {} ticks recorded to {}.", options.ticks, path.display());
+    }
+    Ok(())
+}
+
+/// Writes the mandala of every concept, and returns how many were written.
+fn export_mandalas(core: &Core, dir: &Path, size: u32) -> ImageResult<usize> {
+    fs::create_dir_all(dir)?;
+    let concepts = core.conceptual_hierarchy.get_all_concepts();
+    for concept in &concepts {
+        let file_name = format!("{}_{}.png", concept.id, sanitized(&concept.name));
+        generate_trace_image(&concept.trace, size, size).save(dir.join(file_name))?;
+    }
+    Ok(concepts.len())
+}
+
+/// A concept name usable in a file name.
+fn sanitized(name: &str) -> String {
+    name.chars().map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' }).collect()
+}
+
+fn record(core: &mut Core, view: RecordedView, path: &Path, ticks: usize, size: u32) -> ImageResult<()> {
+    let as_gif = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+    let mut gif = if as_gif { Some(GifEncoder::new(BufWriter::new(File::create(path)?))) } else { None };
+    match gif.as_mut() {
+        Some(encoder) => encoder.set_repeat(Repeat::Infinite)?,
+        None => fs::create_dir_all(path)?,
+    }
+
+    // The Boot view keeps the glow of a fired neuron for a few ticks, as in the window.
+    let mut column_states = vec![0.0f32; core.connectome.neurons.len()];
+    for tick in 0..ticks {
+        core.tick();
+        let potentials: Vec<f32> = core.connectome.neurons.iter().map(|neuron| neuron.potential).collect();
+        let frame = match view {
+            RecordedView::Eeg => eeg_frame(&potentials, size),
+            RecordedView::Boot => {
+                let fired: HashSet<u64> = core.last_fired_neurons.iter().copied().collect();
+                for (i, (state, potential)) in column_states.iter_mut().zip(&potentials).enumerate() {
+                    *state = if fired.contains(&(i as u64)) { 1.0 } else { (*state * 0.95).max(*potential) };
+                }
+                boot_frame(&column_states, size)
+            }
+        };
+        match gif.as_mut() {
+            Some(encoder) => encoder.encode_frame(Frame::from_parts(frame, 0, 0, Delay::from_numer_denom_ms(FRAME_DELAY_MS, 1)))?,
+            None => frame.save(path.join(format!("frame_{:05}.png", tick)))?,
+        }
+    }
+    Ok(())
+}
+
+/// The EEG view: the potentials of the first neurons as a line, a potential of 1 at three
+/// quarters of the height.
+fn eeg_frame(potentials: &[f32], size: u32) -> RgbaImage {
+    let mut image = RgbaImage::from_pixel(size, size, BACKGROUND);
+    let points = potentials.len().min(EEG_POINTS);
+    if points < 2 {
+        return image;
+    }
+    let scale = |i: usize, potential: f32| {
+        let x = i as f32 / (points - 1) as f32 * (size - 1) as f32;
+        let y = (1.0 - potential * 0.5) * 0.5 * (size - 1) as f32;
+        (x, y)
+    };
+    for i in 1..points {
+        draw_line(&mut image, scale(i - 1, potentials[i - 1]), scale(i, potentials[i]), EEG_COLOR);
+    }
+    image
+}
+
+/// The ring of the Boot view: a dot per neuron, from blue at rest to white when it fires.
+fn boot_frame(column_states: &[f32], size: u32) -> RgbaImage {
+    let mut image = RgbaImage::from_pixel(size, size, BACKGROUND);
+    let center = size as f32 / 2.0;
+    let radius = center * 0.45;
+    for (i, state) in column_states.iter().enumerate() {
+        let angle = i as f32 / column_states.len() as f32 * std::f32::consts::TAU;
+        let state = state.clamp(0.0, 1.0);
+        let mix = |rest: f32, fired: f32| ((rest + (fired - rest) * state) * 255.0) as u8;
+        let color = Rgba([mix(0.1, 0.8), mix(0.3, 0.9), mix(0.8, 1.0), 255]);
+        let (x, y) = (center + angle.cos() * radius, center - angle.sin() * radius);
+        fill_circle(&mut image, x, y, 1.0 + 2.0 * state, color);
+    }
+    image
+}
+
+fn draw_line(image: &mut RgbaImage, from: (f32, f32), to: (f32, f32), color: Rgba<u8>) {
+    let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil().max(1.0) as usize;
+    for step in 0..=steps {
+        let t = step as f32 / steps as f32;
+        put_pixel(image, from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t, color);
+    }
+}
+
+fn fill_circle(image: &mut RgbaImage, cx: f32, cy: f32, radius: f32, color: Rgba<u8>) {
+    let r = radius.ceil() as i32;
+    for dy in -r..=r {
+        for dx in -r..=r {
+            if (dx * dx + dy * dy) as f32 <= radius * radius {
+                put_pixel(image, cx + dx as f32, cy + dy as f32, color);
+            }
+        }
+    }
+}
+
+fn put_pixel(image: &mut RgbaImage, x: f32, y: f32, color: Rgba<u8>) {
+    let (x, y) = (x.round(), y.round());
+    if x >= 0.0 && y >= 0.0 && (x as u32) < image.width() && (y as u32) < image.height() {
+        image.put_pixel(x as u32, y as u32, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Result<Option<HeadlessOptions>, String> {
+        HeadlessOptions::from_args(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_headless_options_are_parsed() {
+        assert_eq!(args(""), Ok(None), "The window opens");
+        let options = args("--record-boot boot.gif --ticks 10 --size 64").unwrap().unwrap();
+        assert_eq!(options.recording, Some((RecordedView::Boot, PathBuf::from("boot.gif"))));
+        assert_eq!((options.ticks, options.size, options.mandala_dir), (10, 64, None));
+        assert!(args("--record-eeg").is_err());
+        assert!(args("--export-mandalas out --size 0").is_err());
+        assert!(args("--fullscreen").is_err());
+    }
+
+    #[test]
+    fn test_recordings_write_a_frame_per_tick() {
+        let dir = std::env::temp_dir().join(format!("neurova_headless_{}", std::process::id()));
+        let frames = dir.join("eeg");
+        let gif = dir.join("boot.gif");
+        let mut core = Core::new_for_tests();
+        let start = core.tick;
+        record(&mut core, RecordedView::Eeg, &frames, 3, 32).unwrap();
+        assert_eq!(fs::read_dir(&frames).unwrap().count(), 3);
+        let frame = image::open(frames.join("frame_00002.png")).unwrap();
+        assert_eq!((frame.width(), frame.height()), (32, 32));
+
+        record(&mut core, RecordedView::Boot, &gif, 2, 32).unwrap();
+        assert!(fs::read(&gif).unwrap().starts_with(b"GIF89a"));
+        assert_eq!(core.tick, start + 5);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,10 +1,16 @@
+use std::collections::HashSet;
 use std::time::{Instant, Duration};
-use std::sync::{Arc, Mutex};
-use std::thread;
+use std::sync::Arc;
 use wgpu::util::DeviceExt;
 use sysinfo::System;
 
-use agi_core::{Core, conceptual_hierarchy::ConceptNode};
+use agi_core::{Core, CoreHandle, conceptual_hierarchy::ConceptNode};
+use agi_core::activation_record::ActivationRecord;
+use agi_core::eeg::{DEFAULT_SPECTRUM_WINDOW, EegSpectrum};
+use agi_core::entanglement_map::EntanglementGraph;
+use agi_core::core_actor::{PendingReply, TryRecvError};
+use agi_core::hippocampus::RelatedMemory;
+use agi_core::thalamus::QueryType;
 
 
 use winit::{
@@ -18,7 +24,37 @@ use egui_wgpu::Renderer;
 use egui_winit::State as EguiState;
 use egui::{TextureId};
 
+mod concept_graph;
+mod connectome_graph;
+mod entanglement_view;
+mod gpu_connectome;
 mod gui;
+mod headless;
+mod reasoning_trace;
+#[cfg(feature = "tts")]
+mod speaker;
+mod spectrogram;
+
+use concept_graph::{ConceptGraphView, GraphConcept};
+use connectome_graph::{ConnectomeGraph, GraphLayout, OrbitCamera};
+use entanglement_view::EntanglementView;
+use gpu_connectome::{ConnectomeLayout, GpuActivity, GpuConnectome};
+use headless::HeadlessOptions;
+use reasoning_trace::TraceReplay;
+use spectrogram::SpectrogramView;
+
+/// Delay between two ticks of the core actor at startup; it can be changed from the GUI.
+const TICK_INTERVAL_MS: u64 = 10;
+/// Ticks of the GPU connectome per frame.
+const GPU_TICKS_PER_FRAME: u32 = 4;
+/// Potential given to the neurons the core just fired, enough to fire them on the GPU as well.
+const GPU_STIMULUS: f32 = 1.0;
+/// Delay between two requests for the activation record of a prompt, while it is recorded.
+const ACTIVATION_REFRESH: Duration = Duration::from_millis(150);
+/// Delay between two columns of the spectrogram.
+const SPECTRUM_REFRESH: Duration = Duration::from_millis(100);
+/// Delay between two requests for the entanglement graph of the quantum core.
+const ENTANGLEMENT_REFRESH: Duration = Duration::from_millis(250);
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
@@ -42,6 +78,10 @@ enum VisualizationMode {
     BootAnimation,
     EEGPlot,
     MandalaViewer,
+    ConnectomeGraph,
+    ConceptGraph,
+    Spectrogram,
+    EntanglementGraph,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -81,11 +121,52 @@ struct State {
     start_time: Instant,
     last_wakeup_time: Instant,
     // AGI Core and UI State
-    core: Arc<Mutex<Core>>,
+    core: CoreHandle,
+    pending_response: Option<PendingReply<Option<(String, QueryType)>>>,
+    // Reasoning trace of the last prompt, fetched until the core has recorded every tick of it.
+    trace: TraceReplay,
+    pending_activation: Option<PendingReply<Option<ActivationRecord>>>,
+    next_activation_request: Option<Instant>,
+    // Simulation controls
+    step_ticks: u64,
+    tick_interval_ms: u64,
+    // EEG spectrogram
+    spectrogram: SpectrogramView,
+    pending_spectrum: Option<PendingReply<EegSpectrum>>,
+    last_spectrum_request: Instant,
+    // Entanglement graph of the quantum core
+    entanglement: EntanglementView,
+    pending_entanglement: Option<PendingReply<EntanglementGraph>>,
+    last_entanglement_request: Instant,
+    concept_names: Vec<String>,
+    concept_names_count: usize,
+    pending_concept_names: Option<PendingReply<Vec<String>>>,
+    pending_concept: Option<PendingReply<Option<ConceptNode>>>,
+    // Concept graph
+    concept_graph: ConceptGraphView,
+    concept_graph_count: usize,
+    pending_concept_graph: Option<PendingReply<Vec<GraphConcept>>>,
+    concept_memories: Vec<(u64, String)>,
+    pending_concept_memories: Option<PendingReply<Vec<(u64, String)>>>,
+    // Memory browser
+    memory_list: Vec<(u64, String)>,
+    memory_list_count: usize,
+    pending_memory_list: Option<PendingReply<Vec<(u64, String)>>>,
+    related_memories: Option<(u64, Vec<RelatedMemory>)>,
+    pending_related: Option<(u64, PendingReply<Option<Vec<RelatedMemory>>>)>,
     columns_data: Vec<Column>,
+    // The connectome mirrored on the GPU, None when the device cannot hold it.
+    gpu_connectome: Option<GpuConnectome>,
+    gpu_activity: GpuActivity,
+    // The connectome in 3D, None when it has no neurons.
+    connectome_graph: Option<ConnectomeGraph>,
+    camera: OrbitCamera,
     prompt_buffer: String,
     agi_response: String, // Still used for the last raw response
     chat_history: Vec<String>,
+    // Reads the last answer aloud.
+    #[cfg(feature = "tts")]
+    speaker: speaker::Speaker,
 }
 
 impl State {
@@ -108,7 +189,12 @@ impl State {
             &wgpu::DeviceDescriptor {
                 label: None,
                 required_features: wgpu::Features::empty(),
-                required_limits: wgpu::Limits::default(),
+                // Large connectomes need the biggest storage buffers the adapter offers.
+                required_limits: wgpu::Limits {
+                    max_storage_buffer_binding_size: adapter.limits().max_storage_buffer_binding_size,
+                    max_buffer_size: adapter.limits().max_buffer_size,
+                    ..wgpu::Limits::default()
+                },
             },
             None,
         ).await.unwrap();
@@ -141,54 +227,19 @@ impl State {
             source: wgpu::ShaderSource::Wgsl(include_str!("eeg_shader.wgsl").into()),
         });
 
-        let core = { 
-            let mut core = Core::new(None);
+        let core = {
+            let mut core = seed_core();
             core.set_wakeup_stages(5); // Start the wakeup sequence
-            // --- AGI Consciousness Seeding ---
-            // Load foundational knowledge from external files.
-            println!("--- Seeding AGI consciousness... ---");
-
-            // Use CARGO_MANIFEST_DIR to create robust paths to the knowledge files.
-            let manifest_dir = env!("CARGO_MANIFEST_DIR");
-            let base_path = std::path::Path::new(manifest_dir).parent().unwrap();
-
-            // 1. Load identity
-            let identity_path = base_path.join("identity.txt");
-            if let Err(e) = core.learn_from_file(identity_path.to_str().unwrap()) {
-                eprintln!("FATAL: Could not load identity.txt from {:?}: {}", identity_path, e);
-            } else {
-                println!("[OK] Identity loaded.");
-            }
 
-            // 2. Load knowledge base
-            let knowledge_path = base_path.join("knowledge.txt");
-            if let Err(e) = core.learn_from_file(knowledge_path.to_str().unwrap()) {
-                eprintln!("FATAL: Could not load knowledge.txt from {:?}: {}", knowledge_path, e);
-            } else {
-                println!("[OK] Knowledge base loaded.");
-            }
-            
-            // 3. Assimilate all loaded knowledge into the holographic memory
-            println!("--- Assimilating knowledge... ---");
-            core.assimilate_knowledge();
-            println!("[OK] Knowledge assimilated into holographic memory.");
-            Arc::new(Mutex::new(core))
+            // Spawn the AGI actor thread. It ticks every 10 ms between the GUI's requests.
+            CoreHandle::spawn(core, Some(Duration::from_millis(TICK_INTERVAL_MS))).unwrap_or_else(|e| {
+                eprintln!("FATAL: {}", e);
+                std::process::exit(1);
+            })
         };
 
-        // Spawn AGI thread
-        let agi_core_clone = Arc::clone(&core);
-        thread::spawn(move || {
-            loop {
-                let mut agi_core = agi_core_clone.lock().unwrap();
-                agi_core.tick();
-                // We can drop the lock here to allow the main thread to access the core
-                drop(agi_core);
-                thread::sleep(Duration::from_millis(10)); // Tick rate
-            }
-        });
-
         // Dynamically create columns based on the actual number of neurons loaded.
-        let num_columns = core.lock().unwrap().connectome.neurons.len();
+        let num_columns = core.latest_snapshot().neuron_potentials.len();
         let columns_data: Vec<Column> = (0..num_columns).map(|i| {
             let angle = (i as f32 / num_columns as f32) * 2.0 * std::f32::consts::PI;
             let (sin, cos) = angle.sin_cos();
@@ -294,6 +345,42 @@ impl State {
             usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
         });
 
+        // Mirror the core's connectome on the GPU: the EEG plot follows its activity.
+        let gpu_connectome = match core.exec_blocking(|core| ConnectomeLayout::new(&core.connectome)) {
+            Ok(layout) => match GpuConnectome::new(&device, &layout, EEG_NUM_POINTS) {
+                Ok(gpu_connectome) => {
+                    println!("[OK] Connectome uploaded to the GPU ({} neurons).", layout.neuron_count());
+                    Some(gpu_connectome)
+                }
+                Err(e) => {
+                    eprintln!("GPU connectome disabled: {}", e);
+                    None
+                }
+            },
+            Err(e) => {
+                eprintln!("GPU connectome disabled: {}", e);
+                None
+            }
+        };
+
+        // Lay out the connectome in 3D for the graph viewer. The force-directed layout runs here,
+        // not on the core actor.
+        let connectome_graph = match core.exec_blocking(|core| GraphLayout::new(&core.connectome)) {
+            Ok(mut layout) => {
+                layout.relax();
+                let graph = ConnectomeGraph::new(&device, &config, &layout);
+                match &graph {
+                    Some(_) => println!("[OK] Connectome graph laid out ({} neurons, {} synapses).", layout.neuron_count(), layout.synapse_count()),
+                    None => eprintln!("Connectome graph disabled: the connectome has no neurons"),
+                }
+                graph
+            }
+            Err(e) => {
+                eprintln!("Connectome graph disabled: {}", e);
+                None
+            }
+        };
+
         // Create bind groups
         let boot_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &boot_bind_group_layout,
@@ -426,10 +513,42 @@ impl State {
             start_time: Instant::now(),
             last_wakeup_time: Instant::now(),
             core,
+            pending_response: None,
+            trace: TraceReplay::default(),
+            pending_activation: None,
+            next_activation_request: None,
+            step_ticks: 1,
+            tick_interval_ms: TICK_INTERVAL_MS,
+            spectrogram: SpectrogramView::default(),
+            pending_spectrum: None,
+            last_spectrum_request: Instant::now(),
+            entanglement: EntanglementView::default(),
+            pending_entanglement: None,
+            last_entanglement_request: Instant::now(),
+            concept_names: Vec::new(),
+            concept_names_count: usize::MAX,
+            pending_concept_names: None,
+            pending_concept: None,
+            concept_graph: ConceptGraphView::default(),
+            concept_graph_count: usize::MAX,
+            pending_concept_graph: None,
+            concept_memories: Vec::new(),
+            pending_concept_memories: None,
+            memory_list: Vec::new(),
+            memory_list_count: usize::MAX,
+            pending_memory_list: None,
+            related_memories: None,
+            pending_related: None,
             columns_data,
+            gpu_connectome,
+            gpu_activity: GpuActivity::default(),
+            connectome_graph,
+            camera: OrbitCamera::default(),
             prompt_buffer: String::new(),
             chat_history: Vec::new(),
             agi_response: "AGI is initializing...".to_string(),
+            #[cfg(feature = "tts")]
+            speaker: speaker::Speaker::default(),
         }
     }
 
@@ -441,6 +560,9 @@ impl State {
             self.surface.configure(&self.device, &self.config);
             self.uniforms.resolution_x = new_size.width as f32;
             self.uniforms.resolution_y = new_size.height as f32;
+            if let Some(graph) = &mut self.connectome_graph {
+                graph.resize(&self.device, new_size.width, new_size.height);
+            }
         }
     }
 
@@ -468,6 +590,10 @@ impl State {
                     KeyCode::KeyB => self.mode = VisualizationMode::BootAnimation,
                     KeyCode::KeyE => self.mode = VisualizationMode::EEGPlot,
                     KeyCode::KeyM => self.mode = VisualizationMode::MandalaViewer,
+                    KeyCode::KeyC => self.mode = VisualizationMode::ConnectomeGraph,
+                    KeyCode::KeyH => self.mode = VisualizationMode::ConceptGraph,
+                    KeyCode::KeyS => self.mode = VisualizationMode::Spectrogram,
+                    KeyCode::KeyQ => self.mode = VisualizationMode::EntanglementGraph,
                     _ => return false, // Return false for unhandled keys
                 }
                 true // Return true because we handled the input
@@ -477,66 +603,131 @@ impl State {
     }
 
     fn update(&mut self) {
-        // Check for new AGI response and clear it from the core to prevent spam
-        // Lock the core once and perform all necessary updates
         // --- High-Priority: AGI Response Handling ---
-        // We use a blocking lock here to ensure we *always* process a response if one is ready.
-        // This prevents race conditions where the UI thread misses a response because the AGI thread has the lock.
-        {
-            let mut core = self.core.lock().unwrap();
-            if let Some(response) = core.get_response() {
-                if !response.is_empty() {
-                    let formatted_response = format!("AGI: {}", response);
-                    if self.chat_history.last().map_or(true, |last| last != &formatted_response) {
-                        self.chat_history.push(formatted_response);
+        // Prompts are queued on the core actor; we poll for the answer without ever blocking the frame.
+        if let Some(pending) = self.pending_response.as_mut() {
+            match pending.try_recv() {
+                Ok(Some((response, _query_type))) => {
+                    if !response.is_empty() {
+                        self.chat_history.push(format!("AGI: {}", response));
                         self.agi_response = response;
                     }
-                    // This is now redundant as get_response consumes the result, but kept for clarity.
-                    core.clear_response(); 
+                    self.pending_response = None;
+                    self.next_activation_request = Some(Instant::now());
                 }
+                Ok(None) | Err(TryRecvError::Closed) => self.pending_response = None,
+                Err(TryRecvError::Empty) => {}
             }
         }
 
+        self.poll_concept_requests();
+        self.poll_memory_requests();
+        self.poll_activation_record();
+        #[cfg(feature = "tts")]
+        self.speaker.poll();
+        self.trace.advance();
+
         // --- Lower-Priority: Visualization Updates ---
-        // We use a non-blocking `try_lock` here. If the core is busy, we'll just skip
-        // updating the visuals for one frame. This keeps the UI responsive.
+        // The actor publishes a snapshot after every tick; reading it never waits on the core.
         self.uniforms.time = self.start_time.elapsed().as_secs_f32();
+        let snapshot = self.core.latest_snapshot();
 
         match self.app_state {
             AppState::WakingUp => {
-                if self.last_wakeup_time.elapsed() >= std::time::Duration::from_millis(200) {
-                    let mut core = self.core.lock().unwrap();
-                    if !core.advance_wakeup_stage() {
-                        self.app_state = AppState::Running;
-                        self.mode = VisualizationMode::MandalaViewer; // Switch to Mandala view after wakeup
-                        println!("Wakeup sequence complete. Switching to Mandala viewer.");
-                    }
+                // The stage is read from the snapshot; the next one is requested without waiting
+                // for the actor, and shows up in a later snapshot.
+                if snapshot.wakeup_stage >= snapshot.wakeup_stages {
+                    self.app_state = AppState::Running;
+                    self.mode = VisualizationMode::MandalaViewer; // Switch to Mandala view after wakeup
+                    println!("Wakeup sequence complete. Switching to Mandala viewer.");
+                } else if self.last_wakeup_time.elapsed() >= std::time::Duration::from_millis(200)
+                    && self.core.try_submit(|core| core.advance_wakeup_stage()).is_ok()
+                {
                     self.last_wakeup_time = Instant::now();
                 }
                 // Update awareness level for the shader
-                self.uniforms.awareness_level = self.core.lock().unwrap().get_awakening_level();
+                self.uniforms.awareness_level = snapshot.awakening_level;
             }
             AppState::Running => {
-                if let Ok(core) = self.core.try_lock() {
-                    match self.mode {
-                        VisualizationMode::BootAnimation => {
-                            // This mode should not be active in Running state, but as a fallback:
-                            for (i, neuron) in core.connectome.neurons.iter().enumerate() {
-                                if let Some(column) = self.columns_data.get_mut(i) {
-                                    column.state = if neuron.firing {
-                                        1.0
-                                    } else {
-                                        (column.state * 0.95).max(neuron.potential / 1.0)
-                                    };
+                // Refresh the memory browser whenever memories were added or forgotten. While the
+                // queue of the actor is full the request is sent again on a later frame.
+                if snapshot.memories_in_hippocampus != self.memory_list_count && self.pending_memory_list.is_none() {
+                    self.pending_memory_list = self.core.try_submit(|core| {
+                        core.hippocampus.holographic_memory.iter().map(|memory| (memory.id, memory.text.clone())).collect()
+                    }).ok();
+                    if self.pending_memory_list.is_some() {
+                        self.memory_list_count = snapshot.memories_in_hippocampus;
+                    }
+                }
+                // The GPU connectome runs a few ticks per frame, driven by what the core fires.
+                if let Some(gpu_connectome) = &self.gpu_connectome {
+                    gpu_connectome.step(&self.device, &self.queue, GPU_TICKS_PER_FRAME, &snapshot.fired_neurons, GPU_STIMULUS);
+                    self.gpu_activity = gpu_connectome.read_activity(&self.device, &self.queue);
+                }
+                match self.mode {
+                    VisualizationMode::BootAnimation | VisualizationMode::EntanglementGraph => {
+                        // The entanglement graph is drawn over the ring of the boot animation.
+                        if self.mode == VisualizationMode::EntanglementGraph {
+                            self.poll_entanglement_graph();
+                        }
+                        // The boot animation should not be active in Running state, but as a fallback:
+                        let fired: HashSet<u64> = snapshot.fired_neurons.iter().copied().collect();
+                        for (i, potential) in snapshot.neuron_potentials.iter().enumerate() {
+                            if let Some(column) = self.columns_data.get_mut(i) {
+                                column.state = if fired.contains(&(i as u64)) {
+                                    1.0
+                                } else {
+                                    (column.state * 0.95).max(*potential / 1.0)
+                                };
+                            }
+                        }
+                        self.queue.write_buffer(&self.column_buffer, 0, bytemuck::cast_slice(&self.columns_data));
+                    }
+                    VisualizationMode::EEGPlot => {
+                        let potentials = if self.gpu_connectome.is_some() { &self.gpu_activity.potentials } else { &snapshot.neuron_potentials };
+                        let eeg_data: Vec<f32> = potentials.iter().take(self.eeg_num_points as usize).copied().collect();
+                        self.queue.write_buffer(&self.eeg_data_buffer, 0, bytemuck::cast_slice(&eeg_data));
+                    }
+                    VisualizationMode::MandalaViewer => {
+                        // Refresh the concept list whenever the hierarchy has grown.
+                        if snapshot.concepts_in_hierarchy != self.concept_names_count && self.pending_concept_names.is_none() {
+                            self.pending_concept_names = self.core.try_submit(|core| core.conceptual_hierarchy.get_all_concept_names()).ok();
+                            if self.pending_concept_names.is_some() {
+                                self.concept_names_count = snapshot.concepts_in_hierarchy;
+                            }
+                        }
+                    }
+                    VisualizationMode::ConceptGraph => {
+                        // Refresh the graph whenever the hierarchy has grown.
+                        if snapshot.concepts_in_hierarchy != self.concept_graph_count && self.pending_concept_graph.is_none() {
+                            self.pending_concept_graph = self.core.try_submit(|core| GraphConcept::collect(&core.conceptual_hierarchy)).ok();
+                            if self.pending_concept_graph.is_some() {
+                                self.concept_graph_count = snapshot.concepts_in_hierarchy;
+                            }
+                        }
+                    }
+                    VisualizationMode::Spectrogram => {
+                        if let Some(pending) = self.pending_spectrum.as_mut() {
+                            match pending.try_recv() {
+                                Ok(spectrum) => {
+                                    self.spectrogram.push(spectrum);
+                                    self.pending_spectrum = None;
                                 }
+                                Err(TryRecvError::Closed) => self.pending_spectrum = None,
+                                Err(TryRecvError::Empty) => {}
                             }
-                            self.queue.write_buffer(&self.column_buffer, 0, bytemuck::cast_slice(&self.columns_data));
                         }
-                        VisualizationMode::EEGPlot => {
-                            let eeg_data = core.get_eeg_potentials(self.eeg_num_points as usize);
-                            self.queue.write_buffer(&self.eeg_data_buffer, 0, bytemuck::cast_slice(&eeg_data));
+                        if self.pending_spectrum.is_none() && self.last_spectrum_request.elapsed() >= SPECTRUM_REFRESH {
+                            self.last_spectrum_request = Instant::now();
+                            self.pending_spectrum = self.core.try_submit(|core| core.get_eeg_spectrum(DEFAULT_SPECTRUM_WINDOW)).ok();
+                        }
+                    }
+                    VisualizationMode::ConnectomeGraph => {
+                        if let Some(graph) = &mut self.connectome_graph {
+                            graph.update_activity(&self.queue, &snapshot.neuron_potentials, &snapshot.fired_neurons);
+                            let aspect = self.size.width as f32 / self.size.height.max(1) as f32;
+                            graph.set_camera(&self.queue, &self.camera, aspect);
                         }
-                        VisualizationMode::MandalaViewer => {}
                     }
                 }
             }
@@ -545,6 +736,151 @@ impl State {
         self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[self.uniforms]));
     }
 
+    /// Fetches the entanglement graph of the quantum core every `ENTANGLEMENT_REFRESH`.
+    fn poll_entanglement_graph(&mut self) {
+        if let Some(pending) = self.pending_entanglement.as_mut() {
+            match pending.try_recv() {
+                Ok(graph) => {
+                    self.entanglement.set_graph(graph);
+                    self.pending_entanglement = None;
+                }
+                Err(TryRecvError::Closed) => self.pending_entanglement = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        if self.pending_entanglement.is_none() && self.last_entanglement_request.elapsed() >= ENTANGLEMENT_REFRESH {
+            self.last_entanglement_request = Instant::now();
+            self.pending_entanglement = self.core.try_submit(|core| core.get_entanglement_graph()).ok();
+        }
+    }
+
+    /// Asks the core actor for the memories clustered around `memory_id` ("see related" action).
+    fn request_related_memories(&mut self, memory_id: u64) {
+        self.pending_related = self
+            .core
+            .try_submit(move |core| core.related_memories(memory_id, 5))
+            .ok()
+            .map(|pending| (memory_id, pending));
+    }
+
+    /// Selects a concept of the graph: its trace and its memories are fetched from the core actor
+    /// (see `poll_concept_requests`).
+    fn select_graph_concept(&mut self, id: u64) {
+        self.concept_graph.selected = Some(id);
+        self.selected_concept_name = self.concept_graph.concept(id).map(|concept| concept.name.clone());
+        self.pending_concept = self.core.try_submit(move |core| core.conceptual_hierarchy.get_concept(id).cloned()).ok();
+        self.pending_concept_memories = self
+            .core
+            .try_submit(move |core| {
+                core.hippocampus
+                    .holographic_memory
+                    .iter()
+                    .filter(|memory| memory.concepts.contains(&id))
+                    .map(|memory| (memory.id, memory.text.clone()))
+                    .collect()
+            })
+            .ok();
+    }
+
+    /// Fetches the activation record of the last prompt while the core is still recording it.
+    fn poll_activation_record(&mut self) {
+        if let Some(pending) = self.pending_activation.as_mut() {
+            match pending.try_recv() {
+                Ok(record) => {
+                    self.pending_activation = None;
+                    if let Some(record) = record {
+                        if !record.is_complete() {
+                            self.next_activation_request = Some(Instant::now() + ACTIVATION_REFRESH);
+                        }
+                        self.trace.set_record(record);
+                    }
+                }
+                Err(TryRecvError::Closed) => self.pending_activation = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        if self.pending_activation.is_none() && self.next_activation_request.is_some_and(|at| Instant::now() >= at) {
+            self.pending_activation = self.core.try_submit(|core| core.activation_record().cloned()).ok();
+            if self.pending_activation.is_some() {
+                self.next_activation_request = None;
+            }
+        }
+    }
+
+    /// Collects the answers to memory browser queries sent to the core actor by the GUI.
+    fn poll_memory_requests(&mut self) {
+        if let Some(pending) = self.pending_memory_list.as_mut() {
+            match pending.try_recv() {
+                Ok(memories) => {
+                    self.memory_list = memories;
+                    self.pending_memory_list = None;
+                }
+                Err(TryRecvError::Closed) => self.pending_memory_list = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+
+        if let Some((memory_id, pending)) = self.pending_related.as_mut() {
+            match pending.try_recv() {
+                Ok(related) => {
+                    self.related_memories = related.map(|related| (*memory_id, related));
+                    self.pending_related = None;
+                }
+                Err(TryRecvError::Closed) => self.pending_related = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+    }
+
+    /// Collects the answers to concept queries sent to the core actor by the GUI.
+    fn poll_concept_requests(&mut self) {
+        if let Some(pending) = self.pending_concept_names.as_mut() {
+            match pending.try_recv() {
+                Ok(names) => {
+                    self.concept_names = names;
+                    self.pending_concept_names = None;
+                }
+                Err(TryRecvError::Closed) => self.pending_concept_names = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+
+        if let Some(pending) = self.pending_concept_graph.as_mut() {
+            match pending.try_recv() {
+                Ok(concepts) => {
+                    self.concept_graph.set_concepts(concepts);
+                    self.pending_concept_graph = None;
+                }
+                Err(TryRecvError::Closed) => self.pending_concept_graph = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+
+        if let Some(pending) = self.pending_concept_memories.as_mut() {
+            match pending.try_recv() {
+                Ok(memories) => {
+                    self.concept_memories = memories;
+                    self.pending_concept_memories = None;
+                }
+                Err(TryRecvError::Closed) => self.pending_concept_memories = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+
+        if let Some(pending) = self.pending_concept.as_mut() {
+            match pending.try_recv() {
+                Ok(concept) => {
+                    self.pending_concept = None;
+                    if let Some(concept) = concept {
+                        self.update_mandala_texture(&concept);
+                    }
+                }
+                Err(TryRecvError::Closed) => self.pending_concept = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+    }
+
     fn update_mandala_texture(&mut self, concept: &ConceptNode) {
         const TEXTURE_SIZE: u32 = 512;
         let trace = &concept.trace;
@@ -661,6 +997,12 @@ impl State {
         // Egui: Update buffers
         self.egui_renderer.update_buffers(&self.device, &self.queue, &mut encoder, &paint_jobs, &screen_descriptor);
 
+        // The connectome graph has a pass of its own, with a depth buffer; egui is drawn over it.
+        let graph = self.connectome_graph.as_ref().filter(|_| self.mode == VisualizationMode::ConnectomeGraph);
+        if let Some(graph) = graph {
+            graph.render(&mut encoder, &view);
+        }
+
         {
             // Main render pass
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -669,7 +1011,10 @@ impl State {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 }),
+                        load: match graph {
+                            Some(_) => wgpu::LoadOp::Load,
+                            None => wgpu::LoadOp::Clear(wgpu::Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 }),
+                        },
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -680,7 +1025,7 @@ impl State {
 
             // Draw the background visualization first
             match self.mode {
-                VisualizationMode::BootAnimation => {
+                VisualizationMode::BootAnimation | VisualizationMode::EntanglementGraph => {
                     render_pass.set_pipeline(&self.boot_pipeline);
                     render_pass.set_bind_group(0, &self.boot_bind_group, &[]);
                     render_pass.draw(0..6, 0..1); // Draw a quad
@@ -692,6 +1037,10 @@ impl State {
                 }
                 // For MandalaViewer, the background is clear, and the mandala is in the UI
                 VisualizationMode::MandalaViewer => {}
+                // Already drawn by its own pass
+                VisualizationMode::ConnectomeGraph => {}
+                // Painted by egui
+                VisualizationMode::ConceptGraph | VisualizationMode::Spectrogram => {}
             }
 
             // Draw Egui on top
@@ -707,6 +1056,46 @@ impl State {
 
 
 
+/// A core with the identity and the knowledge base learned and assimilated.
+fn seed_core() -> Core {
+    let mut core = Core::new(None).unwrap_or_else(|e| {
+        eprintln!("FATAL: {}", e);
+        std::process::exit(1);
+    });
+    // --- AGI Consciousness Seeding ---
+    // Load foundational knowledge from external files.
+    println!("--- Seeding AGI consciousness... ---");
+    if let Err(e) = core.awaken(agi_core::bulk_assimilation::print_files()) {
+        eprintln!("FATAL: Could not awaken: {}", e);
+    }
+
+    // Use CARGO_MANIFEST_DIR to create robust paths to the knowledge files.
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let base_path = std::path::Path::new(manifest_dir).parent().unwrap();
+
+    // 1. Load identity
+    let identity_path = base_path.join("identity.txt");
+    if let Err(e) = core.learn_from_file(identity_path.to_str().unwrap()) {
+        eprintln!("FATAL: Could not load identity.txt from {:?}: {}", identity_path, e);
+    } else {
+        println!("[OK] Identity loaded.");
+    }
+
+    // 2. Load knowledge base
+    let knowledge_path = base_path.join("knowledge.txt");
+    if let Err(e) = core.learn_from_file(knowledge_path.to_str().unwrap()) {
+        eprintln!("FATAL: Could not load knowledge.txt from {:?}: {}", knowledge_path, e);
+    } else {
+        println!("[OK] Knowledge base loaded.");
+    }
+    
+    // 3. Assimilate all loaded knowledge into the holographic memory
+    println!("--- Assimilating knowledge... ---");
+    core.assimilate_knowledge();
+    println!("[OK] Knowledge assimilated into holographic memory.");
+    core
+}
+
 pub async fn run() {
     let event_loop = EventLoop::new().unwrap();
     let window = Arc::new(WindowBuilder::new().build(&event_loop).unwrap());
@@ -760,6 +1149,19 @@ fn main() {
 
     println!("RAM Usage: {} MB / {} MB", used_memory_mb, total_memory_mb);
 
-    pollster::block_on(run());
+    // Rendering to files needs neither a window nor a GPU.
+    match HeadlessOptions::from_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => {
+            if let Err(e) = headless::run(&options, seed_core()) {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Ok(None) => pollster::block_on(run()),
+        Err(e) => {
+            eprintln!("ERROR: {}\n\n{}", e, headless::USAGE);
+            std::process::exit(2);
+        }
+    }
 }
 
@@ -0,0 +1,156 @@
+//! reasoning_trace.rs - The wave set off by the last prompt, drawn over the Boot and EEG views.
+//!
+//! The core records the neurons stimulated by a prompt and those fired at each of the following
+//! ticks (see `agi_core::activation_record`). The replay walks through these steps: the
+//! stimulated neurons stay marked, the neurons fired at the current step light up, and the
+//! pathway the wave took so far is drawn between them, the last synapses brightest.
+
+use std::time::{Duration, Instant};
+
+use agi_core::activation_record::ActivationRecord;
+use egui::{Color32, Pos2, Rect, Stroke, Vec2};
+
+/// Time spent on each step of the replay.
+const STEP_DURATION: Duration = Duration::from_millis(80);
+/// Steps over which a synapse of the pathway fades out.
+const PATHWAY_FADE_STEPS: usize = 8;
+
+const STIMULATED_COLOR: Color32 = Color32::from_rgb(230, 180, 60);
+const FIRED_COLOR: Color32 = Color32::from_rgb(255, 240, 140);
+
+/// Where neuron `id` of the `neuron_count` neurons of the Boot view's ring is drawn on `screen`.
+pub fn ring_position(id: usize, neuron_count: usize, screen: Rect) -> Pos2 {
+    let angle = id as f32 / neuron_count as f32 * std::f32::consts::TAU;
+    // The ring is drawn in clip space, whose y axis points up.
+    screen.center() + Vec2::new(angle.cos() * screen.width(), -angle.sin() * screen.height()) * 0.45 * 0.5
+}
+
+#[derive(Debug)]
+pub struct TraceReplay {
+    record: Option<ActivationRecord>,
+    pub visible: bool,
+    pub step: usize,
+    pub playing: bool,
+    last_step: Instant,
+}
+
+impl Default for TraceReplay {
+    fn default() -> Self {
+        Self { record: None, visible: true, step: 0, playing: false, last_step: Instant::now() }
+    }
+}
+
+impl TraceReplay {
+    /// Replaces the record. The replay starts over for a new prompt, and carries on while the
+    /// record of the same prompt fills up.
+    pub fn set_record(&mut self, record: ActivationRecord) {
+        if self.record.as_ref().map(|current| current.response_id) != Some(record.response_id) {
+            self.step = 0;
+            self.playing = true;
+            self.last_step = Instant::now();
+        }
+        self.record = Some(record);
+    }
+
+    /// The number of steps that can be shown.
+    pub fn steps(&self) -> usize {
+        self.record.as_ref().map_or(0, ActivationRecord::steps)
+    }
+
+    /// Moves the replay on by one step every `STEP_DURATION`; it stops at the last recorded step.
+    pub fn advance(&mut self) {
+        if !self.playing || self.last_step.elapsed() < STEP_DURATION {
+            return;
+        }
+        self.last_step = Instant::now();
+        if self.step + 1 < self.steps() {
+            self.step += 1;
+        } else if self.record.as_ref().is_some_and(ActivationRecord::is_complete) {
+            self.playing = false;
+        }
+    }
+
+    /// Starts the replay from the stimulation.
+    pub fn replay(&mut self) {
+        self.step = 0;
+        self.playing = true;
+        self.last_step = Instant::now();
+    }
+
+    /// Draws the trace over the ring of the Boot view, whose `neuron_count` neurons are spread
+    /// on a circle of 0.45 times the size of `screen` (see the columns of `State::new`).
+    pub fn paint_ring(&self, painter: &egui::Painter, screen: Rect, neuron_count: usize) {
+        let Some(record) = self.record.as_ref().filter(|_| self.visible && neuron_count > 0) else {
+            return;
+        };
+        let position = |id: u64| ring_position(id as usize, neuron_count, screen);
+
+        for synapse in record.pathway.iter().filter(|synapse| synapse.step <= self.step) {
+            let stroke = Stroke::new(1.5, FIRED_COLOR.gamma_multiply(pathway_alpha(self.step - synapse.step)));
+            painter.line_segment([position(synapse.from), position(synapse.to)], stroke);
+        }
+        for &id in &record.stimulated {
+            painter.circle_stroke(position(id), 6.0, Stroke::new(2.0, STIMULATED_COLOR));
+        }
+        for &id in record.fired_at(self.step) {
+            painter.circle_filled(position(id), 4.0, FIRED_COLOR);
+        }
+    }
+
+    /// Draws the trace over the EEG view, where the first `point_count` neurons are plotted
+    /// from the left of `screen` to its right.
+    pub fn paint_eeg(&self, painter: &egui::Painter, screen: Rect, point_count: usize) {
+        let Some(record) = self.record.as_ref().filter(|_| self.visible && point_count > 1) else {
+            return;
+        };
+        let x = |id: u64| screen.left() + id as f32 / (point_count - 1) as f32 * screen.width();
+        let plotted = |id: &&u64| (**id as usize) < point_count;
+
+        for &id in record.stimulated.iter().filter(plotted) {
+            let bottom = Pos2::new(x(id), screen.bottom());
+            painter.line_segment([bottom, bottom - Vec2::new(0.0, 24.0)], Stroke::new(2.0, STIMULATED_COLOR));
+        }
+        for &id in record.fired_at(self.step).iter().filter(plotted) {
+            painter.line_segment([Pos2::new(x(id), screen.top()), Pos2::new(x(id), screen.bottom())], Stroke::new(1.0, FIRED_COLOR.gamma_multiply(0.6)));
+        }
+    }
+
+    /// The timeline of the replay: a slider over the steps and a play/pause/replay button.
+    pub fn controls(&mut self, ui: &mut egui::Ui) {
+        let Some(record) = &self.record else {
+            ui.label("Send a prompt to trace its activation wave.");
+            return;
+        };
+        ui.label(format!("Prompt: {}", record.prompt));
+        ui.label(format!("{} neurons stimulated, {} synapses in the pathway.", record.stimulated.len(), record.pathway.len()));
+        ui.checkbox(&mut self.visible, "Show the trace");
+
+        let last_step = self.steps().saturating_sub(1);
+        ui.horizontal(|ui| {
+            let label = if self.playing {
+                "Pause"
+            } else if self.step >= last_step {
+                "Replay"
+            } else {
+                "Play"
+            };
+            if ui.button(label).clicked() {
+                if self.playing {
+                    self.playing = false;
+                } else if self.step >= last_step {
+                    self.replay();
+                } else {
+                    self.playing = true;
+                }
+            }
+            if ui.add(egui::Slider::new(&mut self.step, 0..=last_step).text("tick")).dragged() {
+                self.playing = false;
+            }
+        });
+    }
+}
+
+/// The opacity of a synapse of the pathway crossed `age` steps ago.
+fn pathway_alpha(age: usize) -> f32 {
+    1.0 - age.min(PATHWAY_FADE_STEPS) as f32 / (PATHWAY_FADE_STEPS + 1) as f32
+}
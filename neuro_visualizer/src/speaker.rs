@@ -0,0 +1,76 @@
+//! speaker.rs - Plays the answers aloud (feature `tts`).
+//!
+//! The core prepares the utterance with the voice of the active personality profile (see
+//! `Core::utterance`); the engine and the playback then run on a thread of their own, so that
+//! neither the core actor nor the frame waits for the audio.
+
+use std::io::Cursor;
+use std::sync::mpsc::{self, Receiver, TryRecvError as ChannelEmpty};
+
+use agi_core::CoreHandle;
+use agi_core::core_actor::{PendingReply, TryRecvError};
+use agi_core::speech::Utterance;
+
+#[derive(Default)]
+pub struct Speaker {
+    pending: Option<PendingReply<Utterance>>,
+    // Ends with the playback, with the error that stopped it if any.
+    playing: Option<Receiver<Result<(), String>>>,
+    pub last_error: Option<String>,
+}
+
+impl Speaker {
+    pub fn is_busy(&self) -> bool {
+        self.pending.is_some() || self.playing.is_some()
+    }
+
+    /// Asks the core for the utterance of `text`; it is played once `poll` collects it.
+    pub fn speak(&mut self, core: &CoreHandle, text: String) {
+        match core.try_submit(move |core| core.utterance(&text)) {
+            Ok(pending) => {
+                self.last_error = None;
+                self.pending = Some(pending);
+            }
+            Err(e) => self.last_error = Some(e.to_string()),
+        }
+    }
+
+    pub fn poll(&mut self) {
+        if let Some(pending) = self.pending.as_mut() {
+            match pending.try_recv() {
+                Ok(utterance) => {
+                    self.pending = None;
+                    self.playing = Some(play(utterance));
+                }
+                Err(TryRecvError::Closed) => self.pending = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        if let Some(playing) = self.playing.as_ref() {
+            match playing.try_recv() {
+                Ok(result) => {
+                    self.last_error = result.err();
+                    self.playing = None;
+                }
+                Err(ChannelEmpty::Disconnected) => self.playing = None,
+                Err(ChannelEmpty::Empty) => {}
+            }
+        }
+    }
+}
+
+/// Synthesizes and plays `utterance` on a new thread.
+fn play(utterance: Utterance) -> Receiver<Result<(), String>> {
+    let (done, finished) = mpsc::channel();
+    std::thread::spawn(move || {
+        let played = utterance.speak().map_err(|e| e.to_string()).and_then(|speech| {
+            let (_stream, output) = rodio::OutputStream::try_default().map_err(|e| e.to_string())?;
+            let sink = rodio::Sink::try_new(&output).map_err(|e| e.to_string())?;
+            sink.append(rodio::Decoder::new(Cursor::new(speech.wav)).map_err(|e| e.to_string())?);
+            sink.sleep_until_end();
+            Ok(())
+        });
+        let _ = done.send(played);
+    });
+    finished
+}
@@ -0,0 +1,81 @@
+//! spectrogram.rs - The spectrum of the EEG over time.
+//!
+//! Each spectrum fetched from the core (see `Core::get_eeg_spectrum`) becomes a column of the
+//! spectrogram, low frequencies at the bottom, its bins coloured by their power on a log scale.
+//! Bars above it show the share of the pseudo delta/theta/alpha/beta bands in the last spectrum,
+//! so that reasoning (a prompt sets off a wave, see `reasoning_trace`) can be told from idling.
+
+use std::collections::VecDeque;
+
+use agi_core::eeg::{BandPower, EegSpectrum};
+use egui::{Align2, Color32, FontId, Pos2, Rect, Sense, Stroke, Vec2};
+
+/// Spectra kept, the oldest on the left.
+const MAX_COLUMNS: usize = 240;
+const BAND_BAR_HEIGHT: f32 = 18.0;
+
+#[derive(Debug, Default)]
+pub struct SpectrogramView {
+    columns: VecDeque<Vec<f32>>,
+    bands: BandPower,
+}
+
+impl SpectrogramView {
+    pub fn push(&mut self, spectrum: EegSpectrum) {
+        if spectrum.window == 0 {
+            return;
+        }
+        if self.columns.len() == MAX_COLUMNS {
+            self.columns.pop_front();
+        }
+        // The DC bin is the mean, removed before the analysis.
+        self.columns.push_back(spectrum.power.iter().skip(1).map(|power| power.ln_1p()).collect());
+        self.bands = spectrum.bands;
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui) {
+        let text_color = ui.visuals().text_color();
+        let bands = [("delta", self.bands.delta), ("theta", self.bands.theta), ("alpha", self.bands.alpha), ("beta", self.bands.beta)];
+        for (name, share) in bands {
+            ui.horizontal(|ui| {
+                ui.add_sized([50.0, BAND_BAR_HEIGHT], egui::Label::new(name));
+                ui.add(egui::ProgressBar::new(share).desired_width(240.0).text(format!("{:.0}%", share * 100.0)));
+            });
+        }
+
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::hover());
+        let rect = response.rect;
+        painter.rect_filled(rect, 0.0, Color32::from_rgb(0, 0, 13));
+        let Some(bins) = self.columns.iter().map(Vec::len).max().filter(|bins| *bins > 0) else {
+            painter.text(rect.center(), Align2::CENTER_CENTER, "Waiting for enough ticks to analyse.", FontId::proportional(14.0), text_color);
+            return;
+        };
+        let max_power = self.columns.iter().flatten().copied().fold(f32::EPSILON, f32::max);
+
+        let cell = Vec2::new(rect.width() / MAX_COLUMNS as f32, rect.height() / bins as f32);
+        // The newest column is on the right edge.
+        let first_x = rect.right() - self.columns.len() as f32 * cell.x;
+        for (x, column) in self.columns.iter().enumerate() {
+            for (bin, power) in column.iter().enumerate() {
+                let min = Pos2::new(first_x + x as f32 * cell.x, rect.bottom() - (bin + 1) as f32 * cell.y);
+                painter.rect_filled(Rect::from_min_size(min, cell), 0.0, heat(power / max_power));
+            }
+        }
+        painter.rect_stroke(rect, 0.0, Stroke::new(1.0, Color32::from_gray(80)));
+        painter.text(rect.left_top() + Vec2::new(4.0, 4.0), Align2::LEFT_TOP, "0.5 cycle/tick", FontId::proportional(12.0), text_color);
+        painter.text(rect.left_bottom() + Vec2::new(4.0, -4.0), Align2::LEFT_BOTTOM, "0", FontId::proportional(12.0), text_color);
+    }
+}
+
+/// A colour from dark blue (no power) through green to yellow (the highest power).
+fn heat(level: f32) -> Color32 {
+    let level = level.clamp(0.0, 1.0);
+    let channel = |from: f32, to: f32, t: f32| (from + (to - from) * t) as u8;
+    if level < 0.5 {
+        let t = level * 2.0;
+        Color32::from_rgb(channel(0.0, 20.0, t), channel(0.0, 160.0, t), channel(40.0, 120.0, t))
+    } else {
+        let t = (level - 0.5) * 2.0;
+        Color32::from_rgb(channel(20.0, 250.0, t), channel(160.0, 230.0, t), channel(120.0, 60.0, t))
+    }
+}
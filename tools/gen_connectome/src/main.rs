@@ -1,38 +1,64 @@
-use rand::Rng;
-use std::fs::File;
-use std::io::{self, Write};
+use agi_core::connectome_builder::{ConnectomeBuilder, Topology};
+use agi_core::rng::RngProvider;
+use agi_core::synapse_matrix::SynapseMatrix;
+use std::io;
 use std::path::Path;
 
-const NUM_NEURONS: u64 = 1_000;
-const NUM_SYNAPSES: u64 = 100_000;
+const NUM_NEURONS: usize = 1_000;
+const SYNAPSES_PER_NEURON: usize = 100;
 
-fn main() -> io::Result<()> {
-    println!("Generating connectome with {} neurons and {} synapses...", NUM_NEURONS, NUM_SYNAPSES);
+const USAGE: &str = "Usage: gen_connectome [--neurons N] [--topology uniform|small-world|modular|scale-free] \
+[--inhibitory RATIO] [--decay LENGTH] [--seed SEED]";
 
-    let manifest_dir = env!("CARGO_MANIFEST_DIR");
-    let workspace_root = Path::new(manifest_dir).parent().unwrap().parent().unwrap(); // Go up two levels from tools/gen_connectome
-    let output_file = workspace_root.join("quantized_connectome.bin");
-    let mut file = File::create(&output_file)?;
-    let mut rng = rand::thread_rng();
-
-    // 1. Write number of neurons (u64)
-    file.write_all(&NUM_NEURONS.to_le_bytes())?;
+fn parse<T: std::str::FromStr>(flag: &str, value: Option<String>) -> io::Result<T> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid value for {}\n{}", flag, USAGE)))
+}
 
-    // 2. Write number of synapses (u64)
-    file.write_all(&NUM_SYNAPSES.to_le_bytes())?;
+fn main() -> io::Result<()> {
+    let mut neurons = NUM_NEURONS;
+    let mut topology = Topology::Uniform { synapses_per_neuron: SYNAPSES_PER_NEURON };
+    let mut inhibitory_ratio = None;
+    let mut distance_decay = None;
+    let mut seed = None;
 
-    // 3. Write synapse data
-    for _ in 0..NUM_SYNAPSES {
-        let source_index: u32 = rng.gen_range(0..NUM_NEURONS as u32);
-        let target_index: u32 = rng.gen_range(0..NUM_NEURONS as u32);
-        let weight: f32 = rng.gen_range(-1.0..1.0);
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--neurons" => neurons = parse(&flag, args.next())?,
+            "--topology" => {
+                topology = match args.next().as_deref() {
+                    Some("uniform") => Topology::Uniform { synapses_per_neuron: SYNAPSES_PER_NEURON },
+                    Some("small-world") => Topology::SmallWorld { neighbors: SYNAPSES_PER_NEURON, rewiring: 0.1 },
+                    Some("modular") => Topology::Modular { modules: 10, synapses_per_neuron: SYNAPSES_PER_NEURON, intra_module: 0.9 },
+                    Some("scale-free") => Topology::ScaleFree { edges_per_neuron: SYNAPSES_PER_NEURON },
+                    _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, USAGE)),
+                }
+            }
+            "--inhibitory" => inhibitory_ratio = Some(parse(&flag, args.next())?),
+            "--decay" => distance_decay = Some(parse(&flag, args.next())?),
+            "--seed" => seed = Some(parse(&flag, args.next())?),
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, USAGE)),
+        }
+    }
 
-        file.write_all(&source_index.to_le_bytes())?;
-        file.write_all(&target_index.to_le_bytes())?;
-        file.write_all(&weight.to_le_bytes())?;
+    println!("Generating a {:?} connectome with {} neurons...", topology, neurons);
+    let mut builder = ConnectomeBuilder::new(neurons).topology(topology).with_rng(RngProvider::new(seed).stream("gen_connectome"));
+    if let Some(ratio) = inhibitory_ratio {
+        builder = builder.inhibitory_ratio(ratio);
+    }
+    if let Some(decay) = distance_decay {
+        builder = builder.distance_decay(decay);
     }
+    let synapses = builder.build_synapses();
+
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let workspace_root = Path::new(manifest_dir).parent().unwrap().parent().unwrap(); // Go up two levels from tools/gen_connectome
+    let output_file = workspace_root.join("quantized_connectome.bin");
+    SynapseMatrix::from_synapses(neurons, &synapses).write_v2(&output_file)?;
 
-    println!("Successfully generated {}", output_file.display());
+    println!("Successfully generated {} ({} synapses)", output_file.display(), synapses.len());
 
     Ok(())
 }